@@ -0,0 +1,3 @@
+//! Helpers shared by the AirChainPay relay and wallet core
+
+pub mod http;
@@ -0,0 +1,80 @@
+//! Conditional GET: `ETag` / `If-None-Match` for read endpoints whose body only changes with the config
+
+use crate::infrastructure::config::ResponseCacheConfig;
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::{HttpRequest, HttpResponse};
+use serde_json::Value;
+
+/// Whether `If-None-Match` lists `etag` (or is `*`). Weak comparison, as GET allows.
+fn matches_if_none_match(req: &HttpRequest, etag: &str) -> bool {
+    let strip = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    req.headers()
+        .get_all(header::IF_NONE_MATCH)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || strip(tag) == strip(etag))
+}
+
+/// `body` as a 200, or an empty 304 if the client already holds `etag`
+pub fn json_with_etag(req: &HttpRequest, etag: &str, cache: &ResponseCacheConfig, body: Value) -> HttpResponse {
+    if !cache.etags {
+        return HttpResponse::Ok().json(body);
+    }
+    let cache_control = if cache.max_age_secs == 0 {
+        "no-cache".to_string()
+    } else {
+        format!("max-age={}", cache.max_age_secs)
+    };
+    let mut response = if matches_if_none_match(req, etag) {
+        HttpResponse::NotModified().finish()
+    } else {
+        HttpResponse::Ok().json(body)
+    };
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&cache_control) {
+        response.headers_mut().insert(header::CACHE_CONTROL, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::test::TestRequest;
+    use serde_json::json;
+
+    const ETAG: &str = "W/\"18f-3\"";
+
+    #[test]
+    fn test_matching_if_none_match_is_not_modified() {
+        let cache = ResponseCacheConfig::default();
+
+        let fresh = json_with_etag(&TestRequest::get().to_http_request(), ETAG, &cache, json!({"ok": true}));
+        assert_eq!(fresh.status(), StatusCode::OK);
+        assert_eq!(fresh.headers().get(header::ETAG).unwrap(), ETAG);
+        assert_eq!(fresh.headers().get(header::CACHE_CONTROL).unwrap(), "max-age=60");
+
+        for if_none_match in [ETAG, "\"18f-3\"", "\"other\", W/\"18f-3\"", "*"] {
+            let req = TestRequest::get().insert_header((header::IF_NONE_MATCH, if_none_match)).to_http_request();
+            let response = json_with_etag(&req, ETAG, &cache, json!({"ok": true}));
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED, "{}", if_none_match);
+            assert_eq!(response.headers().get(header::ETAG).unwrap(), ETAG);
+        }
+
+        let stale = TestRequest::get().insert_header((header::IF_NONE_MATCH, "W/\"18f-2\"")).to_http_request();
+        assert_eq!(json_with_etag(&stale, ETAG, &cache, json!({})).status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_disabled_etags_always_send_body() {
+        let cache = ResponseCacheConfig { etags: false, ..ResponseCacheConfig::default() };
+        let req = TestRequest::get().insert_header((header::IF_NONE_MATCH, ETAG)).to_http_request();
+
+        let response = json_with_etag(&req, ETAG, &cache, json!({}));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::ETAG).is_none());
+    }
+}
@@ -0,0 +1,133 @@
+//! Sparse fieldsets: `?fields=a,b` trims a JSON response to the requested top-level fields
+
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Fields of a stored transaction as returned by `/transactions`
+pub const TRANSACTION_FIELDS: &[&str] = &[
+    "id",
+    "signed_tx",
+    "chain_id",
+    "timestamp",
+    "status",
+    "tx_hash",
+    "error_details",
+    "security",
+    "broadcast_count",
+    "last_broadcast_at",
+];
+
+/// Fields of the `/transaction/...` detail and status responses
+pub const TRANSACTION_DETAIL_FIELDS: &[&str] = &[
+    "success",
+    "transaction_id",
+    "status",
+    "transaction_hash",
+    "chain_id",
+    "chain_name",
+    "timestamp",
+    "message",
+    "block_explorer_url",
+    "decoded_input",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSelection {
+    fields: HashSet<String>,
+}
+
+impl FieldSelection {
+    /// Parse a comma-separated `fields` value against an endpoint's schema.
+    /// Returns `None` when no selection was requested, so the full response is sent.
+    pub fn parse(fields: Option<&str>, known: &[&str]) -> Result<Option<Self>, String> {
+        let Some(fields) = fields else {
+            return Ok(None);
+        };
+
+        let requested: HashSet<String> = fields
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(str::to_string)
+            .collect();
+        if requested.is_empty() {
+            return Err("fields must name at least one field".to_string());
+        }
+
+        let mut unknown: Vec<&str> = requested
+            .iter()
+            .map(String::as_str)
+            .filter(|field| !known.contains(field))
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort_unstable();
+            return Err(format!(
+                "Unknown field(s): {}. Available fields: {}",
+                unknown.join(", "),
+                known.join(", ")
+            ));
+        }
+
+        Ok(Some(Self { fields: requested }))
+    }
+
+    /// Read the `fields` query parameter
+    pub fn from_query(query: &HashMap<String, String>, known: &[&str]) -> Result<Option<Self>, String> {
+        Self::parse(query.get("fields").map(String::as_str), known)
+    }
+
+    /// Keep only the selected keys of an object, or of every object in an array
+    pub fn apply(&self, value: Value) -> Value {
+        match value {
+            Value::Object(mut object) => {
+                object.retain(|key, _| self.fields.contains(key));
+                Value::Object(object)
+            }
+            Value::Array(items) => Value::Array(items.into_iter().map(|item| self.apply(item)).collect()),
+            other => other,
+        }
+    }
+}
+
+/// Apply an optional selection; without one the value is returned untouched
+pub fn select(selection: Option<&FieldSelection>, value: Value) -> Value {
+    match selection {
+        Some(selection) => selection.apply(value),
+        None => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_subset_returns_only_requested_fields() {
+        let selection = FieldSelection::parse(Some("id, status"), TRANSACTION_FIELDS).unwrap().unwrap();
+        let transactions = json!([
+            { "id": "a", "status": "Pending", "chain_id": 1114, "signed_tx": "0x01" },
+            { "id": "b", "status": "Completed", "chain_id": 84532, "tx_hash": "0xabc" },
+        ]);
+
+        assert_eq!(
+            selection.apply(transactions),
+            json!([{ "id": "a", "status": "Pending" }, { "id": "b", "status": "Completed" }])
+        );
+    }
+
+    #[test]
+    fn test_unknown_field_is_rejected() {
+        let err = FieldSelection::parse(Some("status,private_key"), TRANSACTION_DETAIL_FIELDS).unwrap_err();
+        assert!(err.contains("private_key"));
+        assert!(!err.contains("Unknown field(s): status"));
+        assert!(FieldSelection::parse(Some(" , "), TRANSACTION_DETAIL_FIELDS).is_err());
+    }
+
+    #[test]
+    fn test_missing_fields_parameter_keeps_full_response() {
+        assert_eq!(FieldSelection::from_query(&HashMap::new(), TRANSACTION_FIELDS).unwrap(), None);
+        let body = json!({ "success": true, "status": "completed" });
+        assert_eq!(select(None, body.clone()), body);
+    }
+}
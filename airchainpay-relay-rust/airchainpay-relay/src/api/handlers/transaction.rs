@@ -1,25 +1,34 @@
-use actix_web::{get, post, delete, web, HttpResponse, Responder};
+use actix_web::{get, post, delete, web, HttpRequest, HttpResponse, Responder};
 use actix_web::web::Data;
 use serde::{Deserialize, Serialize};
-use crate::infrastructure::storage::file_storage::{Storage, Transaction};
+use crate::infrastructure::storage::file_storage::{IdempotencyClaim, IdempotencyRecord, PaymentRecord, PaymentStatsFilter, Storage, Transaction, TransactionState};
 use crate::infrastructure::blockchain::manager::BlockchainManager;
+use crate::infrastructure::blockchain::calldata::CalldataDecoder;
 use crate::infrastructure::monitoring::manager::{MonitoringManager, AlertSeverity};
 use crate::utils::error_handler::EnhancedErrorHandler;
-use crate::infrastructure::config::DynamicConfigManager;
+use crate::infrastructure::config::{Config, DynamicConfigManager};
 use crate::middleware::error_handling::ErrorResponseBuilder;
 use crate::utils::audit::{AuditLogger, AuditSeverity, AuditFilter, AuditEventType};
+use crate::validators::registered_sender;
 use crate::utils::backup::{BackupType, BackupFilter, BackupManager, RestoreOptions};
 use std::sync::Arc;
 use std::collections::HashMap;
 use std::env;
 use actix_web::web::{Json, Query, Path};
 use chrono::{DateTime, Utc};
-use crate::app::transaction_service::{QueuedTransaction, TransactionProcessor, TransactionPriority};
+use crate::app::transaction_service::{QueuedTransaction, TransactionProcessor, TransactionPriority, TransactionStatusUpdate};
+use crate::app::bundle::TransactionBundle;
+use crate::app::fee_accounting::FeeAccountant;
+use crate::app::webhooks::{WebhookError, WebhookRegistry};
+use crate::api::conditional::json_with_etag;
+use crate::api::fields::{self, FieldSelection, TRANSACTION_DETAIL_FIELDS, TRANSACTION_FIELDS};
 use serde_json::json;
 use crate::domain::auth;
 use crate::domain::error::{RelayError, BlockchainError};
 use ethers::core::types::Address;
 use std::str::FromStr;
+use std::future::Future;
+use sha2::{Digest, Sha256};
 
 #[derive(Debug, Deserialize)]
 pub struct ContractPaymentsQuery {
@@ -44,15 +53,25 @@ pub struct PaymentResponse {
     pub log_index: u64,
 }
 
-// Helper function to get block explorer URL for a given chain ID
-fn get_block_explorer_url(chain_id: u64, tx_hash: &str) -> String {
-    match chain_id {
-        1114 => format!("https://scan.test2.btcs.network/tx/{}", tx_hash), // Core Testnet
-        84532 => format!("https://sepolia.basescan.org/tx/{}", tx_hash), // Base Sepolia
-        17000 => format!("https://holesky.etherscan.io/tx/{}", tx_hash), // Morph Holesky
-        4202 => format!("https://sepolia.scroll.io/tx/{}", tx_hash), // Lisk Sepolia
-        _ => format!("https://explorer.unknown.com/tx/{}", tx_hash), // Fallback
+// Helper function to get the block explorer URL for a transaction from the chain config.
+// Unknown chains and chains without an explorer yield `None`, rendered as null in responses.
+fn get_block_explorer_url(config: &Config, chain_id: u64, tx_hash: &str) -> Option<String> {
+    let url = config.explorer_tx_url(chain_id, tx_hash);
+    if url.is_none() {
+        log::debug!("No block explorer configured for chain {}", chain_id);
     }
+    url
+}
+
+// Helper function to read the `fields` query parameter, answering 400 for fields outside the schema
+fn requested_fields(query: &HashMap<String, String>, known: &[&str]) -> Result<Option<FieldSelection>, Box<HttpResponse>> {
+    FieldSelection::from_query(query, known).map_err(|e| {
+        Box::new(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "error": "Invalid fields parameter",
+            "message": e,
+        })))
+    })
 }
 
 // Helper function to get chain name for a given chain ID
@@ -76,12 +95,139 @@ async fn health() -> impl Responder {
     }))
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct SendTxRequest {
     pub signed_tx: String,
     pub rpc_url: String,
     pub chain_id: u64,
- 
+    /// Unix timestamp (seconds) after which the transaction must not be broadcast
+    #[serde(default)]
+    pub valid_until: Option<i64>,
+}
+
+impl SendTxRequest {
+    /// Parsed broadcast deadline; errors on out-of-range timestamps
+    fn deadline(&self) -> Result<Option<DateTime<Utc>>, String> {
+        self.valid_until
+            .map(|secs| DateTime::from_timestamp(secs, 0).ok_or_else(|| format!("Invalid valid_until timestamp: {}", secs)))
+            .transpose()
+    }
+}
+
+/// Header a client sets so retrying a submission cannot broadcast the transaction twice
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// Whose `Idempotency-Key` namespace a request uses: the subject of a valid bearer token,
+/// else its API key, else the client IP. API keys are only kept hashed.
+fn idempotency_principal(http_req: &HttpRequest) -> String {
+    let bearer = http_req.headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if let Some(token) = bearer {
+        let claims = match http_req.app_data::<Data<Arc<auth::AuthManager>>>() {
+            Some(auth_manager) => auth_manager.validate_token(token).ok(),
+            None => auth::verify_jwt_token(token).ok(),
+        };
+        if let Some(claims) = claims {
+            return format!("sub:{}", claims.sub);
+        }
+    }
+    let api_key = http_req.headers()
+        .get(crate::middleware::rate_limiting::API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty());
+    if let Some(api_key) = api_key {
+        return format!("key:{}", hex::encode(Sha256::digest(api_key.as_bytes())));
+    }
+    format!("ip:{}", http_req.connection_info().peer_addr().unwrap_or("unknown"))
+}
+
+/// Hash of a submission body, to tell a retry from a different request reusing its key
+fn request_hash<T: Serialize>(body: &T) -> String {
+    hex::encode(Sha256::digest(serde_json::to_vec(body).unwrap_or_default()))
+}
+
+/// Run `submit` at most once per `Idempotency-Key` and principal. A repeated key gets the
+/// stored response of its first submission, marked with `Idempotent-Replayed: true`, until
+/// the key expires after `ttl_secs`; repeating it with a different body is rejected with
+/// `422`. Only responses for a stored transaction are kept, so a request rejected before
+/// that can be retried with the same key.
+async fn idempotent_submission<F>(http_req: &HttpRequest, storage: &Arc<Storage>, ttl_secs: u64, request_hash: &str, submit: F) -> HttpResponse
+where
+    F: Future<Output = HttpResponse>,
+{
+    let key = match http_req.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        None => return submit.await,
+        Some(value) => match value.to_str().map(str::trim) {
+            Ok(key) if !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LEN => format!("{}:{}", idempotency_principal(http_req), key),
+            _ => return ErrorResponseBuilder::bad_request(&format!("{} must be 1-{} visible ASCII characters", IDEMPOTENCY_KEY_HEADER, MAX_IDEMPOTENCY_KEY_LEN)),
+        },
+    };
+
+    let ttl = chrono::Duration::seconds(ttl_secs.min(i64::MAX as u64) as i64);
+    let claim = {
+        let (key, request_hash) = (key.clone(), request_hash.to_string());
+        storage.blocking(move |storage| storage.claim_idempotency_key(&key, &request_hash, ttl)).await
+    };
+    match claim {
+        Ok(IdempotencyClaim::New) => {}
+        Ok(IdempotencyClaim::InProgress) => {
+            return ErrorResponseBuilder::conflict("A request with this Idempotency-Key is still being processed");
+        }
+        Ok(IdempotencyClaim::Mismatch) => {
+            return ErrorResponseBuilder::unprocessable_entity("This Idempotency-Key was already used with a different request body");
+        }
+        Ok(IdempotencyClaim::Completed(record)) => {
+            let status = actix_web::http::StatusCode::from_u16(record.status).unwrap_or(actix_web::http::StatusCode::OK);
+            return HttpResponse::build(status)
+                .insert_header(("Idempotent-Replayed", "true"))
+                .json(record.response);
+        }
+        Err(e) => return ErrorResponseBuilder::internal_server_error(&format!("Failed to check idempotency key: {}", e)),
+    }
+
+    // Released on every path that doesn't complete the key, including the request being dropped
+    let _claim = ClaimedIdempotencyKey { storage: Arc::clone(storage), key: key.clone() };
+    let (response, body) = submit.await.into_parts();
+    let body = match actix_web::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(_) => return ErrorResponseBuilder::internal_server_error("Failed to read submission response"),
+    };
+    let json: Option<serde_json::Value> = serde_json::from_slice(&body).ok();
+    let transaction_id = json.as_ref()
+        .and_then(|json| json.get("transaction_id"))
+        .and_then(|id| id.as_str())
+        .map(str::to_string);
+    if let (Some(transaction_id), Some(json)) = (transaction_id, json) {
+        let record = IdempotencyRecord {
+            key: key.clone(),
+            request_hash: request_hash.to_string(),
+            transaction_id,
+            status: response.status().as_u16(),
+            response: json,
+            created_at: Utc::now(),
+        };
+        if let Err(e) = storage.blocking(move |storage| storage.complete_idempotency_key(record)).await {
+            log::error!("Failed to store idempotency key {}: {}", key, e);
+        }
+    }
+    response.set_body(body).map_into_boxed_body()
+}
+
+/// In-flight claim on an idempotency key, released when dropped. Releasing a completed
+/// key only clears its in-flight marker, so the stored response is kept.
+struct ClaimedIdempotencyKey {
+    storage: Arc<Storage>,
+    key: String,
+}
+
+impl Drop for ClaimedIdempotencyKey {
+    fn drop(&mut self) {
+        self.storage.release_idempotency_key(&self.key);
+    }
 }
 
 // Add this helper function before process_transaction
@@ -92,7 +238,8 @@ async fn handle_transaction_submission(
     error_handler: Data<Arc<EnhancedErrorHandler>>,
     config_manager: Data<Arc<DynamicConfigManager>>,
     processor: Data<Arc<TransactionProcessor>>,
-) -> impl Responder {
+    audit_logger: Data<Arc<AuditLogger>>,
+) -> HttpResponse {
     // Basic raw tx hex sanity check (do not treat as a tx hash)
     let signed_tx_str = req.signed_tx.as_str();
     if !(signed_tx_str.starts_with("0x") 
@@ -102,12 +249,24 @@ async fn handle_transaction_submission(
     {
         return ErrorResponseBuilder::bad_request("Invalid raw transaction: must be 0x-prefixed, even-length, valid hex");
     }
+    let valid_until = match req.deadline() {
+        Ok(deadline) => deadline,
+        Err(e) => return ErrorResponseBuilder::bad_request(&e),
+    };
+
+    // Closed relays only accept transactions sent by registered wallets
+    let registered_sender_config = config_manager.get_config().await.registered_sender;
+    match registered_sender::sender_allowed(&registered_sender_config, &storage, &audit_logger, signed_tx_str, req.chain_id).await {
+        Ok(true) => {}
+        Ok(false) => return ErrorResponseBuilder::forbidden("Transaction sender is not a registered wallet"),
+        Err(e) => return ErrorResponseBuilder::bad_request(&format!("Invalid raw transaction: {}", e)),
+    }
 
-    // Use blockchain manager to check network status
-    let network_status = blockchain_manager.get_ref().get_network_status().await;
-    let is_healthy = match network_status {
-        Ok(status) => status.get("overall_status").map(|s| s == "healthy").unwrap_or(false),
-        Err(_) => false,
+    // Check the target chain's RPC health from the cache; a chain without a provider is
+    // left to transaction validation
+    let is_healthy = match blockchain_manager.get_ref().chain_health(req.chain_id).await {
+        Ok(chain_health) => chain_health.healthy,
+        Err(_) => true,
     };
     
     if !is_healthy {
@@ -116,10 +275,13 @@ async fn handle_transaction_submission(
     
     // Create transaction validator
     let config = config_manager.get_ref().get_config().await;
-    let validator = crate::validators::transaction_validator::TransactionValidator::new(std::sync::Arc::new(config));
+    let validator = crate::validators::transaction_validator::TransactionValidator::new(std::sync::Arc::new(config))
+        .with_denylist(config_manager.recipient_denylist())
+        .with_allowlist(config_manager.recipient_allowlist())
+        .with_plugins(config_manager.validator_plugins());
     
     // Comprehensive transaction validation using TransactionValidator
-    match validator.validate_transaction(&req.signed_tx).await {
+    match validator.validate_transaction(&req.signed_tx, req.chain_id).await {
         Ok(validation_result) => {
             if !validation_result.valid {
                             // Use error_utils for proper error handling and recording
@@ -204,10 +366,11 @@ async fn handle_transaction_submission(
     );
     
     // Save to storage with proper error handling
-    match storage.save_transaction(transaction.clone()) {
+    let record = transaction.clone();
+    match storage.blocking(move |storage| storage.save_transaction(record)).await {
         Ok(_) => {
             // Update metrics
-            let _ = storage.update_metrics("transactions_received", 1);
+            let _ = storage.blocking(|storage| storage.update_metrics("transactions_received", 1)).await;
             
             // Create QueuedTransaction and enqueue for processing
             let mut metadata = std::collections::HashMap::new();
@@ -227,7 +390,19 @@ async fn handle_transaction_submission(
                 retry_delay: std::time::Duration::from_secs(2),
                 chain_id: req.chain_id,
                 metadata,
+                valid_until,
+                not_before: None,
             };
+
+            // Never queue a transaction whose deadline has already passed
+            if processor.expire_if_past_deadline(&queued_tx).await {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "expired",
+                    "message": "Transaction deadline (valid_until) has already passed",
+                    "transaction_id": transaction.id,
+                    "status": "expired"
+                }));
+            }
             
             // Enqueue transaction for blockchain processing
             match processor.enqueue_transaction(queued_tx).await {
@@ -272,7 +447,9 @@ async fn handle_transaction_submission(
                     let _ = error_handler.record_error(error_record).await;
                     
                     // Update transaction status to queue_failed with error details
-                    if let Err(update_err) = storage.update_transaction_status_with_error(&transaction.id, "queue_failed", None, Some(format!("Queue enqueue failed: {}", e))) {
+                    let (id, details) = (transaction.id.clone(), format!("Queue enqueue failed: {}", e));
+                    let updated = storage.blocking(move |storage| storage.update_transaction_status_with_error(&id, TransactionState::QueueFailed, None, Some(details))).await;
+                    if let Err(update_err) = updated {
                         let error_record = crate::utils::error_handler::ErrorRecord {
                             id: uuid::Uuid::new_v4().to_string(),
                             timestamp: chrono::Utc::now(),
@@ -346,23 +523,72 @@ async fn handle_transaction_submission(
 
 // Update process_transaction to call the helper
 #[post("/send_tx")]
+#[allow(clippy::too_many_arguments)]
 async fn process_transaction(
+    http_req: HttpRequest,
     req: web::Json<SendTxRequest>,
     storage: Data<Arc<Storage>>,
     blockchain_manager: Data<Arc<BlockchainManager>>,
     error_handler: Data<Arc<EnhancedErrorHandler>>,
     config_manager: Data<Arc<DynamicConfigManager>>,
     processor: Data<Arc<TransactionProcessor>>,
+    audit_logger: Data<Arc<AuditLogger>>,
 ) -> impl Responder {
-    handle_transaction_submission(req, storage, blockchain_manager, error_handler, config_manager, processor).await
+    queue_submission(http_req, req, storage, blockchain_manager, error_handler, config_manager, processor, audit_logger).await
+}
+
+/// Validate and queue a submission at most once per `Idempotency-Key`
+#[allow(clippy::too_many_arguments)]
+async fn queue_submission(
+    http_req: HttpRequest,
+    req: web::Json<SendTxRequest>,
+    storage: Data<Arc<Storage>>,
+    blockchain_manager: Data<Arc<BlockchainManager>>,
+    error_handler: Data<Arc<EnhancedErrorHandler>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+    processor: Data<Arc<TransactionProcessor>>,
+    audit_logger: Data<Arc<AuditLogger>>,
+) -> HttpResponse {
+    let ttl_secs = config_manager.get_config().await.idempotency.ttl_secs;
+    let idempotency_storage = Arc::clone(&storage);
+    let request_hash = request_hash(&*req);
+    idempotent_submission(
+        &http_req,
+        &idempotency_storage,
+        ttl_secs,
+        &request_hash,
+        handle_transaction_submission(req, storage, blockchain_manager, error_handler, config_manager, processor, audit_logger),
+    ).await
 }
 
 #[post("/simple_send_tx")]
 async fn simple_send_tx(
+    http_req: HttpRequest,
     req: web::Json<SendTxRequest>,
     storage: Data<Arc<Storage>>,
     blockchain_manager: Data<Arc<BlockchainManager>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+    audit_logger: Data<Arc<AuditLogger>>,
 ) -> impl Responder {
+    let ttl_secs = config_manager.get_config().await.idempotency.ttl_secs;
+    let idempotency_storage = Arc::clone(&storage);
+    let request_hash = request_hash(&*req);
+    idempotent_submission(
+        &http_req,
+        &idempotency_storage,
+        ttl_secs,
+        &request_hash,
+        send_transaction_immediately(req, storage, blockchain_manager, config_manager, audit_logger),
+    ).await
+}
+
+async fn send_transaction_immediately(
+    req: web::Json<SendTxRequest>,
+    storage: Data<Arc<Storage>>,
+    blockchain_manager: Data<Arc<BlockchainManager>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+    audit_logger: Data<Arc<AuditLogger>>,
+) -> HttpResponse {
     // Minimal raw tx hex validation before immediate broadcast
     let signed_tx_str = req.signed_tx.as_str();
     if !(signed_tx_str.starts_with("0x") 
@@ -377,6 +603,32 @@ async fn simple_send_tx(
             "timestamp": chrono::Utc::now().to_rfc3339(),
         }));
     }
+    let valid_until = match req.deadline() {
+        Ok(deadline) => deadline,
+        Err(e) => return ErrorResponseBuilder::bad_request(&e),
+    };
+
+    let config = config_manager.get_config().await;
+    match registered_sender::sender_allowed(&config.registered_sender, &storage, &audit_logger, signed_tx_str, req.chain_id).await {
+        Ok(true) => {}
+        Ok(false) => return ErrorResponseBuilder::forbidden("Transaction sender is not a registered wallet"),
+        Err(e) => return ErrorResponseBuilder::bad_request(&format!("Invalid raw transaction: {}", e)),
+    }
+
+    // The same checks, denylist and plugins included, as for queued submissions
+    let validator = crate::validators::transaction_validator::TransactionValidator::new(Arc::new(config))
+        .with_denylist(config_manager.recipient_denylist())
+        .with_allowlist(config_manager.recipient_allowlist())
+        .with_plugins(config_manager.validator_plugins());
+    match validator.validate_transaction(signed_tx_str, req.chain_id).await {
+        Ok(result) if result.valid => {
+            if !result.warnings.is_empty() {
+                println!("Transaction validation warnings: {}", result.warnings.join(", "));
+            }
+        }
+        Ok(result) => return ErrorResponseBuilder::bad_request(&format!("Transaction validation failed: {}", result.errors.join(", "))),
+        Err(e) => return ErrorResponseBuilder::bad_request(&format!("Transaction validation failed: {}", e)),
+    }
 
     // Create transaction record
     let transaction = Transaction::new(
@@ -385,11 +637,25 @@ async fn simple_send_tx(
     );
     
     // Save to storage
-    match storage.save_transaction(transaction.clone()) {
+    let record = transaction.clone();
+    match storage.blocking(move |storage| storage.save_transaction(record)).await {
         Ok(_) => {
             // Update metrics
-            let _ = storage.update_metrics("transactions_received", 1);
+            let _ = storage.blocking(|storage| storage.update_metrics("transactions_received", 1)).await;
             
+            if valid_until.is_some_and(|deadline| Utc::now() > deadline) {
+                let id = transaction.id.clone();
+                let _ = storage.blocking(move |storage| {
+                    storage.update_transaction_status_with_error(&id, TransactionState::Expired, None, Some("Not broadcast: valid_until deadline passed".to_string()))
+                }).await;
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "success": false,
+                    "message": "Transaction deadline (valid_until) has already passed",
+                    "transaction_id": transaction.id,
+                    "status": "expired",
+                }));
+            }
+
             // Try to send the transaction immediately
             match blockchain_manager.send_transaction(&QueuedTransaction {
                 transaction: serde_json::json!({}),
@@ -405,11 +671,15 @@ async fn simple_send_tx(
                     metadata.insert("id".to_string(), serde_json::Value::String(transaction.id.clone()));
                     metadata
                 },
+                valid_until,
+                not_before: None,
             }).await {
                 Ok(tx_hash) => {
                     // Update transaction with hash
                     let tx_hash_str = format!("{:?}", tx_hash);
-                    let _ = storage.update_transaction_status_with_error(&transaction.id, "completed", Some(tx_hash_str.clone()), None);
+                    let (id, hash) = (transaction.id.clone(), tx_hash_str.clone());
+                    let _ = storage.blocking(move |storage| storage.update_transaction_status_with_error(&id, TransactionState::Completed, Some(hash), None)).await;
+                    let config = config_manager.get_config().await;
                     
                     HttpResponse::Ok().json(serde_json::json!({
                         "success": true,
@@ -420,13 +690,42 @@ async fn simple_send_tx(
                         "chain_name": get_chain_name(req.chain_id),
                         "timestamp": chrono::Utc::now().to_rfc3339(),
                         "status": "completed",
-                        "block_explorer_url": get_block_explorer_url(req.chain_id, &tx_hash_str),
+                        "block_explorer_url": get_block_explorer_url(&config, req.chain_id, &tx_hash_str),
                     }))
                 }
                 Err(e) => {
                     // Update transaction as failed
-                    let _ = storage.update_transaction_status_with_error(&transaction.id, "failed", None, Some(format!("Blockchain error: {}", e)));
-                    
+                    let (id, details) = (transaction.id.clone(), format!("Blockchain error: {}", e));
+                    let _ = storage.blocking(move |storage| storage.update_transaction_status_with_error(&id, TransactionState::Failed, None, Some(details))).await;
+
+                    if let Some(BlockchainError::NonceHeld { nonce, expected }) = e.downcast_ref::<BlockchainError>() {
+                        return HttpResponse::Conflict().json(serde_json::json!({
+                            "success": false,
+                            "message": "Transaction nonce is ahead of the sender's next nonce",
+                            "transaction_id": transaction.id,
+                            "error": e.to_string(),
+                            "nonce": nonce,
+                            "expected_nonce": expected,
+                            "chain_id": req.chain_id,
+                            "timestamp": chrono::Utc::now().to_rfc3339(),
+                            "status": "failed",
+                        }));
+                    }
+
+                    if let Some(BlockchainError::Reverted { reason, data }) = e.downcast_ref::<BlockchainError>() {
+                        return HttpResponse::BadRequest().json(serde_json::json!({
+                            "success": false,
+                            "message": "Transaction reverted",
+                            "transaction_id": transaction.id,
+                            "error": e.to_string(),
+                            "revert_reason": reason,
+                            "revert_data": data,
+                            "chain_id": req.chain_id,
+                            "timestamp": chrono::Utc::now().to_rfc3339(),
+                            "status": "failed",
+                        }));
+                    }
+
                     HttpResponse::InternalServerError().json(serde_json::json!({
                         "success": false,
                         "message": "Transaction failed to send",
@@ -459,13 +758,15 @@ async fn legacy_submit_transaction(
     error_handler: Data<Arc<EnhancedErrorHandler>>,
     config_manager: Data<Arc<DynamicConfigManager>>,
     processor: Data<Arc<TransactionProcessor>>,
+    audit_logger: Data<Arc<AuditLogger>>,
 ) -> impl Responder {
-    handle_transaction_submission(req, storage, blockchain_manager, error_handler, config_manager, processor).await
+    handle_transaction_submission(req, storage, blockchain_manager, error_handler, config_manager, processor, audit_logger).await
 }
 
 #[get("/contract/payments")]
-async fn get_contract_payments(
+pub async fn get_contract_payments(
     blockchain_manager: Data<Arc<BlockchainManager>>,
+    storage: Data<Arc<Storage>>,
     query: Query<ContractPaymentsQuery>,
 ) -> impl Responder {
     let chain_id = query.chain_id.unwrap_or(1);
@@ -482,6 +783,22 @@ async fn get_contract_payments(
         to_address,
     ).await {
         Ok(events) => {
+            // Index every fetched event so it is available to /payments/stats
+            let records = events.iter().map(|event| PaymentRecord {
+                chain_id,
+                from: format!("{:?}", event.from),
+                to: format!("{:?}", event.to),
+                amount: event.amount.to_string(),
+                payment_reference: event.payment_reference.clone(),
+                is_relayed: event.is_relayed,
+                tx_hash: format!("{:?}", event.tx_hash),
+                block_number: event.block_number,
+                log_index: event.log_index,
+            }).collect();
+            if let Err(e) = storage.blocking(move |storage| storage.record_payments(records)).await {
+                log::warn!("Failed to index payment events for chain {}: {}", chain_id, e);
+            }
+
             // Apply pagination
             let offset = query.offset.unwrap_or(0) as usize;
             let limit = query.limit.unwrap_or(100) as usize;
@@ -515,11 +832,263 @@ async fn get_contract_payments(
     }
 }
 
+#[get("/payments/stats")]
+pub async fn get_payment_stats(
+    storage: Data<Arc<Storage>>,
+    query: Query<PaymentStatsFilter>,
+) -> impl Responder {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid range",
+                "message": "`from` must not be greater than `to`"
+            }));
+        }
+    }
+
+    match storage.payment_stats(&query) {
+        Ok(stats) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "chain_id": query.chain_id,
+            "from": query.from,
+            "to": query.to,
+            "stats": stats,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to aggregate payments",
+            "message": e.to_string()
+        })),
+    }
+}
+
+/// Require a bearer JWT issued with the admin token type
+fn require_admin(req: &HttpRequest) -> Result<(), Box<HttpResponse>> {
+    let token = req.headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let Some(token) = token else {
+        return Err(Box::new(ErrorResponseBuilder::unauthorized("Missing bearer token")));
+    };
+
+    // Tokens are checked by the app's AuthManager when one is registered
+    let verified = match req.app_data::<Data<Arc<auth::AuthManager>>>() {
+        Some(auth_manager) => auth_manager.validate_token(token).map_err(|e| Box::new(e) as Box<dyn std::error::Error>),
+        None => auth::verify_jwt_token(token),
+    };
+    match verified {
+        Ok(claims) if claims.typ == auth::ADMIN_TOKEN_TYPE => Ok(()),
+        Ok(_) => Err(Box::new(ErrorResponseBuilder::forbidden("Admin token required"))),
+        Err(_) => Err(Box::new(ErrorResponseBuilder::unauthorized("Invalid or expired token"))),
+    }
+}
+
+#[get("/queue")]
+pub async fn get_queue(
+    req: HttpRequest,
+    processor: Data<Arc<TransactionProcessor>>,
+) -> impl Responder {
+    if let Err(response) = require_admin(&req) {
+        return *response;
+    }
+
+    let queue = processor.queue_snapshot().await;
+    let total: usize = queue.values().map(Vec::len).sum();
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "total_queued": total,
+        "chains": queue,
+        "timestamp": Utc::now().to_rfc3339(),
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountingSummaryQuery {
+    pub chain_id: Option<u64>,
+}
+
+#[get("/accounting/summary")]
+pub async fn get_accounting_summary(
+    req: HttpRequest,
+    query: Query<AccountingSummaryQuery>,
+    fee_accountant: Data<Arc<FeeAccountant>>,
+) -> impl Responder {
+    if let Err(response) = require_admin(&req) {
+        return *response;
+    }
+
+    let chains: Vec<_> = match query.chain_id {
+        Some(chain_id) => fee_accountant.chain_summary(chain_id).into_iter().collect(),
+        None => fee_accountant.summary().into_values().collect(),
+    };
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "markup": fee_accountant.markup(),
+        "chains": chains,
+        "timestamp": Utc::now().to_rfc3339(),
+    }))
+}
+
+#[delete("/queue/{id}")]
+pub async fn delete_queued_transaction(
+    req: HttpRequest,
+    path: Path<String>,
+    processor: Data<Arc<TransactionProcessor>>,
+) -> impl Responder {
+    if let Err(response) = require_admin(&req) {
+        return *response;
+    }
+
+    let id = path.into_inner();
+    match processor.cancel_queued(&id).await {
+        Ok(Some(cancelled)) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "Transaction removed from queue and marked cancelled",
+            "transaction": cancelled,
+        })),
+        Ok(None) => ErrorResponseBuilder::not_found(&format!("No queued transaction with id {}", id)),
+        Err(e) => ErrorResponseBuilder::internal_server_error(&format!("Failed to cancel queued transaction: {}", e)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookRequest {
+    pub url: String,
+}
+
+/// Register a callback URL; it is stored only after answering the verification challenge
+#[post("/webhooks")]
+pub async fn register_webhook(
+    http_req: HttpRequest,
+    req: Json<WebhookRequest>,
+    webhooks: Data<Arc<WebhookRegistry>>,
+) -> impl Responder {
+    if let Err(response) = require_admin(&http_req) {
+        return *response;
+    }
+
+    match webhooks.register(&req.url).await {
+        Ok(webhook) => HttpResponse::Created().json(json!({
+            "success": true,
+            "webhook": webhook,
+        })),
+        Err(e @ (WebhookError::InvalidUrl(_) | WebhookError::VerificationFailed(_))) => ErrorResponseBuilder::bad_request(&e.to_string()),
+        Err(e @ WebhookError::Storage(_)) => ErrorResponseBuilder::internal_server_error(&e.to_string()),
+    }
+}
+
+#[get("/webhooks")]
+pub async fn list_webhooks(
+    req: HttpRequest,
+    webhooks: Data<Arc<WebhookRegistry>>,
+) -> impl Responder {
+    if let Err(response) = require_admin(&req) {
+        return *response;
+    }
+
+    let webhooks = webhooks.list();
+    HttpResponse::Ok().json(json!({
+        "success": true,
+        "total_count": webhooks.len(),
+        "webhooks": webhooks,
+    }))
+}
+
+#[delete("/webhooks/{id}")]
+pub async fn delete_webhook(
+    req: HttpRequest,
+    path: Path<String>,
+    webhooks: Data<Arc<WebhookRegistry>>,
+) -> impl Responder {
+    if let Err(response) = require_admin(&req) {
+        return *response;
+    }
+
+    let id = path.into_inner();
+    match webhooks.delete(&id).await {
+        Ok(true) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": "Webhook deleted",
+        })),
+        Ok(false) => ErrorResponseBuilder::not_found(&format!("No webhook with id {}", id)),
+        Err(e) => ErrorResponseBuilder::internal_server_error(&format!("Failed to delete webhook: {}", e)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BundleRequest {
+    pub chain_id: u64,
+    /// Signed transactions in broadcast order
+    pub signed_txs: Vec<String>,
+}
+
+#[post("/transactions/bundle")]
+pub async fn submit_transaction_bundle(
+    req: Json<BundleRequest>,
+    blockchain_manager: Data<Arc<BlockchainManager>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+    processor: Data<Arc<TransactionProcessor>>,
+    storage: Data<Arc<Storage>>,
+    audit_logger: Data<Arc<AuditLogger>>,
+) -> impl Responder {
+    if !blockchain_manager.supports_chain(req.chain_id) {
+        return ErrorResponseBuilder::bad_request(&format!("Unsupported chain_id: {}", req.chain_id));
+    }
+    let bundle = match TransactionBundle::from_signed(req.chain_id, &req.signed_txs, processor.bundle_config().max_transactions) {
+        Ok(bundle) => bundle,
+        Err(e) => return ErrorResponseBuilder::bad_request(&format!("Invalid bundle: {}", e)),
+    };
+
+    let config = config_manager.get_config().await;
+    for (index, signed_tx) in req.signed_txs.iter().enumerate() {
+        match registered_sender::sender_allowed(&config.registered_sender, &storage, &audit_logger, signed_tx, req.chain_id).await {
+            Ok(true) => {}
+            Ok(false) => return ErrorResponseBuilder::forbidden(&format!("Transaction {} sender is not a registered wallet", index)),
+            Err(e) => return ErrorResponseBuilder::bad_request(&format!("Transaction {} is invalid: {}", index, e)),
+        }
+    }
+    let validator = crate::validators::transaction_validator::TransactionValidator::new(Arc::new(config))
+        .with_denylist(config_manager.recipient_denylist())
+        .with_allowlist(config_manager.recipient_allowlist())
+        .with_plugins(config_manager.validator_plugins());
+    for (index, signed_tx) in req.signed_txs.iter().enumerate() {
+        match validator.validate_transaction(signed_tx, req.chain_id).await {
+            Ok(result) if result.valid => {}
+            Ok(result) => {
+                return ErrorResponseBuilder::bad_request(&format!("Transaction {} validation failed: {}", index, result.errors.join(", ")));
+            }
+            Err(e) => return ErrorResponseBuilder::bad_request(&format!("Transaction {} validation failed: {}", index, e)),
+        }
+    }
+
+    let bundle_id = bundle.id.clone();
+    let sender = bundle.sender;
+    let transaction_ids = bundle.transaction_ids();
+    match processor.submit_bundle(bundle).await {
+        Ok(()) => HttpResponse::Accepted().json(json!({
+            "success": true,
+            "status": "queued",
+            "bundle_id": bundle_id,
+            "sender": format!("{:?}", sender),
+            "chain_id": req.chain_id,
+            "transaction_ids": transaction_ids,
+            "timestamp": Utc::now().to_rfc3339(),
+        })),
+        Err(e) => ErrorResponseBuilder::internal_server_error(&format!("Failed to queue bundle: {}", e)),
+    }
+}
+
 #[derive(Deserialize)]
 struct TokenRequest {
     api_key: String,
 }
 
+#[derive(Deserialize)]
+struct RefreshTokenRequest {
+    refresh_token: String,
+}
+
 #[derive(Deserialize)]
 struct ValidationRequest {
     address: Option<String>,
@@ -529,23 +1098,54 @@ struct ValidationRequest {
 }
 
 #[post("/auth/token")]
-async fn generate_token(
+pub async fn generate_token(
     req: web::Json<TokenRequest>,
+    auth_manager: Data<Arc<auth::AuthManager>>,
 ) -> impl Responder {
-    let api_key = std::env::var("API_KEY").unwrap_or_else(|_| "dev_api_key".to_string());
+    // Operators holding ADMIN_API_KEY get an admin-scoped token
+    let token_type = match auth_manager.token_type_for_api_key(&req.api_key) {
+        Ok(token_type) => token_type,
+        Err(auth::ApiKeyError::NotConfigured) => {
+            return ErrorResponseBuilder::service_unavailable("Token issuance is disabled: API_KEY is not configured");
+        }
+        Err(auth::ApiKeyError::Invalid) => {
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Invalid API key"
+            }));
+        }
+    };
     
-    if req.api_key != api_key {
-        return HttpResponse::Unauthorized().json(serde_json::json!({
-            "error": "Invalid API key"
-        }));
+    match auth_manager.issue_tokens(&auth::api_key_subject(&req.api_key), token_type) {
+        Ok(tokens) => HttpResponse::Ok().json(serde_json::json!({
+            "token": tokens.access_token,
+            "expires_at": tokens.expires_at,
+            "refresh_token": tokens.refresh_token,
+            "refresh_expires_at": tokens.refresh_expires_at,
+        })),
+        Err(e) => ErrorResponseBuilder::internal_server_error(&e.to_string()),
+    }
+}
+
+/// Exchange a refresh token from `/auth/token` for a new access token
+#[post("/auth/refresh")]
+pub async fn refresh_token(
+    req: web::Json<RefreshTokenRequest>,
+    auth_manager: Data<Arc<auth::AuthManager>>,
+) -> impl Responder {
+    match auth_manager.refresh(&req.refresh_token) {
+        Ok(access) => HttpResponse::Ok().json(serde_json::json!({
+            "token": access.access_token,
+            "expires_at": access.expires_at,
+        })),
+        Err(auth::TokenError::Expired) => HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Refresh token expired",
+            "code": "TOKEN_EXPIRED",
+        })),
+        Err(e) => HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": e.to_string(),
+            "code": "INVALID_TOKEN",
+        })),
     }
-    
-    // Generate JWT token
-    let token = auth::generate_jwt_token("api-client", "relay");
-    
-    HttpResponse::Ok().json(serde_json::json!({
-        "token": token
-    }))
 }
 
 #[post("/validate")]
@@ -621,23 +1221,32 @@ async fn get_transactions(
     storage: Data<Arc<Storage>>,
     query: web::Query<std::collections::HashMap<String, String>>,
 ) -> impl Responder {
+    let selection = match requested_fields(&query, TRANSACTION_FIELDS) {
+        Ok(selection) => selection,
+        Err(response) => return *response,
+    };
     let limit = query.get("limit")
         .and_then(|s| s.parse::<usize>().ok())
         .unwrap_or(100);
     
     let transactions = storage.get_transactions(limit);
-    HttpResponse::Ok().json(transactions)
+    match serde_json::to_value(&transactions) {
+        Ok(body) => HttpResponse::Ok().json(fields::select(selection.as_ref(), body)),
+        Err(e) => ErrorResponseBuilder::internal_server_error(&format!("Failed to serialize transactions: {}", e)),
+    }
 }
 
 #[get("/metrics")]
 async fn get_metrics(
     _storage: Data<Arc<Storage>>,
     monitoring_manager: Data<Arc<MonitoringManager>>,
+    blockchain_manager: Data<Arc<BlockchainManager>>,
+    processor: Data<Arc<TransactionProcessor>>,
 ) -> impl Responder {
     let metrics = monitoring_manager.get_metrics().await;
     let system_metrics = monitoring_manager.get_system_metrics().await;
     
-    let prometheus_metrics = format!(
+    let mut prometheus_metrics = format!(
         "# HELP airchainpay_transactions_received_total Total number of transactions received
 # TYPE airchainpay_transactions_received_total counter
 airchainpay_transactions_received_total {}
@@ -686,6 +1295,14 @@ airchainpay_requests_failed_total {}
 # TYPE airchainpay_response_time_avg_ms gauge
 airchainpay_response_time_avg_ms {}
 
+# HELP airchainpay_response_time_p95_ms 95th percentile response time in milliseconds
+# TYPE airchainpay_response_time_p95_ms gauge
+airchainpay_response_time_p95_ms {}
+
+# HELP airchainpay_response_time_p99_ms 99th percentile response time in milliseconds
+# TYPE airchainpay_response_time_p99_ms gauge
+airchainpay_response_time_p99_ms {}
+
 # HELP airchainpay_active_connections Current number of active connections
 # TYPE airchainpay_active_connections gauge
 airchainpay_active_connections {}
@@ -774,6 +1391,8 @@ airchainpay_system_thread_count {}
         metrics.requests_successful,
         metrics.requests_failed,
         metrics.response_time_avg_ms,
+        metrics.response_time_p95_ms,
+        metrics.response_time_p99_ms,
         metrics.active_connections,
         metrics.database_operations,
         metrics.database_errors,
@@ -795,6 +1414,30 @@ airchainpay_system_thread_count {}
         system_metrics.thread_count,
     );
 
+    prometheus_metrics.push_str("
+# HELP airchainpay_rpc_active_connections RPC connections currently in use per provider host
+# TYPE airchainpay_rpc_active_connections gauge
+");
+    let mut rpc_connections: Vec<_> = blockchain_manager.rpc_pool().active_connections().into_iter().collect();
+    rpc_connections.sort();
+    for (host, active) in rpc_connections {
+        prometheus_metrics.push_str(&format!("airchainpay_rpc_active_connections{{host=\"{}\"}} {}\n", host, active));
+    }
+
+    prometheus_metrics.push_str("
+# HELP airchainpay_queue_depth Transactions waiting in the processing queue per priority
+# TYPE airchainpay_queue_depth gauge
+");
+    for (priority, depth) in processor.queue_depth_by_priority().await {
+        prometheus_metrics.push_str(&format!("airchainpay_queue_depth{{priority=\"{}\"}} {}\n", priority.as_str(), depth));
+    }
+
+    prometheus_metrics.push_str(&format!("
+# HELP airchainpay_nonce_held_transactions Transactions held until an earlier nonce from the same sender is broadcast
+# TYPE airchainpay_nonce_held_transactions gauge
+airchainpay_nonce_held_transactions {}
+", blockchain_manager.nonce_sequencer().held_count()));
+
     HttpResponse::Ok()
         .content_type("text/plain")
         .body(prometheus_metrics)
@@ -1968,9 +2611,12 @@ async fn detailed_health(
     let system_metrics = monitoring_manager.get_system_metrics().await;
     let alerts = monitoring_manager.get_alerts(50).await;
     let db_health = storage.check_health().await;
+    blockchain_manager.force_refresh().await;
     let blockchain_status = blockchain_manager.get_network_status().await.unwrap_or_else(|_| HashMap::new());
     let blockchain_healthy = blockchain_status.get("is_healthy").and_then(|v| v.parse::<bool>().ok()).unwrap_or(false);
     let config_status = config_manager.get_status().await;
+    let monitoring_health = monitoring_manager.subsystem_health();
+    let monitoring_healthy = monitoring_health["status"] == "healthy";
     
     // Calculate response time
     let response_time = start_time.elapsed().as_millis() as f64;
@@ -1986,7 +2632,7 @@ async fn detailed_health(
     
     let overall_status = if critical_alerts > 0 {
         "critical"
-    } else if !db_health.is_healthy || !blockchain_healthy {
+    } else if !db_health.is_healthy || !blockchain_healthy || !monitoring_healthy {
         "degraded"
     } else if warning_alerts > 0 {
         "warning"
@@ -2033,6 +2679,7 @@ async fn detailed_health(
                 "file_watcher_active": config_status.file_watcher_active,
                 "validation_errors": config_status.validation_errors,
             },
+            "monitoring": monitoring_health,
         },
         
         "alerts": {
@@ -2118,6 +2765,7 @@ async fn component_health(
             }))
         },
         "blockchain" => {
+            blockchain_manager.force_refresh().await;
             let blockchain_status = blockchain_manager.get_network_status().await.unwrap_or_else(|_| HashMap::new());
             HttpResponse::Ok().json(serde_json::json!({
                 "component": "blockchain",
@@ -2255,18 +2903,21 @@ async fn health_metrics(
     }))
 }
 
+/// Queue a signed transaction. The queue entry and its retry policy are built here, after the
+/// same sender, validation and idempotency checks as `/send_tx`.
 #[post("/submit_transaction")]
+#[allow(clippy::too_many_arguments)]
 pub async fn submit_transaction(
-    tx: web::Json<QueuedTransaction>,
-    processor: web::Data<std::sync::Arc<TransactionProcessor>>,
-) -> impl Responder {
-    match processor.enqueue_transaction(tx.into_inner()).await {
-        Ok(_) => HttpResponse::Ok().json(json!({ "status": "queued" })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({
-            "status": "error",
-            "message": format!("Failed to enqueue transaction: {}", e)
-        }))
-    }
+    http_req: HttpRequest,
+    req: web::Json<SendTxRequest>,
+    storage: Data<Arc<Storage>>,
+    blockchain_manager: Data<Arc<BlockchainManager>>,
+    error_handler: Data<Arc<EnhancedErrorHandler>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+    processor: Data<Arc<TransactionProcessor>>,
+    audit_logger: Data<Arc<AuditLogger>>,
+) -> impl Responder {
+    queue_submission(http_req, req, storage, blockchain_manager, error_handler, config_manager, processor, audit_logger).await
 }
 
 #[post("/test_tx")]
@@ -2278,19 +2929,35 @@ async fn test_transaction() -> impl Responder {
     }))
 }
 
+/// Add what the transaction's input data calls to a detail response, unless decoding is disabled
+fn with_decoded_input(mut body: serde_json::Value, transaction: &Transaction, decoder: &CalldataDecoder, config: &Config) -> serde_json::Value {
+    if config.calldata_decoder.enabled {
+        body["decoded_input"] = json!(decoder.decode_signed_tx(&transaction.signed_tx));
+    }
+    body
+}
+
 #[get("/transaction/{transaction_id}")]
 async fn get_transaction_details(
     path: web::Path<String>,
     storage: Data<Arc<Storage>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+    decoder: Data<Arc<CalldataDecoder>>,
+    query: web::Query<HashMap<String, String>>,
 ) -> impl Responder {
+    let selection = match requested_fields(&query, TRANSACTION_DETAIL_FIELDS) {
+        Ok(selection) => selection,
+        Err(response) => return *response,
+    };
+    let config = config_manager.get_config().await;
     let transaction_id = path.into_inner();
     
     let transactions = storage.get_transactions(1000); // Get all transactions
     if let Some(transaction) = transactions.iter().find(|t| t.id == transaction_id) {
-        match transaction.status.as_str() {
+        let body = match transaction.status.as_str() {
             "completed" => {
                 if let Some(tx_hash) = &transaction.tx_hash {
-                    HttpResponse::Ok().json(serde_json::json!({
+                    serde_json::json!({
                         "success": true,
                         "transaction_id": transaction.id,
                         "status": transaction.status,
@@ -2299,10 +2966,10 @@ async fn get_transaction_details(
                         "chain_name": get_chain_name(transaction.chain_id),
                         "timestamp": transaction.timestamp.to_rfc3339(),
                         "message": "Transaction completed successfully",
-                        "block_explorer_url": get_block_explorer_url(transaction.chain_id, tx_hash),
-                    }))
+                        "block_explorer_url": get_block_explorer_url(&config, transaction.chain_id, tx_hash),
+                    })
                 } else {
-                    HttpResponse::Ok().json(serde_json::json!({
+                    serde_json::json!({
                         "success": true,
                         "transaction_id": transaction.id,
                         "status": transaction.status,
@@ -2310,11 +2977,11 @@ async fn get_transaction_details(
                         "chain_id": transaction.chain_id,
                         "timestamp": transaction.timestamp.to_rfc3339(),
                         "message": "Transaction completed but hash not available"
-                    }))
+                    })
                 }
             },
             "pending" => {
-                HttpResponse::Ok().json(serde_json::json!({
+                serde_json::json!({
                     "success": true,
                     "transaction_id": transaction.id,
                     "status": transaction.status,
@@ -2322,10 +2989,10 @@ async fn get_transaction_details(
                     "chain_id": transaction.chain_id,
                     "timestamp": transaction.timestamp.to_rfc3339(),
                     "message": "Transaction is still being processed"
-                }))
+                })
             },
             "failed" => {
-                HttpResponse::Ok().json(serde_json::json!({
+                serde_json::json!({
                     "success": true,
                     "transaction_id": transaction.id,
                     "status": transaction.status,
@@ -2333,10 +3000,10 @@ async fn get_transaction_details(
                     "chain_id": transaction.chain_id,
                     "timestamp": transaction.timestamp.to_rfc3339(),
                     "message": "Transaction failed to process"
-                }))
+                })
             },
             _ => {
-                HttpResponse::Ok().json(serde_json::json!({
+                serde_json::json!({
                     "success": true,
                     "transaction_id": transaction.id,
                     "status": transaction.status,
@@ -2344,9 +3011,11 @@ async fn get_transaction_details(
                     "chain_id": transaction.chain_id,
                     "timestamp": transaction.timestamp.to_rfc3339(),
                     "message": format!("Transaction status: {}", transaction.status)
-                }))
+                })
             }
-        }
+        };
+        let body = with_decoded_input(body, transaction, &decoder, &config);
+        HttpResponse::Ok().json(fields::select(selection.as_ref(), body))
     } else {
         HttpResponse::NotFound().json(serde_json::json!({
             "success": false,
@@ -2360,7 +3029,14 @@ async fn get_transaction_details(
 async fn get_transaction_status(
     path: web::Path<String>,
     storage: Data<Arc<Storage>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+    query: web::Query<HashMap<String, String>>,
 ) -> impl Responder {
+    let selection = match requested_fields(&query, TRANSACTION_DETAIL_FIELDS) {
+        Ok(selection) => selection,
+        Err(response) => return *response,
+    };
+    let config = config_manager.get_config().await;
     let transaction_id = path.into_inner();
     
     let transactions = storage.get_transactions(1000);
@@ -2378,7 +3054,7 @@ async fn get_transaction_status(
         let mut response_obj = response.as_object().unwrap().clone();
         if let Some(tx_hash) = &transaction.tx_hash {
             response_obj.insert("transaction_hash".to_string(), serde_json::Value::String(tx_hash.clone()));
-            response_obj.insert("block_explorer_url".to_string(), serde_json::Value::String(get_block_explorer_url(transaction.chain_id, tx_hash)));
+            response_obj.insert("block_explorer_url".to_string(), json!(get_block_explorer_url(&config, transaction.chain_id, tx_hash)));
         } else {
             response_obj.insert("transaction_hash".to_string(), serde_json::Value::Null);
         }
@@ -2392,7 +3068,7 @@ async fn get_transaction_status(
         };
         response_obj.insert("message".to_string(), serde_json::Value::String(message.to_string()));
         
-        HttpResponse::Ok().json(serde_json::Value::Object(response_obj))
+        HttpResponse::Ok().json(fields::select(selection.as_ref(), serde_json::Value::Object(response_obj)))
     } else {
         HttpResponse::NotFound().json(serde_json::json!({
             "success": false,
@@ -2402,12 +3078,155 @@ async fn get_transaction_status(
     }
 }
 
+/// Status name sent to WebSocket subscribers; pending transactions are waiting in the queue
+fn live_status_label(state: TransactionState) -> &'static str {
+    match state {
+        TransactionState::Pending => "queued",
+        other => other.as_str(),
+    }
+}
+
+/// Whether a live status stream ends after `state`. A transaction is only left `Dropped`
+/// when the relay gave up on it, so that ends the stream as well.
+fn ends_live_stream(state: TransactionState) -> bool {
+    state.is_terminal() || state == TransactionState::Dropped
+}
+
+fn live_status_message(config: &Config, transaction_id: &str, chain_id: u64, update: &TransactionStatusUpdate) -> serde_json::Value {
+    json!({
+        "transaction_id": transaction_id,
+        "status": live_status_label(update.state),
+        "chain_id": chain_id,
+        "transaction_hash": update.tx_hash,
+        "block_explorer_url": update.tx_hash.as_deref().and_then(|hash| get_block_explorer_url(config, chain_id, hash)),
+        "error": update.error,
+        "terminal": ends_live_stream(update.state),
+        "timestamp": update.timestamp.to_rfc3339(),
+    })
+}
+
+/// Current state of a stored transaction, in the shape of a processor status update
+fn current_status(storage: &Storage, transaction_id: &str) -> Option<(u64, TransactionStatusUpdate)> {
+    storage.get_transaction(transaction_id).map(|t| {
+        (t.chain_id, TransactionStatusUpdate {
+            transaction_id: t.id,
+            state: t.status,
+            tx_hash: t.tx_hash,
+            error: t.error_details,
+            timestamp: Utc::now(),
+        })
+    })
+}
+
+/// Close a live status stream, telling the client why
+async fn close_live_stream(session: actix_ws::Session, reason: &str) {
+    let _ = session.close(Some(actix_ws::CloseReason {
+        code: actix_ws::CloseCode::Normal,
+        description: Some(reason.to_string()),
+    })).await;
+}
+
+/// Live status over a WebSocket: the current status on connect, then every transition
+/// the processor makes until the transaction reaches a terminal state. At most
+/// `status_stream.max_connections` streams are open at once, and a stream is closed once
+/// idle for `idle_timeout_secs` or open for `max_lifetime_secs`.
+#[get("/ws/transactions/{id}")]
+async fn transaction_status_ws(
+    req: HttpRequest,
+    body: web::Payload,
+    path: web::Path<String>,
+    storage: Data<Arc<Storage>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+    processor: Data<Arc<TransactionProcessor>>,
+) -> actix_web::Result<HttpResponse> {
+    let transaction_id = path.into_inner();
+    let config = config_manager.get_config().await;
+    let limits = config.status_stream.clone();
+    // Subscribe before reading the current status so no transition in between is lost
+    let Some(mut stream) = processor.open_status_stream(limits.max_connections) else {
+        return Ok(HttpResponse::ServiceUnavailable().json(json!({
+            "success": false,
+            "error": "Too many live status streams",
+            "message": format!("At most {} live status streams may be open at once", limits.max_connections)
+        })));
+    };
+    let Some((chain_id, current)) = current_status(&storage, &transaction_id) else {
+        return Ok(HttpResponse::NotFound().json(json!({
+            "success": false,
+            "error": "Transaction not found",
+            "message": format!("No transaction found with ID: {}", transaction_id)
+        })));
+    };
+
+    let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+    let storage = Arc::clone(storage.get_ref());
+
+    actix_web::rt::spawn(async move {
+        let idle_timeout = std::time::Duration::from_secs(limits.idle_timeout_secs);
+        let lifetime = tokio::time::sleep(std::time::Duration::from_secs(limits.max_lifetime_secs));
+        tokio::pin!(lifetime);
+        let mut idle_deadline = tokio::time::Instant::now() + idle_timeout;
+
+        let message = live_status_message(&config, &transaction_id, chain_id, &current);
+        if session.text(message.to_string()).await.is_err() {
+            return;
+        }
+        let mut finished = ends_live_stream(current.state);
+
+        while !finished {
+            tokio::select! {
+                update = stream.updates.recv() => {
+                    let update = match update {
+                        Ok(update) if update.transaction_id == transaction_id => update,
+                        Ok(_) => continue,
+                        // Missed updates are replaced by the stored status
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => match current_status(&storage, &transaction_id) {
+                            Some((_, update)) => update,
+                            None => break,
+                        },
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    };
+                    idle_deadline = tokio::time::Instant::now() + idle_timeout;
+                    finished = ends_live_stream(update.state);
+                    let message = live_status_message(&config, &transaction_id, chain_id, &update);
+                    if session.text(message.to_string()).await.is_err() {
+                        return;
+                    }
+                }
+                message = messages.recv() => match message {
+                    Some(Ok(actix_ws::Message::Ping(bytes))) => {
+                        idle_deadline = tokio::time::Instant::now() + idle_timeout;
+                        if session.pong(&bytes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(actix_ws::Message::Close(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => idle_deadline = tokio::time::Instant::now() + idle_timeout,
+                },
+                _ = tokio::time::sleep_until(idle_deadline) => {
+                    close_live_stream(session, &format!("No status update for {}s", limits.idle_timeout_secs)).await;
+                    return;
+                }
+                _ = &mut lifetime => {
+                    close_live_stream(session, &format!("Stream open for its maximum of {}s", limits.max_lifetime_secs)).await;
+                    return;
+                }
+            }
+        }
+        let _ = session.close(None).await;
+    });
+
+    Ok(response)
+}
+
 #[get("/transactions/user/{user_id}")]
 async fn get_user_transactions(
     path: web::Path<String>,
     storage: Data<Arc<Storage>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
     query: web::Query<std::collections::HashMap<String, String>>,
 ) -> impl Responder {
+    let config = config_manager.get_config().await;
     let user_id = path.into_inner();
     let limit = query.get("limit")
         .and_then(|s| s.parse::<usize>().ok())
@@ -2428,7 +3247,7 @@ async fn get_user_transactions(
             
             if let Some(tx_hash) = &t.tx_hash {
                 tx_obj["transaction_hash"] = serde_json::Value::String(tx_hash.clone());
-                tx_obj["block_explorer_url"] = serde_json::Value::String(get_block_explorer_url(t.chain_id, tx_hash));
+                tx_obj["block_explorer_url"] = json!(get_block_explorer_url(&config, t.chain_id, tx_hash));
             } else {
                 tx_obj["transaction_hash"] = serde_json::Value::Null;
             }
@@ -2447,7 +3266,10 @@ async fn get_user_transactions(
 }
 
 #[get("/chains/supported")]
-async fn get_supported_chains() -> impl Responder {
+async fn get_supported_chains(
+    req: HttpRequest,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+) -> impl Responder {
     let chains = vec![
         serde_json::json!({
             "chain_id": 1114,
@@ -2483,7 +3305,8 @@ async fn get_supported_chains() -> impl Responder {
         })
     ];
     
-    HttpResponse::Ok().json(serde_json::json!({
+    let cache = config_manager.get_config().await.response_cache;
+    json_with_etag(&req, &config_manager.etag(), &cache, serde_json::json!({
         "success": true,
         "supported_chains": chains,
         "total_chains": chains.len(),
@@ -2493,7 +3316,9 @@ async fn get_supported_chains() -> impl Responder {
 
 #[get("/chains/{chain_id}/info")]
 async fn get_chain_info(
+    req: HttpRequest,
     path: web::Path<u64>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
 ) -> impl Responder {
     let chain_id = path.into_inner();
     
@@ -2545,18 +3370,52 @@ async fn get_chain_info(
         })
     };
     
-    HttpResponse::Ok().json(serde_json::json!({
+    let cache = config_manager.get_config().await.response_cache;
+    json_with_etag(&req, &config_manager.etag(), &cache, serde_json::json!({
         "success": true,
         "chain_info": chain_info,
         "timestamp": chrono::Utc::now().to_rfc3339(),
     }))
 }
 
+#[get("/chains/{chain_id}/eta")]
+async fn get_chain_eta(
+    path: web::Path<u64>,
+    blockchain_manager: Data<Arc<BlockchainManager>>,
+) -> impl Responder {
+    let chain_id = path.into_inner();
+
+    if !blockchain_manager.supports_chain(chain_id) {
+        return ErrorResponseBuilder::bad_request(&format!("Unsupported chain_id: {chain_id}"));
+    }
+
+    match blockchain_manager.get_confirmation_eta(chain_id).await {
+        Ok(eta) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "chain_id": chain_id,
+            "chain_name": get_chain_name(chain_id),
+            "eta": eta,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        })),
+        Err(e) => ErrorResponseBuilder::service_unavailable(
+            &format!("Unable to estimate confirmation time for chain {chain_id}: {e}")
+        ),
+    }
+}
+
 #[get("/transaction/hash/{tx_hash}")]
 async fn get_transaction_by_hash(
     path: web::Path<String>,
     storage: Data<Arc<Storage>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+    decoder: Data<Arc<CalldataDecoder>>,
+    query: web::Query<HashMap<String, String>>,
 ) -> impl Responder {
+    let selection = match requested_fields(&query, TRANSACTION_DETAIL_FIELDS) {
+        Ok(selection) => selection,
+        Err(response) => return *response,
+    };
+    let config = config_manager.get_config().await;
     let tx_hash = path.into_inner();
     
     let transactions = storage.get_transactions(1000);
@@ -2571,7 +3430,7 @@ async fn get_transaction_by_hash(
             "chain_id": transaction.chain_id,
             "chain_name": get_chain_name(transaction.chain_id),
             "timestamp": transaction.timestamp.to_rfc3339(),
-            "block_explorer_url": get_block_explorer_url(transaction.chain_id, &tx_hash),
+            "block_explorer_url": get_block_explorer_url(&config, transaction.chain_id, &tx_hash),
         });
         
         // Add appropriate message based on status
@@ -2583,8 +3442,9 @@ async fn get_transaction_by_hash(
             _ => &format!("Transaction status: {}", transaction.status)
         };
         response_obj.insert("message".to_string(), serde_json::Value::String(message.to_string()));
+        let body = with_decoded_input(serde_json::Value::Object(response_obj), transaction, &decoder, &config);
         
-        HttpResponse::Ok().json(serde_json::Value::Object(response_obj))
+        HttpResponse::Ok().json(fields::select(selection.as_ref(), body))
     } else {
         HttpResponse::NotFound().json(serde_json::json!({
             "success": false,
@@ -2594,6 +3454,50 @@ async fn get_transaction_by_hash(
     }
 }
 
+/// Status of a transaction read from the chain itself rather than the relay's records
+#[get("/transaction/hash/{tx_hash}/onchain")]
+async fn get_onchain_transaction_status(
+    path: web::Path<String>,
+    query: web::Query<HashMap<String, String>>,
+    blockchain_manager: Data<Arc<BlockchainManager>>,
+    config_manager: Data<Arc<DynamicConfigManager>>,
+) -> impl Responder {
+    let tx_hash = path.into_inner();
+    let hash = match ethers::types::H256::from_str(&tx_hash) {
+        Ok(hash) => hash,
+        Err(_) => return ErrorResponseBuilder::bad_request(&format!("Invalid transaction hash: {tx_hash}")),
+    };
+    let chain_id = match query.get("chain_id").map(|v| v.parse::<u64>()) {
+        Some(Ok(chain_id)) => chain_id,
+        Some(Err(_)) => return ErrorResponseBuilder::bad_request("chain_id must be a number"),
+        None => return ErrorResponseBuilder::bad_request("chain_id query parameter is required"),
+    };
+    if !blockchain_manager.supports_chain(chain_id) {
+        return ErrorResponseBuilder::bad_request(&format!("Unsupported chain_id: {chain_id}"));
+    }
+
+    match blockchain_manager.get_onchain_status(chain_id, hash).await {
+        Ok(status) => {
+            let config = config_manager.get_config().await;
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "transaction_hash": tx_hash,
+                "chain_id": chain_id,
+                "chain_name": get_chain_name(chain_id),
+                "status": status.state,
+                "block_number": status.block_number,
+                "confirmations": status.confirmations,
+                "gas_used": status.gas_used,
+                "block_explorer_url": get_block_explorer_url(&config, chain_id, &tx_hash),
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+            }))
+        }
+        Err(e) => ErrorResponseBuilder::service_unavailable(
+            &format!("Unable to read transaction {tx_hash} from chain {chain_id}: {e}")
+        ),
+    }
+}
+
 /// Health check endpoint for contract connectivity
 #[get("/health/contracts")]
 async fn contract_health_check(
@@ -2767,3 +3671,570 @@ async fn detailed_contract_health_check(
     }))
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App, HttpServer};
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::{transaction::eip2718::TypedTransaction, Eip1559TransactionRequest};
+
+    /// Signed transfer that passes the validator, whose signature check reads the last
+    /// byte of the raw transaction as `v`; signing is deterministic, so search the nonces
+    fn signed_tx() -> String {
+        let wallet: LocalWallet = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(1114u64);
+        (0u64..)
+            .map(|nonce| {
+                let tx: TypedTransaction = Eip1559TransactionRequest::new()
+                    .to("0x2222222222222222222222222222222222222222".parse::<Address>().unwrap())
+                    .value(100u64)
+                    .gas(21_000)
+                    .max_fee_per_gas(2_000_000_000u64)
+                    .max_priority_fee_per_gas(1_000_000_000u64)
+                    .nonce(nonce)
+                    .chain_id(1114u64)
+                    .into();
+                let signature = wallet.sign_transaction_sync(&tx).unwrap();
+                tx.rlp_signed(&signature).to_vec()
+            })
+            .find(|raw| matches!(raw.last(), Some(0 | 1 | 27 | 28)))
+            .map(|raw| format!("0x{}", hex::encode(raw)))
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_auth_token_requires_a_configured_api_key() {
+        let auth_manager = |api_key: Option<&str>| Arc::new(
+            auth::AuthManager::with_secret("test_secret_for_token_endpoint_1234567890abcdef".to_string(), 600, 3600)
+                .with_api_keys(api_key.map(str::to_string), Some("admin-key".to_string())),
+        );
+        let request = |key: &str| test::TestRequest::post().uri("/auth/token").set_json(json!({ "api_key": key })).to_request();
+
+        let app = test::init_service(App::new().app_data(Data::new(auth_manager(None))).service(generate_token)).await;
+        let resp = test::call_service(&app, request("dev_api_key")).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::SERVICE_UNAVAILABLE);
+        assert!(test::call_service(&app, request("admin-key")).await.status().is_success());
+
+        let dir = std::env::temp_dir().join(format!("airchainpay-auth-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let blockchain_manager = Arc::new(BlockchainManager::new(Config::default()).unwrap());
+        let processor = Arc::new(TransactionProcessor::new(blockchain_manager, storage, None));
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(auth_manager(Some("relay-key"))))
+                .app_data(Data::new(processor))
+                .service(generate_token)
+                .service(get_queue),
+        ).await;
+        assert_eq!(test::call_service(&app, request("dev_api_key")).await.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+        let tokens: serde_json::Value = test::read_body_json(test::call_service(&app, request("admin-key")).await).await;
+
+        // The refresh token of an admin pair doesn't open admin endpoints
+        let refresh = test::TestRequest::get().uri("/queue")
+            .insert_header(("Authorization", format!("Bearer {}", tokens["refresh_token"].as_str().unwrap())))
+            .to_request();
+        assert_eq!(test::call_service(&app, refresh).await.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+        let access = test::TestRequest::get().uri("/queue")
+            .insert_header(("Authorization", format!("Bearer {}", tokens["token"].as_str().unwrap())))
+            .to_request();
+        assert!(test::call_service(&app, access).await.status().is_success());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// A relay for chain 1114 that only accepts registered senders; nothing listens on its RPC port
+    fn registered_sender_required() -> Config {
+        let mut config = Config::default();
+        config.supported_chains.insert(1114, crate::infrastructure::config::ChainConfig {
+            rpc_url: "http://127.0.0.1:1".to_string(),
+            contract_address: String::new(),
+            ..Default::default()
+        });
+        config.registered_sender.require_registered = true;
+        config
+    }
+
+    #[actix_web::test]
+    async fn test_simple_send_tx_requires_a_registered_sender() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-simple-send-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let config = registered_sender_required();
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&storage)))
+                .app_data(Data::new(Arc::new(BlockchainManager::new(config.clone()).unwrap())))
+                .app_data(Data::new(Arc::new(DynamicConfigManager::from_config(config).unwrap())))
+                .app_data(Data::new(Arc::new(AuditLogger::new(format!("{}/audit.json", dir), 100))))
+                .service(simple_send_tx),
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri("/simple_send_tx")
+            .set_json(json!({ "signed_tx": signed_tx(), "rpc_url": "http://127.0.0.1:8545", "chain_id": 1114 }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), actix_web::http::StatusCode::FORBIDDEN);
+        assert!(storage.get_transactions(10).is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    struct RejectAllPlugin;
+
+    #[async_trait::async_trait]
+    impl crate::validators::plugin::TransactionValidatorPlugin for RejectAllPlugin {
+        fn name(&self) -> &str {
+            "limits"
+        }
+
+        async fn validate(&self, _tx: &crate::validators::plugin::SubmittedTransaction<'_>) -> anyhow::Result<crate::validators::plugin::PluginFindings> {
+            Ok(crate::validators::plugin::PluginFindings::reject("daily limit reached"))
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_simple_send_tx_runs_validator_plugins() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-simple-send-plugins-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let mut config = registered_sender_required();
+        config.registered_sender.require_registered = false;
+        let config_manager = Arc::new(DynamicConfigManager::from_config(config.clone()).unwrap());
+        config_manager.validator_plugins().register(Arc::new(RejectAllPlugin));
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&storage)))
+                .app_data(Data::new(Arc::new(BlockchainManager::new(config).unwrap())))
+                .app_data(Data::new(config_manager))
+                .app_data(Data::new(Arc::new(AuditLogger::new(format!("{}/audit.json", dir), 100))))
+                .service(simple_send_tx),
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri("/simple_send_tx")
+            .set_json(json!({ "signed_tx": signed_tx(), "rpc_url": "http://127.0.0.1:8545", "chain_id": 1114 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("limits: daily limit reached"), "{}", body);
+        assert!(storage.get_transactions(10).is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[actix_web::test]
+    async fn test_transaction_bundle_requires_a_registered_sender() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-bundle-sender-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let config = registered_sender_required();
+        let blockchain_manager = Arc::new(BlockchainManager::new(config.clone()).unwrap());
+        let processor = Arc::new(TransactionProcessor::new(Arc::clone(&blockchain_manager), Arc::clone(&storage), None));
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&storage)))
+                .app_data(Data::new(blockchain_manager))
+                .app_data(Data::new(Arc::new(DynamicConfigManager::from_config(config).unwrap())))
+                .app_data(Data::new(processor))
+                .app_data(Data::new(Arc::new(AuditLogger::new(format!("{}/audit.json", dir), 100))))
+                .service(submit_transaction_bundle),
+        ).await;
+
+        let req = test::TestRequest::post()
+            .uri("/transactions/bundle")
+            .set_json(json!({ "chain_id": 1114, "signed_txs": [signed_tx()] }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), actix_web::http::StatusCode::FORBIDDEN);
+        assert!(storage.get_transactions(10).is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[actix_web::test]
+    async fn test_submit_transaction_is_validated_like_send_tx() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-submit-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let config = registered_sender_required();
+        let blockchain_manager = Arc::new(BlockchainManager::new(config.clone()).unwrap());
+        let processor = Arc::new(TransactionProcessor::new(Arc::clone(&blockchain_manager), Arc::clone(&storage), None));
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&storage)))
+                .app_data(Data::new(blockchain_manager))
+                .app_data(Data::new(Arc::new(EnhancedErrorHandler::new())))
+                .app_data(Data::new(Arc::new(DynamicConfigManager::from_config(config).unwrap())))
+                .app_data(Data::new(processor))
+                .app_data(Data::new(Arc::new(AuditLogger::new(format!("{}/audit.json", dir), 100))))
+                .service(submit_transaction),
+        ).await;
+
+        // A client-built queue entry is no longer accepted
+        let queued = test::TestRequest::post()
+            .uri("/submit_transaction")
+            .set_json(json!({ "transaction": {}, "chain_id": 1114, "retry_count": 0, "max_retries": 1000000, "metadata": { "signedTx": signed_tx() } }))
+            .to_request();
+        assert_eq!(test::call_service(&app, queued).await.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::post()
+            .uri("/submit_transaction")
+            .set_json(json!({ "signed_tx": signed_tx(), "rpc_url": "http://127.0.0.1:8545", "chain_id": 1114 }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), actix_web::http::StatusCode::FORBIDDEN);
+        assert!(storage.get_transactions(10).is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[actix_web::test]
+    async fn test_webhook_registration_requires_an_admin_token() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-webhooks-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let auth_manager = Arc::new(auth::AuthManager::with_secret("test_secret_for_webhook_endpoint_1234567890abcdef".to_string(), 600, 3600));
+        let webhooks = Arc::new(WebhookRegistry::new(crate::infrastructure::config::WebhookConfig::default(), storage));
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&auth_manager)))
+                .app_data(Data::new(webhooks))
+                .service(register_webhook),
+        ).await;
+        let request = |token: Option<String>| {
+            let req = test::TestRequest::post().uri("/webhooks").set_json(json!({ "url": "https://127.0.0.1/hook" }));
+            match token {
+                Some(token) => req.insert_header(("Authorization", format!("Bearer {}", token))).to_request(),
+                None => req.to_request(),
+            }
+        };
+
+        assert_eq!(test::call_service(&app, request(None)).await.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+        let client = auth_manager.issue_tokens("api-client", "relay").unwrap();
+        assert_eq!(test::call_service(&app, request(Some(client.access_token))).await.status(), actix_web::http::StatusCode::FORBIDDEN);
+
+        // An admin gets past the gate, but not to a loopback address
+        let admin = auth_manager.issue_tokens("api-client", auth::ADMIN_TOKEN_TYPE).unwrap();
+        let resp = test::call_service(&app, request(Some(admin.access_token))).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert!(body.to_string().contains("non-public address"), "{}", body);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[actix_web::test]
+    async fn test_repeated_idempotency_key_submits_once() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-idempotency-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let blockchain_manager = Arc::new(BlockchainManager::new(Config::default()).unwrap());
+        let processor = Arc::new(TransactionProcessor::new(Arc::clone(&blockchain_manager), Arc::clone(&storage), None));
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&storage)))
+                .app_data(Data::new(blockchain_manager))
+                .app_data(Data::new(Arc::new(EnhancedErrorHandler::new())))
+                .app_data(Data::new(Arc::new(DynamicConfigManager::from_config(Config::default()).unwrap())))
+                .app_data(Data::new(Arc::clone(&processor)))
+                .app_data(Data::new(Arc::new(AuditLogger::new(format!("{}/audit.json", dir), 100))))
+                .service(process_transaction),
+        ).await;
+
+        let body = json!({ "signed_tx": signed_tx(), "rpc_url": "http://127.0.0.1:8545", "chain_id": 1114 });
+        let mut responses = Vec::new();
+        for _ in 0..2 {
+            let req = test::TestRequest::post()
+                .uri("/send_tx")
+                .insert_header((IDEMPOTENCY_KEY_HEADER, "checkout-42"))
+                .set_json(&body)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert!(resp.status().is_success());
+            let replayed = resp.headers().contains_key("Idempotent-Replayed");
+            let json: serde_json::Value = test::read_body_json(resp).await;
+            responses.push((replayed, json));
+        }
+
+        assert!(!responses[0].0);
+        assert!(responses[1].0);
+        assert_eq!(responses[0].1, responses[1].1);
+        assert_eq!(storage.get_transactions(10).len(), 1);
+        let queued: usize = processor.queue_snapshot().await.values().map(Vec::len).sum();
+        assert_eq!(queued, 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[actix_web::test]
+    async fn test_idempotency_keys_are_scoped_per_principal_and_body() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-idempotency-scope-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let blockchain_manager = Arc::new(BlockchainManager::new(Config::default()).unwrap());
+        let processor = Arc::new(TransactionProcessor::new(Arc::clone(&blockchain_manager), Arc::clone(&storage), None));
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&storage)))
+                .app_data(Data::new(blockchain_manager))
+                .app_data(Data::new(Arc::new(EnhancedErrorHandler::new())))
+                .app_data(Data::new(Arc::new(DynamicConfigManager::from_config(Config::default()).unwrap())))
+                .app_data(Data::new(processor))
+                .app_data(Data::new(Arc::new(AuditLogger::new(format!("{}/audit.json", dir), 100))))
+                .service(process_transaction),
+        ).await;
+        let submit = |api_key: &str, rpc_url: &str| test::TestRequest::post()
+            .uri("/send_tx")
+            .insert_header((IDEMPOTENCY_KEY_HEADER, "order-7"))
+            .insert_header((crate::middleware::rate_limiting::API_KEY_HEADER, api_key.to_string()))
+            .set_json(json!({ "signed_tx": signed_tx(), "rpc_url": rpc_url, "chain_id": 1114 }))
+            .to_request();
+
+        // Two API keys using the same key value don't see each other's submissions
+        let alpha = test::call_service(&app, submit("alpha", "http://127.0.0.1:8545")).await;
+        assert!(alpha.status().is_success());
+        let beta = test::call_service(&app, submit("beta", "http://127.0.0.1:8545")).await;
+        assert!(beta.status().is_success());
+        assert!(!beta.headers().contains_key("Idempotent-Replayed"));
+        assert_eq!(storage.get_transactions(10).len(), 2);
+
+        // Reusing a key for a different body is an error, not a replay of the first response
+        let changed = test::call_service(&app, submit("alpha", "http://127.0.0.1:9545")).await;
+        assert_eq!(changed.status(), actix_web::http::StatusCode::UNPROCESSABLE_ENTITY);
+        let retried = test::call_service(&app, submit("alpha", "http://127.0.0.1:8545")).await;
+        assert!(retried.headers().contains_key("Idempotent-Replayed"));
+        assert_eq!(storage.get_transactions(10).len(), 2);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[actix_web::test]
+    async fn test_idempotency_keys_are_scoped_per_token_holder() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-idempotency-jwt-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let blockchain_manager = Arc::new(BlockchainManager::new(Config::default()).unwrap());
+        let processor = Arc::new(TransactionProcessor::new(Arc::clone(&blockchain_manager), Arc::clone(&storage), None));
+        let auth_manager = Arc::new(
+            auth::AuthManager::with_secret("test_secret_for_idempotency_scope_1234567890abcdef".to_string(), 600, 3600)
+                .with_api_keys(Some("client-a-key".to_string()), Some("client-b-key".to_string())),
+        );
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&storage)))
+                .app_data(Data::new(blockchain_manager))
+                .app_data(Data::new(Arc::new(EnhancedErrorHandler::new())))
+                .app_data(Data::new(Arc::new(DynamicConfigManager::from_config(Config::default()).unwrap())))
+                .app_data(Data::new(processor))
+                .app_data(Data::new(Arc::new(AuditLogger::new(format!("{}/audit.json", dir), 100))))
+                .app_data(Data::new(auth_manager))
+                .service(generate_token)
+                .service(process_transaction),
+        ).await;
+        let mut tokens = Vec::new();
+        for api_key in ["client-a-key", "client-b-key"] {
+            let req = test::TestRequest::post().uri("/auth/token").set_json(json!({ "api_key": api_key })).to_request();
+            let body: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+            tokens.push(body["token"].as_str().unwrap().to_string());
+        }
+        let submit = |token: &str| test::TestRequest::post()
+            .uri("/send_tx")
+            .insert_header((IDEMPOTENCY_KEY_HEADER, "order-7"))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(json!({ "signed_tx": signed_tx(), "rpc_url": "http://127.0.0.1:8545", "chain_id": 1114 }))
+            .to_request();
+
+        let first = test::call_service(&app, submit(&tokens[0])).await;
+        assert!(first.status().is_success());
+        let first: serde_json::Value = test::read_body_json(first).await;
+        let second = test::call_service(&app, submit(&tokens[1])).await;
+        assert!(!second.headers().contains_key("Idempotent-Replayed"));
+        let second: serde_json::Value = test::read_body_json(second).await;
+        assert_ne!(first["transaction_id"], second["transaction_id"]);
+        assert_eq!(storage.get_transactions(10).len(), 2);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[actix_web::test]
+    async fn test_dropped_submission_releases_its_idempotency_key() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-idempotency-drop-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let http_req = test::TestRequest::post()
+            .insert_header((IDEMPOTENCY_KEY_HEADER, "order-9"))
+            .to_http_request();
+
+        // The client disconnects while the submission is still running
+        let abandoned = idempotent_submission(&http_req, &storage, 60, "hash", std::future::pending());
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(50), abandoned).await.is_err());
+
+        let key = format!("{}:order-9", idempotency_principal(&http_req));
+        assert!(matches!(storage.claim_idempotency_key(&key, "hash", chrono::Duration::seconds(60)).unwrap(), IdempotencyClaim::New));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[actix_web::test]
+    async fn test_websocket_streams_status_until_terminal() {
+        use futures_util::StreamExt;
+
+        let dir = std::env::temp_dir().join(format!("airchainpay-ws-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let mut config = Config::default();
+        // Nothing listens on this port, so every broadcast fails and the transaction ends failed
+        if let Some(chain) = config.supported_chains.get_mut(&1114) {
+            chain.rpc_url = "http://127.0.0.1:1".to_string();
+        }
+        let blockchain_manager = Arc::new(BlockchainManager::new(config.clone()).unwrap());
+        let processor = Arc::new(TransactionProcessor::new(Arc::clone(&blockchain_manager), Arc::clone(&storage), None));
+        let config_manager = Arc::new(DynamicConfigManager::from_config(config).unwrap());
+        let audit_logger = Arc::new(AuditLogger::new(format!("{}/audit.json", dir), 100));
+
+        let app_storage = Arc::clone(&storage);
+        let app_processor = Arc::clone(&processor);
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(Data::new(Arc::clone(&app_storage)))
+                .app_data(Data::new(Arc::clone(&blockchain_manager)))
+                .app_data(Data::new(Arc::new(EnhancedErrorHandler::new())))
+                .app_data(Data::new(Arc::clone(&config_manager)))
+                .app_data(Data::new(Arc::clone(&app_processor)))
+                .app_data(Data::new(Arc::clone(&audit_logger)))
+                .service(process_transaction)
+                .service(transaction_status_ws)
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let address = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        let client = awc::Client::default();
+        let body = json!({ "signed_tx": signed_tx(), "rpc_url": "http://127.0.0.1:1", "chain_id": 1114 });
+        let submitted: serde_json::Value = client.post(format!("http://{}/send_tx", address))
+            .send_json(&body)
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let transaction_id = submitted["transaction_id"].as_str().unwrap().to_string();
+
+        let (_, mut socket) = client.ws(format!("ws://{}/ws/transactions/{}", address, transaction_id)).connect().await.unwrap();
+        processor.start().await.unwrap();
+
+        let mut messages = Vec::new();
+        while let Some(frame) = tokio::time::timeout(std::time::Duration::from_secs(30), socket.next()).await.unwrap() {
+            match frame.unwrap() {
+                awc::ws::Frame::Text(text) => {
+                    let message: serde_json::Value = serde_json::from_slice(&text).unwrap();
+                    assert_eq!(message["transaction_id"], transaction_id);
+                    messages.push(message);
+                }
+                awc::ws::Frame::Close(_) => break,
+                _ => {}
+            }
+        }
+
+        let statuses: Vec<&str> = messages.iter().map(|m| m["status"].as_str().unwrap()).collect();
+        assert_eq!(statuses.first(), Some(&"queued"));
+        assert!(statuses.contains(&"processing"), "{:?}", statuses);
+        assert_eq!(statuses.last(), Some(&"failed"));
+        assert_eq!(messages.last().unwrap()["terminal"], true);
+        assert!(messages.last().unwrap()["error"].as_str().unwrap().starts_with("Failed after"));
+
+        processor.shutdown().await;
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[actix_web::test]
+    async fn test_websocket_closes_for_dropped_transaction() {
+        use futures_util::StreamExt;
+
+        let dir = std::env::temp_dir().join(format!("airchainpay-ws-dropped-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let transaction = Transaction::new(signed_tx(), 1114);
+        let transaction_id = transaction.id.clone();
+        storage.save_transaction(transaction).unwrap();
+        storage.update_transaction_status_with_error(&transaction_id, TransactionState::Dropped, None, Some("Not found in mempool".to_string())).unwrap();
+
+        let blockchain_manager = Arc::new(BlockchainManager::new(Config::default()).unwrap());
+        let processor = Arc::new(TransactionProcessor::new(blockchain_manager, Arc::clone(&storage), None));
+        let config_manager = Arc::new(DynamicConfigManager::from_config(Config::default()).unwrap());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(Data::new(Arc::clone(&storage)))
+                .app_data(Data::new(Arc::clone(&config_manager)))
+                .app_data(Data::new(Arc::clone(&processor)))
+                .service(transaction_status_ws)
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let address = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        let (_, mut socket) = awc::Client::default().ws(format!("ws://{}/ws/transactions/{}", address, transaction_id)).connect().await.unwrap();
+        let mut messages = Vec::new();
+        while let Some(frame) = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next()).await.unwrap() {
+            match frame.unwrap() {
+                awc::ws::Frame::Text(text) => messages.push(serde_json::from_slice::<serde_json::Value>(&text).unwrap()),
+                awc::ws::Frame::Close(_) => break,
+                _ => {}
+            }
+        }
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["status"], "dropped");
+        assert_eq!(messages[0]["terminal"], true);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[actix_web::test]
+    async fn test_websocket_streams_are_capped_and_closed_when_idle() {
+        use futures_util::StreamExt;
+
+        let dir = std::env::temp_dir().join(format!("airchainpay-ws-limits-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let transaction = Transaction::new(signed_tx(), 1114);
+        let transaction_id = transaction.id.clone();
+        storage.save_transaction(transaction).unwrap();
+
+        let mut config = Config::default();
+        config.status_stream.max_connections = 1;
+        config.status_stream.idle_timeout_secs = 1;
+        let blockchain_manager = Arc::new(BlockchainManager::new(config.clone()).unwrap());
+        let processor = Arc::new(TransactionProcessor::new(blockchain_manager, Arc::clone(&storage), None));
+        let config_manager = Arc::new(DynamicConfigManager::from_config(config).unwrap());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(Data::new(Arc::clone(&storage)))
+                .app_data(Data::new(Arc::clone(&config_manager)))
+                .app_data(Data::new(Arc::clone(&processor)))
+                .service(transaction_status_ws)
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let address = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        let url = format!("ws://{}/ws/transactions/{}", address, transaction_id);
+        let client = awc::Client::default();
+        let (_, mut socket) = client.ws(url.as_str()).connect().await.unwrap();
+        let second = client.get(format!("http://{}/ws/transactions/{}", address, transaction_id)).send().await.unwrap();
+        assert_eq!(second.status(), actix_web::http::StatusCode::SERVICE_UNAVAILABLE);
+
+        // The transaction stays pending, so the stream ends on the idle timeout
+        let mut statuses = Vec::new();
+        let mut close_reason = None;
+        while let Some(frame) = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next()).await.unwrap() {
+            match frame.unwrap() {
+                awc::ws::Frame::Text(text) => statuses.push(serde_json::from_slice::<serde_json::Value>(&text).unwrap()["status"].clone()),
+                awc::ws::Frame::Close(reason) => {
+                    close_reason = reason.and_then(|reason| reason.description);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(statuses, vec![json!("queued")]);
+        assert!(close_reason.unwrap().contains("No status update"));
+
+        // The closed stream gave its slot back
+        let mut reconnected = None;
+        for _ in 0..50 {
+            if let Ok(connection) = client.ws(url.as_str()).connect().await {
+                reconnected = Some(connection);
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(reconnected.is_some());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
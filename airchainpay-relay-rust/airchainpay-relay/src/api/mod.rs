@@ -1,2 +1,4 @@
+pub mod conditional;
+pub mod fields;
 pub mod handlers;
 pub use handlers::*; 
\ No newline at end of file
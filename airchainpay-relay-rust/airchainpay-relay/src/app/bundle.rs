@@ -0,0 +1,148 @@
+//! Ordered transaction bundles
+//!
+//! A bundle is a list of signed transactions from one sender with consecutive
+//! nonces. The processor broadcasts them strictly in order, each one only after
+//! the previous one was mined.
+
+use anyhow::{anyhow, Result};
+use ethers::core::utils::rlp::{Decodable, Rlp};
+use ethers::types::{Address, Transaction, H256, U256};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleTransaction {
+    /// Id of the stored transaction record
+    pub id: String,
+    pub signed_tx: String,
+    pub nonce: U256,
+    pub tx_hash: H256,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionBundle {
+    pub id: String,
+    pub chain_id: u64,
+    pub sender: Address,
+    /// In broadcast order, nonces ascending by one
+    pub transactions: Vec<BundleTransaction>,
+}
+
+impl TransactionBundle {
+    /// Decode `signed_txs` and check they form a valid bundle for `chain_id`
+    pub fn from_signed(chain_id: u64, signed_txs: &[String], max_transactions: usize) -> Result<Self> {
+        if signed_txs.is_empty() {
+            return Err(anyhow!("Bundle contains no transactions"));
+        }
+        if signed_txs.len() > max_transactions {
+            return Err(anyhow!("Bundle has {} transactions, at most {} are allowed", signed_txs.len(), max_transactions));
+        }
+
+        let mut sender: Option<Address> = None;
+        let mut transactions: Vec<BundleTransaction> = Vec::with_capacity(signed_txs.len());
+        for (index, signed_tx) in signed_txs.iter().enumerate() {
+            let bytes = hex::decode(signed_tx.trim_start_matches("0x"))
+                .map_err(|e| anyhow!("Transaction {} is not valid hex: {}", index, e))?;
+            let tx = Transaction::decode(&Rlp::new(&bytes))
+                .map_err(|e| anyhow!("Transaction {} could not be decoded: {}", index, e))?;
+            if let Some(tx_chain_id) = tx.chain_id {
+                if tx_chain_id != U256::from(chain_id) {
+                    return Err(anyhow!("Transaction {} is signed for chain {}, not {}", index, tx_chain_id, chain_id));
+                }
+            }
+            let from = tx.recover_from()
+                .map_err(|e| anyhow!("Transaction {} has an invalid signature: {}", index, e))?;
+            match sender {
+                None => sender = Some(from),
+                Some(expected) if expected != from => {
+                    return Err(anyhow!("Transaction {} is from {:?}, bundle sender is {:?}", index, from, expected));
+                }
+                Some(_) => {}
+            }
+            if let Some(previous) = transactions.last() {
+                if tx.nonce != previous.nonce + 1 {
+                    return Err(anyhow!("Transaction {} has nonce {}, expected {}", index, tx.nonce, previous.nonce + 1));
+                }
+            }
+            transactions.push(BundleTransaction {
+                id: uuid::Uuid::new_v4().to_string(),
+                signed_tx: signed_tx.clone(),
+                nonce: tx.nonce,
+                tx_hash: tx.hash,
+            });
+        }
+
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            chain_id,
+            sender: sender.expect("bundle is not empty"),
+            transactions,
+        })
+    }
+
+    pub fn transaction_ids(&self) -> Vec<String> {
+        self.transactions.iter().map(|tx| tx.id.clone()).collect()
+    }
+}
+
+/// How each transaction of a processed bundle ended up
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BundleOutcome {
+    pub mined: Vec<String>,
+    pub reverted: Vec<String>,
+    pub failed: Vec<String>,
+    /// Never broadcast because the bundle halted
+    pub cancelled: Vec<String>,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::{transaction::eip2718::TypedTransaction, Eip1559TransactionRequest};
+
+    pub(crate) const SENDER_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+    const OTHER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    pub(crate) fn signed_tx(key: &str, chain_id: u64, nonce: u64) -> String {
+        let wallet = key.parse::<LocalWallet>().unwrap().with_chain_id(chain_id);
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to("0x2222222222222222222222222222222222222222".parse::<Address>().unwrap())
+            .value(1_000u64)
+            .gas(21_000)
+            .max_fee_per_gas(2_000_000_000u64)
+            .max_priority_fee_per_gas(1_000_000_000u64)
+            .nonce(nonce)
+            .chain_id(chain_id)
+            .into();
+        let signature = wallet.sign_transaction_sync(&tx).unwrap();
+        format!("0x{}", hex::encode(tx.rlp_signed(&signature)))
+    }
+
+    #[test]
+    fn test_bundle_keeps_order_and_sender() {
+        let txs: Vec<String> = (7..10).map(|nonce| signed_tx(SENDER_KEY, 1114, nonce)).collect();
+        let bundle = TransactionBundle::from_signed(1114, &txs, 16).unwrap();
+
+        let wallet = SENDER_KEY.parse::<LocalWallet>().unwrap();
+        assert_eq!(bundle.sender, wallet.address());
+        assert_eq!(bundle.transactions.iter().map(|tx| tx.nonce.as_u64()).collect::<Vec<_>>(), vec![7, 8, 9]);
+        let expected_hash = H256::from(ethers::utils::keccak256(hex::decode(txs[0].trim_start_matches("0x")).unwrap()));
+        assert_eq!(bundle.transactions[0].tx_hash, expected_hash);
+    }
+
+    #[test]
+    fn test_invalid_bundles_are_rejected() {
+        let gap = vec![signed_tx(SENDER_KEY, 1114, 0), signed_tx(SENDER_KEY, 1114, 2)];
+        assert!(TransactionBundle::from_signed(1114, &gap, 16).unwrap_err().to_string().contains("nonce"));
+
+        let mixed = vec![signed_tx(SENDER_KEY, 1114, 0), signed_tx(OTHER_KEY, 1114, 1)];
+        assert!(TransactionBundle::from_signed(1114, &mixed, 16).unwrap_err().to_string().contains("bundle sender"));
+
+        let wrong_chain = vec![signed_tx(SENDER_KEY, 84532, 0)];
+        assert!(TransactionBundle::from_signed(1114, &wrong_chain, 16).is_err());
+
+        let too_many: Vec<String> = (0..3).map(|nonce| signed_tx(SENDER_KEY, 1114, nonce)).collect();
+        assert!(TransactionBundle::from_signed(1114, &too_many, 2).is_err());
+        assert!(TransactionBundle::from_signed(1114, &[], 16).is_err());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::infrastructure::config::FeeMarkupConfig;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use ethers::types::{TransactionReceipt, H256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Gas cost and markup of one broadcast transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeRecord {
+    pub chain_id: u64,
+    pub tx_hash: H256,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    pub gas_cost_wei: U256,
+    pub markup_wei: U256,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ChainTotals {
+    transactions: u64,
+    gas_used: U256,
+    gas_cost_wei: U256,
+    markup_wei: U256,
+    last_recorded_at: Option<DateTime<Utc>>,
+}
+
+/// Per-chain totals; wei amounts are decimal strings so clients do not lose precision
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChainAccountingSummary {
+    pub chain_id: u64,
+    pub transactions: u64,
+    pub gas_used: String,
+    pub gas_cost_wei: String,
+    pub markup_wei: String,
+    /// Gas cost plus markup: what the relay is owed for its broadcasts
+    pub fees_due_wei: String,
+    pub last_recorded_at: Option<DateTime<Utc>>,
+}
+
+/// Gas the relay spends broadcasting versus the fees it charges for that, per chain.
+/// Totals are kept in memory and cover the time since the relay started.
+pub struct FeeAccountant {
+    markup: FeeMarkupConfig,
+    chains: Mutex<HashMap<u64, ChainTotals>>,
+}
+
+impl FeeAccountant {
+    pub fn new(markup: FeeMarkupConfig) -> Self {
+        Self {
+            markup,
+            chains: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn markup(&self) -> &FeeMarkupConfig {
+        &self.markup
+    }
+
+    /// Markup charged for a transaction that cost `gas_cost_wei`
+    pub fn markup_for(&self, gas_cost_wei: U256) -> U256 {
+        gas_cost_wei * U256::from(self.markup.markup_bps) / U256::from(BPS_DENOMINATOR)
+            + U256::from(self.markup.flat_fee_wei)
+    }
+
+    /// Account for a mined transaction using the receipt's `gasUsed × effectiveGasPrice`
+    pub fn record_receipt(&self, chain_id: u64, receipt: &TransactionReceipt) -> Result<FeeRecord> {
+        let gas_used = receipt.gas_used
+            .ok_or_else(|| anyhow!("Receipt {:?} has no gasUsed", receipt.transaction_hash))?;
+        let effective_gas_price = receipt.effective_gas_price
+            .ok_or_else(|| anyhow!("Receipt {:?} has no effectiveGasPrice", receipt.transaction_hash))?;
+        let gas_cost_wei = gas_used.checked_mul(effective_gas_price)
+            .ok_or_else(|| anyhow!("Gas cost overflow for {:?}", receipt.transaction_hash))?;
+        let markup_wei = self.markup_for(gas_cost_wei);
+
+        let mut chains = self.chains.lock().unwrap();
+        let totals = chains.entry(chain_id).or_default();
+        totals.transactions += 1;
+        totals.gas_used += gas_used;
+        totals.gas_cost_wei += gas_cost_wei;
+        totals.markup_wei += markup_wei;
+        totals.last_recorded_at = Some(Utc::now());
+
+        Ok(FeeRecord {
+            chain_id,
+            tx_hash: receipt.transaction_hash,
+            gas_used,
+            effective_gas_price,
+            gas_cost_wei,
+            markup_wei,
+        })
+    }
+
+    pub fn chain_summary(&self, chain_id: u64) -> Option<ChainAccountingSummary> {
+        let chains = self.chains.lock().unwrap();
+        chains.get(&chain_id).map(|totals| Self::summarize(chain_id, totals))
+    }
+
+    /// Totals for every chain the relay has broadcast on, ordered by chain id
+    pub fn summary(&self) -> BTreeMap<u64, ChainAccountingSummary> {
+        let chains = self.chains.lock().unwrap();
+        chains.iter()
+            .map(|(chain_id, totals)| (*chain_id, Self::summarize(*chain_id, totals)))
+            .collect()
+    }
+
+    fn summarize(chain_id: u64, totals: &ChainTotals) -> ChainAccountingSummary {
+        ChainAccountingSummary {
+            chain_id,
+            transactions: totals.transactions,
+            gas_used: totals.gas_used.to_string(),
+            gas_cost_wei: totals.gas_cost_wei.to_string(),
+            markup_wei: totals.markup_wei.to_string(),
+            fees_due_wei: (totals.gas_cost_wei + totals.markup_wei).to_string(),
+            last_recorded_at: totals.last_recorded_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt(gas_used: u64, effective_gas_price: u64) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: H256::random(),
+            gas_used: Some(gas_used.into()),
+            effective_gas_price: Some(effective_gas_price.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_gas_cost_and_markup_accumulate_per_chain() {
+        // 2.5% markup plus 1000 wei per transaction
+        let accountant = FeeAccountant::new(FeeMarkupConfig { markup_bps: 250, flat_fee_wei: 1_000 });
+
+        let first = accountant.record_receipt(1114, &receipt(21_000, 2_000_000_000)).unwrap();
+        assert_eq!(first.gas_cost_wei, U256::from(42_000_000_000_000u64));
+        assert_eq!(first.markup_wei, U256::from(1_050_000_001_000u64));
+
+        accountant.record_receipt(1114, &receipt(50_000, 1_000_000_000)).unwrap();
+        accountant.record_receipt(84532, &receipt(30_000, 100)).unwrap();
+
+        let core = accountant.chain_summary(1114).unwrap();
+        assert_eq!(core.transactions, 2);
+        assert_eq!(core.gas_used, "71000");
+        assert_eq!(core.gas_cost_wei, "92000000000000");
+        assert_eq!(core.markup_wei, "2300000002000");
+        assert_eq!(core.fees_due_wei, "94300000002000");
+
+        let base = accountant.chain_summary(84532).unwrap();
+        assert_eq!(base.gas_cost_wei, "3000000");
+        assert_eq!(base.markup_wei, "76000");
+        assert_eq!(accountant.summary().keys().copied().collect::<Vec<_>>(), vec![1114, 84532]);
+        assert!(accountant.chain_summary(1).is_none());
+    }
+
+    #[test]
+    fn test_without_markup_fees_due_equal_gas_cost() {
+        let accountant = FeeAccountant::new(FeeMarkupConfig::default());
+        let record = accountant.record_receipt(1114, &receipt(21_000, 10)).unwrap();
+        assert_eq!(record.markup_wei, U256::zero());
+        accountant.record_receipt(1114, &receipt(21_000, 10)).unwrap();
+
+        let summary = accountant.chain_summary(1114).unwrap();
+        assert_eq!(summary.gas_cost_wei, "420000");
+        assert_eq!(summary.markup_wei, "0");
+        assert_eq!(summary.fees_due_wei, summary.gas_cost_wei);
+    }
+
+    #[test]
+    fn test_receipt_without_effective_gas_price_is_not_recorded() {
+        let accountant = FeeAccountant::new(FeeMarkupConfig::default());
+        let mut incomplete = receipt(21_000, 1);
+        incomplete.effective_gas_price = None;
+
+        assert!(accountant.record_receipt(1114, &incomplete).is_err());
+        assert!(accountant.summary().is_empty());
+    }
+}
@@ -1,2 +1,5 @@
 pub mod transaction_service;
 pub mod scheduler;
+pub mod fee_accounting;
+pub mod bundle;
+pub mod webhooks;
@@ -1,10 +1,15 @@
-use crate::infrastructure::blockchain::manager::BlockchainManager;
-use crate::infrastructure::storage::file_storage::Storage;
+use crate::app::bundle::{BundleOutcome, TransactionBundle};
+use crate::app::fee_accounting::FeeAccountant;
+use crate::app::webhooks::WebhookRegistry;
+use crate::domain::error::BlockchainError;
+use crate::infrastructure::config::{BundleConfig, BundleRevertPolicy};
+use crate::infrastructure::blockchain::manager::{BlockchainManager, MempoolStatus, OnchainState};
+use crate::infrastructure::storage::file_storage::{Storage, Transaction, TransactionState};
 use anyhow::Result;
 use std::sync::Arc;
-use tokio::sync::{RwLock, Mutex};
+use tokio::sync::{broadcast, RwLock, Mutex};
 use tokio::time::{Duration};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::cmp::Ordering;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -27,6 +32,77 @@ pub struct QueuedTransaction {
     pub retry_delay: Duration,
     pub chain_id: u64,
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Deadline after which the transaction must not be broadcast
+    #[serde(default)]
+    pub valid_until: Option<DateTime<Utc>>,
+    /// Not picked up by a worker before this time, for transactions put back in the queue
+    #[serde(default)]
+    pub not_before: Option<DateTime<Utc>>,
+}
+
+impl QueuedTransaction {
+    /// Relay transaction id, from the metadata or the transaction payload
+    pub fn id(&self) -> Option<&str> {
+        self.metadata.get("id")
+            .or_else(|| self.transaction.get("id"))
+            .and_then(|v| v.as_str())
+    }
+
+    /// Whether a worker may pick the transaction up at `now`
+    pub fn is_ready_at(&self, now: DateTime<Utc>) -> bool {
+        self.not_before.is_none_or(|not_before| now >= not_before)
+    }
+
+    /// Whether the broadcast deadline has passed at `now`
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.valid_until.is_some_and(|deadline| now > deadline)
+    }
+
+    /// The bundle carried by this entry, when it queues a whole bundle
+    pub fn bundle(&self) -> Option<TransactionBundle> {
+        self.metadata.get(BUNDLE_METADATA_KEY)
+            .and_then(|bundle| serde_json::from_value(bundle.clone()).ok())
+    }
+}
+
+/// Metadata key under which a queued bundle is stored
+const BUNDLE_METADATA_KEY: &str = "bundle";
+
+/// How long a transaction held for an earlier nonce stays back in the queue before a
+/// worker tries it again
+const HELD_REQUEUE_DELAY: Duration = Duration::from_millis(500);
+
+/// Source of the current time, injectable so expiry can be tested deterministically
+pub type Clock = Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>;
+
+/// Operator view of a transaction waiting in the queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedTransactionInfo {
+    pub id: Option<String>,
+    pub chain_id: u64,
+    pub priority: TransactionPriority,
+    pub retry_count: u32,
+    pub max_retries: u32,
+    pub queued_at: DateTime<Utc>,
+    pub age_secs: i64,
+}
+
+impl TransactionPriority {
+    pub const ALL: [TransactionPriority; 4] = [
+        TransactionPriority::Low,
+        TransactionPriority::Normal,
+        TransactionPriority::High,
+        TransactionPriority::Critical,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionPriority::Low => "low",
+            TransactionPriority::Normal => "normal",
+            TransactionPriority::High => "high",
+            TransactionPriority::Critical => "critical",
+        }
+    }
 }
 
 impl PartialEq for QueuedTransaction {
@@ -90,6 +166,23 @@ pub struct TransactionProcessorConfig {
     pub batch_processing: bool,
     pub batch_size: usize,
     pub batch_timeout: Duration,
+    pub mempool_check_delay: Duration,
+    pub rebroadcast_dropped: bool,
+    /// How long shutdown waits for in-flight transactions before aborting their workers
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: Duration,
+    /// Time in queue that earns a transaction one extra priority level, so low
+    /// priority work cannot starve. Zero disables aging.
+    #[serde(default = "default_priority_aging_interval")]
+    pub priority_aging_interval: Duration,
+}
+
+fn default_shutdown_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_priority_aging_interval() -> Duration {
+    Duration::from_secs(60)
 }
 
 impl Default for TransactionProcessorConfig {
@@ -107,23 +200,156 @@ impl Default for TransactionProcessorConfig {
             batch_processing: false,
             batch_size: 10,
             batch_timeout: Duration::from_secs(30),
+            mempool_check_delay: Duration::from_secs(15),
+            rebroadcast_dropped: true,
+            shutdown_timeout: Duration::from_secs(30),
+            priority_aging_interval: default_priority_aging_interval(),
         }
     }
 }
 
+/// What was left unfinished when the processor stopped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// Queued transactions written to storage and restored on the next start
+    pub queued_persisted: usize,
+    pub persisted_ids: Vec<String>,
+    /// Transactions being processed when shutdown began that finished in time
+    pub in_flight_completed: usize,
+    /// Transactions still processing at the timeout; their workers were aborted and they
+    /// stay in the persisted queue to be retried on the next start
+    pub in_flight_abandoned: usize,
+    pub abandoned_ids: Vec<String>,
+    /// Webhook deliveries still in progress when the report was made
+    pub pending_callbacks: usize,
+    /// Failures while persisting the queue or flushing storage/audit
+    pub errors: Vec<String>,
+}
+
+impl ShutdownReport {
+    pub fn is_clean(&self) -> bool {
+        self.in_flight_abandoned == 0 && self.errors.is_empty()
+    }
+
+    pub fn log(&self) {
+        let summary = format!(
+            "Shutdown report: {} queued persisted, {} in-flight completed, {} in-flight abandoned, {} pending callbacks, {} errors",
+            self.queued_persisted, self.in_flight_completed, self.in_flight_abandoned, self.pending_callbacks, self.errors.len()
+        );
+        if self.is_clean() {
+            log::info!("{}", summary);
+        } else {
+            log::warn!("{}", summary);
+        }
+        if !self.abandoned_ids.is_empty() {
+            log::warn!("Abandoned in-flight transactions: {}", self.abandoned_ids.join(", "));
+        }
+        for error in &self.errors {
+            log::error!("Shutdown error: {}", error);
+        }
+    }
+
+    pub fn write_to_file(&self, path: &str) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
 pub struct TransactionQueue {
     queue: VecDeque<QueuedTransaction>,
+    priority_ordering: bool,
+    aging_interval: Duration,
 }
 
 impl TransactionQueue {
     pub fn new(_max_size: usize) -> Self {
         Self {
             queue: VecDeque::new(),
+            priority_ordering: false,
+            aging_interval: Duration::ZERO,
+        }
+    }
+
+    /// Dequeue by effective priority instead of arrival order
+    pub fn with_priority(mut self, aging_interval: Duration) -> Self {
+        self.priority_ordering = true;
+        self.aging_interval = aging_interval;
+        self
+    }
+
+    /// Base priority plus one level for every full aging interval spent in the queue
+    pub fn effective_priority(&self, tx: &QueuedTransaction, now: DateTime<Utc>) -> u64 {
+        let base = tx.priority.clone() as u64;
+        if self.aging_interval.is_zero() {
+            return base;
         }
+        // Whole nanoseconds, so an interval under a millisecond still divides safely
+        let waited = (now - tx.queued_at).to_std().unwrap_or_default();
+        let levels = waited.as_nanos() / self.aging_interval.as_nanos();
+        base.saturating_add(u64::try_from(levels).unwrap_or(u64::MAX))
+    }
+
+    /// Queued transactions per base priority; every priority is present, aging is ignored
+    pub fn depth_by_priority(&self) -> BTreeMap<TransactionPriority, usize> {
+        let mut depth: BTreeMap<TransactionPriority, usize> = TransactionPriority::ALL.iter().map(|p| (p.clone(), 0)).collect();
+        for tx in &self.queue {
+            *depth.entry(tx.priority.clone()).or_default() += 1;
+        }
+        depth
     }
 
     pub fn pop(&mut self) -> Option<QueuedTransaction> {
-        self.queue.pop_front()
+        self.pop_at(Utc::now())
+    }
+
+    /// Remove the transaction with the highest effective priority at `now`; ties go
+    /// to the one queued first. Transactions not yet ready at `now` are skipped.
+    pub fn pop_at(&mut self, now: DateTime<Utc>) -> Option<QueuedTransaction> {
+        if !self.priority_ordering {
+            let index = self.queue.iter().position(|tx| tx.is_ready_at(now))?;
+            return self.queue.remove(index);
+        }
+        let index = self.queue
+            .iter()
+            .enumerate()
+            .filter(|(_, tx)| tx.is_ready_at(now))
+            .max_by(|(ia, a), (ib, b)| {
+                self.effective_priority(a, now)
+                    .cmp(&self.effective_priority(b, now))
+                    .then_with(|| b.queued_at.cmp(&a.queued_at))
+                    .then_with(|| ib.cmp(ia))
+            })
+            .map(|(index, _)| index)?;
+        self.queue.remove(index)
+    }
+}
+
+/// A transaction's move to a new lifecycle state, published to status subscribers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionStatusUpdate {
+    pub transaction_id: String,
+    /// `Pending` is published when the transaction enters the queue
+    pub state: TransactionState,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Status updates buffered per subscriber before it starts lagging
+const STATUS_UPDATE_CAPACITY: usize = 256;
+
+/// Status updates for one live stream, holding one of the processor's stream slots
+/// until it is dropped
+pub struct StatusStream {
+    pub updates: broadcast::Receiver<TransactionStatusUpdate>,
+    open_streams: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl Drop for StatusStream {
+    fn drop(&mut self) {
+        self.open_streams.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
     }
 }
 
@@ -149,6 +375,17 @@ pub struct TransactionProcessor {
     metrics: Arc<RwLock<TransactionMetrics>>,
     workers: Arc<RwLock<HashMap<String, tokio::task::JoinHandle<()>>>>,
     running: Arc<RwLock<bool>>,
+    clock: Clock,
+    /// Transaction id being processed, per worker
+    in_flight: Arc<std::sync::Mutex<HashMap<String, String>>>,
+    /// Queued and in-flight transactions by id, mirrored to storage until they finish
+    durable: DurableQueue,
+    fee_accountant: Option<Arc<FeeAccountant>>,
+    bundle_config: BundleConfig,
+    status_updates: broadcast::Sender<TransactionStatusUpdate>,
+    /// Live status streams currently open, see `open_status_stream`
+    open_streams: Arc<std::sync::atomic::AtomicUsize>,
+    webhooks: Option<Arc<WebhookRegistry>>,
 }
 
 impl TransactionProcessor {
@@ -158,7 +395,11 @@ impl TransactionProcessor {
         config: Option<TransactionProcessorConfig>,
     ) -> Self {
         let config = config.unwrap_or_default();
-        let queue = Arc::new(Mutex::new(TransactionQueue::new(config.max_queue_size)));
+        let mut queue = TransactionQueue::new(config.max_queue_size);
+        if config.enable_priority_queue {
+            queue = queue.with_priority(config.priority_aging_interval);
+        }
+        let queue = Arc::new(Mutex::new(queue));
         let metrics = Arc::new(RwLock::new(TransactionMetrics {
             total_processed: 0,
             total_successful: 0,
@@ -180,43 +421,406 @@ impl TransactionProcessor {
             metrics,
             workers,
             running: Arc::new(RwLock::new(false)),
+            clock: Arc::new(Utc::now),
+            in_flight: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            durable: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            fee_accountant: None,
+            bundle_config: BundleConfig::default(),
+            status_updates: broadcast::channel(STATUS_UPDATE_CAPACITY).0,
+            open_streams: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            webhooks: None,
         }
     }
 
+    /// Replace the clock used for deadline checks and priority aging
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Record the gas cost and markup of every mined transaction
+    pub fn with_fee_accountant(mut self, fee_accountant: Arc<FeeAccountant>) -> Self {
+        self.fee_accountant = Some(fee_accountant);
+        self
+    }
+
+    /// Revert policy and receipt polling used for transaction bundles
+    pub fn with_bundle_config(mut self, bundle_config: BundleConfig) -> Self {
+        self.bundle_config = bundle_config;
+        self
+    }
+
+    /// Webhooks whose outstanding deliveries are counted in the shutdown report
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookRegistry>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    pub fn bundle_config(&self) -> &BundleConfig {
+        &self.bundle_config
+    }
+
+    /// Store a record for every transaction of the bundle and queue the bundle as one
+    /// entry, so a single worker processes it and it is persisted like any transaction
+    pub async fn submit_bundle(&self, bundle: TransactionBundle) -> Result<()> {
+        let records = bundle.clone();
+        self.storage.blocking(move |storage| save_bundle_records(storage, &records)).await?;
+        let mut metadata = HashMap::new();
+        metadata.insert("id".to_string(), serde_json::json!(bundle.id));
+        metadata.insert(BUNDLE_METADATA_KEY.to_string(), serde_json::to_value(&bundle)?);
+        let entry = QueuedTransaction {
+            transaction: serde_json::json!({ "id": bundle.id, "chain_id": bundle.chain_id }),
+            priority: TransactionPriority::Normal,
+            queued_at: Utc::now(),
+            retry_count: 0,
+            max_retries: 0,
+            retry_delay: Duration::ZERO,
+            chain_id: bundle.chain_id,
+            metadata,
+            valid_until: None,
+            not_before: None,
+        };
+
+        let mut queue_guard = self.queue.lock().await;
+        if queue_guard.queue.len() >= self.config.max_queue_size {
+            drop(queue_guard);
+            let ids = bundle.transaction_ids();
+            let _ = self.storage.blocking(move |storage| {
+                for id in ids {
+                    let _ = storage.update_transaction_status_with_error(&id, TransactionState::Cancelled, None, Some("Transaction queue is full".to_string()));
+                }
+                Ok(())
+            }).await;
+            return Err(anyhow::anyhow!("Transaction queue is full (max: {})", self.config.max_queue_size));
+        }
+        self.remember_queued(&bundle.id, &entry).await;
+        queue_guard.queue.push_back(entry);
+        drop(queue_guard);
+        for id in bundle.transaction_ids() {
+            self.publish_status(&id, TransactionState::Pending, None, None);
+        }
+        Ok(())
+    }
+
+    /// Process a bundle taken from the queue, then drop it from the persisted queue
+    async fn process_queued_bundle(&self, bundle: TransactionBundle, worker_name: &str) {
+        let outcome = self.process_bundle(&bundle).await;
+        self.forget_queued(&bundle.id).await;
+        println!(
+            "{} finished bundle {}: {} mined, {} reverted, {} failed, {} cancelled",
+            worker_name, bundle.id, outcome.mined.len(), outcome.reverted.len(), outcome.failed.len(), outcome.cancelled.len()
+        );
+    }
+
+    /// Broadcast the bundle in nonce order, each transaction only once the previous
+    /// one is mined. A revert halts the rest of the bundle under `BundleRevertPolicy::Halt`;
+    /// a broadcast or receipt failure always does. Transactions already completed, as when a
+    /// bundle is resumed after a restart, are not sent again.
+    pub async fn process_bundle(&self, bundle: &TransactionBundle) -> BundleOutcome {
+        let mut outcome = BundleOutcome::default();
+        let poll_interval = Duration::from_millis(self.bundle_config.receipt_poll_interval_ms);
+        let timeout = Duration::from_secs(self.bundle_config.receipt_timeout_secs);
+
+        for (index, bundled) in bundle.transactions.iter().enumerate() {
+            if self.storage.get_transaction_state(&bundled.id) == Some(TransactionState::Completed) {
+                outcome.mined.push(bundled.id.clone());
+                continue;
+            }
+            let mut metadata = HashMap::new();
+            metadata.insert("id".to_string(), serde_json::json!(bundled.id));
+            metadata.insert("signedTx".to_string(), serde_json::json!(bundled.signed_tx));
+            let tx = QueuedTransaction {
+                transaction: serde_json::json!({ "id": bundled.id, "signed_tx": bundled.signed_tx, "chain_id": bundle.chain_id }),
+                priority: TransactionPriority::Normal,
+                queued_at: Utc::now(),
+                retry_count: 0,
+                max_retries: 0,
+                retry_delay: Duration::ZERO,
+                chain_id: bundle.chain_id,
+                metadata,
+                valid_until: None,
+                not_before: None,
+            };
+            let _ = self.update_status(&bundled.id, TransactionState::Processing, None, None).await;
+
+            let result = match self.broadcast_bundled(&tx, poll_interval).await {
+                Ok(tx_hash) => {
+                    self.record_broadcast(&bundled.id, tx_hash).await;
+                    self.blockchain_manager.poll_mined_receipt(bundle.chain_id, tx_hash, poll_interval, timeout).await
+                }
+                Err(e) => Err(e),
+            };
+
+            let halt_reason = match result {
+                Ok(receipt) => {
+                    let tx_hash = Some(format!("{:?}", receipt.transaction_hash));
+                    if let Some(fee_accountant) = &self.fee_accountant {
+                        if let Err(e) = fee_accountant.record_receipt(bundle.chain_id, &receipt) {
+                            println!("Bundle {} could not account fees for {:?}: {}", bundle.id, receipt.transaction_hash, e);
+                        }
+                    }
+                    if receipt.status == Some(0u64.into()) {
+                        let block = receipt.block_number.map(|b| b.as_u64()).unwrap_or_default();
+                        let _ = self.update_status(&bundled.id, TransactionState::Failed, tx_hash, Some(format!("Reverted in block {}", block))).await;
+                        outcome.reverted.push(bundled.id.clone());
+                        match self.bundle_config.revert_policy {
+                            BundleRevertPolicy::Halt => Some(format!("transaction {} reverted", bundled.id)),
+                            BundleRevertPolicy::Continue => None,
+                        }
+                    } else {
+                        let _ = self.update_status(&bundled.id, TransactionState::Completed, tx_hash, None).await;
+                        outcome.mined.push(bundled.id.clone());
+                        None
+                    }
+                }
+                Err(e) => {
+                    let _ = self.update_status(&bundled.id, TransactionState::Failed, None, Some(e.to_string())).await;
+                    outcome.failed.push(bundled.id.clone());
+                    Some(format!("transaction {} failed: {}", bundled.id, e))
+                }
+            };
+
+            if let Some(reason) = halt_reason {
+                for remaining in &bundle.transactions[index + 1..] {
+                    let _ = self.update_status(&remaining.id, TransactionState::Cancelled, None, Some(format!("Bundle {} halted: {}", bundle.id, reason))).await;
+                    outcome.cancelled.push(remaining.id.clone());
+                }
+                break;
+            }
+        }
+        outcome
+    }
+
+    /// Broadcast one transaction of a bundle. A nonce hold is waited out here rather than
+    /// re-queued, since the worker stays with the bundle until its last receipt anyway; the
+    /// sequencer's hold timeout bounds the wait.
+    async fn broadcast_bundled(&self, tx: &QueuedTransaction, poll_interval: Duration) -> Result<ethers::types::H256> {
+        loop {
+            match self.blockchain_manager.broadcast_transaction(tx).await {
+                Err(e) if matches!(e.downcast_ref::<BlockchainError>(), Some(BlockchainError::NonceHeld { .. })) => {
+                    tokio::time::sleep(poll_interval).await;
+                }
+                result => return result.map(|(tx_hash, _)| tx_hash),
+            }
+        }
+    }
+
+    /// Receive every status transition the processor makes from now on
+    pub fn subscribe_status_updates(&self) -> broadcast::Receiver<TransactionStatusUpdate> {
+        self.status_updates.subscribe()
+    }
+
+    /// Subscribe a live status stream, unless `max_streams` are open already
+    pub fn open_status_stream(&self, max_streams: usize) -> Option<StatusStream> {
+        use std::sync::atomic::Ordering;
+        self.open_streams
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |open| (open < max_streams).then_some(open + 1))
+            .ok()?;
+        Some(StatusStream { updates: self.status_updates.subscribe(), open_streams: Arc::clone(&self.open_streams) })
+    }
+
+    /// Store a status transition and publish it to subscribers once it is recorded
+    async fn update_status(&self, id: &str, state: TransactionState, tx_hash: Option<String>, error: Option<String>) -> Result<()> {
+        let durable = Arc::clone(&self.durable);
+        let (stored_id, stored_hash, stored_error) = (id.to_string(), tx_hash.clone(), error.clone());
+        self.storage.blocking(move |storage| {
+            storage.update_transaction_status_with_error(&stored_id, state, stored_hash, stored_error)?;
+            if state.is_terminal() {
+                forget_queued(storage, &durable, &stored_id);
+            }
+            Ok(())
+        }).await?;
+        self.publish_status(id, state, tx_hash, error);
+        Ok(())
+    }
+
+    /// Rewrite the persisted queue as exactly the queued and in-flight transactions,
+    /// compacting the journal that `remember_queued` and `forget_queued` append to
+    async fn persist_durable(&self) -> Result<()> {
+        let durable = Arc::clone(&self.durable);
+        self.storage.blocking(move |storage| persist_durable(storage, &lock_durable(&durable))).await
+    }
+
+    /// Track a transaction in the persisted queue, replacing any earlier copy
+    async fn remember_queued(&self, id: &str, tx: &QueuedTransaction) {
+        let (durable, id, tx) = (Arc::clone(&self.durable), id.to_string(), tx.clone());
+        let _ = self.storage.blocking(move |storage| {
+            remember_queued(storage, &durable, &id, tx);
+            Ok(())
+        }).await;
+    }
+
+    /// Stop tracking a transaction that will not be processed again
+    async fn forget_queued(&self, id: &str) {
+        let (durable, id) = (Arc::clone(&self.durable), id.to_string());
+        let _ = self.storage.blocking(move |storage| {
+            forget_queued(storage, &durable, &id);
+            Ok(())
+        }).await;
+    }
+
+    fn publish_status(&self, id: &str, state: TransactionState, tx_hash: Option<String>, error: Option<String>) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.status_updates.send(TransactionStatusUpdate {
+            transaction_id: id.to_string(),
+            state,
+            tx_hash,
+            error,
+            timestamp: Utc::now(),
+        });
+    }
+
+    /// Mark the transaction expired if its `valid_until` deadline has passed.
+    /// Returns true when it must not be broadcast.
+    pub async fn expire_if_past_deadline(&self, tx: &QueuedTransaction) -> bool {
+        let now = (self.clock)();
+        if !tx.is_expired_at(now) {
+            return false;
+        }
+        if let Some(id) = tx.id() {
+            let deadline = tx.valid_until.map(|d| d.to_rfc3339()).unwrap_or_default();
+            let _ = self.update_status(id, TransactionState::Expired, None, Some(format!("Not broadcast: deadline {} passed", deadline))).await;
+        }
+        true
+    }
+
     pub async fn enqueue_transaction(&self, tx: QueuedTransaction) -> Result<()> {
         let mut queue_guard = self.queue.lock().await;
         if queue_guard.queue.len() >= self.config.max_queue_size {
             return Err(anyhow::anyhow!("Transaction queue is full (max: {})", self.config.max_queue_size));
         }
+        let id = tx.id().map(str::to_string);
+        if let Some(id) = &id {
+            self.remember_queued(id, &tx).await;
+        }
         queue_guard.queue.push_back(tx);
+        drop(queue_guard);
+        if let Some(id) = id {
+            self.publish_status(&id, TransactionState::Pending, None, None);
+        }
         Ok(())
     }
 
-    async fn process_transaction(&self, tx: QueuedTransaction, worker_name: &str) {
+    /// Snapshot of the queue grouped by chain, in processing order
+    pub async fn queue_snapshot(&self) -> HashMap<u64, Vec<QueuedTransactionInfo>> {
+        let now = Utc::now();
+        let queue_guard = self.queue.lock().await;
+        let mut by_chain: HashMap<u64, Vec<QueuedTransactionInfo>> = HashMap::new();
+        for tx in &queue_guard.queue {
+            by_chain.entry(tx.chain_id).or_default().push(QueuedTransactionInfo {
+                id: tx.id().map(str::to_string),
+                chain_id: tx.chain_id,
+                priority: tx.priority.clone(),
+                retry_count: tx.retry_count,
+                max_retries: tx.max_retries,
+                queued_at: tx.queued_at,
+                age_secs: (now - tx.queued_at).num_seconds(),
+            });
+        }
+        by_chain
+    }
+
+    /// Current queue depth per priority, for the metrics endpoint
+    pub async fn queue_depth_by_priority(&self) -> BTreeMap<TransactionPriority, usize> {
+        self.queue.lock().await.depth_by_priority()
+    }
+
+    /// Drop a queued transaction before a worker picks it up and mark it cancelled.
+    /// Returns `None` when no queued transaction has that id.
+    pub async fn cancel_queued(&self, id: &str) -> Result<Option<QueuedTransactionInfo>> {
+        let removed = {
+            let mut queue_guard = self.queue.lock().await;
+            match queue_guard.queue.iter().position(|tx| tx.id() == Some(id)) {
+                Some(index) => queue_guard.queue.remove(index),
+                None => None,
+            }
+        };
+        let Some(tx) = removed else {
+            return Ok(None);
+        };
+        self.forget_queued(id).await;
+
+        if self.storage.get_transaction_state(id).is_some() {
+            self.update_status(id, TransactionState::Cancelled, None, Some("Removed from queue by operator".to_string())).await?;
+        }
+
+        Ok(Some(QueuedTransactionInfo {
+            id: Some(id.to_string()),
+            chain_id: tx.chain_id,
+            priority: tx.priority,
+            retry_count: tx.retry_count,
+            max_retries: tx.max_retries,
+            queued_at: tx.queued_at,
+            age_secs: (Utc::now() - tx.queued_at).num_seconds(),
+        }))
+    }
+
+    async fn process_transaction(&self, mut tx: QueuedTransaction, worker_name: &str) {
         println!("{} is processing transaction: {:?}", worker_name, tx);
-        let max_retries = 3;
-        let mut attempt = 0;
-        let tx_id = tx.metadata.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+        // Attempts made before a restart count towards the limit
+        let max_retries = tx.max_retries;
+        let mut attempt = tx.retry_count;
+        let tx_id = tx.id().unwrap_or("").to_string();
         let mut last_err = None;
+
+        if let Some(bundle) = tx.bundle() {
+            self.process_queued_bundle(bundle, worker_name).await;
+            return;
+        }
+
+        if self.settle_earlier_broadcast(&tx, &tx_id, worker_name).await {
+            return;
+        }
+
+        if self.expire_if_past_deadline(&tx).await {
+            println!("{} skipped expired transaction {}", worker_name, tx_id);
+            return;
+        }
+
+        if attempt >= max_retries {
+            let details = format!("Retry limit reached: {} of {} attempts already made", attempt, max_retries);
+            let _ = self.update_status(&tx_id, TransactionState::Failed, None, Some(details.clone())).await;
+            println!("{} not sending transaction {}: {}", worker_name, tx_id, details);
+            return;
+        }
         
-        // Update status to processing
-        let _ = self.storage.update_transaction_status_with_error(&tx_id, "processing", None, None);
+        // Update status to processing, unless the transaction is back from a nonce hold and
+        // already marked retrying
+        if tx.not_before.is_none() {
+            let _ = self.update_status(&tx_id, TransactionState::Processing, None, None).await;
+        }
         
         while attempt < max_retries {
-            match self.blockchain_manager.send_transaction(&tx).await {
-                Ok(tx_hash) => {
+            if attempt > 0 && self.expire_if_past_deadline(&tx).await {
+                println!("{} stopped retrying expired transaction {}", worker_name, tx_id);
+                return;
+            }
+            match self.broadcast_and_confirm(&tx, &tx_id, worker_name).await {
+                Ok(None) => {
+                    // Dropped and not re-broadcast: nothing left to retry after a restart
+                    self.forget_queued(&tx_id).await;
+                    return;
+                }
+                Ok(Some(tx_hash)) => {
                     println!("{} successfully sent transaction: {:?}, hash: {}", worker_name, tx, tx_hash);
-                    let _ = self.storage.update_transaction_status_with_error(&tx_id, "completed", Some(format!("{:?}", tx_hash)), None);
+                    let _ = self.update_status(&tx_id, TransactionState::Completed, Some(format!("{:?}", tx_hash)), None).await;
+                    return;
+                }
+                Err(e) if matches!(e.downcast_ref::<BlockchainError>(), Some(BlockchainError::NonceHeld { .. })) => {
+                    println!("{} put transaction {} back in the queue: {}", worker_name, tx_id, e);
+                    self.requeue_held(tx, &tx_id, &e).await;
                     return;
                 }
                 Err(e) => {
                     println!("{} failed to send transaction (attempt {}): {:?}, error: {:?}", worker_name, attempt + 1, tx, e);
                     last_err = Some(e);
                     attempt += 1;
+                    tx.retry_count = attempt;
                     
                     // Update status to retrying if not the last attempt
                     if attempt < max_retries {
-                        let _ = self.storage.update_transaction_status_with_error(&tx_id, "retrying", None, Some(format!("Attempt {} failed: {}", attempt, last_err.as_ref().unwrap())));
+                        self.remember_queued(&tx_id, &tx).await;
+                        let _ = self.update_status(&tx_id, TransactionState::Retrying, None, Some(format!("Attempt {} failed: {}", attempt, last_err.as_ref().unwrap()))).await;
                     }
                     
                     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
@@ -230,11 +834,170 @@ impl TransactionProcessor {
             None => format!("Failed after {} attempts. No error details available.", max_retries)
         };
         
-        let _ = self.storage.update_transaction_status_with_error(&tx_id, "failed", None, Some(error_details.clone()));
+        let _ = self.update_status(&tx_id, TransactionState::Failed, None, Some(error_details.clone())).await;
         println!("{} permanently failed to send transaction: {:?}, error: {}", worker_name, tx, error_details);
     }
 
+    /// A transaction restored after a restart may have been broadcast before it, so its
+    /// receipt decides the outcome before anything is sent again: a mined transaction is
+    /// finished from its receipt and one still in the mempool is waited on. Returns true
+    /// when the transaction is settled and must not be broadcast.
+    async fn settle_earlier_broadcast(&self, tx: &QueuedTransaction, tx_id: &str, worker_name: &str) -> bool {
+        let broadcast_before = self.storage.get_transaction(tx_id)
+            .is_some_and(|stored| stored.broadcast_count > 0 || stored.tx_hash.is_some());
+        let raw_tx = tx.metadata.get("signedTx")
+            .and_then(|signed| signed.as_str())
+            .and_then(|signed| hex::decode(signed.trim_start_matches("0x")).ok());
+        let (true, Some(raw_tx)) = (broadcast_before, raw_tx) else {
+            return false;
+        };
+        let tx_hash = ethers::types::H256::from(ethers::utils::keccak256(raw_tx));
+
+        let state = match self.blockchain_manager.get_onchain_status(tx.chain_id, tx_hash).await {
+            Ok(status) => status.state,
+            Err(e) => {
+                println!("{} could not look up earlier broadcast {:?} of {}: {}", worker_name, tx_hash, tx_id, e);
+                return false;
+            }
+        };
+        let receipt = match state {
+            OnchainState::NotFound => return false,
+            OnchainState::Pending => self.blockchain_manager.wait_for_mined_receipt(tx.chain_id, tx_hash).await,
+            OnchainState::Success | OnchainState::Reverted => {
+                let poll_interval = Duration::from_millis(self.bundle_config.receipt_poll_interval_ms);
+                let timeout = Duration::from_secs(self.bundle_config.receipt_timeout_secs);
+                self.blockchain_manager.poll_mined_receipt(tx.chain_id, tx_hash, poll_interval, timeout).await
+            }
+        };
+        let receipt = match receipt {
+            Ok(receipt) => receipt,
+            // Dropped from the mempool since: broadcast it again
+            Err(e) => {
+                println!("{} lost track of earlier broadcast {:?} of {}: {}", worker_name, tx_hash, tx_id, e);
+                return false;
+            }
+        };
+
+        let reverted_in = receipt.status
+            .filter(|status| status.is_zero())
+            .map(|_| receipt.block_number.map(|n| n.as_u64()).unwrap_or_default());
+        self.mined(tx.chain_id, receipt, worker_name);
+        let hash = Some(format!("{:?}", tx_hash));
+        let _ = match reverted_in {
+            Some(block) => self.update_status(tx_id, TransactionState::Failed, hash, Some(format!("Reverted in block {}", block))).await,
+            None => self.update_status(tx_id, TransactionState::Completed, hash, None).await,
+        };
+        println!("{} settled transaction {} from the receipt of its earlier broadcast {:?}", worker_name, tx_id, tx_hash);
+        true
+    }
+
+    /// Put a transaction whose nonce is not due yet back in the queue, to be tried again
+    /// after `HELD_REQUEUE_DELAY` without counting an attempt. It is marked retrying the
+    /// first time only, so a long hold doesn't publish a status update every few hundred ms.
+    async fn requeue_held(&self, mut tx: QueuedTransaction, tx_id: &str, reason: &anyhow::Error) {
+        let first_hold = tx.not_before.is_none();
+        tx.not_before = Some((self.clock)() + chrono::Duration::from_std(HELD_REQUEUE_DELAY).unwrap_or_default());
+        self.remember_queued(tx_id, &tx).await;
+        if first_hold {
+            let _ = self.update_status(tx_id, TransactionState::Retrying, None, Some(reason.to_string())).await;
+        }
+        self.queue.lock().await.queue.push_back(tx);
+    }
+
+    /// Broadcast a transaction, verify it reached the mempool and wait for its receipt.
+    /// Returns `None` when the transaction was dropped and not re-broadcast.
+    async fn broadcast_and_confirm(&self, tx: &QueuedTransaction, tx_id: &str, worker_name: &str) -> Result<Option<ethers::types::H256>> {
+        use futures::future::{FusedFuture, FutureExt};
+
+        let (tx_hash, raw_tx) = self.blockchain_manager.broadcast_transaction(tx).await?;
+        self.record_broadcast(tx_id, tx_hash).await;
+
+        // The mempool check sleeps for `mempool_check_delay` first, so it runs as its own
+        // task beside the receipt wait: a transaction mined sooner is finished without
+        // holding the worker for the rest of the delay
+        let blockchain_manager = Arc::clone(&self.blockchain_manager);
+        let (chain_id, delay, rebroadcast) = (tx.chain_id, self.config.mempool_check_delay, self.config.rebroadcast_dropped);
+        let mut mempool_check = tokio::spawn(async move {
+            blockchain_manager.check_mempool(chain_id, tx_hash, raw_tx, delay, rebroadcast).await
+        });
+
+        let receipt = self.blockchain_manager.wait_for_mined_receipt(tx.chain_id, tx_hash).fuse();
+        futures::pin_mut!(receipt);
+        let status = tokio::select! {
+            mined = &mut receipt => match mined {
+                Ok(mined) => {
+                    mempool_check.abort();
+                    return Ok(Some(self.mined(tx.chain_id, mined, worker_name)));
+                }
+                // The node lost the transaction before the check ran; the check decides
+                // whether it is re-broadcast or dropped
+                Err(_) => (&mut mempool_check).await,
+            },
+            status = &mut mempool_check => status,
+        };
+        let status = status.map_err(anyhow::Error::from).and_then(|status| status);
+        if self.apply_mempool_status(status, tx_id, tx_hash, worker_name).await == Some(MempoolStatus::Dropped) {
+            return Ok(None);
+        }
+
+        let mined = if receipt.is_terminated() {
+            self.blockchain_manager.wait_for_mined_receipt(tx.chain_id, tx_hash).await?
+        } else {
+            receipt.await?
+        };
+        Ok(Some(self.mined(tx.chain_id, mined, worker_name)))
+    }
+
+    /// Record the outcome of a mempool check. A re-broadcast transaction keeps its status
+    /// and is still waited on; only one that was not re-broadcast is marked dropped.
+    async fn apply_mempool_status(&self, status: Result<MempoolStatus>, tx_id: &str, tx_hash: ethers::types::H256, worker_name: &str) -> Option<MempoolStatus> {
+        let delay = self.config.mempool_check_delay;
+        let status = match status {
+            Ok(status) => status,
+            Err(e) => {
+                println!("{} mempool check failed for {:?}: {}", worker_name, tx_hash, e);
+                return None;
+            }
+        };
+        match status {
+            MempoolStatus::Seen => {}
+            MempoolStatus::Rebroadcast => {
+                self.record_broadcast(tx_id, tx_hash).await;
+                println!("{} transaction {:?} missing from mempool after {:?}, re-broadcast once", worker_name, tx_hash, delay);
+            }
+            MempoolStatus::Dropped => {
+                println!("{} transaction {:?} missing from mempool after {:?}, marked dropped", worker_name, tx_hash, delay);
+                let _ = self.update_status(tx_id, TransactionState::Dropped, Some(format!("{:?}", tx_hash)), Some(format!("Not found in mempool after {:?}", delay))).await;
+            }
+        }
+        Some(status)
+    }
+
+    /// Account the fees of a mined transaction and return its hash
+    fn mined(&self, chain_id: u64, receipt: ethers::types::TransactionReceipt, worker_name: &str) -> ethers::types::H256 {
+        if let Some(fee_accountant) = &self.fee_accountant {
+            if let Err(e) = fee_accountant.record_receipt(chain_id, &receipt) {
+                println!("{} could not account fees for {:?}: {}", worker_name, receipt.transaction_hash, e);
+            }
+        }
+        receipt.transaction_hash
+    }
+
+    /// Copy the throttle's send counts into the stored transaction record
+    async fn record_broadcast(&self, tx_id: &str, tx_hash: ethers::types::H256) {
+        if let Some(counts) = self.blockchain_manager.broadcast_counts(tx_hash) {
+            let tx_id = tx_id.to_string();
+            let _ = self.storage.blocking(move |storage| storage.record_broadcast(&tx_id, counts.broadcasts, counts.last_broadcast_at)).await;
+        }
+    }
+
     pub async fn start(&self) -> Result<()> {
+        match self.restore_pending_queue().await {
+            Ok(0) => {}
+            Ok(restored) => println!("Restored {} persisted transactions to the queue", restored),
+            Err(e) => println!("Failed to restore the persisted transaction queue: {}", e),
+        }
+
         let mut running = self.running.write().await;
         *running = true;
         drop(running);
@@ -255,10 +1018,13 @@ impl TransactionProcessor {
                     }
                     let maybe_tx = {
                         let mut queue_guard = queue.lock().await;
-                        queue_guard.pop()
+                        queue_guard.pop_at((processor.clock)())
                     };
                     if let Some(tx) = maybe_tx {
+                        let tx_id = tx.id().unwrap_or_default().to_string();
+                        processor.in_flight.lock().unwrap().insert(worker_name_for_task.clone(), tx_id);
                         processor.process_transaction(tx, &worker_name_for_task).await;
+                        processor.in_flight.lock().unwrap().remove(&worker_name_for_task);
                     } else {
                         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
                     }
@@ -272,9 +1038,121 @@ impl TransactionProcessor {
         Ok(())
     }
 
+    /// Re-queue transactions persisted before the last stop or crash, keeping their retry
+    /// counts. Transactions already queued or finished in storage are skipped. Returns how
+    /// many were restored.
+    pub async fn restore_pending_queue(&self) -> Result<usize> {
+        let pending: Vec<QueuedTransaction> = self.storage.blocking(|storage| storage.load_pending_queue()).await?;
+        let mut restored = 0;
+        {
+            let mut queue_guard = self.queue.lock().await;
+            let mut durable = lock_durable(&self.durable);
+            for tx in pending {
+                let Some(id) = tx.id().map(str::to_string) else {
+                    continue;
+                };
+                let finished = self.storage.get_transaction_state(&id).is_some_and(|state| state.is_terminal());
+                if finished || durable.contains_key(&id) {
+                    continue;
+                }
+                durable.insert(id, tx.clone());
+                queue_guard.queue.push_back(tx);
+                restored += 1;
+            }
+        }
+        self.persist_durable().await?;
+        Ok(restored)
+    }
+
+    /// Stop the workers, persist everything still queued, wait up to `shutdown_timeout`
+    /// for in-flight transactions and flush storage, reporting what was left unfinished
+    pub async fn shutdown(&self) -> ShutdownReport {
+        let started_at = Utc::now();
+        let mut errors = Vec::new();
+        *self.running.write().await = false;
+
+        // Drain under the queue lock so no worker can pick up another transaction; the
+        // drained transactions stay in the persisted queue
+        let pending: Vec<QueuedTransaction> = self.queue.lock().await.queue.drain(..).collect();
+        let persisted_ids = pending.iter().map(|tx| tx.id().unwrap_or_default().to_string()).collect();
+        let queued_persisted = match self.persist_durable().await {
+            Ok(()) => pending.len(),
+            Err(e) => {
+                errors.push(format!("Failed to persist {} queued transactions: {}", pending.len(), e));
+                0
+            }
+        };
+
+        let in_flight_at_start = self.in_flight.lock().unwrap().len();
+        let handles: Vec<_> = self.workers.write().await.drain().map(|(_, handle)| handle).collect();
+        let aborts: Vec<_> = handles.iter().map(|handle| handle.abort_handle()).collect();
+        if tokio::time::timeout(self.config.shutdown_timeout, futures::future::join_all(handles)).await.is_err() {
+            aborts.iter().for_each(|handle| handle.abort());
+        }
+        let abandoned_ids: Vec<String> = self.in_flight.lock().unwrap().drain().map(|(_, id)| id).collect();
+
+        if let Err(e) = self.storage.blocking(|storage| storage.save_data()).await {
+            errors.push(format!("Failed to flush storage: {}", e));
+        }
+
+        ShutdownReport {
+            started_at,
+            finished_at: Utc::now(),
+            queued_persisted,
+            persisted_ids,
+            in_flight_completed: in_flight_at_start.saturating_sub(abandoned_ids.len()),
+            in_flight_abandoned: abandoned_ids.len(),
+            abandoned_ids,
+            pending_callbacks: self.webhooks.as_ref().map_or(0, |webhooks| webhooks.pending_deliveries()),
+            errors,
+        }
+    }
+
 
 
     
+}
+
+/// Queued and in-flight transactions by id, as mirrored to the persisted queue
+type DurableQueue = Arc<std::sync::Mutex<HashMap<String, QueuedTransaction>>>;
+
+/// The in-memory copy of the persisted queue. A worker that panicked while holding the
+/// lock leaves the map consistent (every change is a single insert or remove), so a
+/// poisoned lock is recovered rather than taking every later submission down with it.
+fn lock_durable(durable: &DurableQueue) -> std::sync::MutexGuard<'_, HashMap<String, QueuedTransaction>> {
+    durable.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn persist_durable(storage: &Storage, durable: &HashMap<String, QueuedTransaction>) -> Result<()> {
+    let mut pending: Vec<(&str, &QueuedTransaction)> = durable.iter().map(|(id, tx)| (id.as_str(), tx)).collect();
+    pending.sort_by_key(|(_, tx)| tx.queued_at);
+    storage.persist_pending_queue(&pending)
+}
+
+fn remember_queued(storage: &Storage, durable: &DurableQueue, id: &str, tx: QueuedTransaction) {
+    let mut durable = lock_durable(durable);
+    if let Err(e) = storage.journal_queued(id, &tx) {
+        println!("Failed to persist queued transaction {}: {}", id, e);
+    }
+    durable.insert(id.to_string(), tx);
+}
+
+fn forget_queued(storage: &Storage, durable: &DurableQueue, id: &str) {
+    let mut durable = lock_durable(durable);
+    if durable.remove(id).is_some() {
+        if let Err(e) = storage.journal_dequeued(id) {
+            println!("Failed to remove transaction {} from the persisted queue: {}", id, e);
+        }
+    }
+}
+
+fn save_bundle_records(storage: &Storage, bundle: &TransactionBundle) -> Result<()> {
+    for bundled in &bundle.transactions {
+        let mut record = Transaction::new(bundled.signed_tx.clone(), bundle.chain_id);
+        record.id = bundled.id.clone();
+        storage.save_transaction(record)?;
+    }
+    Ok(())
 }
 
 impl Clone for TransactionProcessor {
@@ -287,6 +1165,779 @@ impl Clone for TransactionProcessor {
             metrics: Arc::clone(&self.metrics),
             workers: Arc::clone(&self.workers),
             running: Arc::clone(&self.running),
+            clock: Arc::clone(&self.clock),
+            in_flight: Arc::clone(&self.in_flight),
+            durable: Arc::clone(&self.durable),
+            fee_accountant: self.fee_accountant.clone(),
+            bundle_config: self.bundle_config.clone(),
+            status_updates: self.status_updates.clone(),
+            open_streams: Arc::clone(&self.open_streams),
+            webhooks: self.webhooks.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::Config;
+    use crate::utils::mock_http::{MockResponse, MockServer};
+
+    fn processor() -> (TransactionProcessor, Arc<Storage>, String) {
+        processor_with_config(Config::default())
+    }
+
+    fn processor_with_rpc(rpc_url: &str) -> (TransactionProcessor, Arc<Storage>, String) {
+        let mut config = Config::default();
+        config.supported_chains.insert(1114, crate::infrastructure::config::ChainConfig {
+            rpc_url: rpc_url.to_string(),
+            contract_address: String::new(),
+            ..Default::default()
+        });
+        processor_with_config(config)
+    }
+
+    fn processor_with_config(config: Config) -> (TransactionProcessor, Arc<Storage>, String) {
+        let dir = std::env::temp_dir().join(format!("airchainpay-queue-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let blockchain_manager = Arc::new(BlockchainManager::new(config).unwrap());
+        (TransactionProcessor::new(blockchain_manager, Arc::clone(&storage), None), storage, dir)
+    }
+
+    fn queued(storage: &Storage, chain_id: u64, priority: TransactionPriority) -> (QueuedTransaction, String) {
+        let transaction = Transaction::new("0xdeadbeef".to_string(), chain_id);
+        let id = transaction.id.clone();
+        storage.save_transaction(transaction).unwrap();
+        let tx = QueuedTransaction {
+            transaction: serde_json::json!({ "id": id, "signed_tx": "0xdeadbeef", "chain_id": chain_id }),
+            priority,
+            queued_at: Utc::now() - chrono::Duration::seconds(30),
+            retry_count: 1,
+            max_retries: 3,
+            retry_delay: Duration::from_secs(2),
+            chain_id,
+            metadata: HashMap::new(),
+            valid_until: None,
+            not_before: None,
+        };
+        (tx, id)
+    }
+
+    #[tokio::test]
+    async fn test_queue_snapshot_reflects_enqueued_items() {
+        let (processor, storage, dir) = processor();
+        let (core_tx, core_id) = queued(&storage, 1114, TransactionPriority::High);
+        let (base_tx, base_id) = queued(&storage, 84532, TransactionPriority::Normal);
+        processor.enqueue_transaction(core_tx).await.unwrap();
+        processor.enqueue_transaction(base_tx).await.unwrap();
+
+        let snapshot = processor.queue_snapshot().await;
+        assert_eq!(snapshot.len(), 2);
+        let core = &snapshot[&1114];
+        assert_eq!(core.len(), 1);
+        assert_eq!(core[0].id.as_deref(), Some(core_id.as_str()));
+        assert_eq!(core[0].priority, TransactionPriority::High);
+        assert_eq!(core[0].retry_count, 1);
+        assert!(core[0].age_secs >= 30);
+        assert_eq!(snapshot[&84532][0].id.as_deref(), Some(base_id.as_str()));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued_removes_from_processing() {
+        let (processor, storage, dir) = processor();
+        let (first, first_id) = queued(&storage, 1114, TransactionPriority::Normal);
+        let (second, second_id) = queued(&storage, 1114, TransactionPriority::Normal);
+        processor.enqueue_transaction(first).await.unwrap();
+        processor.enqueue_transaction(second).await.unwrap();
+
+        let cancelled = processor.cancel_queued(&first_id).await.unwrap().expect("queued transaction");
+        assert_eq!(cancelled.chain_id, 1114);
+        assert_eq!(storage.get_transaction_state(&first_id), Some(TransactionState::Cancelled));
+        assert!(processor.cancel_queued(&first_id).await.unwrap().is_none());
+
+        // Workers only ever see the remaining transaction
+        let next = processor.queue.lock().await.pop().unwrap();
+        assert_eq!(next.id(), Some(second_id.as_str()));
+        assert!(processor.queue.lock().await.pop().is_none());
+        assert_eq!(storage.get_transaction_state(&second_id), Some(TransactionState::Pending));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_status_updates_follow_queue_transitions() {
+        let (processor, storage, dir) = processor();
+        let mut updates = processor.subscribe_status_updates();
+        let (tx, id) = queued(&storage, 1114, TransactionPriority::Normal);
+        processor.enqueue_transaction(tx).await.unwrap();
+        processor.cancel_queued(&id).await.unwrap().expect("queued transaction");
+
+        let queued_update = updates.recv().await.unwrap();
+        assert_eq!(queued_update.transaction_id, id);
+        assert_eq!(queued_update.state, TransactionState::Pending);
+        let cancelled = updates.recv().await.unwrap();
+        assert_eq!(cancelled.state, TransactionState::Cancelled);
+        assert_eq!(cancelled.error.as_deref(), Some("Removed from queue by operator"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// Feed one fresh Normal transaction per tick while a Low one waits; returns the
+    /// tick at which the Low transaction was dequeued, if it ever was
+    async fn ticks_until_low_dequeued(aging_interval: Duration) -> Option<usize> {
+        let dir = std::env::temp_dir().join(format!("airchainpay-queue-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let blockchain_manager = Arc::new(BlockchainManager::new(Config::default()).unwrap());
+        let config = TransactionProcessorConfig { priority_aging_interval: aging_interval, ..Default::default() };
+        let start = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let now = Arc::new(std::sync::Mutex::new(start));
+        let clock_now = Arc::clone(&now);
+        let processor = TransactionProcessor::new(blockchain_manager, Arc::clone(&storage), Some(config))
+            .with_clock(Arc::new(move || *clock_now.lock().unwrap()));
+
+        let (mut low, low_id) = queued(&storage, 1114, TransactionPriority::Low);
+        low.queued_at = start;
+        processor.enqueue_transaction(low).await.unwrap();
+
+        let mut dequeued_at = None;
+        for tick in 0..20 {
+            let current = start + chrono::Duration::seconds(30 * tick as i64);
+            *now.lock().unwrap() = current;
+            let (mut normal, _) = queued(&storage, 1114, TransactionPriority::Normal);
+            normal.queued_at = current;
+            processor.enqueue_transaction(normal).await.unwrap();
+            let next = processor.queue.lock().await.pop_at((processor.clock)()).unwrap();
+            if next.id() == Some(low_id.as_str()) {
+                dequeued_at = Some(tick);
+                break;
+            }
+        }
+        let _ = std::fs::remove_dir_all(dir);
+        dequeued_at
+    }
+
+    #[tokio::test]
+    async fn test_aged_low_priority_outranks_fresh_normal() {
+        // Without aging the Low transaction starves behind the stream of Normal ones
+        assert_eq!(ticks_until_low_dequeued(Duration::ZERO).await, None);
+
+        // One level per minute: after a minute it ties a fresh Normal and wins on queue time
+        assert_eq!(ticks_until_low_dequeued(Duration::from_secs(60)).await, Some(2));
+        // A slower rate takes proportionally longer
+        assert_eq!(ticks_until_low_dequeued(Duration::from_secs(300)).await, Some(10));
+    }
+
+    #[tokio::test]
+    async fn test_mixed_priorities_dequeue_in_priority_order() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-queue-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Storage::with_data_dir(dir.clone()).unwrap();
+        let start = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let mut queue = TransactionQueue::new(10).with_priority(Duration::from_secs(60));
+
+        // Arrival order: aged Low, Normal, Low, High, Normal
+        let mut ids = Vec::new();
+        for (priority, queued_secs_ago) in [
+            (TransactionPriority::Low, 150),
+            (TransactionPriority::Normal, 10),
+            (TransactionPriority::Low, 5),
+            (TransactionPriority::High, 4),
+            (TransactionPriority::Normal, 3),
+        ] {
+            let (mut tx, id) = queued(&storage, 1114, priority);
+            tx.queued_at = start - chrono::Duration::seconds(queued_secs_ago);
+            queue.queue.push_back(tx);
+            ids.push(id);
+        }
+
+        let depth = queue.depth_by_priority();
+        assert_eq!(depth[&TransactionPriority::Low], 2);
+        assert_eq!(depth[&TransactionPriority::Normal], 2);
+        assert_eq!(depth[&TransactionPriority::High], 1);
+        assert_eq!(depth[&TransactionPriority::Critical], 0);
+
+        // The Low queued 150s ago has been promoted twice and ties High, winning on queue time
+        let order: Vec<String> = std::iter::from_fn(|| queue.pop_at(start))
+            .map(|tx| tx.id().unwrap().to_string())
+            .collect();
+        assert_eq!(order, vec![ids[0].clone(), ids[3].clone(), ids[1].clone(), ids[4].clone(), ids[2].clone()]);
+        assert!(queue.depth_by_priority().values().all(|&n| n == 0));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_effective_priority_grows_with_time_in_queue() {
+        let queue = TransactionQueue::new(10).with_priority(Duration::from_secs(60));
+        let storage = Storage::with_data_dir(std::env::temp_dir().join(format!("airchainpay-queue-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string()).unwrap();
+        let (mut tx, _) = queued(&storage, 1114, TransactionPriority::Low);
+        let start = Utc::now();
+        tx.queued_at = start;
+
+        assert_eq!(queue.effective_priority(&tx, start), 1);
+        assert_eq!(queue.effective_priority(&tx, start + chrono::Duration::seconds(59)), 1);
+        assert_eq!(queue.effective_priority(&tx, start + chrono::Duration::seconds(150)), 3);
+        assert_eq!(TransactionQueue::new(10).with_priority(Duration::ZERO).effective_priority(&tx, start + chrono::Duration::hours(1)), 1);
+
+        // Sub-millisecond intervals age by the nanosecond instead of dividing by zero
+        let fine = TransactionQueue::new(10).with_priority(Duration::from_micros(500));
+        assert_eq!(fine.effective_priority(&tx, start + chrono::Duration::milliseconds(2)), 5);
+        assert_eq!(TransactionQueue::new(10).with_priority(Duration::from_nanos(1)).effective_priority(&tx, start + chrono::Duration::days(365 * 1000)), u64::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_report_matches_persisted_queue() {
+        let (processor, storage, dir) = processor();
+        let mut queued_ids = Vec::new();
+        for chain_id in [1114, 1114, 84532] {
+            let (tx, id) = queued(&storage, chain_id, TransactionPriority::Normal);
+            processor.enqueue_transaction(tx).await.unwrap();
+            queued_ids.push(id);
+        }
+
+        let report = processor.shutdown().await;
+        assert!(report.is_clean(), "{:?}", report.errors);
+        assert_eq!(report.queued_persisted, 3);
+        assert_eq!(report.in_flight_completed + report.in_flight_abandoned, 0);
+        assert!(processor.queue_snapshot().await.is_empty());
+
+        // The report describes exactly what a fresh process finds on disk
+        let reopened = Storage::with_data_dir(dir.clone()).unwrap();
+        let persisted: Vec<QueuedTransaction> = reopened.load_pending_queue().unwrap();
+        let mut persisted_ids: Vec<String> = persisted.iter().map(|tx| tx.id().unwrap().to_string()).collect();
+        let mut reported_ids = report.persisted_ids.clone();
+        persisted_ids.sort();
+        reported_ids.sort();
+        queued_ids.sort();
+        assert_eq!(persisted_ids, reported_ids);
+        assert_eq!(reported_ids, queued_ids);
+
+        let restarted = TransactionProcessor::new(Arc::clone(&processor.blockchain_manager), Arc::new(reopened), None);
+        assert_eq!(restarted.restore_pending_queue().await.unwrap(), 3);
+        assert_eq!(restarted.queue_snapshot().await.values().map(Vec::len).sum::<usize>(), 3);
+        // Restored transactions stay persisted until they finish, and are not queued twice
+        assert_eq!(restarted.storage.load_pending_queue::<QueuedTransaction>().unwrap().len(), 3);
+        assert_eq!(restarted.restore_pending_queue().await.unwrap(), 0);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_queue_persistence_survives_a_poisoned_lock() {
+        let (processor, storage, dir) = processor();
+        let (first, first_id) = queued(&storage, 1114, TransactionPriority::Normal);
+        processor.enqueue_transaction(first).await.unwrap();
+
+        let durable = Arc::clone(&processor.durable);
+        let _ = std::thread::spawn(move || {
+            let _guard = durable.lock().unwrap();
+            panic!("worker panicked while holding the queue lock");
+        }).join();
+        assert!(processor.durable.is_poisoned());
+
+        let (second, second_id) = queued(&storage, 1114, TransactionPriority::Normal);
+        processor.enqueue_transaction(second).await.unwrap();
+        let persisted: Vec<QueuedTransaction> = storage.load_pending_queue().unwrap();
+        let ids: Vec<&str> = persisted.iter().filter_map(QueuedTransaction::id).collect();
+        assert_eq!(ids, vec![first_id.as_str(), second_id.as_str()]);
+        assert_eq!(processor.shutdown().await.queued_persisted, 2);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_report_counts_pending_webhook_deliveries() {
+        let (processor, storage, dir) = processor();
+        // The endpoint never answers, so the delivery hangs
+        let endpoint = MockServer::start(|_| MockResponse::status(200).delayed(Duration::from_secs(3600))).await;
+        storage.save_webhook(crate::infrastructure::storage::file_storage::Webhook {
+            id: "hook".to_string(),
+            url: endpoint.url("/hook"),
+            created_at: Utc::now(),
+            verified_at: Utc::now(),
+        }).unwrap();
+        let config = crate::infrastructure::config::WebhookConfig {
+            allowed_schemes: vec!["http".to_string()],
+            allow_private_networks: true,
+            ..Default::default()
+        };
+        let webhooks = Arc::new(WebhookRegistry::new(config, Arc::clone(&storage)));
+        let processor = processor.with_webhooks(Arc::clone(&webhooks));
+
+        assert_eq!(processor.shutdown().await.pending_callbacks, 0);
+        webhooks.notify(&TransactionStatusUpdate {
+            transaction_id: "tx-1".to_string(),
+            state: TransactionState::Completed,
+            tx_hash: None,
+            error: None,
+            timestamp: Utc::now(),
+        });
+        assert_eq!(processor.shutdown().await.pending_callbacks, 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_queue_survives_restart_without_shutdown() {
+        let (processor, storage, dir) = processor();
+        let (tx, id) = queued(&storage, 1114, TransactionPriority::High);
+        processor.enqueue_transaction(tx).await.unwrap();
+        let (exhausted, exhausted_id) = queued(&storage, 1114, TransactionPriority::Normal);
+        let exhausted = QueuedTransaction { retry_count: 3, max_retries: 3, ..exhausted };
+        processor.enqueue_transaction(exhausted).await.unwrap();
+        storage.save_data().unwrap();
+
+        // Crash: a new processor over the same data directory, no shutdown in between
+        let reopened = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let restarted = TransactionProcessor::new(Arc::clone(&processor.blockchain_manager), Arc::clone(&reopened), None);
+        assert_eq!(restarted.restore_pending_queue().await.unwrap(), 2);
+
+        let next = restarted.queue.lock().await.pop().unwrap();
+        assert_eq!(next.id(), Some(id.as_str()));
+        assert_eq!(next.retry_count, 1);
+        assert_eq!(next.max_retries, 3);
+
+        // Its retries were used up before the restart: failed without another broadcast
+        let exhausted = restarted.queue.lock().await.pop().unwrap();
+        assert_eq!(exhausted.id(), Some(exhausted_id.as_str()));
+        restarted.process_transaction(exhausted, "worker-test").await;
+        assert_eq!(reopened.get_transaction_state(&exhausted_id), Some(TransactionState::Failed));
+        let persisted: Vec<QueuedTransaction> = reopened.load_pending_queue().unwrap();
+        assert_eq!(persisted.len(), 1);
+        assert_eq!(persisted[0].id(), Some(id.as_str()));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_reports_abandoned_in_flight_transactions() {
+        let dir = std::env::temp_dir().join(format!("airchainpay-queue-{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        let storage = Arc::new(Storage::with_data_dir(dir.clone()).unwrap());
+        let blockchain_manager = Arc::new(BlockchainManager::new(Config::default()).unwrap());
+        let config = TransactionProcessorConfig { shutdown_timeout: Duration::from_millis(100), ..Default::default() };
+        let processor = TransactionProcessor::new(blockchain_manager, Arc::clone(&storage), Some(config));
+
+        // No provider for the chain: the worker fails and backs off between retries
+        let (tx, id) = queued(&storage, 1114, TransactionPriority::Normal);
+        processor.enqueue_transaction(tx).await.unwrap();
+        processor.start().await.unwrap();
+        for _ in 0..50 {
+            if !processor.in_flight.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let report = processor.shutdown().await;
+        assert_eq!(report.queued_persisted, 0);
+        assert_eq!(report.in_flight_abandoned, 1);
+        assert_eq!(report.abandoned_ids, vec![id]);
+        assert!(!report.is_clean());
+        assert!(processor.workers.read().await.is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// Minimal JSON-RPC node recording every method it is called with
+    async fn spawn_rpc() -> MockServer {
+        MockServer::json_rpc(|method, _| {
+            if method == "eth_sendRawTransaction" {
+                serde_json::json!(format!("0x{}", "ab".repeat(32)))
+            } else {
+                serde_json::Value::Null
+            }
+        }).await
+    }
+
+    fn fixed_clock(now: DateTime<Utc>) -> Clock {
+        Arc::new(move || now)
+    }
+
+    #[tokio::test]
+    async fn test_past_deadline_is_expired_without_broadcast() {
+        let rpc = spawn_rpc().await;
+        let (processor, storage, dir) = processor_with_rpc(&rpc.url);
+        let now = Utc::now();
+        let processor = processor.with_clock(fixed_clock(now));
+
+        let (mut tx, id) = queued(&storage, 1114, TransactionPriority::Normal);
+        tx.valid_until = Some(now - chrono::Duration::seconds(1));
+        assert!(processor.expire_if_past_deadline(&tx).await);
+        assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Expired));
+
+        processor.process_transaction(tx, "worker-test").await;
+        assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Expired));
+        assert!(rpc.rpc_methods().is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_future_deadline_broadcasts_normally() {
+        let rpc = spawn_rpc().await;
+        let (processor, storage, dir) = processor_with_rpc(&rpc.url);
+        let now = Utc::now();
+        let processor = processor.with_clock(fixed_clock(now));
+
+        let (mut tx, id) = queued(&storage, 1114, TransactionPriority::Normal);
+        tx.metadata.insert("signedTx".to_string(), serde_json::json!("0xdeadbeef"));
+        tx.valid_until = Some(now + chrono::Duration::seconds(60));
+        assert!(!processor.expire_if_past_deadline(&tx).await);
+
+        let worker = tokio::spawn(async move { processor.process_transaction(tx, "worker-test").await });
+        let broadcast = tokio::time::timeout(Duration::from_secs(5), async {
+            while !rpc.rpc_methods().iter().any(|m| m == "eth_sendRawTransaction") {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        }).await;
+        worker.abort();
+
+        assert!(broadcast.is_ok(), "transaction was not broadcast");
+        assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Processing));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_restored_transaction_mined_before_restart_is_not_broadcast_again() {
+        let hash = ethers::types::H256::from(ethers::utils::keccak256([0xde, 0xad, 0xbe, 0xef]));
+        let rpc = MockServer::json_rpc(move |method, _| match method {
+            "eth_getTransactionReceipt" => mined_receipt(hash, false),
+            "eth_blockNumber" => serde_json::json!("0x10"),
+            _ => serde_json::Value::Null,
+        }).await;
+        let (processor, storage, dir) = processor_with_rpc(&rpc.url);
+
+        let (mut tx, id) = queued(&storage, 1114, TransactionPriority::Normal);
+        tx.metadata.insert("signedTx".to_string(), serde_json::json!("0xdeadbeef"));
+        storage.record_broadcast(&id, 1, Utc::now()).unwrap();
+
+        processor.process_transaction(tx, "worker-test").await;
+        let stored = storage.get_transaction(&id).unwrap();
+        assert_eq!(stored.status, TransactionState::Completed);
+        assert_eq!(stored.tx_hash, Some(format!("{:?}", hash)));
+        assert!(!rpc.rpc_methods().iter().any(|m| m == "eth_sendRawTransaction"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_already_known_broadcast_counts_as_sent() {
+        let rpc = MockServer::start(|request| match request.json()["method"].as_str() {
+            Some("eth_sendRawTransaction") => {
+                MockResponse::rpc_error(request, serde_json::json!({ "code": -32000, "message": "already known" }))
+            }
+            _ => MockResponse::rpc_result(request, serde_json::Value::Null),
+        }).await;
+        let (processor, storage, dir) = processor_with_rpc(&rpc.url);
+
+        let (mut tx, _) = queued(&storage, 1114, TransactionPriority::Normal);
+        tx.metadata.insert("signedTx".to_string(), serde_json::json!("0xdeadbeef"));
+        let (tx_hash, _) = processor.blockchain_manager.broadcast_transaction(&tx).await.unwrap();
+        assert_eq!(tx_hash, ethers::types::H256::from(ethers::utils::keccak256([0xde, 0xad, 0xbe, 0xef])));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// Node that mines a sent transaction on the second receipt poll. Transactions
+    /// in `reverting` are mined with status 0. Returns "sent:<hash>" / "mined:<hash>" events.
+    async fn spawn_mining_rpc(reverting: Vec<ethers::types::H256>) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        let polls = std::sync::Mutex::new(HashMap::<ethers::types::H256, u32>::new());
+        let rpc = MockServer::json_rpc(move |method, params| match method {
+            "eth_sendRawTransaction" => {
+                let raw = hex::decode(params[0].as_str().unwrap().trim_start_matches("0x")).unwrap();
+                let hash = ethers::types::H256::from(ethers::utils::keccak256(raw));
+                polls.lock().unwrap().insert(hash, 0);
+                recorded.lock().unwrap().push(format!("sent:{:?}", hash));
+                serde_json::json!(hash)
+            }
+            "eth_getTransactionReceipt" => {
+                let hash: ethers::types::H256 = serde_json::from_value(params[0].clone()).unwrap();
+                let mut polls = polls.lock().unwrap();
+                let Some(count) = polls.get_mut(&hash) else { return serde_json::Value::Null };
+                *count += 1;
+                if *count < 2 {
+                    return serde_json::Value::Null;
+                }
+                if *count == 2 {
+                    recorded.lock().unwrap().push(format!("mined:{:?}", hash));
+                }
+                mined_receipt(hash, reverting.contains(&hash))
+            }
+            _ => serde_json::Value::Null,
+        }).await;
+        (rpc.url, events)
+    }
+
+    /// Receipt of `hash` mined in block 0x10, with status 0 when `reverted`
+    fn mined_receipt(hash: ethers::types::H256, reverted: bool) -> serde_json::Value {
+        serde_json::json!({
+            "transactionHash": hash,
+            "transactionIndex": "0x0",
+            "blockHash": format!("0x{}", "cd".repeat(32)),
+            "blockNumber": "0x10",
+            "from": "0x2222222222222222222222222222222222222222",
+            "to": "0x2222222222222222222222222222222222222222",
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "contractAddress": null,
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "status": if reverted { "0x0" } else { "0x1" },
+            "type": "0x2",
+            "effectiveGasPrice": "0x1",
+        })
+    }
+
+    fn bundle_processor(rpc_url: &str, revert_policy: BundleRevertPolicy) -> (TransactionProcessor, Arc<Storage>, String) {
+        let (processor, storage, dir) = processor_with_rpc(rpc_url);
+        let processor = processor.with_bundle_config(BundleConfig {
+            revert_policy,
+            receipt_poll_interval_ms: 10,
+            receipt_timeout_secs: 5,
+            ..Default::default()
+        });
+        (processor, storage, dir)
+    }
+
+    fn bundle_of(count: u64) -> TransactionBundle {
+        let signed: Vec<String> = (0..count)
+            .map(|nonce| crate::app::bundle::tests::signed_tx(crate::app::bundle::tests::SENDER_KEY, 1114, nonce))
+            .collect();
+        TransactionBundle::from_signed(1114, &signed, 16).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bundle_broadcasts_each_transaction_after_previous_is_mined() {
+        let (url, events) = spawn_mining_rpc(Vec::new()).await;
+        let (processor, storage, dir) = bundle_processor(&url, BundleRevertPolicy::Halt);
+        let bundle = bundle_of(3);
+        save_bundle_records(&storage, &bundle).unwrap();
+
+        let outcome = processor.process_bundle(&bundle).await;
+        assert_eq!(outcome.mined, bundle.transaction_ids());
+
+        let expected: Vec<String> = bundle.transactions.iter()
+            .flat_map(|tx| [format!("sent:{:?}", tx.tx_hash), format!("mined:{:?}", tx.tx_hash)])
+            .collect();
+        assert_eq!(*events.lock().unwrap(), expected);
+        for id in bundle.transaction_ids() {
+            assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Completed));
         }
+
+        let _ = std::fs::remove_dir_all(dir);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_bundle_halts_after_mid_bundle_revert() {
+        let bundle = bundle_of(3);
+        let (url, events) = spawn_mining_rpc(vec![bundle.transactions[1].tx_hash]).await;
+        let (processor, storage, dir) = bundle_processor(&url, BundleRevertPolicy::Halt);
+        save_bundle_records(&storage, &bundle).unwrap();
+
+        let outcome = processor.process_bundle(&bundle).await;
+        let ids = bundle.transaction_ids();
+        assert_eq!(outcome.mined, vec![ids[0].clone()]);
+        assert_eq!(outcome.reverted, vec![ids[1].clone()]);
+        assert_eq!(outcome.cancelled, vec![ids[2].clone()]);
+
+        let last_hash = format!("{:?}", bundle.transactions[2].tx_hash);
+        assert!(!events.lock().unwrap().iter().any(|event| event.ends_with(&last_hash)));
+        assert_eq!(storage.get_transaction_state(&ids[0]), Some(TransactionState::Completed));
+        assert_eq!(storage.get_transaction_state(&ids[1]), Some(TransactionState::Failed));
+        assert_eq!(storage.get_transaction_state(&ids[2]), Some(TransactionState::Cancelled));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_bundle_continue_policy_sends_past_revert() {
+        let bundle = bundle_of(3);
+        let (url, events) = spawn_mining_rpc(vec![bundle.transactions[1].tx_hash]).await;
+        let (processor, storage, dir) = bundle_processor(&url, BundleRevertPolicy::Continue);
+        save_bundle_records(&storage, &bundle).unwrap();
+
+        let outcome = processor.process_bundle(&bundle).await;
+        let ids = bundle.transaction_ids();
+        assert_eq!(outcome.mined, vec![ids[0].clone(), ids[2].clone()]);
+        assert_eq!(outcome.reverted, vec![ids[1].clone()]);
+        assert!(outcome.cancelled.is_empty());
+        assert_eq!(events.lock().unwrap().len(), 6);
+        assert_eq!(storage.get_transaction_state(&ids[2]), Some(TransactionState::Completed));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_bundle_halts_when_broadcast_fails() {
+        // No provider for the chain: the first broadcast fails
+        let (processor, storage, dir) = processor();
+        let bundle = bundle_of(2);
+        save_bundle_records(&storage, &bundle).unwrap();
+
+        let outcome = processor.process_bundle(&bundle).await;
+        let ids = bundle.transaction_ids();
+        assert_eq!(outcome.failed, vec![ids[0].clone()]);
+        assert_eq!(outcome.cancelled, vec![ids[1].clone()]);
+        assert_eq!(storage.get_transaction_state(&ids[1]), Some(TransactionState::Cancelled));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_bundle_receipt_polling_survives_rpc_errors() {
+        // Every receipt request fails once with an undecodable answer before the receipt is there
+        let polls = std::sync::Mutex::new(HashMap::<ethers::types::H256, u32>::new());
+        let rpc = MockServer::json_rpc(move |method, params| match method {
+            "eth_sendRawTransaction" => {
+                let raw = hex::decode(params[0].as_str().unwrap().trim_start_matches("0x")).unwrap();
+                serde_json::json!(ethers::types::H256::from(ethers::utils::keccak256(raw)))
+            }
+            "eth_getTransactionReceipt" => {
+                let hash: ethers::types::H256 = serde_json::from_value(params[0].clone()).unwrap();
+                let mut polls = polls.lock().unwrap();
+                let count = polls.entry(hash).or_default();
+                *count += 1;
+                if *count == 1 { serde_json::json!("unavailable") } else { mined_receipt(hash, false) }
+            }
+            _ => serde_json::Value::Null,
+        }).await;
+        let (processor, storage, dir) = bundle_processor(&rpc.url, BundleRevertPolicy::Halt);
+        let bundle = bundle_of(2);
+        save_bundle_records(&storage, &bundle).unwrap();
+
+        let outcome = processor.process_bundle(&bundle).await;
+        assert_eq!(outcome.mined, bundle.transaction_ids());
+        for id in bundle.transaction_ids() {
+            assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Completed));
+        }
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_submitted_bundle_is_queued_and_processed_by_a_worker() {
+        let (url, events) = spawn_mining_rpc(Vec::new()).await;
+        let (processor, storage, dir) = bundle_processor(&url, BundleRevertPolicy::Halt);
+        let bundle = bundle_of(2);
+        processor.submit_bundle(bundle.clone()).await.unwrap();
+
+        // One queue entry, persisted until a worker has finished the bundle
+        let queued: Vec<QueuedTransactionInfo> = processor.queue_snapshot().await.into_values().flatten().collect();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].id.as_deref(), Some(bundle.id.as_str()));
+        let persisted: Vec<QueuedTransaction> = storage.load_pending_queue().unwrap();
+        assert_eq!(persisted[0].bundle().unwrap().transaction_ids(), bundle.transaction_ids());
+        assert!(events.lock().unwrap().is_empty());
+
+        processor.start().await.unwrap();
+        let finished = tokio::time::timeout(Duration::from_secs(10), async {
+            while storage.get_transaction_state(&bundle.transactions[1].id) != Some(TransactionState::Completed) {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        }).await;
+        assert!(finished.is_ok(), "bundle was not processed");
+        let report = processor.shutdown().await;
+        assert_eq!(report.in_flight_abandoned, 0);
+        assert!(storage.load_pending_queue::<QueuedTransaction>().unwrap().is_empty());
+        assert_eq!(events.lock().unwrap().len(), 4);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_mined_transaction_does_not_wait_for_the_mempool_check() {
+        let hash = ethers::types::H256::repeat_byte(0xab);
+        let rpc = MockServer::json_rpc(move |method, _| match method {
+            "eth_sendRawTransaction" => serde_json::json!(hash),
+            "eth_getTransactionByHash" => serde_json::json!({
+                "hash": hash,
+                "nonce": "0x0",
+                "blockHash": format!("0x{}", "cd".repeat(32)),
+                "blockNumber": "0x10",
+                "transactionIndex": "0x0",
+                "from": "0x2222222222222222222222222222222222222222",
+                "to": "0x2222222222222222222222222222222222222222",
+                "value": "0x0",
+                "gasPrice": "0x1",
+                "gas": "0x5208",
+                "input": "0x",
+                "v": "0x1b",
+                "r": "0x1",
+                "s": "0x1",
+            }),
+            "eth_getTransactionReceipt" => mined_receipt(hash, false),
+            _ => serde_json::Value::Null,
+        }).await;
+        let (processor, storage, dir) = processor_with_rpc(&rpc.url);
+        let config = TransactionProcessorConfig { mempool_check_delay: Duration::from_secs(120), ..Default::default() };
+        let processor = TransactionProcessor::new(Arc::clone(&processor.blockchain_manager), Arc::clone(&storage), Some(config));
+
+        let (mut tx, id) = queued(&storage, 1114, TransactionPriority::Normal);
+        tx.metadata.insert("signedTx".to_string(), serde_json::json!("0xdeadbeef"));
+        let finished = tokio::time::timeout(Duration::from_secs(30), processor.process_transaction(tx, "worker-test")).await;
+
+        assert!(finished.is_ok(), "worker waited for the mempool check");
+        assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Completed));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_rebroadcast_transaction_is_not_marked_dropped() {
+        let (processor, storage, dir) = processor();
+        let (_, id) = queued(&storage, 1114, TransactionPriority::Normal);
+        storage.update_transaction_status(&id, TransactionState::Processing, None).unwrap();
+        let mut updates = processor.subscribe_status_updates();
+        let hash = ethers::types::H256::repeat_byte(0xab);
+
+        let status = processor.apply_mempool_status(Ok(MempoolStatus::Rebroadcast), &id, hash, "worker-test").await;
+        assert_eq!(status, Some(MempoolStatus::Rebroadcast));
+        assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Processing));
+        assert!(updates.try_recv().is_err());
+
+        let status = processor.apply_mempool_status(Ok(MempoolStatus::Dropped), &id, hash, "worker-test").await;
+        assert_eq!(status, Some(MempoolStatus::Dropped));
+        assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Dropped));
+        assert_eq!(updates.try_recv().unwrap().state, TransactionState::Dropped);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_held_nonce_is_put_back_in_the_queue() {
+        // The chain expects nonce 1 from the sender; the transaction carries nonce 2
+        let rpc = MockServer::json_rpc(|method, _| match method {
+            "eth_getTransactionCount" => serde_json::json!("0x1"),
+            _ => serde_json::Value::Null,
+        }).await;
+        let (processor, storage, dir) = processor_with_rpc(&rpc.url);
+        let (mut tx, id) = queued(&storage, 1114, TransactionPriority::Normal);
+        let signed = crate::app::bundle::tests::signed_tx(crate::app::bundle::tests::SENDER_KEY, 1114, 2);
+        tx.metadata.insert("signedTx".to_string(), serde_json::json!(signed));
+
+        let finished = tokio::time::timeout(Duration::from_secs(5), processor.process_transaction(tx, "worker-test")).await;
+        assert!(finished.is_ok(), "worker waited for the held nonce");
+        assert!(!rpc.rpc_methods().iter().any(|method| method == "eth_sendRawTransaction"));
+        assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Retrying));
+
+        // Back in the queue without using up an attempt, and not picked up again right away
+        let mut queue = processor.queue.lock().await;
+        let requeued = &queue.queue[0];
+        assert_eq!(requeued.id(), Some(id.as_str()));
+        assert_eq!(requeued.retry_count, 1);
+        let not_before = requeued.not_before.unwrap();
+        assert!(queue.pop_at(not_before - chrono::Duration::milliseconds(1)).is_none());
+        let again = queue.pop_at(not_before).unwrap();
+        drop(queue);
+        assert!(lock_durable(&processor.durable).contains_key(&id));
+
+        // Held again on the next pick-up: still retrying, with no further status updates
+        let mut updates = processor.subscribe_status_updates();
+        processor.process_transaction(again, "worker-test").await;
+        assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Retrying));
+        assert!(updates.try_recv().is_err());
+        assert_eq!(processor.queue.lock().await.queue.len(), 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
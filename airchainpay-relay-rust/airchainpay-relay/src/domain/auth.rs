@@ -23,11 +23,119 @@ pub struct Claims {
     pub exp: i64,    // Expiration time
     pub iat: i64,    // Issued at
     pub typ: String, // Token type
+    /// For refresh tokens, the type of the access tokens they can be exchanged for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// For refresh tokens, a fingerprint of the API key they were issued for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kfp: Option<String>,
 }
 
+/// Token type of refresh tokens, which are only accepted by `/auth/refresh`
+pub const REFRESH_TOKEN_TYPE: &str = "refresh";
+
+/// Default lifetime of access tokens
+pub const DEFAULT_ACCESS_TOKEN_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Default lifetime of refresh tokens
+pub const DEFAULT_REFRESH_TOKEN_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Why a token was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenError {
+    Expired,
+    /// Valid, but not the kind of token the caller asked for (e.g. a refresh token used for access)
+    WrongType { expected: String, found: String },
+    Invalid(String),
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenError::Expired => write!(f, "Token has expired"),
+            TokenError::WrongType { expected, found } => write!(f, "Expected a {expected} token, got a {found} token"),
+            TokenError::Invalid(msg) => write!(f, "Invalid token: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+/// Access token and the refresh token that renews it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub expires_at: String,
+    pub refresh_token: String,
+    pub refresh_expires_at: String,
+}
+
+/// A freshly minted access token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessToken {
+    pub access_token: String,
+    pub expires_at: String,
+}
+
+/// Token type granting access to operator-only endpoints such as queue management
+pub const ADMIN_TOKEN_TYPE: &str = "admin";
+
+/// Subject of tokens issued for `api_key`. Clients holding different keys get separate
+/// idempotency scopes and rate-limit budgets; the key itself never appears in a token.
+pub fn api_key_subject(api_key: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("key:{}", hex::encode(Sha256::digest(api_key.as_bytes())))
+}
+
+/// Default tolerance for client/server clock differences when checking expiry
+pub const DEFAULT_CLOCK_SKEW_SECS: u64 = 300;
+
+/// Most clock skew allowed past an on-chain deadline. The contract compares the deadline
+/// with the block timestamp, so a meta-transaction accepted much later would only revert.
+pub const MAX_DEADLINE_SKEW_SECS: u64 = 5;
+
+/// Clock skew tolerance from `CLOCK_SKEW_TOLERANCE_SECS`, if set
+pub fn clock_skew_from_env() -> Option<u64> {
+    std::env::var("CLOCK_SKEW_TOLERANCE_SECS").ok().and_then(|v| v.parse().ok())
+}
+
+/// Check a signed message's validity window (`notBefore` / `expirationTime`, unix seconds)
+/// against `now`, allowing `skew_secs` of clock difference on either side
+pub fn check_validity_window(now: i64, not_before: Option<i64>, expires_at: Option<i64>, skew_secs: u64) -> Result<(), String> {
+    let skew = i64::try_from(skew_secs).unwrap_or(i64::MAX);
+    if let Some(not_before) = not_before {
+        if now.saturating_add(skew) < not_before {
+            return Err(format!("Message is not valid until {} (now {}, skew tolerance {}s)", not_before, now, skew_secs));
+        }
+    }
+    if let Some(expires_at) = expires_at {
+        if now.saturating_sub(skew) > expires_at {
+            return Err(format!("Message expired at {} (now {}, skew tolerance {}s)", expires_at, now, skew_secs));
+        }
+    }
+    Ok(())
+}
+
+/// Issues and validates access and refresh tokens signed with one secret
 #[derive(Debug, Clone)]
 pub struct AuthManager {
+    secret: String,
+    access_token_ttl: Duration,
+    refresh_token_ttl: Duration,
+    /// Tolerated clock difference when checking `exp`
+    leeway_secs: u64,
+    /// Key exchanged for relay tokens; without one only admin tokens are issued
+    api_key: Option<String>,
+    /// Key exchanged for admin tokens
+    admin_api_key: Option<String>,
+}
 
+/// Why `/auth/token` refused an API key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiKeyError {
+    /// `API_KEY` is not configured, so relay tokens are never issued
+    NotConfigured,
+    Invalid,
 }
 
 impl Default for AuthManager {
@@ -37,10 +145,134 @@ impl Default for AuthManager {
 }
 
 impl AuthManager {
+    /// Secret from `JWT_SECRET`, lifetimes from `JWT_ACCESS_TOKEN_TTL_SECS` and
+    /// `JWT_REFRESH_TOKEN_TTL_SECS`, and the keys `/auth/token` accepts from `API_KEY`
+    /// and `ADMIN_API_KEY`
     pub fn new() -> Self {
+        let ttl = |name: &str, default: u64| {
+            std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+        };
+        let key = |name: &str| std::env::var(name).ok().filter(|key| !key.is_empty());
+        Self::with_secret(
+            Self::get_or_generate_jwt_secret(),
+            ttl("JWT_ACCESS_TOKEN_TTL_SECS", DEFAULT_ACCESS_TOKEN_TTL_SECS),
+            ttl("JWT_REFRESH_TOKEN_TTL_SECS", DEFAULT_REFRESH_TOKEN_TTL_SECS),
+        ).with_api_keys(key("API_KEY"), key("ADMIN_API_KEY"))
+    }
+
+    pub fn with_secret(secret: String, access_token_ttl_secs: u64, refresh_token_ttl_secs: u64) -> Self {
         Self {
+            secret,
+            access_token_ttl: Duration::seconds(i64::try_from(access_token_ttl_secs).unwrap_or(i64::MAX / 1000)),
+            refresh_token_ttl: Duration::seconds(i64::try_from(refresh_token_ttl_secs).unwrap_or(i64::MAX / 1000)),
+            leeway_secs: clock_skew_from_env().unwrap_or(DEFAULT_CLOCK_SKEW_SECS),
+            api_key: None,
+            admin_api_key: None,
+        }
+    }
+
+    pub fn with_api_keys(mut self, api_key: Option<String>, admin_api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self.admin_api_key = admin_api_key;
+        self
+    }
 
+    /// Token type an API key presented to `/auth/token` is exchanged for
+    pub fn token_type_for_api_key(&self, key: &str) -> Result<&'static str, ApiKeyError> {
+        if self.admin_api_key.as_deref() == Some(key) {
+            return Ok(ADMIN_TOKEN_TYPE);
         }
+        match &self.api_key {
+            None => Err(ApiKeyError::NotConfigured),
+            Some(api_key) if api_key == key => Ok("relay"),
+            Some(_) => Err(ApiKeyError::Invalid),
+        }
+    }
+
+    fn sign(&self, claims: &Claims) -> Result<String, TokenError> {
+        encode(&Header::default(), claims, &EncodingKey::from_secret(self.secret.as_ref()))
+            .map_err(|e| TokenError::Invalid(format!("Failed to sign token: {e}")))
+    }
+
+    fn mint_access_token(&self, subject: &str, token_type: &str) -> Result<AccessToken, TokenError> {
+        let now = Utc::now();
+        let expires_at = now + self.access_token_ttl;
+        let access_token = self.sign(&Claims {
+            sub: subject.to_string(),
+            exp: expires_at.timestamp(),
+            iat: now.timestamp(),
+            typ: token_type.to_string(),
+            scope: None,
+            kfp: None,
+        })?;
+        Ok(AccessToken { access_token, expires_at: expires_at.to_rfc3339() })
+    }
+
+    /// Fingerprint of the API key currently exchanged for `token_type`, if one is configured
+    fn key_fingerprint(&self, token_type: &str) -> Option<String> {
+        use sha2::{Digest, Sha256};
+        let key = if token_type == ADMIN_TOKEN_TYPE { self.admin_api_key.as_ref() } else { self.api_key.as_ref() };
+        key.map(|key| hex::encode(Sha256::digest(format!("{}:{}", self.secret, key).as_bytes())))
+    }
+
+    /// An access token of `token_type` and a refresh token that renews it while the API key
+    /// for `token_type` stays the same
+    pub fn issue_tokens(&self, subject: &str, token_type: &str) -> Result<TokenPair, TokenError> {
+        let access = self.mint_access_token(subject, token_type)?;
+        let now = Utc::now();
+        let refresh_expires_at = now + self.refresh_token_ttl;
+        let refresh_token = self.sign(&Claims {
+            sub: subject.to_string(),
+            exp: refresh_expires_at.timestamp(),
+            iat: now.timestamp(),
+            typ: REFRESH_TOKEN_TYPE.to_string(),
+            scope: Some(token_type.to_string()),
+            kfp: self.key_fingerprint(token_type),
+        })?;
+        Ok(TokenPair {
+            access_token: access.access_token,
+            expires_at: access.expires_at,
+            refresh_token,
+            refresh_expires_at: refresh_expires_at.to_rfc3339(),
+        })
+    }
+
+    /// Check an access token's signature and expiry, with expired tokens reported as
+    /// `TokenError::Expired`. Refresh tokens are rejected: they are only good for `refresh`.
+    pub fn validate_token(&self, token: &str) -> Result<Claims, TokenError> {
+        let claims = self.decode_claims(token)?;
+        if claims.typ == REFRESH_TOKEN_TYPE {
+            return Err(TokenError::WrongType { expected: "access".to_string(), found: claims.typ });
+        }
+        Ok(claims)
+    }
+
+    fn decode_claims(&self, token: &str) -> Result<Claims, TokenError> {
+        let mut validation = Validation::default();
+        validation.leeway = self.leeway_secs;
+        validation.set_required_spec_claims(&["exp", "iat", "sub"]);
+        decode::<Claims>(token, &DecodingKey::from_secret(self.secret.as_ref()), &validation)
+            .map(|data| data.claims)
+            .map_err(|e| match e.kind() {
+                jsonwebtoken::errors::ErrorKind::ExpiredSignature => TokenError::Expired,
+                _ => TokenError::Invalid(e.to_string()),
+            })
+    }
+
+    /// Exchange a valid refresh token for a new access token of the type it was issued for,
+    /// as long as the API key it was issued for is still configured
+    pub fn refresh(&self, refresh_token: &str) -> Result<AccessToken, TokenError> {
+        let claims = self.decode_claims(refresh_token)?;
+        if claims.typ != REFRESH_TOKEN_TYPE {
+            return Err(TokenError::WrongType { expected: REFRESH_TOKEN_TYPE.to_string(), found: claims.typ });
+        }
+        let token_type = claims.scope
+            .ok_or_else(|| TokenError::Invalid("Refresh token has no scope".to_string()))?;
+        // Rotating the API key ends every session that was started with the old one
+        if claims.kfp != self.key_fingerprint(&token_type) {
+            return Err(TokenError::Invalid("Refresh token was issued for an API key that is no longer valid".to_string()));
+        }
+        self.mint_access_token(&claims.sub, &token_type)
     }
 
     /// Generate a secure JWT secret
@@ -70,6 +302,8 @@ impl AuthManager {
             exp: exp.timestamp(),
             iat: now.timestamp(),
             typ: token_type.to_string(),
+            scope: None,
+            kfp: None,
         };
 
         match encode(
@@ -85,15 +319,20 @@ impl AuthManager {
         }
     }
 
-    /// Verify a JWT token
+    /// Verify a JWT access token; refresh tokens are rejected
     pub fn verify_jwt_token(token: &str) -> Result<Claims, Box<dyn std::error::Error>> {
         let secret = Self::get_or_generate_jwt_secret();
+        let mut validation = Validation::default();
+        validation.leeway = clock_skew_from_env().unwrap_or(DEFAULT_CLOCK_SKEW_SECS);
         
         let token_data = decode::<Claims>(
             token,
             &DecodingKey::from_secret(secret.as_ref()),
-            &Validation::default(),
+            &validation,
         )?;
+        if token_data.claims.typ == REFRESH_TOKEN_TYPE {
+            return Err(Box::new(TokenError::WrongType { expected: "access".to_string(), found: token_data.claims.typ }));
+        }
 
         Ok(token_data.claims)
     }
@@ -156,6 +395,25 @@ pub fn generate_production_secrets() -> HashMap<String, String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_expiry_within_skew_window_is_accepted() {
+        let now = 1_700_000_000;
+        // Expired 2 minutes ago by our clock, within the 5 minute tolerance
+        assert!(check_validity_window(now, None, Some(now - 120), DEFAULT_CLOCK_SKEW_SECS).is_ok());
+        // Not valid for another minute by our clock
+        assert!(check_validity_window(now, Some(now + 60), Some(now + 3600), DEFAULT_CLOCK_SKEW_SECS).is_ok());
+    }
+
+    #[test]
+    fn test_expiry_past_skew_window_is_rejected() {
+        let now = 1_700_000_000;
+        assert!(check_validity_window(now, None, Some(now - 3600), DEFAULT_CLOCK_SKEW_SECS).is_err());
+        assert!(check_validity_window(now, Some(now + 3600), None, DEFAULT_CLOCK_SKEW_SECS).is_err());
+        // Zero tolerance is strict
+        assert!(check_validity_window(now, None, Some(now - 1), 0).is_err());
+        assert!(check_validity_window(now, None, Some(now), 0).is_ok());
+    }
+
     #[test]
     fn test_jwt_secret_generation() {
         let secret1 = AuthManager::generate_jwt_secret();
@@ -181,6 +439,92 @@ mod tests {
         std::env::remove_var("JWT_SECRET");
     }
 
+    fn manager(access_ttl_secs: u64) -> AuthManager {
+        AuthManager::with_secret("test_secret_for_token_refresh_1234567890abcdef".to_string(), access_ttl_secs, 3600)
+    }
+
+    #[test]
+    fn test_fresh_token_validates() {
+        let manager = manager(600);
+        let tokens = manager.issue_tokens("partner", "relay").unwrap();
+
+        let claims = manager.validate_token(&tokens.access_token).unwrap();
+        assert_eq!(claims.sub, "partner");
+        assert_eq!(claims.typ, "relay");
+        assert!(claims.iat <= Utc::now().timestamp());
+        assert_eq!(claims.exp - claims.iat, 600);
+
+        // Tokens signed with another secret are invalid, not expired
+        let other = AuthManager::with_secret("another_secret".to_string(), 600, 3600);
+        assert!(matches!(other.validate_token(&tokens.access_token), Err(TokenError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_expired_token_is_rejected_as_expired() {
+        let manager = manager(600);
+        let issued = Utc::now().timestamp() - 7200;
+        let token = manager.sign(&Claims {
+            sub: "partner".to_string(),
+            exp: issued + 600,
+            iat: issued,
+            typ: "relay".to_string(),
+            scope: None,
+            kfp: None,
+        }).unwrap();
+
+        assert_eq!(manager.validate_token(&token).unwrap_err(), TokenError::Expired);
+    }
+
+    #[test]
+    fn test_refresh_issues_new_valid_access_token() {
+        let manager = manager(600);
+        let tokens = manager.issue_tokens("operator", ADMIN_TOKEN_TYPE).unwrap();
+
+        let refreshed = manager.refresh(&tokens.refresh_token).unwrap();
+        let claims = manager.validate_token(&refreshed.access_token).unwrap();
+        assert_eq!(claims.sub, "operator");
+        assert_eq!(claims.typ, ADMIN_TOKEN_TYPE);
+
+        // Access tokens can't be used to refresh
+        assert!(matches!(manager.refresh(&tokens.access_token), Err(TokenError::WrongType { .. })));
+    }
+
+    #[test]
+    fn test_rotating_the_api_key_invalidates_refresh_tokens() {
+        let manager = manager(600).with_api_keys(Some("relay-key".to_string()), Some("admin-key".to_string()));
+        let admin = manager.issue_tokens("operator", ADMIN_TOKEN_TYPE).unwrap();
+        let relay = manager.issue_tokens("partner", "relay").unwrap();
+        assert!(manager.refresh(&admin.refresh_token).is_ok());
+
+        let rotated = manager.with_api_keys(Some("relay-key".to_string()), Some("new-admin-key".to_string()));
+        assert!(matches!(rotated.refresh(&admin.refresh_token), Err(TokenError::Invalid(_))));
+        // Sessions of the key that wasn't rotated carry on
+        assert!(rotated.refresh(&relay.refresh_token).is_ok());
+
+        let removed = rotated.with_api_keys(Some("relay-key".to_string()), None);
+        assert!(matches!(removed.refresh(&admin.refresh_token), Err(TokenError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_refresh_token_is_not_an_access_token() {
+        let manager = manager(600);
+        let tokens = manager.issue_tokens("operator", ADMIN_TOKEN_TYPE).unwrap();
+
+        assert!(matches!(manager.validate_token(&tokens.refresh_token), Err(TokenError::WrongType { .. })));
+        assert!(manager.validate_token(&tokens.access_token).is_ok());
+    }
+
+    #[test]
+    fn test_api_keys_map_to_token_types() {
+        let manager = manager(600);
+        assert_eq!(manager.token_type_for_api_key("anything"), Err(ApiKeyError::NotConfigured));
+
+        let manager = manager.with_api_keys(Some("relay-key".to_string()), Some("admin-key".to_string()));
+        assert_eq!(manager.token_type_for_api_key("relay-key"), Ok("relay"));
+        assert_eq!(manager.token_type_for_api_key("admin-key"), Ok(ADMIN_TOKEN_TYPE));
+        assert_eq!(manager.token_type_for_api_key("dev_api_key"), Err(ApiKeyError::Invalid));
+    }
+
     #[test]
     fn test_production_secrets_generation() {
         let secrets = AuthManager::generate_production_secrets();
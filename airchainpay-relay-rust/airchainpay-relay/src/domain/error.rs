@@ -99,7 +99,7 @@ impl From<anyhow::Error> for RelayError {
 
 impl From<std::io::Error> for RelayError {
     fn from(err: std::io::Error) -> Self {
-        RelayError::Storage(StorageError::IO(err.to_string()))
+        RelayError::Storage(StorageError::from_io(&err))
     }
 }
 
@@ -232,6 +232,11 @@ pub enum BlockchainError {
     ProviderNotFound(u64),
     RetryableError(String),
     NonRetryableError(String),
+    /// The node rejected the transaction because it reverts; `data` is the raw revert data
+    Reverted { reason: String, data: Option<String> },
+    /// The transaction's nonce is ahead of its sender's next nonce; try again once the
+    /// predecessors have been broadcast
+    NonceHeld { nonce: u64, expected: u64 },
 }
 
 impl fmt::Display for BlockchainError {
@@ -251,10 +256,14 @@ impl fmt::Display for BlockchainError {
             BlockchainError::ProviderNotFound(chain_id) => write!(f, "Provider not found for chain: {chain_id}"),
             BlockchainError::RetryableError(msg) => write!(f, "Retryable error: {msg}"),
             BlockchainError::NonRetryableError(msg) => write!(f, "Non-retryable error: {msg}"),
+            BlockchainError::Reverted { reason, .. } => write!(f, "Transaction reverted: {reason}"),
+            BlockchainError::NonceHeld { nonce, expected } => write!(f, "Nonce {nonce} held until nonce {expected} is broadcast"),
         }
     }
 }
 
+impl std::error::Error for BlockchainError {}
+
 impl BlockchainError {
     pub fn to_http_response(&self) -> (actix_web::http::StatusCode, serde_json::Value) {
         let (status_code, error_type) = match self {
@@ -276,6 +285,12 @@ impl BlockchainError {
             BlockchainError::NonRetryableError(_) => {
                 (actix_web::http::StatusCode::BAD_REQUEST, "NON_RETRYABLE_ERROR")
             }
+            BlockchainError::Reverted { .. } => {
+                (actix_web::http::StatusCode::BAD_REQUEST, "TRANSACTION_REVERTED")
+            }
+            BlockchainError::NonceHeld { .. } => {
+                (actix_web::http::StatusCode::CONFLICT, "NONCE_HELD")
+            }
             _ => (actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "BLOCKCHAIN_ERROR"),
         };
 
@@ -373,6 +388,8 @@ pub enum StorageError {
     StorageFull(String),
     PermissionDenied(String),
     CorruptedData(String),
+    /// Failure expected to clear on its own (interrupted call, lock held elsewhere); safe to retry
+    Transient(String),
 }
 
 impl fmt::Display for StorageError {
@@ -389,11 +406,31 @@ impl fmt::Display for StorageError {
             StorageError::StorageFull(msg) => write!(f, "Storage full: {msg}"),
             StorageError::PermissionDenied(msg) => write!(f, "Permission denied: {msg}"),
             StorageError::CorruptedData(msg) => write!(f, "Corrupted data: {msg}"),
+            StorageError::Transient(msg) => write!(f, "Transient storage error: {msg}"),
         }
     }
 }
 
+impl std::error::Error for StorageError {}
+
 impl StorageError {
+    /// Classify an I/O error; interrupted, would-block and timed-out calls are transient
+    pub fn from_io(err: &std::io::Error) -> Self {
+        use std::io::ErrorKind;
+        match err.kind() {
+            ErrorKind::Interrupted | ErrorKind::WouldBlock | ErrorKind::TimedOut => StorageError::Transient(err.to_string()),
+            ErrorKind::NotFound => StorageError::FileNotFound(err.to_string()),
+            ErrorKind::PermissionDenied => StorageError::PermissionDenied(err.to_string()),
+            ErrorKind::StorageFull => StorageError::StorageFull(err.to_string()),
+            ErrorKind::InvalidData => StorageError::CorruptedData(err.to_string()),
+            _ => StorageError::IO(err.to_string()),
+        }
+    }
+
+    pub fn is_transient(&self) -> bool {
+        matches!(self, StorageError::Transient(_))
+    }
+
     pub fn to_http_response(&self) -> (actix_web::http::StatusCode, serde_json::Value) {
         let (status_code, error_type) = match self {
             StorageError::TransactionNotFound(_) | StorageError::DeviceNotFound(_) => {
@@ -405,6 +442,9 @@ impl StorageError {
             StorageError::StorageFull(_) => {
                 (actix_web::http::StatusCode::SERVICE_UNAVAILABLE, "STORAGE_FULL")
             }
+            StorageError::Transient(_) => {
+                (actix_web::http::StatusCode::SERVICE_UNAVAILABLE, "STORAGE_UNAVAILABLE")
+            }
             _ => (actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, "STORAGE_ERROR"),
         };
 
@@ -0,0 +1,213 @@
+//! Decoding of transaction input data against known contract ABIs
+//!
+//! Calls are matched by their 4-byte selector. ERC-20 and the AirChainPay
+//! contracts are always known; more ABIs can be loaded from JSON files.
+
+use crate::infrastructure::config::CalldataDecoderConfig;
+use anyhow::{anyhow, Result};
+use ethers::abi::{Abi, Function, Token};
+use ethers::core::utils::rlp::{Decodable, Rlp};
+use ethers::types::Transaction;
+use serde::Serialize;
+use std::collections::HashMap;
+
+const ERC20_FUNCTIONS: &[&str] = &[
+    "function transfer(address to, uint256 amount) returns (bool)",
+    "function approve(address spender, uint256 amount) returns (bool)",
+    "function transferFrom(address from, address to, uint256 amount) returns (bool)",
+];
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DecodedArg {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: serde_json::Value,
+}
+
+/// Input data of a transaction, decoded when its selector belongs to a known ABI
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum DecodedInput {
+    Call {
+        function: String,
+        signature: String,
+        args: Vec<DecodedArg>,
+    },
+    /// Unknown selector or arguments that do not match the ABI
+    Raw {
+        selector: Option<String>,
+        raw: String,
+    },
+}
+
+pub struct CalldataDecoder {
+    functions: HashMap<[u8; 4], Function>,
+}
+
+impl Default for CalldataDecoder {
+    fn default() -> Self {
+        let mut decoder = Self { functions: HashMap::new() };
+        let erc20 = ethers::abi::parse_abi(ERC20_FUNCTIONS).expect("ERC-20 function signatures are valid");
+        decoder.add_abi(&erc20);
+        for abi_json in [&include_bytes!("../../abi/AirChainPay.json")[..], &include_bytes!("../../abi/AirChainPayToken.json")[..]] {
+            let abi: Abi = serde_json::from_slice(abi_json).expect("bundled contract ABIs are valid");
+            decoder.add_abi(&abi);
+        }
+        decoder
+    }
+}
+
+impl CalldataDecoder {
+    /// Built-in ABIs plus every ABI file listed in `config`
+    pub fn from_config(config: &CalldataDecoderConfig) -> Result<Self> {
+        let mut decoder = Self::default();
+        for path in &config.abi_paths {
+            let contents = std::fs::read(path)
+                .map_err(|e| anyhow!("Failed to read ABI file {}: {}", path, e))?;
+            let abi: Abi = serde_json::from_slice(&contents)
+                .map_err(|e| anyhow!("Invalid ABI in {}: {}", path, e))?;
+            decoder.add_abi(&abi);
+        }
+        Ok(decoder)
+    }
+
+    /// Make every function of `abi` decodable; a later ABI wins on selector clashes
+    pub fn add_abi(&mut self, abi: &Abi) {
+        for function in abi.functions() {
+            self.functions.insert(function.short_signature(), function.clone());
+        }
+    }
+
+    /// Decode raw input data. Empty input (a plain value transfer) decodes to `None`.
+    pub fn decode(&self, data: &[u8]) -> Option<DecodedInput> {
+        if data.is_empty() {
+            return None;
+        }
+        let raw = || DecodedInput::Raw {
+            selector: (data.len() >= 4).then(|| format!("0x{}", hex::encode(&data[..4]))),
+            raw: format!("0x{}", hex::encode(data)),
+        };
+        let Some(selector) = data.get(..4).and_then(|s| <[u8; 4]>::try_from(s).ok()) else {
+            return Some(raw());
+        };
+        let Some(function) = self.functions.get(&selector) else {
+            return Some(raw());
+        };
+        let Ok(tokens) = function.decode_input(&data[4..]) else {
+            return Some(raw());
+        };
+
+        let args = function.inputs.iter().zip(tokens)
+            .map(|(param, token)| DecodedArg {
+                name: param.name.clone(),
+                kind: param.kind.to_string(),
+                value: token_to_json(token),
+            })
+            .collect();
+        Some(DecodedInput::Call {
+            function: function.name.clone(),
+            signature: format!("{}({})", function.name, function.inputs.iter().map(|p| p.kind.to_string()).collect::<Vec<_>>().join(",")),
+            args,
+        })
+    }
+
+    /// Decode the input data of a hex-encoded signed transaction
+    pub fn decode_signed_tx(&self, signed_tx: &str) -> Option<DecodedInput> {
+        let bytes = hex::decode(signed_tx.trim_start_matches("0x")).ok()?;
+        let tx = Transaction::decode(&Rlp::new(&bytes)).ok()?;
+        self.decode(&tx.input)
+    }
+}
+
+fn token_to_json(token: Token) -> serde_json::Value {
+    match token {
+        Token::Address(address) => serde_json::json!(format!("0x{:x}", address)),
+        // Amounts exceed JSON number precision, so integers are decimal strings
+        Token::Uint(value) => serde_json::json!(value.to_string()),
+        Token::Int(value) => serde_json::json!(ethers::types::I256::from_raw(value).to_string()),
+        Token::Bool(value) => serde_json::json!(value),
+        Token::String(value) => serde_json::json!(value),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => serde_json::json!(format!("0x{}", hex::encode(bytes))),
+        Token::Array(items) | Token::FixedArray(items) | Token::Tuple(items) => {
+            serde_json::Value::Array(items.into_iter().map(token_to_json).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{Address, U256};
+
+    const RECIPIENT: &str = "0x2222222222222222222222222222222222222222";
+
+    fn arg(decoded: &DecodedInput, index: usize) -> &DecodedArg {
+        match decoded {
+            DecodedInput::Call { args, .. } => &args[index],
+            DecodedInput::Raw { .. } => panic!("expected a decoded call, got {:?}", decoded),
+        }
+    }
+
+    #[test]
+    fn test_decodes_erc20_transfer() {
+        let recipient: Address = RECIPIENT.parse().unwrap();
+        let mut data = ethers::utils::id("transfer(address,uint256)").to_vec();
+        data.extend(ethers::abi::encode(&[Token::Address(recipient), Token::Uint(U256::from(1_500_000u64))]));
+
+        let decoded = CalldataDecoder::default().decode(&data).unwrap();
+
+        let DecodedInput::Call { function, signature, .. } = &decoded else { panic!("not decoded") };
+        assert_eq!(function, "transfer");
+        assert_eq!(signature, "transfer(address,uint256)");
+        assert_eq!(arg(&decoded, 0), &DecodedArg { name: "to".into(), kind: "address".into(), value: serde_json::json!(RECIPIENT) });
+        assert_eq!(arg(&decoded, 1).value, serde_json::json!("1500000"));
+    }
+
+    #[test]
+    fn test_decodes_payment_function() {
+        let recipient: Address = RECIPIENT.parse().unwrap();
+        let mut data = ethers::utils::id("processNativePayment(address,string)").to_vec();
+        data.extend(ethers::abi::encode(&[Token::Address(recipient), Token::String("invoice-42".into())]));
+
+        let decoded = CalldataDecoder::default().decode(&data).unwrap();
+
+        let json = serde_json::to_value(&decoded).unwrap();
+        assert_eq!(json["function"], "processNativePayment");
+        assert_eq!(json["args"][0]["value"], RECIPIENT);
+        assert_eq!(json["args"][1]["type"], "string");
+        assert_eq!(json["args"][1]["value"], "invoice-42");
+    }
+
+    #[test]
+    fn test_unknown_selector_stays_raw() {
+        let decoder = CalldataDecoder::default();
+        let data = vec![0xde, 0xad, 0xbe, 0xef, 0x01];
+        assert_eq!(decoder.decode(&data), Some(DecodedInput::Raw {
+            selector: Some("0xdeadbeef".into()),
+            raw: "0xdeadbeef01".into(),
+        }));
+
+        // A known selector with arguments that do not fit the ABI is not guessed at either
+        let truncated = ethers::utils::id("transfer(address,uint256)").to_vec();
+        assert!(matches!(decoder.decode(&truncated), Some(DecodedInput::Raw { .. })));
+        assert_eq!(decoder.decode(&[]), None);
+    }
+
+    #[test]
+    fn test_configured_abi_files_are_loaded() {
+        let path = std::env::temp_dir().join(format!("airchainpay-abi-{}.json", uuid::Uuid::new_v4()));
+        let abi = ethers::abi::parse_abi(&["function deposit(uint256 assets, address receiver)"]).unwrap();
+        std::fs::write(&path, serde_json::to_vec(&abi).unwrap()).unwrap();
+
+        let config = CalldataDecoderConfig { enabled: true, abi_paths: vec![path.display().to_string()] };
+        let decoder = CalldataDecoder::from_config(&config).unwrap();
+        let mut data = ethers::utils::id("deposit(uint256,address)").to_vec();
+        data.extend(ethers::abi::encode(&[Token::Uint(U256::from(7u64)), Token::Address(Address::zero())]));
+        assert!(matches!(decoder.decode(&data), Some(DecodedInput::Call { ref function, .. }) if function == "deposit"));
+        std::fs::remove_file(&path).unwrap();
+
+        let missing = CalldataDecoderConfig { enabled: true, abi_paths: vec!["/nonexistent/abi.json".into()] };
+        assert!(CalldataDecoder::from_config(&missing).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::infrastructure::config::ChainHealthConfig;
+use chrono::{DateTime, Duration, Utc};
+use ethers::providers::Middleware;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Result of probing a chain's RPC endpoint with `eth_blockNumber`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainHealth {
+    pub chain_id: u64,
+    pub healthy: bool,
+    pub block_number: Option<u64>,
+    pub response_time_ms: u64,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Probe the chain's RPC endpoint once; no answer within `timeout` counts as unhealthy
+pub async fn check_chain_health<M: Middleware>(provider: &M, chain_id: u64, timeout: std::time::Duration) -> ChainHealth {
+    let started = std::time::Instant::now();
+    let result = tokio::time::timeout(timeout, provider.get_block_number()).await;
+    let response_time_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(Ok(block)) => ChainHealth {
+            chain_id,
+            healthy: true,
+            block_number: Some(block.as_u64()),
+            response_time_ms,
+            error: None,
+            checked_at: Utc::now(),
+        },
+        Ok(Err(e)) => ChainHealth {
+            chain_id,
+            healthy: false,
+            block_number: None,
+            response_time_ms,
+            error: Some(format!("Failed to read latest block: {}", e)),
+            checked_at: Utc::now(),
+        },
+        Err(_) => ChainHealth {
+            chain_id,
+            healthy: false,
+            block_number: None,
+            response_time_ms,
+            error: Some(format!("RPC did not answer within {}ms", timeout.as_millis())),
+            checked_at: Utc::now(),
+        },
+    }
+}
+
+/// Last health check per chain, reused until it is older than the TTL
+pub struct ChainHealthCache {
+    ttl: Duration,
+    probe_timeout: std::time::Duration,
+    entries: Mutex<HashMap<u64, ChainHealth>>,
+    /// Per chain, held while a stale chain is re-checked so concurrent readers share one RPC
+    /// call; a slow chain never holds up another
+    refreshing: Mutex<HashMap<u64, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl ChainHealthCache {
+    pub fn new(config: &ChainHealthConfig) -> Self {
+        Self {
+            ttl: Duration::seconds(config.cache_ttl_secs as i64),
+            probe_timeout: std::time::Duration::from_secs(config.probe_timeout_secs),
+            entries: Mutex::new(HashMap::new()),
+            refreshing: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn refresh_lock(&self, chain_id: u64) -> Arc<tokio::sync::Mutex<()>> {
+        Arc::clone(self.refreshing.lock().unwrap().entry(chain_id).or_default())
+    }
+
+    fn get_fresh_at(&self, chain_id: u64, now: DateTime<Utc>) -> Option<ChainHealth> {
+        self.entries.lock().unwrap()
+            .get(&chain_id)
+            .filter(|health| now - health.checked_at < self.ttl)
+            .cloned()
+    }
+
+    pub fn insert(&self, health: ChainHealth) {
+        self.entries.lock().unwrap().insert(health.chain_id, health);
+    }
+
+    /// Every cached result, fresh or not
+    pub fn all(&self) -> Vec<ChainHealth> {
+        let mut all: Vec<ChainHealth> = self.entries.lock().unwrap().values().cloned().collect();
+        all.sort_by_key(|health| health.chain_id);
+        all
+    }
+}
+
+/// The chain's health, from the cache while it is fresh and otherwise from one live check
+pub async fn cached_chain_health<M: Middleware>(provider: &M, cache: &ChainHealthCache, chain_id: u64) -> ChainHealth {
+    if let Some(health) = cache.get_fresh_at(chain_id, Utc::now()) {
+        return health;
+    }
+    let lock = cache.refresh_lock(chain_id);
+    let _refreshing = lock.lock().await;
+    // Another caller may have refreshed it while this one waited
+    if let Some(health) = cache.get_fresh_at(chain_id, Utc::now()) {
+        return health;
+    }
+    let health = check_chain_health(provider, chain_id, cache.probe_timeout).await;
+    cache.insert(health.clone());
+    health
+}
+
+/// Re-check the chain regardless of the cache and store the result
+pub async fn refresh_chain_health<M: Middleware>(provider: &M, cache: &ChainHealthCache, chain_id: u64) -> ChainHealth {
+    let health = check_chain_health(provider, chain_id, cache.probe_timeout).await;
+    cache.insert(health.clone());
+    health
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{MockProvider, Provider};
+    use ethers::types::U64;
+
+    fn cache(ttl_secs: u64) -> ChainHealthCache {
+        ChainHealthCache::new(&ChainHealthConfig { cache_ttl_secs: ttl_secs, refresh_interval_secs: 0, ..Default::default() })
+    }
+
+    fn assert_no_more_requests(mock: &MockProvider) {
+        assert!(matches!(mock.assert_request("eth_blockNumber", ()), Err(ethers::providers::MockError::EmptyRequests)));
+    }
+
+    #[tokio::test]
+    async fn test_rapid_reads_share_one_status_query() {
+        let (provider, mock) = Provider::mocked();
+        let cache = cache(60);
+        mock.push(U64::from(100)).unwrap();
+
+        // Two submissions in a row: only the first reaches the node
+        let first = cached_chain_health(&provider, &cache, 1114).await;
+        let second = cached_chain_health(&provider, &cache, 1114).await;
+        assert!(first.healthy);
+        assert_eq!(first.block_number, Some(100));
+        assert_eq!(second, first);
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        assert_no_more_requests(&mock);
+    }
+
+    #[tokio::test]
+    async fn test_expired_and_forced_checks_query_again() {
+        let (provider, mock) = Provider::mocked();
+        let cache = cache(0);
+        mock.push(U64::from(101)).unwrap();
+        mock.push(U64::from(100)).unwrap();
+
+        assert_eq!(cached_chain_health(&provider, &cache, 1114).await.block_number, Some(100));
+        assert_eq!(cached_chain_health(&provider, &cache, 1114).await.block_number, Some(101));
+
+        let cache = self::cache(60);
+        mock.push(U64::from(103)).unwrap();
+        mock.push(U64::from(102)).unwrap();
+        assert_eq!(cached_chain_health(&provider, &cache, 1114).await.block_number, Some(102));
+        assert_eq!(refresh_chain_health(&provider, &cache, 1114).await.block_number, Some(103));
+        assert_eq!(cached_chain_health(&provider, &cache, 1114).await.block_number, Some(103));
+    }
+
+    #[tokio::test]
+    async fn test_failed_check_is_unhealthy() {
+        let (provider, _mock) = Provider::mocked();
+        let health = cached_chain_health(&provider, &cache(60), 1114).await;
+        assert!(!health.healthy);
+        assert!(health.error.is_some());
+        assert_eq!(health.block_number, None);
+    }
+
+    #[tokio::test]
+    async fn test_hanging_chain_times_out_without_blocking_other_chains() {
+        use crate::utils::mock_http::{MockResponse, MockServer};
+        use ethers::providers::Http;
+
+        let hanging = MockServer::start(|request| {
+            MockResponse::rpc_result(request, serde_json::json!("0x10")).delayed(std::time::Duration::from_secs(30))
+        }).await;
+        let hanging = Provider::<Http>::try_from(hanging.url.as_str()).unwrap();
+        let (healthy, mock) = Provider::mocked();
+        mock.push(U64::from(100)).unwrap();
+        let cache = ChainHealthCache::new(&ChainHealthConfig { cache_ttl_secs: 60, refresh_interval_secs: 0, probe_timeout_secs: 1 });
+
+        let started = std::time::Instant::now();
+        let (stuck, other) = tokio::join!(
+            cached_chain_health(&hanging, &cache, 1),
+            async {
+                // Give the hanging probe time to take its chain's lock
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                let other = cached_chain_health(&healthy, &cache, 1114).await;
+                (other, started.elapsed())
+            },
+        );
+        assert!(other.0.healthy);
+        assert!(other.1 < std::time::Duration::from_millis(900), "{:?}", other.1);
+        assert!(!stuck.healthy);
+        assert!(stuck.error.unwrap().contains("did not answer"));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+}
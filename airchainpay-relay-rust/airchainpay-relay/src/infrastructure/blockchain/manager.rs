@@ -1,10 +1,18 @@
-use crate::infrastructure::config::Config;
+use crate::infrastructure::config::{Config, FeeBounds};
+use crate::infrastructure::blockchain::pooled_http::{PooledHttp, RpcConnectionPool};
+use crate::infrastructure::blockchain::health::{ChainHealth, ChainHealthCache, cached_chain_health, refresh_chain_health};
+use crate::infrastructure::blockchain::nonce::NonceManager;
+use crate::infrastructure::blockchain::rebroadcast::{BroadcastCounts, RebroadcastThrottle};
+use crate::domain::error::BlockchainError;
+use crate::infrastructure::blockchain::revert::node_error;
+use crate::infrastructure::blockchain::sequencer::{NonceSequencer, Turn, decode_sender_nonce};
+use crate::infrastructure::blockchain::simulation::{SimulatedCall, SimulationCache, simulate_call};
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use ethers::{
-    providers::{Provider, Http},
+    providers::Provider,
     core::types::{Address, U256, H256, Log, Bytes, Filter, BlockNumber},
     prelude::*,
 };
@@ -45,19 +53,237 @@ pub struct PaymentEvent {
     pub log_index: u64,
 }
 
+/// Outcome of checking whether a broadcast transaction reached the mempool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MempoolStatus {
+    /// The node knows about the transaction
+    Seen,
+    /// The transaction was missing and has been re-broadcast once
+    Rebroadcast,
+    /// The transaction was missing and was not re-broadcast
+    Dropped,
+}
+
+/// Check that a broadcast transaction is known to the node after `delay`,
+/// re-broadcasting it once if it was silently dropped and `rebroadcast` is set
+pub async fn check_mempool_inclusion<M: Middleware>(
+    provider: &M,
+    tx_hash: H256,
+    raw_tx: Bytes,
+    delay: std::time::Duration,
+    rebroadcast: bool,
+) -> Result<MempoolStatus> {
+    tokio::time::sleep(delay).await;
+
+    let known = provider.get_transaction(tx_hash).await
+        .map_err(|e| anyhow!("Failed to look up transaction {:?}: {}", tx_hash, e))?;
+    if known.is_some() {
+        return Ok(MempoolStatus::Seen);
+    }
+    if !rebroadcast {
+        return Ok(MempoolStatus::Dropped);
+    }
+
+    provider.send_raw_transaction(raw_tx).await
+        .map_err(|e| anyhow!("Failed to re-broadcast transaction {:?}: {}", tx_hash, e))?;
+    Ok(MempoolStatus::Rebroadcast)
+}
+
+/// State of a transaction as reported by the node, independent of the relay's records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnchainState {
+    /// Known to the node but not mined yet
+    Pending,
+    /// Neither mined nor in the node's mempool
+    NotFound,
+    Success,
+    Reverted,
+}
+
+/// Receipt-derived status of a transaction read directly from the chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnchainStatus {
+    pub state: OnchainState,
+    pub block_number: Option<u64>,
+    /// Blocks on top of and including the one the transaction was mined in; 0 until mined
+    pub confirmations: u64,
+    pub gas_used: Option<U256>,
+}
+
+/// Whether the node refused a transaction because it already has it
+fn is_already_known(error: &ProviderError) -> bool {
+    RpcError::as_error_response(error).is_some_and(|e| {
+        let message = e.message.to_ascii_lowercase();
+        message.contains("already known") || message.contains("known transaction") || message.contains("already imported")
+    })
+}
+
+/// Look up the receipt of `tx_hash` and derive its confirmations and success or revert.
+/// Without a mined receipt, the mempool lookup tells pending and unknown transactions apart.
+pub async fn fetch_onchain_status<M: Middleware>(provider: &M, tx_hash: H256) -> Result<OnchainStatus> {
+    let receipt = provider.get_transaction_receipt(tx_hash).await
+        .map_err(|e| anyhow!("Failed to fetch receipt for {:?}: {}", tx_hash, e))?;
+
+    if let Some((receipt, block_number)) = receipt.and_then(|r| r.block_number.map(|n| (r, n.as_u64()))) {
+        let latest_block = provider.get_block_number().await
+            .map_err(|e| anyhow!("Failed to fetch latest block number: {}", e))?
+            .as_u64();
+        // Pre-Byzantium receipts carry no status; they only exist for executed transactions
+        let state = match receipt.status {
+            Some(status) if status.is_zero() => OnchainState::Reverted,
+            _ => OnchainState::Success,
+        };
+        return Ok(OnchainStatus {
+            state,
+            block_number: Some(block_number),
+            confirmations: latest_block.saturating_sub(block_number) + 1,
+            gas_used: receipt.gas_used,
+        });
+    }
+
+    let known = provider.get_transaction(tx_hash).await
+        .map_err(|e| anyhow!("Failed to look up transaction {:?}: {}", tx_hash, e))?;
+    Ok(OnchainStatus {
+        state: if known.is_some() { OnchainState::Pending } else { OnchainState::NotFound },
+        block_number: None,
+        confirmations: 0,
+        gas_used: None,
+    })
+}
+
+/// Number of recent blocks sampled when deriving the average block time
+pub const DEFAULT_ETA_BLOCK_SAMPLE: u64 = 20;
+
+/// Estimated confirmation time for a single fee tier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeTierEta {
+    pub tier: String,
+    pub gas_price: U256,
+    pub expected_blocks: u64,
+    pub eta_seconds: f64,
+}
+
+/// Confirmation time estimates across the slow/medium/fast fee tiers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationEta {
+    pub latest_block: u64,
+    pub sampled_blocks: u64,
+    pub average_block_time_seconds: f64,
+    pub tiers: Vec<FeeTierEta>,
+}
+
+// (tier, gas price percentage of the current price, blocks until inclusion)
+const FEE_TIERS: [(&str, u64, u64); 3] = [
+    ("slow", 90, 6),
+    ("medium", 100, 3),
+    ("fast", 125, 1),
+];
+
+/// Estimate confirmation times from recent block timestamps and the current gas price
+pub async fn estimate_confirmation_eta<M: Middleware>(provider: &M, sample_size: u64) -> Result<ConfirmationEta> {
+    let latest_block = provider.get_block_number().await
+        .map_err(|e| anyhow!("Failed to fetch latest block number: {}", e))?
+        .as_u64();
+    let sampled_blocks = sample_size.min(latest_block);
+    if sampled_blocks == 0 {
+        return Err(anyhow!("Not enough blocks to derive block time"));
+    }
+
+    let newest = provider.get_block(latest_block).await
+        .map_err(|e| anyhow!("Failed to fetch block {}: {}", latest_block, e))?
+        .ok_or_else(|| anyhow!("Block {} not found", latest_block))?;
+    let oldest_number = latest_block - sampled_blocks;
+    let oldest = provider.get_block(oldest_number).await
+        .map_err(|e| anyhow!("Failed to fetch block {}: {}", oldest_number, e))?
+        .ok_or_else(|| anyhow!("Block {} not found", oldest_number))?;
+
+    // The mean of consecutive intervals telescopes to the overall span
+    let span = newest.timestamp.saturating_sub(oldest.timestamp).as_u64();
+    if span == 0 {
+        return Err(anyhow!("Recent block timestamps do not advance"));
+    }
+    let average_block_time_seconds = span as f64 / sampled_blocks as f64;
+
+    let gas_price = provider.get_gas_price().await
+        .map_err(|e| anyhow!("Failed to fetch gas price: {}", e))?;
+
+    let tiers = FEE_TIERS.iter()
+        .map(|(tier, percent, expected_blocks)| FeeTierEta {
+            tier: tier.to_string(),
+            gas_price: gas_price * U256::from(*percent) / U256::from(100u64),
+            expected_blocks: *expected_blocks,
+            eta_seconds: average_block_time_seconds * *expected_blocks as f64,
+        })
+        .collect();
+
+    Ok(ConfirmationEta {
+        latest_block,
+        sampled_blocks,
+        average_block_time_seconds,
+        tiers,
+    })
+}
+
+/// Clamp oracle-suggested EIP-1559 fees into the configured band, returning
+/// `(max_fee_per_gas, max_priority_fee_per_gas)`. The priority fee never exceeds the max fee.
+pub fn clamp_fees(bounds: &FeeBounds, max_fee_per_gas: U256, max_priority_fee_per_gas: U256) -> (U256, U256) {
+    fn clamp(value: U256, min: Option<u64>, max: Option<u64>) -> U256 {
+        let value = min.map_or(value, |min| value.max(U256::from(min)));
+        max.map_or(value, |max| value.min(U256::from(max)))
+    }
+
+    let max_fee = clamp(max_fee_per_gas, bounds.min_max_fee_per_gas, bounds.max_max_fee_per_gas);
+    let priority_fee = clamp(max_priority_fee_per_gas, bounds.min_priority_fee_per_gas, bounds.max_priority_fee_per_gas)
+        .min(max_fee);
+
+    if max_fee != max_fee_per_gas {
+        log::warn!("Clamped max fee per gas from {} to {} wei", max_fee_per_gas, max_fee);
+    }
+    if priority_fee != max_priority_fee_per_gas {
+        log::warn!("Clamped priority fee per gas from {} to {} wei", max_priority_fee_per_gas, priority_fee);
+    }
+
+    (max_fee, priority_fee)
+}
+
+/// Ask the provider's fee oracle for EIP-1559 fees and clamp them into `bounds`
+pub async fn estimate_bounded_fees<M: Middleware>(provider: &M, bounds: &FeeBounds) -> Result<(U256, U256)> {
+    let (max_fee, priority_fee) = provider.estimate_eip1559_fees(None).await
+        .map_err(|e| anyhow!("Failed to estimate EIP-1559 fees: {}", e))?;
+    Ok(clamp_fees(bounds, max_fee, priority_fee))
+}
+
+/// Provider type used for every configured chain
+pub type RpcProvider = Provider<PooledHttp>;
+
 pub struct BlockchainManager {
-    providers: HashMap<u64, Provider<Http>>,
-    contracts: HashMap<u64, HashMap<ContractType, Contract<Provider<Http>>>>,
+    providers: HashMap<u64, RpcProvider>,
+    contracts: HashMap<u64, HashMap<ContractType, Contract<RpcProvider>>>,
+    rpc_pool: Arc<RpcConnectionPool>,
+    fee_bounds: FeeBounds,
+    sponsored_paused: std::sync::RwLock<HashSet<u64>>,
+    clock_skew_tolerance: u64,
+    rebroadcast_throttle: RebroadcastThrottle,
+    simulate_before_send: bool,
+    simulation_cache: SimulationCache,
+    chain_health: ChainHealthCache,
+    chain_health_refresh_interval: u64,
+    nonce_sequencer: NonceSequencer,
+    /// Set when a sponsor address is configured
+    sponsor_nonces: Option<NonceManager>,
 }
 
 impl BlockchainManager {
     pub fn new(config: Config) -> Result<Self> {
         let mut providers = HashMap::new();
         let mut contracts = HashMap::new();
+        let rpc_pool = Arc::new(RpcConnectionPool::new(config.rpc_pool.clone())?);
         
         for (chain_id, chain_config) in &config.supported_chains {
-            let provider = Provider::<Http>::try_from(&chain_config.rpc_url)
+            let transport = rpc_pool.transport(&chain_config.rpc_url)
                 .map_err(|e| anyhow!("Failed to create HTTP provider for chain {}: {}", chain_id, e))?;
+            let provider = Provider::new(transport);
             
             providers.insert(*chain_id, provider.clone());
             
@@ -93,12 +319,112 @@ impl BlockchainManager {
             }
         }
         
+        let sponsor_nonces = match &config.sponsor.address {
+            Some(address) => {
+                let sponsor: Address = address.parse()
+                    .map_err(|e| anyhow!("Invalid sponsor address '{}': {}", address, e))?;
+                Some(NonceManager::new(sponsor))
+            }
+            None => None,
+        };
+        
         Ok(Self {
             providers,
             contracts,
+            rpc_pool,
+            fee_bounds: config.fee_bounds.clone(),
+            sponsored_paused: std::sync::RwLock::new(HashSet::new()),
+            clock_skew_tolerance: config.security.clock_skew_tolerance(),
+            rebroadcast_throttle: RebroadcastThrottle::new(config.rebroadcast.clone()),
+            simulate_before_send: config.simulation.simulate_before_send,
+            simulation_cache: SimulationCache::new(&config.simulation),
+            chain_health: ChainHealthCache::new(&config.chain_health),
+            chain_health_refresh_interval: config.chain_health.refresh_interval_secs,
+            nonce_sequencer: NonceSequencer::new(&config.nonce_sequencing),
+            sponsor_nonces,
         })
     }
 
+    /// Read the sponsor's nonce on every chain, so the first sponsored sends don't wait for it
+    pub async fn sync_sponsor_nonces(&self) {
+        let Some(sponsor_nonces) = &self.sponsor_nonces else {
+            return;
+        };
+        for (chain_id, provider) in &self.providers {
+            match sponsor_nonces.sync(*chain_id, provider).await {
+                Ok(nonce) => log::info!("Sponsor nonce on chain {} synchronized at {}", chain_id, nonce),
+                Err(e) => log::warn!("{}", e),
+            }
+        }
+    }
+
+    /// Stop accepting sponsored (relay-paid) broadcasts on a chain
+    pub fn pause_sponsored(&self, chain_id: u64) {
+        self.sponsored_paused.write().unwrap().insert(chain_id);
+    }
+
+    pub fn resume_sponsored(&self, chain_id: u64) {
+        self.sponsored_paused.write().unwrap().remove(&chain_id);
+    }
+
+    pub fn is_sponsored_paused(&self, chain_id: u64) -> bool {
+        self.sponsored_paused.read().unwrap().contains(&chain_id)
+    }
+
+    /// Reject meta-transactions whose deadline has passed. The configured clock skew is
+    /// capped at `MAX_DEADLINE_SKEW_SECS` here, since the contract enforces the deadline itself.
+    fn ensure_deadline_valid(&self, deadline: U256) -> Result<()> {
+        let deadline = if deadline > U256::from(i64::MAX as u64) { i64::MAX } else { deadline.as_u64() as i64 };
+        crate::domain::auth::check_validity_window(
+            chrono::Utc::now().timestamp(),
+            None,
+            Some(deadline),
+            self.clock_skew_tolerance.min(crate::domain::auth::MAX_DEADLINE_SKEW_SECS),
+        )
+        .map_err(|e| anyhow!("Meta-transaction deadline check failed: {}", e))
+    }
+
+    fn ensure_sponsored_allowed(&self, chain_id: u64) -> Result<()> {
+        if self.is_sponsored_paused(chain_id) {
+            return Err(anyhow!("Sponsored broadcasts are paused on chain {}: sponsor balance is low", chain_id));
+        }
+        Ok(())
+    }
+
+    pub fn chain_ids(&self) -> Vec<u64> {
+        self.providers.keys().copied().collect()
+    }
+
+    pub fn provider(&self, chain_id: u64) -> Option<&RpcProvider> {
+        self.providers.get(&chain_id)
+    }
+
+    /// Shared connection pool behind every chain's provider
+    pub fn rpc_pool(&self) -> &RpcConnectionPool {
+        &self.rpc_pool
+    }
+
+    /// Set oracle fees on a contract call, clamped into the configured fee band
+    async fn with_bounded_fees<D: ethers::abi::Detokenize>(
+        &self,
+        chain_id: u64,
+        mut call: ContractCall<RpcProvider, D>,
+    ) -> Result<ContractCall<RpcProvider, D>> {
+        let provider = self.providers.get(&chain_id)
+            .ok_or_else(|| anyhow!("No provider for chain_id {}", chain_id))?;
+        let (max_fee, priority_fee) = estimate_bounded_fees(provider, &self.fee_bounds).await?;
+        match call.tx.as_eip1559_mut() {
+            Some(tx) => {
+                tx.max_fee_per_gas = Some(max_fee);
+                tx.max_priority_fee_per_gas = Some(priority_fee);
+            }
+            None => {
+                call.tx.set_gas_price(max_fee);
+            }
+        }
+        Ok(call)
+    }
+
     /// Execute a meta-transaction on the AirChainPay contract
     pub async fn execute_meta_transaction(
         &self,
@@ -110,6 +436,8 @@ impl BlockchainManager {
         deadline: U256,
         signature: Bytes,
     ) -> Result<H256> {
+        self.ensure_sponsored_allowed(chain_id)?;
+        self.ensure_deadline_valid(deadline)?;
         let contract = self.get_contract(chain_id, ContractType::AirChainPay)?;
         
         let call = contract.method::<_, H256>(
@@ -117,9 +445,8 @@ impl BlockchainManager {
             (from, to, amount, payment_reference, deadline, signature)
         )?;
         
-        let pending_tx = call.send().await?;
-        let receipt = pending_tx.await?;
-        Ok(receipt.unwrap().transaction_hash)
+        let call = self.with_bounded_fees(chain_id, call).await?;
+        self.send_sponsored(chain_id, call).await
     }
 
     /// Execute a token meta-transaction on the AirChainPayToken contract
@@ -134,6 +461,8 @@ impl BlockchainManager {
         deadline: U256,
         signature: Bytes,
     ) -> Result<H256> {
+        self.ensure_sponsored_allowed(chain_id)?;
+        self.ensure_deadline_valid(deadline)?;
         let contract = self.get_contract(chain_id, ContractType::AirChainPayToken)?;
         
         let call = contract.method::<_, H256>(
@@ -141,7 +470,38 @@ impl BlockchainManager {
             (from, to, token, amount, payment_reference, deadline, signature)
         )?;
         
-        let pending_tx = call.send().await?;
+        let call = self.with_bounded_fees(chain_id, call).await?;
+        self.send_sponsored(chain_id, call).await
+    }
+
+    /// Send a relay-paid call from the sponsor account with a reserved nonce. Without a
+    /// configured sponsor the node picks the sender and nonce.
+    async fn send_sponsored<D: ethers::abi::Detokenize>(
+        &self,
+        chain_id: u64,
+        mut call: ContractCall<RpcProvider, D>,
+    ) -> Result<H256> {
+        let nonce = match &self.sponsor_nonces {
+            Some(sponsor_nonces) => {
+                let provider = self.providers.get(&chain_id)
+                    .ok_or_else(|| anyhow!("No provider for chain_id {}", chain_id))?;
+                let nonce = sponsor_nonces.reserve(chain_id, provider).await?;
+                call.tx.set_from(sponsor_nonces.sponsor());
+                call.tx.set_nonce(nonce);
+                Some((sponsor_nonces, provider, nonce))
+            }
+            None => None,
+        };
+        
+        let pending_tx = match call.send().await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                if let Some((sponsor_nonces, provider, nonce)) = nonce {
+                    sponsor_nonces.release(chain_id, nonce, provider).await;
+                }
+                return Err(e.into());
+            }
+        };
         let receipt = pending_tx.await?;
         Ok(receipt.unwrap().transaction_hash)
     }
@@ -161,6 +521,7 @@ impl BlockchainManager {
             (recipient, payment_reference)
         )?.value(value);
         
+        let call = self.with_bounded_fees(chain_id, call).await?;
         let pending_tx = call.send().await?;
         let receipt = pending_tx.await?;
         Ok(receipt.unwrap().transaction_hash)
@@ -182,6 +543,7 @@ impl BlockchainManager {
             (token, amount, recipient, payment_reference)
         )?;
         
+        let call = self.with_bounded_fees(chain_id, call).await?;
         let pending_tx = call.send().await?;
         let receipt = pending_tx.await?;
         Ok(receipt.unwrap().transaction_hash)
@@ -230,7 +592,7 @@ impl BlockchainManager {
     }
 
     /// Get contract instance for a specific chain and type
-    fn get_contract(&self, chain_id: u64, contract_type: ContractType) -> Result<&Contract<Provider<Http>>> {
+    fn get_contract(&self, chain_id: u64, contract_type: ContractType) -> Result<&Contract<RpcProvider>> {
         let chain_contracts = self.contracts.get(&chain_id)
             .ok_or_else(|| anyhow!("No contracts found for chain_id {}", chain_id))?;
         
@@ -240,16 +602,115 @@ impl BlockchainManager {
         Ok(contract)
     }
 
+    /// Overall status of every chain, from the health cache (stale chains are re-checked)
     pub async fn get_network_status(&self) -> Result<HashMap<String, String>> {
-        // Return overall network status for all chains
+        let mut chains = Vec::with_capacity(self.providers.len());
+        for (chain_id, provider) in &self.providers {
+            chains.push(cached_chain_health(provider, &self.chain_health, *chain_id).await);
+        }
+        let connected = chains.iter().filter(|health| health.healthy).count();
+        let average_response_time_ms = if chains.is_empty() {
+            0.0
+        } else {
+            chains.iter().map(|health| health.response_time_ms as f64).sum::<f64>() / chains.len() as f64
+        };
+
         let mut status = HashMap::new();
-        status.insert("overall_status".to_string(), "healthy".to_string());
+        let is_healthy = connected == chains.len();
+        status.insert("overall_status".to_string(), if is_healthy { "healthy" } else { "degraded" }.to_string());
+        status.insert("is_healthy".to_string(), is_healthy.to_string());
         status.insert("total_chains".to_string(), self.providers.len().to_string());
+        status.insert("total_networks".to_string(), self.providers.len().to_string());
+        status.insert("connected_networks".to_string(), connected.to_string());
+        status.insert("average_response_time_ms".to_string(), average_response_time_ms.to_string());
         status.insert("timestamp".to_string(), chrono::Utc::now().to_rfc3339());
         Ok(status)
     }
 
+    /// Health of one chain's RPC endpoint, cached for `CHAIN_HEALTH_TTL_SECS`
+    pub async fn chain_health(&self, chain_id: u64) -> Result<ChainHealth> {
+        let provider = self.providers.get(&chain_id)
+            .ok_or_else(|| anyhow!("No provider for chain_id {}", chain_id))?;
+        Ok(cached_chain_health(provider, &self.chain_health, chain_id).await)
+    }
+
+    /// Re-check every chain now, bypassing the cache; used by the health endpoints. Chains are
+    /// probed concurrently so a slow one doesn't delay the rest.
+    pub async fn force_refresh(&self) -> Vec<ChainHealth> {
+        let mut refreshed = futures_util::future::join_all(
+            self.providers.iter().map(|(chain_id, provider)| refresh_chain_health(provider, &self.chain_health, *chain_id)),
+        ).await;
+        refreshed.sort_by_key(|health| health.chain_id);
+        refreshed
+    }
+
+    /// Keep the health cache warm so submissions rarely wait for a live check
+    pub fn start_health_refresh(manager: Arc<Self>) {
+        if manager.chain_health_refresh_interval == 0 || manager.providers.is_empty() {
+            return;
+        }
+        let period = std::time::Duration::from_secs(manager.chain_health_refresh_interval);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                for health in manager.force_refresh().await {
+                    if !health.healthy {
+                        log::warn!("Chain {} RPC is unhealthy: {}", health.chain_id, health.error.unwrap_or_default());
+                    }
+                }
+            }
+        });
+    }
+
+    /// Check whether a provider is configured for a chain
+    pub fn supports_chain(&self, chain_id: u64) -> bool {
+        self.providers.contains_key(&chain_id)
+    }
+
+    /// Estimate confirmation times per fee tier for a chain
+    pub async fn get_confirmation_eta(&self, chain_id: u64) -> Result<ConfirmationEta> {
+        let provider = self.providers.get(&chain_id)
+            .ok_or_else(|| anyhow!("No provider for chain_id {}", chain_id))?;
+        estimate_confirmation_eta(provider, DEFAULT_ETA_BLOCK_SAMPLE).await
+    }
+
+    /// Read a transaction's receipt status straight from the chain
+    pub async fn get_onchain_status(&self, chain_id: u64, tx_hash: H256) -> Result<OnchainStatus> {
+        let provider = self.providers.get(&chain_id)
+            .ok_or_else(|| anyhow!("No provider for chain_id {}", chain_id))?;
+        fetch_onchain_status(provider, tx_hash).await
+    }
+
+    /// How often a transaction has been sent, as tracked by the re-broadcast throttle
+    pub fn broadcast_counts(&self, tx_hash: H256) -> Option<BroadcastCounts> {
+        self.rebroadcast_throttle.counts(tx_hash)
+    }
+
+    /// Per-sender nonce ordering of broadcasts
+    pub fn nonce_sequencer(&self) -> &NonceSequencer {
+        &self.nonce_sequencer
+    }
+
+    /// Dry-run a call on the chain's latest block, reusing a cached result for the same block
+    pub async fn simulate(&self, chain_id: u64, call: &SimulatedCall) -> Result<Bytes> {
+        let provider = self.providers.get(&chain_id)
+            .ok_or_else(|| anyhow!("No provider for chain_id {}", chain_id))?;
+        simulate_call(provider, &self.simulation_cache, chain_id, call).await
+    }
+
     pub async fn send_transaction(&self, tx: &QueuedTransaction) -> Result<H256> {
+        let (tx_hash, _raw_tx) = self.broadcast_transaction(tx).await?;
+        self.wait_for_receipt(tx.chain_id, tx_hash).await
+    }
+
+    /// Broadcast the signed transaction without waiting for it to be mined.
+    /// Sending an already broadcast transaction again is subject to the re-broadcast throttle.
+    /// With simulate-before-send enabled, a transaction that would revert is not sent.
+    /// A transaction whose nonce is ahead of its sender's next nonce is not sent and comes
+    /// back as a `BlockchainError::NonceHeld` until its predecessors have been broadcast.
+    /// A revert reported by the node comes back as a `BlockchainError::Reverted`.
+    pub async fn broadcast_transaction(&self, tx: &QueuedTransaction) -> Result<(H256, Bytes)> {
         let chain_id = tx.chain_id;
         let signed_tx_hex = match &tx.metadata.get("signedTx") {
             Some(val) => val.as_str().ok_or_else(|| anyhow!("signedTx is not a string"))?,
@@ -257,10 +718,114 @@ impl BlockchainManager {
         };
         let provider = self.providers.get(&chain_id)
             .ok_or_else(|| anyhow!("No provider for chain_id {}", chain_id))?;
-        let raw_tx_bytes = hex::decode(signed_tx_hex.trim_start_matches("0x"))?;
-        let pending_tx = provider.send_raw_transaction(Bytes::from(raw_tx_bytes)).await?;
-        let receipt = pending_tx.await?;
-        Ok(receipt.unwrap().transaction_hash)
+        let sender_nonce = decode_sender_nonce(signed_tx_hex).ok();
+        if let Some((sender, nonce)) = sender_nonce {
+            if let Turn::Held { expected } = self.nonce_sequencer.check_turn(chain_id, sender, nonce, provider).await? {
+                return Err(BlockchainError::NonceHeld { nonce: nonce.low_u64(), expected: expected.low_u64() }.into());
+            }
+        }
+        let result = self.send_signed(chain_id, signed_tx_hex, provider).await;
+        if let Some((sender, nonce)) = sender_nonce {
+            match &result {
+                Ok(_) => self.nonce_sequencer.advance(chain_id, sender, nonce),
+                Err(_) => self.nonce_sequencer.resync(chain_id, sender, provider).await,
+            }
+        }
+        result
+    }
+
+    async fn send_signed(&self, chain_id: u64, signed_tx_hex: &str, provider: &RpcProvider) -> Result<(H256, Bytes)> {
+        let raw_tx = Bytes::from(hex::decode(signed_tx_hex.trim_start_matches("0x"))?);
+        let tx_hash = H256::from(ethers::utils::keccak256(&raw_tx));
+        self.rebroadcast_throttle.check_at(tx_hash, chrono::Utc::now())?;
+        if self.simulate_before_send {
+            let call = SimulatedCall::from_signed_tx(signed_tx_hex)?;
+            self.simulate(chain_id, &call).await
+                .map_err(|e| anyhow!("Transaction simulation failed: {}", e))?;
+        }
+        match provider.send_raw_transaction(raw_tx.clone()).await {
+            Ok(pending_tx) => {
+                self.rebroadcast_throttle.record_at(pending_tx.tx_hash(), chrono::Utc::now());
+                Ok((pending_tx.tx_hash(), raw_tx))
+            }
+            // An earlier broadcast (e.g. before a restart) already reached this node
+            Err(e) if is_already_known(&e) => {
+                self.rebroadcast_throttle.record_at(tx_hash, chrono::Utc::now());
+                Ok((tx_hash, raw_tx))
+            }
+            Err(e) => Err(node_error(e)),
+        }
+    }
+
+    /// Check that a broadcast transaction reached the node's mempool
+    pub async fn check_mempool(
+        &self,
+        chain_id: u64,
+        tx_hash: H256,
+        raw_tx: Bytes,
+        delay: std::time::Duration,
+        rebroadcast: bool,
+    ) -> Result<MempoolStatus> {
+        let provider = self.providers.get(&chain_id)
+            .ok_or_else(|| anyhow!("No provider for chain_id {}", chain_id))?;
+        let send_at = chrono::Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default();
+        let rebroadcast = rebroadcast && match self.rebroadcast_throttle.check_mempool_rebroadcast_at(tx_hash, send_at) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("{}", e);
+                false
+            }
+        };
+        let status = check_mempool_inclusion(provider, tx_hash, raw_tx, delay, rebroadcast).await?;
+        if status == MempoolStatus::Rebroadcast {
+            self.rebroadcast_throttle.record_at(tx_hash, chrono::Utc::now());
+        }
+        Ok(status)
+    }
+
+    /// Wait until a broadcast transaction is mined
+    pub async fn wait_for_receipt(&self, chain_id: u64, tx_hash: H256) -> Result<H256> {
+        Ok(self.wait_for_mined_receipt(chain_id, tx_hash).await?.transaction_hash)
+    }
+
+    /// Wait until a broadcast transaction is mined and return its full receipt
+    pub async fn wait_for_mined_receipt(&self, chain_id: u64, tx_hash: H256) -> Result<ethers::types::TransactionReceipt> {
+        let provider = self.providers.get(&chain_id)
+            .ok_or_else(|| anyhow!("No provider for chain_id {}", chain_id))?;
+        PendingTransaction::new(tx_hash, provider).await?
+            .ok_or_else(|| anyhow!("Transaction {:?} was dropped before being mined", tx_hash))
+    }
+
+    /// Poll for the receipt of a broadcast transaction until it is mined or `timeout` elapses.
+    /// Failed RPC calls are retried at the next poll.
+    pub async fn poll_mined_receipt(
+        &self,
+        chain_id: u64,
+        tx_hash: H256,
+        poll_interval: std::time::Duration,
+        timeout: std::time::Duration,
+    ) -> Result<ethers::types::TransactionReceipt> {
+        let provider = self.providers.get(&chain_id)
+            .ok_or_else(|| anyhow!("No provider for chain_id {}", chain_id))?;
+        let started = std::time::Instant::now();
+        let mut last_error = None;
+        loop {
+            match provider.get_transaction_receipt(tx_hash).await {
+                Ok(Some(receipt)) if receipt.block_number.is_some() => return Ok(receipt),
+                Ok(_) => {}
+                Err(e) => {
+                    log::warn!("Receipt poll for {:?} failed, retrying: {}", tx_hash, e);
+                    last_error = Some(e);
+                }
+            }
+            if started.elapsed() >= timeout {
+                return Err(match last_error {
+                    Some(e) => anyhow!("Transaction {:?} was not mined within {:?}; last RPC error: {}", tx_hash, timeout, e),
+                    None => anyhow!("Transaction {:?} was not mined within {:?}", tx_hash, timeout),
+                });
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
     }
 
     /// Fetch Payment events from contracts
@@ -356,4 +921,237 @@ impl BlockchainManager {
         })
     }
 }
- 
\ No newline at end of file
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{MockProvider, Provider};
+
+    fn block_at(number: u64, timestamp: u64) -> Block<H256> {
+        Block {
+            number: Some(number.into()),
+            timestamp: timestamp.into(),
+            ..Default::default()
+        }
+    }
+
+    // MockProvider pops responses from the back, so push them in reverse call order
+    fn mock_chain(latest: u64, sample: u64, block_time: u64, gas_price: u64) -> Provider<MockProvider> {
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(gas_price)).unwrap();
+        mock.push(block_at(latest - sample, 1_700_000_000)).unwrap();
+        mock.push(block_at(latest, 1_700_000_000 + sample * block_time)).unwrap();
+        mock.push(U64::from(latest)).unwrap();
+        provider
+    }
+
+    #[tokio::test]
+    async fn test_eta_derives_block_time_from_timestamps() {
+        let provider = mock_chain(1_000, 10, 12, 20_000_000_000);
+        let eta = estimate_confirmation_eta(&provider, 10).await.unwrap();
+
+        assert_eq!(eta.latest_block, 1_000);
+        assert_eq!(eta.sampled_blocks, 10);
+        assert!((eta.average_block_time_seconds - 12.0).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_eta_ordering_across_tiers() {
+        let provider = mock_chain(500, 20, 2, 1_000_000_000);
+        let eta = estimate_confirmation_eta(&provider, 20).await.unwrap();
+
+        let names: Vec<&str> = eta.tiers.iter().map(|t| t.tier.as_str()).collect();
+        assert_eq!(names, ["slow", "medium", "fast"]);
+
+        let (slow, medium, fast) = (&eta.tiers[0], &eta.tiers[1], &eta.tiers[2]);
+        assert!(slow.eta_seconds > medium.eta_seconds);
+        assert!(medium.eta_seconds > fast.eta_seconds);
+        assert!(slow.gas_price < medium.gas_price);
+        assert!(medium.gas_price < fast.gas_price);
+        assert_eq!(medium.gas_price, U256::from(1_000_000_000u64));
+    }
+
+    fn empty_mock() -> (Provider<MockProvider>, MockProvider) {
+        Provider::mocked()
+    }
+
+    #[tokio::test]
+    async fn test_mempool_seen_transaction() {
+        let (provider, mock) = empty_mock();
+        let tx_hash = H256::repeat_byte(0x11);
+        mock.push(ethers::types::Transaction { hash: tx_hash, ..Default::default() }).unwrap();
+
+        let status = check_mempool_inclusion(&provider, tx_hash, Bytes::from(vec![0x01]), std::time::Duration::ZERO, true)
+            .await
+            .unwrap();
+
+        assert_eq!(status, MempoolStatus::Seen);
+        mock.assert_request("eth_getTransactionByHash", [tx_hash]).unwrap();
+        assert!(mock.assert_request("eth_sendRawTransaction", ()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mempool_dropped_transaction_is_rebroadcast_once() {
+        let (provider, mock) = empty_mock();
+        let tx_hash = H256::repeat_byte(0x22);
+        let raw_tx = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+        // Responses pop from the back: lookup returns null, then the re-broadcast hash
+        mock.push(tx_hash).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+
+        let status = check_mempool_inclusion(&provider, tx_hash, raw_tx.clone(), std::time::Duration::ZERO, true)
+            .await
+            .unwrap();
+
+        assert_eq!(status, MempoolStatus::Rebroadcast);
+        mock.assert_request("eth_getTransactionByHash", [tx_hash]).unwrap();
+        mock.assert_request("eth_sendRawTransaction", [raw_tx]).unwrap();
+        assert!(mock.assert_request("eth_sendRawTransaction", ()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mempool_dropped_without_rebroadcast() {
+        let (provider, mock) = empty_mock();
+        let tx_hash = H256::repeat_byte(0x33);
+        mock.push(serde_json::Value::Null).unwrap();
+
+        let status = check_mempool_inclusion(&provider, tx_hash, Bytes::from(vec![0x01]), std::time::Duration::ZERO, false)
+            .await
+            .unwrap();
+
+        assert_eq!(status, MempoolStatus::Dropped);
+        mock.assert_request("eth_getTransactionByHash", [tx_hash]).unwrap();
+        assert!(mock.assert_request("eth_sendRawTransaction", ()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_onchain_status_of_confirmed_receipt() {
+        let (provider, mock) = empty_mock();
+        let tx_hash = H256::repeat_byte(0x44);
+        // Responses pop from the back: the receipt, then the latest block number
+        mock.push(U64::from(105u64)).unwrap();
+        mock.push(ethers::types::TransactionReceipt {
+            transaction_hash: tx_hash,
+            block_number: Some(U64::from(100u64)),
+            gas_used: Some(U256::from(21_000u64)),
+            status: Some(U64::from(1u64)),
+            ..Default::default()
+        }).unwrap();
+
+        let status = fetch_onchain_status(&provider, tx_hash).await.unwrap();
+
+        assert_eq!(status.state, OnchainState::Success);
+        assert_eq!(status.block_number, Some(100));
+        assert_eq!(status.confirmations, 6);
+        assert_eq!(status.gas_used, Some(U256::from(21_000u64)));
+        mock.assert_request("eth_getTransactionReceipt", [tx_hash]).unwrap();
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        assert!(mock.assert_request("eth_getTransactionByHash", ()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_onchain_status_of_reverted_receipt() {
+        let (provider, mock) = empty_mock();
+        mock.push(U64::from(100u64)).unwrap();
+        mock.push(ethers::types::TransactionReceipt {
+            block_number: Some(U64::from(100u64)),
+            status: Some(U64::zero()),
+            ..Default::default()
+        }).unwrap();
+
+        let status = fetch_onchain_status(&provider, H256::repeat_byte(0x55)).await.unwrap();
+        assert_eq!(status.state, OnchainState::Reverted);
+        assert_eq!(status.confirmations, 1);
+    }
+
+    #[tokio::test]
+    async fn test_onchain_status_of_pending_receipt() {
+        let (provider, mock) = empty_mock();
+        let tx_hash = H256::repeat_byte(0x66);
+        // A null receipt, then the mempool lookup finds the transaction
+        mock.push(ethers::types::Transaction { hash: tx_hash, ..Default::default() }).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+
+        let status = fetch_onchain_status(&provider, tx_hash).await.unwrap();
+        assert_eq!(status.state, OnchainState::Pending);
+        assert_eq!(status.block_number, None);
+        assert_eq!(status.confirmations, 0);
+        mock.assert_request("eth_getTransactionReceipt", [tx_hash]).unwrap();
+        mock.assert_request("eth_getTransactionByHash", [tx_hash]).unwrap();
+
+        let (provider, mock) = empty_mock();
+        mock.push(serde_json::Value::Null).unwrap();
+        mock.push(serde_json::Value::Null).unwrap();
+        let status = fetch_onchain_status(&provider, tx_hash).await.unwrap();
+        assert_eq!(status.state, OnchainState::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_eta_rejects_stalled_timestamps() {
+        let provider = mock_chain(100, 5, 0, 1_000_000_000);
+        assert!(estimate_confirmation_eta(&provider, 5).await.is_err());
+    }
+
+    fn fee_band() -> FeeBounds {
+        FeeBounds {
+            min_priority_fee_per_gas: Some(1_000_000_000),
+            max_priority_fee_per_gas: Some(5_000_000_000),
+            min_max_fee_per_gas: Some(10_000_000_000),
+            max_max_fee_per_gas: Some(100_000_000_000),
+        }
+    }
+
+    #[test]
+    fn test_fees_below_floor_are_raised() {
+        let (max_fee, priority_fee) = clamp_fees(&fee_band(), U256::from(1_000_000u64), U256::from(1u64));
+        assert_eq!(max_fee, U256::from(10_000_000_000u64));
+        assert_eq!(priority_fee, U256::from(1_000_000_000u64));
+    }
+
+    #[test]
+    fn test_fees_above_ceiling_are_lowered() {
+        let (max_fee, priority_fee) = clamp_fees(&fee_band(), U256::exp10(15), U256::exp10(12));
+        assert_eq!(max_fee, U256::from(100_000_000_000u64));
+        assert_eq!(priority_fee, U256::from(5_000_000_000u64));
+    }
+
+    #[test]
+    fn test_fees_within_band_are_unchanged() {
+        let (max_fee, priority_fee) = clamp_fees(&fee_band(), U256::from(20_000_000_000u64), U256::from(2_000_000_000u64));
+        assert_eq!(max_fee, U256::from(20_000_000_000u64));
+        assert_eq!(priority_fee, U256::from(2_000_000_000u64));
+
+        // Unbounded config passes oracle values through
+        let (max_fee, priority_fee) = clamp_fees(&FeeBounds::default(), U256::exp10(15), U256::exp10(12));
+        assert_eq!((max_fee, priority_fee), (U256::exp10(15), U256::exp10(12)));
+    }
+
+    #[test]
+    fn test_priority_fee_never_exceeds_max_fee() {
+        let bounds = FeeBounds { max_max_fee_per_gas: Some(3_000_000_000), ..fee_band() };
+        let (max_fee, priority_fee) = clamp_fees(&bounds, U256::from(50_000_000_000u64), U256::from(4_000_000_000u64));
+        assert_eq!(max_fee, U256::from(3_000_000_000u64));
+        assert_eq!(priority_fee, max_fee);
+    }
+
+    #[tokio::test]
+    async fn test_meta_transaction_deadline_respects_skew() {
+        let manager = BlockchainManager::new(Config::default()).unwrap();
+        let now = chrono::Utc::now().timestamp() as u64;
+
+        // A second or two past the deadline is tolerated, the full clock skew window is not
+        assert!(manager.ensure_deadline_valid(U256::from(now - 2)).is_ok());
+        assert!(manager.ensure_deadline_valid(U256::from(now - 30)).is_err());
+
+        let err = manager.execute_meta_transaction(
+            1114,
+            Address::zero(),
+            Address::zero(),
+            U256::one(),
+            "ref".to_string(),
+            U256::from(now - 3600),
+            Bytes::default(),
+        ).await.unwrap_err();
+        assert!(err.to_string().contains("expired"));
+    }
+}
@@ -1,2 +1,10 @@
+pub mod calldata;
 pub mod ethereum;
-pub mod manager; 
\ No newline at end of file
+pub mod health;
+pub mod manager;
+pub mod nonce;
+pub mod pooled_http;
+pub mod rebroadcast;
+pub mod revert;
+pub mod sequencer;
+pub mod simulation;
@@ -0,0 +1,184 @@
+use anyhow::{Result, anyhow};
+use ethers::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::collections::hash_map::Entry;
+use tokio::sync::Mutex;
+
+/// Sponsor nonce bookkeeping for one chain
+#[derive(Debug, Default)]
+struct ChainNonces {
+    /// Lowest nonce never handed out
+    next: U256,
+    /// Handed-out nonces whose send failed; reused before `next` so they leave no gap
+    released: BTreeSet<U256>,
+}
+
+impl ChainNonces {
+    /// Nonces below the sponsor's on-chain count are spent, whoever spent them
+    fn reconcile(&mut self, onchain_count: U256) {
+        self.released = self.released.split_off(&onchain_count);
+        self.next = self.next.max(onchain_count);
+    }
+}
+
+/// Hands out the sponsor account's nonces for sponsored sends. Reservations are
+/// serialized, so concurrent sends on a chain never share a nonce.
+pub struct NonceManager {
+    sponsor: Address,
+    chains: Mutex<HashMap<u64, ChainNonces>>,
+}
+
+impl NonceManager {
+    pub fn new(sponsor: Address) -> Self {
+        Self {
+            sponsor,
+            chains: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn sponsor(&self) -> Address {
+        self.sponsor
+    }
+
+    /// The sponsor's transaction count on the chain, including pending transactions
+    async fn onchain_count<M: Middleware>(&self, chain_id: u64, provider: &M) -> Result<U256> {
+        provider.get_transaction_count(self.sponsor, Some(BlockNumber::Pending.into())).await
+            .map_err(|e| anyhow!("Failed to fetch sponsor nonce on chain {}: {}", chain_id, e))
+    }
+
+    /// Reconcile a chain with its on-chain count. Returns the next nonce to be handed out.
+    pub async fn sync<M: Middleware>(&self, chain_id: u64, provider: &M) -> Result<U256> {
+        let onchain_count = self.onchain_count(chain_id, provider).await?;
+        let mut chains = self.chains.lock().await;
+        let nonces = chains.entry(chain_id).or_insert_with(|| ChainNonces { next: onchain_count, ..Default::default() });
+        nonces.reconcile(onchain_count);
+        Ok(nonces.released.first().copied().unwrap_or(nonces.next))
+    }
+
+    /// Reserve a nonce for a sponsored send, syncing the chain first if it has not been yet
+    pub async fn reserve<M: Middleware>(&self, chain_id: u64, provider: &M) -> Result<U256> {
+        let mut chains = self.chains.lock().await;
+        let nonces = match chains.entry(chain_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                // Hold the lock so concurrent first sends don't each start from the same count
+                let onchain_count = self.onchain_count(chain_id, provider).await?;
+                entry.insert(ChainNonces { next: onchain_count, ..Default::default() })
+            }
+        };
+        if let Some(nonce) = nonces.released.pop_first() {
+            return Ok(nonce);
+        }
+        let nonce = nonces.next;
+        nonces.next += U256::one();
+        Ok(nonce)
+    }
+
+    /// Give back the nonce of a send that failed and reconcile the chain. The nonce is
+    /// handed out again unless the chain shows it was spent after all.
+    pub async fn release<M: Middleware>(&self, chain_id: u64, nonce: U256, provider: &M) {
+        let onchain_count = self.onchain_count(chain_id, provider).await;
+        let mut chains = self.chains.lock().await;
+        let Some(nonces) = chains.get_mut(&chain_id) else {
+            return;
+        };
+        if nonce < nonces.next {
+            nonces.released.insert(nonce);
+        }
+        match onchain_count {
+            Ok(onchain_count) => nonces.reconcile(onchain_count),
+            Err(e) => log::warn!("Sponsor nonce on chain {} not reconciled after a failed send: {}", chain_id, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    const SPONSOR: &str = "0x1111111111111111111111111111111111111111";
+
+    fn manager() -> Arc<NonceManager> {
+        Arc::new(NonceManager::new(SPONSOR.parse().unwrap()))
+    }
+
+    /// Reserve a nonce and send a transaction with it, giving the nonce back if the send fails
+    async fn sponsored_broadcast(manager: &NonceManager, provider: &Provider<MockProvider>) -> Result<U256> {
+        let nonce = manager.reserve(1114, provider).await?;
+        let tx = TransactionRequest::new().from(manager.sponsor()).nonce(nonce);
+        let sent: Result<H256, _> = provider.request("eth_sendTransaction", [tx]).await;
+        match sent {
+            Ok(_) => Ok(nonce),
+            Err(e) => {
+                manager.release(1114, nonce, provider).await;
+                Err(anyhow!("Send failed: {}", e))
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_broadcasts_get_sequential_nonces() {
+        let manager = manager();
+        let (provider, mock) = Provider::mocked();
+        for _ in 0..10 {
+            mock.push(H256::random()).unwrap();
+        }
+        mock.push(U256::from(7u64)).unwrap();
+
+        let tasks: Vec<_> = (0..10)
+            .map(|_| {
+                let manager = Arc::clone(&manager);
+                let provider = provider.clone();
+                tokio::spawn(async move { sponsored_broadcast(&manager, &provider).await })
+            })
+            .collect();
+        let mut nonces = Vec::new();
+        for task in tasks {
+            nonces.push(task.await.unwrap().unwrap().as_u64());
+        }
+        nonces.sort_unstable();
+
+        assert_eq!(nonces, (7..17).collect::<Vec<_>>());
+        // The chain was only asked once; every later nonce came from the local counter
+        mock.assert_request("eth_getTransactionCount", (manager.sponsor(), "pending")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_send_nonce_is_reused() {
+        let manager = manager();
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(3u64)).unwrap();
+        manager.sync(1114, &provider).await.unwrap();
+        assert_eq!(manager.reserve(1114, &provider).await.unwrap(), U256::from(3u64));
+
+        // Nonce 4 fails to send while 5 goes through, leaving a gap the chain is stuck behind
+        mock.push(U256::from(4u64)).unwrap();
+        let failed = manager.reserve(1114, &provider).await.unwrap();
+        let sent = manager.reserve(1114, &provider).await.unwrap();
+        assert_eq!((failed, sent), (U256::from(4u64), U256::from(5u64)));
+        manager.release(1114, failed, &provider).await;
+
+        assert_eq!(manager.reserve(1114, &provider).await.unwrap(), U256::from(4u64));
+        assert_eq!(manager.reserve(1114, &provider).await.unwrap(), U256::from(6u64));
+    }
+
+    #[tokio::test]
+    async fn test_release_drops_nonces_spent_on_chain() {
+        let manager = manager();
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(0u64)).unwrap();
+        let nonce = manager.reserve(1114, &provider).await.unwrap();
+        assert_eq!(nonce, U256::zero());
+
+        // The send errored but the sponsor was used elsewhere meanwhile: the chain is at 9
+        mock.push(U256::from(9u64)).unwrap();
+        manager.release(1114, nonce, &provider).await;
+        assert_eq!(manager.reserve(1114, &provider).await.unwrap(), U256::from(9u64));
+
+        // A failing sync leaves the chain uninitialized, so the next reservation retries it
+        let (failing, _mock) = Provider::mocked();
+        assert!(manager.reserve(84532, &failing).await.is_err());
+        assert!(manager.sync(84532, &failing).await.is_err());
+    }
+}
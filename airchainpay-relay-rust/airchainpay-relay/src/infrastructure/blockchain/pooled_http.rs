@@ -0,0 +1,349 @@
+use crate::infrastructure::config::RpcPoolConfig;
+use airchainpay_common::http::parse_retry_after;
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use ethers::providers::{JsonRpcClient, JsonRpcError, ProviderError, RpcError};
+use serde::{Serialize, de::DeserializeOwned};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::Semaphore;
+
+/// Wait before retrying a 429 that came without a usable `Retry-After`
+const DEFAULT_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Connection budget for one RPC host, shared by every chain that uses it
+#[derive(Debug)]
+struct HostSlot {
+    limiter: Semaphore,
+    active: AtomicUsize,
+}
+
+/// Keeps the number of in-flight requests to a host in sync, even if the request future is dropped
+struct ActiveGuard<'a>(&'a AtomicUsize);
+
+impl<'a> ActiveGuard<'a> {
+    fn new(active: &'a AtomicUsize) -> Self {
+        active.fetch_add(1, Ordering::SeqCst);
+        Self(active)
+    }
+}
+
+impl Drop for ActiveGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// One keep-alive `reqwest` client shared by all RPC transports, with a cap on
+/// concurrent connections per host so bursts cannot trip provider limits.
+#[derive(Debug)]
+pub struct RpcConnectionPool {
+    client: reqwest::Client,
+    config: RpcPoolConfig,
+    hosts: Mutex<HashMap<String, Arc<HostSlot>>>,
+}
+
+impl RpcConnectionPool {
+    pub fn new(config: RpcPoolConfig) -> Result<Self> {
+        let mut builder = reqwest::Client::builder()
+            .pool_max_idle_per_host(config.max_idle_per_host)
+            .pool_idle_timeout(Duration::from_secs(config.idle_timeout_secs))
+            .tcp_keepalive(Duration::from_secs(60));
+        if config.request_timeout_secs > 0 {
+            builder = builder.timeout(Duration::from_secs(config.request_timeout_secs));
+        }
+        let client = builder
+            .build()
+            .map_err(|e| anyhow!("Failed to build RPC HTTP client: {}", e))?;
+
+        Ok(Self {
+            client,
+            config,
+            hosts: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn config(&self) -> &RpcPoolConfig {
+        &self.config
+    }
+
+    /// Build a transport for `url` that shares this pool's connections and host caps
+    pub fn transport(self: &Arc<Self>, url: &str) -> Result<PooledHttp> {
+        let url = reqwest::Url::parse(url).map_err(|e| anyhow!("Invalid RPC URL {}: {}", url, e))?;
+        let host = Self::host_key(&url);
+        let slot = {
+            let mut hosts = self.hosts.lock().unwrap();
+            Arc::clone(hosts.entry(host.clone()).or_insert_with(|| {
+                let permits = match self.config.max_connections_per_host {
+                    0 => Semaphore::MAX_PERMITS,
+                    cap => cap,
+                };
+                Arc::new(HostSlot { limiter: Semaphore::new(permits), active: AtomicUsize::new(0) })
+            }))
+        };
+
+        Ok(PooledHttp {
+            client: self.client.clone(),
+            url,
+            host,
+            slot,
+            next_id: Arc::new(AtomicU64::new(1)),
+            rate_limit_retries: self.config.rate_limit_retries,
+            max_retry_after: Duration::from_secs(self.config.max_retry_after_secs),
+        })
+    }
+
+    /// Connections currently in use, keyed by `host:port`
+    pub fn active_connections(&self) -> HashMap<String, usize> {
+        self.hosts.lock().unwrap()
+            .iter()
+            .map(|(host, slot)| (host.clone(), slot.active.load(Ordering::SeqCst)))
+            .collect()
+    }
+
+    fn host_key(url: &reqwest::Url) -> String {
+        format!(
+            "{}:{}",
+            url.host_str().unwrap_or_default(),
+            url.port_or_known_default().unwrap_or_default()
+        )
+    }
+}
+
+/// HTTP JSON-RPC transport for ethers providers backed by an `RpcConnectionPool`
+#[derive(Debug, Clone)]
+pub struct PooledHttp {
+    client: reqwest::Client,
+    url: reqwest::Url,
+    host: String,
+    slot: Arc<HostSlot>,
+    next_id: Arc<AtomicU64>,
+    rate_limit_retries: u32,
+    max_retry_after: Duration,
+}
+
+impl PooledHttp {
+    pub fn url(&self) -> &reqwest::Url {
+        &self.url
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+}
+
+#[derive(Debug)]
+pub enum PooledHttpError {
+    Http(reqwest::Error),
+    /// Still rate limited after the configured retries, or asked to wait too long
+    RateLimited { retry_after: Option<Duration> },
+    JsonRpc(JsonRpcError),
+    SerdeJson { err: serde_json::Error, text: String },
+}
+
+impl fmt::Display for PooledHttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "RPC HTTP error: {}", e),
+            Self::RateLimited { retry_after: Some(wait) } => write!(f, "RPC provider rate limited the request, retry after {:?}", wait),
+            Self::RateLimited { retry_after: None } => write!(f, "RPC provider rate limited the request"),
+            Self::JsonRpc(e) => write!(f, "{}", e),
+            Self::SerdeJson { err, text } => write!(f, "Deserialization error: {}. Response: {}", err, text),
+        }
+    }
+}
+
+impl std::error::Error for PooledHttpError {}
+
+impl RpcError for PooledHttpError {
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            Self::JsonRpc(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            Self::SerdeJson { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PooledHttpError> for ProviderError {
+    fn from(e: PooledHttpError) -> Self {
+        ProviderError::JsonRpcClientError(Box::new(e))
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for PooledHttp {
+    type Error = PooledHttpError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let payload = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id.fetch_add(1, Ordering::SeqCst),
+            "method": method,
+            "params": params,
+        });
+
+        // A 429 is retried no sooner than the provider's Retry-After, without holding a
+        // connection slot while waiting
+        let mut rate_limited = 0;
+        let body = loop {
+            let (status, retry_after, body) = {
+                let _permit = self.slot.limiter.acquire().await.expect("RPC host limiter is never closed");
+                let _active = ActiveGuard::new(&self.slot.active);
+                let response = self.client.post(self.url.clone()).json(&payload).send().await
+                    .map_err(PooledHttpError::Http)?;
+                let retry_after = response.headers().get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| parse_retry_after(value, SystemTime::now()));
+                (response.status(), retry_after, response.bytes().await.map_err(PooledHttpError::Http)?)
+            };
+            if status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                break body;
+            }
+            let wait = retry_after.unwrap_or(DEFAULT_RATE_LIMIT_BACKOFF);
+            if rate_limited >= self.rate_limit_retries || wait > self.max_retry_after {
+                return Err(PooledHttpError::RateLimited { retry_after });
+            }
+            rate_limited += 1;
+            log::warn!("RPC host {} rate limited {}, retrying in {:?}", self.host, method, wait);
+            tokio::time::sleep(wait).await;
+        };
+
+        let text = || String::from_utf8_lossy(&body).to_string();
+        let mut value: serde_json::Value = serde_json::from_slice(&body)
+            .map_err(|err| PooledHttpError::SerdeJson { err, text: text() })?;
+        if let Some(error) = value.get("error") {
+            let error: JsonRpcError = serde_json::from_value(error.clone())
+                .map_err(|err| PooledHttpError::SerdeJson { err, text: text() })?;
+            return Err(PooledHttpError::JsonRpc(error));
+        }
+        let result = value.get_mut("result").map(serde_json::Value::take).unwrap_or_default();
+        serde_json::from_value(result).map_err(|err| PooledHttpError::SerdeJson { err, text: text() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mock_http::{MockResponse, MockServer};
+    use ethers::providers::{Middleware, Provider};
+
+    /// Keep-alive JSON-RPC server answering `eth_blockNumber` after `delay`
+    async fn spawn_keepalive_rpc(delay: Duration) -> MockServer {
+        MockServer::start(move |request| MockResponse::rpc_result(request, serde_json::json!("0x10")).delayed(delay)).await
+    }
+
+    fn pool(max_idle_per_host: usize, max_connections_per_host: usize) -> Arc<RpcConnectionPool> {
+        Arc::new(RpcConnectionPool::new(RpcPoolConfig {
+            max_idle_per_host,
+            idle_timeout_secs: 30,
+            max_connections_per_host,
+            ..Default::default()
+        }).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_repeated_calls_reuse_connection() {
+        let rpc = spawn_keepalive_rpc(Duration::ZERO).await;
+        let pool = pool(4, 4);
+        let provider = Provider::new(pool.transport(&rpc.url).unwrap());
+
+        for _ in 0..5 {
+            assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 16);
+        }
+        assert_eq!(rpc.connections(), 1);
+        assert!(pool.active_connections().values().all(|active| *active == 0));
+    }
+
+    #[tokio::test]
+    async fn test_hanging_request_times_out() {
+        let rpc = spawn_keepalive_rpc(Duration::from_secs(30)).await;
+        let pool = Arc::new(RpcConnectionPool::new(RpcPoolConfig { request_timeout_secs: 1, ..Default::default() }).unwrap());
+        let provider = Provider::new(pool.transport(&rpc.url).unwrap());
+
+        let started = std::time::Instant::now();
+        assert!(provider.get_block_number().await.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_pool_settings_cap_connections_per_host() {
+        let rpc = spawn_keepalive_rpc(Duration::from_millis(50)).await;
+        let pool = pool(1, 1);
+        assert_eq!(pool.config().max_idle_per_host, 1);
+        assert_eq!(pool.config().max_connections_per_host, 1);
+
+        // Two chains on the same host share one connection budget
+        let first = Provider::new(pool.transport(&rpc.url).unwrap());
+        let second = Provider::new(pool.transport(&rpc.url).unwrap());
+        let (a, b) = tokio::join!(first.get_block_number(), second.get_block_number());
+        assert!(a.is_ok() && b.is_ok());
+
+        assert_eq!(rpc.peak_in_flight(), 1);
+        assert_eq!(pool.active_connections().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_json_rpc_errors_surface_as_error_responses() {
+        let rpc = MockServer::start(|request| {
+            MockResponse::rpc_error(request, serde_json::json!({ "code": -32000, "message": "nonce too low" }))
+        }).await;
+
+        let transport = pool(1, 1).transport(&rpc.url).unwrap();
+        let err = transport.request::<_, serde_json::Value>("eth_blockNumber", ()).await.unwrap_err();
+        assert_eq!(err.as_error_response().map(|e| e.message.as_str()), Some("nonce too low"));
+    }
+
+    /// Server answering the first `limited` requests with 429 and `Retry-After: retry_after`,
+    /// then `eth_blockNumber` results
+    async fn spawn_rate_limited_rpc(limited: usize, retry_after: &'static str) -> MockServer {
+        let seen = AtomicUsize::new(0);
+        MockServer::start(move |request| {
+            if seen.fetch_add(1, Ordering::SeqCst) < limited {
+                MockResponse::status(429).header("retry-after", retry_after)
+            } else {
+                MockResponse::rpc_result(request, serde_json::json!("0x10"))
+            }
+        }).await
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_request_waits_for_retry_after() {
+        let rpc = spawn_rate_limited_rpc(1, "1").await;
+        let provider = Provider::new(pool(1, 1).transport(&rpc.url).unwrap());
+
+        assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 16);
+        let requests = rpc.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].received_at - requests[0].received_at >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_beyond_the_cap_fails_without_waiting() {
+        let rpc = spawn_rate_limited_rpc(1, "3600").await;
+        let transport = pool(1, 1).transport(&rpc.url).unwrap();
+
+        let err = transport.request::<_, serde_json::Value>("eth_blockNumber", ()).await.unwrap_err();
+        assert!(matches!(err, PooledHttpError::RateLimited { retry_after: Some(wait) } if wait == Duration::from_secs(3600)));
+        assert_eq!(rpc.requests().len(), 1);
+
+        // Retries stop after `rate_limit_retries`
+        let rpc = spawn_rate_limited_rpc(10, "0").await;
+        let transport = pool(1, 1).transport(&rpc.url).unwrap();
+        assert!(transport.request::<_, serde_json::Value>("eth_blockNumber", ()).await.is_err());
+        assert_eq!(rpc.requests().len(), 1 + RpcPoolConfig::default().rate_limit_retries as usize);
+    }
+}
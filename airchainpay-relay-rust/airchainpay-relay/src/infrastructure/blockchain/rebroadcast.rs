@@ -0,0 +1,157 @@
+use crate::infrastructure::config::RebroadcastConfig;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::H256;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Send history is forgotten once a transaction has not been broadcast for this long
+const RETENTION_HOURS: i64 = 24;
+
+/// How often a signed transaction has been sent to the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BroadcastCounts {
+    /// Successful sends, including the first broadcast
+    pub broadcasts: u32,
+    pub last_broadcast_at: DateTime<Utc>,
+}
+
+impl BroadcastCounts {
+    pub fn rebroadcasts(&self) -> u32 {
+        self.broadcasts.saturating_sub(1)
+    }
+}
+
+/// Limits how often the same signed transaction is sent again. Every path that
+/// (re)broadcasts goes through `BlockchainManager`, which consults this throttle,
+/// so retries, mempool re-broadcasts and resubmissions share one budget.
+pub struct RebroadcastThrottle {
+    config: RebroadcastConfig,
+    sent: Mutex<HashMap<H256, BroadcastCounts>>,
+}
+
+impl RebroadcastThrottle {
+    pub fn new(config: RebroadcastConfig) -> Self {
+        Self {
+            config,
+            sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &RebroadcastConfig {
+        &self.config
+    }
+
+    /// Whether `tx_hash` may be sent at `now`. The first broadcast is always allowed.
+    pub fn check_at(&self, tx_hash: H256, now: DateTime<Utc>) -> Result<()> {
+        let sent = self.sent.lock().unwrap();
+        let Some(counts) = sent.get(&tx_hash) else {
+            return Ok(());
+        };
+
+        if counts.rebroadcasts() >= self.config.max_rebroadcasts {
+            return Err(anyhow!(
+                "Re-broadcast of {:?} halted: already re-broadcast {} time(s) (max {})",
+                tx_hash, counts.rebroadcasts(), self.config.max_rebroadcasts
+            ));
+        }
+        let elapsed = now - counts.last_broadcast_at;
+        let min_interval = Duration::seconds(self.config.min_interval_secs as i64);
+        if elapsed < min_interval {
+            return Err(anyhow!(
+                "Re-broadcast of {:?} suppressed: last sent {}s ago (minimum interval {}s)",
+                tx_hash, elapsed.num_seconds(), self.config.min_interval_secs
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether a transaction missing from the mempool may be sent again at `now`. The
+    /// first such re-broadcast is exempt from the minimum interval: the mempool check
+    /// runs sooner than the interval allows, and without it a dropped transaction would
+    /// never be resent. Later ones are throttled like any other re-broadcast.
+    pub fn check_mempool_rebroadcast_at(&self, tx_hash: H256, now: DateTime<Utc>) -> Result<()> {
+        let first_rebroadcast = self.counts(tx_hash).is_some_and(|counts| counts.rebroadcasts() == 0);
+        if first_rebroadcast && self.config.max_rebroadcasts > 0 {
+            return Ok(());
+        }
+        self.check_at(tx_hash, now)
+    }
+
+    /// Record a successful send of `tx_hash` at `now`
+    pub fn record_at(&self, tx_hash: H256, now: DateTime<Utc>) -> BroadcastCounts {
+        let mut sent = self.sent.lock().unwrap();
+        sent.retain(|_, counts| now - counts.last_broadcast_at < Duration::hours(RETENTION_HOURS));
+        let counts = sent.entry(tx_hash).or_insert(BroadcastCounts { broadcasts: 0, last_broadcast_at: now });
+        counts.broadcasts += 1;
+        counts.last_broadcast_at = now;
+        *counts
+    }
+
+    pub fn counts(&self, tx_hash: H256) -> Option<BroadcastCounts> {
+        self.sent.lock().unwrap().get(&tx_hash).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle(min_interval_secs: u64, max_rebroadcasts: u32) -> RebroadcastThrottle {
+        RebroadcastThrottle::new(RebroadcastConfig { min_interval_secs, max_rebroadcasts })
+    }
+
+    #[test]
+    fn test_rebroadcast_within_interval_is_suppressed() {
+        let throttle = throttle(30, 3);
+        let tx_hash = H256::repeat_byte(0x42);
+        let start = Utc::now();
+
+        assert!(throttle.check_at(tx_hash, start).is_ok());
+        throttle.record_at(tx_hash, start);
+
+        let err = throttle.check_at(tx_hash, start + Duration::seconds(10)).unwrap_err();
+        assert!(err.to_string().contains("suppressed"));
+        assert!(throttle.check_at(tx_hash, start + Duration::seconds(30)).is_ok());
+
+        // Other transactions are unaffected
+        assert!(throttle.check_at(H256::repeat_byte(0x43), start).is_ok());
+    }
+
+    #[test]
+    fn test_first_mempool_rebroadcast_allowed_with_default_config() {
+        let throttle = RebroadcastThrottle::new(RebroadcastConfig::default());
+        let mempool_check_delay = crate::app::transaction_service::TransactionProcessorConfig::default().mempool_check_delay;
+        let tx_hash = H256::repeat_byte(0x42);
+        let sent_at = Utc::now();
+        throttle.record_at(tx_hash, sent_at);
+
+        let check_at = sent_at + Duration::from_std(mempool_check_delay).unwrap();
+        assert!(throttle.check_at(tx_hash, check_at).is_err());
+        assert!(throttle.check_mempool_rebroadcast_at(tx_hash, check_at).is_ok());
+
+        // Only the first one is exempt
+        throttle.record_at(tx_hash, check_at);
+        assert!(throttle.check_mempool_rebroadcast_at(tx_hash, check_at + Duration::seconds(1)).is_err());
+    }
+
+    #[test]
+    fn test_max_rebroadcasts_halts_further_attempts() {
+        let throttle = throttle(5, 2);
+        let tx_hash = H256::repeat_byte(0x42);
+        let mut now = Utc::now();
+
+        for _ in 0..3 {
+            throttle.check_at(tx_hash, now).unwrap();
+            throttle.record_at(tx_hash, now);
+            now += Duration::seconds(60);
+        }
+
+        let counts = throttle.counts(tx_hash).unwrap();
+        assert_eq!(counts.broadcasts, 3);
+        assert_eq!(counts.rebroadcasts(), 2);
+        let err = throttle.check_at(tx_hash, now + Duration::hours(1)).unwrap_err();
+        assert!(err.to_string().contains("halted"));
+    }
+}
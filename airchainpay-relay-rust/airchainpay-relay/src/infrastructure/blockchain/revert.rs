@@ -0,0 +1,119 @@
+use crate::domain::error::BlockchainError;
+use ethers::abi::{ParamType, Token};
+use ethers::providers::{ProviderError, RpcError};
+
+/// Selector of Solidity's `Error(string)`, which `require` and `revert("...")` encode with
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// The message of `Error(string)` revert data, or None for other payloads (custom errors, panics)
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    let payload = data.strip_prefix(&ERROR_STRING_SELECTOR)?;
+    match ethers::abi::decode(&[ParamType::String], payload).ok()?.pop()? {
+        Token::String(reason) => Some(reason),
+        _ => None,
+    }
+}
+
+/// Revert data from a node error's `data`, which nodes send either as a hex string or
+/// nested as `{ "data": "0x..." }`
+fn revert_data(data: &serde_json::Value) -> Option<String> {
+    match data {
+        serde_json::Value::String(hex) if hex.starts_with("0x") => Some(hex.clone()),
+        serde_json::Value::Object(object) => object.get("data").and_then(revert_data),
+        _ => None,
+    }
+}
+
+/// The node's error response as a `BlockchainError::Reverted` when it reports a revert,
+/// decoding the reason from the revert data and falling back to the node's message
+pub fn decode_node_revert(error: &ProviderError) -> Option<BlockchainError> {
+    let response = error.as_error_response()?;
+    let data = response.data.as_ref().and_then(revert_data);
+    let decoded = data.as_deref()
+        .and_then(|hex| hex::decode(hex.trim_start_matches("0x")).ok())
+        .and_then(|bytes| decode_revert_reason(&bytes));
+    let message = response.message.as_str();
+    if data.is_none() && !message.to_lowercase().contains("revert") {
+        return None;
+    }
+    let reason = decoded.unwrap_or_else(|| {
+        message.split_once("execution reverted: ")
+            .map(|(_, reason)| reason.to_string())
+            .unwrap_or_else(|| message.to_string())
+    });
+    Some(BlockchainError::Reverted { reason, data })
+}
+
+/// Convert a node error into an `anyhow::Error`, keeping reverts as a downcastable `BlockchainError`
+pub fn node_error(error: ProviderError) -> anyhow::Error {
+    match decode_node_revert(&error) {
+        Some(reverted) => anyhow::Error::new(reverted),
+        None => anyhow::anyhow!(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{JsonRpcError, Middleware, MockResponse, Provider};
+    use ethers::types::Bytes;
+
+    /// `Error(string)` revert data for `reason`
+    fn error_string(reason: &str) -> String {
+        let mut data = ERROR_STRING_SELECTOR.to_vec();
+        data.extend(ethers::abi::encode(&[Token::String(reason.to_string())]));
+        format!("0x{}", hex::encode(data))
+    }
+
+    #[tokio::test]
+    async fn test_node_revert_with_error_string_is_decoded() {
+        let (provider, mock) = Provider::mocked();
+        let data = error_string("Insufficient allowance");
+        mock.push_response(MockResponse::Error(JsonRpcError {
+            code: 3,
+            message: "execution reverted".to_string(),
+            data: Some(serde_json::Value::String(data.clone())),
+        }));
+
+        let error = provider.send_raw_transaction(Bytes::from(vec![0x01])).await.unwrap_err();
+        let error = node_error(error);
+        match error.downcast_ref::<BlockchainError>() {
+            Some(BlockchainError::Reverted { reason, data: raw }) => {
+                assert_eq!(reason, "Insufficient allowance");
+                assert_eq!(raw.as_deref(), Some(data.as_str()));
+            }
+            other => panic!("expected a decoded revert, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_revert_without_data_uses_node_message() {
+        let error = ProviderError::JsonRpcClientError(Box::new(ethers::providers::HttpClientError::JsonRpcError(JsonRpcError {
+            code: -32000,
+            message: "execution reverted: paused".to_string(),
+            data: None,
+        })));
+        match decode_node_revert(&error) {
+            Some(BlockchainError::Reverted { reason, data }) => {
+                assert_eq!(reason, "paused");
+                assert_eq!(data, None);
+            }
+            other => panic!("expected a revert, got {:?}", other),
+        }
+
+        let error = ProviderError::JsonRpcClientError(Box::new(ethers::providers::HttpClientError::JsonRpcError(JsonRpcError {
+            code: -32000,
+            message: "nonce too low".to_string(),
+            data: None,
+        })));
+        assert!(decode_node_revert(&error).is_none());
+    }
+
+    #[test]
+    fn test_other_payloads_have_no_reason() {
+        // Panic(uint256) and custom errors are not Error(string)
+        assert_eq!(decode_revert_reason(&hex::decode("4e487b710000000000000000000000000000000000000000000000000000000000000011").unwrap()), None);
+        assert_eq!(decode_revert_reason(&[]), None);
+        assert_eq!(decode_revert_reason(&hex::decode(error_string("ok").trim_start_matches("0x")).unwrap()).as_deref(), Some("ok"));
+    }
+}
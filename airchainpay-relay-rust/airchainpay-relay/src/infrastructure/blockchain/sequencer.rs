@@ -0,0 +1,267 @@
+use crate::infrastructure::config::NonceSequencingConfig;
+use crate::validators::registered_sender::recover_sender;
+use anyhow::{Result, anyhow};
+use ethers::core::utils::rlp::Rlp;
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Sender and nonce of a raw signed transaction, legacy (with or without Celo's fee fields) or typed
+pub fn decode_sender_nonce(signed_tx: &str) -> Result<(Address, U256)> {
+    let sender = recover_sender(signed_tx)?;
+    let tx_bytes = hex::decode(signed_tx.trim_start_matches("0x"))
+        .map_err(|e| anyhow!("Failed to decode hex: {}", e))?;
+    let rlp = Rlp::new(&tx_bytes);
+    let nonce = if rlp.is_list() {
+        rlp.val_at(0).map_err(|e| anyhow!("Failed to decode transaction nonce: {}", e))?
+    } else {
+        let (tx, _) = TypedTransaction::decode_signed(&rlp)
+            .map_err(|e| anyhow!("Failed to decode transaction: {}", e))?;
+        tx.nonce().copied().unwrap_or_default()
+    };
+    Ok((sender, nonce))
+}
+
+/// Nonce bookkeeping for one sender on one chain
+#[derive(Debug)]
+struct SenderSequence {
+    /// Nonce the node expects next from this sender
+    next: U256,
+    /// Nonces waiting for a predecessor to be broadcast, with when each was first held
+    held: HashMap<U256, Instant>,
+    last_used: Instant,
+}
+
+impl SenderSequence {
+    fn new(next: U256) -> Self {
+        Self { next, held: HashMap::new(), last_used: Instant::now() }
+    }
+
+    /// Forget held nonces the sequence has moved past
+    fn release(&mut self) {
+        let next = self.next;
+        self.held.retain(|nonce, _| *nonce > next);
+    }
+}
+
+/// Whether a transaction may be broadcast now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    /// Its nonce is the next one expected, or already used
+    Ready,
+    /// A predecessor has not been broadcast yet; `expected` is the sender's next nonce
+    Held { expected: U256 },
+}
+
+/// How long a sender with nothing held is remembered after its last transaction. A
+/// returning sender's nonce is read from the chain again.
+const IDLE_SENDER_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Tracks each sender's next nonce so transactions that arrive out of order are still
+/// broadcast in nonce order. A transaction that is ahead is reported as held rather than
+/// waited for, so the caller can put it back in the queue and try again later.
+pub struct NonceSequencer {
+    hold_timeout: Duration,
+    idle_ttl: Duration,
+    senders: Mutex<HashMap<(u64, Address), SenderSequence>>,
+    /// Idle senders are dropped at most once per `idle_ttl`
+    last_eviction: Mutex<Instant>,
+}
+
+impl NonceSequencer {
+    pub fn new(config: &NonceSequencingConfig) -> Self {
+        Self {
+            hold_timeout: Duration::from_secs(config.hold_timeout_secs),
+            idle_ttl: IDLE_SENDER_TTL,
+            senders: Mutex::new(HashMap::new()),
+            last_eviction: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Forget senders that have nothing held and haven't been seen for `idle_ttl`
+    fn evict_idle(&self) {
+        {
+            let mut last_eviction = self.last_eviction.lock().unwrap();
+            if last_eviction.elapsed() < self.idle_ttl {
+                return;
+            }
+            *last_eviction = Instant::now();
+        }
+        let idle_ttl = self.idle_ttl;
+        self.senders.lock().unwrap()
+            .retain(|_, sequence| !sequence.held.is_empty() || sequence.last_used.elapsed() < idle_ttl);
+    }
+
+    /// The sender's pending transaction count on the chain
+    async fn onchain_count<M: Middleware>(chain_id: u64, sender: Address, provider: &M) -> Result<U256> {
+        provider.get_transaction_count(sender, Some(BlockNumber::Pending.into())).await
+            .map_err(|e| anyhow!("Failed to fetch nonce of {:?} on chain {}: {}", sender, chain_id, e))
+    }
+
+    /// Whether `nonce` is the next nonce expected from the sender. Nonces at or below it
+    /// (re-broadcasts, replacements) are ready straight away. Once the nonce has been held
+    /// for the hold timeout the sender's nonce is re-read from the chain, since the gap may
+    /// have been filled outside the relay, and it errors if the nonce is still ahead. If the
+    /// sender's nonce can't be read from the chain the transaction is let through
+    /// unsequenced and the node decides.
+    pub async fn check_turn<M: Middleware>(&self, chain_id: u64, sender: Address, nonce: U256, provider: &M) -> Result<Turn> {
+        let key = (chain_id, sender);
+        self.evict_idle();
+        if !self.senders.lock().unwrap().contains_key(&key) {
+            match Self::onchain_count(chain_id, sender, provider).await {
+                Ok(next) => {
+                    self.senders.lock().unwrap().entry(key).or_insert_with(|| SenderSequence::new(next));
+                }
+                Err(e) => {
+                    log::warn!("{}; sending nonce {} unsequenced", e, nonce);
+                    return Ok(Turn::Ready);
+                }
+            }
+        }
+
+        {
+            let mut senders = self.senders.lock().unwrap();
+            let sequence = senders.entry(key).or_insert_with(|| SenderSequence::new(nonce));
+            sequence.last_used = Instant::now();
+            if nonce <= sequence.next {
+                sequence.held.remove(&nonce);
+                return Ok(Turn::Ready);
+            }
+            let held_since = *sequence.held.entry(nonce).or_insert_with(Instant::now);
+            if held_since.elapsed() < self.hold_timeout {
+                return Ok(Turn::Held { expected: sequence.next });
+            }
+        }
+
+        self.resync(chain_id, sender, provider).await;
+        let mut senders = self.senders.lock().unwrap();
+        let sequence = senders.entry(key).or_insert_with(|| SenderSequence::new(nonce));
+        sequence.held.remove(&nonce);
+        if nonce <= sequence.next {
+            return Ok(Turn::Ready);
+        }
+        Err(anyhow!(
+            "Nonce {} of {:?} held for {:?} waiting for nonce {} on chain {}",
+            nonce, sender, self.hold_timeout, sequence.next, chain_id
+        ))
+    }
+
+    /// Record a broadcast nonce, making the sender's next transaction ready
+    pub fn advance(&self, chain_id: u64, sender: Address, nonce: U256) {
+        if let Some(sequence) = self.senders.lock().unwrap().get_mut(&(chain_id, sender)) {
+            sequence.next = sequence.next.max(nonce + U256::one());
+            sequence.release();
+        }
+    }
+
+    /// Re-read the sender's nonce from the chain after a failed broadcast, so held
+    /// transactions become ready if the chain has moved past the one that failed
+    pub async fn resync<M: Middleware>(&self, chain_id: u64, sender: Address, provider: &M) {
+        match Self::onchain_count(chain_id, sender, provider).await {
+            Ok(count) => {
+                if let Some(sequence) = self.senders.lock().unwrap().get_mut(&(chain_id, sender)) {
+                    sequence.next = count;
+                    sequence.release();
+                }
+            }
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+
+    /// Transactions currently held awaiting a predecessor, across all senders
+    pub fn held_count(&self) -> usize {
+        self.senders.lock().unwrap().values().map(|sequence| sequence.held.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENDER: &str = "0x1111111111111111111111111111111111111111";
+
+    fn sequencer(hold_timeout_secs: u64) -> NonceSequencer {
+        NonceSequencer::new(&NonceSequencingConfig { hold_timeout_secs })
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_nonce_is_held_until_its_predecessor_is_sent() {
+        let sequencer = sequencer(10);
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(1u64)).unwrap();
+        let sender: Address = SENDER.parse().unwrap();
+
+        // Nonce 2 arrives first and is held: the chain expects 1
+        let turn = sequencer.check_turn(1114, sender, 2u64.into(), &provider).await.unwrap();
+        assert_eq!(turn, Turn::Held { expected: 1u64.into() });
+        assert_eq!(sequencer.held_count(), 1);
+
+        // Nonce 1 goes out immediately, after which nonce 2 is ready
+        assert_eq!(sequencer.check_turn(1114, sender, 1u64.into(), &provider).await.unwrap(), Turn::Ready);
+        sequencer.advance(1114, sender, 1u64.into());
+        assert_eq!(sequencer.held_count(), 0);
+        assert_eq!(sequencer.check_turn(1114, sender, 2u64.into(), &provider).await.unwrap(), Turn::Ready);
+
+        // The chain was only asked once; later nonces came from the local sequence
+        mock.assert_request("eth_getTransactionCount", (sender, "pending")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_gap_that_never_fills_times_out() {
+        let sequencer = sequencer(0);
+        let (provider, mock) = Provider::mocked();
+        // First read, then the re-read before giving up
+        mock.push(U256::from(1u64)).unwrap();
+        mock.push(U256::from(1u64)).unwrap();
+        let sender: Address = SENDER.parse().unwrap();
+
+        let err = sequencer.check_turn(1114, sender, 3u64.into(), &provider).await.unwrap_err();
+        assert!(err.to_string().contains("waiting for nonce 1"), "{}", err);
+        assert_eq!(sequencer.held_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_gap_filled_outside_the_relay_is_picked_up_at_the_hold_timeout() {
+        let sequencer = sequencer(0);
+        let (provider, mock) = Provider::mocked();
+        // The sender used nonce 1 elsewhere by the time the hold runs out
+        mock.push(U256::from(2u64)).unwrap();
+        mock.push(U256::from(1u64)).unwrap();
+        let sender: Address = SENDER.parse().unwrap();
+
+        assert_eq!(sequencer.check_turn(1114, sender, 2u64.into(), &provider).await.unwrap(), Turn::Ready);
+        assert_eq!(sequencer.held_count(), 0);
+        assert_eq!(sequencer.check_turn(1114, sender, 3u64.into(), &provider).await.unwrap_err().to_string(),
+            format!("Nonce 3 of {:?} held for 0ns waiting for nonce 2 on chain 1114", sender));
+    }
+
+    #[tokio::test]
+    async fn test_idle_senders_are_forgotten() {
+        let mut sequencer = sequencer(10);
+        sequencer.idle_ttl = Duration::ZERO;
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(5u64)).unwrap();
+        mock.push(U256::from(1u64)).unwrap();
+        let sender: Address = SENDER.parse().unwrap();
+        let other: Address = "0x2222222222222222222222222222222222222222".parse().unwrap();
+
+        // A sender with a held nonce is kept; one with nothing held is dropped
+        assert_eq!(sequencer.check_turn(1114, sender, 2u64.into(), &provider).await.unwrap(), Turn::Held { expected: 1u64.into() });
+        assert_eq!(sequencer.check_turn(1114, other, 5u64.into(), &provider).await.unwrap(), Turn::Ready);
+        sequencer.evict_idle();
+        let senders = sequencer.senders.lock().unwrap();
+        assert!(senders.contains_key(&(1114, sender)));
+        assert!(!senders.contains_key(&(1114, other)));
+    }
+
+    #[test]
+    fn test_decode_sender_nonce() {
+        let signed = crate::app::bundle::tests::signed_tx(crate::app::bundle::tests::SENDER_KEY, 1114, 7);
+        let (sender, nonce) = decode_sender_nonce(&signed).unwrap();
+        assert_eq!(sender, recover_sender(&signed).unwrap());
+        assert_eq!(nonce, U256::from(7u64));
+        assert!(decode_sender_nonce("0xdeadbeef").is_err());
+    }
+}
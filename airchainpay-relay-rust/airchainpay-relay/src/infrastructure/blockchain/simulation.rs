@@ -0,0 +1,319 @@
+use crate::infrastructure::config::SimulationConfig;
+use crate::validators::registered_sender::recover_sender;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
+use ethers::core::utils::rlp::{DecoderError, Rlp};
+use ethers::providers::{Middleware, MiddlewareError};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, BlockId, BlockNumber, Bytes, TransactionRequest, U256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// The parts of a transaction that decide the outcome of an `eth_call`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimulatedCall {
+    pub from: Address,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+fn decode_error(e: DecoderError) -> anyhow::Error {
+    anyhow!("Failed to decode transaction: {}", e)
+}
+
+impl SimulatedCall {
+    /// The call made by a signed raw transaction, legacy (with or without Celo's fee fields) or typed
+    pub fn from_signed_tx(signed_tx: &str) -> Result<Self> {
+        let from = recover_sender(signed_tx)?;
+        let tx_bytes = hex::decode(signed_tx.trim_start_matches("0x"))
+            .map_err(|e| anyhow!("Failed to decode hex: {}", e))?;
+        let rlp = Rlp::new(&tx_bytes);
+        if rlp.is_list() {
+            // [nonce, gasPrice, gasLimit, to, value, data, v, r, s], or with feeCurrency,
+            // gatewayFeeRecipient and gatewayFee after gasLimit
+            let to_index = if rlp.item_count().map_err(decode_error)? == 12 { 6 } else { 3 };
+            let to = rlp.at(to_index).map_err(decode_error)?;
+            return Ok(Self {
+                from,
+                to: if to.is_empty() { None } else { Some(to.as_val().map_err(decode_error)?) },
+                value: rlp.val_at(to_index + 1).map_err(decode_error)?,
+                data: rlp.val_at::<Vec<u8>>(to_index + 2).map_err(decode_error)?.into(),
+            });
+        }
+        let (tx, _) = TypedTransaction::decode_signed(&rlp)
+            .map_err(|e| anyhow!("Failed to decode transaction: {}", e))?;
+        Ok(Self {
+            from,
+            to: tx.to().and_then(|to| to.as_address().copied()),
+            value: tx.value().copied().unwrap_or_default(),
+            data: tx.data().cloned().unwrap_or_default(),
+        })
+    }
+
+    fn request(&self) -> TypedTransaction {
+        let mut request = TransactionRequest::new().from(self.from).value(self.value).data(self.data.clone());
+        if let Some(to) = self.to {
+            request = request.to(to);
+        }
+        request.into()
+    }
+}
+
+/// Outcome of a simulation; the error is the node's revert message
+type SimulationResult = std::result::Result<Bytes, String>;
+
+struct CachedSimulation {
+    result: SimulationResult,
+    simulated_at: DateTime<Utc>,
+}
+
+/// Recent `eth_call` results keyed by chain, block and call. Entries expire after the TTL
+/// and are dropped as soon as a newer block is seen on their chain, since its state may differ.
+pub struct SimulationCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<(u64, u64, SimulatedCall), CachedSimulation>>,
+    latest_blocks: Mutex<HashMap<u64, u64>>,
+}
+
+impl SimulationCache {
+    pub fn new(config: &SimulationConfig) -> Self {
+        Self {
+            ttl: Duration::seconds(config.cache_ttl_secs as i64),
+            entries: Mutex::new(HashMap::new()),
+            latest_blocks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record the chain's latest block, invalidating results simulated on older blocks
+    pub fn observe_block(&self, chain_id: u64, block: u64) {
+        let mut latest_blocks = self.latest_blocks.lock().unwrap();
+        let latest = latest_blocks.entry(chain_id).or_insert(block);
+        if block > *latest {
+            *latest = block;
+            self.entries.lock().unwrap().retain(|(chain, simulated_block, _), _| *chain != chain_id || *simulated_block >= block);
+        }
+    }
+
+    fn get_at(&self, chain_id: u64, block: u64, call: &SimulatedCall, now: DateTime<Utc>) -> Option<SimulationResult> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(&(chain_id, block, call.clone()))
+            .filter(|cached| now - cached.simulated_at < self.ttl)
+            .map(|cached| cached.result.clone())
+    }
+
+    fn insert_at(&self, chain_id: u64, block: u64, call: SimulatedCall, result: SimulationResult, now: DateTime<Utc>) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, cached| now - cached.simulated_at < self.ttl);
+        entries.insert((chain_id, block, call), CachedSimulation { result, simulated_at: now });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Whether a JSON-RPC error is the call reverting, as opposed to the node failing to run it
+/// (rate limits, unsupported methods, internal errors)
+fn is_revert(error: &ethers::providers::JsonRpcError) -> bool {
+    (error.code == 3 && error.data.is_some()) || error.message.to_ascii_lowercase().contains("execution reverted")
+}
+
+/// Dry-run `call` on the latest block, reusing the result of an identical call on the same
+/// block within the cache TTL. Reverts are errors and are cached like successes; any other
+/// failure is not cached.
+pub async fn simulate_call<M: Middleware>(provider: &M, cache: &SimulationCache, chain_id: u64, call: &SimulatedCall) -> Result<Bytes> {
+    let block = provider.get_block_number().await
+        .map_err(|e| anyhow!("Failed to read latest block: {}", e))?
+        .as_u64();
+    cache.observe_block(chain_id, block);
+
+    let now = Utc::now();
+    let result = match cache.get_at(chain_id, block, call, now) {
+        Some(result) => result,
+        None => {
+            let at = BlockId::Number(BlockNumber::Number(block.into()));
+            let result = match provider.call(&call.request(), Some(at)).await {
+                Ok(output) => Ok(output),
+                Err(e) => match e.as_error_response() {
+                    Some(response) if is_revert(response) => Err(response.message.clone()),
+                    _ => return Err(anyhow!("Simulation failed: {}", e)),
+                },
+            };
+            cache.insert_at(chain_id, block, call.clone(), result.clone(), now);
+            result
+        }
+    };
+    result.map_err(|message| anyhow!("Simulation reverted: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{MockProvider, MockResponse, Provider};
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::{Eip1559TransactionRequest, U64};
+
+    fn cache(ttl_secs: u64) -> SimulationCache {
+        SimulationCache::new(&SimulationConfig { simulate_before_send: true, cache_ttl_secs: ttl_secs })
+    }
+
+    fn call() -> SimulatedCall {
+        SimulatedCall {
+            from: Address::repeat_byte(0x11),
+            to: Some(Address::repeat_byte(0x22)),
+            value: U256::from(1_000u64),
+            data: Bytes::from(vec![0xa9, 0x05, 0x9c, 0xbb]),
+        }
+    }
+
+    fn assert_block_number_request(mock: &MockProvider) {
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+    }
+
+    fn assert_call_request(mock: &MockProvider, call: &SimulatedCall, block: u64) {
+        let at = BlockId::Number(BlockNumber::Number(block.into()));
+        mock.assert_request("eth_call", (call.request(), at)).unwrap();
+    }
+
+    fn assert_no_more_requests(mock: &MockProvider) {
+        assert!(matches!(mock.assert_request("eth_call", ()), Err(ethers::providers::MockError::EmptyRequests)));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_simulation_within_ttl_makes_one_call() {
+        let (provider, mock) = Provider::mocked();
+        let cache = cache(60);
+        // Responses pop from the back
+        mock.push(U64::from(100)).unwrap();
+        mock.push::<Bytes, _>(Bytes::from(vec![0x01])).unwrap();
+        mock.push(U64::from(100)).unwrap();
+
+        assert_eq!(simulate_call(&provider, &cache, 1114, &call()).await.unwrap(), Bytes::from(vec![0x01]));
+        assert_eq!(simulate_call(&provider, &cache, 1114, &call()).await.unwrap(), Bytes::from(vec![0x01]));
+        assert_block_number_request(&mock);
+        assert_call_request(&mock, &call(), 100);
+        assert_block_number_request(&mock);
+        assert_no_more_requests(&mock);
+    }
+
+    #[tokio::test]
+    async fn test_new_block_reruns_simulation() {
+        let (provider, mock) = Provider::mocked();
+        let cache = cache(60);
+        mock.push::<Bytes, _>(Bytes::from(vec![0x02])).unwrap();
+        mock.push(U64::from(101)).unwrap();
+        mock.push::<Bytes, _>(Bytes::from(vec![0x01])).unwrap();
+        mock.push(U64::from(100)).unwrap();
+
+        assert_eq!(simulate_call(&provider, &cache, 1114, &call()).await.unwrap(), Bytes::from(vec![0x01]));
+        assert_eq!(simulate_call(&provider, &cache, 1114, &call()).await.unwrap(), Bytes::from(vec![0x02]));
+        assert_block_number_request(&mock);
+        assert_call_request(&mock, &call(), 100);
+        assert_block_number_request(&mock);
+        assert_call_request(&mock, &call(), 101);
+        assert_no_more_requests(&mock);
+        // The result from block 100 was invalidated
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_revert_is_cached_but_transport_errors_are_not() {
+        let (provider, mock) = Provider::mocked();
+        let cache = cache(60);
+        mock.push(U64::from(100)).unwrap();
+        mock.push_response(MockResponse::Error(ethers::providers::JsonRpcError {
+            code: 3,
+            message: "execution reverted: insufficient balance".to_string(),
+            data: None,
+        }));
+        mock.push(U64::from(100)).unwrap();
+
+        for _ in 0..2 {
+            let err = simulate_call(&provider, &cache, 1114, &call()).await.unwrap_err();
+            assert!(err.to_string().contains("insufficient balance"), "{}", err);
+        }
+        assert_block_number_request(&mock);
+        assert_call_request(&mock, &call(), 100);
+        assert_block_number_request(&mock);
+        assert_no_more_requests(&mock);
+
+        // No response at all is a transport failure
+        let other = SimulatedCall { value: U256::zero(), ..call() };
+        mock.push(U64::from(100)).unwrap();
+        assert!(simulate_call(&provider, &cache, 1114, &other).await.is_err());
+        assert!(cache.get_at(1114, 100, &other, Utc::now()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_node_errors_other_than_reverts_are_not_cached() {
+        let (provider, mock) = Provider::mocked();
+        let cache = cache(60);
+        let errors = [
+            (-32005, "rate limit exceeded"),
+            (-32601, "the method eth_call does not exist/is not available"),
+            (-32000, "header not found"),
+        ];
+        for (code, message) in errors {
+            mock.push_response(MockResponse::Error(ethers::providers::JsonRpcError { code, message: message.to_string(), data: None }));
+            mock.push(U64::from(100)).unwrap();
+
+            let err = simulate_call(&provider, &cache, 1114, &call()).await.unwrap_err();
+            assert!(err.to_string().starts_with("Simulation failed"), "{}", err);
+            assert!(cache.is_empty(), "{} was cached", message);
+        }
+
+        // A revert with data but no "execution reverted" message is still a revert
+        mock.push_response(MockResponse::Error(ethers::providers::JsonRpcError {
+            code: 3,
+            message: "VM Exception".to_string(),
+            data: Some(serde_json::json!("0x08c379a0")),
+        }));
+        mock.push(U64::from(100)).unwrap();
+        assert!(simulate_call(&provider, &cache, 1114, &call()).await.unwrap_err().to_string().starts_with("Simulation reverted"));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let cache = cache(15);
+        let now = Utc::now();
+        cache.insert_at(1114, 100, call(), Ok(Bytes::default()), now);
+
+        assert!(cache.get_at(1114, 100, &call(), now + Duration::seconds(14)).is_some());
+        assert!(cache.get_at(1114, 100, &call(), now + Duration::seconds(15)).is_none());
+        assert!(cache.get_at(84532, 100, &call(), now).is_none());
+        // A new block on another chain leaves this one alone
+        cache.observe_block(1114, 100);
+        cache.observe_block(84532, 500);
+        assert!(cache.get_at(1114, 100, &call(), now).is_some());
+    }
+
+    #[test]
+    fn test_call_from_signed_eip1559_transaction() {
+        let wallet = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(1114u64);
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(Address::repeat_byte(0x22))
+            .value(1_000u64)
+            .data(vec![0xa9, 0x05, 0x9c, 0xbb])
+            .nonce(0)
+            .gas(60_000)
+            .max_fee_per_gas(2_000_000_000u64)
+            .max_priority_fee_per_gas(1_000_000_000u64)
+            .chain_id(1114u64)
+            .into();
+        let signature = wallet.sign_transaction_sync(&tx).unwrap();
+        let signed_tx = format!("0x{}", hex::encode(tx.rlp_signed(&signature)));
+
+        let call = SimulatedCall::from_signed_tx(&signed_tx).unwrap();
+        assert_eq!(call, SimulatedCall { from: wallet.address(), ..self::call() });
+        assert!(SimulatedCall::from_signed_tx("0xdeadbeef").is_err());
+    }
+}
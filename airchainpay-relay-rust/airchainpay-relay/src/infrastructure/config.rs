@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::env;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::{RwLock, watch};
 use tokio::time::sleep;
@@ -12,6 +13,11 @@ use anyhow::{Result, anyhow};
 use std::sync::mpsc::channel;
 use chrono::{DateTime, Utc};
 use notify::Watcher;
+use sha2::{Digest, Sha256};
+use crate::validators::allowlist::RecipientAllowlist;
+use crate::validators::denylist::RecipientDenylist;
+use crate::validators::plugin::ValidatorPlugins;
+use crate::infrastructure::storage::encryption::StorageCipher;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChainConfig {
@@ -21,6 +27,9 @@ pub struct ChainConfig {
     pub explorer: String,
     pub currency_symbol: Option<String>,
     pub max_gas_limit: Option<u64>,
+    /// Transactions priced above this (legacy gas price or EIP-1559 max fee) are rejected
+    #[serde(default)]
+    pub max_gas_price_wei: Option<u64>,
 }
 
 impl Default for ChainConfig {
@@ -32,6 +41,749 @@ impl Default for ChainConfig {
             explorer: "https://scan.test2.btcs.network".to_string(),
             currency_symbol: Some("TCORE2".to_string()),
             max_gas_limit: None,
+            max_gas_price_wei: None,
+        }
+    }
+}
+
+impl ChainConfig {
+    /// Explorer link for a transaction, or `None` when no explorer is configured
+    pub fn explorer_tx_url(&self, tx_hash: &str) -> Option<String> {
+        let base = self.explorer.trim().trim_end_matches('/');
+        if base.is_empty() {
+            return None;
+        }
+        Some(format!("{}/tx/{}", base, tx_hash))
+    }
+}
+
+/// Response header carrying the registry's EIP-191 signature over the chain config body
+pub const CHAIN_CONFIG_SIGNATURE_HEADER: &str = "x-chain-config-signature";
+
+/// Central chain registry fetched at startup and merged over the local `supported_chains`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteChainSource {
+    pub url: String,
+    /// Hex SHA-256 the response body must match
+    pub expected_sha256: Option<String>,
+    /// Address whose signature over the body must be present in `CHAIN_CONFIG_SIGNATURE_HEADER`
+    pub signer: Option<String>,
+    pub timeout_secs: u64,
+}
+
+impl RemoteChainSource {
+    pub fn from_env() -> Option<Self> {
+        let url = env::var("CHAIN_CONFIG_URL").ok().filter(|u| !u.is_empty())?;
+        Some(Self {
+            url,
+            expected_sha256: env::var("CHAIN_CONFIG_SHA256").ok().filter(|h| !h.is_empty()),
+            signer: env::var("CHAIN_CONFIG_SIGNER").ok().filter(|s| !s.is_empty()),
+            timeout_secs: env::var("CHAIN_CONFIG_TIMEOUT_SECS")
+                .ok()
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(10),
+        })
+    }
+
+    /// Download, verify and parse the remote chain registry
+    pub async fn fetch(&self) -> Result<HashMap<u64, ChainConfig>> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.timeout_secs))
+            .build()?;
+        let response = client.get(&self.url).send().await
+            .map_err(|e| anyhow!("Failed to fetch chain config from {}: {}", self.url, e))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Chain config request to {} returned {}", self.url, response.status()));
+        }
+        let signature = response.headers()
+            .get(CHAIN_CONFIG_SIGNATURE_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.bytes().await
+            .map_err(|e| anyhow!("Failed to read chain config body: {}", e))?;
+
+        self.verify(&body, signature.as_deref())?;
+
+        let chains: HashMap<u64, ChainConfig> = serde_json::from_slice(&body)
+            .map_err(|e| anyhow!("Failed to deserialize remote chain config: {}", e))?;
+        for (chain_id, chain) in &chains {
+            if !Config::is_valid_hex_address(&chain.contract_address) || chain.rpc_url.is_empty() {
+                return Err(anyhow!("Remote chain config for chain {} ({}) is invalid", chain_id, chain.name));
+            }
+        }
+        Ok(chains)
+    }
+
+    fn verify(&self, body: &[u8], signature: Option<&str>) -> Result<()> {
+        // An unauthenticated registry could point every chain at an attacker's contract
+        if self.expected_sha256.is_none() && self.signer.is_none() {
+            return Err(anyhow!("Refusing remote chain config from {}: set CHAIN_CONFIG_SHA256 or CHAIN_CONFIG_SIGNER", self.url));
+        }
+        if let Some(expected) = &self.expected_sha256 {
+            let actual = hex::encode(Sha256::digest(body));
+            if !actual.eq_ignore_ascii_case(expected.trim_start_matches("0x")) {
+                return Err(anyhow!("Chain config hash mismatch: expected {}, got {}", expected, actual));
+            }
+        }
+        if let Some(signer) = &self.signer {
+            let signer = ethers::types::Address::from_str(signer)
+                .map_err(|e| anyhow!("Invalid chain config signer address: {}", e))?;
+            let signature = signature
+                .ok_or_else(|| anyhow!("Chain config response is missing the {} header", CHAIN_CONFIG_SIGNATURE_HEADER))?;
+            let signature = ethers::types::Signature::from_str(signature)
+                .map_err(|e| anyhow!("Invalid chain config signature: {}", e))?;
+            signature.verify(body.to_vec(), signer)
+                .map_err(|e| anyhow!("Chain config signature verification failed: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Bounds (in wei) applied to oracle fee suggestions before broadcasting
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct FeeBounds {
+    pub min_priority_fee_per_gas: Option<u64>,
+    pub max_priority_fee_per_gas: Option<u64>,
+    pub min_max_fee_per_gas: Option<u64>,
+    pub max_max_fee_per_gas: Option<u64>,
+}
+
+impl FeeBounds {
+    pub fn from_env() -> Self {
+        let read = |key: &str| env::var(key).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            min_priority_fee_per_gas: read("MIN_PRIORITY_FEE_WEI"),
+            max_priority_fee_per_gas: read("MAX_PRIORITY_FEE_WEI"),
+            min_max_fee_per_gas: read("MIN_MAX_FEE_WEI"),
+            max_max_fee_per_gas: read("MAX_MAX_FEE_WEI"),
+        }
+    }
+}
+
+/// Low-balance monitoring for the address that pays gas on sponsored (meta) transactions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SponsorMonitorConfig {
+    pub address: Option<String>,
+    /// Balance (in wei) below which a critical alert is raised
+    pub min_balance_wei: u64,
+    pub check_interval_secs: u64,
+    /// Reject sponsored broadcasts on a chain while its balance is below the threshold
+    pub pause_when_low: bool,
+}
+
+impl Default for SponsorMonitorConfig {
+    fn default() -> Self {
+        Self {
+            address: None,
+            min_balance_wei: 10_000_000_000_000_000, // 0.01 native token
+            check_interval_secs: 300,
+            pause_when_low: false,
+        }
+    }
+}
+
+impl SponsorMonitorConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            address: env::var("SPONSOR_ADDRESS").ok().filter(|a| !a.is_empty()),
+            min_balance_wei: env::var("SPONSOR_MIN_BALANCE_WEI").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.min_balance_wei),
+            check_interval_secs: env::var("SPONSOR_CHECK_INTERVAL_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.check_interval_secs),
+            pause_when_low: env::var("SPONSOR_PAUSE_WHEN_LOW").map(|v| v == "true").unwrap_or(false),
+        }
+    }
+}
+
+/// Recipient addresses the relay refuses to forward transfers to (sanctions/scam lists)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct DenylistConfig {
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    /// Optional file with one address per line; `#` starts a comment
+    pub file: Option<String>,
+}
+
+impl DenylistConfig {
+    pub fn from_env() -> Self {
+        Self {
+            addresses: env::var("RECIPIENT_DENYLIST")
+                .map(|v| v.split(',').map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect())
+                .unwrap_or_default(),
+            file: env::var("RECIPIENT_DENYLIST_FILE").ok().filter(|f| !f.is_empty()),
+        }
+    }
+}
+
+/// Recipient addresses transfers are restricted to; empty allows every recipient.
+/// The denylist takes precedence.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct AllowlistConfig {
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    /// Optional file with one address per line; `#` starts a comment
+    pub file: Option<String>,
+}
+
+impl AllowlistConfig {
+    pub fn from_env() -> Self {
+        Self {
+            addresses: env::var("RECIPIENT_ALLOWLIST")
+                .map(|v| v.split(',').map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect())
+                .unwrap_or_default(),
+            file: env::var("RECIPIENT_ALLOWLIST_FILE").ok().filter(|f| !f.is_empty()),
+        }
+    }
+}
+
+/// Restrict submissions to transactions sent by wallets registered with the relay.
+/// Off by default, so open relays accept any sender.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct RegisteredSenderConfig {
+    pub require_registered: bool,
+    /// Wallet addresses registered at startup, in addition to those already stored
+    #[serde(default)]
+    pub wallets: Vec<String>,
+}
+
+impl RegisteredSenderConfig {
+    pub fn from_env() -> Self {
+        Self {
+            require_registered: env::var("REQUIRE_REGISTERED_SENDER").map(|v| v == "true").unwrap_or(false),
+            wallets: env::var("REGISTERED_WALLETS")
+                .map(|v| v.split(',').map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Which callback URLs may be registered as webhooks, and how they are verified
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WebhookConfig {
+    pub allowed_schemes: Vec<String>,
+    /// Hosts webhook URLs may point at, subdomains included; empty allows any host
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    /// How long the endpoint has to answer the verification challenge
+    pub verify_timeout_secs: u64,
+    /// Allow URLs resolving to loopback, private or link-local addresses
+    #[serde(default)]
+    pub allow_private_networks: bool,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            allowed_schemes: vec!["https".to_string()],
+            allowed_hosts: Vec::new(),
+            verify_timeout_secs: 10,
+            allow_private_networks: false,
+        }
+    }
+}
+
+impl WebhookConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let list = |name: &str| {
+            env::var(name).ok().map(|v| {
+                v.split(',').map(|item| item.trim().to_lowercase()).filter(|item| !item.is_empty()).collect::<Vec<_>>()
+            })
+        };
+        Self {
+            allowed_schemes: list("WEBHOOK_ALLOWED_SCHEMES").unwrap_or(defaults.allowed_schemes),
+            allowed_hosts: list("WEBHOOK_ALLOWED_HOSTS").unwrap_or(defaults.allowed_hosts),
+            verify_timeout_secs: env::var("WEBHOOK_VERIFY_TIMEOUT_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.verify_timeout_secs),
+            allow_private_networks: env::var("WEBHOOK_ALLOW_PRIVATE_NETWORKS").map(|v| v == "true").unwrap_or(false),
+        }
+    }
+}
+
+/// Connection pooling for the relay's outbound RPC clients
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RpcPoolConfig {
+    pub max_idle_per_host: usize,
+    pub idle_timeout_secs: u64,
+    /// Concurrent connections allowed to one RPC host; 0 means unlimited
+    pub max_connections_per_host: usize,
+    /// Times a rate-limited (429) request is retried after the provider's `Retry-After`
+    #[serde(default = "default_rate_limit_retries")]
+    pub rate_limit_retries: u32,
+    /// Longest `Retry-After` the relay waits out; a longer one fails the request
+    #[serde(default = "default_max_retry_after_secs")]
+    pub max_retry_after_secs: u64,
+    /// Longest a single RPC request may take, so a hanging endpoint can't block its callers; 0 means no limit
+    #[serde(default = "default_rpc_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+}
+
+fn default_rate_limit_retries() -> u32 {
+    2
+}
+
+fn default_max_retry_after_secs() -> u64 {
+    30
+}
+
+fn default_rpc_request_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for RpcPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_per_host: 16,
+            idle_timeout_secs: 90,
+            max_connections_per_host: 32,
+            rate_limit_retries: default_rate_limit_retries(),
+            max_retry_after_secs: default_max_retry_after_secs(),
+            request_timeout_secs: default_rpc_request_timeout_secs(),
+        }
+    }
+}
+
+impl RpcPoolConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_idle_per_host: env::var("RPC_POOL_MAX_IDLE_PER_HOST").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_idle_per_host),
+            idle_timeout_secs: env::var("RPC_POOL_IDLE_TIMEOUT_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.idle_timeout_secs),
+            max_connections_per_host: env::var("RPC_POOL_MAX_CONNECTIONS_PER_HOST").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_connections_per_host),
+            rate_limit_retries: env::var("RPC_RATE_LIMIT_RETRIES").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.rate_limit_retries),
+            max_retry_after_secs: env::var("RPC_MAX_RETRY_AFTER_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_retry_after_secs),
+            request_timeout_secs: env::var("RPC_REQUEST_TIMEOUT_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.request_timeout_secs),
+        }
+    }
+}
+
+/// Latency objective per route: a warning alert is raised when a route's p95 exceeds it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatencySlaConfig {
+    pub p95_threshold_ms: f64,
+    /// Only requests within this rolling window count towards the percentiles
+    pub window_secs: u64,
+    /// Routes with fewer requests in the window are not evaluated
+    pub min_samples: usize,
+}
+
+impl Default for LatencySlaConfig {
+    fn default() -> Self {
+        Self {
+            p95_threshold_ms: 2000.0,
+            window_secs: 300,
+            min_samples: 20,
+        }
+    }
+}
+
+impl LatencySlaConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            p95_threshold_ms: env::var("LATENCY_SLA_P95_MS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.p95_threshold_ms),
+            window_secs: env::var("LATENCY_SLA_WINDOW_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.window_secs),
+            min_samples: env::var("LATENCY_SLA_MIN_SAMPLES").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.min_samples),
+        }
+    }
+}
+
+/// How long a failed metrics update keeps the monitoring subsystem reported as degraded
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MonitoringHealthConfig {
+    pub degraded_window_secs: u64,
+}
+
+impl Default for MonitoringHealthConfig {
+    fn default() -> Self {
+        Self { degraded_window_secs: 300 }
+    }
+}
+
+impl MonitoringHealthConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            degraded_window_secs: env::var("MONITORING_DEGRADED_WINDOW_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.degraded_window_secs),
+        }
+    }
+}
+
+/// Limits on the `/ws/transactions/{id}` live status streams
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StatusStreamConfig {
+    /// Streams open at once across all clients; further connections get `503`
+    pub max_connections: usize,
+    /// A stream without a status update or client message for this long is closed
+    pub idle_timeout_secs: u64,
+    /// A stream is closed after this long however active it is
+    pub max_lifetime_secs: u64,
+}
+
+impl Default for StatusStreamConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 1000,
+            idle_timeout_secs: 300,
+            max_lifetime_secs: 3600,
+        }
+    }
+}
+
+impl StatusStreamConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_connections: env::var("STATUS_WS_MAX_CONNECTIONS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_connections),
+            idle_timeout_secs: env::var("STATUS_WS_IDLE_TIMEOUT_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.idle_timeout_secs),
+            max_lifetime_secs: env::var("STATUS_WS_MAX_LIFETIME_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_lifetime_secs),
+        }
+    }
+}
+
+/// What happens to the rest of a transaction bundle once one of its transactions reverts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleRevertPolicy {
+    /// Cancel every transaction after the reverted one
+    #[default]
+    Halt,
+    /// Keep broadcasting the remaining transactions in order
+    Continue,
+}
+
+/// Ordered submission of several transactions from one sender
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BundleConfig {
+    pub revert_policy: BundleRevertPolicy,
+    pub max_transactions: usize,
+    /// How often the receipt of the in-flight bundle transaction is polled
+    pub receipt_poll_interval_ms: u64,
+    /// A bundle transaction not mined within this time fails and halts the bundle
+    pub receipt_timeout_secs: u64,
+}
+
+impl Default for BundleConfig {
+    fn default() -> Self {
+        Self {
+            revert_policy: BundleRevertPolicy::Halt,
+            max_transactions: 16,
+            receipt_poll_interval_ms: 2000,
+            receipt_timeout_secs: 300,
+        }
+    }
+}
+
+impl BundleConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            revert_policy: match env::var("BUNDLE_REVERT_POLICY").ok().as_deref().map(str::trim) {
+                Some(v) if v.eq_ignore_ascii_case("continue") => BundleRevertPolicy::Continue,
+                Some(v) if v.eq_ignore_ascii_case("halt") => BundleRevertPolicy::Halt,
+                _ => defaults.revert_policy,
+            },
+            max_transactions: env::var("BUNDLE_MAX_TRANSACTIONS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_transactions),
+            receipt_poll_interval_ms: env::var("BUNDLE_RECEIPT_POLL_MS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.receipt_poll_interval_ms),
+            receipt_timeout_secs: env::var("BUNDLE_RECEIPT_TIMEOUT_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.receipt_timeout_secs),
+        }
+    }
+}
+
+/// Decoding of transaction input data in detail responses
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CalldataDecoderConfig {
+    pub enabled: bool,
+    /// JSON ABI files decoded in addition to ERC-20 and the AirChainPay contracts
+    pub abi_paths: Vec<String>,
+}
+
+impl Default for CalldataDecoderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            abi_paths: Vec::new(),
+        }
+    }
+}
+
+impl CalldataDecoderConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: env::var("DECODE_CALLDATA").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.enabled),
+            abi_paths: env::var("CALLDATA_ABI_PATHS").ok()
+                .map(|v| v.split(',').map(str::trim).filter(|p| !p.is_empty()).map(String::from).collect())
+                .unwrap_or(defaults.abi_paths),
+        }
+    }
+}
+
+/// Limits on sending the same signed transaction to the network again
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RebroadcastConfig {
+    /// Minimum time between two sends of the same transaction
+    pub min_interval_secs: u64,
+    /// Sends allowed after the first broadcast
+    pub max_rebroadcasts: u32,
+}
+
+impl Default for RebroadcastConfig {
+    fn default() -> Self {
+        Self {
+            min_interval_secs: 30,
+            max_rebroadcasts: 3,
+        }
+    }
+}
+
+impl RebroadcastConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            min_interval_secs: env::var("REBROADCAST_MIN_INTERVAL_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.min_interval_secs),
+            max_rebroadcasts: env::var("MAX_REBROADCASTS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_rebroadcasts),
+        }
+    }
+}
+
+/// Dry-running transactions with `eth_call` before they are broadcast
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SimulationConfig {
+    /// Refuse to broadcast transactions whose simulation reverts
+    pub simulate_before_send: bool,
+    /// How long an identical call at the same block reuses its simulation result
+    pub cache_ttl_secs: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            simulate_before_send: false,
+            cache_ttl_secs: 15,
+        }
+    }
+}
+
+impl SimulationConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            simulate_before_send: env::var("SIMULATE_BEFORE_SEND").ok()
+                .map(|v| v == "true")
+                .unwrap_or(defaults.simulate_before_send),
+            cache_ttl_secs: env::var("SIMULATION_CACHE_TTL_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.cache_ttl_secs),
+        }
+    }
+}
+
+/// Cached per-chain RPC health, read by transaction submission instead of a live RPC call
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChainHealthConfig {
+    /// How long a chain's health check result is reused
+    pub cache_ttl_secs: u64,
+    /// How often the background task re-checks every chain; 0 disables it
+    pub refresh_interval_secs: u64,
+    /// A probe that hasn't answered after this long marks the chain unhealthy
+    #[serde(default = "default_chain_health_timeout_secs")]
+    pub probe_timeout_secs: u64,
+}
+
+fn default_chain_health_timeout_secs() -> u64 {
+    5
+}
+
+impl Default for ChainHealthConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl_secs: 10,
+            refresh_interval_secs: 5,
+            probe_timeout_secs: default_chain_health_timeout_secs(),
+        }
+    }
+}
+
+impl ChainHealthConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            cache_ttl_secs: env::var("CHAIN_HEALTH_TTL_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.cache_ttl_secs),
+            refresh_interval_secs: env::var("CHAIN_HEALTH_REFRESH_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.refresh_interval_secs),
+            probe_timeout_secs: env::var("CHAIN_HEALTH_TIMEOUT_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.probe_timeout_secs),
+        }
+    }
+}
+
+/// Per-sender nonce sequencing: a transaction whose nonce is ahead of the sender's next
+/// nonce is held until its predecessors have been broadcast
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NonceSequencingConfig {
+    /// How long a transaction waits for a missing predecessor before it is failed
+    pub hold_timeout_secs: u64,
+}
+
+impl Default for NonceSequencingConfig {
+    fn default() -> Self {
+        Self {
+            hold_timeout_secs: 60,
+        }
+    }
+}
+
+impl NonceSequencingConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            hold_timeout_secs: env::var("NONCE_HOLD_TIMEOUT_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.hold_timeout_secs),
+        }
+    }
+}
+
+/// Conditional GET support for rarely changing read endpoints (chain info)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResponseCacheConfig {
+    /// Send an `ETag` and answer a matching `If-None-Match` with 304
+    pub etags: bool,
+    /// `Cache-Control: max-age` sent with those responses; 0 sends `no-cache`
+    pub max_age_secs: u64,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            etags: true,
+            max_age_secs: 60,
+        }
+    }
+}
+
+impl ResponseCacheConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            etags: env::var("RESPONSE_ETAGS").ok()
+                .map(|v| v != "false")
+                .unwrap_or(defaults.etags),
+            max_age_secs: env::var("RESPONSE_CACHE_MAX_AGE_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_age_secs),
+        }
+    }
+}
+
+/// Replaying the original response for retried submissions carrying an `Idempotency-Key`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IdempotencyConfig {
+    /// How long a key keeps answering with the response of its first submission
+    pub ttl_secs: u64,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self { ttl_secs: 86_400 }
+    }
+}
+
+impl IdempotencyConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            ttl_secs: env::var("IDEMPOTENCY_KEY_TTL_SECS").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.ttl_secs),
+        }
+    }
+}
+
+/// How the relay tracks the sponsor account's nonces for sponsored sends
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SponsorNonceConfig {
+    /// Read the sponsor's nonce on every chain at startup instead of on the first sponsored send
+    pub eager_sync: bool,
+}
+
+impl Default for SponsorNonceConfig {
+    fn default() -> Self {
+        Self { eager_sync: true }
+    }
+}
+
+impl SponsorNonceConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            eager_sync: env::var("SPONSOR_NONCE_EAGER_SYNC").map(|v| v != "false").unwrap_or(defaults.eager_sync),
+        }
+    }
+}
+
+/// What the relay charges on top of the gas it spends broadcasting a transaction
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct FeeMarkupConfig {
+    /// Markup on the gas cost, in basis points (100 = 1%)
+    pub markup_bps: u64,
+    /// Fixed fee added per broadcast transaction
+    pub flat_fee_wei: u64,
+}
+
+impl FeeMarkupConfig {
+    pub fn from_env() -> Self {
+        let read = |key: &str| env::var(key).ok().and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+        Self {
+            markup_bps: read("RELAY_MARKUP_BPS"),
+            flat_fee_wei: read("RELAY_FLAT_FEE_WEI"),
         }
     }
 }
@@ -40,6 +792,24 @@ impl Default for ChainConfig {
 pub struct RateLimitConfig {
     pub window_ms: u64,
     pub max_requests: u32,
+    /// Requests per window for individual API keys or JWT subjects, overriding the default
+    /// per-principal budget. Only API keys listed here are trusted to identify a caller.
+    #[serde(default, skip_serializing)]
+    pub per_key_limits: HashMap<String, u32>,
+}
+
+impl RateLimitConfig {
+    /// Parse `RATE_LIMIT_PER_KEY`, e.g. `partner-key:5000,mobile-app:500`; malformed entries are skipped
+    pub fn per_key_limits_from_env() -> HashMap<String, u32> {
+        env::var("RATE_LIMIT_PER_KEY").unwrap_or_default()
+            .split(',')
+            .filter_map(|entry| {
+                let (key, limit) = entry.trim().rsplit_once(':')?;
+                Some((key.trim().to_string(), limit.trim().parse().ok()?))
+            })
+            .filter(|(key, _)| !key.is_empty())
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -53,6 +823,15 @@ pub struct SecurityConfig {
     pub api_key: String,
     pub max_connections: u32,
     pub session_timeout: u64,
+    /// Allowed client/server clock difference (seconds) when checking signature expiry
+    #[serde(default)]
+    pub clock_skew_tolerance_secs: Option<u64>,
+}
+
+impl SecurityConfig {
+    pub fn clock_skew_tolerance(&self) -> u64 {
+        self.clock_skew_tolerance_secs.unwrap_or(crate::domain::auth::DEFAULT_CLOCK_SKEW_SECS)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -72,6 +851,9 @@ pub struct DatabaseConfig {
     pub retention_days: u32,
     pub enable_encryption: bool,
     pub compression_enabled: bool,
+    /// Hex-encoded 32-byte key for `enable_encryption`; read from the environment only
+    #[serde(default, skip_serializing)]
+    pub master_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +872,46 @@ pub struct Config {
     pub monitoring: MonitoringConfig,
     pub database: DatabaseConfig,
     pub supported_chains: HashMap<u64, ChainConfig>,
+    #[serde(default)]
+    pub fee_bounds: FeeBounds,
+    #[serde(default)]
+    pub sponsor: SponsorMonitorConfig,
+    #[serde(default)]
+    pub denylist: DenylistConfig,
+    #[serde(default)]
+    pub allowlist: AllowlistConfig,
+    #[serde(default)]
+    pub registered_sender: RegisteredSenderConfig,
+    #[serde(default)]
+    pub webhooks: WebhookConfig,
+    #[serde(default)]
+    pub rpc_pool: RpcPoolConfig,
+    #[serde(default)]
+    pub fee_markup: FeeMarkupConfig,
+    #[serde(default)]
+    pub rebroadcast: RebroadcastConfig,
+    #[serde(default)]
+    pub simulation: SimulationConfig,
+    #[serde(default)]
+    pub chain_health: ChainHealthConfig,
+    #[serde(default)]
+    pub nonce_sequencing: NonceSequencingConfig,
+    #[serde(default)]
+    pub response_cache: ResponseCacheConfig,
+    #[serde(default)]
+    pub sponsor_nonce: SponsorNonceConfig,
+    #[serde(default)]
+    pub idempotency: IdempotencyConfig,
+    #[serde(default)]
+    pub latency_sla: LatencySlaConfig,
+    #[serde(default)]
+    pub monitoring_health: MonitoringHealthConfig,
+    #[serde(default)]
+    pub status_stream: StatusStreamConfig,
+    #[serde(default)]
+    pub bundle: BundleConfig,
+    #[serde(default)]
+    pub calldata_decoder: CalldataDecoderConfig,
     pub config_file_path: Option<String>,
     pub last_modified: Option<u64>,
     pub version: String,
@@ -112,6 +934,26 @@ impl Default for Config {
             monitoring: MonitoringConfig::default(),
             database: DatabaseConfig::default(),
             supported_chains: HashMap::new(),
+            fee_bounds: FeeBounds::default(),
+            sponsor: SponsorMonitorConfig::default(),
+            denylist: DenylistConfig::default(),
+            allowlist: AllowlistConfig::default(),
+            registered_sender: RegisteredSenderConfig::default(),
+            webhooks: WebhookConfig::default(),
+            rpc_pool: RpcPoolConfig::default(),
+            fee_markup: FeeMarkupConfig::default(),
+            rebroadcast: RebroadcastConfig::default(),
+            simulation: SimulationConfig::default(),
+            chain_health: ChainHealthConfig::default(),
+            nonce_sequencing: NonceSequencingConfig::default(),
+            response_cache: ResponseCacheConfig::default(),
+            idempotency: IdempotencyConfig::default(),
+            sponsor_nonce: SponsorNonceConfig::default(),
+            latency_sla: LatencySlaConfig::default(),
+            monitoring_health: MonitoringHealthConfig::default(),
+            status_stream: StatusStreamConfig::default(),
+            bundle: BundleConfig::default(),
+            calldata_decoder: CalldataDecoderConfig::default(),
             config_file_path: None,
             last_modified: Some(Utc::now().timestamp() as u64),
             version: env!("CARGO_PKG_VERSION").to_string(),
@@ -153,48 +995,89 @@ impl Default for ConfigStatus {
 #[allow(dead_code)]
 pub struct DynamicConfigManager {
     config: Arc<RwLock<Config>>,
-    config_watcher: Option<notify::FsEventWatcher>,
+    config_watcher: Option<notify::RecommendedWatcher>,
     reload_sender: watch::Sender<bool>,
     reload_receiver: watch::Receiver<bool>,
     config_file_path: String,
     environment: String,
+    remote_chains: RwLock<HashMap<u64, ChainConfig>>,
+    recipient_denylist: Arc<RecipientDenylist>,
+    recipient_allowlist: Arc<RecipientAllowlist>,
+    validator_plugins: Arc<ValidatorPlugins>,
+    /// Bumped on every change to the live config; with `started_at` it identifies a config
+    /// revision across restarts, see `etag`
+    version: AtomicU64,
+    started_at: DateTime<Utc>,
 }
 
 impl DynamicConfigManager {
     pub fn new() -> Result<Self> {
-        let config = Config::new()?;
-        let config_file_path = env::var("CONFIG_FILE").unwrap_or_else(|_| "config.json".to_string());
+        let manager = Self::from_config(Config::new()?)?;
         
+        // Start file watcher if config file exists
+        if Path::new(&manager.config_file_path).exists() {
+            manager.start_file_watcher()?;
+        }
+        
+        Ok(manager)
+    }
+
+    /// Manager serving a fixed starting config, without watching the config file
+    pub fn from_config(config: Config) -> Result<Self> {
         let (reload_sender, reload_receiver) = watch::channel(false);
+        let recipient_denylist = Arc::new(RecipientDenylist::from_config(&config.denylist)?);
+        let recipient_allowlist = Arc::new(RecipientAllowlist::from_config(&config.allowlist)?);
         
-        let manager = Self {
+        Ok(Self {
             config: Arc::new(RwLock::new(config)),
             config_watcher: None,
             reload_sender,
             reload_receiver,
-            config_file_path: config_file_path.clone(),
+            config_file_path: env::var("CONFIG_FILE").unwrap_or_else(|_| "config.json".to_string()),
             environment: env::var("RUST_ENV").unwrap_or_else(|_| "development".to_string()),
-        };
-        
-        // Start file watcher if config file exists
-        if Path::new(&config_file_path).exists() {
-            manager.start_file_watcher()?;
-        }
-        
-        Ok(manager)
+            remote_chains: RwLock::new(HashMap::new()),
+            recipient_denylist,
+            recipient_allowlist,
+            validator_plugins: Arc::new(ValidatorPlugins::default()),
+            version: AtomicU64::new(0),
+            started_at: Utc::now(),
+        })
     }
     
     pub async fn get_config(&self) -> Config {
         self.config.read().await.clone()
     }
     
+    /// Recipient denylist, rebuilt whenever the configuration is updated or reloaded
+    pub fn recipient_denylist(&self) -> Arc<RecipientDenylist> {
+        Arc::clone(&self.recipient_denylist)
+    }
+    
+    /// Recipient allowlist, rebuilt whenever the configuration is updated or reloaded
+    pub fn recipient_allowlist(&self) -> Arc<RecipientAllowlist> {
+        Arc::clone(&self.recipient_allowlist)
+    }
+    
+    /// Custom transaction validators run after the built-in checks on every submission
+    pub fn validator_plugins(&self) -> Arc<ValidatorPlugins> {
+        Arc::clone(&self.validator_plugins)
+    }
+    
+    /// Weak ETag of the current config revision, for responses derived from the config
+    pub fn etag(&self) -> String {
+        format!("W/\"{:x}-{}\"", self.started_at.timestamp_millis(), self.version.load(Ordering::SeqCst))
+    }
+    
     pub async fn update_config(&self, new_config: Config) -> Result<()> {
         // Validate the new configuration
         new_config.validate()?;
+        self.recipient_denylist.reload(&new_config.denylist)?;
+        self.recipient_allowlist.reload(&new_config.allowlist)?;
         
         // Update the configuration
         let mut config = self.config.write().await;
         *config = new_config;
+        self.version.fetch_add(1, Ordering::SeqCst);
         
         // Notify reload
         let _ = self.reload_sender.send(true);
@@ -204,9 +1087,29 @@ impl DynamicConfigManager {
     }
     
     pub async fn reload_config(&self) -> Result<()> {
-        let new_config = Config::new()?;
+        let mut new_config = Config::new()?;
+        new_config.merge_remote_chains(self.remote_chains.read().await.clone());
         self.update_config(new_config).await
     }
+
+    /// Merge the remote chain registry into the live config. If the registry is unreachable or fails
+    /// verification the local chains stay in effect and `false` is returned.
+    pub async fn apply_remote_chains(&self, source: &RemoteChainSource) -> bool {
+        match source.fetch().await {
+            Ok(chains) => {
+                println!("Loaded {} chain(s) from remote registry {}", chains.len(), source.url);
+                *self.remote_chains.write().await = chains.clone();
+                self.config.write().await.merge_remote_chains(chains);
+                self.version.fetch_add(1, Ordering::SeqCst);
+                let _ = self.reload_sender.send(true);
+                true
+            }
+            Err(e) => {
+                eprintln!("Warning: {}; falling back to local chain config", e);
+                false
+            }
+        }
+    }
     
 
     
@@ -445,7 +1348,8 @@ impl Config {
                     "CHAIN_ID", 
                     "CONTRACT_ADDRESS",
                     "API_KEY",
-                    "JWT_SECRET"
+                    "JWT_SECRET",
+                    "STORAGE_MASTER_KEY"
                 ];
                 
                 for var in &required_vars {
@@ -493,12 +1397,30 @@ impl Config {
             let mut config: Config = serde_json::from_str(&content)
                 .map_err(|e| anyhow!("Failed to deserialize config: {}", e))?;
             config.last_modified = Some(Utc::now().timestamp() as u64);
+            // The master key is never written to config files
+            if config.database.master_key.is_none() {
+                config.database.master_key = env::var("STORAGE_MASTER_KEY").ok().filter(|k| !k.is_empty());
+            }
             Ok(config)
         } else {
             Err(anyhow!("Config file not found"))
         }
     }
     
+    /// Explorer link for a transaction on `chain_id`, or `None` for chains without a configured explorer
+    pub fn explorer_tx_url(&self, chain_id: u64, tx_hash: &str) -> Option<String> {
+        self.supported_chains.get(&chain_id)?.explorer_tx_url(tx_hash)
+    }
+
+    /// Remote entries replace local ones with the same chain id; local-only chains are kept
+    pub fn merge_remote_chains(&mut self, chains: HashMap<u64, ChainConfig>) {
+        if chains.is_empty() {
+            return;
+        }
+        self.supported_chains.extend(chains);
+        self.last_modified = Some(Utc::now().timestamp() as u64);
+    }
+
     pub fn save_to_file(&self, file_path: &str) -> Result<()> {
         let content = serde_json::to_string_pretty(self)
             .map_err(|e| anyhow!("Failed to serialize config: {}", e))?;
@@ -522,6 +1444,7 @@ impl Config {
             rate_limits: RateLimitConfig {
                 window_ms: 15 * 60 * 1000,
                 max_requests: u32::from_str(&env::var("RATE_LIMIT_MAX").unwrap_or_else(|_| "1000".to_string()))?,
+                per_key_limits: RateLimitConfig::per_key_limits_from_env(),
             },
             security: SecurityConfig {
                 enable_jwt_validation: true,
@@ -533,6 +1456,7 @@ impl Config {
                 api_key: env::var("API_KEY").unwrap_or_else(|_| "dev_api_key".to_string()),
                 max_connections: 100,
                 session_timeout: 3600,
+                clock_skew_tolerance_secs: crate::domain::auth::clock_skew_from_env(),
             },
             monitoring: MonitoringConfig {
                 enable_metrics: true,
@@ -546,10 +1470,31 @@ impl Config {
                 data_dir: env::var("DATA_DIR").unwrap_or_else(|_| "./data".to_string()),
                 backup_interval: 3600,
                 retention_days: 30,
-                enable_encryption: false,
+                enable_encryption: env::var("STORAGE_ENCRYPTION").map(|v| v == "true").unwrap_or(false),
                 compression_enabled: true,
+                master_key: env::var("STORAGE_MASTER_KEY").ok().filter(|k| !k.is_empty()),
             },
-            supported_chains: Self::get_supported_chains(),
+            supported_chains: Self::get_supported_chains()?,
+            fee_bounds: FeeBounds::from_env(),
+            sponsor: SponsorMonitorConfig::from_env(),
+            denylist: DenylistConfig::from_env(),
+            allowlist: AllowlistConfig::from_env(),
+            registered_sender: RegisteredSenderConfig::from_env(),
+            webhooks: WebhookConfig::from_env(),
+            rpc_pool: RpcPoolConfig::from_env(),
+            fee_markup: FeeMarkupConfig::from_env(),
+            rebroadcast: RebroadcastConfig::from_env(),
+            simulation: SimulationConfig::from_env(),
+            chain_health: ChainHealthConfig::from_env(),
+            nonce_sequencing: NonceSequencingConfig::from_env(),
+            response_cache: ResponseCacheConfig::from_env(),
+            idempotency: IdempotencyConfig::from_env(),
+            sponsor_nonce: SponsorNonceConfig::from_env(),
+            latency_sla: LatencySlaConfig::from_env(),
+            monitoring_health: MonitoringHealthConfig::from_env(),
+            status_stream: StatusStreamConfig::from_env(),
+            bundle: BundleConfig::from_env(),
+            calldata_decoder: CalldataDecoderConfig::from_env(),
             config_file_path: None,
             last_modified: Some(Utc::now().timestamp() as u64),
         })
@@ -570,6 +1515,7 @@ impl Config {
             rate_limits: RateLimitConfig {
                 window_ms: 15 * 60 * 1000,
                 max_requests: u32::from_str(&env::var("RATE_LIMIT_MAX").unwrap_or_else(|_| "500".to_string()))?,
+                per_key_limits: RateLimitConfig::per_key_limits_from_env(),
             },
             security: SecurityConfig {
                 enable_jwt_validation: true,
@@ -581,6 +1527,7 @@ impl Config {
                 api_key: env::var("API_KEY").unwrap_or_else(|_| "staging_key".to_string()),
                 max_connections: 50,
                 session_timeout: 1800,
+                clock_skew_tolerance_secs: crate::domain::auth::clock_skew_from_env(),
             },
             monitoring: MonitoringConfig {
                 enable_metrics: env::var("ENABLE_METRICS").unwrap_or_else(|_| "true".to_string()) == "true",
@@ -594,10 +1541,31 @@ impl Config {
                 data_dir: env::var("DATA_DIR").unwrap_or_else(|_| "./data".to_string()),
                 backup_interval: 3600,
                 retention_days: 30,
-                enable_encryption: false,
+                enable_encryption: env::var("STORAGE_ENCRYPTION").map(|v| v == "true").unwrap_or(false),
                 compression_enabled: true,
+                master_key: env::var("STORAGE_MASTER_KEY").ok().filter(|k| !k.is_empty()),
             },
-            supported_chains: Self::get_supported_chains(),
+            supported_chains: Self::get_supported_chains()?,
+            fee_bounds: FeeBounds::from_env(),
+            sponsor: SponsorMonitorConfig::from_env(),
+            denylist: DenylistConfig::from_env(),
+            allowlist: AllowlistConfig::from_env(),
+            registered_sender: RegisteredSenderConfig::from_env(),
+            webhooks: WebhookConfig::from_env(),
+            rpc_pool: RpcPoolConfig::from_env(),
+            fee_markup: FeeMarkupConfig::from_env(),
+            rebroadcast: RebroadcastConfig::from_env(),
+            simulation: SimulationConfig::from_env(),
+            chain_health: ChainHealthConfig::from_env(),
+            nonce_sequencing: NonceSequencingConfig::from_env(),
+            response_cache: ResponseCacheConfig::from_env(),
+            idempotency: IdempotencyConfig::from_env(),
+            sponsor_nonce: SponsorNonceConfig::from_env(),
+            latency_sla: LatencySlaConfig::from_env(),
+            monitoring_health: MonitoringHealthConfig::from_env(),
+            status_stream: StatusStreamConfig::from_env(),
+            bundle: BundleConfig::from_env(),
+            calldata_decoder: CalldataDecoderConfig::from_env(),
             config_file_path: None,
             last_modified: Some(Utc::now().timestamp() as u64),
         })
@@ -618,6 +1586,7 @@ impl Config {
             rate_limits: RateLimitConfig {
                 window_ms: 15 * 60 * 1000,
                 max_requests: u32::from_str(&env::var("RATE_LIMIT_MAX").unwrap_or_else(|_| "100".to_string()))?,
+                per_key_limits: RateLimitConfig::per_key_limits_from_env(),
             },
             security: SecurityConfig {
                 enable_jwt_validation: env::var("ENABLE_JWT_VALIDATION").unwrap_or_else(|_| "true".to_string()) != "false",
@@ -629,6 +1598,7 @@ impl Config {
                 api_key: env::var("API_KEY").unwrap_or_else(|_| "production_key".to_string()),
                 max_connections: 100,
                 session_timeout: 3600,
+                clock_skew_tolerance_secs: crate::domain::auth::clock_skew_from_env(),
             },
             monitoring: MonitoringConfig {
                 enable_metrics: env::var("ENABLE_METRICS").unwrap_or_else(|_| "true".to_string()) == "true",
@@ -644,8 +1614,29 @@ impl Config {
                 retention_days: 30,
                 enable_encryption: true,
                 compression_enabled: true,
+                master_key: env::var("STORAGE_MASTER_KEY").ok().filter(|k| !k.is_empty()),
             },
-            supported_chains: Self::get_supported_chains(),
+            supported_chains: Self::get_supported_chains()?,
+            fee_bounds: FeeBounds::from_env(),
+            sponsor: SponsorMonitorConfig::from_env(),
+            denylist: DenylistConfig::from_env(),
+            allowlist: AllowlistConfig::from_env(),
+            registered_sender: RegisteredSenderConfig::from_env(),
+            webhooks: WebhookConfig::from_env(),
+            rpc_pool: RpcPoolConfig::from_env(),
+            fee_markup: FeeMarkupConfig::from_env(),
+            rebroadcast: RebroadcastConfig::from_env(),
+            simulation: SimulationConfig::from_env(),
+            chain_health: ChainHealthConfig::from_env(),
+            nonce_sequencing: NonceSequencingConfig::from_env(),
+            response_cache: ResponseCacheConfig::from_env(),
+            idempotency: IdempotencyConfig::from_env(),
+            sponsor_nonce: SponsorNonceConfig::from_env(),
+            latency_sla: LatencySlaConfig::from_env(),
+            monitoring_health: MonitoringHealthConfig::from_env(),
+            status_stream: StatusStreamConfig::from_env(),
+            bundle: BundleConfig::from_env(),
+            calldata_decoder: CalldataDecoderConfig::from_env(),
             config_file_path: None,
             last_modified: Some(Utc::now().timestamp() as u64),
         })
@@ -702,7 +1693,18 @@ impl Config {
         Ok(address)
     }
     
-    fn get_supported_chains() -> HashMap<u64, ChainConfig> {
+    /// `<PREFIX>_MAX_GAS_PRICE_WEI`, unset meaning no cap. A value that is not a number is
+    /// an error rather than silently lifting the cap.
+    fn max_gas_price_from_env(prefix: &str) -> Result<Option<u64>> {
+        let key = format!("{}_MAX_GAS_PRICE_WEI", prefix);
+        match env::var(&key) {
+            Ok(value) => value.trim().parse().map(Some)
+                .map_err(|e| anyhow!("Invalid {}: {:?} is not a wei amount ({})", key, value, e)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn get_supported_chains() -> Result<HashMap<u64, ChainConfig>> {
         let mut chains = HashMap::new();
 
         // Core Testnet 2 (Primary)
@@ -751,6 +1753,7 @@ impl Config {
                     }),
                 ),
                 max_gas_limit: None,
+                max_gas_price_wei: Self::max_gas_price_from_env("CORE_TESTNET2")?,
             },
         );
 
@@ -800,6 +1803,7 @@ impl Config {
                     }),
                 ),
                 max_gas_limit: None,
+                max_gas_price_wei: Self::max_gas_price_from_env("BASE_SEPOLIA")?,
             },
         );
 
@@ -849,6 +1853,7 @@ impl Config {
                     }),
                 ),
                 max_gas_limit: None,
+                max_gas_price_wei: Self::max_gas_price_from_env("LISK_SEPOLIA")?,
             },
         );
 
@@ -898,10 +1903,11 @@ impl Config {
                     }),
                 ),
                 max_gas_limit: None,
+                max_gas_price_wei: Self::max_gas_price_from_env("HOLESKY")?,
             },
         );
 
-        chains
+        Ok(chains)
     }
     
     fn validate(&self) -> Result<()> {
@@ -937,6 +1943,12 @@ impl Config {
                 ));
             }
         }
+
+        if self.database.enable_encryption {
+            let master_key = self.database.master_key.as_deref()
+                .ok_or_else(|| anyhow!("STORAGE_MASTER_KEY is required when storage encryption is enabled"))?;
+            StorageCipher::from_master_key(master_key)?;
+        }
         
         match self.environment.as_str() {
             "production" => {
@@ -976,6 +1988,7 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::mock_http::{unreachable_url, MockResponse, MockServer};
     
     #[test]
     fn test_hex_address_validation() {
@@ -1016,6 +2029,21 @@ mod tests {
         std::env::remove_var("TEST_EMPTY_VAR");
         std::env::remove_var("TEST_VALID_VAR");
     }
+
+    #[test]
+    fn test_max_gas_price_from_env_rejects_unparseable_value() {
+        assert_eq!(Config::max_gas_price_from_env("TEST_GAS_CAP_UNSET").unwrap(), None);
+
+        std::env::set_var("TEST_GAS_CAP_VALID_MAX_GAS_PRICE_WEI", "5000");
+        assert_eq!(Config::max_gas_price_from_env("TEST_GAS_CAP_VALID").unwrap(), Some(5000));
+
+        std::env::set_var("TEST_GAS_CAP_BAD_MAX_GAS_PRICE_WEI", "50 gwei");
+        let err = Config::max_gas_price_from_env("TEST_GAS_CAP_BAD").unwrap_err();
+        assert!(err.to_string().contains("TEST_GAS_CAP_BAD_MAX_GAS_PRICE_WEI"));
+
+        std::env::remove_var("TEST_GAS_CAP_VALID_MAX_GAS_PRICE_WEI");
+        std::env::remove_var("TEST_GAS_CAP_BAD_MAX_GAS_PRICE_WEI");
+    }
     
     #[test]
     fn test_validate_contract_address() {
@@ -1071,4 +2099,167 @@ mod tests {
             }
         }
     }
-} 
\ No newline at end of file
+
+    /// Registry serving `body`, signed with `signature` when given
+    async fn spawn_registry(body: String, signature: Option<String>) -> String {
+        let registry = MockServer::start(move |_| {
+            let response = MockResponse::status(200).header("content-type", "application/json").body(body.clone());
+            match &signature {
+                Some(signature) => response.header(CHAIN_CONFIG_SIGNATURE_HEADER, signature),
+                None => response,
+            }
+        }).await;
+        registry.url("/chains.json")
+    }
+
+    fn registry_body() -> String {
+        serde_json::json!({
+            "1114": {
+                "name": "Core Testnet 2 (registry)",
+                "rpc_url": "https://registry.example/core",
+                "contract_address": "0x1111111111111111111111111111111111111111",
+                "explorer": "https://scan.test2.btcs.network",
+                "currency_symbol": "TCORE2",
+                "max_gas_limit": 500000
+            },
+            "999": {
+                "name": "Registry Chain",
+                "rpc_url": "https://registry.example/999",
+                "contract_address": "0x2222222222222222222222222222222222222222",
+                "explorer": "https://registry.example/explorer",
+                "currency_symbol": null,
+                "max_gas_limit": null
+            }
+        }).to_string()
+    }
+
+    fn source(url: String) -> RemoteChainSource {
+        RemoteChainSource { url, expected_sha256: None, signer: None, timeout_secs: 2 }
+    }
+
+    #[tokio::test]
+    async fn test_remote_chain_config_loaded() {
+        let body = registry_body();
+        let url = spawn_registry(body.clone(), None).await;
+        let source = RemoteChainSource {
+            expected_sha256: Some(hex::encode(Sha256::digest(body.as_bytes()))),
+            ..source(url)
+        };
+
+        let chains = source.fetch().await.expect("registry should load");
+        let mut config = Config { supported_chains: Config::get_supported_chains().unwrap(), ..Config::default() };
+        config.merge_remote_chains(chains);
+
+        assert_eq!(config.supported_chains[&1114].rpc_url, "https://registry.example/core");
+        assert_eq!(config.supported_chains[&1114].max_gas_limit, Some(500000));
+        assert_eq!(config.supported_chains[&999].name, "Registry Chain");
+        // Chains the registry doesn't know about are kept
+        assert!(config.supported_chains.contains_key(&84532));
+    }
+
+    #[tokio::test]
+    async fn test_remote_chain_config_verification() {
+        use ethers::signers::{LocalWallet, Signer};
+
+        let body = registry_body();
+        let wallet = LocalWallet::from_str("0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap();
+        let signature = wallet.sign_message(body.as_bytes()).await.unwrap();
+
+        let signed_url = spawn_registry(body.clone(), Some(signature.to_string())).await;
+        let signed = RemoteChainSource { signer: Some(format!("{:?}", wallet.address())), ..source(signed_url.clone()) };
+        assert_eq!(signed.fetch().await.expect("valid signature").len(), 2);
+
+        let wrong_signer = RemoteChainSource {
+            signer: Some("0x3333333333333333333333333333333333333333".to_string()),
+            ..source(signed_url.clone())
+        };
+        assert!(wrong_signer.fetch().await.is_err());
+
+        let bad_hash = RemoteChainSource { expected_sha256: Some("00".repeat(32)), ..source(signed_url) };
+        assert!(bad_hash.fetch().await.is_err());
+
+        let unsigned_url = spawn_registry(body, None).await;
+        let unsigned = RemoteChainSource { signer: Some(format!("{:?}", wallet.address())), ..source(unsigned_url) };
+        assert!(unsigned.fetch().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_remote_chain_config_requires_integrity_check() {
+        let url = spawn_registry(registry_body(), None).await;
+        let err = source(url).fetch().await.expect_err("URL-only registry must be refused");
+        assert!(err.to_string().contains("CHAIN_CONFIG_SHA256"), "{}", err);
+
+        let local = Config::get_supported_chains().unwrap();
+        let manager = manager(Config { supported_chains: local.clone(), ..Config::default() });
+        assert!(!manager.apply_remote_chains(&source(spawn_registry(registry_body(), None).await)).await);
+        assert_eq!(manager.get_config().await.supported_chains[&1114].rpc_url, local[&1114].rpc_url);
+    }
+
+    fn manager(config: Config) -> DynamicConfigManager {
+        let (reload_sender, reload_receiver) = watch::channel(false);
+        DynamicConfigManager {
+            config: Arc::new(RwLock::new(config)),
+            config_watcher: None,
+            reload_sender,
+            reload_receiver,
+            config_file_path: "config.json".to_string(),
+            environment: "development".to_string(),
+            remote_chains: RwLock::new(HashMap::new()),
+            recipient_denylist: Arc::new(RecipientDenylist::default()),
+            recipient_allowlist: Arc::new(RecipientAllowlist::default()),
+            validator_plugins: Arc::new(ValidatorPlugins::default()),
+            version: AtomicU64::new(0),
+            started_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_config_change_invalidates_etag() {
+        let manager = manager(Config::default());
+        let etag = manager.etag();
+        assert!(etag.starts_with("W/\""), "{}", etag);
+        assert_eq!(manager.etag(), etag);
+
+        manager.update_config(Config { port: 4001, ..Config::default() }).await.unwrap();
+        assert_ne!(manager.etag(), etag);
+    }
+
+    #[tokio::test]
+    async fn test_remote_chain_config_fallback_when_unreachable() {
+        let source = source(format!("{}/chains.json", unreachable_url()));
+
+        let local = Config::get_supported_chains().unwrap();
+        let manager = manager(Config { supported_chains: local.clone(), ..Config::default() });
+
+        assert!(!manager.apply_remote_chains(&source).await);
+        let config = manager.get_config().await;
+        assert_eq!(config.supported_chains.len(), local.len());
+        assert_eq!(config.supported_chains[&1114].rpc_url, local[&1114].rpc_url);
+    }
+
+    #[test]
+    fn test_explorer_urls_come_from_chain_config() {
+        let mut config = Config { supported_chains: Config::get_supported_chains().unwrap(), ..Config::default() };
+        let tx_hash = "0xabc123";
+
+        // Known chains keep their explorer links without a hardcoded map
+        assert_eq!(config.explorer_tx_url(1114, tx_hash).as_deref(), Some("https://scan.test2.btcs.network/tx/0xabc123"));
+        assert_eq!(config.explorer_tx_url(84532, tx_hash).as_deref(), Some("https://sepolia.basescan.org/tx/0xabc123"));
+        assert_eq!(config.explorer_tx_url(17000, tx_hash).as_deref(), Some("https://holesky.etherscan.io/tx/0xabc123"));
+        let lisk_explorer = config.supported_chains[&4202].explorer.clone();
+        assert_eq!(config.explorer_tx_url(4202, tx_hash), Some(format!("{}/tx/{}", lisk_explorer, tx_hash)));
+
+        // A chain added through config gets links automatically; trailing slashes are tolerated
+        config.supported_chains.insert(59141, ChainConfig {
+            name: "Linea Sepolia".to_string(),
+            explorer: "https://sepolia.lineascan.build/".to_string(),
+            ..ChainConfig::default()
+        });
+        assert_eq!(config.explorer_tx_url(59141, tx_hash).as_deref(), Some("https://sepolia.lineascan.build/tx/0xabc123"));
+
+        // Unknown chains and chains without an explorer have no link
+        assert_eq!(config.explorer_tx_url(999_999, tx_hash), None);
+        config.supported_chains.get_mut(&59141).unwrap().explorer = String::new();
+        assert_eq!(config.explorer_tx_url(59141, tx_hash), None);
+    }
+}
@@ -1,5 +1,9 @@
-use std::collections::HashMap;
-use std::sync::Arc;
+use crate::infrastructure::config::{LatencySlaConfig, MonitoringHealthConfig};
+use futures_util::FutureExt;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
@@ -25,6 +29,10 @@ pub struct PrometheusMetrics {
     pub requests_successful: u64,
     pub requests_failed: u64,
     pub response_time_avg_ms: f64,
+    #[serde(default)]
+    pub response_time_p95_ms: f64,
+    #[serde(default)]
+    pub response_time_p99_ms: f64,
     pub active_connections: u64,
     pub database_operations: u64,
     pub database_errors: u64,
@@ -82,6 +90,45 @@ pub struct AlertRule {
     pub enabled: bool,
 }
 
+pub const LATENCY_SLA_ALERT: &str = "latency_sla_breach";
+
+/// Latency percentiles over a set of requests
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub samples: usize,
+}
+
+impl LatencyPercentiles {
+    /// Nearest-rank percentiles; `None` for an empty set
+    pub fn from_samples(samples: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let mut sorted: Vec<f64> = samples.into_iter().collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let rank = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1];
+        Some(Self {
+            p95_ms: rank(0.95),
+            p99_ms: rank(0.99),
+            samples: sorted.len(),
+        })
+    }
+}
+
+/// Metrics updates that panicked and were dropped instead of failing the request
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RecordingFailures {
+    pub total: u64,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    /// Operation and panic message of the last failure
+    pub last_failure: Option<String>,
+}
+
+/// Timestamped request latencies in milliseconds
+type LatencySamples = VecDeque<(DateTime<Utc>, f64)>;
+
 #[derive(Debug)]
 pub struct MonitoringManager {
     metrics: Arc<RwLock<PrometheusMetrics>>,
@@ -90,6 +137,16 @@ pub struct MonitoringManager {
     alert_rules: Arc<RwLock<Vec<AlertRule>>>,
     start_time: DateTime<Utc>,
     response_times: Arc<RwLock<Vec<f64>>>,
+    latency_sla: LatencySlaConfig,
+    /// Recent request latencies per route, oldest first
+    route_latencies: Arc<RwLock<HashMap<String, LatencySamples>>>,
+    /// Routes with an open SLA alert, so a sustained breach alerts once
+    sla_breached_routes: Arc<RwLock<HashSet<String>>>,
+    health_config: MonitoringHealthConfig,
+    recording_failures: Arc<Mutex<RecordingFailures>>,
+    /// Makes every metrics update panic, to exercise the best-effort paths
+    #[cfg(test)]
+    fail_recording: std::sync::atomic::AtomicBool,
 }
 
 impl Default for MonitoringManager {
@@ -107,6 +164,13 @@ impl MonitoringManager {
             alert_rules: Arc::new(RwLock::new(Self::default_alert_rules())),
             start_time: Utc::now(),
             response_times: Arc::new(RwLock::new(Vec::new())),
+            latency_sla: LatencySlaConfig::default(),
+            route_latencies: Arc::new(RwLock::new(HashMap::new())),
+            sla_breached_routes: Arc::new(RwLock::new(HashSet::new())),
+            health_config: MonitoringHealthConfig::default(),
+            recording_failures: Arc::new(Mutex::new(RecordingFailures::default())),
+            #[cfg(test)]
+            fail_recording: std::sync::atomic::AtomicBool::new(false),
         };
 
         // Start system metrics collection
@@ -122,6 +186,60 @@ impl MonitoringManager {
         manager
     }
 
+    pub fn with_latency_sla(mut self, latency_sla: LatencySlaConfig) -> Self {
+        self.latency_sla = latency_sla;
+        self
+    }
+
+    pub fn with_health_config(mut self, health_config: MonitoringHealthConfig) -> Self {
+        self.health_config = health_config;
+        self
+    }
+
+    /// Run a metrics update without letting it fail the caller: a panic is logged and
+    /// counted against the subsystem's health instead of unwinding into request handling
+    async fn best_effort(&self, operation: &str, update: impl Future<Output = ()>) {
+        if let Err(panic) = AssertUnwindSafe(update).catch_unwind().await {
+            let message = panic.downcast_ref::<&str>().map(|m| m.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            log::error!("Monitoring {} failed, metric dropped: {}", operation, message);
+            let mut failures = self.recording_failures.lock().unwrap_or_else(|e| e.into_inner());
+            failures.total += 1;
+            failures.last_failure_at = Some(Utc::now());
+            failures.last_failure = Some(format!("{operation}: {message}"));
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn inject_recording_failure(&self, fail: bool) {
+        self.fail_recording.store(fail, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[cfg(test)]
+    fn check_injected_failure(&self) {
+        if self.fail_recording.load(std::sync::atomic::Ordering::SeqCst) {
+            panic!("injected monitoring failure");
+        }
+    }
+
+    #[cfg(not(test))]
+    fn check_injected_failure(&self) {}
+
+    /// Health of the monitoring subsystem itself: degraded while a metrics update has failed
+    /// within the configured window
+    pub fn subsystem_health(&self) -> serde_json::Value {
+        let failures = self.recording_failures.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let window = chrono::Duration::seconds(self.health_config.degraded_window_secs as i64);
+        let degraded = failures.last_failure_at.is_some_and(|at| Utc::now() - at < window);
+        serde_json::json!({
+            "status": if degraded { "degraded" } else { "healthy" },
+            "recording_failures": failures.total,
+            "last_failure_at": failures.last_failure_at.map(|at| at.to_rfc3339()),
+            "last_failure": failures.last_failure,
+        })
+    }
+
     async fn update_system_metrics_internal(system_metrics: &Arc<RwLock<SystemMetrics>>) {
         let mut metrics = system_metrics.write().await;
         
@@ -206,6 +324,11 @@ impl MonitoringManager {
 
 
     pub async fn increment_metric(&self, metric_name: &str) {
+        self.best_effort("increment_metric", self.increment_metric_inner(metric_name)).await;
+    }
+
+    async fn increment_metric_inner(&self, metric_name: &str) {
+        self.check_injected_failure();
         let mut metrics = self.metrics.write().await;
         
         match metric_name {
@@ -238,12 +361,19 @@ impl MonitoringManager {
         
         // Update uptime
         metrics.uptime_seconds = (Utc::now() - self.start_time).num_seconds() as f64;
+        // The alert rules read the metrics again; holding the write lock would deadlock
+        drop(metrics);
         
         // Check alert rules
         self.check_alert_rules().await;
     }
 
     pub async fn record_response_time(&self, response_time_ms: f64) {
+        self.best_effort("record_response_time", self.record_response_time_inner(response_time_ms)).await;
+    }
+
+    async fn record_response_time_inner(&self, response_time_ms: f64) {
+        self.check_injected_failure();
         let mut response_times = self.response_times.write().await;
         response_times.push(response_time_ms);
         
@@ -252,9 +382,82 @@ impl MonitoringManager {
             response_times.remove(0);
         }
         
-        // Update average response time
+        // Update average and tail response times
         let mut metrics = self.metrics.write().await;
         metrics.response_time_avg_ms = response_times.iter().sum::<f64>() / response_times.len() as f64;
+        if let Some(percentiles) = LatencyPercentiles::from_samples(response_times.iter().copied()) {
+            metrics.response_time_p95_ms = percentiles.p95_ms;
+            metrics.response_time_p99_ms = percentiles.p99_ms;
+        }
+    }
+
+    /// Record a request's latency against its route and check the route's p95 against the SLA
+    pub async fn record_route_latency(&self, route: &str, response_time_ms: f64) {
+        self.record_route_latency_at(route, response_time_ms, Utc::now()).await;
+    }
+
+    pub async fn record_route_latency_at(&self, route: &str, response_time_ms: f64, now: DateTime<Utc>) {
+        self.best_effort("record_route_latency", self.record_route_latency_inner(route, response_time_ms, now)).await;
+    }
+
+    async fn record_route_latency_inner(&self, route: &str, response_time_ms: f64, now: DateTime<Utc>) {
+        self.check_injected_failure();
+        let percentiles = {
+            let mut route_latencies = self.route_latencies.write().await;
+            let window_start = now - chrono::Duration::seconds(self.latency_sla.window_secs as i64);
+            for samples in route_latencies.values_mut() {
+                while samples.front().is_some_and(|(at, _)| *at < window_start) {
+                    samples.pop_front();
+                }
+            }
+            route_latencies.retain(|_, samples| !samples.is_empty());
+
+            let samples = route_latencies.entry(route.to_string()).or_default();
+            samples.push_back((now, response_time_ms));
+            if samples.len() < self.latency_sla.min_samples {
+                return;
+            }
+            LatencyPercentiles::from_samples(samples.iter().map(|(_, ms)| *ms))
+        };
+        let Some(percentiles) = percentiles else { return };
+
+        let breached = percentiles.p95_ms > self.latency_sla.p95_threshold_ms;
+        let newly_breached = {
+            let mut breached_routes = self.sla_breached_routes.write().await;
+            if breached {
+                breached_routes.insert(route.to_string())
+            } else {
+                breached_routes.remove(route);
+                false
+            }
+        };
+        if !newly_breached {
+            return;
+        }
+
+        let mut metadata = HashMap::new();
+        metadata.insert("route".to_string(), serde_json::json!(route));
+        metadata.insert("percentile".to_string(), serde_json::json!("p95"));
+        metadata.insert("p95_ms".to_string(), serde_json::json!(percentiles.p95_ms));
+        metadata.insert("p99_ms".to_string(), serde_json::json!(percentiles.p99_ms));
+        metadata.insert("threshold_ms".to_string(), serde_json::json!(self.latency_sla.p95_threshold_ms));
+        metadata.insert("window_secs".to_string(), serde_json::json!(self.latency_sla.window_secs));
+        metadata.insert("samples".to_string(), serde_json::json!(percentiles.samples));
+        self.raise_alert(
+            LATENCY_SLA_ALERT,
+            AlertSeverity::Warning,
+            format!(
+                "p95 latency of {} is {:.0}ms over the last {}s (threshold {:.0}ms)",
+                route, percentiles.p95_ms, self.latency_sla.window_secs, self.latency_sla.p95_threshold_ms
+            ),
+            metadata,
+        ).await;
+    }
+
+    /// p95/p99 of a route over the current window
+    pub async fn route_latency_percentiles(&self, route: &str) -> Option<LatencyPercentiles> {
+        let route_latencies = self.route_latencies.read().await;
+        LatencyPercentiles::from_samples(route_latencies.get(route)?.iter().map(|(_, ms)| *ms))
     }
 
 
@@ -333,6 +536,29 @@ impl MonitoringManager {
         }
     }
 
+    /// Record an alert raised outside the metric rules (e.g. by a background monitor)
+    pub async fn raise_alert(
+        &self,
+        name: &str,
+        severity: AlertSeverity,
+        message: String,
+        metadata: HashMap<String, serde_json::Value>,
+    ) -> Alert {
+        let alert = Alert {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            severity,
+            message,
+            timestamp: Utc::now(),
+            resolved: false,
+            metadata,
+        };
+
+        self.alerts.write().await.push(alert.clone());
+        self.send_notification(&alert).await;
+        alert
+    }
+
     pub async fn get_metrics(&self) -> PrometheusMetrics {
         self.metrics.read().await.clone()
     }
@@ -383,9 +609,9 @@ impl MonitoringManager {
         };
         
         health.insert("status".to_string(), serde_json::Value::String(status.to_string()));
-        health.insert("uptime_seconds".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(metrics.uptime_seconds).unwrap()));
+        health.insert("uptime_seconds".to_string(), serde_json::json!(metrics.uptime_seconds));
         health.insert("memory_usage_bytes".to_string(), serde_json::Value::Number(serde_json::Number::from(metrics.memory_usage_bytes)));
-        health.insert("cpu_usage_percent".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(metrics.cpu_usage_percent).unwrap()));
+        health.insert("cpu_usage_percent".to_string(), serde_json::json!(metrics.cpu_usage_percent));
         health.insert("active_alerts".to_string(), serde_json::Value::Number(serde_json::Number::from(alerts.iter().filter(|a| !a.resolved).count() as u64)));
         
         // Transaction metrics
@@ -395,6 +621,7 @@ impl MonitoringManager {
         tx_metrics.insert("failed".to_string(), serde_json::Value::Number(serde_json::Number::from(metrics.transactions_failed)));
         tx_metrics.insert("broadcasted".to_string(), serde_json::Value::Number(serde_json::Number::from(metrics.transactions_broadcasted)));
         health.insert("transactions".to_string(), serde_json::Value::Object(tx_metrics.into_iter().collect::<serde_json::Map<String, serde_json::Value>>()));
+        health.insert("monitoring".to_string(), self.subsystem_health());
         
         health
     }
@@ -420,6 +647,8 @@ impl Default for PrometheusMetrics {
             requests_successful: 0,
             requests_failed: 0,
             response_time_avg_ms: 0.0,
+            response_time_p95_ms: 0.0,
+            response_time_p99_ms: 0.0,
             active_connections: 0,
             database_operations: 0,
             database_errors: 0,
@@ -463,4 +692,98 @@ impl std::fmt::Display for AlertSeverity {
             AlertSeverity::Critical => write!(f, "CRITICAL"),
         }
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(p95_threshold_ms: f64) -> MonitoringManager {
+        MonitoringManager::new().with_latency_sla(LatencySlaConfig {
+            p95_threshold_ms,
+            window_secs: 60,
+            min_samples: 20,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_failed_recording_is_contained_and_degrades_health() {
+        let manager = MonitoringManager::new();
+        assert_eq!(manager.subsystem_health()["status"], "healthy");
+
+        manager.inject_recording_failure(true);
+        manager.increment_metric("requests_total").await;
+        manager.record_response_time(12.0).await;
+        manager.record_route_latency("/health", 12.0).await;
+        let health = manager.subsystem_health();
+        assert_eq!(health["status"], "degraded");
+        assert_eq!(health["recording_failures"], 3);
+        assert!(health["last_failure"].as_str().unwrap().contains("injected monitoring failure"));
+        assert_eq!(manager.get_health_status().await["monitoring"]["status"], "degraded");
+
+        // Recording resumes once the fault clears; the health flag ages out with the window
+        manager.inject_recording_failure(false);
+        manager.increment_metric("requests_total").await;
+        assert_eq!(manager.get_metrics().await.requests_total, 1);
+        let manager = manager.with_health_config(MonitoringHealthConfig { degraded_window_secs: 0 });
+        assert_eq!(manager.subsystem_health()["status"], "healthy");
+        assert_eq!(manager.subsystem_health()["recording_failures"], 3);
+    }
+
+    #[test]
+    fn test_nearest_rank_percentiles() {
+        let percentiles = LatencyPercentiles::from_samples((1..=100).map(f64::from)).unwrap();
+        assert_eq!(percentiles.p95_ms, 95.0);
+        assert_eq!(percentiles.p99_ms, 99.0);
+        assert_eq!(percentiles.samples, 100);
+        assert!(LatencyPercentiles::from_samples(Vec::new()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_p95_breach_raises_warning_with_route() {
+        let manager = manager(500.0);
+        let now = Utc::now();
+        // 18 fast requests and 2 slow ones: with 20 samples p95 is the 19th fastest
+        for _ in 0..18 {
+            manager.record_route_latency_at("/api/transactions", 40.0, now).await;
+            manager.record_route_latency_at("/health", 5.0, now).await;
+        }
+        assert!(manager.get_alerts(10).await.is_empty());
+        manager.record_route_latency_at("/api/transactions", 900.0, now).await;
+        manager.record_route_latency_at("/api/transactions", 1200.0, now).await;
+
+        let alerts = manager.get_alerts(10).await;
+        assert_eq!(alerts.len(), 1);
+        let alert = &alerts[0];
+        assert_eq!(alert.name, LATENCY_SLA_ALERT);
+        assert!(matches!(alert.severity, AlertSeverity::Warning));
+        assert_eq!(alert.metadata["route"], "/api/transactions");
+        assert_eq!(alert.metadata["percentile"], "p95");
+        assert_eq!(alert.metadata["p95_ms"], 900.0);
+        assert_eq!(alert.metadata["p99_ms"], 1200.0);
+
+        // A sustained breach does not alert on every request
+        manager.record_route_latency_at("/api/transactions", 1500.0, now).await;
+        assert_eq!(manager.get_alerts(10).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_samples_outside_window_are_ignored() {
+        let manager = manager(500.0);
+        let start = Utc::now();
+        for _ in 0..20 {
+            manager.record_route_latency_at("/send_tx", 2000.0, start).await;
+        }
+        assert_eq!(manager.get_alerts(10).await.len(), 1);
+
+        // Once the slow requests age out of the window the route recovers
+        let later = start + chrono::Duration::seconds(61);
+        for _ in 0..20 {
+            manager.record_route_latency_at("/send_tx", 10.0, later).await;
+        }
+        let percentiles = manager.route_latency_percentiles("/send_tx").await.unwrap();
+        assert_eq!(percentiles.samples, 20);
+        assert_eq!(percentiles.p95_ms, 10.0);
+        assert!(manager.sla_breached_routes.read().await.is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::infrastructure::blockchain::manager::BlockchainManager;
+use crate::infrastructure::config::SponsorMonitorConfig;
+use crate::infrastructure::monitoring::manager::{AlertSeverity, MonitoringManager};
+use anyhow::{Result, anyhow};
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+pub const SPONSOR_LOW_BALANCE_ALERT: &str = "sponsor_low_balance";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SponsorBalanceStatus {
+    pub chain_id: u64,
+    pub balance: U256,
+    pub threshold: U256,
+    pub low: bool,
+}
+
+/// Watches the sponsor (gas-paying) address on every chain, raising a critical alert
+/// when its balance drops below the configured threshold and optionally pausing
+/// sponsored broadcasts on that chain until it is topped up.
+pub struct SponsorBalanceMonitor {
+    blockchain_manager: Arc<BlockchainManager>,
+    monitoring_manager: Arc<MonitoringManager>,
+    config: SponsorMonitorConfig,
+    sponsor: Address,
+    /// Open low-balance alert id per chain, so an alert fires once per incident
+    active_alerts: RwLock<HashMap<u64, String>>,
+}
+
+impl SponsorBalanceMonitor {
+    /// Returns `None` when no sponsor address is configured
+    pub fn new(
+        config: SponsorMonitorConfig,
+        blockchain_manager: Arc<BlockchainManager>,
+        monitoring_manager: Arc<MonitoringManager>,
+    ) -> Result<Option<Self>> {
+        let Some(address) = &config.address else {
+            return Ok(None);
+        };
+        let sponsor = Address::from_str(address)
+            .map_err(|e| anyhow!("Invalid sponsor address '{}': {}", address, e))?;
+
+        Ok(Some(Self {
+            blockchain_manager,
+            monitoring_manager,
+            config,
+            sponsor,
+            active_alerts: RwLock::new(HashMap::new()),
+        }))
+    }
+
+    /// Check the sponsor balance on one chain and update alerts and the pause state
+    pub async fn check_chain<M: Middleware>(&self, chain_id: u64, provider: &M) -> Result<SponsorBalanceStatus> {
+        let balance = provider.get_balance(self.sponsor, None).await
+            .map_err(|e| anyhow!("Failed to fetch sponsor balance on chain {}: {}", chain_id, e))?;
+        let threshold = U256::from(self.config.min_balance_wei);
+        let low = balance < threshold;
+
+        let mut active_alerts = self.active_alerts.write().await;
+        if low {
+            if self.config.pause_when_low {
+                self.blockchain_manager.pause_sponsored(chain_id);
+            }
+            if let Entry::Vacant(slot) = active_alerts.entry(chain_id) {
+                let mut metadata = HashMap::new();
+                metadata.insert("chain_id".to_string(), serde_json::json!(chain_id));
+                metadata.insert("sponsor".to_string(), serde_json::json!(format!("{:?}", self.sponsor)));
+                metadata.insert("balance_wei".to_string(), serde_json::json!(balance.to_string()));
+                metadata.insert("threshold_wei".to_string(), serde_json::json!(threshold.to_string()));
+                metadata.insert("sponsored_paused".to_string(), serde_json::json!(self.config.pause_when_low));
+
+                let alert = self.monitoring_manager.raise_alert(
+                    SPONSOR_LOW_BALANCE_ALERT,
+                    AlertSeverity::Critical,
+                    format!(
+                        "Sponsor {:?} balance on chain {} is {} wei, below threshold {} wei",
+                        self.sponsor, chain_id, balance, threshold
+                    ),
+                    metadata,
+                ).await;
+                slot.insert(alert.id);
+            }
+        } else {
+            self.blockchain_manager.resume_sponsored(chain_id);
+            if let Some(alert_id) = active_alerts.remove(&chain_id) {
+                let _ = self.monitoring_manager.resolve_alert(&alert_id).await;
+            }
+        }
+
+        Ok(SponsorBalanceStatus { chain_id, balance, threshold, low })
+    }
+
+    /// Check every configured chain, logging chains whose balance could not be fetched
+    pub async fn check_all(&self) -> Vec<SponsorBalanceStatus> {
+        let mut statuses = Vec::new();
+        for chain_id in self.blockchain_manager.chain_ids() {
+            let Some(provider) = self.blockchain_manager.provider(chain_id) else { continue };
+            match self.check_chain(chain_id, provider).await {
+                Ok(status) => statuses.push(status),
+                Err(e) => log::warn!("{}", e),
+            }
+        }
+        statuses
+    }
+
+    pub fn start(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(self.config.check_interval_secs.max(1)));
+            loop {
+                interval.tick().await;
+                self.check_all().await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::Config;
+
+    const SPONSOR: &str = "0x1111111111111111111111111111111111111111";
+
+    fn monitor(pause_when_low: bool) -> (SponsorBalanceMonitor, Arc<BlockchainManager>, Arc<MonitoringManager>) {
+        let blockchain_manager = Arc::new(BlockchainManager::new(Config::default()).unwrap());
+        let monitoring_manager = Arc::new(MonitoringManager::new());
+        let config = SponsorMonitorConfig {
+            address: Some(SPONSOR.to_string()),
+            min_balance_wei: 1_000_000,
+            check_interval_secs: 60,
+            pause_when_low,
+        };
+        let monitor = SponsorBalanceMonitor::new(config, Arc::clone(&blockchain_manager), Arc::clone(&monitoring_manager))
+            .unwrap()
+            .unwrap();
+        (monitor, blockchain_manager, monitoring_manager)
+    }
+
+    #[tokio::test]
+    async fn test_low_balance_raises_alert_and_pauses_sponsored() {
+        let (monitor, blockchain_manager, monitoring_manager) = monitor(true);
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(10u64)).unwrap();
+        mock.push(U256::from(999_999u64)).unwrap();
+
+        let status = monitor.check_chain(1114, &provider).await.unwrap();
+        assert!(status.low);
+        assert!(blockchain_manager.is_sponsored_paused(1114));
+        assert!(!blockchain_manager.is_sponsored_paused(84532));
+
+        let alerts = monitoring_manager.get_alerts(10).await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].name, SPONSOR_LOW_BALANCE_ALERT);
+        assert!(matches!(alerts[0].severity, AlertSeverity::Critical));
+
+        let err = blockchain_manager.execute_meta_transaction(
+            1114,
+            Address::zero(),
+            Address::zero(),
+            U256::one(),
+            "ref".to_string(),
+            U256::one(),
+            Bytes::default(),
+        ).await.unwrap_err();
+        assert!(err.to_string().contains("paused"));
+
+        // Still low on the next check: no duplicate alert
+        monitor.check_chain(1114, &provider).await.unwrap();
+        assert_eq!(monitoring_manager.get_alerts(10).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_top_up_resumes_sponsored_and_resolves_alert() {
+        let (monitor, blockchain_manager, monitoring_manager) = monitor(true);
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::exp10(18)).unwrap();
+        mock.push(U256::from(5u64)).unwrap();
+
+        assert!(monitor.check_chain(1114, &provider).await.unwrap().low);
+        assert!(blockchain_manager.is_sponsored_paused(1114));
+
+        assert!(!monitor.check_chain(1114, &provider).await.unwrap().low);
+        assert!(!blockchain_manager.is_sponsored_paused(1114));
+        assert!(monitoring_manager.get_alerts(10).await.iter().all(|a| a.resolved));
+    }
+
+    #[tokio::test]
+    async fn test_low_balance_alerts_without_pausing_when_disabled() {
+        let (monitor, blockchain_manager, monitoring_manager) = monitor(false);
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::zero()).unwrap();
+
+        assert!(monitor.check_chain(1114, &provider).await.unwrap().low);
+        assert!(!blockchain_manager.is_sponsored_paused(1114));
+        assert_eq!(monitoring_manager.get_alerts(10).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_monitor_disabled_without_sponsor_address() {
+        let blockchain_manager = Arc::new(BlockchainManager::new(Config::default()).unwrap());
+        let monitoring_manager = Arc::new(MonitoringManager::new());
+        let monitor = SponsorBalanceMonitor::new(SponsorMonitorConfig::default(), blockchain_manager, monitoring_manager).unwrap();
+        assert!(monitor.is_none());
+    }
+}
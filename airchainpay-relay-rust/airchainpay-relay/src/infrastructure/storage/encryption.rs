@@ -0,0 +1,51 @@
+use aes_gcm::aead::{Aead, OsRng, Payload};
+use aes_gcm::{AeadCore, Aes256Gcm, KeyInit, Nonce};
+use anyhow::{Result, anyhow};
+
+/// Marks an encrypted data file: `MAGIC || nonce (12 bytes) || ciphertext || tag (16 bytes)`
+const MAGIC: &[u8] = b"ACPENC1\n";
+const NONCE_LEN: usize = 12;
+
+/// AES-256-GCM encryption of the relay's data files. Every write gets a fresh random
+/// nonce, and the file name is bound in as associated data so one file's contents
+/// cannot be swapped in for another's.
+pub struct StorageCipher {
+    cipher: Aes256Gcm,
+}
+
+impl StorageCipher {
+    /// Build the cipher from a master key given as 64 hex characters (32 bytes)
+    pub fn from_master_key(master_key: &str) -> Result<Self> {
+        let key = hex::decode(master_key.trim().trim_start_matches("0x"))
+            .map_err(|e| anyhow!("Storage master key must be hex: {}", e))?;
+        if key.len() != 32 {
+            return Err(anyhow!("Storage master key must be 32 bytes, got {}", key.len()));
+        }
+        let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| anyhow!("Invalid storage master key: {}", e))?;
+        Ok(Self { cipher })
+    }
+
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    pub fn encrypt(&self, name: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self.cipher
+            .encrypt(&nonce, Payload { msg: plaintext, aad: name.as_bytes() })
+            .map_err(|_| anyhow!("Failed to encrypt {}", name))?;
+        Ok([MAGIC, nonce.as_slice(), &ciphertext].concat())
+    }
+
+    /// Decrypt a file written by `encrypt`; fails if it was modified or written under another name or key
+    pub fn decrypt(&self, name: &str, data: &[u8]) -> Result<Vec<u8>> {
+        let body = data.strip_prefix(MAGIC).ok_or_else(|| anyhow!("{} is not encrypted", name))?;
+        if body.len() < NONCE_LEN {
+            return Err(anyhow!("{} is truncated", name));
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: name.as_bytes() })
+            .map_err(|_| anyhow!("Failed to decrypt {}: wrong key or tampered data", name))
+    }
+}
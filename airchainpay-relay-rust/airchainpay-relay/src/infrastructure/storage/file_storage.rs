@@ -1,11 +1,75 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
+use std::fmt;
+use std::io::Write;
 use std::path::Path;
-use std::sync::Mutex;
-use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use crate::utils::database::DatabaseHealth;
+use crate::domain::error::StorageError;
+use crate::infrastructure::config::DatabaseConfig;
+use crate::infrastructure::storage::encryption::StorageCipher;
+use crate::utils::error_handler::ErrorRecord;
+use ethers::types::U256;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+/// Lifecycle state of a relayed transaction
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionState {
+    Pending,
+    Processing,
+    Retrying,
+    Dropped,
+    Completed,
+    Failed,
+    QueueFailed,
+    Cancelled,
+    Expired,
+}
+
+impl TransactionState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionState::Pending => "pending",
+            TransactionState::Processing => "processing",
+            TransactionState::Retrying => "retrying",
+            TransactionState::Dropped => "dropped",
+            TransactionState::Completed => "completed",
+            TransactionState::Failed => "failed",
+            TransactionState::QueueFailed => "queue_failed",
+            TransactionState::Cancelled => "cancelled",
+            TransactionState::Expired => "expired",
+        }
+    }
+
+    /// Terminal states never transition again
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, TransactionState::Completed | TransactionState::Failed | TransactionState::QueueFailed | TransactionState::Cancelled | TransactionState::Expired)
+    }
+
+    /// Whether moving from `self` to `next` is a legal lifecycle transition
+    pub fn can_transition_to(&self, next: TransactionState) -> bool {
+        use TransactionState::*;
+        matches!(
+            (self, next),
+            (Pending, Processing | Completed | Failed | QueueFailed | Dropped | Cancelled | Expired)
+                | (Processing, Retrying | Dropped | Completed | Failed)
+                | (Retrying, Retrying | Processing | Dropped | Completed | Failed | Cancelled | Expired)
+                | (Dropped, Retrying | Processing | Completed | Failed | Cancelled | Expired)
+        )
+    }
+}
+
+impl fmt::Display for TransactionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Transaction {
@@ -13,10 +77,15 @@ pub struct Transaction {
     pub signed_tx: String,
     pub chain_id: u64,
     pub timestamp: DateTime<Utc>,
-    pub status: String,
+    pub status: TransactionState,
     pub tx_hash: Option<String>,
     pub error_details: Option<String>,
     pub security: TransactionSecurity,
+    /// Times the signed transaction was sent to the network, including re-broadcasts
+    #[serde(default)]
+    pub broadcast_count: u32,
+    #[serde(default)]
+    pub last_broadcast_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -35,19 +104,193 @@ pub struct Metrics {
     pub last_updated: DateTime<Utc>,
 }
 
+/// Callback URL whose endpoint passed the challenge-response verification
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub created_at: DateTime<Utc>,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// Response of the first submission made with a client-supplied `Idempotency-Key`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IdempotencyRecord {
+    pub key: String,
+    /// Hash of the request body the key was first used with
+    #[serde(default)]
+    pub request_hash: String,
+    pub transaction_id: String,
+    /// HTTP status of the original response
+    pub status: u16,
+    pub response: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of claiming an idempotency key for a new submission
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyClaim {
+    /// The key is unused; the caller submits and then completes or releases it
+    New,
+    /// A submission with this key has not finished yet
+    InProgress,
+    /// The key was already used; replay its response
+    Completed(IdempotencyRecord),
+    /// The key was already used with a different request body
+    Mismatch,
+}
+
+/// Indexed on-chain payment event
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PaymentRecord {
+    pub chain_id: u64,
+    pub from: String,
+    pub to: String,
+    /// Decimal amount in the smallest unit
+    pub amount: String,
+    pub payment_reference: String,
+    pub is_relayed: bool,
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub log_index: u64,
+}
+
+/// Key of an on-chain event that has already been acted on
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct ProcessedEvent {
+    pub tx_hash: String,
+    pub log_index: u64,
+}
+
+impl ProcessedEvent {
+    pub fn new(tx_hash: impl Into<String>, log_index: u64) -> Self {
+        Self { tx_hash: tx_hash.into(), log_index }
+    }
+}
+
+/// Filter for payment aggregation; block bounds are inclusive
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PaymentStatsFilter {
+    pub chain_id: Option<u64>,
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct PaymentStats {
+    pub count: u64,
+    pub total_amount: String,
+    pub relayed_count: u64,
+    pub relayed_amount: String,
+    pub direct_count: u64,
+    pub direct_amount: String,
+}
+
+/// Bounded retries for transient storage failures
+#[derive(Debug, Clone, Copy)]
+pub struct StorageRetryPolicy {
+    pub max_attempts: u32,
+    /// Delay before the second attempt; grows linearly with each retry
+    pub backoff: std::time::Duration,
+}
+
+impl Default for StorageRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: std::time::Duration::from_millis(25),
+        }
+    }
+}
+
+impl StorageRetryPolicy {
+    /// Run `op`, retrying transient failures up to `max_attempts` times.
+    /// Permanent failures are returned without retrying. Backs off with a blocking sleep,
+    /// so async code reaches this through `Storage::blocking`.
+    pub fn run<T>(&self, operation: &str, mut op: impl FnMut() -> std::io::Result<T>) -> Result<T, StorageError> {
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    let error = StorageError::from_io(&e);
+                    if !error.is_transient() || attempt >= self.max_attempts {
+                        let record = ErrorRecord::from_storage_error(&error, operation, attempt, self.max_attempts.saturating_sub(1));
+                        log::error!(
+                            "Storage {} failed after {} attempt(s): {} ({:?}, {:?})",
+                            operation, attempt, error, record.error_type, record.severity
+                        );
+                        return Err(error);
+                    }
+                    log::warn!("Storage {} hit a transient error (attempt {}/{}): {}", operation, attempt, self.max_attempts, error);
+                    std::thread::sleep(self.backoff * attempt);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Append-only log of changes to the processor queue; see `Storage::load_pending_queue`
+const PENDING_QUEUE_JOURNAL: &str = "pending_queue.journal";
+/// Whole-queue snapshot written by earlier versions, still read on startup
+const LEGACY_PENDING_QUEUE: &str = "pending_queue.json";
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum PendingQueueOp<T> {
+    Put { id: String, item: T },
+    Remove { id: String },
+}
+
 pub struct Storage {
     data_dir: String,
+    retry: StorageRetryPolicy,
     transactions: Mutex<Vec<Transaction>>,
     metrics: Mutex<Metrics>,
+    payments: Mutex<Vec<PaymentRecord>>,
+    /// Ledger of events already acted on, so reprocessing after a restart is idempotent
+    processed_events: Mutex<HashSet<ProcessedEvent>>,
+    /// Events whose action is running in `process_event_once`
+    events_in_flight: Mutex<HashSet<ProcessedEvent>>,
+    /// Lowercased addresses of wallets registered with the relay
+    registered_wallets: Mutex<BTreeSet<String>>,
+    webhooks: Mutex<Vec<Webhook>>,
+    idempotency_records: Mutex<HashMap<String, IdempotencyRecord>>,
+    /// Idempotency keys claimed by submissions that are still running, with their request hash
+    idempotency_in_flight: Mutex<HashMap<String, String>>,
+    /// Encrypts data files at rest when `DatabaseConfig::enable_encryption` is set
+    cipher: Option<StorageCipher>,
 }
 
 impl Storage {
     pub fn new() -> Result<Self> {
-        let data_dir = "data".to_string();
+        Self::with_data_dir("data")
+    }
+
+    pub fn with_data_dir(data_dir: impl Into<String>) -> Result<Self> {
+        Self::open(data_dir.into(), None)
+    }
+
+    /// Storage in the configured data directory, encrypting its files with the master
+    /// key when `enable_encryption` is set
+    pub fn from_config(config: &DatabaseConfig) -> Result<Self> {
+        let cipher = if config.enable_encryption {
+            let master_key = config.master_key.as_deref()
+                .ok_or_else(|| anyhow!("Storage encryption is enabled but no master key is configured (STORAGE_MASTER_KEY)"))?;
+            Some(StorageCipher::from_master_key(master_key)?)
+        } else {
+            None
+        };
+        Self::open(config.data_dir.clone(), cipher)
+    }
+
+    fn open(data_dir: String, cipher: Option<StorageCipher>) -> Result<Self> {
         fs::create_dir_all(&data_dir)?;
         
         let storage = Storage {
             data_dir,
+            retry: StorageRetryPolicy::default(),
             transactions: Mutex::new(Vec::new()),
             metrics: Mutex::new(Metrics {
                 transactions_received: 0,
@@ -56,57 +299,141 @@ impl Storage {
                 auth_failures: 0,
                 last_updated: Utc::now(),
             }),
+            payments: Mutex::new(Vec::new()),
+            processed_events: Mutex::new(HashSet::new()),
+            events_in_flight: Mutex::new(HashSet::new()),
+            registered_wallets: Mutex::new(BTreeSet::new()),
+            webhooks: Mutex::new(Vec::new()),
+            idempotency_records: Mutex::new(HashMap::new()),
+            idempotency_in_flight: Mutex::new(HashMap::new()),
+            cipher,
         };
         
         storage.load_data()?;
         Ok(storage)
     }
+
+    pub fn with_retry_policy(mut self, retry: StorageRetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Run `op` on the blocking thread pool. Storage writes do file I/O and back off
+    /// between retries with a blocking sleep, which would stall every task sharing a
+    /// runtime thread, so async callers that write go through here.
+    pub async fn blocking<T, F>(self: &Arc<Self>, op: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Storage) -> Result<T> + Send + 'static,
+    {
+        let storage = Arc::clone(self);
+        tokio::task::spawn_blocking(move || op(&storage))
+            .await
+            .map_err(|e| anyhow!("Storage task failed: {}", e))?
+    }
+
+    fn write_file(&self, name: &str, contents: &str) -> Result<()> {
+        let path = format!("{}/{}", self.data_dir, name);
+        let bytes = match &self.cipher {
+            Some(cipher) => cipher.encrypt(name, contents.as_bytes())?,
+            None => contents.as_bytes().to_vec(),
+        };
+        self.retry.run(&format!("write of {}", path), || fs::write(&path, &bytes))?;
+        Ok(())
+    }
+
+    /// Contents of a data file, or `None` when it does not exist yet. With encryption on,
+    /// plaintext files left from before it was enabled are still read; they are
+    /// encrypted the next time they are written.
+    fn read_file(&self, name: &str) -> Result<Option<String>> {
+        let path = format!("{}/{}", self.data_dir, name);
+        if !Path::new(&path).exists() {
+            return Ok(None);
+        }
+        let bytes = self.retry.run(&format!("read of {}", path), || fs::read(&path))?;
+        let bytes = match (&self.cipher, StorageCipher::is_encrypted(&bytes)) {
+            (Some(cipher), true) => cipher.decrypt(name, &bytes)?,
+            (None, true) => return Err(anyhow!("{} is encrypted but storage encryption is disabled", path)),
+            (_, false) => bytes,
+        };
+        Ok(Some(String::from_utf8(bytes).map_err(|e| anyhow!("{} is not valid UTF-8: {}", path, e))?))
+    }
     
     fn load_data(&self) -> Result<()> {
         // Load transactions
-        let tx_file = format!("{}/transactions.json", self.data_dir);
-        if Path::new(&tx_file).exists() {
-            let data = fs::read_to_string(&tx_file)?;
+        if let Some(data) = self.read_file("transactions.json")? {
             let transactions: Vec<Transaction> = serde_json::from_str(&data)?;
             *self.transactions.lock().unwrap() = transactions;
         }
         
         // Load metrics
-        let metrics_file = format!("{}/metrics.json", self.data_dir);
-        if Path::new(&metrics_file).exists() {
-            let data = fs::read_to_string(&metrics_file)?;
+        if let Some(data) = self.read_file("metrics.json")? {
             let metrics: Metrics = serde_json::from_str(&data)?;
             *self.metrics.lock().unwrap() = metrics;
         }
+
+        // Load indexed payments
+        if let Some(data) = self.read_file("payments.json")? {
+            let payments: Vec<PaymentRecord> = serde_json::from_str(&data)?;
+            *self.payments.lock().unwrap() = payments;
+        }
+
+        // Load the processed-events ledger; payments indexed before the ledger existed count as processed
+        let mut processed = self.processed_events.lock().unwrap();
+        if let Some(data) = self.read_file("processed_events.json")? {
+            let events: Vec<ProcessedEvent> = serde_json::from_str(&data)?;
+            processed.extend(events);
+        }
+        processed.extend(
+            self.payments.lock().unwrap().iter().map(|p| ProcessedEvent::new(p.tx_hash.clone(), p.log_index)),
+        );
+
+        // Load registered wallets
+        if let Some(data) = self.read_file("registered_wallets.json")? {
+            let wallets: BTreeSet<String> = serde_json::from_str(&data)?;
+            *self.registered_wallets.lock().unwrap() = wallets;
+        }
+
+        // Load verified webhooks
+        if let Some(data) = self.read_file("webhooks.json")? {
+            let webhooks: Vec<Webhook> = serde_json::from_str(&data)?;
+            *self.webhooks.lock().unwrap() = webhooks;
+        }
+
+        // Load idempotency keys
+        if let Some(data) = self.read_file("idempotency_keys.json")? {
+            let records: Vec<IdempotencyRecord> = serde_json::from_str(&data)?;
+            *self.idempotency_records.lock().unwrap() = records.into_iter().map(|r| (r.key.clone(), r)).collect();
+        }
         
         Ok(())
     }
     
     pub fn save_data(&self) -> Result<()> {
         // Save transactions
-        let tx_file = format!("{}/transactions.json", self.data_dir);
         let transactions = self.transactions.lock().unwrap();
         let data = serde_json::to_string_pretty(&*transactions)?;
-        fs::write(&tx_file, data)?;
+        self.write_file("transactions.json", &data)?;
         
         // Save metrics
-        let metrics_file = format!("{}/metrics.json", self.data_dir);
         let mut metrics = self.metrics.lock().unwrap();
         metrics.last_updated = Utc::now();
         let data = serde_json::to_string_pretty(&*metrics)?;
-        fs::write(&metrics_file, data)?;
+        self.write_file("metrics.json", &data)?;
         
         Ok(())
     }
     
     pub fn save_transaction(&self, transaction: Transaction) -> Result<()> {
-        let mut transactions = self.transactions.lock().unwrap();
-        transactions.push(transaction);
-        
-        // Keep only last 1000 transactions
-        if transactions.len() > 1000 {
-            let len = transactions.len();
-            transactions.drain(0..len - 1000);
+        {
+            let mut transactions = self.transactions.lock().unwrap();
+            transactions.push(transaction);
+
+            // Keep only last 1000 transactions
+            if transactions.len() > 1000 {
+                let len = transactions.len();
+                transactions.drain(0..len - 1000);
+            }
         }
         
         self.save_data()?;
@@ -118,41 +445,84 @@ impl Storage {
         transactions.iter().rev().take(limit).cloned().collect()
     }
     
-    pub fn update_transaction_status(&self, id: &str, status: &str, tx_hash: Option<String>) -> Result<()> {
-        let mut transactions = self.transactions.lock().unwrap();
-        if let Some(tx) = transactions.iter_mut().find(|t| t.id == id) {
-            tx.status = status.to_string();
-            tx.tx_hash = tx_hash;
-            self.save_data()?;
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Transaction not found: {}", id))
-        }
+    pub fn update_transaction_status(&self, id: &str, status: TransactionState, tx_hash: Option<String>) -> Result<()> {
+        self.transition_transaction(id, None, status, tx_hash, None).map(|_| ())
     }
     
-    pub fn update_transaction_status_with_error(&self, id: &str, status: &str, tx_hash: Option<String>, error_details: Option<String>) -> Result<()> {
-        let mut transactions = self.transactions.lock().unwrap();
-        if let Some(tx) = transactions.iter_mut().find(|t| t.id == id) {
-            tx.status = status.to_string();
+    pub fn update_transaction_status_with_error(&self, id: &str, status: TransactionState, tx_hash: Option<String>, error_details: Option<String>) -> Result<()> {
+        self.transition_transaction(id, None, status, tx_hash, error_details).map(|_| ())
+    }
+
+    /// Atomically move a transaction to `next`, rejecting illegal transitions.
+    /// When `expected` is set the update only applies if the current state still
+    /// matches it (compare-and-set). Returns the previous state.
+    pub fn transition_transaction(
+        &self,
+        id: &str,
+        expected: Option<TransactionState>,
+        next: TransactionState,
+        tx_hash: Option<String>,
+        error_details: Option<String>,
+    ) -> Result<TransactionState> {
+        let previous = {
+            let mut transactions = self.transactions.lock().unwrap();
+            let tx = transactions.iter_mut().find(|t| t.id == id)
+                .ok_or_else(|| anyhow!("Transaction not found: {}", id))?;
+
+            let current = tx.status;
+            if let Some(expected) = expected {
+                if current != expected {
+                    return Err(anyhow!("Transaction {} is {} (expected {})", id, current, expected));
+                }
+            }
+            if !current.can_transition_to(next) {
+                return Err(anyhow!("Illegal transaction state transition for {}: {} -> {}", id, current, next));
+            }
+
+            tx.status = next;
             tx.tx_hash = tx_hash;
             tx.error_details = error_details;
-            self.save_data()?;
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Transaction not found: {}", id))
+            current
+        };
+
+        self.save_data()?;
+        Ok(previous)
+    }
+
+    /// Record how often a transaction has been broadcast, as reported by the re-broadcast throttle
+    pub fn record_broadcast(&self, id: &str, broadcast_count: u32, last_broadcast_at: DateTime<Utc>) -> Result<()> {
+        {
+            let mut transactions = self.transactions.lock().unwrap();
+            let tx = transactions.iter_mut().find(|t| t.id == id)
+                .ok_or_else(|| anyhow!("Transaction not found: {}", id))?;
+            tx.broadcast_count = broadcast_count;
+            tx.last_broadcast_at = Some(last_broadcast_at);
         }
+        self.save_data()
+    }
+
+    pub fn get_transaction(&self, id: &str) -> Option<Transaction> {
+        let transactions = self.transactions.lock().unwrap();
+        transactions.iter().find(|t| t.id == id).cloned()
+    }
+
+    pub fn get_transaction_state(&self, id: &str) -> Option<TransactionState> {
+        let transactions = self.transactions.lock().unwrap();
+        transactions.iter().find(|t| t.id == id).map(|t| t.status)
     }
 
-    
     pub fn update_metrics(&self, field: &str, value: u64) -> Result<()> {
-        let mut metrics = self.metrics.lock().unwrap();
-        match field {
-            "transactions_received" => metrics.transactions_received += value,
-            "transactions_processed" => metrics.transactions_processed += value,
-            "transactions_failed" => metrics.transactions_failed += value,
-            "auth_failures" => metrics.auth_failures += value,
-            _ => return Err(anyhow::anyhow!("Unknown metric field: {}", field)),
+        {
+            let mut metrics = self.metrics.lock().unwrap();
+            match field {
+                "transactions_received" => metrics.transactions_received += value,
+                "transactions_processed" => metrics.transactions_processed += value,
+                "transactions_failed" => metrics.transactions_failed += value,
+                "auth_failures" => metrics.auth_failures += value,
+                _ => return Err(anyhow::anyhow!("Unknown metric field: {}", field)),
+            }
         }
+        // save_data takes the metrics lock itself
         self.save_data()?;
         Ok(())
     }
@@ -161,6 +531,201 @@ impl Storage {
         self.metrics.lock().unwrap().clone()
     }
     
+    /// Whether the event has already been acted on
+    pub fn is_event_processed(&self, tx_hash: &str, log_index: u64) -> bool {
+        self.processed_events.lock().unwrap().contains(&ProcessedEvent::new(tx_hash, log_index))
+    }
+
+    /// Run `action` for an event only if it has not been processed before, then record it
+    /// in the ledger. Returns `false` without running `action` for an event already processed
+    /// or being processed by another caller; if `action` fails the event stays unprocessed so
+    /// it can be retried. No lock is held while `action` runs, so it may use this storage.
+    pub fn process_event_once<F>(&self, tx_hash: &str, log_index: u64, action: F) -> Result<bool>
+    where
+        F: FnOnce() -> Result<()>,
+    {
+        let key = ProcessedEvent::new(tx_hash, log_index);
+        {
+            let processed = self.processed_events.lock().unwrap();
+            if processed.contains(&key) || !self.events_in_flight.lock().unwrap().insert(key.clone()) {
+                return Ok(false);
+            }
+        }
+
+        let result = action().and_then(|()| {
+            let mut processed = self.processed_events.lock().unwrap();
+            processed.insert(key.clone());
+            self.persist_processed_events(&processed)
+        });
+        self.events_in_flight.lock().unwrap().remove(&key);
+        result.map(|()| true)
+    }
+
+    fn persist_processed_events(&self, processed: &HashSet<ProcessedEvent>) -> Result<()> {
+        let data = serde_json::to_string_pretty(&processed.iter().collect::<Vec<_>>())?;
+        self.write_file("processed_events.json", &data)?;
+        Ok(())
+    }
+
+    /// Replace the persisted processor queue with `items`, keyed by transaction id, and
+    /// compact the journal down to them
+    pub fn persist_pending_queue<T: Serialize>(&self, items: &[(&str, &T)]) -> Result<()> {
+        let mut journal = String::new();
+        for (id, item) in items {
+            journal.push_str(&self.encode_journal_line(&PendingQueueOp::Put { id: id.to_string(), item })?);
+            journal.push('\n');
+        }
+        let path = format!("{}/{}", self.data_dir, PENDING_QUEUE_JOURNAL);
+        self.retry.run(&format!("write of {}", path), || fs::write(&path, journal.as_bytes()))?;
+        self.remove_legacy_pending_queue()
+    }
+
+    /// Record that `id` was queued (or re-queued) by appending to the queue journal
+    pub fn journal_queued<T: Serialize>(&self, id: &str, item: &T) -> Result<()> {
+        self.append_journal_line(&PendingQueueOp::Put { id: id.to_string(), item })
+    }
+
+    /// Record that `id` left the queue by appending to the queue journal
+    pub fn journal_dequeued(&self, id: &str) -> Result<()> {
+        self.append_journal_line(&PendingQueueOp::<()>::Remove { id: id.to_string() })
+    }
+
+    /// Queue persisted before the last stop or crash, replayed from the journal in the
+    /// order transactions were first queued; empty when there is none. A torn last line
+    /// from a crash mid-append is skipped.
+    pub fn load_pending_queue<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        let mut pending: Vec<T> = match self.read_file(LEGACY_PENDING_QUEUE)? {
+            Some(data) => serde_json::from_str(&data)?,
+            None => Vec::new(),
+        };
+        let path = format!("{}/{}", self.data_dir, PENDING_QUEUE_JOURNAL);
+        if !Path::new(&path).exists() {
+            return Ok(pending);
+        }
+        let data = self.retry.run(&format!("read of {}", path), || fs::read_to_string(&path))?;
+        let mut replayed: Vec<(String, T)> = Vec::new();
+        for (number, line) in data.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let op = match self.decode_journal_line(line) {
+                Ok(op) => op,
+                Err(e) => {
+                    log::warn!("Skipping unreadable line {} of {}: {}", number + 1, path, e);
+                    continue;
+                }
+            };
+            match op {
+                PendingQueueOp::Put { id, item } => match replayed.iter_mut().find(|(queued, _)| *queued == id) {
+                    Some(entry) => entry.1 = item,
+                    None => replayed.push((id, item)),
+                },
+                PendingQueueOp::Remove { id } => replayed.retain(|(queued, _)| *queued != id),
+            }
+        }
+        pending.extend(replayed.into_iter().map(|(_, item)| item));
+        Ok(pending)
+    }
+
+    pub fn clear_pending_queue(&self) -> Result<()> {
+        let path = format!("{}/{}", self.data_dir, PENDING_QUEUE_JOURNAL);
+        if Path::new(&path).exists() {
+            self.retry.run(&format!("removal of {}", path), || fs::remove_file(&path))?;
+        }
+        self.remove_legacy_pending_queue()
+    }
+
+    /// Drop the whole-file queue written by older versions once the journal supersedes it
+    fn remove_legacy_pending_queue(&self) -> Result<()> {
+        let path = format!("{}/{}", self.data_dir, LEGACY_PENDING_QUEUE);
+        if Path::new(&path).exists() {
+            self.retry.run(&format!("removal of {}", path), || fs::remove_file(&path))?;
+        }
+        Ok(())
+    }
+
+    fn append_journal_line<T: Serialize>(&self, op: &PendingQueueOp<T>) -> Result<()> {
+        let mut line = self.encode_journal_line(op)?;
+        line.push('\n');
+        let path = format!("{}/{}", self.data_dir, PENDING_QUEUE_JOURNAL);
+        self.retry.run(&format!("append to {}", path), || {
+            fs::OpenOptions::new().create(true).append(true).open(&path)?.write_all(line.as_bytes())
+        })?;
+        Ok(())
+    }
+
+    /// One journal line: the op as JSON, or with encryption on, the base64 of its ciphertext
+    fn encode_journal_line<T: Serialize>(&self, op: &PendingQueueOp<T>) -> Result<String> {
+        let json = serde_json::to_string(op)?;
+        match &self.cipher {
+            Some(cipher) => Ok(BASE64.encode(cipher.encrypt(PENDING_QUEUE_JOURNAL, json.as_bytes())?)),
+            None => Ok(json),
+        }
+    }
+
+    fn decode_journal_line<T: DeserializeOwned>(&self, line: &str) -> Result<PendingQueueOp<T>> {
+        let line = line.trim();
+        if line.starts_with('{') {
+            return Ok(serde_json::from_str(line)?);
+        }
+        let cipher = self.cipher.as_ref()
+            .ok_or_else(|| anyhow!("{} is encrypted but storage encryption is disabled", PENDING_QUEUE_JOURNAL))?;
+        let bytes = cipher.decrypt(PENDING_QUEUE_JOURNAL, &BASE64.decode(line)?)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Add payment events to the index, skipping ones already in the processed-events
+    /// ledger (same tx hash and log index). Returns how many were new.
+    pub fn record_payments(&self, records: Vec<PaymentRecord>) -> Result<usize> {
+        let mut payments = self.payments.lock().unwrap();
+        let mut processed = self.processed_events.lock().unwrap();
+        let mut added = 0;
+        for record in records {
+            if processed.insert(ProcessedEvent::new(record.tx_hash.clone(), record.log_index)) {
+                payments.push(record);
+                added += 1;
+            }
+        }
+        if added == 0 {
+            return Ok(0);
+        }
+
+        self.write_file("payments.json", &serde_json::to_string_pretty(&*payments)?)?;
+        self.persist_processed_events(&processed)?;
+        Ok(added)
+    }
+
+    /// Aggregate indexed payments matching `filter`
+    pub fn payment_stats(&self, filter: &PaymentStatsFilter) -> Result<PaymentStats> {
+        let payments = self.payments.lock().unwrap();
+        let mut count = 0u64;
+        let mut relayed_count = 0u64;
+        let mut relayed_amount = U256::zero();
+        let mut direct_amount = U256::zero();
+
+        for payment in payments.iter().filter(|p| {
+            filter.chain_id.is_none_or(|chain_id| p.chain_id == chain_id)
+                && filter.from.is_none_or(|from| p.block_number >= from)
+                && filter.to.is_none_or(|to| p.block_number <= to)
+        }) {
+            let amount = U256::from_dec_str(&payment.amount)
+                .map_err(|e| anyhow!("Invalid amount '{}' for payment {}: {}", payment.amount, payment.tx_hash, e))?;
+            count += 1;
+            if payment.is_relayed {
+                relayed_count += 1;
+                relayed_amount += amount;
+            } else {
+                direct_amount += amount;
+            }
+        }
+
+        Ok(PaymentStats {
+            count,
+            total_amount: (relayed_amount + direct_amount).to_string(),
+            relayed_count,
+            relayed_amount: relayed_amount.to_string(),
+            direct_count: count - relayed_count,
+            direct_amount: direct_amount.to_string(),
+        })
+    }
+    
     // Add missing methods for API compatibility
     pub async fn check_health(&self) -> DatabaseHealth {
         // Basic health check - verify data directory exists and is writable
@@ -187,12 +752,102 @@ impl Storage {
         }
     }
 
-    // Get registered mobile wallet instances
+    /// Addresses of the wallets registered with the relay, lowercased and sorted
     pub fn get_registered_wallets(&self) -> Vec<String> {
-        // Return list of registered mobile wallet app instances
-        // This tracks mobile apps that have authenticated with the relay
-        // For now, return empty list - would be populated when mobile apps register
-        Vec::new()
+        self.registered_wallets.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Register a wallet address. Returns `false` if it was already registered.
+    pub fn register_wallet(&self, address: &str) -> Result<bool> {
+        let mut wallets = self.registered_wallets.lock().unwrap();
+        if !wallets.insert(address.to_lowercase()) {
+            return Ok(false);
+        }
+        let data = serde_json::to_string_pretty(&*wallets)?;
+        self.write_file("registered_wallets.json", &data)?;
+        Ok(true)
+    }
+
+    pub fn is_registered_wallet(&self, address: &str) -> bool {
+        self.registered_wallets.lock().unwrap().contains(&address.to_lowercase())
+    }
+
+    pub fn get_webhooks(&self) -> Vec<Webhook> {
+        self.webhooks.lock().unwrap().clone()
+    }
+
+    /// Store a webhook, replacing the one with the same id
+    pub fn save_webhook(&self, webhook: Webhook) -> Result<()> {
+        let mut webhooks = self.webhooks.lock().unwrap();
+        match webhooks.iter_mut().find(|w| w.id == webhook.id) {
+            Some(existing) => *existing = webhook,
+            None => webhooks.push(webhook),
+        }
+        self.persist_webhooks(&webhooks)
+    }
+
+    /// Remove a webhook. Returns `false` if there was none with that id.
+    pub fn delete_webhook(&self, id: &str) -> Result<bool> {
+        let mut webhooks = self.webhooks.lock().unwrap();
+        let before = webhooks.len();
+        webhooks.retain(|w| w.id != id);
+        if webhooks.len() == before {
+            return Ok(false);
+        }
+        self.persist_webhooks(&webhooks)?;
+        Ok(true)
+    }
+
+    fn persist_webhooks(&self, webhooks: &[Webhook]) -> Result<()> {
+        let data = serde_json::to_string_pretty(webhooks)?;
+        self.write_file("webhooks.json", &data)
+    }
+
+    /// Claim an idempotency key for a submission whose body hashes to `request_hash`.
+    /// Records older than `ttl` are dropped first, so an expired key counts as unused.
+    pub fn claim_idempotency_key(&self, key: &str, request_hash: &str, ttl: chrono::Duration) -> Result<IdempotencyClaim> {
+        let mut records = self.idempotency_records.lock().unwrap();
+        let cutoff = Utc::now() - ttl;
+        let before = records.len();
+        records.retain(|_, r| r.created_at > cutoff);
+        if records.len() != before {
+            self.persist_idempotency_records(&records)?;
+        }
+        if let Some(record) = records.get(key) {
+            // Records stored before request hashes were kept match any body
+            if !record.request_hash.is_empty() && record.request_hash != request_hash {
+                return Ok(IdempotencyClaim::Mismatch);
+            }
+            return Ok(IdempotencyClaim::Completed(record.clone()));
+        }
+
+        let mut in_flight = self.idempotency_in_flight.lock().unwrap();
+        match in_flight.get(key) {
+            Some(claimed) if claimed == request_hash => Ok(IdempotencyClaim::InProgress),
+            Some(_) => Ok(IdempotencyClaim::Mismatch),
+            None => {
+                in_flight.insert(key.to_string(), request_hash.to_string());
+                Ok(IdempotencyClaim::New)
+            }
+        }
+    }
+
+    /// Store the response of a claimed key's submission so repeats replay it
+    pub fn complete_idempotency_key(&self, record: IdempotencyRecord) -> Result<()> {
+        let mut records = self.idempotency_records.lock().unwrap();
+        self.idempotency_in_flight.lock().unwrap().remove(&record.key);
+        records.insert(record.key.clone(), record);
+        self.persist_idempotency_records(&records)
+    }
+
+    /// Give up a claimed key without recording a response, so it can be used again
+    pub fn release_idempotency_key(&self, key: &str) {
+        self.idempotency_in_flight.lock().unwrap().remove(key);
+    }
+
+    fn persist_idempotency_records(&self, records: &HashMap<String, IdempotencyRecord>) -> Result<()> {
+        let data = serde_json::to_string_pretty(&records.values().collect::<Vec<_>>())?;
+        self.write_file("idempotency_keys.json", &data)
     }
 }
 
@@ -203,7 +858,7 @@ impl Transaction {
             signed_tx,
             chain_id,
             timestamp: Utc::now(),
-            status: "pending".to_string(),
+            status: TransactionState::Pending,
             tx_hash: None,
             error_details: None,
             security: TransactionSecurity {
@@ -211,6 +866,421 @@ impl Transaction {
                 created_at: Utc::now(),
                 server_id: "default".to_string(),
             },
+            broadcast_count: 0,
+            last_broadcast_at: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::error_handler::{ErrorSeverity, ErrorType};
+
+    fn temp_storage() -> (Storage, String) {
+        let dir = std::env::temp_dir()
+            .join(format!("airchainpay-storage-{}", Uuid::new_v4()))
+            .to_string_lossy()
+            .to_string();
+        (Storage::with_data_dir(dir.clone()).unwrap(), dir)
+    }
+
+    #[test]
+    fn test_completed_cannot_regress_to_pending() {
+        let (storage, dir) = temp_storage();
+        let tx = Transaction::new("0xdeadbeef".to_string(), 84532);
+        let id = tx.id.clone();
+        storage.save_transaction(tx).unwrap();
+
+        storage.update_transaction_status(&id, TransactionState::Processing, None).unwrap();
+        storage.update_transaction_status(&id, TransactionState::Completed, Some("0xabc".to_string())).unwrap();
+
+        assert!(storage.update_transaction_status(&id, TransactionState::Pending, None).is_err());
+        assert!(storage.update_transaction_status(&id, TransactionState::Retrying, None).is_err());
+        assert_eq!(storage.get_transaction_state(&id), Some(TransactionState::Completed));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_compare_and_set_requires_expected_state() {
+        let (storage, dir) = temp_storage();
+        let tx = Transaction::new("0xdeadbeef".to_string(), 84532);
+        let id = tx.id.clone();
+        storage.save_transaction(tx).unwrap();
+
+        let stale = storage.transition_transaction(&id, Some(TransactionState::Processing), TransactionState::Completed, None, None);
+        assert!(stale.is_err());
+
+        let previous = storage.transition_transaction(&id, Some(TransactionState::Pending), TransactionState::Processing, None, None).unwrap();
+        assert_eq!(previous, TransactionState::Pending);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_concurrent_updates_converge() {
+        let (storage, dir) = temp_storage();
+        let storage = Arc::new(storage);
+        let tx = Transaction::new("0xdeadbeef".to_string(), 1114);
+        let id = tx.id.clone();
+        storage.save_transaction(tx).unwrap();
+        storage.update_transaction_status(&id, TransactionState::Processing, None).unwrap();
+
+        let targets = [
+            TransactionState::Completed,
+            TransactionState::Failed,
+            TransactionState::Pending,
+            TransactionState::Retrying,
+            TransactionState::Completed,
+            TransactionState::Dropped,
+        ];
+        let handles: Vec<_> = (0..24)
+            .map(|i| {
+                let storage = Arc::clone(&storage);
+                let id = id.clone();
+                let next = targets[i % targets.len()];
+                std::thread::spawn(move || (next, storage.update_transaction_status(&id, next, None).is_ok()))
+            })
+            .collect();
+        let results: Vec<(TransactionState, bool)> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        // Exactly one terminal update wins and the transaction never regresses to pending
+        let terminal_wins = results.iter().filter(|(next, ok)| *ok && next.is_terminal()).count();
+        assert_eq!(terminal_wins, 1);
+        assert!(results.iter().all(|(next, ok)| !(*ok && *next == TransactionState::Pending)));
+
+        let state = storage.get_transaction_state(&id).unwrap();
+        assert!(state.is_terminal());
+        for next in targets {
+            assert!(storage.update_transaction_status(&id, next, None).is_err());
+        }
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    fn payment(chain_id: u64, block_number: u64, amount: u64, is_relayed: bool, log_index: u64) -> PaymentRecord {
+        PaymentRecord {
+            chain_id,
+            from: "0x1111111111111111111111111111111111111111".to_string(),
+            to: "0x2222222222222222222222222222222222222222".to_string(),
+            amount: amount.to_string(),
+            payment_reference: format!("ref-{}-{}", block_number, log_index),
+            is_relayed,
+            tx_hash: format!("0x{:064x}", block_number),
+            block_number,
+            log_index,
+        }
+    }
+
+    fn seeded_storage() -> (Storage, String) {
+        let (storage, dir) = temp_storage();
+        let added = storage.record_payments(vec![
+            payment(1114, 100, 10, true, 0),
+            payment(1114, 150, 20, false, 0),
+            payment(1114, 200, 30, true, 0),
+            payment(84532, 120, 1000, true, 0),
+            payment(84532, 120, 2000, false, 1),
+        ]).unwrap();
+        assert_eq!(added, 5);
+        (storage, dir)
+    }
+
+    #[test]
+    fn test_payment_stats_aggregates() {
+        let (storage, dir) = seeded_storage();
+
+        let all = storage.payment_stats(&PaymentStatsFilter::default()).unwrap();
+        assert_eq!(all, PaymentStats {
+            count: 5,
+            total_amount: "3060".to_string(),
+            relayed_count: 3,
+            relayed_amount: "1040".to_string(),
+            direct_count: 2,
+            direct_amount: "2020".to_string(),
+        });
+
+        // Re-indexing the same events does not double count
+        assert_eq!(storage.record_payments(vec![payment(1114, 100, 10, true, 0)]).unwrap(), 0);
+        assert_eq!(storage.payment_stats(&PaymentStatsFilter::default()).unwrap().count, 5);
+
+        // Index survives a reload from disk
+        let reloaded = Storage::with_data_dir(dir.clone()).unwrap();
+        assert_eq!(reloaded.payment_stats(&PaymentStatsFilter::default()).unwrap(), all);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_payment_stats_filters_by_chain_and_block_range() {
+        let (storage, dir) = seeded_storage();
+
+        let core = storage.payment_stats(&PaymentStatsFilter { chain_id: Some(1114), ..Default::default() }).unwrap();
+        assert_eq!((core.count, core.total_amount.as_str()), (3, "60"));
+        assert_eq!((core.relayed_count, core.direct_count), (2, 1));
+
+        let ranged = storage.payment_stats(&PaymentStatsFilter { chain_id: Some(1114), from: Some(150), to: Some(200) }).unwrap();
+        assert_eq!((ranged.count, ranged.total_amount.as_str()), (2, "50"));
+        assert_eq!(ranged.relayed_amount, "30");
+
+        let across_chains = storage.payment_stats(&PaymentStatsFilter { chain_id: None, from: Some(110), to: Some(150) }).unwrap();
+        assert_eq!((across_chains.count, across_chains.total_amount.as_str()), (3, "3020"));
+
+        let empty = storage.payment_stats(&PaymentStatsFilter { chain_id: Some(4202), ..Default::default() }).unwrap();
+        assert_eq!((empty.count, empty.total_amount.as_str()), (0, "0"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_same_event_twice_is_processed_once() {
+        let (storage, dir) = temp_storage();
+        let event = payment(1114, 300, 42, true, 3);
+        let mut side_effects = 0;
+
+        for _ in 0..2 {
+            storage.process_event_once(&event.tx_hash, event.log_index, || {
+                side_effects += 1;
+                Ok(())
+            }).unwrap();
         }
+        assert_eq!(side_effects, 1);
+        assert!(storage.is_event_processed(&event.tx_hash, 3));
+        assert!(!storage.is_event_processed(&event.tx_hash, 4));
+
+        // A failed action leaves the event unprocessed so it can be retried
+        let other = payment(1114, 301, 1, false, 0);
+        assert!(storage.process_event_once(&other.tx_hash, 0, || Err(anyhow!("callback failed"))).is_err());
+        assert!(!storage.is_event_processed(&other.tx_hash, 0));
+
+        let _ = fs::remove_dir_all(dir);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_event_action_may_record_payments() {
+        let (storage, dir) = temp_storage();
+        let event = payment(1114, 500, 9, true, 2);
+
+        // The action runs without the ledger locked, so recording the payment doesn't deadlock
+        let ran = storage.process_event_once(&event.tx_hash, event.log_index, || {
+            storage.record_payments(vec![payment(1114, 501, 9, true, 0)]).map(|_| ())
+        }).unwrap();
+        assert!(ran);
+        assert!(storage.is_event_processed(&event.tx_hash, 2));
+        assert_eq!(storage.payment_stats(&PaymentStatsFilter::default()).unwrap().count, 1);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_processed_ledger_survives_restart() {
+        let (storage, dir) = temp_storage();
+        let event = payment(1114, 400, 7, false, 0);
+        assert_eq!(storage.record_payments(vec![event.clone(), event.clone()]).unwrap(), 1);
+        drop(storage);
+
+        // After a restart the indexer re-feeds the same event: no new record, no side effect
+        let restarted = Storage::with_data_dir(dir.clone()).unwrap();
+        assert_eq!(restarted.record_payments(vec![event.clone()]).unwrap(), 0);
+        let ran = restarted.process_event_once(&event.tx_hash, event.log_index, || {
+            panic!("side effect must not run for a processed event")
+        }).unwrap();
+        assert!(!ran);
+        assert_eq!(restarted.payment_stats(&PaymentStatsFilter::default()).unwrap().count, 1);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    fn quick_retry() -> StorageRetryPolicy {
+        StorageRetryPolicy { max_attempts: 3, backoff: std::time::Duration::from_millis(1) }
+    }
+
+    #[test]
+    fn test_transient_failure_succeeds_on_retry() {
+        let mut attempts = 0;
+        let value = quick_retry().run("write", || {
+            attempts += 1;
+            if attempts == 1 {
+                Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "interrupted"))
+            } else {
+                Ok(42)
+            }
+        }).unwrap();
+
+        assert_eq!(value, 42);
+        assert_eq!(attempts, 2);
+    }
+
+    #[test]
+    fn test_permanent_failure_surfaces_immediately() {
+        let mut attempts = 0;
+        let err = quick_retry().run("write", || -> std::io::Result<()> {
+            attempts += 1;
+            Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "read-only"))
+        }).unwrap_err();
+
+        assert_eq!(attempts, 1);
+        assert!(matches!(err, StorageError::PermissionDenied(_)));
+        let record = ErrorRecord::from_storage_error(&err, "write", attempts, 2);
+        assert!(matches!(record.error_type, ErrorType::SecurityViolation));
+        assert_eq!(record.severity, ErrorSeverity::High);
+        assert_eq!(record.retry_count, 0);
+    }
+
+    #[test]
+    fn test_retries_are_bounded() {
+        let mut attempts = 0;
+        let err = quick_retry().run("read", || -> std::io::Result<()> {
+            attempts += 1;
+            Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "disk busy"))
+        }).unwrap_err();
+
+        assert_eq!(attempts, 3);
+        assert!(err.is_transient());
+        let record = ErrorRecord::from_storage_error(&err, "read", attempts, 2);
+        assert!(matches!(record.error_type, ErrorType::Timeout));
+        assert_eq!(record.retry_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_backoff_runs_off_the_runtime_thread() {
+        let (storage, dir) = temp_storage();
+        let storage = Arc::new(storage.with_retry_policy(StorageRetryPolicy { max_attempts: 3, backoff: std::time::Duration::from_millis(100) }));
+        let ticker = tokio::spawn(async {
+            for _ in 0..10 {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        });
+
+        let result = storage.blocking(|storage| {
+            Ok(storage.retry.run("write", || -> std::io::Result<()> {
+                Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "disk busy"))
+            }).is_err())
+        }).await;
+
+        // The single runtime thread kept running other tasks during the 300ms of backoff
+        assert!(result.unwrap());
+        assert!(ticker.is_finished());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    const MASTER_KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    fn encrypted_config(dir: &str, master_key: Option<&str>) -> DatabaseConfig {
+        DatabaseConfig {
+            data_dir: dir.to_string(),
+            enable_encryption: true,
+            master_key: master_key.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_encrypted_records_round_trip() {
+        let (plain, dir) = temp_storage();
+        // A record written before encryption was turned on is still readable
+        let legacy = Transaction::new("0xfeedface".to_string(), 1114);
+        plain.save_transaction(legacy.clone()).unwrap();
+        drop(plain);
+
+        let storage = Storage::from_config(&encrypted_config(&dir, Some(MASTER_KEY))).unwrap();
+        let tx = Transaction::new("0xdeadbeef".to_string(), 84532);
+        storage.save_transaction(tx.clone()).unwrap();
+        storage.register_wallet("0x1111111111111111111111111111111111111111").unwrap();
+
+        for name in ["transactions.json", "metrics.json", "registered_wallets.json"] {
+            let bytes = fs::read(format!("{}/{}", dir, name)).unwrap();
+            assert!(StorageCipher::is_encrypted(&bytes), "{} is not encrypted", name);
+            assert!(serde_json::from_slice::<serde_json::Value>(&bytes).is_err());
+            assert!(!String::from_utf8_lossy(&bytes).contains("0xdeadbeef"));
+        }
+
+        let reopened = Storage::from_config(&encrypted_config(&dir, Some(MASTER_KEY))).unwrap();
+        let ids: Vec<_> = reopened.get_transactions(10).into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![tx.id, legacy.id]);
+        assert_eq!(reopened.get_registered_wallets().len(), 1);
+
+        // Without the right key the data cannot be read
+        let other_key = MASTER_KEY.replace("00", "ff");
+        assert!(Storage::from_config(&encrypted_config(&dir, Some(&other_key))).is_err());
+        assert!(Storage::from_config(&encrypted_config(&dir, None)).is_err());
+        assert!(Storage::with_data_dir(dir.clone()).is_err());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_pending_queue_journal_replays_and_compacts() {
+        let (storage, dir) = temp_storage();
+        // A whole-file queue left by an older version is still picked up
+        fs::write(format!("{}/pending_queue.json", dir), r#"["legacy"]"#).unwrap();
+        storage.journal_queued("a", &"first").unwrap();
+        storage.journal_queued("b", &"second").unwrap();
+        storage.journal_queued("a", &"first, retried").unwrap();
+        storage.journal_dequeued("b").unwrap();
+        storage.journal_queued("c", &"third").unwrap();
+        // A crash mid-append leaves a torn last line
+        fs::OpenOptions::new().append(true).open(format!("{}/pending_queue.journal", dir)).unwrap()
+            .write_all(br#"{"op":"put","id":"d","it"#).unwrap();
+        assert_eq!(storage.load_pending_queue::<String>().unwrap(), vec!["legacy", "first, retried", "third"]);
+
+        storage.persist_pending_queue(&[("c", &"third")]).unwrap();
+        assert!(!Path::new(&format!("{}/pending_queue.json", dir)).exists());
+        assert_eq!(fs::read_to_string(format!("{}/pending_queue.journal", dir)).unwrap().lines().count(), 1);
+        assert_eq!(storage.load_pending_queue::<String>().unwrap(), vec!["third"]);
+
+        // With encryption on, every appended line is sealed on its own
+        let encrypted = Storage::from_config(&encrypted_config(&dir, Some(MASTER_KEY))).unwrap();
+        encrypted.journal_queued("e", &"0xdeadbeef").unwrap();
+        let journal = fs::read_to_string(format!("{}/pending_queue.journal", dir)).unwrap();
+        assert!(!journal.contains("0xdeadbeef"));
+        assert_eq!(encrypted.load_pending_queue::<String>().unwrap(), vec!["third", "0xdeadbeef"]);
+
+        storage.clear_pending_queue().unwrap();
+        assert!(storage.load_pending_queue::<String>().unwrap().is_empty());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_tampered_or_swapped_files_are_rejected() {
+        let cipher = StorageCipher::from_master_key(MASTER_KEY).unwrap();
+        let sealed = cipher.encrypt("metrics.json", b"{}").unwrap();
+        assert_eq!(cipher.decrypt("metrics.json", &sealed).unwrap(), b"{}");
+        // Fresh nonce per write
+        assert_ne!(cipher.encrypt("metrics.json", b"{}").unwrap(), sealed);
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(cipher.decrypt("metrics.json", &tampered).is_err());
+        assert!(cipher.decrypt("payments.json", &sealed).is_err());
+        assert!(StorageCipher::from_master_key("abcd").is_err());
+    }
+
+    #[test]
+    fn test_idempotency_keys_replay_until_they_expire() {
+        let (storage, dir) = temp_storage();
+        let ttl = chrono::Duration::hours(1);
+        assert_eq!(storage.claim_idempotency_key("retry-1", "body-a", ttl).unwrap(), IdempotencyClaim::New);
+        assert_eq!(storage.claim_idempotency_key("retry-1", "body-a", ttl).unwrap(), IdempotencyClaim::InProgress);
+        assert_eq!(storage.claim_idempotency_key("retry-1", "body-b", ttl).unwrap(), IdempotencyClaim::Mismatch);
+
+        let record = IdempotencyRecord {
+            key: "retry-1".to_string(),
+            request_hash: "body-a".to_string(),
+            transaction_id: "tx-1".to_string(),
+            status: 200,
+            response: serde_json::json!({ "transaction_id": "tx-1" }),
+            created_at: Utc::now(),
+        };
+        storage.complete_idempotency_key(record.clone()).unwrap();
+        let reopened = Storage::with_data_dir(dir.clone()).unwrap();
+        assert_eq!(reopened.claim_idempotency_key("retry-1", "body-a", ttl).unwrap(), IdempotencyClaim::Completed(record));
+        // Reusing the key for another body is refused rather than replaying the first response
+        assert_eq!(reopened.claim_idempotency_key("retry-1", "body-b", ttl).unwrap(), IdempotencyClaim::Mismatch);
+        assert_eq!(reopened.claim_idempotency_key("retry-1", "body-a", chrono::Duration::zero()).unwrap(), IdempotencyClaim::New);
+
+        // A released key can be claimed again
+        reopened.release_idempotency_key("retry-1");
+        assert_eq!(reopened.claim_idempotency_key("retry-1", "body-b", ttl).unwrap(), IdempotencyClaim::New);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod file_storage;
+pub mod encryption;
 // pub mod db_storage; 
\ No newline at end of file
@@ -1,25 +1,33 @@
 use actix_web::{App, HttpServer, web};
 
 use std::sync::Arc;
-use airchainpay_relay::infrastructure::config::DynamicConfigManager;
+use airchainpay_relay::infrastructure::config::{DynamicConfigManager, RemoteChainSource};
 use airchainpay_relay::infrastructure::storage::file_storage::Storage;
 use airchainpay_relay::infrastructure::blockchain::manager::BlockchainManager;
+use airchainpay_relay::infrastructure::blockchain::calldata::CalldataDecoder;
 use airchainpay_relay::domain::auth::AuthManager;
 use airchainpay_relay::infrastructure::monitoring::manager::MonitoringManager;
+use airchainpay_relay::infrastructure::monitoring::sponsor::SponsorBalanceMonitor;
 use airchainpay_relay::utils::error_handler::EnhancedErrorHandler;
 use airchainpay_relay::utils::backup::BackupManager;
 use airchainpay_relay::utils::audit::AuditLogger;
 use airchainpay_relay::infrastructure::logger::Logger;
-use airchainpay_relay::app::transaction_service::TransactionProcessor;
+use airchainpay_relay::app::transaction_service::{TransactionProcessor, TransactionProcessorConfig};
+use airchainpay_relay::app::fee_accounting::FeeAccountant;
+use airchainpay_relay::app::webhooks::WebhookRegistry;
 use airchainpay_relay::utils::backup::BackupConfig;
 use airchainpay_relay::middleware::metrics::MetricsMiddleware;
 use airchainpay_relay::middleware::error_handling::ErrorHandlingMiddleware;
 use airchainpay_relay::middleware::rate_limiting::RateLimitingMiddleware;
 use airchainpay_relay::middleware::ComprehensiveSecurityMiddleware;
+use airchainpay_relay::middleware::request_id::RequestIdMiddleware;
 use airchainpay_relay::api::*;
 use airchainpay_relay::api::handlers::transaction::{
     validate_inputs, simple_send_tx, get_transaction_details, 
-    get_transaction_status, get_user_transactions, get_supported_chains, get_chain_info, get_transaction_by_hash
+    get_transaction_status, get_user_transactions, get_supported_chains, get_chain_info, get_chain_eta, get_transaction_by_hash, get_onchain_transaction_status,
+    get_contract_payments, get_payment_stats, get_queue, delete_queued_transaction, get_accounting_summary,
+    submit_transaction_bundle, register_webhook, list_webhooks, delete_webhook, transaction_status_ws,
+    generate_token, refresh_token,
 };
 use airchainpay_relay::utils::animated_ascii;
 use std::env;
@@ -46,6 +54,14 @@ async fn main() -> std::io::Result<()> {
         }
     };
     
+    // Merge the central chain registry, if configured, before anything reads supported_chains
+    if let Some(source) = RemoteChainSource::from_env() {
+        log::info!("🌐 Fetching chain config from {}", source.url);
+        if !config_manager.apply_remote_chains(&source).await {
+            log::warn!("⚠️ Remote chain config unavailable, using local chain config");
+        }
+    }
+
     // Get initial configuration
     let config = config_manager.get_config().await;
     log::info!("✅ Configuration loaded successfully");
@@ -80,7 +96,7 @@ async fn main() -> std::io::Result<()> {
     log::info!("✅ All contract addresses validated successfully");
     
     // Initialize storage with error handling
-    let storage = match Storage::new() {
+    let storage = match Storage::from_config(&config.database) {
         Ok(storage) => {
             log::info!("✅ Storage initialized successfully");
             Arc::new(storage)
@@ -90,7 +106,20 @@ async fn main() -> std::io::Result<()> {
             return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("Storage initialization failed: {}", e)));
         }
     };
-    
+    for wallet in &config.registered_sender.wallets {
+        if !airchainpay_relay::infrastructure::blockchain::ethereum::validate_ethereum_address(wallet) {
+            log::error!("❌ Invalid registered wallet address: '{}'", wallet);
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("Invalid registered wallet address: {}", wallet)));
+        }
+        if let Err(e) = storage.register_wallet(wallet) {
+            log::error!("❌ Failed to register wallet {}: {}", wallet, e);
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("Wallet registration failed: {}", e)));
+        }
+    }
+    if config.registered_sender.require_registered {
+        log::info!("🔒 Only registered wallets may submit transactions ({} registered)", storage.get_registered_wallets().len());
+    }
+
     // Initialize blockchain manager with error handling
     let blockchain_manager = match BlockchainManager::new(config.clone()) {
         Ok(manager) => {
@@ -103,14 +132,43 @@ async fn main() -> std::io::Result<()> {
         }
     };
     
+    BlockchainManager::start_health_refresh(Arc::clone(&blockchain_manager));
+    
+    if config.sponsor_nonce.eager_sync {
+        let blockchain_manager = Arc::clone(&blockchain_manager);
+        tokio::spawn(async move { blockchain_manager.sync_sponsor_nonces().await });
+    }
+    
+    let webhook_registry = Arc::new(WebhookRegistry::new(config.webhooks.clone(), Arc::clone(&storage)));
+    
+    let calldata_decoder = match CalldataDecoder::from_config(&config.calldata_decoder) {
+        Ok(decoder) => Arc::new(decoder),
+        Err(e) => {
+            log::error!("❌ Failed to load calldata ABIs: {}", e);
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("Calldata decoder initialization failed: {}", e)));
+        }
+    };
+    
     // Initialize auth manager
     let auth_manager = Arc::new(AuthManager::new());
     log::info!("✅ Auth manager initialized successfully");
     
     // Initialize monitoring manager
-    let monitoring_manager = Arc::new(MonitoringManager::new());
+    let monitoring_manager = Arc::new(MonitoringManager::new()
+        .with_latency_sla(config.latency_sla.clone())
+        .with_health_config(config.monitoring_health.clone()));
     log::info!("✅ Monitoring manager initialized successfully");
     
+    // Watch the sponsor balance so broadcasts don't fail silently when it runs dry
+    match SponsorBalanceMonitor::new(config.sponsor.clone(), Arc::clone(&blockchain_manager), Arc::clone(&monitoring_manager)) {
+        Ok(Some(monitor)) => {
+            Arc::new(monitor).start();
+            log::info!("✅ Sponsor balance monitor started");
+        }
+        Ok(None) => log::info!("ℹ️ No SPONSOR_ADDRESS set, sponsor balance monitor disabled"),
+        Err(e) => log::warn!("⚠️ Sponsor balance monitor not started: {}", e),
+    }
+    
     // Initialize backup manager
     let backup_config = BackupConfig::default();
     let backup_manager = Arc::new(BackupManager::new(backup_config, "data".to_string())
@@ -125,20 +183,30 @@ async fn main() -> std::io::Result<()> {
     let audit_logger = Arc::new(AuditLogger::new("audit.log".to_string(), 10000)
         .with_monitoring(Arc::clone(&monitoring_manager)));
     log::info!("✅ Audit logger initialized successfully");
+    config_manager.recipient_denylist().attach_audit_logger(Arc::clone(&audit_logger));
     
     // Initialize enhanced error handler
     let error_handler = Arc::new(EnhancedErrorHandler::new());
     log::info!("✅ Error handler initialized successfully");
     
     // Initialize enhanced transaction processor
+    let mut processor_config = TransactionProcessorConfig::default();
+    if let Some(secs) = std::env::var("PRIORITY_AGING_INTERVAL_SECS").ok().and_then(|v| v.parse().ok()) {
+        processor_config.priority_aging_interval = std::time::Duration::from_secs(secs);
+    }
+    let fee_accountant = Arc::new(FeeAccountant::new(config.fee_markup.clone()));
     let transaction_processor = Arc::new(TransactionProcessor::new(
         Arc::clone(&blockchain_manager),
         Arc::clone(&storage),
-        None, // Use default config
-    ));
+        Some(processor_config),
+    ).with_fee_accountant(Arc::clone(&fee_accountant))
+    .with_bundle_config(config.bundle.clone())
+    .with_webhooks(Arc::clone(&webhook_registry)));
     log::info!("✅ Transaction processor initialized successfully");
+    Arc::clone(&webhook_registry).spawn_delivery(transaction_processor.subscribe_status_updates());
     
-    // Start the transaction processor with error handling
+    // Start the transaction processor with error handling; it re-queues anything the
+    // previous run left unfinished
     if let Err(e) = transaction_processor.start().await {
         log::error!("❌ Failed to start transaction processor: {}", e);
         return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("Transaction processor startup failed: {}", e)));
@@ -152,19 +220,29 @@ async fn main() -> std::io::Result<()> {
     log::info!("📊 Environment: {}", config.environment);
     log::info!("🔗 Supported chains: {}", config.supported_chains.len());
     
+    // The server closure takes ownership of these; keep handles for the shutdown report
+    let shutdown_processor = Arc::clone(&transaction_processor);
+    let shutdown_audit_logger = Arc::clone(&audit_logger);
+    
+    let rate_limit_keys = config.rate_limits.per_key_limits.clone();
+
     HttpServer::new(move || {
         App::new()
             // Global built-in middleware only
             .wrap(actix_web::middleware::Logger::default())
             .wrap(actix_web::middleware::Compress::default())
             .wrap(actix_cors::Cors::permissive())
+            .wrap(RequestIdMiddleware)
             .app_data(web::Data::new(Arc::clone(&storage)))
             .app_data(web::Data::new(Arc::clone(&blockchain_manager)))
+            .app_data(web::Data::new(Arc::clone(&calldata_decoder)))
             .app_data(web::Data::new(Arc::clone(&auth_manager)))
             .app_data(web::Data::new(Arc::clone(&monitoring_manager)))
             .app_data(web::Data::new(Arc::clone(&backup_manager)))
             .app_data(web::Data::new(Arc::clone(&audit_logger)))
             .app_data(web::Data::new(Arc::clone(&transaction_processor)))
+            .app_data(web::Data::new(Arc::clone(&fee_accountant)))
+            .app_data(web::Data::new(Arc::clone(&webhook_registry)))
             .app_data(web::Data::new(Arc::clone(&config_manager)))
             // Health endpoints (no custom middleware)
             .service(health)
@@ -175,6 +253,8 @@ async fn main() -> std::io::Result<()> {
             .service(health_metrics)
             .service(contract_health_check)
             .service(detailed_contract_health_check)
+            .service(generate_token)
+            .service(refresh_token)
             // API endpoints with custom middleware
             .service(
                 web::scope("/api")
@@ -191,7 +271,7 @@ async fn main() -> std::io::Result<()> {
                         100, // 100 requests per window
                         10,  // 10 burst requests
                         std::time::Duration::from_secs(60) // 1 minute window
-                    ))
+                    ).with_key_limits(rate_limit_keys.clone()))
                     .service(submit_transaction)
                     .service(legacy_submit_transaction)
                     .service(test_transaction)
@@ -232,15 +312,45 @@ async fn main() -> std::io::Result<()> {
                     .service(get_transactions)
                     .service(get_transaction_details)
                     .service(get_transaction_status)
+                    .service(transaction_status_ws)
                     .service(get_user_transactions)
                     .service(get_supported_chains)
                     .service(get_chain_info)
+                    .service(get_chain_eta)
+                    .service(get_contract_payments)
+                    .service(get_payment_stats)
+                    .service(get_queue)
+                    .service(delete_queued_transaction)
+                    .service(submit_transaction_bundle)
+                    .service(get_accounting_summary)
+                    .service(register_webhook)
+                    .service(list_webhooks)
+                    .service(delete_webhook)
                     .service(get_transaction_by_hash)
+                    .service(get_onchain_transaction_status)
                     .service(get_metrics)
                     .service(get_devices)
             )
     })
     .bind(("0.0.0.0", port))?
     .run()
-    .await
+    .await?;
+
+    log::info!("🛑 Server stopped, shutting down transaction processor");
+    let mut report = shutdown_processor.shutdown().await;
+    let details = serde_json::to_value(&report)
+        .ok()
+        .and_then(|value| value.as_object().cloned())
+        .map(|fields| fields.into_iter().collect())
+        .unwrap_or_default();
+    if let Err(e) = shutdown_audit_logger.log_system_operation("shutdown", report.is_clean(), None, details).await {
+        report.errors.push(format!("Failed to flush audit log: {}", e));
+    }
+    report.log();
+    if let Ok(path) = env::var("SHUTDOWN_REPORT_FILE") {
+        if let Err(e) = report.write_to_file(&path) {
+            log::error!("❌ Failed to write shutdown report to {}: {}", path, e);
+        }
+    }
+    Ok(())
 }
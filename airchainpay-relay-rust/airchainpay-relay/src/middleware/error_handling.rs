@@ -5,6 +5,7 @@ use actix_web::{
 };
 use std::sync::Arc;
 use crate::utils::error_handler::{ErrorType, ErrorSeverity, ErrorRecord, EnhancedErrorHandler, CriticalPath};
+use crate::middleware::request_id::{current_or_new_request_id, current_request_id};
 use futures_util::future::{LocalBoxFuture, Ready, ready};
 use serde_json::json;
 use chrono::Utc;
@@ -96,14 +97,15 @@ where
                 }
             }
 
-            // Extract request information before calling service
-            let request_info = req.request().clone();
-            
             // Execute the service with error handling
             match service.call(req).await {
                 Ok(response) => {
                     let duration = start_time.elapsed();
                     println!("Request completed: {} {} - {}ms", method, path, duration.as_millis());
+                    // Handler errors arrive as responses; record the server-side ones
+                    if let Some(error) = response.response().error().filter(|_| response.status().is_server_error()) {
+                        error_handler.record_error(request_error_record(&path, &method, duration, error.to_string())).await;
+                    }
                     Ok(response.map_into_boxed_body())
                 }
                 Err(error) => {
@@ -112,34 +114,7 @@ where
                     
                     // Categorize and record the error
                     let severity = ErrorSeverity::High;
-                    let category = ErrorType::Unknown;
-                    
-                    let mut context = std::collections::HashMap::new();
-                    context.insert("path".to_string(), path.clone());
-                    context.insert("method".to_string(), method.clone());
-                    context.insert("duration_ms".to_string(), duration.as_millis().to_string());
-                    
-                    // Record error in error handler
-                    let error_record = ErrorRecord {
-                        id: uuid::Uuid::new_v4().to_string(),
-                        timestamp: Utc::now(),
-                        path: CriticalPath::TransactionProcessing,
-                        error_type: category,
-                        error_message: error_msg.clone(),
-                        context: context.clone(),
-                        severity: severity.clone(),
-                        retry_count: 0,
-                        max_retries: 0,
-                        resolved: false,
-                        resolution_time: None,
-                        stack_trace: None,
-                        user_id: None,
-                        device_id: None,
-                        transaction_id: None,
-                        chain_id: None,
-                        ip_address: None,
-                        component: get_component_from_path(&path),
-                    };
+                    let error_record = request_error_record(&path, &method, duration, error_msg.clone());
                     let _ = error_handler.record_error(error_record).await;
 
                     // Return appropriate error response based on severity
@@ -151,7 +126,7 @@ where
                                     "error": "Internal server error",
                                     "message": "A critical error occurred",
                                     "timestamp": Utc::now().to_rfc3339(),
-                                    "request_id": uuid::Uuid::new_v4().to_string(),
+                                    "request_id": current_or_new_request_id(),
                                 }))
                         }
                         ErrorSeverity::High => {
@@ -161,7 +136,7 @@ where
                                     "error": "Service error",
                                     "message": "A high severity error occurred",
                                     "timestamp": Utc::now().to_rfc3339(),
-                                    "request_id": uuid::Uuid::new_v4().to_string(),
+                                    "request_id": current_or_new_request_id(),
                                 }))
                         }
                         ErrorSeverity::Medium => {
@@ -171,7 +146,7 @@ where
                                     "error": "Request error",
                                     "message": "A medium severity error occurred",
                                     "timestamp": Utc::now().to_rfc3339(),
-                                    "request_id": uuid::Uuid::new_v4().to_string(),
+                                    "request_id": current_or_new_request_id(),
                                 }))
                         }
                         ErrorSeverity::Low => {
@@ -181,7 +156,7 @@ where
                                     "error": "Request error",
                                     "message": "A low severity error occurred",
                                     "timestamp": Utc::now().to_rfc3339(),
-                                    "request_id": uuid::Uuid::new_v4().to_string(),
+                                    "request_id": current_or_new_request_id(),
                                 }))
                         }
                         ErrorSeverity::Fatal => {
@@ -191,18 +166,52 @@ where
                                     "error": "Fatal error occurred",
                                     "message": "A fatal error occurred",
                                     "timestamp": Utc::now().to_rfc3339(),
-                                    "request_id": uuid::Uuid::new_v4().to_string(),
+                                    "request_id": current_or_new_request_id(),
                                 }))
                         }
                     };
 
-                    Ok(ServiceResponse::new(request_info, error_response.map_into_boxed_body()))
+                    // Holding a clone of the request across the call would stop routing from
+                    // borrowing it mutably, so the response is handed back as an error
+                    Err(actix_web::error::InternalError::from_response(error_msg, error_response).into())
                 }
             }
         })
     }
 }
 
+/// Error record for a failed request, tagged with the request's correlation id
+fn request_error_record(path: &str, method: &str, duration: std::time::Duration, error_msg: String) -> ErrorRecord {
+    let mut context = std::collections::HashMap::new();
+    context.insert("path".to_string(), path.to_string());
+    context.insert("method".to_string(), method.to_string());
+    context.insert("duration_ms".to_string(), duration.as_millis().to_string());
+    if let Some(request_id) = current_request_id() {
+        context.insert("request_id".to_string(), request_id);
+    }
+
+    ErrorRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: Utc::now(),
+        path: CriticalPath::TransactionProcessing,
+        error_type: ErrorType::Unknown,
+        error_message: error_msg,
+        context,
+        severity: ErrorSeverity::High,
+        retry_count: 0,
+        max_retries: 0,
+        resolved: false,
+        resolution_time: None,
+        stack_trace: None,
+        user_id: None,
+        device_id: None,
+        transaction_id: None,
+        chain_id: None,
+        ip_address: None,
+        component: get_component_from_path(path),
+    }
+}
+
 /// Check if an endpoint is critical and needs circuit breaker protection
 pub fn is_critical_endpoint(path: &str) -> bool {
     let critical_paths = [
@@ -256,14 +265,14 @@ pub async fn global_error_handler(error: Error) -> HttpResponse {
             "error": "Internal server error",
             "message": error_msg,
             "timestamp": Utc::now().to_rfc3339(),
-            "request_id": uuid::Uuid::new_v4().to_string(),
+            "request_id": current_or_new_request_id(),
         })
     } else {
         json!({
             "error": "Internal server error",
             "message": "An unexpected error occurred",
             "timestamp": Utc::now().to_rfc3339(),
-            "request_id": uuid::Uuid::new_v4().to_string(),
+            "request_id": current_or_new_request_id(),
         })
     };
 
@@ -279,7 +288,7 @@ impl ErrorResponseBuilder {
             "error": "Bad request",
             "message": message,
             "timestamp": Utc::now().to_rfc3339(),
-            "request_id": uuid::Uuid::new_v4().to_string(),
+            "request_id": current_or_new_request_id(),
         }))
     }
 
@@ -288,7 +297,7 @@ impl ErrorResponseBuilder {
             "error": "Unauthorized",
             "message": message,
             "timestamp": Utc::now().to_rfc3339(),
-            "request_id": uuid::Uuid::new_v4().to_string(),
+            "request_id": current_or_new_request_id(),
         }))
     }
 
@@ -297,7 +306,7 @@ impl ErrorResponseBuilder {
             "error": "Forbidden",
             "message": message,
             "timestamp": Utc::now().to_rfc3339(),
-            "request_id": uuid::Uuid::new_v4().to_string(),
+            "request_id": current_or_new_request_id(),
         }))
     }
 
@@ -306,7 +315,25 @@ impl ErrorResponseBuilder {
             "error": "Not found",
             "message": message,
             "timestamp": Utc::now().to_rfc3339(),
-            "request_id": uuid::Uuid::new_v4().to_string(),
+            "request_id": current_or_new_request_id(),
+        }))
+    }
+
+    pub fn conflict(message: &str) -> HttpResponse {
+        HttpResponse::Conflict().json(json!({
+            "error": "Conflict",
+            "message": message,
+            "timestamp": Utc::now().to_rfc3339(),
+            "request_id": current_or_new_request_id(),
+        }))
+    }
+
+    pub fn unprocessable_entity(message: &str) -> HttpResponse {
+        HttpResponse::UnprocessableEntity().json(json!({
+            "error": "Unprocessable entity",
+            "message": message,
+            "timestamp": Utc::now().to_rfc3339(),
+            "request_id": current_or_new_request_id(),
         }))
     }
 
@@ -315,7 +342,7 @@ impl ErrorResponseBuilder {
             "error": "Internal server error",
             "message": message,
             "timestamp": Utc::now().to_rfc3339(),
-            "request_id": uuid::Uuid::new_v4().to_string(),
+            "request_id": current_or_new_request_id(),
         }))
     }
 
@@ -324,7 +351,7 @@ impl ErrorResponseBuilder {
             "error": "Service unavailable",
             "message": message,
             "timestamp": Utc::now().to_rfc3339(),
-            "request_id": uuid::Uuid::new_v4().to_string(),
+            "request_id": current_or_new_request_id(),
         }))
     }
 
@@ -336,7 +363,7 @@ impl ErrorResponseBuilder {
                 "message": message,
                 "retry_after": retry_after,
                 "timestamp": Utc::now().to_rfc3339(),
-                "request_id": uuid::Uuid::new_v4().to_string(),
+                "request_id": current_or_new_request_id(),
             }))
     }
 }
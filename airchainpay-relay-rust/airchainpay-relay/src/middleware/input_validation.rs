@@ -12,18 +12,6 @@ use lazy_static::lazy_static;
 use actix_web::body::BoxBody;
 
 lazy_static! {
-    static ref SQL_INJECTION_PATTERNS: Vec<Regex> = vec![
-        Regex::new(r"(?i)SELECT.*FROM").unwrap(),
-        Regex::new(r"(?i)INSERT.*INTO").unwrap(),
-        Regex::new(r"(?i)UPDATE.*SET").unwrap(),
-        Regex::new(r"(?i)DELETE.*FROM").unwrap(),
-        Regex::new(r"(?i)DROP.*TABLE").unwrap(),
-        Regex::new(r"(?i)CREATE.*TABLE").unwrap(),
-        Regex::new(r"(?i)UNION.*SELECT").unwrap(),
-        Regex::new(r"(?i)EXEC.*\(").unwrap(),
-        Regex::new(r"(?i)EXECUTE.*\(").unwrap(),
-    ];
-
     static ref XSS_PATTERNS: Vec<Regex> = vec![
         Regex::new(r"(?i)<script").unwrap(),
         Regex::new(r"(?i)javascript:").unwrap(),
@@ -36,43 +24,180 @@ lazy_static! {
         Regex::new(r"(?i)alert\s*\(").unwrap(),
     ];
 
-    static ref COMMAND_INJECTION_PATTERNS: Vec<Regex> = vec![
-        Regex::new(r"[;&|`$()]").unwrap(),
-        Regex::new(r"&&").unwrap(),
-        Regex::new(r"\|\|").unwrap(),
-        Regex::new(r">\s*\w+").unwrap(),
-        Regex::new(r"<\s*\w+").unwrap(),
-    ];
+    static ref INTEGER: Regex = Regex::new(r"^[0-9]{1,20}$").unwrap();
+    static ref DECIMAL: Regex = Regex::new(r"^[0-9]{1,78}(\.[0-9]{1,78})?$").unwrap();
+    static ref HEX_QUANTITY: Regex = Regex::new(r"^0[xX][0-9a-fA-F]{1,64}$").unwrap();
+    static ref PATH_SEGMENT: Regex = Regex::new(r"^[A-Za-z0-9_.:~@%-]*$").unwrap();
+}
 
-    static ref PATH_TRAVERSAL_PATTERNS: Vec<Regex> = vec![
-        Regex::new(r"\.\.").unwrap(),
-        Regex::new(r"\.\.").unwrap(),
-        Regex::new(r"\\").unwrap(),
-        Regex::new(r"//").unwrap(),
-    ];
+/// What a request field must look like, decided by its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// 0x-prefixed 20-byte hex address
+    Address,
+    /// 0x-prefixed 32-byte hex hash
+    Hash,
+    /// 0x-prefixed hex bytes of any even length (raw transactions, calldata, signatures)
+    HexData,
+    /// Unsigned integer (chain ids, block numbers, paging)
+    Integer,
+    /// Non-negative decimal amount or 0x-prefixed hex quantity (as in JSON-RPC)
+    Amount,
+    /// Free text, only bounded in length and checked for markup
+    Text,
+}
+
+impl FieldKind {
+    /// Kind of a query parameter or JSON field; names are matched case-insensitively and
+    /// with or without underscores, so `chainId` and `chain_id` are the same field
+    pub fn for_field(name: &str) -> Self {
+        let name: String = name.chars().filter(|c| *c != '_' && *c != '-').flat_map(char::to_lowercase).collect();
+        match name.as_str() {
+            "address" | "from" | "to" | "fromaddress" | "toaddress" | "contractaddress" | "walletaddress"
+            | "sender" | "recipient" | "tokenaddress" => FieldKind::Address,
+            "hash" | "txhash" | "transactionhash" | "blockhash" => FieldKind::Hash,
+            "signedtx" | "signedtxs" | "rawtx" | "signature" | "data" | "calldata" | "input" => FieldKind::HexData,
+            "chainid" | "nonce" | "blocknumber" | "fromblock" | "toblock" | "limit" | "offset" | "page" | "validuntil" => FieldKind::Integer,
+            "amount" | "value" | "gasprice" | "gaslimit" => FieldKind::Amount,
+            _ => FieldKind::Text,
+        }
+    }
+
+    /// Kind of a query parameter on a route, for routes that use a common name differently
+    pub fn for_route(path: &str, name: &str) -> Self {
+        ROUTE_FIELD_KINDS.iter()
+            .find(|(route, field, _)| path.trim_end_matches('/').ends_with(route) && name.eq_ignore_ascii_case(field))
+            .map(|(_, _, kind)| *kind)
+            .unwrap_or_else(|| Self::for_field(name))
+    }
+}
+
+/// Route-specific field kinds: `/payments/stats` takes `from`/`to` as unix timestamps
+const ROUTE_FIELD_KINDS: &[(&str, &str, FieldKind)] = &[
+    ("/payments/stats", "from", FieldKind::Integer),
+    ("/payments/stats", "to", FieldKind::Integer),
+];
+
+fn validate_hex(value: &str, bytes: Option<usize>) -> Result<(), String> {
+    let digits = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X"))
+        .ok_or_else(|| "must be 0x-prefixed hex".to_string())?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("must be 0x-prefixed hex".to_string());
+    }
+    match bytes {
+        Some(bytes) if digits.len() != bytes * 2 => Err(format!("must be {} bytes of hex", bytes)),
+        None if digits.len() % 2 != 0 => Err("must be an even number of hex digits".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Validate one named field by its kind. Free text is bounded by `max_text_length` and may
+/// not carry markup; ordinary words ("for order 123") are never rejected.
+pub fn validate_field(name: &str, value: &str, config: &ValidationConfig) -> Result<(), String> {
+    validate_field_as(FieldKind::for_field(name), name, value, config)
+}
+
+fn validate_field_as(kind: FieldKind, name: &str, value: &str, config: &ValidationConfig) -> Result<(), String> {
+    let checked = match kind {
+        FieldKind::Address => validate_hex(value, Some(20)),
+        FieldKind::Hash => validate_hex(value, Some(32)),
+        FieldKind::HexData => validate_hex(value, None),
+        FieldKind::Integer if INTEGER.is_match(value) => Ok(()),
+        FieldKind::Integer => Err("must be an unsigned integer".to_string()),
+        FieldKind::Amount if DECIMAL.is_match(value) || HEX_QUANTITY.is_match(value) => Ok(()),
+        FieldKind::Amount => Err("must be a non-negative decimal number or 0x-prefixed hex quantity".to_string()),
+        FieldKind::Text => validate_text(value, config),
+    };
+    checked.map_err(|e| format!("Invalid field '{}': {}", name, e))
+}
+
+fn validate_text(value: &str, config: &ValidationConfig) -> Result<(), String> {
+    if value.chars().count() > config.max_text_length {
+        return Err(format!("longer than {} characters", config.max_text_length));
+    }
+    if value.chars().any(|c| c.is_control() && c != '\n' && c != '\t') {
+        return Err("contains control characters".to_string());
+    }
+    if config.enable_xss_check {
+        if let Some(pattern) = XSS_PATTERNS.iter().find(|p| p.is_match(value)) {
+            return Err(format!("markup not allowed: {}", pattern.as_str()));
+        }
+    }
+    if let Some(pattern) = config.blocked_patterns.iter().find(|p| value.contains(p.as_str())) {
+        return Err(format!("blocked pattern: {}", pattern));
+    }
+    Ok(())
+}
+
+/// Validate a URL path segment: ids, hashes, numbers and names, without traversal
+pub fn validate_path_segment(segment: &str, config: &ValidationConfig) -> Result<(), String> {
+    if segment.len() > config.max_text_length {
+        return Err(format!("Path segment longer than {} characters", config.max_text_length));
+    }
+    if segment == ".." || segment.contains("..") {
+        return Err("Path traversal detected".to_string());
+    }
+    if !PATH_SEGMENT.is_match(segment) {
+        return Err(format!("Invalid characters in path segment '{}'", segment));
+    }
+    if segment.starts_with("0x") {
+        validate_hex(segment, None).map_err(|e| format!("Invalid path segment '{}': {}", segment, e))?;
+    }
+    Ok(())
+}
+
+/// Validate a JSON request body field by field. The body is read here and handed back to
+/// the request, so handlers still extract it; non-JSON bodies are left alone.
+pub async fn validate_json_body(req: &mut ServiceRequest, config: &ValidationConfig) -> Result<(), String> {
+    let is_json = req.headers().get(actix_web::http::header::CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok())
+        .is_some_and(|ct| ct.contains("application/json"));
+    if !is_json {
+        return Ok(());
+    }
+
+    let body = req.extract::<actix_web::web::Bytes>().await
+        .map_err(|e| format!("Invalid request body: {e}"))?;
+    let checked = if body.is_empty() {
+        Ok(())
+    } else {
+        std::str::from_utf8(&body)
+            .map_err(|_| "Request body is not valid UTF-8".to_string())
+            .and_then(|json| validate_json_input(json, config))
+    };
+    req.set_payload(actix_web::dev::Payload::from(body));
+    checked
+}
+
+/// Validate every query parameter of a request to `path` by route and name; values are
+/// checked after URL decoding
+pub fn validate_query(path: &str, query_string: &str, config: &ValidationConfig) -> Result<(), String> {
+    let params = actix_web::web::Query::<Vec<(String, String)>>::from_query(query_string)
+        .map_err(|e| format!("Invalid query string: {}", e))?;
+    for (name, value) in params.iter() {
+        validate_field_as(FieldKind::for_route(path, name), name, value, config)?;
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
 pub struct ValidationConfig {
     pub max_input_length: usize,
+    /// Longest free-text field or path segment accepted
+    pub max_text_length: usize,
     pub allowed_content_types: Vec<String>,
     pub blocked_patterns: Vec<String>,
-    pub enable_sql_injection_check: bool,
     pub enable_xss_check: bool,
-    pub enable_command_injection_check: bool,
-    pub enable_path_traversal_check: bool,
 }
 
 impl Default for ValidationConfig {
     fn default() -> Self {
         Self {
             max_input_length: 10000,
+            max_text_length: 1024,
             allowed_content_types: vec!["application/json".to_string(), "application/x-www-form-urlencoded".to_string()],
             blocked_patterns: vec!["<script>".to_string(), "javascript:".to_string()],
-            enable_sql_injection_check: true,
             enable_xss_check: true,
-            enable_command_injection_check: true,
-            enable_path_traversal_check: true,
         }
     }
 }
@@ -133,7 +258,7 @@ where
         self.service.poll_ready(cx)
     }
 
-    fn call(&self, req: ServiceRequest) -> Self::Future {
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
         let service = Arc::clone(&self.service);
         let config = self.config.clone();
 
@@ -170,25 +295,20 @@ where
             }
 
             // Validate URL parameters
-            for (_key, value) in req.query_string().split('&').filter_map(|pair| {
-                let mut parts = pair.splitn(2, '=');
-                Some((parts.next()?, parts.next()?))
-            }) {
-                if let Err(e) = validate_input(value, &config) {
-                    return Ok(req.into_response(
-                        HttpResponse::BadRequest()
-                            .json(serde_json::json!({
-                                "error": "Invalid input",
-                                "message": e
-                            }))
-                            .map_into_boxed_body()
-                    ));
-                }
+            if let Err(e) = validate_query(req.path(), req.query_string(), &config) {
+                return Ok(req.into_response(
+                    HttpResponse::BadRequest()
+                        .json(serde_json::json!({
+                            "error": "Invalid input",
+                            "message": e
+                        }))
+                        .map_into_boxed_body()
+                ));
             }
 
             // Validate path parameters
             for segment in req.path().split('/') {
-                if let Err(e) = validate_input(segment, &config) {
+                if let Err(e) = validate_path_segment(segment, &config) {
                     return Ok(req.into_response(
                         HttpResponse::BadRequest()
                             .json(serde_json::json!({
@@ -200,6 +320,18 @@ where
                 }
             }
 
+            // Validate JSON body fields
+            if let Err(e) = validate_json_body(&mut req, &config).await {
+                return Ok(req.into_response(
+                    HttpResponse::BadRequest()
+                        .json(serde_json::json!({
+                            "error": "Invalid input",
+                            "message": e
+                        }))
+                        .map_into_boxed_body()
+                ));
+            }
+
             // Call the inner service
             let fut = service.call(req);
             let res = fut.await?;
@@ -208,53 +340,6 @@ where
     }
 }
 
-fn validate_input(input: &str, config: &ValidationConfig) -> Result<(), String> {
-    // Check for SQL injection
-    if config.enable_sql_injection_check {
-        for pattern in &*SQL_INJECTION_PATTERNS {
-            if pattern.is_match(input) {
-                return Err(format!("SQL injection detected: {}", pattern.as_str()));
-            }
-        }
-    }
-
-    // Check for XSS
-    if config.enable_xss_check {
-        for pattern in &*XSS_PATTERNS {
-            if pattern.is_match(input) {
-                return Err(format!("XSS attack detected: {}", pattern.as_str()));
-            }
-        }
-    }
-
-    // Check for command injection
-    if config.enable_command_injection_check {
-        for pattern in &*COMMAND_INJECTION_PATTERNS {
-            if pattern.is_match(input) {
-                return Err(format!("Command injection detected: {}", pattern.as_str()));
-            }
-        }
-    }
-
-    // Check for path traversal
-    if config.enable_path_traversal_check {
-        for pattern in &*PATH_TRAVERSAL_PATTERNS {
-            if pattern.is_match(input) {
-                return Err(format!("Path traversal detected: {}", pattern.as_str()));
-            }
-        }
-    }
-
-    // Check for blocked patterns
-    for pattern in &config.blocked_patterns {
-        if input.contains(pattern) {
-            return Err(format!("Blocked pattern detected: {pattern}"));
-        }
-    }
-
-    Ok(())
-}
-
 pub fn sanitize_input(input: &str) -> String {
     // HTML entity encoding
     input
@@ -270,26 +355,106 @@ pub fn sanitize_input(input: &str) -> String {
 pub fn validate_json_input(json_str: &str, config: &ValidationConfig) -> Result<(), String> {
     // Parse JSON and validate each value
     match serde_json::from_str::<serde_json::Value>(json_str) {
-        Ok(value) => validate_json_value(&value, config),
+        Ok(value) => validate_json_value(None, &value, config),
         Err(e) => Err(format!("Invalid JSON: {e}")),
     }
 }
 
-fn validate_json_value(value: &serde_json::Value, config: &ValidationConfig) -> Result<(), String> {
+/// Strings are validated by the name of the field holding them (array items by the
+/// array's field); strings without a field name are free text
+fn validate_json_value(field: Option<&str>, value: &serde_json::Value, config: &ValidationConfig) -> Result<(), String> {
     match value {
-        serde_json::Value::String(s) => validate_input(s, config),
+        serde_json::Value::String(s) => match field {
+            Some(name) => validate_field(name, s, config),
+            None => validate_text(s, config),
+        },
         serde_json::Value::Object(obj) => {
-            for (_, v) in obj {
-                validate_json_value(v, config)?;
+            for (k, v) in obj {
+                validate_json_value(Some(k), v, config)?;
             }
             Ok(())
         }
         serde_json::Value::Array(arr) => {
             for v in arr {
-                validate_json_value(v, config)?;
+                validate_json_value(field, v, config)?;
             }
             Ok(())
         }
         _ => Ok(()),
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "0x8589427373d6d84e98730d7795d8f6f8731fda16";
+
+    #[test]
+    fn test_ordinary_words_pass_free_text_fields() {
+        let config = ValidationConfig::default();
+        for memo in ["for order 123", "Rock AND roll OR jazz", "select your plan, update settings"] {
+            assert!(validate_field("memo", memo, &config).is_ok(), "{memo}");
+        }
+        let body = serde_json::json!({ "to": ADDRESS, "amount": "1.5", "chain_id": 1114, "memo": "for order 123" });
+        assert!(validate_json_input(&body.to_string(), &config).is_ok());
+        assert!(validate_query("/api/transactions", "memo=for%20order%20123&limit=10", &config).is_ok());
+    }
+
+    #[test]
+    fn test_script_tag_in_json_body_is_rejected() {
+        let config = ValidationConfig::default();
+        let body = serde_json::json!({ "to": ADDRESS, "memo": "<script>alert('x')</script>" });
+        assert!(validate_json_input(&body.to_string(), &config).is_err());
+        let nested = serde_json::json!({ "items": [{ "note": "<SCRIPT src=x>" }] });
+        assert!(validate_json_input(&nested.to_string(), &config).is_err());
+    }
+
+    #[test]
+    fn test_fields_are_validated_by_kind() {
+        let config = ValidationConfig::default();
+        assert!(validate_field("to", ADDRESS, &config).is_ok());
+        assert!(validate_field("toAddress", "0x1234", &config).is_err());
+        assert!(validate_field("tx_hash", &format!("0x{}", "ab".repeat(32)), &config).is_ok());
+        assert!(validate_field("tx_hash", ADDRESS, &config).is_err());
+        assert!(validate_field("signed_tx", "0xabc", &config).is_err());
+        assert!(validate_field("chain_id", "1114", &config).is_ok());
+        assert!(validate_field("chainId", "1114 OR 1=1", &config).is_err());
+        assert!(validate_field("amount", "0.25", &config).is_ok());
+        assert!(validate_field("amount", "-1", &config).is_err());
+        assert!(validate_field("value", "0xde0b6b3a7640000", &config).is_ok());
+        assert!(validate_field("gasPrice", "0x", &config).is_err());
+        assert!(validate_field("value", "0xzz", &config).is_err());
+        assert!(validate_field("memo", &"x".repeat(config.max_text_length + 1), &config).is_err());
+
+        // The payment stats route takes `from`/`to` as timestamps
+        assert!(validate_query("/api/payments/stats", "from=1700000000&to=1800000000", &config).is_ok());
+        assert!(validate_query("/api/contract/payments", "from_address=1700000000", &config).is_err());
+    }
+
+    #[test]
+    fn test_bundle_of_long_signed_transactions_passes() {
+        let config = ValidationConfig::default();
+        // A contract call's signed transaction is well past the free-text limit
+        let signed_tx = format!("0x02f9{}", "ab".repeat(config.max_text_length));
+        let bundle = serde_json::json!({ "chain_id": 1114, "signed_txs": [signed_tx, "0x02f86b"] });
+        assert!(validate_json_input(&bundle.to_string(), &config).is_ok());
+        let call = serde_json::json!({ "to": ADDRESS, "data": format!("0xa9059cbb{}", "00".repeat(1024)) });
+        assert!(validate_json_input(&call.to_string(), &config).is_ok());
+
+        let bad = serde_json::json!({ "chain_id": 1114, "signed_txs": ["0x02f8<script>"] });
+        assert!(validate_json_input(&bad.to_string(), &config).is_err());
+        assert!(validate_field("calldata", "0xabc", &config).is_err());
+    }
+
+    #[test]
+    fn test_path_segments() {
+        let config = ValidationConfig::default();
+        for segment in ["transaction", "0a1b-uuid", "1114", ADDRESS, ""] {
+            assert!(validate_path_segment(segment, &config).is_ok(), "{segment}");
+        }
+        for segment in ["..", "0xnothex", "a<b", "x;rm"] {
+            assert!(validate_path_segment(segment, &config).is_err(), "{segment}");
+        }
+    }
+}
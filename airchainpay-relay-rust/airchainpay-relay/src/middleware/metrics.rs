@@ -11,6 +11,9 @@ use futures_util::future::ready;
 use crate::infrastructure::monitoring::manager::MonitoringManager;
 use std::marker::PhantomData;
 
+/// Route label for requests that match no route, so scanning random URLs can't add labels
+pub const UNMATCHED_ROUTE: &str = "<unmatched>";
+
 #[derive(Clone)]
 pub struct MetricsMiddleware {
     monitoring_manager: Arc<MonitoringManager>,
@@ -73,6 +76,8 @@ where
 
             // Record request details
             let path = req.path().to_string();
+            // Route pattern (e.g. `/api/transaction/{transaction_id}`) keeps per-route stats bounded
+            let route = req.match_pattern().unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
             let method = req.method().to_string();
             let client_ip = req.connection_info().peer_addr().unwrap_or("unknown").to_string();
 
@@ -84,6 +89,7 @@ where
             let response_time = start_time.elapsed();
             let response_time_ms = response_time.as_millis() as f64;
             monitoring_manager.record_response_time(response_time_ms).await;
+            monitoring_manager.record_route_latency(&route, response_time_ms).await;
 
             match res {
                 Ok(res) => {
@@ -145,4 +151,43 @@ impl MetricsCollector {
     pub fn new(monitoring_manager: Arc<MonitoringManager>) -> Self {
         Self { monitoring_manager }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn test_requests_succeed_when_monitoring_fails() {
+        let monitoring_manager = Arc::new(MonitoringManager::new());
+        monitoring_manager.inject_recording_failure(true);
+        let app = test::init_service(
+            App::new()
+                .wrap(MetricsMiddleware::new(Arc::clone(&monitoring_manager)))
+                .route("/transactions", web::get().to(|| async { HttpResponse::Ok().body("ok") })),
+        ).await;
+
+        let response = test::call_service(&app, test::TestRequest::get().uri("/transactions").to_request()).await;
+        assert!(response.status().is_success());
+        assert_eq!(test::read_body(response).await, "ok");
+        assert_eq!(monitoring_manager.subsystem_health()["status"], "degraded");
+    }
+
+    #[actix_web::test]
+    async fn test_unmatched_paths_share_one_route_label() {
+        let monitoring_manager = Arc::new(MonitoringManager::new());
+        let app = test::init_service(
+            App::new()
+                .wrap(MetricsMiddleware::new(Arc::clone(&monitoring_manager)))
+                .route("/transaction/{id}", web::get().to(|| async { HttpResponse::Ok().finish() })),
+        ).await;
+
+        for uri in ["/transaction/1", "/transaction/2", "/scan/a", "/scan/b", "/wp-admin.php"] {
+            test::call_service(&app, test::TestRequest::get().uri(uri).to_request()).await;
+        }
+        assert_eq!(monitoring_manager.route_latency_percentiles("/transaction/{id}").await.unwrap().samples, 2);
+        assert_eq!(monitoring_manager.route_latency_percentiles(UNMATCHED_ROUTE).await.unwrap().samples, 3);
+        assert!(monitoring_manager.route_latency_percentiles("/scan/a").await.is_none());
+    }
+}
@@ -15,6 +15,7 @@ use futures::task::{Context, Poll};
 pub mod error_handling;
 pub mod input_validation;
 pub mod rate_limiting;
+pub mod request_id;
 pub mod metrics;
 pub mod security;
 pub mod critical_error_middleware;
@@ -99,13 +100,12 @@ where
         self.service.poll_ready(cx)
     }
 
-    fn call(&self, req: ServiceRequest) -> Self::Future {
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
         let service = Arc::clone(&self.service);
         let config = self.config.clone();
 
         Box::pin(async move {
             // Apply comprehensive security checks
-            let req = req;
             
             // 1. Request size validation
             if let Some(content_length) = req.headers().get("content-length") {
@@ -173,6 +173,19 @@ where
                 ));
             }
 
+            // 5. JSON body validation
+            if let Err(validation_error) = input_validation::validate_json_body(&mut req, &config.input_validation).await {
+                return Ok(req.into_response(
+                    HttpResponse::BadRequest()
+                        .json(serde_json::json!({
+                            "error": "Input validation failed",
+                            "message": validation_error,
+                            "timestamp": std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()
+                        }))
+                        .map_into_boxed_body()
+                ));
+            }
+
             // Call the inner service
             let fut = service.call(req);
             let res = fut.await?;
@@ -224,67 +237,13 @@ fn log_security_event(
     }
 }
 
-fn validate_request_input(req: &ServiceRequest, _config: &EnhancedSecurityConfig) -> Result<(), String> {
-    // Validate URL parameters
-    for (key, value) in req.query_string().split('&').filter_map(|pair| {
-        let mut parts = pair.splitn(2, '=');
-        Some((parts.next()?, parts.next()?))
-    }) {
-        if let Err(e) = validate_input(value) {
-            return Err(format!("Invalid query parameter '{key}': {e}"));
-        }
-    }
+/// Query parameters are validated by route and field name (hex for addresses and hashes,
+/// numbers for ids and amounts, bounded free text otherwise) and path segments by shape
+fn validate_request_input(req: &ServiceRequest, config: &EnhancedSecurityConfig) -> Result<(), String> {
+    input_validation::validate_query(req.path(), req.query_string(), &config.input_validation)?;
 
-    // Validate path parameters
     for segment in req.path().split('/') {
-        if let Err(e) = validate_input(segment) {
-            return Err(format!("Invalid path segment: {e}"));
-        }
-    }
-
-    Ok(())
-}
-
-fn validate_input(input: &str) -> Result<(), String> {
-    // Check for SQL injection patterns
-    let sql_patterns = [
-        "SELECT", "INSERT", "UPDATE", "DELETE", "DROP", "CREATE",
-        "UNION", "OR", "AND", "EXEC", "EXECUTE", "SCRIPT"
-    ];
-
-    let input_upper = input.to_uppercase();
-    for pattern in &sql_patterns {
-        if input_upper.contains(pattern) {
-            return Err(format!("SQL injection pattern detected: {pattern}"));
-        }
-    }
-
-    // Check for XSS patterns
-    let xss_patterns = [
-        "<script>", "javascript:", "onload=", "onerror=",
-        "onclick=", "onmouseover=", "eval(", "alert("
-    ];
-
-    let input_lower = input.to_lowercase();
-    for pattern in &xss_patterns {
-        if input_lower.contains(pattern) {
-            return Err(format!("XSS pattern detected: {pattern}"));
-        }
-    }
-
-    // Check for path traversal
-    if input.contains("..") || input.contains("\\") || input.contains("//") {
-        return Err("Path traversal detected".to_string());
-    }
-
-    // Check for command injection
-    let cmd_patterns = [";", "|", "&", "`", "$", "&&", "||", ">", "<"];
-
-    let input_lower = input.to_lowercase();
-    for pattern in &cmd_patterns {
-        if input_lower.contains(pattern) {
-            return Err(format!("Command injection pattern detected: {pattern}"));
-        }
+        input_validation::validate_path_segment(segment, &config.input_validation)?;
     }
 
     Ok(())
@@ -312,4 +271,46 @@ where
         actix_web::http::header::HeaderValue::from_static("1; mode=block"),
     );
     res
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App};
+
+    #[actix_web::test]
+    async fn test_query_validation_is_field_specific() {
+        let app = test::init_service(
+            App::new()
+                .wrap(ComprehensiveSecurityMiddleware::new(EnhancedSecurityConfig::default()))
+                .route("/api/transactions", web::get().to(HttpResponse::Ok)),
+        ).await;
+
+        let req = test::TestRequest::get().uri("/api/transactions?memo=for%20order%20123&limit=5").to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+
+        let req = test::TestRequest::get().uri("/api/transactions?chain_id=1%20OR%201%3D1").to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), actix_web::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_json_body_is_validated_and_passed_on() {
+        let app = test::init_service(
+            App::new()
+                .wrap(ComprehensiveSecurityMiddleware::new(EnhancedSecurityConfig::default()))
+                .route("/api/echo", web::post().to(|body: web::Json<serde_json::Value>| async move {
+                    HttpResponse::Ok().json(body.into_inner())
+                })),
+        ).await;
+
+        let payload = serde_json::json!({ "value": "0xde0b6b3a7640000", "memo": "for order 123" });
+        let req = test::TestRequest::post().uri("/api/echo").set_json(&payload).to_request();
+        let echoed: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(echoed, payload);
+
+        let req = test::TestRequest::post().uri("/api/echo")
+            .set_json(serde_json::json!({ "memo": "<script>alert(1)</script>" }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), actix_web::http::StatusCode::BAD_REQUEST);
+    }
+}
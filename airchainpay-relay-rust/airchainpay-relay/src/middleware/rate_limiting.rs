@@ -18,11 +18,18 @@ pub struct RateLimitEntry {
     pub burst_count: u32,
 }
 
+/// Header carrying the caller's API key
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Budgets are tracked per principal: the subject of a valid bearer JWT (unique to the API key
+/// it was issued for), else an API key listed in the per-key limits, else the client IP.
+/// Unknown API keys count against the IP so that rotating made-up keys does not buy a fresh budget.
 #[derive(Debug, Clone)]
 pub struct RateLimitingMiddleware {
     rate_limit: u32,
     burst_limit: u32,
     window_size: Duration,
+    key_limits: Arc<HashMap<String, u32>>,
 }
 
 impl RateLimitingMiddleware {
@@ -31,8 +38,49 @@ impl RateLimitingMiddleware {
             rate_limit,
             burst_limit,
             window_size,
+            key_limits: Arc::new(HashMap::new()),
         }
     }
+
+    /// Requests per window for specific API keys or JWT subjects, see `RateLimitConfig::per_key_limits`.
+    /// A key's budget also applies to the tokens `/auth/token` issued for it.
+    pub fn with_key_limits(mut self, key_limits: HashMap<String, u32>) -> Self {
+        let token_subjects: Vec<(String, u32)> = key_limits.iter()
+            .map(|(key, limit)| (crate::domain::auth::api_key_subject(key), *limit))
+            .collect();
+        let mut key_limits = key_limits;
+        key_limits.extend(token_subjects);
+        self.key_limits = Arc::new(key_limits);
+        self
+    }
+}
+
+/// Counter key for `req` and the identity its budget is looked up by, if it is not an IP
+fn principal(req: &ServiceRequest, key_limits: &HashMap<String, u32>) -> (String, Option<String>) {
+    let bearer = req.headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    // Tokens from `/auth/token` carry a subject derived from the caller's API key
+    let claims = bearer.and_then(|token| match req.app_data::<actix_web::web::Data<Arc<crate::domain::auth::AuthManager>>>() {
+        Some(auth_manager) => auth_manager.validate_token(token).ok(),
+        None => crate::domain::auth::verify_jwt_token(token).ok(),
+    });
+    if let Some(claims) = claims {
+        return (format!("sub:{}", claims.sub), Some(claims.sub));
+    }
+
+    let api_key = req.headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|key| key_limits.contains_key(*key));
+    if let Some(api_key) = api_key {
+        return (format!("key:{}", api_key), Some(api_key.to_string()));
+    }
+
+    let client_ip = req.connection_info().peer_addr().unwrap_or("unknown").to_string();
+    (format!("ip:{}", client_ip), None)
 }
 
 impl<S, B> Transform<S, ServiceRequest> for RateLimitingMiddleware
@@ -53,7 +101,9 @@ where
             rate_limit: self.rate_limit,
             burst_limit: self.burst_limit,
             window_size: self.window_size,
+            key_limits: Arc::clone(&self.key_limits),
             limits: Arc::new(RwLock::new(HashMap::new())),
+            last_eviction: Arc::new(std::sync::Mutex::new(Instant::now())),
             _phantom: std::marker::PhantomData,
         }))
     }
@@ -64,7 +114,11 @@ pub struct RateLimitingService<S, B> {
     rate_limit: u32,
     burst_limit: u32,
     window_size: Duration,
+    key_limits: Arc<HashMap<String, u32>>,
+    /// Counters by principal, see `principal`
     limits: Arc<RwLock<HashMap<String, RateLimitEntry>>>,
+    /// Counters whose window has ended are dropped at most once per window
+    last_eviction: Arc<std::sync::Mutex<Instant>>,
     _phantom: std::marker::PhantomData<B>,
 }
 
@@ -88,16 +142,32 @@ where
         let burst_limit = self.burst_limit;
         let window_size = self.window_size;
         let limits = Arc::clone(&self.limits);
+        let (principal, identity) = principal(&req, &self.key_limits);
+        // A per-key budget scales the burst allowance with it
+        let (rate_limit, burst_limit) = match identity.and_then(|id| self.key_limits.get(&id).copied()) {
+            Some(key_limit) => (
+                key_limit,
+                (u64::from(burst_limit) * u64::from(key_limit) / u64::from(rate_limit.max(1))).clamp(1, u64::from(u32::MAX)) as u32,
+            ),
+            None => (rate_limit, burst_limit),
+        };
+        let evict = {
+            let mut last_eviction = self.last_eviction.lock().unwrap_or_else(|e| e.into_inner());
+            let due = last_eviction.elapsed() >= window_size;
+            if due {
+                *last_eviction = Instant::now();
+            }
+            due
+        };
 
         Box::pin(async move {
-            let client_ip = req.connection_info().peer_addr()
-                .unwrap_or("unknown")
-                .to_string();
-
             let mut limits_guard = limits.write().await;
             let now = Instant::now();
+            if evict {
+                limits_guard.retain(|_, entry| now < entry.reset_time);
+            }
 
-            if let Some(entry) = limits_guard.get_mut(&client_ip) {
+            if let Some(entry) = limits_guard.get_mut(&principal) {
                 if now >= entry.reset_time {
                     // Reset window
                     *entry = RateLimitEntry {
@@ -134,12 +204,13 @@ where
                     entry.burst_count += 1;
                 }
             } else {
-                limits_guard.insert(client_ip.to_string(), RateLimitEntry {
+                limits_guard.insert(principal, RateLimitEntry {
                     count: 1,
                     reset_time: now + window_size,
                     burst_count: 1,
                 });
             }
+            drop(limits_guard);
 
             // Call the inner service
             let res = service.call(req).await?;
@@ -348,4 +419,74 @@ pub mod utils {
             false
         }
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn test_api_keys_have_independent_budgets() {
+        let key_limits = HashMap::from([("alpha".to_string(), 2), ("beta".to_string(), 2)]);
+        let app = test::init_service(
+            App::new()
+                .wrap(RateLimitingMiddleware::new(3, 3, Duration::from_secs(60)).with_key_limits(key_limits))
+                .route("/", web::get().to(|| async { HttpResponse::Ok().finish() })),
+        ).await;
+        let request = |key: &str| test::TestRequest::get().uri("/").insert_header((API_KEY_HEADER, key)).to_request();
+
+        for _ in 0..2 {
+            assert!(test::call_service(&app, request("alpha")).await.status().is_success());
+        }
+        assert_eq!(test::call_service(&app, request("alpha")).await.status(), 429);
+
+        // alpha being exhausted leaves beta's budget and the IP budget untouched
+        for _ in 0..2 {
+            assert!(test::call_service(&app, request("beta")).await.status().is_success());
+        }
+        assert_eq!(test::call_service(&app, request("beta")).await.status(), 429);
+
+        // Unlisted keys share the caller's IP budget
+        for key in ["made-up-1", "made-up-2", "made-up-3"] {
+            assert!(test::call_service(&app, request(key)).await.status().is_success());
+        }
+        assert_eq!(test::call_service(&app, request("made-up-4")).await.status(), 429);
+    }
+
+    #[actix_web::test]
+    async fn test_tokens_of_different_api_keys_have_independent_budgets() {
+        use crate::domain::auth::{api_key_subject, AuthManager};
+
+        let auth_manager = Arc::new(AuthManager::with_secret("test_secret_for_rate_limit_tokens_1234567890abcdef".to_string(), 600, 3600));
+        let alpha = auth_manager.issue_tokens(&api_key_subject("alpha"), "relay").unwrap().access_token;
+        let beta = auth_manager.issue_tokens(&api_key_subject("beta"), "relay").unwrap().access_token;
+        let partner = auth_manager.issue_tokens(&api_key_subject("partner"), "relay").unwrap().access_token;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(auth_manager))
+                .wrap(RateLimitingMiddleware::new(2, 2, Duration::from_secs(60))
+                    .with_key_limits(HashMap::from([("partner".to_string(), 4)])))
+                .route("/", web::get().to(|| async { HttpResponse::Ok().finish() })),
+        ).await;
+        let request = |token: &str| test::TestRequest::get().uri("/")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        for _ in 0..2 {
+            assert!(test::call_service(&app, request(&alpha)).await.status().is_success());
+        }
+        assert_eq!(test::call_service(&app, request(&alpha)).await.status(), 429);
+
+        // A busy client doesn't throttle other token holders
+        for _ in 0..2 {
+            assert!(test::call_service(&app, request(&beta)).await.status().is_success());
+        }
+
+        // A key's own budget carries over to its tokens
+        for _ in 0..4 {
+            assert!(test::call_service(&app, request(&partner)).await.status().is_success());
+        }
+        assert_eq!(test::call_service(&app, request(&partner)).await.status(), 429);
+    }
+}
@@ -0,0 +1,140 @@
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error, HttpMessage,
+};
+use futures_util::future::{LocalBoxFuture, Ready, ready};
+use std::rc::Rc;
+
+/// Header carrying the id that correlates a request's logs, audit events and error records
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied id that is honored; longer ones are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// The request's correlation id, stored in the request extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// Id of the request being handled on this task, if any. Work spawned onto other tasks
+/// doesn't inherit it.
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// The current request's id, or a fresh one outside a request
+pub fn current_or_new_request_id() -> String {
+    current_request_id().unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// A client-supplied id is kept if it is short and limited to characters safe in logs
+fn accept_client_id(value: &str) -> Option<String> {
+    let value = value.trim();
+    let safe = !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    safe.then(|| value.to_string())
+}
+
+/// Gives every request an id, honoring an incoming `X-Request-Id`, and echoes it on the
+/// response. The id is available from the request extensions and, while the request is
+/// handled, from `current_request_id`.
+#[derive(Clone, Default)]
+pub struct RequestIdMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestIdService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdService { service: Rc::new(service) }))
+    }
+}
+
+pub struct RequestIdService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let request_id = req.headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(accept_client_id)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        req.extensions_mut().insert(RequestId(request_id.clone()));
+
+        Box::pin(CURRENT_REQUEST_ID.scope(request_id.clone(), async move {
+            let mut res = service.call(req).await?;
+            if let Ok(value) = HeaderValue::from_str(&request_id) {
+                res.headers_mut().insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+            }
+            Ok(res)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::error_handling::ErrorHandlingMiddleware;
+    use crate::utils::error_handler::EnhancedErrorHandler;
+    use actix_web::{test, web, App, HttpResponse};
+    use std::sync::Arc;
+
+    async fn failing() -> Result<HttpResponse, Error> {
+        Err(actix_web::error::ErrorInternalServerError("storage unavailable"))
+    }
+
+    #[actix_web::test]
+    async fn test_response_and_recorded_error_share_request_id() {
+        let error_handler = Arc::new(EnhancedErrorHandler::new());
+        let app = test::init_service(
+            App::new()
+                .wrap(ErrorHandlingMiddleware::new(Arc::clone(&error_handler)))
+                .wrap(RequestIdMiddleware)
+                .route("/ok", web::get().to(HttpResponse::Ok))
+                .route("/fail", web::get().to(failing)),
+        ).await;
+
+        let res = test::call_service(&app, test::TestRequest::get().uri("/fail").to_request()).await;
+        let request_id = res.headers().get(REQUEST_ID_HEADER).unwrap().to_str().unwrap().to_string();
+        assert!(uuid::Uuid::parse_str(&request_id).is_ok());
+
+        let errors = error_handler.recent_errors(1).await;
+        assert_eq!(errors[0].context.get("request_id"), Some(&request_id));
+
+        // A client-supplied id is kept; an unsafe one is replaced
+        let req = test::TestRequest::get().uri("/ok").insert_header((REQUEST_ID_HEADER, "checkout-42")).to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.headers().get(REQUEST_ID_HEADER).unwrap(), "checkout-42");
+
+        let req = test::TestRequest::get().uri("/ok").insert_header((REQUEST_ID_HEADER, "a b\tc")).to_request();
+        let res = test::call_service(&app, req).await;
+        assert_ne!(res.headers().get(REQUEST_ID_HEADER).unwrap(), "a b\tc");
+    }
+}
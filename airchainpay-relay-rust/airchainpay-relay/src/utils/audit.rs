@@ -9,6 +9,7 @@ use uuid::Uuid;
 // use crate::logger::Logger;
 use std::time::{SystemTime, UNIX_EPOCH};
 use crate::infrastructure::monitoring::manager::MonitoringManager;
+use crate::middleware::request_id::current_request_id;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditEvent {
@@ -120,10 +121,15 @@ impl AuditLogger {
         }
     }
 
-    pub async fn log_event(&self, event: AuditEvent) -> Result<(), Box<dyn std::error::Error>> {
+    /// Record an event; one logged while a request is handled gets that request's id
+    /// unless it already has one
+    pub async fn log_event(&self, mut event: AuditEvent) -> Result<(), Box<dyn std::error::Error>> {
         if !self.enabled {
             return Ok(());
         }
+        if event.request_id.is_none() {
+            event.request_id = current_request_id();
+        }
 
         let mut events = self.events.write().await;
         
@@ -541,6 +547,36 @@ impl AuditLogger {
         self.log_event(event).await
     }
 
+    pub async fn log_system_operation(
+        &self,
+        operation: &str,
+        success: bool,
+        error_message: Option<String>,
+        details: HashMap<String, serde_json::Value>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let event = AuditEvent {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            event_type: AuditEventType::SystemOperation,
+            user_id: None,
+            ip_address: None,
+            user_agent: None,
+            device_id: None,
+            resource: "system".to_string(),
+            action: operation.to_string(),
+            details,
+            success,
+            error_message,
+            session_id: None,
+            request_id: None,
+            severity: if success { AuditSeverity::Low } else { AuditSeverity::High },
+            metadata: HashMap::new(),
+            server_info: Self::get_server_info(),
+        };
+
+        self.log_event(event).await
+    }
+
     pub async fn get_events(&self, filter: Option<AuditFilter>) -> Vec<AuditEvent> {
         let events = self.events.read().await;
         
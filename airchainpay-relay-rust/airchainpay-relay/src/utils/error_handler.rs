@@ -6,7 +6,8 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use anyhow::Error;
 use std::time::{Duration, Instant};
-use crate::domain::error::RelayError;
+use crate::domain::error::{RelayError, StorageError};
+use crate::middleware::request_id::current_request_id;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum CriticalPath {
@@ -72,6 +73,55 @@ pub struct ErrorRecord {
     pub component: String,
 }
 
+impl From<&StorageError> for ErrorType {
+    fn from(error: &StorageError) -> Self {
+        match error {
+            StorageError::Transient(_) => ErrorType::Timeout,
+            StorageError::StorageFull(_) => ErrorType::ResourceExhaustion,
+            StorageError::CorruptedData(_) | StorageError::Deserialization(_) => ErrorType::DataCorruption,
+            StorageError::PermissionDenied(_) => ErrorType::SecurityViolation,
+            StorageError::TransactionNotFound(_) | StorageError::DeviceNotFound(_) | StorageError::DuplicateEntry(_) => ErrorType::ValidationFailure,
+            _ => ErrorType::Database,
+        }
+    }
+}
+
+impl ErrorRecord {
+    /// Record of a storage failure after `attempts` tries. Transient failures that
+    /// exhausted their retries stay medium severity; permanent ones are high.
+    pub fn from_storage_error(error: &StorageError, operation: &str, attempts: u32, max_retries: u32) -> Self {
+        let severity = match error {
+            StorageError::CorruptedData(_) => ErrorSeverity::Critical,
+            e if e.is_transient() => ErrorSeverity::Medium,
+            _ => ErrorSeverity::High,
+        };
+        let mut context = HashMap::new();
+        context.insert("operation".to_string(), operation.to_string());
+        context.insert("transient".to_string(), error.is_transient().to_string());
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            path: CriticalPath::DatabaseOperation,
+            error_type: ErrorType::from(error),
+            error_message: error.to_string(),
+            context,
+            severity,
+            retry_count: attempts.saturating_sub(1),
+            max_retries,
+            resolved: false,
+            resolution_time: None,
+            stack_trace: None,
+            user_id: None,
+            transaction_id: None,
+            chain_id: None,
+            ip_address: None,
+            device_id: None,
+            component: "storage".to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ErrorSeverity {
     Low,
@@ -418,8 +468,12 @@ impl EnhancedErrorHandler {
         }
     }
 
-    /// Record an error (compatible with old ErrorHandler API)
-    pub async fn record_error(&self, error: ErrorRecord) {
+    /// Record an error (compatible with old ErrorHandler API). Errors recorded while a
+    /// request is handled carry its `request_id` in the context.
+    pub async fn record_error(&self, mut error: ErrorRecord) {
+        if let Some(request_id) = current_request_id() {
+            error.context.entry("request_id".to_string()).or_insert(request_id);
+        }
         let mut errors = self.errors.write().await;
         errors.push(error.clone());
         
@@ -494,6 +548,11 @@ impl EnhancedErrorHandler {
         println!("Alert sent: {error:?}");
     }
     
+    /// The most recently recorded errors, newest first
+    pub async fn recent_errors(&self, limit: usize) -> Vec<ErrorRecord> {
+        self.errors.read().await.iter().rev().take(limit).cloned().collect()
+    }
+
     pub async fn update_circuit_breaker_status(&self, path: &CriticalPath, open: bool) {
         println!("Circuit breaker updated for {path:?}: {open}");
     }
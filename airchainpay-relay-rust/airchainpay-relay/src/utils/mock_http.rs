@@ -0,0 +1,240 @@
+//! Local HTTP server for tests that talk to RPC nodes, registries or webhook endpoints.
+//!
+//! The server speaks keep-alive HTTP/1.1 on an ephemeral port, answers every request
+//! with a test-supplied closure and records what it received.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// A request received by a `MockServer`
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    /// Header names are lower-cased
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub received_at: Instant,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The body parsed as JSON, `Null` if it isn't
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).unwrap_or_default()
+    }
+}
+
+/// Response a `MockServer` sends back
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    delay: Duration,
+}
+
+impl MockResponse {
+    pub fn status(status: u16) -> Self {
+        Self { status, headers: Vec::new(), body: Vec::new(), delay: Duration::ZERO }
+    }
+
+    pub fn json(body: &serde_json::Value) -> Self {
+        Self::status(200).header("content-type", "application/json").body(body.to_string())
+    }
+
+    /// JSON-RPC success response to `request`
+    pub fn rpc_result(request: &MockRequest, result: serde_json::Value) -> Self {
+        Self::json(&serde_json::json!({ "jsonrpc": "2.0", "id": request.json()["id"], "result": result }))
+    }
+
+    /// JSON-RPC error response to `request`
+    pub fn rpc_error(request: &MockRequest, error: serde_json::Value) -> Self {
+        Self::json(&serde_json::json!({ "jsonrpc": "2.0", "id": request.json()["id"], "error": error }))
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Hold the response back for `delay`, counting the request as in flight meanwhile
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut head = format!("HTTP/1.1 {} {}\r\ncontent-length: {}\r\n", self.status, reason(self.status), self.body.len());
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(&self.body);
+        bytes
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Status",
+    }
+}
+
+/// Handle to a running mock server; it stops when the test's runtime shuts down
+#[derive(Clone)]
+pub struct MockServer {
+    /// Base URL, `http://127.0.0.1:<port>`
+    pub url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+    connections: Arc<AtomicUsize>,
+    peak_in_flight: Arc<AtomicUsize>,
+}
+
+impl MockServer {
+    /// Start a server answering every request with `respond`
+    pub async fn start<F>(respond: F) -> Self
+    where
+        F: Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = Self {
+            url: format!("http://{}", listener.local_addr().unwrap()),
+            requests: Arc::new(Mutex::new(Vec::new())),
+            connections: Arc::new(AtomicUsize::new(0)),
+            peak_in_flight: Arc::new(AtomicUsize::new(0)),
+        };
+        let respond = Arc::new(respond);
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let handle = server.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                handle.connections.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(handle.clone().serve(socket, Arc::clone(&respond), Arc::clone(&in_flight)));
+            }
+        });
+        server
+    }
+
+    /// Start a JSON-RPC node answering every call with the result `respond(method, params)`
+    pub async fn json_rpc<F>(respond: F) -> Self
+    where
+        F: Fn(&str, &serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    {
+        Self::start(move |request| {
+            let call = request.json();
+            MockResponse::rpc_result(request, respond(call["method"].as_str().unwrap_or_default(), &call["params"]))
+        }).await
+    }
+
+    async fn serve<F>(self, mut socket: tokio::net::TcpStream, respond: Arc<F>, in_flight: Arc<AtomicUsize>)
+    where
+        F: Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let request = loop {
+                if let Some((request, consumed)) = parse_request(&buffer) {
+                    buffer.drain(..consumed);
+                    break request;
+                }
+                match socket.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+                }
+            };
+            self.requests.lock().unwrap().push(request.clone());
+
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak_in_flight.fetch_max(now, Ordering::SeqCst);
+            let response = respond(&request);
+            if !response.delay.is_zero() {
+                tokio::time::sleep(response.delay).await;
+            }
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            if socket.write_all(&response.to_bytes()).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// URL of `path` on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.url, path)
+    }
+
+    /// Every request received so far, in arrival order
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// JSON-RPC methods called so far, in arrival order
+    pub fn rpc_methods(&self) -> Vec<String> {
+        self.requests().iter()
+            .filter_map(|request| request.json()["method"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// TCP connections accepted so far
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+
+    /// Most requests that were being answered at the same time
+    pub fn peak_in_flight(&self) -> usize {
+        self.peak_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+/// One complete request at the front of `buffer` and how many bytes it took
+fn parse_request(buffer: &[u8]) -> Option<(MockRequest, usize)> {
+    let header_end = buffer.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = String::from_utf8_lossy(&buffer[..header_end]);
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    let content_length = headers.iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let end = header_end + 4 + content_length;
+    if buffer.len() < end {
+        return None;
+    }
+    let body = buffer[header_end + 4..end].to_vec();
+    Some((MockRequest { method, path, headers, body, received_at: Instant::now() }, end))
+}
+
+/// URL of a local port nothing is listening on
+pub fn unreachable_url() -> String {
+    // Bind then drop to get a free port
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    format!("http://{}", addr)
+}
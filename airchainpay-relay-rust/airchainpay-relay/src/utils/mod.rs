@@ -8,4 +8,6 @@ pub mod cleanup;
 pub mod prometheus;
 pub mod error_handler;
 pub mod critical_error_handler;
+#[cfg(test)]
+pub mod mock_http;
 pub mod animated_ascii; 
\ No newline at end of file
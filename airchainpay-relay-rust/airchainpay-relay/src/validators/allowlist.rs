@@ -0,0 +1,68 @@
+use crate::infrastructure::config::AllowlistConfig;
+use crate::validators::denylist::read_address_list;
+use anyhow::{Result, anyhow};
+use std::collections::HashSet;
+use std::sync::RwLock;
+
+/// Recipient addresses transfers are restricted to, for relays run for specific merchants.
+/// An empty list allows every recipient. Entries are stored lowercased, and the set can be
+/// swapped at runtime when the configuration (or the allowlist file) changes.
+#[derive(Default)]
+pub struct RecipientAllowlist {
+    addresses: RwLock<HashSet<String>>,
+}
+
+impl RecipientAllowlist {
+    pub fn from_config(config: &AllowlistConfig) -> Result<Self> {
+        let allowlist = Self::default();
+        allowlist.reload(config)?;
+        Ok(allowlist)
+    }
+
+    /// Rebuild the list from inline addresses and the optional file. On error the
+    /// previous list stays in effect. Returns the number of allowlisted addresses.
+    pub fn reload(&self, config: &AllowlistConfig) -> Result<usize> {
+        let addresses = read_address_list(&config.addresses, config.file.as_deref(), "allowlist")?;
+        let count = addresses.len();
+        *self.addresses.write().unwrap() = addresses;
+        Ok(count)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.addresses.read().unwrap().is_empty()
+    }
+
+    /// Whether transfers to `address` may be relayed
+    pub fn allows(&self, address: &str) -> bool {
+        let addresses = self.addresses.read().unwrap();
+        addresses.is_empty() || addresses.contains(&address.to_lowercase())
+    }
+
+    pub fn check(&self, recipient: &str) -> Result<()> {
+        if self.allows(recipient) {
+            return Ok(());
+        }
+        Err(anyhow!("Recipient {} is not on the allowlist", recipient))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MERCHANT: &str = "0x8589427373D6D84E98730D7795D8f6f8731FDA16";
+    const OTHER: &str = "0x1111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_empty_allowlist_allows_everyone() {
+        let allowlist = RecipientAllowlist::from_config(&AllowlistConfig::default()).unwrap();
+        assert!(!allowlist.is_enabled());
+        assert!(allowlist.check(OTHER).is_ok());
+
+        allowlist.reload(&AllowlistConfig { addresses: vec![MERCHANT.to_string()], file: None }).unwrap();
+        assert!(allowlist.allows(&MERCHANT.to_lowercase()));
+        assert!(allowlist.check(OTHER).unwrap_err().to_string().contains("allowlist"));
+        assert!(allowlist.reload(&AllowlistConfig { addresses: vec!["merchant".to_string()], file: None }).is_err());
+        assert!(allowlist.allows(MERCHANT));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::infrastructure::blockchain::ethereum;
+use crate::infrastructure::config::DenylistConfig;
+use crate::utils::audit::{AuditLogger, AuditSeverity};
+use anyhow::{Result, anyhow};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::{Arc, RwLock};
+
+pub const DENYLISTED_RECIPIENT_EVENT: &str = "denylisted_recipient";
+
+/// Recipient addresses that transfers must not be relayed to. Entries are stored
+/// lowercased so matching is case-insensitive, and the set can be swapped at runtime
+/// when the configuration (or the denylist file) changes.
+#[derive(Default)]
+pub struct RecipientDenylist {
+    addresses: RwLock<HashSet<String>>,
+    audit_logger: RwLock<Option<Arc<AuditLogger>>>,
+}
+
+impl RecipientDenylist {
+    pub fn from_config(config: &DenylistConfig) -> Result<Self> {
+        let denylist = Self::default();
+        denylist.reload(config)?;
+        Ok(denylist)
+    }
+
+    /// Security audit events are emitted for blocked recipients once a logger is attached
+    pub fn attach_audit_logger(&self, audit_logger: Arc<AuditLogger>) {
+        *self.audit_logger.write().unwrap() = Some(audit_logger);
+    }
+
+    /// Rebuild the list from inline addresses and the optional file. On error the
+    /// previous list stays in effect. Returns the number of denylisted addresses.
+    pub fn reload(&self, config: &DenylistConfig) -> Result<usize> {
+        let addresses = read_address_list(&config.addresses, config.file.as_deref(), "denylist")?;
+        let count = addresses.len();
+        *self.addresses.write().unwrap() = addresses;
+        Ok(count)
+    }
+
+    pub fn contains(&self, address: &str) -> bool {
+        self.addresses.read().unwrap().contains(&address.to_lowercase())
+    }
+
+    pub fn len(&self) -> usize {
+        self.addresses.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reject a denylisted recipient, recording a security audit event
+    pub async fn check(&self, recipient: &str, chain_id: u64) -> Result<()> {
+        if !self.contains(recipient) {
+            return Ok(());
+        }
+
+        let audit_logger = self.audit_logger.read().unwrap().clone();
+        if let Some(audit_logger) = audit_logger {
+            let mut details = HashMap::new();
+            details.insert("recipient".to_string(), serde_json::json!(recipient.to_lowercase()));
+            details.insert("chain_id".to_string(), serde_json::json!(chain_id));
+            if let Err(e) = audit_logger.log_security_event(
+                None,
+                None,
+                None,
+                DENYLISTED_RECIPIENT_EVENT.to_string(),
+                details,
+                AuditSeverity::High,
+                None,
+            ).await {
+                log::warn!("Failed to record denylist audit event: {}", e);
+            }
+        }
+
+        Err(anyhow!("Recipient {} is on the denylist", recipient))
+    }
+}
+
+/// Lowercased addresses from inline entries plus an optional file with one address per
+/// line (`#` starts a comment). `kind` names the list in errors.
+pub(crate) fn read_address_list(inline: &[String], file: Option<&str>, kind: &str) -> Result<HashSet<String>> {
+    let mut entries = inline.to_vec();
+    if let Some(path) = file {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {} file {}: {}", kind, path, e))?;
+        entries.extend(
+            contents
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or_default().trim().to_string())
+                .filter(|line| !line.is_empty()),
+        );
+    }
+
+    let mut addresses = HashSet::new();
+    for entry in entries {
+        if !ethereum::validate_ethereum_address(&entry) {
+            return Err(anyhow!("Invalid {} address: {}", kind, entry));
+        }
+        addresses.insert(entry.to_lowercase());
+    }
+    Ok(addresses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCKED: &str = "0x8589427373D6D84E98730D7795D8f6f8731FDA16";
+    const OTHER: &str = "0x1111111111111111111111111111111111111111";
+
+    fn temp_file(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{}_{}", name, uuid::Uuid::new_v4()));
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_matching_is_case_insensitive() {
+        let denylist = RecipientDenylist::from_config(&DenylistConfig {
+            addresses: vec![BLOCKED.to_string()],
+            file: None,
+        }).unwrap();
+
+        assert!(denylist.contains(BLOCKED));
+        assert!(denylist.contains(&BLOCKED.to_lowercase()));
+        assert!(denylist.contains(&BLOCKED.to_uppercase().replacen("0X", "0x", 1)));
+        assert!(!denylist.contains(OTHER));
+    }
+
+    #[test]
+    fn test_reload_picks_up_file_additions() {
+        let path = temp_file("denylist", &format!("# scam list\n{}\n", BLOCKED));
+        let config = DenylistConfig { addresses: Vec::new(), file: Some(path.clone()) };
+        let denylist = RecipientDenylist::from_config(&config).unwrap();
+        assert!(denylist.contains(BLOCKED));
+        assert!(!denylist.contains(OTHER));
+
+        fs::write(&path, format!("{}\n{}  # added later\n", BLOCKED, OTHER)).unwrap();
+        assert_eq!(denylist.reload(&config).unwrap(), 2);
+        assert!(denylist.contains(OTHER));
+
+        fs::write(&path, "not-an-address\n").unwrap();
+        assert!(denylist.reload(&config).is_err());
+        assert_eq!(denylist.len(), 2);
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_blocked_recipient_emits_security_event() {
+        let audit_path = std::env::temp_dir().join(format!("denylist_audit_{}.log", uuid::Uuid::new_v4()));
+        let audit_logger = Arc::new(AuditLogger::new(audit_path.to_string_lossy().to_string(), 100));
+        let denylist = RecipientDenylist::from_config(&DenylistConfig {
+            addresses: vec![BLOCKED.to_string()],
+            file: None,
+        }).unwrap();
+        denylist.attach_audit_logger(Arc::clone(&audit_logger));
+
+        assert!(denylist.check(OTHER, 1114).await.is_ok());
+        assert!(audit_logger.get_security_events(None).await.is_empty());
+
+        let err = denylist.check(&BLOCKED.to_lowercase(), 1114).await.unwrap_err();
+        assert!(err.to_string().contains("denylist"));
+        let events = audit_logger.get_security_events(None).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, DENYLISTED_RECIPIENT_EVENT);
+        let _ = fs::remove_file(audit_path);
+    }
+}
@@ -1,2 +1,5 @@
+pub mod allowlist;
+pub mod denylist;
+pub mod plugin;
 pub mod transaction_validator;
- 
\ No newline at end of file
+pub mod registered_sender;
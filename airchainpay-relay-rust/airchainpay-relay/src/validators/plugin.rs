@@ -0,0 +1,71 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use ethers::types::Address;
+use std::sync::{Arc, RwLock};
+
+/// What a plugin is shown of a submitted transaction
+pub struct SubmittedTransaction<'a> {
+    /// Raw signed transaction as submitted, `0x`-prefixed
+    pub signed_tx: &'a str,
+    pub chain_id: u64,
+    /// Signer recovered from the signature; `None` if it could not be recovered
+    pub sender: Option<Address>,
+    /// The `to` address plus any recipient decoded from a known payment call, lowercase hex
+    pub recipients: Vec<String>,
+}
+
+/// Outcome of a plugin: errors reject the submission, warnings only annotate it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PluginFindings {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl PluginFindings {
+    pub fn accept() -> Self {
+        Self::default()
+    }
+
+    pub fn reject(error: impl Into<String>) -> Self {
+        Self { errors: vec![error.into()], warnings: Vec::new() }
+    }
+
+    pub fn warn(warning: impl Into<String>) -> Self {
+        Self { errors: Vec::new(), warnings: vec![warning.into()] }
+    }
+}
+
+/// Deployment-specific rule (KYC, business limits, ...) run by `TransactionValidator` after
+/// its built-in checks. Findings are reported prefixed with the plugin's name; a plugin that
+/// fails with an error rejects the submission rather than letting it through unchecked.
+#[async_trait]
+pub trait TransactionValidatorPlugin: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn validate(&self, tx: &SubmittedTransaction<'_>) -> Result<PluginFindings>;
+}
+
+/// Registered plugins, run in registration order
+#[derive(Default)]
+pub struct ValidatorPlugins {
+    plugins: RwLock<Vec<Arc<dyn TransactionValidatorPlugin>>>,
+}
+
+impl ValidatorPlugins {
+    pub fn register(&self, plugin: Arc<dyn TransactionValidatorPlugin>) {
+        self.plugins.write().unwrap().push(plugin);
+    }
+
+    /// The plugins registered so far; later registrations don't affect the returned list
+    pub fn all(&self) -> Vec<Arc<dyn TransactionValidatorPlugin>> {
+        self.plugins.read().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.plugins.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,163 @@
+use crate::infrastructure::config::RegisteredSenderConfig;
+use crate::infrastructure::storage::file_storage::Storage;
+use crate::utils::audit::{AuditLogger, AuditSeverity};
+use anyhow::{Result, anyhow};
+use ethers::core::utils::rlp::{DecoderError, Rlp, RlpStream};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Signature, H256};
+use ethers::utils::keccak256;
+use std::collections::HashMap;
+
+pub const UNREGISTERED_SENDER_EVENT: &str = "unregistered_sender";
+
+/// Recover the address that signed a raw transaction
+pub fn recover_sender(signed_tx: &str) -> Result<Address> {
+    let tx_bytes = hex::decode(signed_tx.trim_start_matches("0x"))
+        .map_err(|e| anyhow!("Failed to decode hex: {}", e))?;
+    let rlp = Rlp::new(&tx_bytes);
+    let (sighash, signature) = if rlp.is_list() {
+        legacy_sighash(&rlp).map_err(|e| anyhow!("Failed to decode transaction: {}", e))?
+    } else {
+        let (tx, signature) = TypedTransaction::decode_signed(&rlp)
+            .map_err(|e| anyhow!("Failed to decode transaction: {}", e))?;
+        (tx.sighash(), signature)
+    };
+    signature.recover(sighash)
+        .map_err(|e| anyhow!("Failed to recover transaction sender: {}", e))
+}
+
+/// Signing hash and signature of a legacy transaction, decoded field by field because
+/// the Celo-aware ethers decoder only accepts legacy transactions carrying Celo's fee fields
+fn legacy_sighash(rlp: &Rlp) -> Result<(H256, Signature), DecoderError> {
+    // [nonce, gasPrice, gasLimit, to, value, data, v, r, s], or with Celo's
+    // feeCurrency, gatewayFeeRecipient and gatewayFee after gasLimit
+    let fields = match rlp.item_count()? {
+        count @ (9 | 12) => count - 3,
+        _ => return Err(DecoderError::RlpIncorrectListLen),
+    };
+    let signature = Signature { v: rlp.val_at(fields)?, r: rlp.val_at(fields + 1)?, s: rlp.val_at(fields + 2)? };
+    let mut stream = RlpStream::new();
+    stream.begin_unbounded_list();
+    for i in 0..fields {
+        stream.append_raw(rlp.at(i)?.as_raw(), 1);
+    }
+    // EIP-155 signatures commit to the chain id
+    if signature.v >= 35 {
+        stream.append(&((signature.v - 35) / 2)).append(&0u8).append(&0u8);
+    }
+    stream.finalize_unbounded_list();
+    Ok((keccak256(stream.out()).into(), signature))
+}
+
+/// Whether a submission may be relayed. With `require_registered` set the sender is
+/// recovered and must be a registered wallet; an unregistered sender yields `Ok(false)`
+/// after a security audit event is recorded. Errors if the sender cannot be recovered.
+pub async fn sender_allowed(
+    config: &RegisteredSenderConfig,
+    storage: &Storage,
+    audit_logger: &AuditLogger,
+    signed_tx: &str,
+    chain_id: u64,
+) -> Result<bool> {
+    if !config.require_registered {
+        return Ok(true);
+    }
+    let sender = format!("0x{:x}", recover_sender(signed_tx)?);
+    if storage.is_registered_wallet(&sender) {
+        return Ok(true);
+    }
+
+    let mut details = HashMap::new();
+    details.insert("sender".to_string(), serde_json::json!(sender));
+    details.insert("chain_id".to_string(), serde_json::json!(chain_id));
+    if let Err(e) = audit_logger.log_security_event(
+        None,
+        None,
+        None,
+        UNREGISTERED_SENDER_EVENT.to_string(),
+        details,
+        AuditSeverity::High,
+        None,
+    ).await {
+        log::warn!("Failed to record unregistered sender audit event: {}", e);
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::{Eip1559TransactionRequest, TransactionRequest};
+
+    fn wallet() -> LocalWallet {
+        "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(1114u64)
+    }
+
+    fn sign(tx: TypedTransaction) -> String {
+        let signature = wallet().sign_transaction_sync(&tx).unwrap();
+        format!("0x{}", hex::encode(tx.rlp_signed(&signature)))
+    }
+
+    /// An EIP-155 legacy transaction without Celo's fee fields, as most wallets produce
+    fn sign_plain_legacy(to: Address) -> String {
+        let mut unsigned = RlpStream::new_list(9);
+        unsigned.append(&0u64).append(&1_000_000_000u64).append(&21_000u64).append(&to).append(&1_000u64)
+            .append(&Vec::<u8>::new()).append(&1114u64).append(&0u8).append(&0u8);
+        let signature = wallet().sign_hash(keccak256(unsigned.out()).into()).unwrap();
+        let v = signature.v - 27 + 1114 * 2 + 35;
+
+        let mut signed = RlpStream::new_list(9);
+        signed.append(&0u64).append(&1_000_000_000u64).append(&21_000u64).append(&to).append(&1_000u64)
+            .append(&Vec::<u8>::new()).append(&v).append(&signature.r).append(&signature.s);
+        format!("0x{}", hex::encode(signed.out()))
+    }
+
+    #[test]
+    fn test_recover_sender_of_legacy_and_eip1559_transactions() {
+        let to = "0x2222222222222222222222222222222222222222".parse::<Address>().unwrap();
+        let celo_legacy = TransactionRequest::new().to(to).value(1_000u64).gas(21_000).gas_price(1_000_000_000u64).nonce(0).chain_id(1114u64);
+        let eip1559 = Eip1559TransactionRequest::new().to(to).value(1_000u64).gas(21_000)
+            .max_fee_per_gas(2_000_000_000u64).max_priority_fee_per_gas(1_000_000_000u64).nonce(0).chain_id(1114u64);
+
+        assert_eq!(recover_sender(&sign_plain_legacy(to)).unwrap(), wallet().address());
+        assert_eq!(recover_sender(&sign(celo_legacy.into())).unwrap(), wallet().address());
+        assert_eq!(recover_sender(&sign(eip1559.into())).unwrap(), wallet().address());
+        assert!(recover_sender("0xdeadbeef").is_err());
+    }
+
+    fn required(require_registered: bool) -> RegisteredSenderConfig {
+        RegisteredSenderConfig { require_registered, wallets: Vec::new() }
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_sender_is_rejected_only_when_required() {
+        let data_dir = std::env::temp_dir().join(format!("registered_sender_{}", uuid::Uuid::new_v4()));
+        let storage = Storage::with_data_dir(data_dir.to_string_lossy().to_string()).unwrap();
+        let audit_logger = AuditLogger::new(data_dir.join("audit.log").to_string_lossy().to_string(), 100);
+        let to = "0x2222222222222222222222222222222222222222".parse::<Address>().unwrap();
+        let signed_tx = sign_plain_legacy(to);
+
+        // Open relays accept any sender without recording anything
+        assert!(sender_allowed(&required(false), &storage, &audit_logger, &signed_tx, 1114).await.unwrap());
+        assert!(audit_logger.get_security_events(None).await.is_empty());
+
+        assert!(!sender_allowed(&required(true), &storage, &audit_logger, &signed_tx, 1114).await.unwrap());
+        let events = audit_logger.get_security_events(None).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, UNREGISTERED_SENDER_EVENT);
+        assert!(sender_allowed(&required(true), &storage, &audit_logger, "0xdeadbeef", 1114).await.is_err());
+
+        // Registration is case-insensitive and survives a reload
+        let sender = format!("{:?}", wallet().address()).to_uppercase().replacen("0X", "0x", 1);
+        assert!(storage.register_wallet(&sender).unwrap());
+        let storage = Storage::with_data_dir(data_dir.to_string_lossy().to_string()).unwrap();
+        assert_eq!(storage.get_registered_wallets(), vec![sender.to_lowercase()]);
+        assert!(sender_allowed(&required(true), &storage, &audit_logger, &signed_tx, 1114).await.unwrap());
+        assert_eq!(audit_logger.get_security_events(None).await.len(), 1);
+        let _ = std::fs::remove_dir_all(data_dir);
+    }
+}
@@ -1,11 +1,16 @@
 use crate::infrastructure::config::Config;
+use crate::validators::allowlist::RecipientAllowlist;
+use crate::validators::denylist::RecipientDenylist;
+use crate::validators::plugin::{SubmittedTransaction, ValidatorPlugins};
+use crate::validators::registered_sender::recover_sender;
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
-use ethers::types::Transaction;
+use ethers::abi::{ParamType, Token};
+use ethers::types::{Address, Transaction, U256};
 use ethers::core::utils::rlp::{Rlp, Decodable};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,21 +20,66 @@ pub struct ValidationResult {
     pub warnings: Vec<String>,
 }
 
+/// Structural fields read straight from a signed transaction's RLP
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SignedFields {
+    /// None for legacy transactions signed without EIP-155 replay protection
+    chain_id: Option<u64>,
+    gas_limit: U256,
+    /// Raw `to` field; empty for contract creation
+    to: Vec<u8>,
+}
+
 pub struct TransactionValidator {
     config: Arc<Config>,
     // For rate limiting (simple in-memory, per-process)
     rate_limit_state: Arc<Mutex<HashMap<String, (u64, u32)>>>, // (window_start, count)
+    denylist: Option<Arc<RecipientDenylist>>,
+    allowlist: Option<Arc<RecipientAllowlist>>,
+    plugins: Option<Arc<ValidatorPlugins>>,
 }
 
+/// Payment entry points and the position of their recipient argument(s)
+const RECIPIENT_CALLS: &[(&str, usize)] = &[
+    ("pay(address,string)", 0),
+    ("processNativePayment(address,string)", 0),
+    ("processTokenPayment(address,uint256,address,string)", 2),
+    ("executeMetaTransaction(address,address,uint256,string,uint256,bytes)", 1),
+    ("executeNativeMetaTransaction(address,address,uint256,string,uint256,bytes)", 1),
+    ("executeTokenMetaTransaction(address,address,address,uint256,string,uint256,bytes)", 1),
+    ("executeBatchTokenMetaTransaction(address,address[],uint256[],string,uint256,bytes)", 1),
+];
+
 impl TransactionValidator {
     pub fn new(config: Arc<Config>) -> Self {
         Self {
             config,
             rate_limit_state: Arc::new(Mutex::new(HashMap::new())),
+            denylist: None,
+            allowlist: None,
+            plugins: None,
         }
     }
 
-    pub async fn validate_transaction(&self, signed_tx: &str) -> Result<ValidationResult> {
+    pub fn with_denylist(mut self, denylist: Arc<RecipientDenylist>) -> Self {
+        self.denylist = Some(denylist);
+        self
+    }
+
+    /// Only relay transfers to allowlisted recipients; the denylist takes precedence
+    pub fn with_allowlist(mut self, allowlist: Arc<RecipientAllowlist>) -> Self {
+        self.allowlist = Some(allowlist);
+        self
+    }
+
+    /// Run the registered plugins after the built-in checks
+    pub fn with_plugins(mut self, plugins: Arc<ValidatorPlugins>) -> Self {
+        self.plugins = Some(plugins);
+        self
+    }
+
+    /// Validate a signed transaction submitted for `chain_id`
+    pub async fn validate_transaction(&self, signed_tx: &str, chain_id: u64) -> Result<ValidationResult> {
         let mut result = ValidationResult {
             valid: true,
             errors: Vec::new(),
@@ -39,11 +89,26 @@ impl TransactionValidator {
             result.valid = false;
             result.errors.push(format!("Invalid transaction format: {e}"));
         }
-        let chain_id = self.extract_chain_id_from_transaction(signed_tx).unwrap_or(self.config.chain_id);
         if let Err(e) = self.validate_chain_id(chain_id) {
             result.valid = false;
             result.errors.push(format!("Invalid chain ID: {e}"));
         }
+        match Self::decode_signed_fields(signed_tx) {
+            Ok(fields) => {
+                if let Err(e) = Self::validate_signed_chain_id(&fields, chain_id) {
+                    result.valid = false;
+                    result.errors.push(format!("Invalid chain ID: {e}"));
+                }
+                if let Err(e) = Self::validate_to_address(&fields) {
+                    result.valid = false;
+                    result.errors.push(format!("Invalid 'to' address: {e}"));
+                }
+            }
+            Err(e) => {
+                result.valid = false;
+                result.errors.push(format!("Invalid transaction encoding: {e}"));
+            }
+        }
         if let Err(e) = self.validate_transaction_size(signed_tx) {
             result.valid = false;
             result.errors.push(format!("Invalid transaction size: {e}"));
@@ -60,6 +125,10 @@ impl TransactionValidator {
             result.valid = false;
             result.errors.push(format!("Invalid gas limits: {e}"));
         }
+        if let Err(e) = self.validate_gas_price(signed_tx, chain_id) {
+            result.valid = false;
+            result.errors.push(format!("Gas price too high: {e}"));
+        }
         if let Err(e) = self.validate_nonce(signed_tx, chain_id).await {
             result.warnings.push(format!("Nonce validation warning: {e}"));
         }
@@ -67,6 +136,26 @@ impl TransactionValidator {
             result.valid = false;
             result.errors.push(format!("Invalid contract interaction: {e}"));
         }
+        for recipient in self.extract_recipients_from_transaction(signed_tx) {
+            if let Some(denylist) = &self.denylist {
+                if let Err(e) = denylist.check(&recipient, chain_id).await {
+                    result.valid = false;
+                    result.errors.push(format!("Denylisted recipient: {e}"));
+                    continue;
+                }
+            }
+            if let Some(allowlist) = &self.allowlist {
+                // The chain's payment contract is the relay's own, not a payee
+                let is_payment_contract = self.config.supported_chains.get(&chain_id)
+                    .is_some_and(|chain| chain.contract_address.eq_ignore_ascii_case(&recipient));
+                if !is_payment_contract {
+                    if let Err(e) = allowlist.check(&recipient) {
+                        result.valid = false;
+                        result.errors.push(format!("Recipient not allowed: {e}"));
+                    }
+                }
+            }
+        }
         if let Err(e) = self.check_rate_limits().await {
             result.valid = false;
             result.errors.push(format!("Rate limit exceeded: {e}"));
@@ -79,6 +168,28 @@ impl TransactionValidator {
                 result.errors.push(format!("Invalid transaction amount: {e}"));
             }
         }
+
+        if let Some(plugins) = self.plugins.as_ref().filter(|plugins| !plugins.is_empty()) {
+            let submission = SubmittedTransaction {
+                signed_tx,
+                chain_id,
+                sender: recover_sender(signed_tx).ok(),
+                recipients: self.extract_recipients_from_transaction(signed_tx),
+            };
+            for plugin in plugins.all() {
+                match plugin.validate(&submission).await {
+                    Ok(findings) => {
+                        result.valid &= findings.errors.is_empty();
+                        result.errors.extend(findings.errors.into_iter().map(|e| format!("{}: {e}", plugin.name())));
+                        result.warnings.extend(findings.warnings.into_iter().map(|w| format!("{}: {w}", plugin.name())));
+                    }
+                    Err(e) => {
+                        result.valid = false;
+                        result.errors.push(format!("{}: validator failed: {e}", plugin.name()));
+                    }
+                }
+            }
+        }
         
         Ok(result)
     }
@@ -127,22 +238,67 @@ impl TransactionValidator {
             .map_err(|e| anyhow!("Invalid hex payload: {}", e))
     }
 
+    /// The signature must recover to a sender
     async fn validate_signature(&self, signed_tx: &str) -> Result<()> {
+        recover_sender(signed_tx).map(|_| ())
+    }
+
+    /// Chain ID, gas limit and `to` of a legacy (with or without Celo's fee fields),
+    /// EIP-2930 or EIP-1559 transaction, read from the RLP fields directly
+    fn decode_signed_fields(signed_tx: &str) -> Result<SignedFields> {
         let tx_bytes = hex::decode(signed_tx.trim_start_matches("0x"))
             .map_err(|e| anyhow!("Failed to decode hex: {}", e))?;
-        if tx_bytes.len() < 65 {
-            return Err(anyhow!("Transaction too short for signature"));
-        }
-        let signature_start = tx_bytes.len() - 65;
-        let signature = &tx_bytes[signature_start..];
-        if signature.len() != 65 {
-            return Err(anyhow!("Invalid signature length"));
+        let rlp = Rlp::new(&tx_bytes);
+        let fields = if rlp.is_list() {
+            // [nonce, gasPrice, gasLimit, to, value, data, v, r, s], or with Celo's
+            // feeCurrency, gatewayFeeRecipient and gatewayFee after gasLimit
+            let (to_index, v_index) = match rlp.item_count() {
+                Ok(9) => (3, 6),
+                Ok(12) => (6, 9),
+                _ => return Err(anyhow!("Legacy transaction must have 9 or 12 fields")),
+            };
+            let v: u64 = rlp.val_at(v_index).map_err(|e| anyhow!("Failed to decode v: {}", e))?;
+            SignedFields {
+                // EIP-155 signatures commit to the chain id
+                chain_id: (v >= 35).then(|| (v - 35) / 2),
+                gas_limit: rlp.val_at(2).map_err(|e| anyhow!("Failed to decode gas limit: {}", e))?,
+                to: rlp.val_at(to_index).map_err(|e| anyhow!("Failed to decode 'to': {}", e))?,
+            }
+        } else {
+            let (tx_type, payload) = tx_bytes.split_first()
+                .ok_or_else(|| anyhow!("Transaction is empty"))?;
+            let (gas_index, to_index) = match tx_type {
+                // EIP-2930: [chainId, nonce, gasPrice, gasLimit, to, ...]
+                0x01 => (3, 4),
+                // EIP-1559: [chainId, nonce, maxPriorityFeePerGas, maxFeePerGas, gasLimit, to, ...]
+                0x02 => (4, 5),
+                _ => return Err(anyhow!("Unsupported transaction type 0x{:02x}", tx_type)),
+            };
+            let fields = Rlp::new(payload);
+            SignedFields {
+                chain_id: Some(fields.val_at(0).map_err(|e| anyhow!("Failed to decode chain ID: {}", e))?),
+                gas_limit: fields.val_at(gas_index).map_err(|e| anyhow!("Failed to decode gas limit: {}", e))?,
+                to: fields.val_at(to_index).map_err(|e| anyhow!("Failed to decode 'to': {}", e))?,
+            }
+        };
+        Ok(fields)
+    }
+
+    /// The chain the transaction was signed for must be the one it was submitted to
+    fn validate_signed_chain_id(fields: &SignedFields, chain_id: u64) -> Result<()> {
+        match fields.chain_id {
+            Some(signed) if signed == chain_id => Ok(()),
+            Some(signed) => Err(anyhow!("Transaction is signed for chain {}, not {}", signed, chain_id)),
+            None => Err(anyhow!("Transaction is not replay-protected (no EIP-155 chain ID)")),
         }
-        let v = signature[64];
-        if v != 27 && v != 28 && v != 0 && v != 1 {
-            return Err(anyhow!("Invalid signature v value"));
+    }
+
+    /// `to` is either empty (contract creation) or a 20-byte address
+    fn validate_to_address(fields: &SignedFields) -> Result<()> {
+        match fields.to.len() {
+            0 | 20 => Ok(()),
+            len => Err(anyhow!("Expected a 20-byte address, got {} bytes (0x{})", len, hex::encode(&fields.to))),
         }
-        Ok(())
     }
 
     /// Helper to decode a signed transaction into ethers::types::Transaction
@@ -153,8 +309,8 @@ impl TransactionValidator {
         Transaction::decode(&rlp).map_err(|e| anyhow!("Failed to decode transaction: {}", e))
     }
 
-    fn extract_gas_limit_from_transaction(&self, signed_tx: &str) -> Option<u64> {
-        self.decode_transaction(signed_tx).ok().map(|tx| tx.gas.as_u64())
+    fn extract_gas_limit_from_transaction(&self, signed_tx: &str) -> Option<U256> {
+        Self::decode_signed_fields(signed_tx).ok().map(|fields| fields.gas_limit)
     }
 
     fn extract_nonce_from_transaction(&self, signed_tx: &str) -> Option<u64> {
@@ -165,6 +321,39 @@ impl TransactionValidator {
         self.decode_transaction(signed_tx).ok().and_then(|tx| tx.to.map(|to| format!("0x{:x}", to)))
     }
 
+    /// The transaction's `to` plus any recipient decoded from a known payment call
+    fn extract_recipients_from_transaction(&self, signed_tx: &str) -> Vec<String> {
+        let Ok(tx) = self.decode_transaction(signed_tx) else {
+            return Vec::new();
+        };
+        let mut recipients: Vec<Address> = tx.to.into_iter().collect();
+        if tx.input.len() >= 4 {
+            let (selector, args) = tx.input.split_at(4);
+            for (signature, index) in RECIPIENT_CALLS {
+                if ethers::utils::id(signature) != selector {
+                    continue;
+                }
+                let types = Self::param_types(signature);
+                if let Ok(tokens) = ethers::abi::decode(&types, args) {
+                    match tokens.get(*index) {
+                        Some(Token::Address(address)) => recipients.push(*address),
+                        Some(Token::Array(items)) => recipients.extend(items.iter().filter_map(|t| t.clone().into_address())),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        recipients.into_iter().map(|address| format!("0x{:x}", address)).collect()
+    }
+
+    fn param_types(signature: &str) -> Vec<ParamType> {
+        let params = signature.split_once('(').map(|(_, rest)| rest.trim_end_matches(')')).unwrap_or_default();
+        params
+            .split(',')
+            .filter_map(|param| ethers::abi::param_type::Reader::read(param).ok())
+            .collect()
+    }
+
     fn validate_gas_limits(&self, signed_tx: &str, chain_id: u64) -> Result<()> {
         // Set chain-specific default max gas limits
         // Base (ETH): much lower, Core (non-ETH): higher
@@ -188,15 +377,51 @@ impl TransactionValidator {
             .unwrap_or(default_max_gas_limit);
         let gas_limit = self.extract_gas_limit_from_transaction(signed_tx)
             .ok_or_else(|| anyhow!("Failed to extract gas limit from transaction"))?;
-        if gas_limit == 0 {
+        if gas_limit.is_zero() {
             return Err(anyhow!("Gas limit cannot be zero"));
         }
-        if gas_limit > max_gas_limit {
+        if gas_limit > U256::from(max_gas_limit) {
             return Err(anyhow!("Gas limit {} exceeds max allowed {}", gas_limit, max_gas_limit));
         }
         Ok(())
     }
 
+    /// Reject transactions priced above the chain's `max_gas_price_wei`. For EIP-1559
+    /// transactions the max fee is checked, since that is what the sender may end up paying.
+    fn validate_gas_price(&self, signed_tx: &str, chain_id: u64) -> Result<()> {
+        let Some(max_gas_price) = self.config.supported_chains.get(&chain_id).and_then(|c| c.max_gas_price_wei) else {
+            return Ok(());
+        };
+        let Some((field, price)) = Self::extract_fee_cap_from_transaction(signed_tx) else {
+            // Undecodable transactions are already reported by the other checks
+            return Ok(());
+        };
+        if price > U256::from(max_gas_price) {
+            return Err(anyhow!("{} {} wei exceeds the maximum of {} wei for chain {}", field, price, max_gas_price, chain_id));
+        }
+        Ok(())
+    }
+
+    /// Highest per-gas price the transaction allows, read from the RLP fields directly so
+    /// plain legacy transactions are understood as well as typed ones
+    fn extract_fee_cap_from_transaction(signed_tx: &str) -> Option<(&'static str, U256)> {
+        let tx_bytes = hex::decode(signed_tx.trim_start_matches("0x")).ok()?;
+        let rlp = Rlp::new(&tx_bytes);
+        if rlp.is_list() {
+            // Legacy: [nonce, gasPrice, gasLimit, ...]
+            return rlp.val_at(1).ok().map(|price| ("gas price", price));
+        }
+        let (tx_type, payload) = tx_bytes.split_first()?;
+        let fields = Rlp::new(payload);
+        match tx_type {
+            // EIP-2930: [chainId, nonce, gasPrice, ...]
+            0x01 => fields.val_at(2).ok().map(|price| ("gas price", price)),
+            // EIP-1559: [chainId, nonce, maxPriorityFeePerGas, maxFeePerGas, ...]
+            0x02 => fields.val_at(3).ok().map(|price| ("max fee per gas", price)),
+            _ => None,
+        }
+    }
+
     async fn validate_nonce(&self, signed_tx: &str, _chain_id: u64) -> Result<()> {
         // Parse nonce from transaction
         let nonce = self.extract_nonce_from_transaction(signed_tx)
@@ -253,10 +478,6 @@ impl TransactionValidator {
         }
     }
 
-    fn extract_chain_id_from_transaction(&self, signed_tx: &str) -> Option<u64> {
-        self.decode_transaction(signed_tx).ok().and_then(|tx| tx.chain_id).map(|id| id.as_u64()).or(Some(self.config.chain_id))
-    }
-
     fn extract_amount_from_transaction(&self, signed_tx: &str) -> Option<String> {
         self.decode_transaction(signed_tx).ok().map(|tx| tx.value.to_string())
     }
@@ -301,4 +522,308 @@ impl TransactionValidator {
             }
         }
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::DenylistConfig;
+    use crate::validators::plugin::PluginFindings;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::{transaction::eip2718::TypedTransaction, Bytes, Eip1559TransactionRequest};
+
+    const BLOCKED: &str = "0x8589427373d6d84e98730d7795d8f6f8731fda16";
+    const CONTRACT: &str = "0x2222222222222222222222222222222222222222";
+
+    fn signed_tx(to: &str, input: Vec<u8>) -> String {
+        let wallet: LocalWallet = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(1114u64);
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(to.parse::<Address>().unwrap())
+            .value(1_000_000_000_000_000u64)
+            .gas(100_000)
+            .max_fee_per_gas(2_000_000_000u64)
+            .max_priority_fee_per_gas(1_000_000_000u64)
+            .nonce(0)
+            .data(Bytes::from(input))
+            .chain_id(1114u64)
+            .into();
+        let signature = wallet.sign_transaction_sync(&tx).unwrap();
+        format!("0x{}", hex::encode(tx.rlp_signed(&signature)))
+    }
+
+    fn validator() -> TransactionValidator {
+        let denylist = RecipientDenylist::from_config(&DenylistConfig {
+            addresses: vec![BLOCKED.to_uppercase().replacen("0X", "0x", 1)],
+            file: None,
+        }).unwrap();
+        TransactionValidator::new(Arc::new(Config::default())).with_denylist(Arc::new(denylist))
+    }
+
+    fn denylist_errors(result: &ValidationResult) -> Vec<&String> {
+        result.errors.iter().filter(|e| e.starts_with("Denylisted recipient")).collect()
+    }
+
+    #[tokio::test]
+    async fn test_denylisted_recipient_is_blocked() {
+        let result = validator().validate_transaction(&signed_tx(BLOCKED, Vec::new()), 1114).await.unwrap();
+        assert!(!result.valid);
+        assert_eq!(denylist_errors(&result).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_denylisted_recipient_in_payment_calldata_is_blocked() {
+        let mut input = ethers::utils::id("pay(address,string)").to_vec();
+        input.extend(ethers::abi::encode(&[
+            Token::Address(BLOCKED.parse().unwrap()),
+            Token::String("invoice-1".to_string()),
+        ]));
+
+        let result = validator().validate_transaction(&signed_tx(CONTRACT, input), 1114).await.unwrap();
+        assert!(!result.valid);
+        assert_eq!(denylist_errors(&result).len(), 1);
+    }
+
+    fn legacy_signed_tx(gas_price: u64) -> String {
+        let wallet: LocalWallet = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(1114u64);
+        let tx: TypedTransaction = ethers::types::TransactionRequest::new()
+            .to(CONTRACT.parse::<Address>().unwrap())
+            .value(1_000u64)
+            .gas(21_000)
+            .gas_price(gas_price)
+            .nonce(0)
+            .chain_id(1114u64)
+            .into();
+        let signature = wallet.sign_transaction_sync(&tx).unwrap();
+        format!("0x{}", hex::encode(tx.rlp_signed(&signature)))
+    }
+
+    fn capped_validator(max_gas_price_wei: u64) -> TransactionValidator {
+        let mut config = Config::default();
+        config.supported_chains.insert(1114, crate::infrastructure::config::ChainConfig {
+            max_gas_price_wei: Some(max_gas_price_wei),
+            ..Default::default()
+        });
+        TransactionValidator::new(Arc::new(config))
+    }
+
+    fn gas_price_errors(result: &ValidationResult) -> Vec<&String> {
+        result.errors.iter().filter(|e| e.starts_with("Gas price too high")).collect()
+    }
+
+    #[tokio::test]
+    async fn test_gas_price_cap_for_legacy_transactions() {
+        let validator = capped_validator(50_000_000_000);
+
+        let result = validator.validate_transaction(&legacy_signed_tx(60_000_000_000), 1114).await.unwrap();
+        assert!(!result.valid);
+        let errors = gas_price_errors(&result);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("gas price 60000000000 wei exceeds the maximum of 50000000000 wei"));
+
+        // At the cap the transaction passes the gas price check
+        let result = validator.validate_transaction(&legacy_signed_tx(50_000_000_000), 1114).await.unwrap();
+        assert!(gas_price_errors(&result).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_gas_price_cap_for_eip1559_transactions() {
+        // signed_tx uses a 2 gwei max fee and a 1 gwei priority fee
+        let result = capped_validator(1_500_000_000).validate_transaction(&signed_tx(CONTRACT, Vec::new()), 1114).await.unwrap();
+        assert!(!result.valid);
+        assert!(gas_price_errors(&result)[0].contains("max fee per gas 2000000000 wei"));
+
+        let result = capped_validator(2_000_000_000).validate_transaction(&signed_tx(CONTRACT, Vec::new()), 1114).await.unwrap();
+        assert!(gas_price_errors(&result).is_empty());
+
+        // Without a configured cap nothing is rejected
+        let result = validator().validate_transaction(&signed_tx(CONTRACT, Vec::new()), 1114).await.unwrap();
+        assert!(gas_price_errors(&result).is_empty());
+    }
+
+    /// Rejects transfers to one address and flags every submission it sees
+    struct BlockAddressPlugin {
+        blocked: &'static str,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::validators::plugin::TransactionValidatorPlugin for BlockAddressPlugin {
+        fn name(&self) -> &str {
+            "kyc"
+        }
+
+        async fn validate(&self, tx: &SubmittedTransaction<'_>) -> Result<PluginFindings> {
+            assert!(tx.sender.is_some());
+            if tx.recipients.iter().any(|r| r == self.blocked) {
+                return Ok(PluginFindings::reject(format!("recipient {} has not passed KYC", self.blocked)));
+            }
+            Ok(PluginFindings::warn("checked"))
+        }
+    }
+
+    struct FailingPlugin;
+
+    #[async_trait::async_trait]
+    impl crate::validators::plugin::TransactionValidatorPlugin for FailingPlugin {
+        fn name(&self) -> &str {
+            "limits"
+        }
+
+        async fn validate(&self, _tx: &SubmittedTransaction<'_>) -> Result<PluginFindings> {
+            Err(anyhow!("limits service unreachable"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plugin_errors_are_combined_with_builtin_errors() {
+        let plugins = Arc::new(ValidatorPlugins::default());
+        plugins.register(Arc::new(BlockAddressPlugin { blocked: BLOCKED }));
+        let validator = validator().with_plugins(Arc::clone(&plugins));
+
+        let result = validator.validate_transaction(&signed_tx(BLOCKED, Vec::new()), 1114).await.unwrap();
+        assert!(!result.valid);
+        assert_eq!(denylist_errors(&result).len(), 1);
+        assert!(result.errors.contains(&format!("kyc: recipient {} has not passed KYC", BLOCKED)));
+
+        // Only annotated, so the plugin alone doesn't reject it
+        let result = validator.validate_transaction(&signed_tx(CONTRACT, Vec::new()), 1114).await.unwrap();
+        assert!(result.errors.iter().all(|e| !e.starts_with("kyc:")));
+        assert!(result.warnings.contains(&"kyc: checked".to_string()));
+
+        // Plugins run in registration order and a failing one rejects the submission
+        plugins.register(Arc::new(FailingPlugin));
+        let result = validator.validate_transaction(&signed_tx(BLOCKED, Vec::new()), 1114).await.unwrap();
+        let plugin_errors: Vec<&String> = result.errors.iter().filter(|e| e.starts_with("kyc:") || e.starts_with("limits:")).collect();
+        assert_eq!(plugin_errors.len(), 2);
+        assert!(plugin_errors[0].starts_with("kyc:"));
+        assert_eq!(plugin_errors[1], "limits: validator failed: limits service unreachable");
+    }
+
+    fn transfer(chain_id: u64, gas: u64) -> String {
+        let wallet: LocalWallet = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(chain_id);
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .to(CONTRACT.parse::<Address>().unwrap())
+            .value(1_000u64)
+            .gas(gas)
+            .max_fee_per_gas(2_000_000_000u64)
+            .max_priority_fee_per_gas(1_000_000_000u64)
+            .nonce(0)
+            .chain_id(chain_id)
+            .into();
+        let signature = wallet.sign_transaction_sync(&tx).unwrap();
+        format!("0x{}", hex::encode(tx.rlp_signed(&signature)))
+    }
+
+    fn gas_limited_validator(max_gas_limit: u64) -> TransactionValidator {
+        let mut config = Config::default();
+        config.supported_chains.insert(1114, crate::infrastructure::config::ChainConfig {
+            max_gas_limit: Some(max_gas_limit),
+            ..Default::default()
+        });
+        TransactionValidator::new(Arc::new(config))
+    }
+
+    #[tokio::test]
+    async fn test_well_formed_transaction_passes_field_checks() {
+        let result = gas_limited_validator(100_000).validate_transaction(&transfer(1114, 100_000), 1114).await.unwrap();
+        assert!(result.valid, "{:?}", result.errors);
+        assert!(result.errors.is_empty());
+
+        let result = validator().validate_transaction(&legacy_signed_tx(1_000_000_000), 1114).await.unwrap();
+        assert!(result.errors.iter().all(|e| !e.starts_with("Invalid chain ID") && !e.starts_with("Invalid gas limits")), "{:?}", result.errors);
+    }
+
+    #[tokio::test]
+    async fn test_chain_id_must_match_the_request() {
+        let result = validator().validate_transaction(&transfer(1114, 21_000), 84532).await.unwrap();
+        assert!(!result.valid);
+        assert!(result.errors.contains(&"Invalid chain ID: Transaction is signed for chain 1114, not 84532".to_string()), "{:?}", result.errors);
+    }
+
+    #[tokio::test]
+    async fn test_gas_limit_above_chain_maximum_is_rejected() {
+        let result = gas_limited_validator(100_000).validate_transaction(&transfer(1114, 100_001), 1114).await.unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.errors, vec!["Invalid gas limits: Gas limit 100001 exceeds max allowed 100000".to_string()]);
+    }
+
+    #[test]
+    fn test_malformed_fields_are_reported() {
+        // A legacy list whose `to` is 3 bytes: [nonce, gasPrice, gasLimit, to, value, data, v, r, s]
+        let mut stream = ethers::core::utils::rlp::RlpStream::new_list(9);
+        stream.append(&0u8).append(&1u8).append(&21_000u64).append(&vec![0xabu8, 0xcd, 0xef])
+            .append(&0u8).append(&Vec::<u8>::new()).append(&2263u64).append(&1u8).append(&1u8);
+        let signed = format!("0x{}", hex::encode(stream.out()));
+        let fields = TransactionValidator::decode_signed_fields(&signed).unwrap();
+        assert_eq!(fields.chain_id, Some(1114));
+        assert!(TransactionValidator::validate_to_address(&fields).unwrap_err().to_string().contains("got 3 bytes"));
+
+        assert!(TransactionValidator::decode_signed_fields("0x03c0").is_err());
+    }
+
+    fn listed_validator(allowed: &[&str]) -> TransactionValidator {
+        let mut config = Config::default();
+        config.supported_chains.insert(1114, crate::infrastructure::config::ChainConfig {
+            contract_address: CONTRACT.to_string(),
+            ..Default::default()
+        });
+        let allowlist = RecipientAllowlist::from_config(&crate::infrastructure::config::AllowlistConfig {
+            addresses: allowed.iter().map(|a| a.to_string()).collect(),
+            file: None,
+        }).unwrap();
+        let denylist = RecipientDenylist::from_config(&DenylistConfig { addresses: vec![BLOCKED.to_string()], file: None }).unwrap();
+        TransactionValidator::new(Arc::new(config))
+            .with_denylist(Arc::new(denylist))
+            .with_allowlist(Arc::new(allowlist))
+    }
+
+    fn pay(recipient: &str) -> String {
+        let mut input = ethers::utils::id("pay(address,string)").to_vec();
+        input.extend(ethers::abi::encode(&[
+            Token::Address(recipient.parse().unwrap()),
+            Token::String("order-7".to_string()),
+        ]));
+        signed_tx(CONTRACT, input)
+    }
+
+    fn allowlist_errors(result: &ValidationResult) -> Vec<&String> {
+        result.errors.iter().filter(|e| e.starts_with("Recipient not allowed")).collect()
+    }
+
+    #[tokio::test]
+    async fn test_allowlisted_recipient_passes() {
+        const MERCHANT: &str = "0x3333333333333333333333333333333333333333";
+        let result = listed_validator(&[MERCHANT]).validate_transaction(&pay(MERCHANT), 1114).await.unwrap();
+        assert!(allowlist_errors(&result).is_empty(), "{:?}", result.errors);
+        assert!(denylist_errors(&result).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recipient_outside_allowlist_is_rejected() {
+        let other = "0x4444444444444444444444444444444444444444";
+        let result = listed_validator(&["0x3333333333333333333333333333333333333333"]).validate_transaction(&pay(other), 1114).await.unwrap();
+        assert!(!result.valid);
+        assert_eq!(allowlist_errors(&result), vec![&format!("Recipient not allowed: Recipient {} is not on the allowlist", other)]);
+    }
+
+    #[tokio::test]
+    async fn test_denylist_takes_precedence_over_allowlist() {
+        let result = listed_validator(&[BLOCKED]).validate_transaction(&pay(BLOCKED), 1114).await.unwrap();
+        assert!(!result.valid);
+        assert_eq!(denylist_errors(&result).len(), 1);
+        assert!(allowlist_errors(&result).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_normal_recipient_passes_denylist() {
+        let result = validator().validate_transaction(&signed_tx(CONTRACT, Vec::new()), 1114).await.unwrap();
+        assert!(denylist_errors(&result).is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+//! Raw EIP-155 legacy transaction signing for `no_std + alloc` targets
+//!
+//! Raw key bytes and a [`LegacyTransaction`] go in, signed RLP bytes come out, with no
+//! network, filesystem or `std` involved, only `core` and `alloc`. The wallet core signs
+//! through the same functions, so hardware wallets and the app produce identical bytes.
+//!
+//! ```ignore
+//! let raw_tx = airchainpay_signing::sign_legacy_raw(&transaction, &key_bytes)?;
+//! let tx_hash = airchainpay_signing::transaction_hash(&raw_tx);
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt;
+use rlp::RlpStream;
+use secp256k1::{Message, Secp256k1, SecretKey};
+use sha3::{Digest, Keccak256};
+
+/// Fields of an unsigned legacy transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LegacyTransaction<'a> {
+    pub nonce: u64,
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    /// Hex recipient address, with or without `0x`; empty for contract creation
+    pub to: &'a str,
+    /// Amount in wei, as a decimal string
+    pub value: &'a str,
+    pub data: &'a [u8],
+    pub chain_id: u64,
+}
+
+/// Why a transaction could not be encoded or signed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningError {
+    InvalidTo,
+    InvalidValue,
+    ValueOverflow,
+    InvalidKeyLength,
+    InvalidKey,
+}
+
+impl fmt::Display for SigningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SigningError::InvalidTo => "Invalid to address",
+            SigningError::InvalidValue => "Invalid value",
+            SigningError::ValueOverflow => "Value exceeds 256 bits",
+            SigningError::InvalidKeyLength => "Invalid private key length",
+            SigningError::InvalidKey => "Invalid private key",
+        })
+    }
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Transaction hash of a signed raw transaction, as reported by the node
+pub fn transaction_hash(raw_tx: &[u8]) -> [u8; 32] {
+    keccak256(raw_tx)
+}
+
+/// Decoded fields shared by the EIP-155 signing payload and the signed transaction
+struct LegacyFields<'a> {
+    tx: &'a LegacyTransaction<'a>,
+    to: Vec<u8>,
+    value: Vec<u8>,
+}
+
+impl<'a> LegacyFields<'a> {
+    fn decode(tx: &'a LegacyTransaction<'a>) -> Result<Self, SigningError> {
+        let to = if tx.to.is_empty() {
+            Vec::new()
+        } else {
+            hex::decode(tx.to.trim_start_matches("0x")).map_err(|_| SigningError::InvalidTo)?
+        };
+        Ok(Self { tx, to, value: decimal_to_be_bytes(tx.value)? })
+    }
+
+    fn append_to(&self, stream: &mut RlpStream) {
+        stream.append(&self.tx.nonce);
+        stream.append(&self.tx.gas_price);
+        stream.append(&self.tx.gas_limit);
+        if self.to.is_empty() {
+            stream.append_empty_data();
+        } else {
+            stream.append(&self.to.as_slice());
+        }
+        stream.append(&self.value.as_slice());
+        stream.append(&self.tx.data);
+    }
+}
+
+/// RLP payload signed for an EIP-155 legacy transaction:
+/// `rlp([nonce, gasPrice, gasLimit, to, value, data, chainId, 0, 0])`
+pub fn encode_legacy_signing_payload(tx: &LegacyTransaction) -> Result<Vec<u8>, SigningError> {
+    Ok(signing_payload(&LegacyFields::decode(tx)?))
+}
+
+fn signing_payload(fields: &LegacyFields) -> Vec<u8> {
+    let mut stream = RlpStream::new_list(9);
+    fields.append_to(&mut stream);
+    stream.append(&fields.tx.chain_id);
+    stream.append_empty_data();
+    stream.append_empty_data();
+    stream.out().to_vec()
+}
+
+/// Sign a legacy transaction with EIP-155 replay protection and return the raw RLP bytes
+/// accepted by `eth_sendRawTransaction`. `key_bytes` is the 32-byte secp256k1 secret key.
+pub fn sign_legacy_raw(tx: &LegacyTransaction, key_bytes: &[u8]) -> Result<Vec<u8>, SigningError> {
+    let key_bytes: [u8; 32] = key_bytes.try_into().map_err(|_| SigningError::InvalidKeyLength)?;
+    let secret_key = SecretKey::from_byte_array(key_bytes).map_err(|_| SigningError::InvalidKey)?;
+
+    let fields = LegacyFields::decode(tx)?;
+    let sighash = keccak256(&signing_payload(&fields));
+    let signature = Secp256k1::signing_only().sign_ecdsa_recoverable(Message::from_digest(sighash), &secret_key);
+    let (recovery_id, compact) = signature.serialize_compact();
+    let v = i32::from(recovery_id) as u64 + 35 + 2 * tx.chain_id;
+
+    let mut stream = RlpStream::new_list(9);
+    fields.append_to(&mut stream);
+    stream.append(&v);
+    // r and s are RLP integers, so leading zero bytes are dropped
+    stream.append(&trim_leading_zeros(&compact[..32]));
+    stream.append(&trim_leading_zeros(&compact[32..]));
+    Ok(stream.out().to_vec())
+}
+
+fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+/// Minimal big-endian bytes of a decimal amount of at most 256 bits; zero is empty
+fn decimal_to_be_bytes(value: &str) -> Result<Vec<u8>, SigningError> {
+    if value.is_empty() {
+        return Err(SigningError::InvalidValue);
+    }
+    let mut buf = [0u8; 32];
+    for digit in value.bytes() {
+        if !digit.is_ascii_digit() {
+            return Err(SigningError::InvalidValue);
+        }
+        let mut carry = u16::from(digit - b'0');
+        for byte in buf.iter_mut().rev() {
+            let product = u16::from(*byte) * 10 + carry;
+            *byte = product as u8;
+            carry = product >> 8;
+        }
+        if carry != 0 {
+            return Err(SigningError::ValueOverflow);
+        }
+    }
+    Ok(trim_leading_zeros(&buf).to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The worked example from EIP-155
+    fn eip155_example() -> LegacyTransaction<'static> {
+        LegacyTransaction {
+            nonce: 9,
+            gas_price: 20_000_000_000,
+            gas_limit: 21_000,
+            to: "0x3535353535353535353535353535353535353535",
+            value: "1000000000000000000",
+            data: &[],
+            chain_id: 1,
+        }
+    }
+
+    #[test]
+    fn test_signs_eip155_example() {
+        let key = [0x46u8; 32];
+        let tx = eip155_example();
+        assert_eq!(
+            encode_legacy_signing_payload(&tx).unwrap(),
+            hex::decode("ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080").unwrap()
+        );
+        assert_eq!(
+            sign_legacy_raw(&tx, &key).unwrap(),
+            hex::decode(concat!(
+                "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025",
+                "a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276",
+                "a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+            )).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_fields_and_keys() {
+        let key = [0x46u8; 32];
+        assert_eq!(sign_legacy_raw(&LegacyTransaction { to: "0xzz", ..eip155_example() }, &key), Err(SigningError::InvalidTo));
+        assert_eq!(sign_legacy_raw(&eip155_example(), &key[..31]), Err(SigningError::InvalidKeyLength));
+        assert_eq!(sign_legacy_raw(&eip155_example(), &[0u8; 32]), Err(SigningError::InvalidKey));
+    }
+
+    #[test]
+    fn test_decimal_to_be_bytes() {
+        assert_eq!(decimal_to_be_bytes("0").unwrap(), Vec::<u8>::new());
+        assert_eq!(decimal_to_be_bytes("255").unwrap(), [0xff]);
+        assert_eq!(decimal_to_be_bytes("256").unwrap(), [0x01, 0x00]);
+        assert_eq!(decimal_to_be_bytes("1000000000000000000").unwrap(), [0x0d, 0xe0, 0xb6, 0xb3, 0xa7, 0x64, 0x00, 0x00]);
+        // U256::MAX, then one past it
+        assert_eq!(
+            decimal_to_be_bytes("115792089237316195423570985008687907853269984665640564039457584007913129639935").unwrap(),
+            [0xff; 32]
+        );
+        assert_eq!(
+            decimal_to_be_bytes("115792089237316195423570985008687907853269984665640564039457584007913129639936"),
+            Err(SigningError::ValueOverflow)
+        );
+        assert_eq!(decimal_to_be_bytes(""), Err(SigningError::InvalidValue));
+        assert_eq!(decimal_to_be_bytes("-1"), Err(SigningError::InvalidValue));
+        assert_eq!(decimal_to_be_bytes("1.5"), Err(SigningError::InvalidValue));
+    }
+}
@@ -5,59 +5,123 @@
 use crate::shared::error::WalletError;
 use crate::shared::types::BLEPaymentData;
 use crate::shared::types::BLEDeviceInfo;
-use aes_gcm::{Aes256Gcm, aead::{Aead}};
-use aes_gcm::KeyInit;
-use aes_gcm::aead::generic_array::GenericArray;
-use rand_core::OsRng;
-use rand_core::RngCore;
+use crate::core::crypto::encryption::{EncryptedData, EncryptionAlgorithm, EncryptionManager};
+#[cfg(not(target_arch = "wasm32"))]
 use futures_lite::stream::StreamExt;
+use std::time::Duration;
 
 /// BLE security manager
 pub struct BLESecurityManager {
-   
+    /// Refuse every radio operation; payment data encryption keeps working
+    offline: bool,
 }
 
 impl BLESecurityManager {
     pub fn new() -> Self {
-        Self {}
+        Self { offline: false }
+    }
+
+    pub fn with_offline_mode(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    fn ensure_online(&self, operation: &str) -> Result<(), WalletError> {
+        if self.offline {
+            return Err(WalletError::offline(operation));
+        }
+        Ok(())
     }
 
     pub async fn init(&self) -> Result<(), WalletError> {
+        self.ensure_online("BLE")?;
         log::info!("Initializing BLE security manager");
         Ok(())
     }
 
     /// Start BLE advertising (peripheral role)
     pub async fn start_advertising(&self) -> Result<(), WalletError> {
+        self.ensure_online("BLE advertising")?;
         log::info!("Starting BLE advertising");
         // Bluest does not support peripheral/advertising yet
         Err(WalletError::ble("BLE advertising (peripheral role) not supported on this platform. AirChainPay is a mobile app, not a BLE device/peripheral."))
     }
 
     pub async fn stop_advertising(&self) -> Result<(), WalletError> {
+        self.ensure_online("BLE advertising")?;
         log::info!("Stopping BLE advertising");
         Ok(())
     }
 
     /// Start BLE scanning (central role)
     pub async fn start_scanning(&self) -> Result<(), WalletError> {
+        self.ensure_online("BLE scanning")?;
         log::info!("Starting BLE scanning");
         Ok(())
     }
 
+    /// Scan for nearby peripherals (central role) until `timeout` elapses
+    ///
+    /// Each peripheral is reported once with its latest name and signal strength,
+    /// strongest signal first.
+    pub async fn scan_for_devices(&self, timeout: Duration) -> Result<Vec<BLEDeviceInfo>, WalletError> {
+        self.ensure_online("BLE scanning")?;
+        if timeout.is_zero() {
+            return Err(WalletError::validation("BLE scan timeout must be greater than zero"));
+        }
+        log::info!("Scanning for BLE devices for {:?}", timeout);
+        #[cfg(target_os = "android")]
+        {
+            return Err(WalletError::ble("BLE functionality is not yet implemented for Android (JNI required for Adapter::new)".to_string()));
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            return Err(WalletError::ble("BLE is not available in WebAssembly builds".to_string()));
+        }
+        #[cfg(not(any(target_os = "android", target_arch = "wasm32")))]
+        {
+            let deadline = tokio::time::Instant::now() + timeout;
+            let adapter = bluest::Adapter::default().await.ok_or_else(|| WalletError::ble("No Bluetooth adapter found".to_string()))?;
+            tokio::time::timeout_at(deadline, adapter.wait_available())
+                .await
+                .map_err(|_| WalletError::ble("Bluetooth adapter did not become available before the scan timeout"))?
+                .map_err(|_| WalletError::ble("Bluetooth adapter not available"))?;
+            let mut scan = adapter.scan(&[]).await.map_err(|_| WalletError::ble("Failed to start BLE scan"))?;
+
+            let mut devices = Vec::new();
+            // The scan stream never ends on its own; the deadline is what stops it
+            while let Ok(Some(discovered)) = tokio::time::timeout_at(deadline, scan.next()).await {
+                let name = discovered.adv_data.local_name.clone()
+                    .or_else(|| discovered.device.name().ok());
+                let id = discovered.device.id().to_string();
+                record_discovery(&mut devices, &id, name, discovered.rssi);
+            }
+            devices.sort_by_key(|device| std::cmp::Reverse(device.rssi));
+            log::info!("BLE scan found {} device(s)", devices.len());
+            Ok(devices)
+        }
+    }
+
     pub async fn stop_scanning(&self) -> Result<(), WalletError> {
+        self.ensure_online("BLE scanning")?;
         log::info!("Stopping BLE scanning");
         Ok(())
     }
 
     /// Connect to a BLE device (central role)
     pub async fn connect_to_device(&self, device_info: &BLEDeviceInfo) -> Result<(), WalletError> {
+        self.ensure_online("BLE connection")?;
         log::info!("Connecting to BLE device: {}", device_info.name);
         // BLE device connection logic is stubbed for build
         Ok(())
     }
 
     pub async fn disconnect_from_device(&self) -> Result<(), WalletError> {
+        self.ensure_online("BLE connection")?;
         log::info!("Disconnecting from BLE device");
         // No-op: connection is managed per operation
         Ok(())
@@ -65,12 +129,17 @@ impl BLESecurityManager {
 
     /// Send payment data to a BLE receiver (central role)
     pub async fn send_payment(&self) -> Result<(), WalletError> {
+        self.ensure_online("BLE payment")?;
         log::info!("Sending payment via BLE (central role)");
         #[cfg(target_os = "android")]
         {
             return Err(WalletError::ble("BLE functionality is not yet implemented for Android (JNI required for Adapter::new)".to_string()));
         }
-        #[cfg(not(target_os = "android"))]
+        #[cfg(target_arch = "wasm32")]
+        {
+            return Err(WalletError::ble("BLE is not available in WebAssembly builds".to_string()));
+        }
+        #[cfg(not(any(target_os = "android", target_arch = "wasm32")))]
         {
             let adapter = bluest::Adapter::default().await.ok_or_else(|| WalletError::ble("No Bluetooth adapter found".to_string()))?;
             adapter.wait_available().await.map_err(|_| WalletError::ble("Bluetooth adapter not available"))?;
@@ -84,12 +153,17 @@ impl BLESecurityManager {
 
     /// Receive payment data from a BLE sender (central role)
     pub async fn receive_payment(&self) -> Result<BLEPaymentData, WalletError> {
+        self.ensure_online("BLE payment")?;
         log::info!("Receiving payment via BLE (central role)");
         #[cfg(target_os = "android")]
         {
             return Err(WalletError::ble("BLE functionality is not yet implemented for Android (JNI required for Adapter::new)".to_string()));
         }
-        #[cfg(not(target_os = "android"))]
+        #[cfg(target_arch = "wasm32")]
+        {
+            return Err(WalletError::ble("BLE is not available in WebAssembly builds".to_string()));
+        }
+        #[cfg(not(any(target_os = "android", target_arch = "wasm32")))]
         {
             let adapter = bluest::Adapter::default().await.ok_or_else(|| WalletError::ble("No Bluetooth adapter found".to_string()))?;
             adapter.wait_available().await.map_err(|_| WalletError::ble("Bluetooth adapter not available"))?;
@@ -101,35 +175,75 @@ impl BLESecurityManager {
         }
     }
 
+    /// Encrypt payment data with AES-256-GCM, the cipher every peer supports
     pub async fn encrypt_payment_data(&self, payment_data: &BLEPaymentData, key: &[u8]) -> Result<Vec<u8>, WalletError> {
-        let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
-        let mut nonce = [0u8; 12];
-        let mut rng = OsRng;
-        rng.fill_bytes(&mut nonce);
+        self.encrypt_payment_data_with(payment_data, key, EncryptionAlgorithm::default()).await
+    }
+
+    /// Encrypt payment data with the cipher negotiated with the peer. The output is
+    /// `nonce (12 bytes) || ciphertext || tag (16 bytes)` for either cipher.
+    pub async fn encrypt_payment_data_with(&self, payment_data: &BLEPaymentData, key: &[u8], algorithm: EncryptionAlgorithm) -> Result<Vec<u8>, WalletError> {
         let serialized = serde_json::to_vec(payment_data).map_err(|e| WalletError::crypto(format!("Serialization failed: {}", e)))?;
-        let ciphertext = cipher.encrypt(GenericArray::from_slice(&nonce), serialized.as_ref())
-            .map_err(|e| WalletError::crypto(format!("Encryption failed: {}", e)))?;
-        let mut result = Vec::new();
-        result.extend_from_slice(&nonce);
-        result.extend_from_slice(&ciphertext);
+        let encrypted = EncryptionManager::new(algorithm).encrypt(&serialized, key)?;
+        let mut result = encrypted.nonce;
+        result.extend_from_slice(&encrypted.ciphertext);
+        result.extend_from_slice(&encrypted.tag);
         Ok(result)
     }
 
+    /// Decrypt payment data encrypted with AES-256-GCM
     pub async fn decrypt_payment_data(&self, data: &[u8], key: &[u8]) -> Result<BLEPaymentData, WalletError> {
-        if data.len() < 12 {
+        self.decrypt_payment_data_with(data, key, EncryptionAlgorithm::default()).await
+    }
+
+    /// Decrypt payment data encrypted with the negotiated cipher
+    pub async fn decrypt_payment_data_with(&self, data: &[u8], key: &[u8], algorithm: EncryptionAlgorithm) -> Result<BLEPaymentData, WalletError> {
+        if data.len() < PAYMENT_NONCE_LEN + PAYMENT_TAG_LEN {
             return Err(WalletError::crypto("Encrypted data too short".to_string()));
         }
-        let nonce = &data[..12];
-        let ciphertext = &data[12..];
-        let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
-        let plaintext = cipher.decrypt(GenericArray::from_slice(nonce), ciphertext)
-            .map_err(|e| WalletError::crypto(format!("Decryption failed: {}", e)))?;
+        let (nonce, rest) = data.split_at(PAYMENT_NONCE_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - PAYMENT_TAG_LEN);
+        let encrypted = EncryptedData {
+            algorithm,
+            ciphertext: ciphertext.to_vec(),
+            nonce: nonce.to_vec(),
+            tag: tag.to_vec(),
+        };
+        let plaintext = EncryptionManager::new(algorithm).decrypt(&encrypted, key)?;
         let payment_data: BLEPaymentData = serde_json::from_slice(&plaintext)
             .map_err(|e| WalletError::crypto(format!("Deserialization failed: {}", e)))?;
+        payment_data.validate()?;
         Ok(payment_data)
     }
 }
 
+/// Nonce and authentication tag sizes shared by AES-256-GCM and ChaCha20-Poly1305
+const PAYMENT_NONCE_LEN: usize = 12;
+const PAYMENT_TAG_LEN: usize = 16;
+
+/// RSSI reported for peripherals whose advertisement carried no signal strength
+const UNKNOWN_RSSI: i32 = i32::MIN;
+
+/// Add a scan result to `devices`, updating the entry if the peripheral was already seen
+fn record_discovery(devices: &mut Vec<BLEDeviceInfo>, id: &str, name: Option<String>, rssi: Option<i16>) {
+    let rssi = rssi.map(i32::from);
+    if let Some(device) = devices.iter_mut().find(|device| device.id == id) {
+        if let Some(name) = name {
+            device.name = name;
+        }
+        if let Some(rssi) = rssi {
+            device.rssi = rssi;
+        }
+        return;
+    }
+    devices.push(BLEDeviceInfo {
+        id: id.to_string(),
+        name: name.unwrap_or_else(|| "Unknown".to_string()),
+        address: id.to_string(),
+        rssi: rssi.unwrap_or(UNKNOWN_RSSI),
+    });
+}
+
 /// Initialize BLE
 pub async fn init() -> Result<(), WalletError> {
     log::info!("Initializing BLE");
@@ -158,29 +272,114 @@ mod tests {
     fn test_ble_payment_creation() {
         let payment = BLEPaymentData {
             amount: "1000000000000000000".to_string(),
-            to_address: "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
+            to_address: "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
             token_symbol: "ETH".to_string(),
             network: Network::CoreTestnet,
             reference: Some("Test Payment".to_string()),
         };
         
         assert_eq!(payment.amount, "1000000000000000000");
-        assert_eq!(payment.to_address, "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6");
+        assert_eq!(payment.to_address, "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6");
         assert_eq!(payment.token_symbol, "ETH");
         assert_eq!(payment.network, Network::CoreTestnet);
     }
 
+    #[test]
+    fn test_record_discovery_merges_repeated_advertisements() {
+        let mut devices = Vec::new();
+        record_discovery(&mut devices, "AA:BB", None, None);
+        record_discovery(&mut devices, "CC:DD", Some("Payer".to_string()), Some(-70));
+        record_discovery(&mut devices, "AA:BB", Some("Merchant".to_string()), Some(-40));
+        record_discovery(&mut devices, "CC:DD", None, None);
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!((devices[0].name.as_str(), devices[0].rssi), ("Merchant", -40));
+        assert_eq!((devices[1].name.as_str(), devices[1].rssi), ("Payer", -70));
+        assert_eq!(devices[1].address, "CC:DD");
+    }
+
+    #[tokio::test]
+    async fn test_scan_for_devices_rejects_zero_timeout_and_offline_mode() {
+        let manager = BLESecurityManager::new();
+        let err = manager.scan_for_devices(Duration::ZERO).await.unwrap_err();
+        assert!(matches!(err, WalletError::Validation(_)));
+
+        let offline = BLESecurityManager::new().with_offline_mode(true);
+        let err = offline.scan_for_devices(Duration::from_millis(100)).await.unwrap_err();
+        assert!(matches!(err, WalletError::Offline(_)));
+    }
+
+    fn payment() -> BLEPaymentData {
+        BLEPaymentData {
+            amount: "250000000000000000".to_string(),
+            to_address: "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
+            token_symbol: "TCORE2".to_string(),
+            network: Network::CoreTestnet,
+            reference: Some("order-17".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_payment_round_trip_with_each_cipher() {
+        let manager = BLESecurityManager::new();
+        let key = [0x42u8; 32];
+        for algorithm in [EncryptionAlgorithm::AES256GCM, EncryptionAlgorithm::ChaCha20Poly1305] {
+            let encrypted = manager.encrypt_payment_data_with(&payment(), &key, algorithm).await.unwrap();
+            let decrypted = manager.decrypt_payment_data_with(&encrypted, &key, algorithm).await.unwrap();
+            assert_eq!(decrypted.amount, payment().amount);
+            assert_eq!(decrypted.reference, payment().reference);
+        }
+
+        // AES stays the default on both ends
+        let encrypted = manager.encrypt_payment_data(&payment(), &key).await.unwrap();
+        let decrypted = manager.decrypt_payment_data_with(&encrypted, &key, EncryptionAlgorithm::AES256GCM).await.unwrap();
+        assert_eq!(decrypted.to_address, payment().to_address);
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_key_or_cipher_fails_cleanly() {
+        let manager = BLESecurityManager::new();
+        let encrypted = manager.encrypt_payment_data_with(&payment(), &[0x42u8; 32], EncryptionAlgorithm::ChaCha20Poly1305).await.unwrap();
+
+        let err = manager.decrypt_payment_data_with(&encrypted, &[0x43u8; 32], EncryptionAlgorithm::ChaCha20Poly1305).await.unwrap_err();
+        assert!(matches!(err, WalletError::Crypto(_)));
+        let err = manager.decrypt_payment_data_with(&encrypted, &[0x42u8; 32], EncryptionAlgorithm::AES256GCM).await.unwrap_err();
+        assert!(matches!(err, WalletError::Crypto(_)));
+        // A key of the wrong size is an error, not a panic
+        let err = manager.decrypt_payment_data(&encrypted, &[0x42u8; 16]).await.unwrap_err();
+        assert!(matches!(err, WalletError::Crypto(_)));
+        let err = manager.decrypt_payment_data(&encrypted[..20], &[0x42u8; 32]).await.unwrap_err();
+        assert!(matches!(err, WalletError::Crypto(_)));
+    }
+
+    #[tokio::test]
+    async fn test_decrypted_payment_with_invalid_recipient_is_rejected() {
+        let manager = BLESecurityManager::new();
+        let key = [0x42u8; 32];
+        for (to_address, code) in [
+            ("0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6", "invalid_checksum"),
+            ("0x742d35cc6634c0532925a3b8d4c9db96c4b4d8", "invalid_address"),
+        ] {
+            let mut sent = payment();
+            sent.to_address = to_address.to_string();
+            let encrypted = manager.encrypt_payment_data(&sent, &key).await.unwrap();
+            let err = manager.decrypt_payment_data(&encrypted, &key).await.unwrap_err();
+            assert_eq!(err.validation_details().unwrap().code, code);
+        }
+    }
+
     #[test]
     fn test_ble_payment_validation() {
         let payment = BLEPaymentData {
             amount: "1000000000000000000".to_string(),
-            to_address: "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
+            to_address: "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
             token_symbol: "ETH".to_string(),
             network: Network::CoreTestnet,
             reference: Some("Test Payment".to_string()),
         };
         
         // Basic validation tests
+        assert!(payment.validate().is_ok());
         assert!(!payment.amount.is_empty());
         assert!(!payment.to_address.is_empty());
         assert!(!payment.token_symbol.is_empty());
@@ -1,6 +1,8 @@
 /// Encryption algorithms
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum EncryptionAlgorithm {
+    #[default]
     AES256GCM,
+    /// Faster than AES on peers without AES hardware acceleration
     ChaCha20Poly1305,
-} 
\ No newline at end of file
+}
@@ -9,6 +9,16 @@ pub mod hash_algorithm;
 pub use hash_manager::*;
 pub use hash_algorithm::*;
 
+/// Compare two byte strings in time that depends only on their lengths, for MACs,
+/// password-derived keys and checksums where an early exit would leak the matching prefix
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
 /// Hash result wrapper
 #[derive(Debug, Clone)]
 pub struct HashResult {
@@ -42,11 +52,20 @@ impl HashResult {
 
 #[cfg(test)]
 mod tests {
-
+    use super::*;
 
     #[test]
     fn test_hashing_module_imports() {
         // Test that hashing module can be imported
         assert!(true);
     }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(&[0xde, 0xad, 0xbe, 0xef], &[0xde, 0xad, 0xbe, 0xef]));
+        assert!(!constant_time_eq(&[0xde, 0xad, 0xbe, 0xef], &[0xde, 0xad, 0xbe, 0xee]));
+        assert!(!constant_time_eq(&[0x00, 0xad, 0xbe, 0xef], &[0xde, 0xad, 0xbe, 0xef]));
+        assert!(!constant_time_eq(&[0xde, 0xad, 0xbe], &[0xde, 0xad, 0xbe, 0xef]));
+    }
 } 
\ No newline at end of file
@@ -5,26 +5,114 @@
 
 use crate::shared::error::WalletError;
 use secp256k1::{SecretKey, PublicKey, Secp256k1};
-use super::SecurePrivateKey;
+use super::{SecurePrivateKey, SigningScheme};
 use bip32::{XPrv, DerivationPath};
 use std::str::FromStr;
-use crate::infrastructure::platform::PlatformStorage;
+use crate::core::crypto::SecurityAuditor;
+use crate::infrastructure::platform::{BiometricAuth, PlatformStorage};
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+/// HKDF context for session keys agreed between two devices before a BLE payment
+pub const BLE_SESSION_KEY_INFO: &[u8] = b"airchainpay-ble-session-key-v1";
+
+/// Standard Ethereum path: first address of the first account
+pub const ETHEREUM_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// BIP-44 path of the `account_index`-th address, as MetaMask numbers its accounts
+pub fn account_derivation_path(account_index: u32) -> String {
+    format!("m/44'/60'/0'/0/{}", account_index)
+}
+
+/// Path of the `account_index`-th account of a wallet whose own key is at `base_path`: the
+/// sibling `account_index` positions after it, so account 0 is `base_path` itself and
+/// `m/44'/60'/0'/0/0` numbers its accounts like `account_derivation_path`
+pub fn account_derivation_path_from(base_path: &str, account_index: u32) -> Result<String, WalletError> {
+    let base = parse_derivation_path(base_path)?;
+    let (last, parent) = match (base.iter().last(), base.parent()) {
+        (Some(last), Some(parent)) => (last, parent),
+        _ => return Err(WalletError::validation(format!("Derivation path {:?} has no key index", base_path))),
+    };
+    let child_number = last.index().checked_add(account_index)
+        .and_then(|index| bip32::ChildNumber::new(index, last.is_hardened()).ok())
+        .ok_or_else(|| WalletError::validation(format!("Account index {} is out of range for {}", account_index, base)))?;
+    let mut path = parent;
+    path.push(child_number);
+    Ok(path.to_string())
+}
+
+/// Parse a BIP-32 derivation path such as `m/44'/60'/0'/0/1`
+pub fn parse_derivation_path(path: &str) -> Result<DerivationPath, WalletError> {
+    DerivationPath::from_str(path.trim())
+        .map_err(|e| WalletError::validation(format!("Invalid derivation path {:?}: {}", path, e)))
+}
+
+/// BIP-32 root key of a BIP-39 seed phrase (no passphrase)
+fn root_xprv(seed_phrase: &str) -> Result<XPrv, WalletError> {
+    let mnemonic = bip39::Mnemonic::parse_in_normalized(bip39::Language::English, seed_phrase)
+        .map_err(|e| WalletError::validation(format!("Invalid BIP39 seed phrase: {}", e)))?;
+    let seed = bip32::Seed::new(mnemonic.to_seed_normalized(""));
+    XPrv::new(seed.as_bytes())
+        .map_err(|e| WalletError::crypto(format!("Failed to create XPrv: {}", e)))
+}
+
+fn derive_xprv(xprv: XPrv, path: impl IntoIterator<Item = bip32::ChildNumber>) -> Result<XPrv, WalletError> {
+    path.into_iter().try_fold(xprv, |xprv, child_number| {
+        xprv.derive_child(child_number)
+            .map_err(|e| WalletError::crypto(format!("Failed to derive child XPrv: {}", e)))
+    })
+}
+
+/// Addresses of the accounts numbered by `account_derivation_path`, derived in memory
+/// without storing any key. Used to scan a seed phrase for accounts in use.
+pub struct AccountAddresses {
+    /// `m/44'/60'/0'/0`, the parent of every account key
+    parent: XPrv,
+}
+
+impl AccountAddresses {
+    pub fn from_seed_phrase(seed_phrase: &str) -> Result<Self, WalletError> {
+        let parent_path = parse_derivation_path("m/44'/60'/0'/0")?;
+        Ok(Self { parent: derive_xprv(root_xprv(seed_phrase)?, parent_path)? })
+    }
+
+    /// Checksum-free lowercase address of the `account_index`-th account
+    pub fn address(&self, account_index: u32) -> Result<String, WalletError> {
+        let child_number = bip32::ChildNumber::new(account_index, false)
+            .map_err(|_| WalletError::validation(format!("Account index {} is out of range", account_index)))?;
+        let child = derive_xprv(self.parent.clone(), [child_number])?;
+        let secret_key = SecretKey::from_byte_array(child.private_key().to_bytes().into())
+            .map_err(|e| WalletError::crypto(format!("Invalid private key: {}", e)))?;
+        let public_key = PublicKey::from_secret_key(&Secp256k1::signing_only(), &secret_key).serialize_uncompressed();
+        let hash = crate::core::crypto::signatures::raw_transaction::keccak256(&public_key[1..]);
+        Ok(format!("0x{}", hex::encode(&hash[12..])))
+    }
+}
 
 /// Key manager for cryptographic key operations
 pub struct KeyManager<'a> {
     secp256k1: Secp256k1<secp256k1::All>,
     storage: &'a dyn PlatformStorage,
+    biometric_auth: Option<Arc<dyn BiometricAuth + Send + Sync>>,
 }
 
 impl<'a> KeyManager<'a> {
     /// Create a new key manager with a platform storage backend
     pub fn new(storage: &'a dyn PlatformStorage) -> Self {
+        SecurityAuditor::audit_storage_passwords();
         Self {
             secp256k1: Secp256k1::new(),
             storage,
+            biometric_auth: None,
         }
     }
 
+    /// Unlock biometric-protected keys returned by `get_private_key` with `biometric_auth`
+    pub fn with_biometric_auth(mut self, biometric_auth: Arc<dyn BiometricAuth + Send + Sync>) -> Self {
+        self.biometric_auth = Some(biometric_auth);
+        self
+    }
+
     /// Initialize the key manager
     pub fn init(&self) -> Result<(), WalletError> {
         log::info!("Initializing key manager");
@@ -36,22 +124,71 @@ impl<'a> KeyManager<'a> {
         SecurePrivateKey::generate(key_id.to_string(), self.storage)
     }
 
+    /// Generate a new private key for the given signing scheme and persist it securely
+    pub fn generate_private_key_with_scheme(&self, key_id: &str, scheme: SigningScheme) -> Result<SecurePrivateKey, WalletError> {
+        let private_key = SecurePrivateKey::generate_with_scheme(key_id.to_string(), scheme, self.storage)?;
+        self.store_scheme(key_id, scheme)?;
+        Ok(private_key)
+    }
+
     /// Import a private key and persist it securely
     pub fn import_private_key(&self, key_id: &str, key_bytes: &[u8]) -> Result<SecurePrivateKey, WalletError> {
         SecurePrivateKey::from_bytes(key_id.to_string(), key_bytes, self.storage)
     }
 
+    /// Import a private key for the given signing scheme and persist it securely
+    pub fn import_private_key_with_scheme(&self, key_id: &str, key_bytes: &[u8], scheme: SigningScheme) -> Result<SecurePrivateKey, WalletError> {
+        let private_key = SecurePrivateKey::from_bytes_with_scheme(key_id.to_string(), key_bytes, scheme, self.storage)?;
+        self.store_scheme(key_id, scheme)?;
+        Ok(private_key)
+    }
+
     /// Get a private key reference (does not load the key into memory)
     pub fn get_private_key(&self, key_id: &str) -> Result<SecurePrivateKey, WalletError> {
         // Verify the key exists in storage
         if !self.storage.exists(key_id)? {
             return Err(WalletError::crypto("Private key not found in storage".to_string()));
         }
-        Ok(SecurePrivateKey::new(key_id.to_string()))
+        let scheme = self.load_scheme(key_id)?;
+        let private_key = SecurePrivateKey::with_scheme(key_id.to_string(), scheme);
+        Ok(match &self.biometric_auth {
+            Some(biometric_auth) => private_key.with_biometric_auth(biometric_auth.clone()),
+            None => private_key,
+        })
     }
 
-    /// Generate a public key from a private key without loading the private key into memory
+    fn store_scheme(&self, key_id: &str, scheme: SigningScheme) -> Result<(), WalletError> {
+        let scheme_key = SecurePrivateKey::scheme_storage_key(key_id);
+        if scheme == SigningScheme::default() {
+            if self.storage.exists(&scheme_key)? {
+                self.storage.delete(&scheme_key)?;
+            }
+            return Ok(());
+        }
+        self.storage.store(&scheme_key, scheme.as_str().as_bytes())
+    }
+
+    fn load_scheme(&self, key_id: &str) -> Result<SigningScheme, WalletError> {
+        let scheme_key = SecurePrivateKey::scheme_storage_key(key_id);
+        if !self.storage.exists(&scheme_key)? {
+            return Ok(SigningScheme::default());
+        }
+        let raw = self.storage.retrieve(&scheme_key)?;
+        let name = std::str::from_utf8(&raw)
+            .map_err(|_| WalletError::crypto("Invalid signing scheme entry".to_string()))?;
+        name.parse()
+    }
+
+    /// Generate a public key from a private key without loading the private key into memory.
+    /// secp256k1 keys yield the 65-byte uncompressed key, ed25519 keys the 32-byte verifying key.
     pub fn get_public_key(&self, private_key: &SecurePrivateKey) -> Result<String, WalletError> {
+        if private_key.scheme() == SigningScheme::Ed25519 {
+            return private_key.with_key(self.storage, |key_bytes| {
+                let signing_key = Self::ed25519_signing_key(key_bytes)?;
+                Ok(hex::encode(signing_key.verifying_key().as_bytes()))
+            });
+        }
+
         private_key.with_key(self.storage, |key_bytes| {
             let secret_key = SecretKey::from_byte_array(key_bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key length".to_string()))?)
                 .map_err(|e| WalletError::crypto(format!("Invalid private key: {}", e)))?;
@@ -82,8 +219,17 @@ impl<'a> KeyManager<'a> {
         Ok(format!("0x{}", address))
     }
 
-    /// Sign a message using a private key without loading it into memory
+    /// Sign a message using a private key without loading it into memory.
+    /// secp256k1 signs the keccak256 digest; ed25519 signs the raw message bytes.
     pub fn sign_message(&self, private_key: &SecurePrivateKey, message: &str) -> Result<String, WalletError> {
+        if private_key.scheme() == SigningScheme::Ed25519 {
+            return private_key.with_key(self.storage, |key_bytes| {
+                use ed25519_dalek::Signer;
+                let signing_key = Self::ed25519_signing_key(key_bytes)?;
+                Ok(hex::encode(signing_key.sign(message.as_bytes()).to_bytes()))
+            });
+        }
+
         private_key.with_key(self.storage, |key_bytes| {
             let secret_key = SecretKey::from_byte_array(key_bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key length".to_string()))?)
                 .map_err(|e| WalletError::crypto(format!("Invalid private key: {}", e)))?;
@@ -98,30 +244,51 @@ impl<'a> KeyManager<'a> {
         })
     }
 
+    /// Agree on a 32-byte session key with the holder of `their_public_key` (secp256k1, hex).
+    /// Both sides get the same key from ECDH over their own private key and the peer's public key;
+    /// the shared x-coordinate is run through HKDF-SHA256 so it can key `encrypt_payment_data`.
+    pub fn derive_shared_key(&self, our_private_key_id: &str, their_public_key: &str) -> Result<Zeroizing<[u8; 32]>, WalletError> {
+        let private_key = self.get_private_key(our_private_key_id)?;
+        if private_key.scheme() != SigningScheme::Secp256k1 {
+            return Err(WalletError::crypto("Key exchange requires a secp256k1 key".to_string()));
+        }
+        let their_public_key = hex::decode(their_public_key.trim_start_matches("0x"))
+            .map_err(|_| WalletError::validation("Invalid hex format".to_string()))?;
+        let their_public_key = PublicKey::from_slice(&their_public_key)
+            .map_err(|e| WalletError::crypto(format!("Invalid public key: {}", e)))?;
+
+        private_key.with_key(self.storage, |key_bytes| {
+            let secret_key = SecretKey::from_byte_array(key_bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key length".to_string()))?)
+                .map_err(|e| WalletError::crypto(format!("Invalid private key: {}", e)))?;
+            let point = Zeroizing::new(secp256k1::ecdh::shared_secret_point(&their_public_key, &secret_key));
+            Ok(Self::hkdf_sha256(&point[..32], BLE_SESSION_KEY_INFO))
+        })
+    }
+
+    /// HKDF-SHA256 (RFC 5869) with an empty salt, producing a single 32-byte output block
+    fn hkdf_sha256(input_key_material: &[u8], info: &[u8]) -> Zeroizing<[u8; 32]> {
+        use hmac::{Hmac, Mac};
+        type HmacSha256 = Hmac<sha2::Sha256>;
+
+        let mut extract = <HmacSha256 as Mac>::new_from_slice(&[0u8; 32]).expect("HMAC accepts any key length");
+        extract.update(input_key_material);
+        let pseudo_random_key = Zeroizing::new(<[u8; 32]>::from(extract.finalize().into_bytes()));
+
+        let mut expand = <HmacSha256 as Mac>::new_from_slice(pseudo_random_key.as_slice()).expect("HMAC accepts any key length");
+        expand.update(info);
+        expand.update(&[1u8]);
+        Zeroizing::new(<[u8; 32]>::from(expand.finalize().into_bytes()))
+    }
+
     /// Derive a private key from a seed phrase without storing the seed phrase in memory
     pub fn derive_private_key_from_seed(&self, seed_phrase: &str, key_id: &str) -> Result<SecurePrivateKey, WalletError> {
-        use bip39::Mnemonic;
-        
-        // Parse the mnemonic
-        let mnemonic = Mnemonic::parse_in_normalized(bip39::Language::English, seed_phrase)
-            .map_err(|e| WalletError::validation(format!("Invalid BIP39 seed phrase: {}", e)))?;
-        
-        let seed = bip32::Seed::new(mnemonic.to_seed_normalized("")); // No passphrase
-        
-        // Derive the BIP32 root key
-        let xprv = XPrv::new(seed.as_bytes())
-            .map_err(|e| WalletError::crypto(format!("Failed to create XPrv: {}", e)))?;
-        
-        // Standard Ethereum path: m/44'/60'/0'/0/0
-        let derivation_path = DerivationPath::from_str("m/44'/60'/0'/0/0")
-            .map_err(|e| WalletError::crypto(format!("Invalid derivation path: {}", e)))?;
-        
-        let mut child_xprv = xprv;
-        for child_number in derivation_path.into_iter() {
-            child_xprv = child_xprv.derive_child(child_number)
-                .map_err(|e| WalletError::crypto(format!("Failed to derive child XPrv: {}", e)))?;
-        }
-        
+        self.derive_private_key_from_seed_with_path(seed_phrase, ETHEREUM_DERIVATION_PATH, key_id)
+    }
+
+    /// Derive the private key at `derivation_path` (e.g. `m/44'/60'/1'/0/0`) from a seed phrase
+    pub fn derive_private_key_from_seed_with_path(&self, seed_phrase: &str, derivation_path: &str, key_id: &str) -> Result<SecurePrivateKey, WalletError> {
+        let derivation_path = parse_derivation_path(derivation_path)?;
+        let child_xprv = derive_xprv(root_xprv(seed_phrase)?, derivation_path)?;
         let private_key_bytes = child_xprv.private_key().to_bytes();
         
         // Store the derived private key securely
@@ -130,10 +297,7 @@ impl<'a> KeyManager<'a> {
 
     /// Validate a private key without loading it into memory
     pub fn validate_private_key(&self, private_key: &SecurePrivateKey) -> Result<bool, WalletError> {
-        private_key.with_key(self.storage, |key_bytes| {
-            let secret_key = SecretKey::from_byte_array(key_bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key length".to_string()))?);
-            Ok(secret_key.is_ok())
-        })
+        private_key.validate(self.storage)
     }
 
     /// Validate a public key
@@ -163,6 +327,12 @@ impl<'a> KeyManager<'a> {
         Ok(true)
     }
 
+    fn ed25519_signing_key(key_bytes: &[u8]) -> Result<ed25519_dalek::SigningKey, WalletError> {
+        let seed = zeroize::Zeroizing::new(<[u8; ed25519_dalek::SECRET_KEY_LENGTH]>::try_from(key_bytes)
+            .map_err(|_| WalletError::crypto("Invalid private key length".to_string()))?);
+        Ok(ed25519_dalek::SigningKey::from_bytes(&seed))
+    }
+
     /// Keccak256 hash function
     fn keccak256(&self, data: &[u8]) -> Vec<u8> {
         use sha3::{Keccak256, Digest};
@@ -254,6 +424,15 @@ mod tests {
         assert!(manager.init().is_ok());
     }
 
+    #[test]
+    fn test_account_derivation_path_from_base() {
+        assert_eq!(account_derivation_path_from(ETHEREUM_DERIVATION_PATH, 3).unwrap(), account_derivation_path(3));
+        assert_eq!(account_derivation_path_from("m/44'/60'/1'/0/5", 2).unwrap(), "m/44'/60'/1'/0/7");
+        assert_eq!(account_derivation_path_from("m/44'/60'/4'", 1).unwrap(), "m/44'/60'/5'");
+        assert!(account_derivation_path_from("m", 1).is_err());
+        assert!(account_derivation_path_from("m/44'/60'/0'/0/0", u32::MAX).is_err());
+    }
+
     #[test]
     fn test_private_key_generation() {
         let storage = MockStorage::new();
@@ -311,4 +490,89 @@ mod tests {
             .expect("Failed to derive private key from seed");
         assert_eq!(private_key.key_id(), "test_id");
     }
-} 
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_shared_key_matches_from_both_sides() {
+        use crate::core::ble::BLESecurityManager;
+        use crate::shared::types::{BLEPaymentData, Network};
+
+        let storage = MockStorage::new();
+        let manager = KeyManager::new(&storage);
+        let payer = manager.generate_private_key("payer_key").unwrap();
+        let merchant = manager.generate_private_key("merchant_key").unwrap();
+        let payer_public = manager.get_public_key(&payer).unwrap();
+        let merchant_public = manager.get_public_key(&merchant).unwrap();
+
+        let payer_side = manager.derive_shared_key("payer_key", &merchant_public).unwrap();
+        let merchant_side = manager.derive_shared_key("merchant_key", &payer_public).unwrap();
+        assert_eq!(*payer_side, *merchant_side);
+        assert_ne!(*payer_side, [0u8; 32]);
+
+        manager.generate_private_key("outsider_key").unwrap();
+        let outsider_side = manager.derive_shared_key("outsider_key", &merchant_public).unwrap();
+        assert_ne!(*outsider_side, *payer_side);
+
+        // The agreed key works for the BLE payment encryption
+        let ble = BLESecurityManager::new();
+        let payment = BLEPaymentData {
+            amount: "1000".to_string(),
+            to_address: "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
+            token_symbol: "ETH".to_string(),
+            network: Network::BaseSepolia,
+            reference: None,
+        };
+        let encrypted = ble.encrypt_payment_data(&payment, payer_side.as_slice()).await.unwrap();
+        let decrypted = ble.decrypt_payment_data(&encrypted, merchant_side.as_slice()).await.unwrap();
+        assert_eq!(decrypted.amount, "1000");
+
+        assert!(manager.derive_shared_key("payer_key", "not hex").is_err());
+        assert!(manager.derive_shared_key("missing_key", &merchant_public).is_err());
+    }
+
+    #[test]
+    fn test_secp256k1_is_default_scheme() {
+        use crate::core::crypto::signatures::SignatureManager;
+
+        let storage = MockStorage::new();
+        let manager = KeyManager::new(&storage);
+        manager.generate_private_key("evm_key")
+            .expect("Failed to generate private key");
+        let private_key = manager.get_private_key("evm_key")
+            .expect("Failed to load private key");
+        assert_eq!(private_key.scheme(), SigningScheme::Secp256k1);
+
+        let public_key = manager.get_public_key(&private_key).unwrap();
+        let signature = manager.sign_message(&private_key, "pay 1 ETH").unwrap();
+        let verifier = SignatureManager::new();
+        assert!(verifier.verify_with_scheme(SigningScheme::Secp256k1, b"pay 1 ETH", &signature, &public_key).unwrap());
+        assert!(!verifier.verify_with_scheme(SigningScheme::Secp256k1, b"pay 2 ETH", &signature, &public_key).unwrap());
+        assert!(verifier.verify_with_scheme(SigningScheme::Ed25519, b"pay 1 ETH", &signature, &public_key).is_err());
+    }
+
+    #[test]
+    fn test_ed25519_key_generation_and_signing() {
+        use crate::core::crypto::signatures::SignatureManager;
+
+        let storage = MockStorage::new();
+        let manager = KeyManager::new(&storage);
+        manager.generate_private_key_with_scheme("ed_key", SigningScheme::Ed25519)
+            .expect("Failed to generate ed25519 key");
+
+        // The scheme is persisted with the key
+        let private_key = manager.get_private_key("ed_key")
+            .expect("Failed to load private key");
+        assert_eq!(private_key.scheme(), SigningScheme::Ed25519);
+        assert!(manager.validate_private_key(&private_key).unwrap());
+
+        let public_key = manager.get_public_key(&private_key).unwrap();
+        assert_eq!(public_key.len(), 64); // 32 bytes * 2 for hex
+
+        let signature = manager.sign_message(&private_key, "hello").unwrap();
+        assert_eq!(signature.len(), 128); // 64 bytes * 2 for hex
+
+        let verifier = SignatureManager::new();
+        assert!(verifier.verify_with_scheme(SigningScheme::Ed25519, b"hello", &signature, &public_key).unwrap());
+        assert!(!verifier.verify_with_scheme(SigningScheme::Ed25519, b"hellO", &signature, &public_key).unwrap());
+        assert!(verifier.verify_with_scheme(SigningScheme::Secp256k1, b"hello", &signature, &public_key).is_err());
+    }
+}
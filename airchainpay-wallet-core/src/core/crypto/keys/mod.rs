@@ -5,8 +5,10 @@
 pub mod secure_private_key;
 pub mod key_manager;
 pub mod secure_seed_phrase;
+pub mod signing_scheme;
 
 // Re-export all public items from submodules
 pub use secure_private_key::*;
 pub use key_manager::*;
-pub use secure_seed_phrase::*; 
\ No newline at end of file
+pub use secure_seed_phrase::*;
+pub use signing_scheme::*; 
\ No newline at end of file
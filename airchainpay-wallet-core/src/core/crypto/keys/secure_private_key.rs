@@ -1,11 +1,20 @@
 use crate::shared::constants::*;
 use crate::shared::error::WalletError;
+use crate::infrastructure::platform::BiometricAuth;
+use std::sync::Arc;
 use zeroize::{Zeroize, Zeroizing};
+use super::SigningScheme;
+
+/// Prompt shown when a biometric-protected key is about to be used
+const BIOMETRIC_REASON: &str = "Authenticate to use your wallet key";
 
 /// Secure private key wrapper that never stores keys in memory
 /// Keys are only accessed through secure storage backends with proper zeroization
 pub struct SecurePrivateKey {
     key_id: String,
+    scheme: SigningScheme,
+    /// Authenticator consulted before a biometric-protected key is loaded
+    biometric_auth: Option<Arc<dyn BiometricAuth + Send + Sync>>,
     // No key bytes stored in memory - only a reference ID
 }
 
@@ -13,7 +22,51 @@ impl SecurePrivateKey {
     /// Create a new secure private key reference
     /// The actual key is stored securely and never loaded into memory
     pub fn new(key_id: String) -> Self {
-        Self { key_id }
+        Self::with_scheme(key_id, SigningScheme::default())
+    }
+
+    /// Create a secure private key reference for a specific signing scheme
+    pub fn with_scheme(key_id: String, scheme: SigningScheme) -> Self {
+        Self { key_id, scheme, biometric_auth: None }
+    }
+
+    /// Attach the authenticator used to unlock this key if it is biometric-protected
+    pub fn with_biometric_auth(mut self, biometric_auth: Arc<dyn BiometricAuth + Send + Sync>) -> Self {
+        self.biometric_auth = Some(biometric_auth);
+        self
+    }
+
+    /// Storage entry marking a key that may only be loaded after biometric authentication
+    fn biometric_storage_key(&self) -> String {
+        format!("{}:biometric", self.key_id)
+    }
+
+    /// Storage entry recording the signing scheme of a key; absent for secp256k1 keys
+    pub(crate) fn scheme_storage_key(key_id: &str) -> String {
+        format!("{}:scheme", key_id)
+    }
+
+    /// Require biometric authentication before every future use of this key
+    pub fn require_biometric(&self, storage: &dyn crate::infrastructure::platform::PlatformStorage) -> Result<(), WalletError> {
+        storage.store(&self.biometric_storage_key(), b"1")
+    }
+
+    /// Check if the key is biometric-protected
+    pub fn requires_biometric(&self, storage: &dyn crate::infrastructure::platform::PlatformStorage) -> Result<bool, WalletError> {
+        storage.exists(&self.biometric_storage_key())
+    }
+
+    /// Fails closed: no authenticator, no biometric hardware or a rejected prompt all deny access
+    fn authenticate(&self) -> Result<(), WalletError> {
+        let auth = self.biometric_auth.as_ref()
+            .ok_or_else(|| WalletError::crypto(format!("Key {} requires biometric authentication", self.key_id)))?;
+        if !auth.is_available()? {
+            return Err(WalletError::crypto("Biometric authentication is not available on this device".to_string()));
+        }
+        if !auth.authenticate(BIOMETRIC_REASON)? {
+            return Err(WalletError::crypto("Biometric authentication failed".to_string()));
+        }
+        Ok(())
     }
 
     /// Get the key ID for secure storage lookup
@@ -21,13 +74,23 @@ impl SecurePrivateKey {
         &self.key_id
     }
 
+    /// Get the signing scheme this key is used with
+    pub fn scheme(&self) -> SigningScheme {
+        self.scheme
+    }
+
     /// Perform cryptographic operations without exposing the key
     /// This method takes a closure that receives the key bytes temporarily
     /// All memory is automatically zeroized after use
+    /// Biometric-protected keys are only loaded after a successful biometric prompt
     pub fn with_key<F, T>(&self, storage: &dyn crate::infrastructure::platform::PlatformStorage, f: F) -> Result<T, WalletError>
     where
         F: FnOnce(&[u8]) -> Result<T, WalletError>,
     {
+        if self.requires_biometric(storage)? {
+            self.authenticate()?;
+        }
+
         // Retrieve key from secure storage into zeroized memory
         let key_bytes = Zeroizing::new(storage.retrieve(&self.key_id)?);
         
@@ -46,23 +109,36 @@ impl SecurePrivateKey {
     /// Create a SecurePrivateKey from existing key bytes and store securely
     /// Input bytes are zeroized after storage
     pub fn from_bytes(key_id: String, bytes: &[u8], storage: &dyn crate::infrastructure::platform::PlatformStorage) -> Result<Self, WalletError> {
+        Self::from_bytes_with_scheme(key_id, bytes, SigningScheme::Secp256k1, storage)
+    }
+
+    /// Create a SecurePrivateKey for the given signing scheme from existing key bytes
+    /// For ed25519 the bytes are the 32-byte secret seed
+    pub fn from_bytes_with_scheme(key_id: String, bytes: &[u8], scheme: SigningScheme, storage: &dyn crate::infrastructure::platform::PlatformStorage) -> Result<Self, WalletError> {
         if bytes.len() != PRIVATE_KEY_SIZE {
             return Err(WalletError::crypto("Invalid private key length".to_string()));
         }
 
-        // Validate the key is a valid secp256k1 private key
-        let _secret_key = secp256k1::SecretKey::from_byte_array(bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key format".to_string()))?)
-            .map_err(|_| WalletError::crypto("Invalid private key".to_string()))?;
+        if scheme == SigningScheme::Secp256k1 {
+            // Validate the key is a valid secp256k1 private key
+            let _secret_key = secp256k1::SecretKey::from_byte_array(bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key format".to_string()))?)
+                .map_err(|_| WalletError::crypto("Invalid private key".to_string()))?;
+        }
 
         // Store the key securely
         storage.store(&key_id, bytes)?;
 
-        Ok(SecurePrivateKey { key_id })
+        Ok(Self::with_scheme(key_id, scheme))
     }
 
     /// Generate a new private key and store it securely
     /// Uses cryptographically secure random number generation
     pub fn generate(key_id: String, storage: &dyn crate::infrastructure::platform::PlatformStorage) -> Result<Self, WalletError> {
+        Self::generate_with_scheme(key_id, SigningScheme::Secp256k1, storage)
+    }
+
+    /// Generate a new private key for the given signing scheme and store it securely
+    pub fn generate_with_scheme(key_id: String, scheme: SigningScheme, storage: &dyn crate::infrastructure::platform::PlatformStorage) -> Result<Self, WalletError> {
         use rand_core::OsRng;
         use rand_core::RngCore;
         use secp256k1::SecretKey;
@@ -71,20 +147,29 @@ impl SecurePrivateKey {
         let mut key_bytes = Zeroizing::new([0u8; PRIVATE_KEY_SIZE]);
         rng.fill_bytes(&mut *key_bytes);
 
-        // Ensure the key is valid for secp256k1
-        let _secret_key = SecretKey::from_byte_array(*key_bytes)
-            .map_err(|_| WalletError::crypto("Generated invalid private key".to_string()))?;
+        // Ensure the key is valid for secp256k1; any 32 bytes are a valid ed25519 seed
+        if scheme == SigningScheme::Secp256k1 {
+            let _secret_key = SecretKey::from_byte_array(*key_bytes)
+                .map_err(|_| WalletError::crypto("Generated invalid private key".to_string()))?;
+        }
 
         // Store the key securely
         storage.store(&key_id, &*key_bytes)?;
 
         // Key bytes are automatically zeroized when Zeroizing is dropped
-        Ok(SecurePrivateKey { key_id })
+        Ok(Self::with_scheme(key_id, scheme))
     }
 
     /// Delete the private key from secure storage
     /// This operation is irreversible
     pub fn delete(&self, storage: &dyn crate::infrastructure::platform::PlatformStorage) -> Result<(), WalletError> {
+        if self.requires_biometric(storage)? {
+            storage.delete(&self.biometric_storage_key())?;
+        }
+        let scheme_key = Self::scheme_storage_key(&self.key_id);
+        if storage.exists(&scheme_key)? {
+            storage.delete(&scheme_key)?;
+        }
         storage.delete(&self.key_id)
     }
 
@@ -95,9 +180,16 @@ impl SecurePrivateKey {
 
     /// Validate the private key without exposing it
     pub fn validate(&self, storage: &dyn crate::infrastructure::platform::PlatformStorage) -> Result<bool, WalletError> {
+        let scheme = self.scheme;
         self.with_key(storage, |key_bytes| {
-            let secret_key = secp256k1::SecretKey::from_byte_array(key_bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key format".to_string()))?);
-            Ok(secret_key.is_ok())
+            match scheme {
+                SigningScheme::Secp256k1 => {
+                    let secret_key = secp256k1::SecretKey::from_byte_array(key_bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key format".to_string()))?);
+                    Ok(secret_key.is_ok())
+                }
+                // with_key already checked the seed length
+                SigningScheme::Ed25519 => Ok(true),
+            }
         })
     }
 }
@@ -125,6 +217,7 @@ impl Zeroize for SecurePrivateKey {
 mod tests {
     use super::*;
     use crate::infrastructure::platform::PlatformStorage;
+    use crate::shared::test_doubles::MockBiometricAuth;
     use std::collections::HashMap;
     use std::sync::Mutex;
 
@@ -220,11 +313,56 @@ mod tests {
         let storage = MockStorage::new();
         let key = SecurePrivateKey::generate("test_key_delete".to_string(), &storage)
             .expect("Failed to generate secure private key");
+        storage.store(&SecurePrivateKey::scheme_storage_key("test_key_delete"), b"ed25519")
+            .expect("Failed to store scheme");
         assert!(key.exists(&storage)
             .expect("Failed to check if key exists"));
         key.delete(&storage)
             .expect("Failed to delete key");
         assert!(!key.exists(&storage)
             .expect("Failed to check if key exists after deletion"));
+        // The scheme entry goes with the key
+        assert!(storage.list_keys().unwrap().is_empty());
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_biometric_protected_key_requires_authentication() {
+        let storage = MockStorage::new();
+        let key = SecurePrivateKey::generate("test_key_biometric".to_string(), &storage)
+            .expect("Failed to generate secure private key");
+        key.require_biometric(&storage).expect("Failed to mark key");
+        assert!(key.requires_biometric(&storage).unwrap());
+
+        let allow = MockBiometricAuth::new(true, true);
+        let key = key.with_biometric_auth(allow.clone());
+        assert!(key.with_key(&storage, |_| Ok(())).is_ok());
+        assert_eq!(allow.prompts(), 1);
+
+        let deny = MockBiometricAuth::new(true, false);
+        let key = SecurePrivateKey::new("test_key_biometric".to_string()).with_biometric_auth(deny.clone());
+        let err = key.with_key(&storage, |_| Ok(())).unwrap_err();
+        assert!(err.to_string().contains("Biometric authentication failed"), "{}", err);
+        assert_eq!(deny.prompts(), 1);
+
+        // No biometrics on the device, or no authenticator at all: fail closed without prompting
+        let unavailable = MockBiometricAuth::new(false, true);
+        let key = SecurePrivateKey::new("test_key_biometric".to_string()).with_biometric_auth(unavailable.clone());
+        assert!(key.with_key(&storage, |_| Ok(())).is_err());
+        assert_eq!(unavailable.prompts(), 0);
+        assert!(SecurePrivateKey::new("test_key_biometric".to_string()).with_key(&storage, |_| Ok(())).is_err());
+
+        key.delete(&storage).expect("Failed to delete key");
+        assert!(storage.list_keys().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unprotected_key_never_prompts() {
+        let storage = MockStorage::new();
+        let deny = MockBiometricAuth::new(true, false);
+        let key = SecurePrivateKey::generate("test_key_plain".to_string(), &storage)
+            .expect("Failed to generate secure private key")
+            .with_biometric_auth(deny.clone());
+        assert!(key.with_key(&storage, |_| Ok(())).is_ok());
+        assert_eq!(deny.prompts(), 0);
+    }
+}
@@ -0,0 +1,36 @@
+//! Signature schemes supported by the key manager
+
+use crate::shared::error::WalletError;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Signature scheme a private key is used with, selected per key.
+/// EVM wallets always use secp256k1; ed25519 is available for non-EVM keys.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SigningScheme {
+    #[default]
+    Secp256k1,
+    Ed25519,
+}
+
+impl SigningScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SigningScheme::Secp256k1 => "secp256k1",
+            SigningScheme::Ed25519 => "ed25519",
+        }
+    }
+}
+
+impl FromStr for SigningScheme {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "secp256k1" => Ok(SigningScheme::Secp256k1),
+            "ed25519" => Ok(SigningScheme::Ed25519),
+            other => Err(WalletError::validation(format!("Unsupported signing scheme: {}", other))),
+        }
+    }
+}
@@ -119,7 +119,7 @@ impl WalletPasswordHasher {
             iterations,
             &mut computed_key,
         ).map_err(|e| WalletError::Crypto(format!("PBKDF2 error: {:?}", e)))?;
-        let result = computed_key == stored_key;
+        let result = crate::core::crypto::constant_time_eq(&computed_key, &stored_key);
         computed_key.zeroize();
         Ok(result)
     }
@@ -47,6 +47,60 @@ impl SecurityAuditResult {
     }
 }
 
+/// Passwords rejected outright by `audit_password_strength`, compared case-insensitively
+/// and ignoring trailing digits and symbols ("Password123!" counts as "password")
+const COMMON_PASSWORDS: &[&str] = &[
+    "password", "passw0rd", "p@ssw0rd", "p@ssword", "123456", "12345678", "123456789",
+    "1234567890", "qwerty", "qwertyuiop", "asdfgh", "zxcvbn", "abc", "letmein", "welcome",
+    "admin", "administrator", "iloveyou", "monkey", "dragon", "football", "baseball",
+    "master", "sunshine", "princess", "shadow", "superman", "trustno1", "changeme",
+    "secret", "login", "wallet", "bitcoin", "ethereum", "crypto", "airchainpay",
+];
+
+/// Score at or above which a password is not reported as weak
+pub const PASSWORD_STRONG_SCORE: u8 = 60;
+
+/// A specific reason a password scored low
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordWeakness {
+    TooShort,
+    NoUppercase,
+    NoLowercase,
+    NoDigit,
+    NoSymbol,
+    RepeatedCharacters,
+    CommonPassword,
+}
+
+impl std::fmt::Display for PasswordWeakness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            Self::TooShort => "shorter than 12 characters",
+            Self::NoUppercase => "no uppercase letter",
+            Self::NoLowercase => "no lowercase letter",
+            Self::NoDigit => "no digit",
+            Self::NoSymbol => "no symbol",
+            Self::RepeatedCharacters => "mostly one repeated character",
+            Self::CommonPassword => "a commonly used password",
+        };
+        f.write_str(description)
+    }
+}
+
+/// Result of `SecurityAuditor::audit_password_strength`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordAudit {
+    /// 0 (trivially guessable) to 100
+    pub score: u8,
+    pub weaknesses: Vec<PasswordWeakness>,
+}
+
+impl PasswordAudit {
+    pub fn is_weak(&self) -> bool {
+        self.score < PASSWORD_STRONG_SCORE
+    }
+}
+
 /// Security auditor for wallet core operations
 pub struct SecurityAuditor;
 
@@ -250,6 +304,76 @@ impl SecurityAuditor {
         Ok(())
     }
 
+    /// Score a storage or backup password on length, character classes and the common-password
+    /// list. Unlike `validate_password_strength` this never fails; callers decide what to do
+    /// with a weak result.
+    pub fn audit_password_strength(password: &str) -> PasswordAudit {
+        let length = password.chars().count();
+        let mut weaknesses = Vec::new();
+        let mut score: u32 = match length {
+            16.. => 40,
+            12..=15 => 30,
+            _ => {
+                weaknesses.push(PasswordWeakness::TooShort);
+                length as u32 * 2
+            }
+        };
+
+        let classes = [
+            (password.chars().any(|c| c.is_uppercase()), PasswordWeakness::NoUppercase),
+            (password.chars().any(|c| c.is_lowercase()), PasswordWeakness::NoLowercase),
+            (password.chars().any(|c| c.is_numeric()), PasswordWeakness::NoDigit),
+            (password.chars().any(|c| !c.is_alphanumeric()), PasswordWeakness::NoSymbol),
+        ];
+        for (present, weakness) in classes {
+            if present {
+                score += 15;
+            } else {
+                weaknesses.push(weakness);
+            }
+        }
+
+        // Variety cannot make up for length
+        if length < 12 {
+            score = score.min(PASSWORD_STRONG_SCORE as u32 - 1);
+        }
+
+        let distinct = password.chars().collect::<std::collections::HashSet<_>>().len();
+        if length > 0 && distinct * 3 < length {
+            weaknesses.push(PasswordWeakness::RepeatedCharacters);
+            score /= 2;
+        }
+
+        let lowered = password.to_lowercase();
+        let stem = lowered.trim_end_matches(|c: char| !c.is_alphabetic());
+        if COMMON_PASSWORDS.contains(&lowered.as_str()) || COMMON_PASSWORDS.contains(&stem) {
+            weaknesses.push(PasswordWeakness::CommonPassword);
+            score = 0;
+        }
+
+        PasswordAudit { score: score.min(100) as u8, weaknesses }
+    }
+
+    /// Log a warning if `password` is weak. `context` names the operation, e.g. "backup".
+    pub fn warn_if_weak_password(password: &str, context: &str) -> PasswordAudit {
+        let audit = Self::audit_password_strength(password);
+        if audit.is_weak() {
+            let weaknesses: Vec<String> = audit.weaknesses.iter().map(|w| w.to_string()).collect();
+            log::warn!(
+                "Weak {} password (score {}/100): {}",
+                context, audit.score, weaknesses.join(", ")
+            );
+        }
+        audit
+    }
+
+    /// Have the platform file stores warn about weak storage passwords
+    pub fn audit_storage_passwords() {
+        crate::infrastructure::platform::set_storage_password_check(|password| {
+            Self::warn_if_weak_password(password, "storage");
+        });
+    }
+
     /// Validate input sanitization
     pub fn validate_input_sanitization(input: &str, max_length: usize) -> Result<(), WalletError> {
         if input.len() > max_length {
@@ -360,6 +484,31 @@ mod tests {
         assert!(SecurityAuditor::validate_password_strength("securepass123!").is_err());
     }
 
+    #[test]
+    fn test_password_audit() {
+        let strong = SecurityAuditor::audit_password_strength("Tr0ub4dor&3-Horse-Staple");
+        assert!(!strong.is_weak());
+        assert!(strong.weaknesses.is_empty());
+        assert_eq!(strong.score, 100);
+
+        let short = SecurityAuditor::audit_password_strength("Ab1!");
+        assert!(short.is_weak());
+        assert_eq!(short.weaknesses, vec![PasswordWeakness::TooShort]);
+
+        for common in ["password", "Password123!", "QWERTY", "letmein!!"] {
+            let audit = SecurityAuditor::audit_password_strength(common);
+            assert_eq!(audit.score, 0, "{}", common);
+            assert!(audit.weaknesses.contains(&PasswordWeakness::CommonPassword), "{}", common);
+        }
+
+        let repeated = SecurityAuditor::audit_password_strength("aaaaaaaaaaaaaaaaA1!");
+        assert!(repeated.weaknesses.contains(&PasswordWeakness::RepeatedCharacters));
+        assert!(repeated.is_weak());
+
+        // Weak passwords are reported, never rejected
+        assert_eq!(SecurityAuditor::warn_if_weak_password("password", "backup").score, 0);
+    }
+
     #[test]
     fn test_input_sanitization() {
         // Valid input
@@ -2,16 +2,22 @@
 //!
 //! This module handles ECDSA signatures for transactions and messages.
 
+pub mod qr_payment;
+pub mod raw_transaction;
 pub mod signature_manager;
 pub mod transaction_signature;
+pub mod typed_data;
 
 // Re-export all public items from submodules
+pub use qr_payment::*;
 pub use signature_manager::*;
 pub use transaction_signature::*;
+pub use typed_data::*;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
 
     #[test]
@@ -20,6 +26,38 @@ mod tests {
         assert!(true); // Manager created successfully
     }
 
+    #[test]
+    fn test_sign_1559_raw_recovers_sender_and_hash() {
+        use ethers::types::transaction::eip2718::TypedTransaction;
+        use ethers::utils::rlp::Rlp;
+
+        // Well-known test key for 0x2c7536E3605D9C16a7a3D7b1898e529396a65c23
+        let key = hex::decode("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap();
+        let tx = crate::shared::types::Transaction {
+            to: "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
+            value: "1000000000000000".to_string(),
+            data: None,
+            gas_limit: Some(21_000),
+            gas_price: None,
+            nonce: Some(7),
+            chain_id: 84532,
+            max_fee_per_gas: Some(2_000_000_000),
+            max_priority_fee_per_gas: Some(1_000_000),
+        };
+
+        let (raw_tx, tx_hash) = SignatureManager::new().sign_1559_raw(&tx, &key).unwrap();
+        assert_eq!(raw_tx[0], 0x02);
+        assert_eq!(tx_hash, "0x58fd0d95d571f671ae94538805a7700f7cdfd5e5f2b2f652d54459445b9cfaee");
+
+        // Decode as a node would and recover the sender from the signature
+        let (decoded, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw_tx)).unwrap();
+        assert!(matches!(decoded, TypedTransaction::Eip1559(_)));
+        assert_eq!(decoded.chain_id().unwrap().as_u64(), 84532);
+        let sender = signature.recover(decoded.sighash()).unwrap();
+        assert_eq!(format!("{:?}", sender), "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23");
+        assert_eq!(format!("{:?}", decoded.hash(&signature)), tx_hash);
+    }
+
     #[test]
     fn test_sign_ble_payment() {
         let manager = SignatureManager::new();
@@ -37,7 +75,12 @@ mod tests {
         let private_key = [1u8; 32];
         
         let signature = manager.sign_qr_payment_with_bytes(payment_data, &private_key).unwrap();
-        assert!(!signature.is_empty());
+        assert_eq!(signature.len(), 2 + 65 * 2);
+
+        let secp = secp256k1::Secp256k1::new();
+        let public_key = secp256k1::SecretKey::from_byte_array(private_key).unwrap().public_key(&secp);
+        assert!(manager.verify_qr_payment(payment_data, &signature, &public_key).unwrap());
+        assert!(!manager.verify_qr_payment(b"other_data", &signature, &public_key).unwrap());
     }
 
     #[test]
@@ -59,4 +102,55 @@ mod tests {
         let signature = manager.sign_ble_payment_with_bytes(&payment_data, &private_key).unwrap();
         assert!(!signature.is_empty());
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_sign_and_verify_with_each_scheme() {
+        use crate::core::crypto::keys::SigningScheme;
+
+        let manager = SignatureManager::new();
+        let key_bytes = [7u8; 32];
+        let message = b"scheme_message";
+
+        let secp = secp256k1::Secp256k1::new();
+        let secret_key = secp256k1::SecretKey::from_byte_array(key_bytes).unwrap();
+        let secp_public = hex::encode(secp256k1::PublicKey::from_secret_key(&secp, &secret_key).serialize());
+        let secp_signature = manager.sign_with_scheme(SigningScheme::Secp256k1, message, &key_bytes).unwrap();
+        assert!(manager.verify_with_scheme(SigningScheme::Secp256k1, message, &secp_signature, &secp_public).unwrap());
+
+        let ed_public = hex::encode(ed25519_dalek::SigningKey::from_bytes(&key_bytes).verifying_key().as_bytes());
+        let ed_signature = manager.sign_with_scheme(SigningScheme::Ed25519, message, &key_bytes).unwrap();
+        assert!(manager.verify_with_scheme(SigningScheme::Ed25519, message, &ed_signature, &ed_public).unwrap());
+        assert!(!manager.verify_with_scheme(SigningScheme::Ed25519, b"other", &ed_signature, &ed_public).unwrap());
+
+        // Same-length signature, wrong scheme: rejected by the verifier
+        assert!(!matches!(
+            manager.verify_with_scheme(SigningScheme::Secp256k1, message, &ed_signature, &secp_public),
+            Ok(true)
+        ));
+    }
+
+    #[test]
+    fn test_payload_signature_independent_of_key_order() {
+        let manager = SignatureManager::new();
+        let key_bytes = [3u8; 32];
+        let secp = secp256k1::Secp256k1::new();
+        let secret_key = secp256k1::SecretKey::from_byte_array(key_bytes).unwrap();
+        let public_key = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+
+        let mut sender: HashMap<&str, serde_json::Value> = HashMap::new();
+        sender.insert("to", serde_json::json!("0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6"));
+        sender.insert("amount", serde_json::json!("1000"));
+        sender.insert("chainId", serde_json::json!(1114));
+        let receiver = serde_json::json!({
+            "chainId": 1114,
+            "amount": "1000",
+            "to": "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6"
+        });
+
+        let signature = manager.sign_payload_with_bytes(&sender, &key_bytes).unwrap();
+        assert!(manager.verify_payload(&receiver, &signature, &public_key).unwrap());
+
+        let tampered = serde_json::json!({ "chainId": 1114, "amount": "1001", "to": "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6" });
+        assert!(!manager.verify_payload(&tampered, &signature, &public_key).unwrap());
+    }
+}
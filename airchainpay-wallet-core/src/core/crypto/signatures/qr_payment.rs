@@ -0,0 +1,194 @@
+//! QR payment payloads
+//!
+//! A QR payload is the base64url (unpadded) encoding of
+//! `version (1) || signer address (20) || signature (65) || canonical JSON payment`.
+//! The signature is the one `SignatureManager::sign_qr_payment_with_bytes` makes over the
+//! canonical JSON: a recoverable secp256k1 signature (`r ‖ s ‖ v`) over its Keccak-256 hash.
+//! Anyone can sign a payload, so decoding returns the signer and the caller decides whether
+//! to trust it.
+
+use crate::shared::error::WalletError;
+use crate::shared::types::BLEPaymentData;
+use crate::shared::utils::{to_canonical_json, to_checksum_address};
+use crate::shared::WalletResult;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, PublicKey, Secp256k1};
+use sha3::{Digest, Keccak256};
+
+/// Current QR payload format version
+pub const QR_PAYMENT_VERSION: u8 = 1;
+
+const ADDRESS_LEN: usize = 20;
+const SIGNATURE_LEN: usize = 65;
+const HEADER_LEN: usize = 1 + ADDRESS_LEN + SIGNATURE_LEN;
+
+/// A payment read from a QR payload whose signature has been checked
+#[derive(Debug, Clone)]
+pub struct QrPayment {
+    pub payment: BLEPaymentData,
+    /// Checksummed address of the key that signed the payment
+    pub signer: String,
+    /// 0x-prefixed 65-byte signature, as made by `sign_qr_payment_with_bytes`
+    pub signature: String,
+}
+
+impl QrPayment {
+    pub fn is_signed_by(&self, address: &str) -> bool {
+        self.signer.eq_ignore_ascii_case(address.trim())
+    }
+}
+
+pub(crate) fn address_of(public_key: &PublicKey) -> [u8; ADDRESS_LEN] {
+    let hash = Keccak256::digest(&public_key.serialize_uncompressed()[1..]);
+    let mut address = [0u8; ADDRESS_LEN];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// Address whose key made the recoverable `signature` over the Keccak-256 hash of `message`
+pub(crate) fn recover_signer(message: &[u8], signature: &[u8]) -> WalletResult<[u8; ADDRESS_LEN]> {
+    if signature.len() != SIGNATURE_LEN {
+        return Err(WalletError::crypto("Invalid signature length".to_string()));
+    }
+    let v = signature[64];
+    let rec_id = RecoveryId::try_from(i32::from(if v >= 27 { v - 27 } else { v }))
+        .map_err(|e| WalletError::crypto(format!("Invalid recovery id: {}", e)))?;
+    let rec_sig = RecoverableSignature::from_compact(&signature[..64], rec_id)
+        .map_err(|e| WalletError::crypto(format!("Invalid signature: {}", e)))?;
+    let public_key = Secp256k1::verification_only()
+        .recover_ecdsa(Message::from_digest(Keccak256::digest(message).into()), &rec_sig)
+        .map_err(|e| WalletError::crypto(format!("Signature recovery failed: {}", e)))?;
+    Ok(address_of(&public_key))
+}
+
+/// Encode a signed payment as a QR payload. `signature` is the hex signature
+/// `sign_qr_payment_with_bytes` made over the payment's canonical JSON; the signer is
+/// recovered from it and embedded, so a signature that doesn't match is rejected here.
+pub fn encode_qr_payment(payment: &BLEPaymentData, signature: &str) -> WalletResult<String> {
+    let signature = hex::decode(signature.trim().trim_start_matches("0x"))
+        .map_err(|e| WalletError::crypto(format!("Invalid signature format: {}", e)))?;
+    let message = to_canonical_json(payment)?;
+    let signer = recover_signer(&message, &signature)?;
+
+    let mut payload = Vec::with_capacity(HEADER_LEN + message.len());
+    payload.push(QR_PAYMENT_VERSION);
+    payload.extend_from_slice(&signer);
+    payload.extend_from_slice(&signature);
+    payload.extend_from_slice(&message);
+    Ok(URL_SAFE_NO_PAD.encode(payload))
+}
+
+/// Decode a QR payload once its signature has been checked against the embedded payment
+/// and signer. Check `QrPayment::signer` before acting on the payment.
+pub fn decode_qr_payment(encoded: &str) -> WalletResult<QrPayment> {
+    let payload = URL_SAFE_NO_PAD.decode(encoded.trim())
+        .map_err(|e| WalletError::validation(format!("Invalid QR payload encoding: {}", e)))?;
+    match payload.first() {
+        Some(&QR_PAYMENT_VERSION) => {}
+        Some(version) => return Err(WalletError::validation(format!("Unsupported QR payload version {}", version))),
+        None => return Err(WalletError::validation("Empty QR payload")),
+    }
+    if payload.len() <= HEADER_LEN {
+        return Err(WalletError::validation("QR payload too short"));
+    }
+
+    let signer = &payload[1..1 + ADDRESS_LEN];
+    let signature = &payload[1 + ADDRESS_LEN..HEADER_LEN];
+    let message = &payload[HEADER_LEN..];
+    if recover_signer(message, signature)? != signer {
+        return Err(WalletError::crypto("QR payment signature does not match the payment data".to_string()));
+    }
+
+    let payment: BLEPaymentData = serde_json::from_slice(message)
+        .map_err(|e| WalletError::validation(format!("Invalid QR payment data: {}", e)))?;
+    Ok(QrPayment {
+        payment,
+        signer: to_checksum_address(&format!("0x{}", hex::encode(signer)))?,
+        signature: format!("0x{}", hex::encode(signature)),
+    })
+}
+
+/// `decode_qr_payment`, failing unless the payment was signed by `expected_signer`
+pub fn decode_qr_payment_from(encoded: &str, expected_signer: &str) -> WalletResult<QrPayment> {
+    let decoded = decode_qr_payment(encoded)?;
+    if !decoded.is_signed_by(expected_signer) {
+        return Err(WalletError::crypto(format!("QR payment was signed by {}, not {}", decoded.signer, expected_signer)));
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::crypto::signatures::SignatureManager;
+    use crate::shared::types::Network;
+
+    fn payment() -> BLEPaymentData {
+        BLEPaymentData {
+            amount: "250000000000000000".to_string(),
+            to_address: "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
+            token_symbol: "TCORE2".to_string(),
+            network: Network::CoreTestnet,
+            reference: Some("invoice-88".to_string()),
+        }
+    }
+
+    fn sign(payment: &BLEPaymentData, key: &[u8; 32]) -> String {
+        SignatureManager::new().sign_qr_payment_with_bytes(&to_canonical_json(payment).unwrap(), key).unwrap()
+    }
+
+    #[test]
+    fn test_qr_payment_round_trip() {
+        // Address of the private key 0x00..01
+        let mut key = [0u8; 32];
+        key[31] = 1;
+        let signer = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+
+        let signature = sign(&payment(), &key);
+        let encoded = encode_qr_payment(&payment(), &signature).unwrap();
+        assert!(encoded.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+        let decoded = decode_qr_payment(&encoded).unwrap();
+        assert_eq!(decoded.payment.amount, payment().amount);
+        assert_eq!(decoded.payment.to_address, payment().to_address);
+        assert_eq!(decoded.payment.reference, payment().reference);
+        assert_eq!(decoded.signature, signature);
+        assert_eq!(decoded.signer, signer);
+        assert!(decode_qr_payment_from(&encoded, &signer.to_lowercase()).is_ok());
+
+        let mut other = payment();
+        other.amount = "1".to_string();
+        assert!(encode_qr_payment(&other, &signature[..130]).is_err());
+    }
+
+    #[test]
+    fn test_tampered_amount_fails_verification() {
+        let signature = sign(&payment(), &[9u8; 32]);
+        let mut payload = URL_SAFE_NO_PAD.decode(encode_qr_payment(&payment(), &signature).unwrap()).unwrap();
+
+        let amount_at = payload.windows(4).position(|w| w == b"2500").unwrap();
+        payload[amount_at] = b'9';
+        let err = decode_qr_payment(&URL_SAFE_NO_PAD.encode(&payload)).unwrap_err();
+        assert!(matches!(err, WalletError::Crypto(_)));
+
+        payload[0] = QR_PAYMENT_VERSION + 1;
+        let err = decode_qr_payment(&URL_SAFE_NO_PAD.encode(&payload)).unwrap_err();
+        assert!(err.to_string().contains("version"));
+    }
+
+    #[test]
+    fn test_payment_re_signed_by_another_key_is_attributed_to_it() {
+        let payee = [9u8; 32];
+        let mut forged = payment();
+        forged.to_address = "0x2222222222222222222222222222222222222222".to_string();
+        let encoded = encode_qr_payment(&forged, &sign(&forged, &[7u8; 32])).unwrap();
+
+        // The forgery decodes, but not as the payee's
+        let decoded = decode_qr_payment(&encoded).unwrap();
+        let payee_address = decode_qr_payment(&encode_qr_payment(&payment(), &sign(&payment(), &payee)).unwrap()).unwrap().signer;
+        assert!(!decoded.is_signed_by(&payee_address));
+        assert!(matches!(decode_qr_payment_from(&encoded, &payee_address), Err(WalletError::Crypto(_))));
+    }
+}
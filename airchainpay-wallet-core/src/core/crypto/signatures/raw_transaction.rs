@@ -0,0 +1,42 @@
+//! Raw EIP-155 legacy transaction signing
+//!
+//! The encoding and signing live in the `no_std` `airchainpay-signing` crate; this module
+//! adapts wallet `Transaction`s and errors to it. It backs `SignatureManager::sign_legacy_raw`
+//! and the `offline_signing` module.
+
+use crate::shared::error::WalletError;
+use crate::shared::types::Transaction;
+use airchainpay_signing::{LegacyTransaction, SigningError};
+
+pub use airchainpay_signing::{keccak256, transaction_hash};
+
+fn legacy_transaction(tx: &Transaction) -> Result<LegacyTransaction<'_>, WalletError> {
+    Ok(LegacyTransaction {
+        nonce: tx.nonce.ok_or_else(|| WalletError::validation("Missing nonce"))?,
+        gas_price: tx.gas_price.ok_or_else(|| WalletError::validation("Missing gas price"))?,
+        gas_limit: tx.gas_limit.ok_or_else(|| WalletError::validation("Missing gas limit"))?,
+        to: &tx.to,
+        value: &tx.value,
+        data: tx.data.as_deref().unwrap_or_default(),
+        chain_id: tx.chain_id,
+    })
+}
+
+fn wallet_error(error: SigningError) -> WalletError {
+    match error {
+        SigningError::InvalidKeyLength | SigningError::InvalidKey => WalletError::crypto(error.to_string()),
+        _ => WalletError::validation(error.to_string()),
+    }
+}
+
+/// RLP payload signed for an EIP-155 legacy transaction:
+/// `rlp([nonce, gasPrice, gasLimit, to, value, data, chainId, 0, 0])`
+pub fn encode_legacy_signing_payload(tx: &Transaction) -> Result<Vec<u8>, WalletError> {
+    airchainpay_signing::encode_legacy_signing_payload(&legacy_transaction(tx)?).map_err(wallet_error)
+}
+
+/// Sign a legacy transaction with EIP-155 replay protection and return the raw RLP bytes
+/// accepted by `eth_sendRawTransaction`. `key_bytes` is the 32-byte secp256k1 secret key.
+pub fn sign_legacy_raw(tx: &Transaction, key_bytes: &[u8]) -> Result<Vec<u8>, WalletError> {
+    airchainpay_signing::sign_legacy_raw(&legacy_transaction(tx)?, key_bytes).map_err(wallet_error)
+}
@@ -1,15 +1,19 @@
 use crate::shared::error::WalletError;
 use crate::shared::WalletResult;
-use crate::core::crypto::keys::SecurePrivateKey;
+use crate::core::crypto::keys::{SecurePrivateKey, SigningScheme};
 use secp256k1::{SecretKey, PublicKey, Secp256k1, Message};
 use secp256k1::ecdsa::{Signature, RecoverableSignature, RecoveryId};
 use sha3::{Keccak256, Digest};
 use std::str::FromStr;
 use super::TransactionSignature;
+use super::typed_data::{typed_data_hash, Eip712Domain, Eip712Types};
 use crate::shared::types::Transaction;
 use ethers::types::U256;
 use rlp::RlpStream;
 
+/// EIP-2718 type byte of an EIP-1559 transaction
+const EIP1559_TX_TYPE: u8 = 0x02;
+
 /// Digital signature manager
 pub struct SignatureManager {
     secp: Secp256k1<secp256k1::All>,
@@ -58,6 +62,56 @@ impl SignatureManager {
         Ok(self.secp.verify_ecdsa(Message::from_digest(hasher.finalize().as_slice().try_into().map_err(|_| WalletError::crypto("Invalid message hash length".to_string()))?), signature, public_key).is_ok())
     }
 
+    /// Sign a message with key bytes under the given scheme, returning the hex signature.
+    /// secp256k1 signs the keccak256 digest (64-byte compact); ed25519 signs the raw message.
+    pub fn sign_with_scheme(&self, scheme: SigningScheme, message: &[u8], key_bytes: &[u8]) -> WalletResult<String> {
+        match scheme {
+            SigningScheme::Secp256k1 => {
+                let secret_key = SecretKey::from_byte_array(key_bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key length".to_string()))?)
+                    .map_err(|e| WalletError::crypto(format!("Invalid private key: {}", e)))?;
+                let message_hash = Keccak256::digest(message);
+                let secp_message = Message::from_digest(message_hash.as_slice().try_into().map_err(|_| WalletError::crypto("Invalid message hash length".to_string()))?);
+                Ok(hex::encode(self.secp.sign_ecdsa(secp_message, &secret_key).serialize_compact()))
+            }
+            SigningScheme::Ed25519 => {
+                use ed25519_dalek::Signer;
+                let seed = zeroize::Zeroizing::new(<[u8; ed25519_dalek::SECRET_KEY_LENGTH]>::try_from(key_bytes)
+                    .map_err(|_| WalletError::crypto("Invalid private key length".to_string()))?);
+                let signing_key = ed25519_dalek::SigningKey::from_bytes(&seed);
+                Ok(hex::encode(signing_key.sign(message).to_bytes()))
+            }
+        }
+    }
+
+    /// Verify a hex signature against a hex public key using the verifier for `scheme`.
+    /// Malformed signatures or keys for the scheme are reported as errors, mismatches as `false`.
+    pub fn verify_with_scheme(&self, scheme: SigningScheme, message: &[u8], signature: &str, public_key: &str) -> WalletResult<bool> {
+        let signature_bytes = hex::decode(signature.trim_start_matches("0x"))
+            .map_err(|_| WalletError::validation("Invalid signature hex".to_string()))?;
+        let public_key_bytes = hex::decode(public_key.trim_start_matches("0x"))
+            .map_err(|_| WalletError::validation("Invalid public key hex".to_string()))?;
+
+        match scheme {
+            SigningScheme::Secp256k1 => {
+                let signature = Signature::from_compact(&signature_bytes)
+                    .map_err(|e| WalletError::crypto(format!("Invalid signature format: {}", e)))?;
+                let public_key = PublicKey::from_slice(&public_key_bytes)
+                    .map_err(|e| WalletError::crypto(format!("Invalid public key: {}", e)))?;
+                self.verify_signature(message, &signature, &public_key)
+            }
+            SigningScheme::Ed25519 => {
+                use ed25519_dalek::Verifier;
+                let signature = ed25519_dalek::Signature::from_slice(&signature_bytes)
+                    .map_err(|e| WalletError::crypto(format!("Invalid signature format: {}", e)))?;
+                let key_bytes: [u8; ed25519_dalek::PUBLIC_KEY_LENGTH] = public_key_bytes.as_slice().try_into()
+                    .map_err(|_| WalletError::crypto("Invalid public key length".to_string()))?;
+                let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&key_bytes)
+                    .map_err(|e| WalletError::crypto(format!("Invalid public key: {}", e)))?;
+                Ok(verifying_key.verify(message, &signature).is_ok())
+            }
+        }
+    }
+
     /// Sign Ethereum transaction (EVM compatible) with key bytes
     pub fn sign_ethereum_transaction_with_bytes(&self, tx: &Transaction, key_bytes: &[u8]) -> WalletResult<TransactionSignature> {
         // Keep legacy API for compatibility, but prefer sign_legacy_raw for production
@@ -78,12 +132,6 @@ impl SignatureManager {
         Err(WalletError::crypto("Use sign_ethereum_transaction_with_bytes instead".to_string()))
     }
 
-    /// Calculate the v value for Ethereum signatures
-    fn calculate_v_eip155(&self, rec_id: RecoveryId, chain_id: u64) -> u64 {
-        let rec_num: i32 = i32::from(rec_id);
-        (rec_num as u64) + 35 + 2 * chain_id
-    }
-
     fn u256_to_bytes_be(val: U256) -> Vec<u8> {
         if val.is_zero() {
             return Vec::new();
@@ -94,95 +142,71 @@ impl SignatureManager {
         buf[first_non_zero..].to_vec()
     }
 
-    fn encode_legacy_signing_payload(
-        &self,
-        tx: &Transaction,
-        nonce: u64,
-        gas_price: u64,
-        gas_limit: u64,
-        to_bytes: Vec<u8>,
-        value_bytes: Vec<u8>,
-        data_bytes: Vec<u8>,
-    ) -> Vec<u8> {
-        let mut s = RlpStream::new_list(9);
-        s.append(&nonce);
-        s.append(&gas_price);
-        s.append(&gas_limit);
-        if to_bytes.is_empty() {
-            s.append_empty_data();
-        } else {
-            s.append(&to_bytes.as_slice());
-        }
-        s.append(&value_bytes.as_slice());
-        s.append(&data_bytes.as_slice());
-        s.append(&tx.chain_id);
-        s.append_empty_data();
-        s.append_empty_data();
-        s.out().to_vec()
-    }
-
-    fn encode_legacy_raw_tx(
-        &self,
-        nonce: u64,
-        gas_price: u64,
-        gas_limit: u64,
-        to_bytes: Vec<u8>,
-        value_bytes: Vec<u8>,
-        data_bytes: Vec<u8>,
-        v: U256,
-        r: Vec<u8>,
-        s: Vec<u8>,
-    ) -> Vec<u8> {
-        let mut st = RlpStream::new_list(9);
-        st.append(&nonce);
-        st.append(&gas_price);
-        st.append(&gas_limit);
-        if to_bytes.is_empty() {
-            st.append_empty_data();
-        } else {
-            st.append(&to_bytes.as_slice());
-        }
-        st.append(&value_bytes.as_slice());
-        st.append(&data_bytes.as_slice());
-        let mut v_bytes = [0u8; 32];
-        v.to_big_endian(&mut v_bytes);
-        let v_trim = Self::u256_to_bytes_be(v);
-        st.append(&v_trim.as_slice());
-        st.append(&r.as_slice());
-        st.append(&s.as_slice());
-        st.out().to_vec()
-    }
-
     /// Sign a legacy (pre-1559) Ethereum transaction with EIP-155 semantics and return raw tx and tx hash
     pub fn sign_legacy_raw(&self, tx: &Transaction, key_bytes: &[u8]) -> WalletResult<(Vec<u8>, String)> {
+        let raw_tx = super::raw_transaction::sign_legacy_raw(tx, key_bytes)?;
+        let tx_hash = format!("0x{}", hex::encode(super::raw_transaction::transaction_hash(&raw_tx)));
+        Ok((raw_tx, tx_hash))
+    }
+
+    /// Sign an EIP-1559 (type 2) transaction and return the `0x02`-prefixed raw tx and its hash,
+    /// as accepted by `eth_sendRawTransaction`
+    pub fn sign_1559_raw(&self, tx: &Transaction, key_bytes: &[u8]) -> WalletResult<(Vec<u8>, String)> {
         let secret_key = SecretKey::from_byte_array(key_bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key length".to_string()))?)
             .map_err(|e| WalletError::crypto(format!("Invalid private key: {}", e)))?;
 
         let nonce = tx.nonce.ok_or_else(|| WalletError::validation("Missing nonce"))?;
-        let gas_price = tx.gas_price.ok_or_else(|| WalletError::validation("Missing gas price"))?;
         let gas_limit = tx.gas_limit.ok_or_else(|| WalletError::validation("Missing gas limit"))?;
+        let max_fee = tx.max_fee_per_gas.ok_or_else(|| WalletError::validation("Missing max fee per gas"))?;
+        let max_priority_fee = tx.max_priority_fee_per_gas.ok_or_else(|| WalletError::validation("Missing max priority fee per gas"))?;
+        if max_priority_fee > max_fee {
+            return Err(WalletError::validation("Max priority fee per gas cannot exceed max fee per gas"));
+        }
 
         let to_bytes = if tx.to.is_empty() { Vec::new() } else { hex::decode(tx.to.trim_start_matches("0x")).map_err(|_| WalletError::validation("Invalid to address"))? };
         let value_u256 = U256::from_dec_str(&tx.value).map_err(|_| WalletError::validation("Invalid value"))?;
         let value_bytes = Self::u256_to_bytes_be(value_u256);
         let data_bytes = tx.data.clone().unwrap_or_default();
+        let fields = |stream: &mut RlpStream| {
+            stream.append(&tx.chain_id);
+            stream.append(&nonce);
+            stream.append(&max_priority_fee);
+            stream.append(&max_fee);
+            stream.append(&gas_limit);
+            if to_bytes.is_empty() {
+                stream.append_empty_data();
+            } else {
+                stream.append(&to_bytes.as_slice());
+            }
+            stream.append(&value_bytes.as_slice());
+            stream.append(&data_bytes.as_slice());
+            // Empty access list
+            stream.begin_list(0);
+        };
 
-        // Signing payload per EIP-155
-        let signing_rlp = self.encode_legacy_signing_payload(tx, nonce, gas_price, gas_limit, to_bytes.clone(), value_bytes.clone(), data_bytes.clone());
+        // Signing payload: keccak256(0x02 || rlp([chainId, nonce, ..., accessList]))
+        let mut payload = RlpStream::new_list(9);
+        fields(&mut payload);
         let mut hasher = Keccak256::new();
-        hasher.update(&signing_rlp);
+        hasher.update([EIP1559_TX_TYPE]);
+        hasher.update(payload.out());
         let sighash = hasher.finalize();
         let msg = Message::from_digest(sighash.as_slice().try_into().map_err(|_| WalletError::crypto("Invalid tx hash length"))?);
 
         let rec_sig: RecoverableSignature = self.secp.sign_ecdsa_recoverable(msg, &secret_key);
         let (rec_id, compact) = rec_sig.serialize_compact();
-        let r = compact[0..32].to_vec();
-        let s = compact[32..64].to_vec();
-        let v_num = self.calculate_v_eip155(rec_id, tx.chain_id);
-        let v_u256 = U256::from(v_num);
+        let y_parity = i32::from(rec_id) as u64;
+        let r = Self::u256_to_bytes_be(U256::from_big_endian(&compact[0..32]));
+        let s = Self::u256_to_bytes_be(U256::from_big_endian(&compact[32..64]));
+
+        let mut signed = RlpStream::new_list(12);
+        fields(&mut signed);
+        signed.append(&y_parity);
+        signed.append(&r.as_slice());
+        signed.append(&s.as_slice());
+        let mut raw_tx = vec![EIP1559_TX_TYPE];
+        raw_tx.extend_from_slice(&signed.out());
 
-        // Raw tx assembly
-        let raw_tx = self.encode_legacy_raw_tx(nonce, gas_price, gas_limit, to_bytes, value_bytes, data_bytes, v_u256, r.clone(), s.clone());
         let mut hasher2 = Keccak256::new();
         hasher2.update(&raw_tx);
         let tx_hash = format!("0x{}", hex::encode(hasher2.finalize()));
@@ -230,9 +254,85 @@ impl SignatureManager {
         self.verify_signature(payment_data, &signature_obj, public_key)
     }
 
-    /// Sign QR payment data with key bytes
+    /// Sign a structured payload (BLE, QR, EIP-712 message) over its canonical JSON encoding
+    pub fn sign_payload_with_bytes<T: serde::Serialize + ?Sized>(&self, payload: &T, key_bytes: &[u8]) -> WalletResult<String> {
+        let canonical = crate::shared::utils::to_canonical_json(payload)?;
+        self.sign_ble_payment_with_bytes(&canonical, key_bytes)
+    }
+
+    /// Verify a signature made with `sign_payload_with_bytes`
+    pub fn verify_payload<T: serde::Serialize + ?Sized>(&self, payload: &T, signature: &str, public_key: &PublicKey) -> WalletResult<bool> {
+        let canonical = crate::shared::utils::to_canonical_json(payload)?;
+        self.verify_ble_payment(&canonical, signature, public_key)
+    }
+
+    /// Sign EIP-712 typed data, returning the 65-byte `r ‖ s ‖ v` signature as 0x-prefixed hex
+    pub fn sign_typed_data_with_bytes(
+        &self,
+        domain: &Eip712Domain,
+        types: &Eip712Types,
+        primary_type: &str,
+        message: &serde_json::Value,
+        key_bytes: &[u8],
+    ) -> WalletResult<String> {
+        let secret_key = SecretKey::from_byte_array(key_bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key length".to_string()))?)
+            .map_err(|e| WalletError::crypto(format!("Invalid private key: {}", e)))?;
+
+        let digest = typed_data_hash(domain, types, primary_type, message)?;
+        let rec_sig = self.secp.sign_ecdsa_recoverable(Message::from_digest(digest), &secret_key);
+        let (rec_id, compact) = rec_sig.serialize_compact();
+
+        let mut signature = compact.to_vec();
+        signature.push(27 + i32::from(rec_id) as u8);
+        Ok(format!("0x{}", hex::encode(signature)))
+    }
+
+    /// Check that an EIP-712 signature over the typed data was made by `address`
+    pub fn verify_typed_data(
+        &self,
+        domain: &Eip712Domain,
+        types: &Eip712Types,
+        primary_type: &str,
+        message: &serde_json::Value,
+        signature: &str,
+        address: &str,
+    ) -> WalletResult<bool> {
+        let digest = typed_data_hash(domain, types, primary_type, message)?;
+        let signature_bytes = hex::decode(signature.trim_start_matches("0x"))
+            .map_err(|e| WalletError::crypto(format!("Invalid signature format: {}", e)))?;
+        if signature_bytes.len() != 65 {
+            return Err(WalletError::crypto("Invalid signature length".to_string()));
+        }
+
+        let v = signature_bytes[64];
+        let rec_id = RecoveryId::try_from(i32::from(if v >= 27 { v - 27 } else { v }))
+            .map_err(|e| WalletError::crypto(format!("Invalid recovery id: {}", e)))?;
+        let rec_sig = RecoverableSignature::from_compact(&signature_bytes[..64], rec_id)
+            .map_err(|e| WalletError::crypto(format!("Invalid signature: {}", e)))?;
+        let public_key = match self.secp.recover_ecdsa(Message::from_digest(digest), &rec_sig) {
+            Ok(public_key) => public_key,
+            Err(_) => return Ok(false),
+        };
+
+        let mut hasher = Keccak256::new();
+        hasher.update(&public_key.serialize_uncompressed()[1..]);
+        let recovered = hex::encode(&hasher.finalize()[12..]);
+        Ok(recovered.eq_ignore_ascii_case(address.trim_start_matches("0x")))
+    }
+
+    /// Sign QR payment data with key bytes, returning the 65-byte `r ‖ s ‖ v` signature over
+    /// its Keccak-256 hash as 0x-prefixed hex. This is the signature `encode_qr_payment` embeds
+    /// when `payment_data` is the payment's canonical JSON.
     pub fn sign_qr_payment_with_bytes(&self, payment_data: &[u8], key_bytes: &[u8]) -> WalletResult<String> {
-        self.sign_ble_payment_with_bytes(payment_data, key_bytes)
+        let secret_key = SecretKey::from_byte_array(key_bytes.try_into().map_err(|_| WalletError::crypto("Invalid private key length".to_string()))?)
+            .map_err(|e| WalletError::crypto(format!("Invalid private key: {}", e)))?;
+        let digest: [u8; 32] = Keccak256::digest(payment_data).into();
+        let rec_sig = self.secp.sign_ecdsa_recoverable(Message::from_digest(digest), &secret_key);
+        let (rec_id, compact) = rec_sig.serialize_compact();
+
+        let mut signature = compact.to_vec();
+        signature.push(27 + i32::from(rec_id) as u8);
+        Ok(format!("0x{}", hex::encode(signature)))
     }
 
     /// Sign QR payment data - legacy method
@@ -241,9 +341,14 @@ impl SignatureManager {
         Err(WalletError::crypto("Use sign_qr_payment_with_bytes instead".to_string()))
     }
 
-    /// Verify QR payment signature
+    /// Verify a signature made with `sign_qr_payment_with_bytes`
     pub fn verify_qr_payment(&self, payment_data: &[u8], signature: &str, public_key: &PublicKey) -> WalletResult<bool> {
-        self.verify_ble_payment(payment_data, signature, public_key)
+        let signature = hex::decode(signature.trim_start_matches("0x"))
+            .map_err(|e| WalletError::crypto(format!("Invalid signature format: {}", e)))?;
+        Ok(match super::qr_payment::recover_signer(payment_data, &signature) {
+            Ok(signer) => signer == super::qr_payment::address_of(public_key),
+            Err(_) => false,
+        })
     }
 }
 
@@ -0,0 +1,178 @@
+//! EIP-712 typed structured data
+//!
+//! Validates application-defined type sets and computes the domain-separated
+//! digest that `SignatureManager::sign_typed_data_with_bytes` signs.
+
+use crate::shared::error::WalletError;
+use crate::shared::WalletResult;
+use ethers::types::transaction::eip712::{Eip712, TypedData};
+use std::collections::{BTreeMap, HashSet};
+
+pub use ethers::types::transaction::eip712::{EIP712Domain as Eip712Domain, Eip712DomainType, Types as Eip712Types};
+
+/// Reserved name of the domain struct; it is derived from `Eip712Domain`, never referenced
+pub const EIP712_DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Strip array suffixes (`Person[]`, `uint256[3][]`) down to the element type
+fn base_type(field_type: &str) -> &str {
+    field_type.split('[').next().unwrap_or(field_type)
+}
+
+fn is_atomic_type(field_type: &str) -> bool {
+    let sized = |prefix: &str, valid: &dyn Fn(u32) -> bool| {
+        field_type
+            .strip_prefix(prefix)
+            .and_then(|size| size.parse::<u32>().ok())
+            .is_some_and(valid)
+    };
+    matches!(field_type, "address" | "bool" | "string" | "bytes")
+        || sized("bytes", &|n| (1..=32).contains(&n))
+        || sized("uint", &|n| n % 8 == 0 && (8..=256).contains(&n))
+        || sized("int", &|n| n % 8 == 0 && (8..=256).contains(&n))
+}
+
+/// Check that every field references an atomic type or a defined struct, that the
+/// primary type exists, and that no struct (directly or transitively) contains itself.
+pub fn validate_types(types: &Eip712Types, primary_type: &str) -> WalletResult<()> {
+    if !types.contains_key(primary_type) {
+        return Err(WalletError::validation(format!("Primary type '{}' is not defined", primary_type)));
+    }
+
+    for (name, fields) in types {
+        let mut seen = HashSet::new();
+        for field in fields {
+            if !seen.insert(field.name.as_str()) {
+                return Err(WalletError::validation(format!("Duplicate field '{}' in type '{}'", field.name, name)));
+            }
+            let referenced = base_type(&field.r#type);
+            if referenced == EIP712_DOMAIN_TYPE {
+                return Err(WalletError::validation(format!("Type '{}' cannot reference {}", name, EIP712_DOMAIN_TYPE)));
+            }
+            if !is_atomic_type(referenced) && !types.contains_key(referenced) {
+                return Err(WalletError::validation(format!(
+                    "Unknown type '{}' referenced by {}.{}", field.r#type, name, field.name
+                )));
+            }
+        }
+    }
+
+    let mut done = HashSet::new();
+    for name in types.keys() {
+        let mut path = Vec::new();
+        check_acyclic(types, name, &mut path, &mut done)?;
+    }
+    Ok(())
+}
+
+fn check_acyclic<'a>(
+    types: &'a Eip712Types,
+    name: &'a str,
+    path: &mut Vec<&'a str>,
+    done: &mut HashSet<&'a str>,
+) -> WalletResult<()> {
+    if done.contains(name) {
+        return Ok(());
+    }
+    if let Some(start) = path.iter().position(|visited| *visited == name) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(name);
+        return Err(WalletError::validation(format!("Cyclic type reference: {}", cycle.join(" -> "))));
+    }
+
+    path.push(name);
+    for field in types.get(name).into_iter().flatten() {
+        let referenced = base_type(&field.r#type);
+        if types.contains_key(referenced) {
+            check_acyclic(types, referenced, path, done)?;
+        }
+    }
+    path.pop();
+    done.insert(name);
+    Ok(())
+}
+
+/// EIP-712 digest: keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))
+pub fn typed_data_hash(
+    domain: &Eip712Domain,
+    types: &Eip712Types,
+    primary_type: &str,
+    message: &serde_json::Value,
+) -> WalletResult<[u8; 32]> {
+    validate_types(types, primary_type)?;
+    let message = message
+        .as_object()
+        .ok_or_else(|| WalletError::validation("Typed data message must be a JSON object"))?;
+
+    let typed_data = TypedData {
+        domain: domain.clone(),
+        types: types.clone(),
+        primary_type: primary_type.to_string(),
+        message: message.clone().into_iter().collect::<BTreeMap<_, _>>(),
+    };
+    typed_data
+        .encode_eip712()
+        .map_err(|e| WalletError::validation(format!("Failed to encode typed data: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field(name: &str, r#type: &str) -> Eip712DomainType {
+        Eip712DomainType { name: name.to_string(), r#type: r#type.to_string() }
+    }
+
+    /// The `Mail` example from the EIP-712 specification
+    fn mail_types() -> Eip712Types {
+        let mut types = Eip712Types::new();
+        types.insert("Person".to_string(), vec![field("name", "string"), field("wallet", "address")]);
+        types.insert(
+            "Mail".to_string(),
+            vec![field("from", "Person"), field("to", "Person"), field("contents", "string")],
+        );
+        types
+    }
+
+    fn mail_domain() -> Eip712Domain {
+        Eip712Domain {
+            name: Some("Ether Mail".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some(1u64.into()),
+            verifying_contract: Some("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC".parse().unwrap()),
+            salt: None,
+        }
+    }
+
+    #[test]
+    fn test_typed_data_hash_matches_spec_example() {
+        let message = json!({
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!"
+        });
+        let digest = typed_data_hash(&mail_domain(), &mail_types(), "Mail", &message).unwrap();
+        assert_eq!(hex::encode(digest), "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2");
+    }
+
+    #[test]
+    fn test_validate_types_rejects_unknown_reference_and_missing_primary() {
+        let mut types = mail_types();
+        assert!(validate_types(&types, "Letter").is_err());
+
+        types.get_mut("Mail").unwrap().push(field("attachment", "Attachment"));
+        let err = validate_types(&types, "Mail").unwrap_err();
+        assert!(err.to_string().contains("Attachment"));
+    }
+
+    #[test]
+    fn test_validate_types_rejects_cycles() {
+        let mut types = Eip712Types::new();
+        types.insert("Node".to_string(), vec![field("value", "uint256"), field("next", "Link")]);
+        types.insert("Link".to_string(), vec![field("target", "Node[]")]);
+
+        let err = validate_types(&types, "Node").unwrap_err();
+        assert!(err.to_string().contains("Cyclic"));
+        assert!(typed_data_hash(&mail_domain(), &types, "Node", &json!({})).is_err());
+    }
+}
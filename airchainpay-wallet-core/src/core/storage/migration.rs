@@ -0,0 +1,241 @@
+//! Resumable migrations
+//!
+//! A migration (storage backend, password rotation, backup format) walks a list of
+//! items. Progress is journaled through `SecureStorage` after every item, so an
+//! interrupted run can be resumed without redoing the items that already finished.
+
+use super::SecureStorage;
+use crate::infrastructure::platform::PlatformStorage;
+use crate::shared::error::WalletError;
+use crate::shared::utils::current_timestamp;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::future::Future;
+use zeroize::Zeroizing;
+
+/// Persisted progress of one migration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationJournal {
+    pub migration_id: String,
+    /// Every item to migrate, in the order they are processed
+    pub items: Vec<String>,
+    pub completed: BTreeSet<String>,
+    pub started_at: u64,
+    pub updated_at: u64,
+}
+
+impl MigrationJournal {
+    pub fn remaining(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .filter(|item| !self.completed.contains(*item))
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.items.iter().all(|item| self.completed.contains(item))
+    }
+}
+
+/// Outcome of a `start` or `resume` that ran to completion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Items migrated by this run
+    pub migrated: Vec<String>,
+    /// Items a previous, interrupted run had already migrated
+    pub skipped: usize,
+}
+
+/// Runs a migration while keeping its journal encrypted under `password`.
+/// The journal is removed once every item has been migrated.
+pub struct MigrationRunner<'a> {
+    storage: &'a dyn PlatformStorage,
+    migration_id: String,
+    password: Zeroizing<String>,
+}
+
+impl<'a> MigrationRunner<'a> {
+    pub fn new(storage: &'a dyn PlatformStorage, migration_id: &str, password: &str) -> Self {
+        Self {
+            storage,
+            migration_id: migration_id.to_string(),
+            password: Zeroizing::new(password.to_string()),
+        }
+    }
+
+    fn journal_key(&self) -> String {
+        format!("migration_journal_{}", self.migration_id)
+    }
+
+    /// Whether an interrupted run of this migration is waiting to be resumed
+    pub fn is_pending(&self) -> Result<bool, WalletError> {
+        self.storage.exists(&self.journal_key())
+    }
+
+    /// Load the journal of an interrupted run, if there is one
+    pub async fn journal(&self) -> Result<Option<MigrationJournal>, WalletError> {
+        if !self.is_pending()? {
+            return Ok(None);
+        }
+        let data = SecureStorage::new(self.storage)
+            .retrieve_data(&self.journal_key(), &self.password)
+            .await?;
+        let journal = serde_json::from_slice(&data)
+            .map_err(|e| WalletError::storage(format!("Migration journal is corrupted: {}", e)))?;
+        Ok(Some(journal))
+    }
+
+    async fn save(&self, journal: &MigrationJournal) -> Result<(), WalletError> {
+        let data = serde_json::to_vec(journal)
+            .map_err(|e| WalletError::storage(format!("Migration journal serialization failed: {}", e)))?;
+        SecureStorage::new(self.storage)
+            .store_data(&self.journal_key(), &data, &self.password)
+            .await
+    }
+
+    /// Journal `items` and migrate them one by one. Fails if an earlier run of this
+    /// migration was interrupted; that run has to be finished with `resume` first.
+    pub async fn start<F, Fut>(&self, items: Vec<String>, migrate: F) -> Result<MigrationReport, WalletError>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<(), WalletError>>,
+    {
+        if self.is_pending()? {
+            return Err(WalletError::invalid_field(
+                "migration_id",
+                format!("Migration {} was interrupted and must be resumed", self.migration_id),
+                "migration_pending",
+            ));
+        }
+        let now = current_timestamp();
+        let journal = MigrationJournal {
+            migration_id: self.migration_id.clone(),
+            items,
+            completed: BTreeSet::new(),
+            started_at: now,
+            updated_at: now,
+        };
+        self.save(&journal).await?;
+        self.run(journal, migrate).await
+    }
+
+    /// Continue an interrupted migration, skipping the items it already finished
+    pub async fn resume<F, Fut>(&self, migrate: F) -> Result<MigrationReport, WalletError>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<(), WalletError>>,
+    {
+        let journal = self.journal().await?.ok_or_else(|| {
+            WalletError::invalid_field(
+                "migration_id",
+                format!("No interrupted migration to resume: {}", self.migration_id),
+                "no_migration",
+            )
+        })?;
+        self.run(journal, migrate).await
+    }
+
+    async fn run<F, Fut>(&self, mut journal: MigrationJournal, mut migrate: F) -> Result<MigrationReport, WalletError>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<(), WalletError>>,
+    {
+        let skipped = journal.completed.len();
+        let mut migrated = Vec::new();
+        for item in journal.remaining() {
+            // An error leaves the journal at the last finished item
+            migrate(item.clone()).await?;
+            journal.completed.insert(item.clone());
+            journal.updated_at = current_timestamp();
+            self.save(&journal).await?;
+            migrated.push(item);
+        }
+
+        self.storage.delete(&self.journal_key())?;
+        log::info!(
+            "Migration {} complete: {} item(s) migrated, {} already done",
+            self.migration_id, migrated.len(), skipped
+        );
+        Ok(MigrationReport { migrated, skipped })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::test_doubles::MemoryStorage;
+    use std::sync::Mutex;
+
+    fn items() -> Vec<String> {
+        (1..=5).map(|i| format!("wallet_{}", i)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_crash_migrates_only_remaining_items() {
+        let storage = MemoryStorage::default();
+        let calls = Mutex::new(Vec::new());
+
+        // Crash while migrating the third item
+        let runner = MigrationRunner::new(&storage, "rotate", "journal-password");
+        let err = runner.start(items(), |item| {
+            let calls = &calls;
+            async move {
+                if item == "wallet_3" {
+                    return Err(WalletError::storage("simulated crash"));
+                }
+                calls.lock().unwrap().push(item);
+                Ok(())
+            }
+        }).await;
+        assert!(err.is_err());
+        assert_eq!(*calls.lock().unwrap(), vec!["wallet_1", "wallet_2"]);
+
+        // A fresh runner, as after a restart, picks up the journal
+        let runner = MigrationRunner::new(&storage, "rotate", "journal-password");
+        assert!(runner.is_pending().unwrap());
+        let journal = runner.journal().await.unwrap().unwrap();
+        assert_eq!(journal.remaining(), vec!["wallet_3", "wallet_4", "wallet_5"]);
+        assert!(runner.start(items(), |_| async { Ok(()) }).await.is_err());
+
+        calls.lock().unwrap().clear();
+        let report = runner.resume(|item| {
+            let calls = &calls;
+            async move {
+                calls.lock().unwrap().push(item);
+                Ok(())
+            }
+        }).await.unwrap();
+
+        assert_eq!(*calls.lock().unwrap(), vec!["wallet_3", "wallet_4", "wallet_5"]);
+        assert_eq!(report.migrated, vec!["wallet_3", "wallet_4", "wallet_5"]);
+        assert_eq!(report.skipped, 2);
+        assert!(!runner.is_pending().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_completed_migration_leaves_nothing_to_resume() {
+        let storage = MemoryStorage::default();
+        let runner = MigrationRunner::new(&storage, "backup_format", "journal-password");
+
+        let report = runner.start(items(), |_| async { Ok(()) }).await.unwrap();
+        assert_eq!(report.migrated.len(), 5);
+        assert_eq!(report.skipped, 0);
+        assert!(runner.journal().await.unwrap().is_none());
+
+        let err = runner.resume(|_| async { Ok(()) }).await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "no_migration");
+    }
+
+    #[tokio::test]
+    async fn test_journal_is_encrypted() {
+        let storage = MemoryStorage::default();
+        let runner = MigrationRunner::new(&storage, "rotate", "journal-password");
+        let _ = runner.start(items(), |_| async { Err(WalletError::storage("simulated crash")) }).await;
+
+        let raw = storage.retrieve("migration_journal_rotate").unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("wallet_1"));
+        let wrong = MigrationRunner::new(&storage, "rotate", "other-password");
+        assert!(wrong.journal().await.is_err());
+    }
+}
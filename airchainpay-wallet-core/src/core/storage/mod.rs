@@ -7,15 +7,27 @@ use crate::shared::error::WalletError;
 use crate::shared::types::{WalletBackupInfo};
 use aes_gcm::{Aes256Gcm, KeyInit};
 use aes_gcm::aead::{Aead, generic_array::GenericArray};
-use argon2::{Argon2, PasswordHasher};
 use rand_core::OsRng;
 use rand_core::RngCore;
 use sha2::Digest;
 use serde_json;
-use crate::infrastructure::platform::{PlatformStorage, FileStorage};
+use crate::infrastructure::platform::{PlatformStorage, FileStorage, KdfParams};
+use crate::shared::utils::validate_password;
+use crate::core::crypto::{constant_time_eq, SecurityAuditor};
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine;
 
+pub mod migration;
+pub use migration::{MigrationJournal, MigrationReport, MigrationRunner};
+
+/// Whether `checksum` (hex SHA-256) matches the decoded backup blob, compared in constant time
+fn backup_checksum_matches(encrypted_data: &[u8], checksum: &str) -> bool {
+    match hex::decode(checksum) {
+        Ok(expected) => constant_time_eq(&sha2::Sha256::digest(encrypted_data), &expected),
+        Err(_) => false,
+    }
+}
+
 /// Secure storage manager
 pub struct SecureStorage<'a> {
     storage: &'a dyn PlatformStorage,
@@ -47,6 +59,9 @@ impl<'a> SecureStorage<'a> {
 
     /// Backup wallet securely (no private keys in wallet struct)
     pub async fn backup_wallet(&self, wallet: &Wallet, password: &str) -> Result<WalletBackupInfo, WalletError> {
+        validate_password(password)?;
+        SecurityAuditor::warn_if_weak_password(password, "backup");
+
         // Convert to safe WalletInfo for serialization
         let wallet_info = wallet.to_wallet_info();
         
@@ -59,64 +74,52 @@ impl<'a> SecureStorage<'a> {
         let mut rng = OsRng;
         rng.fill_bytes(&mut salt);
         
-        // Derive key
-        let salt_str = argon2::password_hash::SaltString::encode_b64(&salt)?;
-        let argon2 = Argon2::default();
-        let password_hash = argon2.hash_password(password.as_bytes(), &salt_str)
-            .map_err(|e| WalletError::crypto(format!("Password hashing failed: {}", e)))?;
-        
-        // Handle the case where hash might be None
-        let hash = password_hash.hash
-            .ok_or_else(|| WalletError::crypto("Password hash is empty".to_string()))?;
-        let hash_bytes = hash.as_bytes();
-        let key = GenericArray::from_slice(&hash_bytes[..32]);
+        // Derive key; the parameters go in front of the ciphertext so restore does not depend on defaults
+        let params = KdfParams::SECURE;
+        let key = params.derive_key(password, &salt)?;
         
         // Encrypt
-        let cipher = Aes256Gcm::new(key);
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(&*key));
         let mut nonce = [0u8; 12];
         let mut rng = OsRng;
         rng.fill_bytes(&mut nonce);
-        let mut encrypted_data = nonce.to_vec();
+        let mut encrypted_data = params.header().to_vec();
+        encrypted_data.extend_from_slice(&nonce);
         let ciphertext = cipher.encrypt(GenericArray::from_slice(&nonce), wallet_bytes.as_ref())
             .map_err(|e| WalletError::crypto(format!("Encryption failed: {}", e)))?;
         encrypted_data.extend_from_slice(&ciphertext);
         
-        // Compute checksum (SHA256 of ciphertext)
-        let _checksum = format!("{:x}", sha2::Sha256::digest(&encrypted_data));
+        // Checksum of the whole blob lets corruption be told apart from a wrong password
+        let checksum = format!("{:x}", sha2::Sha256::digest(&encrypted_data));
         
         Ok(WalletBackupInfo {
             wallet_id: wallet.id.clone(),
             encrypted_data: STANDARD.encode(&encrypted_data),
             salt: STANDARD.encode(&salt),
             version: "1.0".to_string(),
+            checksum: Some(checksum),
         })
     }
 
-    /// Restore wallet securely (no private keys in wallet struct)
+    /// Check that `backup` restores with `password` without restoring it. A wrong password
+    /// gives `Ok(false)`; a truncated or modified backup gives `WalletError::BackupIntegrity`.
+    /// Backups without a checksum cannot tell corruption of the ciphertext from a wrong password.
+    pub async fn verify_backup(&self, backup: &WalletBackupInfo, password: &str) -> Result<bool, WalletError> {
+        let wallet_bytes = match Self::decrypt_backup(backup, password)? {
+            Some(wallet_bytes) => wallet_bytes,
+            None => return Ok(false),
+        };
+        serde_json::from_slice::<WalletInfo>(&wallet_bytes)
+            .map_err(|e| WalletError::backup_integrity(format!("Wallet data is malformed: {}", e)))?;
+        Ok(true)
+    }
+
+    /// Restore wallet securely (no private keys in wallet struct). A backup whose checksum
+    /// doesn't match its data is rejected as corrupted before any key derivation; backups
+    /// made before checksums were recorded restore unverified.
     pub async fn restore_wallet(&self, backup: &WalletBackupInfo, password: &str) -> Result<Wallet, WalletError> {
-        let encrypted_data = STANDARD.decode(&backup.encrypted_data)
-            .map_err(|e| WalletError::crypto(format!("Base64 decode failed: {}", e)))?;
-        let salt = STANDARD.decode(&backup.salt)
-            .map_err(|e| WalletError::crypto(format!("Base64 decode failed: {}", e)))?;
-        
-        if encrypted_data.len() < 12 {
-            return Err(WalletError::crypto("Encrypted data too short".to_string()));
-        }
-        
-        let (nonce, ciphertext) = encrypted_data.split_at(12);
-        let salt_str = argon2::password_hash::SaltString::encode_b64(&salt)?;
-        let argon2 = Argon2::default();
-        let password_hash = argon2.hash_password(password.as_bytes(), &salt_str)
-            .map_err(|e| WalletError::crypto(format!("Password hashing failed: {}", e)))?;
-        
-        // Handle the case where hash might be None
-        let hash = password_hash.hash
-            .ok_or_else(|| WalletError::crypto("Password hash is empty".to_string()))?;
-        let hash_bytes = hash.as_bytes();
-        let key = GenericArray::from_slice(&hash_bytes[..32]);
-        let cipher = Aes256Gcm::new(key);
-        let wallet_bytes = cipher.decrypt(GenericArray::from_slice(nonce), ciphertext)
-            .map_err(|e| WalletError::crypto(format!("Decryption failed: {}", e)))?;
+        let wallet_bytes = Self::decrypt_backup(backup, password)?
+            .ok_or_else(|| WalletError::crypto("Decryption failed: wrong password".to_string()))?;
         
         // Deserialize as WalletInfo first
         let wallet_info: WalletInfo = serde_json::from_slice(&wallet_bytes)
@@ -133,58 +136,87 @@ impl<'a> SecureStorage<'a> {
         Ok(wallet)
     }
 
-    async fn encrypt_data(&self, data: &[u8], password: &str) -> Result<Vec<u8>, WalletError> {
-        use aes_gcm::{Aes256Gcm, aead::{Aead, generic_array::GenericArray}};
-        use rand_core::RngCore;
-        use argon2::{Argon2, PasswordHasher};
+    /// Decrypted wallet data of a backup, or `None` if the password does not open it
+    fn decrypt_backup(backup: &WalletBackupInfo, password: &str) -> Result<Option<zeroize::Zeroizing<Vec<u8>>>, WalletError> {
+        validate_password(password)?;
+        if backup.encrypted_data.is_empty() {
+            return Err(WalletError::invalid_field("encrypted_data", "Backup data cannot be empty", "required"));
+        }
+        if backup.salt.is_empty() {
+            return Err(WalletError::invalid_field("salt", "Backup salt cannot be empty", "required"));
+        }
+
+        let encrypted_data = STANDARD.decode(&backup.encrypted_data)
+            .map_err(|e| WalletError::backup_integrity(format!("Base64 decode failed: {}", e)))?;
+        let salt = STANDARD.decode(&backup.salt)
+            .map_err(|e| WalletError::backup_integrity(format!("Base64 decode failed: {}", e)))?;
+        if let Some(checksum) = &backup.checksum {
+            if !backup_checksum_matches(&encrypted_data, checksum) {
+                return Err(WalletError::backup_integrity("Backup corrupted: checksum mismatch"));
+            }
+        }
+        
+        // Backups without a parameter header were made with the old Argon2 defaults
+        let (params, encrypted_data) = KdfParams::split(&encrypted_data);
+        if encrypted_data.len() < 12 {
+            return Err(WalletError::backup_integrity("Encrypted data too short"));
+        }
         
+        let (nonce, ciphertext) = encrypted_data.split_at(12);
+        let key = params.derive_key(password, &salt)?;
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(&*key));
+        // With the checksum intact, failed authentication can only mean a wrong password
+        Ok(cipher.decrypt(GenericArray::from_slice(nonce), ciphertext).ok().map(zeroize::Zeroizing::new))
+    }
+
+    pub(crate) async fn encrypt_data(&self, data: &[u8], password: &str) -> Result<Vec<u8>, WalletError> {
+        Self::encrypt_with_params(data, password, KdfParams::SECURE)
+    }
+
+    pub(crate) fn encrypt_with_params(data: &[u8], password: &str, params: KdfParams) -> Result<Vec<u8>, WalletError> {
         let mut salt = [0u8; 32];
         let mut rng = OsRng;
         rng.fill_bytes(&mut salt);
-        let salt_str = argon2::password_hash::SaltString::encode_b64(&salt)?;
-        let argon2 = Argon2::default();
-        let password_hash = argon2.hash_password(password.as_bytes(), &salt_str)
-            .map_err(|e| WalletError::crypto(format!("Password hashing failed: {}", e)))?;
-        
-        // Handle the case where hash might be None
-        let hash = password_hash.hash
-            .ok_or_else(|| WalletError::crypto("Password hash is empty".to_string()))?;
-        let hash_bytes = hash.as_bytes();
-        let key = GenericArray::from_slice(&hash_bytes[..32]);
-        let cipher = Aes256Gcm::new(key);
+        let key = params.derive_key(password, &salt)?;
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(&*key));
         let mut nonce = [0u8; 12];
         let mut rng = OsRng;
         rng.fill_bytes(&mut nonce);
         let ciphertext = cipher.encrypt(GenericArray::from_slice(&nonce), data)
             .map_err(|e| WalletError::crypto(format!("Encryption failed: {}", e)))?;
         let mut result = Vec::new();
+        result.extend_from_slice(&params.header());
         result.extend_from_slice(&salt);
         result.extend_from_slice(&nonce);
         result.extend_from_slice(&ciphertext);
         Ok(result)
     }
 
-    async fn decrypt_data(&self, encrypted_data: &[u8], password: &str) -> Result<Vec<u8>, WalletError> {
-        use aes_gcm::{Aes256Gcm, aead::{Aead, generic_array::GenericArray}};
-        use argon2::{Argon2, PasswordHasher};
-        
+    pub(crate) async fn decrypt_data(&self, encrypted_data: &[u8], password: &str) -> Result<Vec<u8>, WalletError> {
+        let key = Self::derive_key(password, encrypted_data)?;
+        Self::decrypt_with_key(encrypted_data, &key)
+    }
+
+    /// AES key of `encrypt_data` output, derived from the password with the parameters and salt
+    /// at its start. Output without a parameter header was written with the old Argon2 defaults.
+    pub(crate) fn derive_key(password: &str, encrypted_data: &[u8]) -> Result<zeroize::Zeroizing<[u8; 32]>, WalletError> {
+        let (params, rest) = KdfParams::split(encrypted_data);
+        if rest.len() < 44 {
+            return Err(WalletError::crypto("Encrypted data too short".to_string()));
+        }
+        params.derive_key(password, &rest[..32])
+    }
+
+    /// Decrypt `encrypt_data` output with an already derived key
+    pub(crate) fn decrypt_with_key(encrypted_data: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, WalletError> {
+        let (_params, encrypted_data) = KdfParams::split(encrypted_data);
         if encrypted_data.len() < 44 {
             return Err(WalletError::crypto("Encrypted data too short".to_string()));
         }
         
-        let (salt, rest) = encrypted_data.split_at(32);
+        let (_salt, rest) = encrypted_data.split_at(32);
         let (nonce, ciphertext) = rest.split_at(12);
-        let salt_str = argon2::password_hash::SaltString::encode_b64(salt)?;
-        let argon2 = Argon2::default();
-        let password_hash = argon2.hash_password(password.as_bytes(), &salt_str)
-            .map_err(|e| WalletError::crypto(format!("Password hashing failed: {}", e)))?;
-        
-        // Handle the case where hash might be None
-        let hash = password_hash.hash
-            .ok_or_else(|| WalletError::crypto("Password hash is empty".to_string()))?;
-        let hash_bytes = hash.as_bytes();
-        let key = GenericArray::from_slice(&hash_bytes[..32]);
-        let cipher = Aes256Gcm::new(key);
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
         let plaintext = cipher.decrypt(GenericArray::from_slice(nonce), ciphertext)
             .map_err(|e| WalletError::crypto(format!("Decryption failed: {}", e)))?;
         Ok(plaintext)
@@ -214,6 +246,12 @@ impl StorageManager {
         storage.restore_wallet(backup, password).await
     }
 
+    pub async fn verify_backup(&self, backup: &WalletBackupInfo, password: &str) -> Result<bool, WalletError> {
+        let file_storage = FileStorage::new()?;
+        let storage = SecureStorage::new(&file_storage);
+        storage.verify_backup(backup, password).await
+    }
+
     pub async fn load_wallet(&self, wallet_id: &str, password: &str) -> Result<Wallet, WalletError> {
         let file_storage = FileStorage::new()?;
         let storage = SecureStorage::new(&file_storage);
@@ -334,6 +372,18 @@ mod tests {
         assert!(secure_storage.retrieve_data("test_key", password).await.is_err());
     }
 
+    #[test]
+    fn test_backup_checksum_matches() {
+        let data = b"encrypted backup blob";
+        let checksum = format!("{:x}", sha2::Sha256::digest(data));
+        assert!(backup_checksum_matches(data, &checksum));
+        assert!(backup_checksum_matches(data, &checksum.to_uppercase()));
+
+        assert!(!backup_checksum_matches(b"encrypted backup blob!", &checksum));
+        assert!(!backup_checksum_matches(data, &checksum[..62]));
+        assert!(!backup_checksum_matches(data, "not hex"));
+    }
+
     #[tokio::test]
     async fn test_wallet_backup_restore() {
         let storage = MockStorage::new();
@@ -358,4 +408,133 @@ mod tests {
         assert_eq!(restored.address, wallet.address);
         assert_eq!(restored.network, wallet.network);
     }
-} 
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_verify_backup() {
+        let storage = MockStorage::new();
+        let secure_storage = SecureStorage::new(&storage);
+        let wallet = Wallet::new(
+            "Test Wallet".to_string(),
+            "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
+            "04...".to_string(),
+            Network::CoreTestnet,
+        ).expect("Failed to create test wallet");
+        let backup = secure_storage.backup_wallet(&wallet, "test_password").await
+            .expect("Failed to backup wallet");
+
+        assert!(secure_storage.verify_backup(&backup, "test_password").await.unwrap());
+        assert!(!secure_storage.verify_backup(&backup, "wrong_password").await.unwrap());
+
+        // A flipped bit is an integrity error, not a wrong password
+        let mut data = STANDARD.decode(&backup.encrypted_data).unwrap();
+        *data.last_mut().unwrap() ^= 1;
+        let corrupted = WalletBackupInfo { encrypted_data: STANDARD.encode(&data), ..backup.clone() };
+        for password in ["test_password", "wrong_password"] {
+            let err = secure_storage.verify_backup(&corrupted, password).await.unwrap_err();
+            assert!(matches!(err, WalletError::BackupIntegrity(_)), "{}", err);
+        }
+        let err = secure_storage.restore_wallet(&corrupted, "test_password").await.err().unwrap();
+        assert!(matches!(err, WalletError::BackupIntegrity(_)), "{}", err);
+
+        let truncated = WalletBackupInfo { encrypted_data: STANDARD.encode(&data[..8]), checksum: None, ..backup.clone() };
+        let err = secure_storage.verify_backup(&truncated, "test_password").await.unwrap_err();
+        assert!(matches!(err, WalletError::BackupIntegrity(_)), "{}", err);
+        let not_base64 = WalletBackupInfo { encrypted_data: "***".to_string(), ..backup };
+        let err = secure_storage.verify_backup(&not_base64, "test_password").await.unwrap_err();
+        assert!(matches!(err, WalletError::BackupIntegrity(_)), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_blobs_record_their_kdf_params() {
+        let storage = MockStorage::new();
+        let secure_storage = SecureStorage::new(&storage);
+        let password = "test_password";
+
+        // A blob written with non-default parameters decrypts from its header alone
+        let params = KdfParams { memory_kib: 8192, iterations: 4, parallelism: 2 };
+        let encrypted = SecureStorage::encrypt_with_params(b"tuned", password, params).unwrap();
+        assert_eq!(KdfParams::split(&encrypted).0, params);
+        assert_eq!(secure_storage.decrypt_data(&encrypted, password).await.unwrap(), b"tuned");
+
+        // Backups made before the header existed fall back to the old Argon2 defaults
+        let wallet = Wallet::new(
+            "Legacy Wallet".to_string(),
+            "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
+            "04...".to_string(),
+            Network::CoreTestnet,
+        ).expect("Failed to create test wallet");
+        let salt = [3u8; 16];
+        let key = KdfParams::LEGACY.derive_key(password, &salt).unwrap();
+        let nonce = [5u8; 12];
+        let ciphertext = Aes256Gcm::new(GenericArray::from_slice(&*key))
+            .encrypt(GenericArray::from_slice(&nonce), serde_json::to_vec(&wallet.to_wallet_info()).unwrap().as_ref())
+            .unwrap();
+        let backup = WalletBackupInfo {
+            wallet_id: wallet.id.clone(),
+            encrypted_data: STANDARD.encode([nonce.as_slice(), &ciphertext].concat()),
+            salt: STANDARD.encode(salt),
+            version: "1.0".to_string(),
+            checksum: None,
+        };
+        let restored = secure_storage.restore_wallet(&backup, password).await.unwrap();
+        assert_eq!(restored.name, "Legacy Wallet");
+
+        // Headers cannot demand arbitrarily expensive derivations
+        let greedy = KdfParams { memory_kib: u32::MAX, iterations: 3, parallelism: 1 };
+        let tampered = [greedy.header().as_slice(), &encrypted[KdfParams::HEADER_LEN..]].concat();
+        assert!(secure_storage.decrypt_data(&tampered, password).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_restore_checks_backup_checksum() {
+        let storage = MockStorage::new();
+        let secure_storage = SecureStorage::new(&storage);
+        let wallet = Wallet::new(
+            "Test Wallet".to_string(),
+            "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
+            "04...".to_string(),
+            Network::CoreTestnet,
+        ).expect("Failed to create test wallet");
+        let backup = secure_storage.backup_wallet(&wallet, "test_password").await.unwrap();
+        assert!(backup.checksum.is_some());
+        assert_eq!(secure_storage.restore_wallet(&backup, "test_password").await.unwrap().address, wallet.address);
+
+        // Rejected by the checksum even with a password that could never decrypt it
+        let mut data = STANDARD.decode(&backup.encrypted_data).unwrap();
+        data[KdfParams::HEADER_LEN + 20] ^= 0x80;
+        let tampered = WalletBackupInfo { encrypted_data: STANDARD.encode(&data), ..backup.clone() };
+        for password in ["test_password", "wrong_password"] {
+            let err = secure_storage.restore_wallet(&tampered, password).await.err().unwrap();
+            assert!(matches!(err, WalletError::BackupIntegrity(_)), "{}", err);
+            assert!(err.to_string().contains("Backup corrupted"));
+        }
+
+        // Backups serialized before the checksum field existed still restore
+        let mut legacy = serde_json::to_value(&backup).unwrap();
+        legacy.as_object_mut().unwrap().remove("checksum");
+        let legacy: WalletBackupInfo = serde_json::from_value(legacy).unwrap();
+        assert!(legacy.checksum.is_none());
+        assert_eq!(secure_storage.restore_wallet(&legacy, "test_password").await.unwrap().address, wallet.address);
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_empty_backup_fields() {
+        let storage = MockStorage::new();
+        let secure_storage = SecureStorage::new(&storage);
+        let backup = WalletBackupInfo {
+            wallet_id: "wallet".to_string(),
+            encrypted_data: String::new(),
+            salt: "c2FsdA==".to_string(),
+            version: "1.0".to_string(),
+            checksum: None,
+        };
+
+        let err = secure_storage.restore_wallet(&backup, "test_password").await
+            .err().expect("empty backup data should be rejected");
+        assert_eq!(err.validation_details().map(|d| d.field.as_str()), Some("encrypted_data"));
+
+        let err = secure_storage.restore_wallet(&backup, "short").await
+            .err().expect("short password should be rejected");
+        assert_eq!(err.validation_details().map(|d| d.field.as_str()), Some("password"));
+    }
+}
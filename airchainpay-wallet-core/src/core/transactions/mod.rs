@@ -3,22 +3,448 @@
 //! This module contains transaction creation, signing, and management.
 
 use crate::shared::error::WalletError;
-use crate::shared::types::{Transaction, SignedTransaction, TransactionHash, TransactionStatus, Network, Amount};
+use crate::shared::types::{Transaction, SignedTransaction, TransactionHash, TransactionStatus, ConfirmationStatus, Network, Amount, TokenInfo, AddressFormat};
 use crate::core::crypto::signatures::SignatureManager;
-use reqwest::Client;
+use crate::shared::utils::{is_valid_ethereum_address, parse_units, validate_ethereum_address, ETHER_DECIMALS};
+use ethers::types::U256;
+use crate::infrastructure::network::RpcClient;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Function selector for ERC-20 `approve(address,uint256)`
+pub const ERC20_APPROVE_SELECTOR: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+
+/// Function selector for ERC-20 `transfer(address,uint256)`
+pub const ERC20_TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+/// ABI-encode a `(address, uint256)` call: selector, left-padded address, big-endian amount
+fn encode_address_amount_call(selector: [u8; 4], address: &str, amount: U256) -> Result<Vec<u8>, WalletError> {
+    let address_bytes = hex::decode(&address[2..])?;
+    let mut data = Vec::with_capacity(4 + 32 + 32);
+    data.extend_from_slice(&selector);
+    data.extend_from_slice(&[0u8; 12]);
+    data.extend_from_slice(&address_bytes);
+    let mut amount_word = [0u8; 32];
+    amount.to_big_endian(&mut amount_word);
+    data.extend_from_slice(&amount_word);
+    Ok(data)
+}
+
+/// `parse_units` with its errors reported against `field`
+fn parse_decimal_amount(field: &str, amount: &str, decimals: u8) -> Result<U256, WalletError> {
+    let units = parse_units(amount, decimals).map_err(|e| match e.validation_details() {
+        Some(details) => WalletError::invalid_field(field, details.reason.clone(), details.code.clone()),
+        None => e,
+    })?;
+    U256::from_dec_str(&units).map_err(|_| WalletError::invalid_field(field, "Amount is too large", "invalid_amount"))
+}
+
+/// `eth_call`/`eth_estimateGas` call object for `transaction` sent from `from`
+fn call_object(transaction: &Transaction, from: Option<&str>) -> Result<serde_json::Value, WalletError> {
+    let value = U256::from_dec_str(&transaction.value)
+        .map_err(|_| WalletError::invalid_field("value", "Transaction value must be a decimal integer", "invalid_amount"))?;
+    let mut call = json!({ "to": transaction.to, "value": format!("0x{:x}", value) });
+    if let Some(from) = from {
+        validate_ethereum_address(from)
+            .map_err(|e| WalletError::invalid_field("from", e.to_string(), "invalid_address"))?;
+        call["from"] = json!(from);
+    }
+    if let Some(data) = &transaction.data {
+        call["data"] = json!(format!("0x{}", hex::encode(data)));
+    }
+    Ok(call)
+}
+
+/// Selector of Solidity's `Error(string)`, used by `require` and `revert` with a message
+pub const REVERT_ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of Solidity's `Panic(uint256)`, used by failed asserts, overflows and the like
+pub const REVERT_PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Human-readable reason from revert data: the message of an `Error(string)` or the code of a
+/// `Panic(uint256)`. `None` for custom errors and empty reverts.
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    let (selector, payload) = (data.get(..4)?, &data[4..]);
+    if selector == REVERT_ERROR_SELECTOR {
+        ethers::abi::decode(&[ethers::abi::ParamType::String], payload).ok()?
+            .pop()?
+            .into_string()
+    } else if selector == REVERT_PANIC_SELECTOR {
+        Some(format!("panic code 0x{:x}", U256::from_big_endian(payload.get(..32)?)))
+    } else {
+        None
+    }
+}
+
+/// Reason a JSON-RPC `error` gives for a reverted call: the decoded revert data when there
+/// is any, otherwise the error message
+fn revert_reason(error: &serde_json::Value) -> String {
+    let data = error.get("data")
+        .and_then(|data| data.as_str().or_else(|| data.get("data").and_then(|d| d.as_str())))
+        .and_then(|data| hex::decode(data.trim_start_matches("0x")).ok());
+    let message = error.get("message").and_then(|m| m.as_str()).unwrap_or("execution reverted");
+    data.as_deref()
+        .and_then(decode_revert_reason)
+        .unwrap_or_else(|| message.to_string())
+}
+
+/// Whether a JSON-RPC `error` says the call itself reverted, as opposed to the node failing
+fn is_revert_error(error: &serde_json::Value) -> bool {
+    error.get("code").and_then(|c| c.as_i64()) == Some(3)
+        || error.get("message").and_then(|m| m.as_str()).is_some_and(|m| m.to_ascii_lowercase().contains("revert"))
+}
+
+/// Minimum fee increase, in percent, nodes require before a pending transaction is replaced
+pub const REPLACEMENT_MIN_BUMP_PERCENT: u64 = 10;
+
+/// Copy of `original` priced at `new_gas_price`, to replace it at the same nonce. For EIP-1559
+/// transactions `new_gas_price` is the new max fee and the priority fee rises by the same ratio.
+/// Fails unless the price is at least `REPLACEMENT_MIN_BUMP_PERCENT` above the original.
+pub fn replacement_transaction(original: &Transaction, new_gas_price: u64) -> Result<Transaction, WalletError> {
+    let original_price = if original.is_eip1559() { original.max_fee_per_gas } else { original.gas_price }
+        .ok_or_else(|| WalletError::invalid_field("gas_price", "Original transaction has no gas price", "required"))?;
+    // Rounded up; `u128::div_ceil` is above the crate's MSRV
+    let minimum = (original_price as u128 * (100 + REPLACEMENT_MIN_BUMP_PERCENT) as u128 + 99) / 100;
+    if (new_gas_price as u128) < minimum {
+        return Err(WalletError::invalid_field(
+            "gas_price",
+            format!(
+                "Replacement gas price {} must be at least {}, {}% above the original {}",
+                new_gas_price, minimum, REPLACEMENT_MIN_BUMP_PERCENT, original_price
+            ),
+            "replacement_underpriced",
+        ));
+    }
+
+    let mut replacement = original.clone();
+    if original.is_eip1559() {
+        let priority = original.max_priority_fee_per_gas.unwrap_or_default() as u128;
+        let bumped = ((priority * new_gas_price as u128 + original_price as u128 - 1) / original_price as u128) as u64;
+        replacement.max_fee_per_gas = Some(new_gas_price);
+        replacement.max_priority_fee_per_gas = Some(bumped.min(new_gas_price));
+    } else {
+        replacement.gas_price = Some(new_gas_price);
+    }
+    Ok(replacement)
+}
+
+/// How `get_gas_price` behaves when `eth_gasPrice` fails
+#[derive(Debug, Clone)]
+pub struct GasPriceFallback {
+    /// Fallback price in wei per network; networks without an entry use `Network::fallback_gas_price`
+    pub per_network: HashMap<Network, u64>,
+    /// A price fetched within this window is preferred over the configured fallback
+    pub max_cache_age: Duration,
+    /// Return the RPC error instead of falling back
+    pub strict: bool,
+}
+
+impl Default for GasPriceFallback {
+    fn default() -> Self {
+        Self {
+            per_network: HashMap::new(),
+            max_cache_age: Duration::from_secs(600),
+            strict: false,
+        }
+    }
+}
+
+impl GasPriceFallback {
+    /// Read `WALLET_CORE_GAS_PRICE_FALLBACK_<NETWORK>` (wei) and `WALLET_CORE_GAS_PRICE_STRICT`
+    pub fn from_env() -> Self {
+        let networks = [
+            (Network::CoreTestnet, "WALLET_CORE_GAS_PRICE_FALLBACK_CORE_TESTNET"),
+            (Network::BaseSepolia, "WALLET_CORE_GAS_PRICE_FALLBACK_BASE_SEPOLIA"),
+            (Network::LiskSepolia, "WALLET_CORE_GAS_PRICE_FALLBACK_LISK_SEPOLIA"),
+            (Network::EthereumHolesky, "WALLET_CORE_GAS_PRICE_FALLBACK_HOLESKY"),
+        ];
+        let per_network = networks.into_iter()
+            .filter_map(|(network, var)| std::env::var(var).ok()?.parse().ok().map(|price| (network, price)))
+            .collect();
+        Self {
+            per_network,
+            strict: std::env::var("WALLET_CORE_GAS_PRICE_STRICT").map(|v| v == "true").unwrap_or(false),
+            ..Self::default()
+        }
+    }
+
+    pub fn price_for(&self, network: &Network) -> u64 {
+        self.per_network.get(network).copied().unwrap_or_else(|| network.fallback_gas_price())
+    }
+}
+
+/// Kind of transaction, used to pick a default gas limit when estimation is unavailable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GasOperation {
+    NativeTransfer,
+    TokenTransfer,
+    ContractCall,
+}
+
+impl GasOperation {
+    const ALL: [GasOperation; 3] = [GasOperation::NativeTransfer, GasOperation::TokenTransfer, GasOperation::ContractCall];
+
+    /// Classify by calldata: none is a native transfer, an ERC-20 `transfer` is a token transfer
+    pub fn of(transaction: &Transaction) -> Self {
+        match transaction.data.as_deref() {
+            None | Some([]) => GasOperation::NativeTransfer,
+            Some(data) if data.starts_with(&ERC20_TRANSFER_SELECTOR) => GasOperation::TokenTransfer,
+            Some(_) => GasOperation::ContractCall,
+        }
+    }
+
+    /// Built-in gas limit for this operation on `network`
+    pub fn default_limit(&self, network: &Network) -> u64 {
+        match (self, network) {
+            (GasOperation::NativeTransfer, _) => 21_000,
+            (GasOperation::TokenTransfer, Network::Custom { .. }) => 100_000,
+            (GasOperation::TokenTransfer, _) => 65_000,
+            (GasOperation::ContractCall, Network::Custom { .. }) => 300_000,
+            (GasOperation::ContractCall, _) => 200_000,
+        }
+    }
+
+    fn env_suffix(&self) -> &'static str {
+        match self {
+            GasOperation::NativeTransfer => "NATIVE_TRANSFER",
+            GasOperation::TokenTransfer => "TOKEN_TRANSFER",
+            GasOperation::ContractCall => "CONTRACT_CALL",
+        }
+    }
+}
+
+/// Gas limits used when `eth_estimateGas` fails or is skipped in offline mode
+#[derive(Debug, Clone, Default)]
+pub struct GasLimitFallback {
+    /// Limit per network and operation; missing entries use `GasOperation::default_limit`
+    pub per_network: HashMap<(Network, GasOperation), u64>,
+    /// Return the estimation error instead of falling back
+    pub strict: bool,
+}
+
+impl GasLimitFallback {
+    /// Read `WALLET_CORE_GAS_LIMIT_<NETWORK>_<OPERATION>` and `WALLET_CORE_GAS_LIMIT_STRICT`,
+    /// e.g. `WALLET_CORE_GAS_LIMIT_BASE_SEPOLIA_TOKEN_TRANSFER=70000`
+    pub fn from_env() -> Self {
+        let networks = [
+            (Network::CoreTestnet, "CORE_TESTNET"),
+            (Network::BaseSepolia, "BASE_SEPOLIA"),
+            (Network::LiskSepolia, "LISK_SEPOLIA"),
+            (Network::EthereumHolesky, "HOLESKY"),
+        ];
+        let per_network = networks.iter()
+            .flat_map(|(network, name)| GasOperation::ALL.into_iter().map(move |operation| (network, name, operation)))
+            .filter_map(|(network, name, operation)| {
+                let var = format!("WALLET_CORE_GAS_LIMIT_{}_{}", name, operation.env_suffix());
+                let limit = std::env::var(var).ok()?.parse().ok()?;
+                Some(((network.clone(), operation), limit))
+            })
+            .collect();
+        Self {
+            per_network,
+            strict: std::env::var("WALLET_CORE_GAS_LIMIT_STRICT").map(|v| v == "true").unwrap_or(false),
+        }
+    }
+
+    pub fn limit_for(&self, network: &Network, operation: GasOperation) -> u64 {
+        self.per_network.get(&(network.clone(), operation)).copied()
+            .unwrap_or_else(|| operation.default_limit(network))
+    }
+}
+
+/// Safety multiplier applied to gas estimates unless `WALLET_CORE_GAS_LIMIT_MULTIPLIER` is set
+pub const DEFAULT_GAS_LIMIT_MULTIPLIER: f64 = 1.2;
+
+/// Result of `eth_estimateGas` for a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasEstimate {
+    /// Gas the node expects the transaction to use
+    pub estimated: u64,
+    /// `estimated` scaled by the safety multiplier and rounded up; use this as the gas limit
+    pub suggested: u64,
+}
+
+impl GasEstimate {
+    fn with_multiplier(estimated: u64, multiplier: f64) -> Self {
+        // Scale in basis points so e.g. 21000 * 1.2 is exactly 25200
+        let bps = (multiplier * 10_000.0).round() as u128;
+        let suggested = (estimated as u128 * bps + 9_999) / 10_000;
+        Self { estimated, suggested: u64::try_from(suggested).unwrap_or(u64::MAX) }
+    }
+}
+
+fn gas_limit_multiplier_from_env() -> f64 {
+    std::env::var("WALLET_CORE_GAS_LIMIT_MULTIPLIER").ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|m| m.is_finite() && *m >= 1.0)
+        .unwrap_or(DEFAULT_GAS_LIMIT_MULTIPLIER)
+}
+
+/// What to do when native value is sent without calldata to an address holding contract
+/// code, which may not be able to receive it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContractRecipientPolicy {
+    /// Skip the `eth_getCode` lookup
+    Allow,
+    /// Log and report a warning, but let the transaction through
+    #[default]
+    Warn,
+    /// Reject the transaction
+    Block,
+}
+
+impl ContractRecipientPolicy {
+    /// Read `WALLET_CORE_CONTRACT_RECIPIENT_POLICY` (`allow`, `warn` or `block`)
+    pub fn from_env() -> Self {
+        match std::env::var("WALLET_CORE_CONTRACT_RECIPIENT_POLICY").map(|v| v.to_ascii_lowercase()).as_deref() {
+            Ok("allow") => Self::Allow,
+            Ok("block") => Self::Block,
+            _ => Self::Warn,
+        }
+    }
+}
+
+/// Last gas price fetched per chain, shared by transaction managers so a recent
+/// value can stand in when the RPC is briefly unavailable
+#[derive(Debug, Clone, Default)]
+pub struct GasPriceCache {
+    prices: Arc<Mutex<HashMap<u64, (u64, Instant)>>>,
+}
+
+impl GasPriceCache {
+    /// Process-wide cache used by `TransactionManager::new`
+    pub fn global() -> Self {
+        static GLOBAL: OnceLock<GasPriceCache> = OnceLock::new();
+        GLOBAL.get_or_init(GasPriceCache::default).clone()
+    }
+
+    fn store(&self, chain_id: u64, price: u64) {
+        if let Ok(mut prices) = self.prices.lock() {
+            prices.insert(chain_id, (price, Instant::now()));
+        }
+    }
+
+    fn recent(&self, chain_id: u64, max_age: Duration) -> Option<u64> {
+        let prices = self.prices.lock().ok()?;
+        let (price, fetched_at) = prices.get(&chain_id)?;
+        (fetched_at.elapsed() <= max_age).then_some(*price)
+    }
+}
 
 /// Transaction manager for handling blockchain transactions
 pub struct TransactionManager {
     signature_manager: SignatureManager,
-    rpc_url: String,
+    rpc: RpcClient,
+    gas_price_fallback: GasPriceFallback,
+    gas_price_cache: GasPriceCache,
+    gas_limit_fallback: GasLimitFallback,
+    gas_limit_multiplier: f64,
+    contract_recipient_policy: ContractRecipientPolicy,
+    /// Refuse every RPC call; building and signing transactions keeps working
+    offline: bool,
 }
 
 impl TransactionManager {
     pub fn new(rpc_url: String) -> Self {
         Self {
             signature_manager: SignatureManager::new(),
-            rpc_url,
+            rpc: RpcClient::new(rpc_url),
+            gas_price_fallback: GasPriceFallback::from_env(),
+            gas_price_cache: GasPriceCache::global(),
+            gas_limit_fallback: GasLimitFallback::from_env(),
+            gas_limit_multiplier: gas_limit_multiplier_from_env(),
+            contract_recipient_policy: ContractRecipientPolicy::from_env(),
+            offline: false,
+        }
+    }
+
+    pub fn with_offline_mode(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    async fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, WalletError> {
+        if self.offline {
+            return Err(WalletError::offline(format!("{} RPC call", method)));
+        }
+        self.rpc.call(method, params).await
+    }
+
+    /// Replace the JSON-RPC client, e.g. to inject a retry clock
+    pub fn with_rpc_client(mut self, rpc: RpcClient) -> Self {
+        self.rpc = rpc;
+        self
+    }
+
+    pub fn with_gas_price_fallback(mut self, fallback: GasPriceFallback) -> Self {
+        self.gas_price_fallback = fallback;
+        self
+    }
+
+    pub fn with_gas_price_cache(mut self, cache: GasPriceCache) -> Self {
+        self.gas_price_cache = cache;
+        self
+    }
+
+    pub fn with_gas_limit_fallback(mut self, fallback: GasLimitFallback) -> Self {
+        self.gas_limit_fallback = fallback;
+        self
+    }
+
+    /// Safety multiplier for `GasEstimate::suggested`; values below 1.0 are raised to 1.0
+    pub fn with_gas_limit_multiplier(mut self, multiplier: f64) -> Self {
+        self.gas_limit_multiplier = if multiplier.is_finite() { multiplier.max(1.0) } else { DEFAULT_GAS_LIMIT_MULTIPLIER };
+        self
+    }
+
+    pub fn with_contract_recipient_policy(mut self, policy: ContractRecipientPolicy) -> Self {
+        self.contract_recipient_policy = policy;
+        self
+    }
+
+    /// Whether `address` has contract code on the latest block
+    pub async fn is_contract(&self, address: &str) -> Result<bool, WalletError> {
+        let resp_json = self.call("eth_getCode", json!([address, "latest"])).await?;
+        let code = resp_json.get("result")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| WalletError::network(format!(
+                "Missing code result: {}",
+                resp_json.get("error").cloned().unwrap_or_default()
+            )))?;
+        Ok(!code.trim_start_matches("0x").is_empty())
+    }
+
+    /// Apply the contract recipient policy to a plain value transfer (value > 0, no calldata).
+    /// Returns the warning when the recipient is a contract and the policy is `Warn`; with
+    /// `Block` that case is an error. Offline, or when the lookup fails, the transaction is
+    /// let through since the recipient cannot be checked.
+    pub async fn check_contract_recipient(&self, transaction: &Transaction) -> Result<Option<String>, WalletError> {
+        let is_plain_transfer = transaction.data.as_ref().map_or(true, |data| data.is_empty())
+            && U256::from_dec_str(&transaction.value).is_ok_and(|value| !value.is_zero());
+        if self.contract_recipient_policy == ContractRecipientPolicy::Allow || !is_plain_transfer || self.offline {
+            return Ok(None);
+        }
+        match self.is_contract(&transaction.to).await {
+            Ok(false) => Ok(None),
+            Ok(true) => {
+                let warning = format!("Recipient {} is a contract and may not be able to receive native funds", transaction.to);
+                if self.contract_recipient_policy == ContractRecipientPolicy::Block {
+                    return Err(WalletError::invalid_field("to", warning, "contract_recipient"));
+                }
+                log::warn!("{}", warning);
+                Ok(Some(warning))
+            }
+            Err(e) => {
+                log::warn!("Could not check whether {} is a contract: {}", transaction.to, e);
+                Ok(None)
+            }
         }
     }
 
@@ -27,6 +453,8 @@ impl TransactionManager {
         Ok(())
     }
 
+    /// Build a plain value transfer without any RPC. `WalletManager` applies the contract
+    /// recipient policy before sending; `check_contract_recipient` returns its warning up front.
     pub async fn create_transaction(
         &self,
         to: String,
@@ -34,10 +462,16 @@ impl TransactionManager {
         network: Network,
     ) -> Result<Transaction, WalletError> {
         if to.is_empty() {
-            return Err(WalletError::validation("Recipient address cannot be empty"));
+            return Err(WalletError::invalid_field("to", "Recipient address cannot be empty", "required"));
+        }
+        network.validate_address(&to)
+            .map_err(|e| WalletError::invalid_field("to", e.to_string(), "invalid_address"))?;
+        // A mistyped character in a checksummed address would otherwise send funds nowhere
+        if network.address_format() == AddressFormat::Evm && !is_valid_ethereum_address(&to) {
+            return Err(WalletError::invalid_field("to", "Address checksum is invalid", "invalid_checksum"));
         }
         if value.is_empty() {
-            return Err(WalletError::validation("Transaction value cannot be empty"));
+            return Err(WalletError::invalid_field("value", "Transaction value cannot be empty", "required"));
         }
         Ok(Transaction {
             to,
@@ -47,9 +481,110 @@ impl TransactionManager {
             gas_price: None,
             nonce: None,
             chain_id: network.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        })
+    }
+
+    /// Like `create_transaction`, with the value given in ether (`"0.1"`) instead of wei.
+    /// The decimal string is converted exactly; more than 18 decimal places is an error.
+    pub async fn create_transaction_ether(
+        &self,
+        to: String,
+        ether_amount: &str,
+        network: Network,
+    ) -> Result<Transaction, WalletError> {
+        let wei = parse_decimal_amount("value", ether_amount.trim(), ETHER_DECIMALS)?;
+        self.create_transaction(to, wei.to_string(), network).await
+    }
+
+    /// Build an ERC-20 `transfer(to, amount)` call against `token`.
+    ///
+    /// `amount` is human-readable (`"12.5"`) and is scaled by the token's decimals;
+    /// the transaction goes to the token contract and carries no native value.
+    pub fn create_token_transfer(
+        &self,
+        token: &TokenInfo,
+        to: &str,
+        amount: &str,
+        network: Network,
+    ) -> Result<Transaction, WalletError> {
+        if token.is_native {
+            return Err(WalletError::invalid_field(
+                "token",
+                format!("{} is the native currency; use create_transaction", token.symbol),
+                "native_token",
+            ));
+        }
+        validate_ethereum_address(&token.address)
+            .map_err(|e| WalletError::invalid_field("token", e.to_string(), "invalid_address"))?;
+        network.validate_address(to)
+            .map_err(|e| WalletError::invalid_field("to", e.to_string(), "invalid_address"))?;
+        let amount = parse_decimal_amount("amount", amount, token.decimals)?;
+        if amount.is_zero() {
+            return Err(WalletError::invalid_field("amount", "Amount must be greater than zero", "invalid_amount"));
+        }
+
+        Ok(Transaction {
+            to: token.address.clone(),
+            value: "0".to_string(),
+            data: Some(encode_address_amount_call(ERC20_TRANSFER_SELECTOR, to, amount)?),
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+            chain_id: network.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        })
+    }
+
+    /// Build an ERC-20 `approve(spender, amount)` call against `token`
+    pub fn create_approval(
+        &self,
+        token: &str,
+        spender: &str,
+        amount: Amount,
+        network: Network,
+    ) -> Result<Transaction, WalletError> {
+        validate_ethereum_address(token)
+            .map_err(|e| WalletError::invalid_field("token", e.to_string(), "invalid_address"))?;
+        validate_ethereum_address(spender)
+            .map_err(|e| WalletError::invalid_field("spender", e.to_string(), "invalid_address"))?;
+        let amount = U256::from_dec_str(&amount)
+            .map_err(|_| WalletError::invalid_field("amount", "Approval amount must be a decimal integer", "invalid_amount"))?;
+
+        let data = encode_address_amount_call(ERC20_APPROVE_SELECTOR, spender, amount)?;
+
+        Ok(Transaction {
+            to: token.to_string(),
+            value: "0".to_string(),
+            data: Some(data),
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+            chain_id: network.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
         })
     }
 
+    /// Build an exact-amount approval followed by a zero-amount reset.
+    ///
+    /// Approving only what the next interaction spends and revoking afterwards
+    /// avoids leaving a standing allowance that a spender could front-run when
+    /// the allowance is later changed.
+    pub fn create_exact_approval_with_reset(
+        &self,
+        token: &str,
+        spender: &str,
+        amount: Amount,
+        network: Network,
+    ) -> Result<(Transaction, Transaction), WalletError> {
+        let approve = self.create_approval(token, spender, amount, network.clone())?;
+        let reset = self.create_approval(token, spender, "0".to_string(), network)?;
+        Ok((approve, reset))
+    }
+
     pub async fn sign_transaction(
         &self,
         transaction: &Transaction,
@@ -59,43 +594,47 @@ impl TransactionManager {
         if private_key_id.is_empty() {
             return Err(WalletError::crypto("Private key ID cannot be empty"));
         }
-
-        // Require nonce, gas price, gas limit for raw signing
-        if transaction.nonce.is_none() || transaction.gas_price.is_none() || transaction.gas_limit.is_none() {
-            return Err(WalletError::validation("Transaction requires nonce, gas_price, and gas_limit"));
-        }
-
         // Create a SecurePrivateKey reference (does not load key into memory)
         let private_key = crate::core::crypto::keys::SecurePrivateKey::new(private_key_id.to_string());
+        self.sign_transaction_with_key(transaction, &private_key, storage).await
+    }
 
-        // Perform EIP-155 legacy signing and get raw tx bytes and hash
+    /// Sign with an already resolved key reference, e.g. one carrying a biometric authenticator
+    pub async fn sign_transaction_with_key(
+        &self,
+        transaction: &Transaction,
+        private_key: &crate::core::crypto::keys::SecurePrivateKey,
+        storage: &dyn crate::infrastructure::platform::PlatformStorage,
+    ) -> Result<SignedTransaction, WalletError> {
+        // Both fee-cap fields select EIP-1559; otherwise fall back to legacy gas_price pricing
+        let eip1559 = transaction.is_eip1559();
+        if transaction.max_fee_per_gas.is_some() != transaction.max_priority_fee_per_gas.is_some() {
+            return Err(WalletError::validation("EIP-1559 transactions require both max_fee_per_gas and max_priority_fee_per_gas"));
+        }
+        if transaction.nonce.is_none() || transaction.gas_limit.is_none() || (!eip1559 && transaction.gas_price.is_none()) {
+            return Err(WalletError::validation("Transaction requires nonce, gas_limit, and gas_price or EIP-1559 fee caps"));
+        }
+
+        // Sign as a type-2 or EIP-155 legacy transaction and get raw tx bytes and hash
         let (raw_tx, tx_hash) = private_key.with_key(storage, |key_bytes| {
-            self.signature_manager.sign_legacy_raw(transaction, key_bytes)
+            if eip1559 {
+                self.signature_manager.sign_1559_raw(transaction, key_bytes)
+            } else {
+                self.signature_manager.sign_legacy_raw(transaction, key_bytes)
+            }
         })?;
 
         Ok(SignedTransaction {
             transaction: transaction.clone(),
             signature: raw_tx, // signature now carries raw RLP bytes
             hash: tx_hash,
+            warnings: Vec::new(),
         })
     }
 
     pub async fn send_transaction(&self, signed_transaction: &SignedTransaction) -> Result<TransactionHash, WalletError> {
-        let client = Client::new();
         let tx_hex = format!("0x{}", hex::encode(&signed_transaction.signature));
-        let params = json!([tx_hex]);
-        let body = json!({
-            "jsonrpc": "2.0",
-            "method": "eth_sendRawTransaction",
-            "params": params,
-            "id": 1
-        });
-        let resp = client.post(&self.rpc_url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| WalletError::network(format!("Failed to send transaction: {}", e)))?;
-        let resp_json: serde_json::Value = resp.json().await.map_err(|e| WalletError::network(format!("Invalid response: {}", e)))?;
+        let resp_json = self.call("eth_sendRawTransaction", json!([tx_hex])).await?;
         if let Some(result) = resp_json.get("result") {
             Ok(result.as_str().unwrap_or_default().to_string())
         } else {
@@ -110,71 +649,236 @@ impl TransactionManager {
         if transaction_hash.is_empty() {
             return Err(WalletError::validation("Transaction hash cannot be empty"));
         }
-        let client = Client::new();
-        let params = json!([transaction_hash]);
-        let body = json!({
-            "jsonrpc": "2.0",
-            "method": "eth_getTransactionReceipt",
-            "params": params,
-            "id": 1
-        });
-        let resp = client.post(&self.rpc_url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| WalletError::network(format!("Failed to get transaction status: {}", e)))?;
-        let resp_json: serde_json::Value = resp.json().await.map_err(|e| WalletError::network(format!("Invalid response: {}", e)))?;
-        if resp_json.get("result").is_some() {
-            Ok(TransactionStatus::Confirmed)
-        } else {
-            Ok(TransactionStatus::Pending)
+        match self.fetch_receipt(transaction_hash).await? {
+            None => Ok(TransactionStatus::Pending),
+            Some((_, true)) => Ok(TransactionStatus::Confirmed),
+            Some((_, false)) => Ok(TransactionStatus::Failed),
+        }
+    }
+
+    /// Poll until the transaction reverts or has `confirmations` blocks on top of (and
+    /// including) its own, checking every `poll_interval`. When `timeout` elapses first the
+    /// last observed status (`Pending` or `Mined`) is returned.
+    pub async fn wait_for_confirmation(
+        &self,
+        transaction_hash: &TransactionHash,
+        confirmations: u64,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<ConfirmationStatus, WalletError> {
+        if transaction_hash.is_empty() {
+            return Err(WalletError::validation("Transaction hash cannot be empty"));
+        }
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = match self.fetch_receipt(transaction_hash).await? {
+                None => ConfirmationStatus::Pending,
+                Some((block_number, false)) => return Ok(ConfirmationStatus::Reverted { block_number }),
+                Some((block_number, true)) => {
+                    let latest = self.get_block_number().await?;
+                    let mined = latest.saturating_sub(block_number) + 1;
+                    if mined >= confirmations {
+                        return Ok(ConfirmationStatus::Confirmed { block_number, confirmations: mined });
+                    }
+                    ConfirmationStatus::Mined { block_number, confirmations: mined }
+                }
+            };
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(status);
+            }
+            tokio::time::sleep(poll_interval.min(deadline - now)).await;
+        }
+    }
+
+    /// Block number and success flag of the transaction's receipt, `None` while it is unmined
+    async fn fetch_receipt(&self, transaction_hash: &TransactionHash) -> Result<Option<(u64, bool)>, WalletError> {
+        let resp_json = self.call("eth_getTransactionReceipt", json!([transaction_hash])).await?;
+        let receipt = match resp_json.get("result") {
+            Some(receipt) if !receipt.is_null() => receipt,
+            _ => return Ok(None),
+        };
+        let quantity = |field: &str| {
+            receipt.get(field)
+                .and_then(|v| v.as_str())
+                .and_then(|v| u64::from_str_radix(v.trim_start_matches("0x"), 16).ok())
+        };
+        let block_number = quantity("blockNumber")
+            .ok_or_else(|| WalletError::network("Receipt is missing blockNumber".to_string()))?;
+        // Pre-Byzantium receipts carry no status; treat them as successful
+        let success = quantity("status").map(|status| status == 1).unwrap_or(true);
+        Ok(Some((block_number, success)))
+    }
+
+    async fn get_block_number(&self) -> Result<u64, WalletError> {
+        let resp_json = self.call("eth_blockNumber", json!([])).await?;
+        let result = resp_json.get("result")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| WalletError::network("Missing block number result".to_string()))?;
+        u64::from_str_radix(result.trim_start_matches("0x"), 16)
+            .map_err(|_| WalletError::network("Invalid block number".to_string()))
+    }
+
+    /// Next nonce for `address`, counting transactions still pending in the mempool
+    pub async fn get_transaction_count(&self, address: &str) -> Result<u64, WalletError> {
+        let resp_json = self.call("eth_getTransactionCount", json!([address, "pending"])).await?;
+        let result = resp_json.get("result")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| WalletError::network(format!(
+                "Missing transaction count result: {}",
+                resp_json.get("error").cloned().unwrap_or_default()
+            )))?;
+        u64::from_str_radix(result.trim_start_matches("0x"), 16)
+            .map_err(|_| WalletError::network("Invalid transaction count".to_string()))
+    }
+
+    /// Number of transactions from `address` already mined; a pending transaction with a
+    /// lower nonce no longer exists and cannot be replaced
+    pub async fn get_mined_transaction_count(&self, address: &str) -> Result<u64, WalletError> {
+        let resp_json = self.call("eth_getTransactionCount", json!([address, "latest"])).await?;
+        let result = resp_json.get("result")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| WalletError::network(format!(
+                "Missing transaction count result: {}",
+                resp_json.get("error").cloned().unwrap_or_default()
+            )))?;
+        u64::from_str_radix(result.trim_start_matches("0x"), 16)
+            .map_err(|_| WalletError::network("Invalid transaction count".to_string()))
+    }
+
+    /// Return a copy of `transaction` with a missing nonce, gas limit and (for legacy
+    /// transactions) gas price fetched from the RPC. Fields already set are kept. A failed
+    /// gas estimate falls back to the configured default limit; if any other lookup fails
+    /// the error is returned and no partially filled transaction escapes.
+    pub async fn fill_transaction(
+        &self,
+        transaction: &Transaction,
+        from: &str,
+        network: Network,
+    ) -> Result<Transaction, WalletError> {
+        let mut filled = transaction.clone();
+        if filled.nonce.is_none() {
+            filled.nonce = Some(self.get_transaction_count(from).await?);
+        }
+        if filled.gas_limit.is_none() {
+            filled.gas_limit = Some(self.estimate_transaction_gas_or_default(transaction, &network).await?);
         }
+        if filled.gas_price.is_none() && !filled.is_eip1559() {
+            filled.gas_price = Some(self.get_gas_price(network).await?);
+        }
+        Ok(filled)
     }
 
+    /// Estimate gas for a plain value transfer; see `estimate_gas_for`
     pub async fn estimate_gas(&self, to_address: &str, amount: u64) -> Result<u64, WalletError> {
-        let client = Client::new();
-        let params = json!([{ "to": to_address, "value": format!("0x{:x}", amount) }]);
-        let body = json!({
-            "jsonrpc": "2.0",
-            "method": "eth_estimateGas",
-            "params": params,
-            "id": 1
-        });
-        let resp = client.post(&self.rpc_url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| WalletError::network(format!("Failed to estimate gas: {}", e)))?;
-        let resp_json: serde_json::Value = resp.json().await.map_err(|e| WalletError::network(format!("Invalid response: {}", e)))?;
-        if let Some(result) = resp_json.get("result") {
-            u64::from_str_radix(result.as_str().unwrap_or("0x5208").trim_start_matches("0x"), 16)
-                .map_err(|_| WalletError::network("Invalid gas estimate".to_string()))
-        } else {
-            Ok(21000)
+        let transaction = Transaction {
+            to: to_address.to_string(),
+            value: amount.to_string(),
+            data: None,
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+            chain_id: 0,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        Ok(self.estimate_gas_for(&transaction, None).await?.estimated)
+    }
+
+    /// Estimate gas for a full transaction, including value and calldata
+    pub async fn estimate_transaction_gas(&self, transaction: &Transaction) -> Result<u64, WalletError> {
+        Ok(self.estimate_gas_for(transaction, None).await?.estimated)
+    }
+
+    /// Estimate gas for `transaction` sent from `from`, passing its recipient, value and
+    /// calldata to `eth_estimateGas`. The suggested limit applies the safety multiplier.
+    pub async fn estimate_gas_for(&self, transaction: &Transaction, from: Option<&str>) -> Result<GasEstimate, WalletError> {
+        let call = call_object(transaction, from)?;
+        let resp_json = self.call("eth_estimateGas", json!([call])).await?;
+        if let Some(error) = resp_json.get("error") {
+            if is_revert_error(error) {
+                return Err(WalletError::transaction(format!("Transaction would revert: {}", revert_reason(error))));
+            }
+            return Err(WalletError::network(format!("eth_estimateGas failed: {}", error)));
         }
+        let result = resp_json.get("result")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| WalletError::network("Missing gas estimate result".to_string()))?;
+        let estimated = u64::from_str_radix(result.trim_start_matches("0x"), 16)
+            .map_err(|_| WalletError::network("Invalid gas estimate".to_string()))?;
+        Ok(GasEstimate::with_multiplier(estimated, self.gas_limit_multiplier))
     }
 
-    pub async fn get_gas_price(&self, _network: Network) -> Result<u64, WalletError> {
-        let client = Client::new();
-        let body = json!({
-            "jsonrpc": "2.0",
-            "method": "eth_gasPrice",
-            "params": [],
-            "id": 1
-        });
-        let resp = client.post(&self.rpc_url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| WalletError::network(format!("Failed to get gas price: {}", e)))?;
-        let resp_json: serde_json::Value = resp.json().await.map_err(|e| WalletError::network(format!("Invalid response: {}", e)))?;
-        if let Some(result) = resp_json.get("result") {
-            u64::from_str_radix(result.as_str().unwrap_or("0x4a817c800").trim_start_matches("0x"), 16)
-                .map_err(|_| WalletError::network("Invalid gas price".to_string()))
-        } else {
-            Ok(20000000000)
+    /// Dry-run `transaction` from `from` with `eth_call` against the latest block. Returns the
+    /// call's return data, or a `WalletError::Transaction` with the decoded revert reason if it
+    /// would revert. Nothing is signed or broadcast.
+    pub async fn simulate_transaction(&self, transaction: &Transaction, from: &str) -> Result<Vec<u8>, WalletError> {
+        let call = call_object(transaction, Some(from))?;
+        let resp_json = self.call("eth_call", json!([call, "latest"])).await?;
+        if let Some(error) = resp_json.get("error") {
+            return Err(WalletError::transaction(format!("Transaction would revert: {}", revert_reason(error))));
+        }
+        let result = resp_json.get("result")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| WalletError::network("Missing eth_call result".to_string()))?;
+        hex::decode(result.trim_start_matches("0x"))
+            .map_err(|_| WalletError::network("Invalid eth_call result".to_string()))
+    }
+
+    /// Gas limit for `transaction` on `network`: the RPC estimate when available, otherwise
+    /// the configured default for its operation type. Only network failures fall back; a
+    /// transaction that would revert is an error, as is any failure in strict mode.
+    pub async fn estimate_transaction_gas_or_default(&self, transaction: &Transaction, network: &Network) -> Result<u64, WalletError> {
+        let operation = GasOperation::of(transaction);
+        if self.offline {
+            return Ok(self.gas_limit_fallback.limit_for(network, operation));
+        }
+        match self.estimate_transaction_gas(transaction).await {
+            Ok(limit) => Ok(limit),
+            Err(e) if self.gas_limit_fallback.strict || !matches!(e, WalletError::Network(_)) => Err(e),
+            Err(e) => {
+                let limit = self.gas_limit_fallback.limit_for(network, operation);
+                log::warn!("eth_estimateGas failed on {} ({}); using default {:?} limit {}", network.name(), e, operation, limit);
+                Ok(limit)
+            }
+        }
+    }
+
+    /// Current gas price for `network`. When `eth_gasPrice` fails, a recently fetched price
+    /// is used, then the configured per-network fallback; in strict mode the error is returned.
+    pub async fn get_gas_price(&self, network: Network) -> Result<u64, WalletError> {
+        // The fallback price must not hide that no RPC is allowed
+        if self.offline {
+            return Err(WalletError::offline("eth_gasPrice RPC call"));
+        }
+        let chain_id = network.chain_id();
+        match self.fetch_gas_price().await {
+            Ok(price) => {
+                self.gas_price_cache.store(chain_id, price);
+                Ok(price)
+            }
+            Err(e) if self.gas_price_fallback.strict => Err(e),
+            Err(e) => {
+                if let Some(price) = self.gas_price_cache.recent(chain_id, self.gas_price_fallback.max_cache_age) {
+                    log::warn!("eth_gasPrice failed on {} ({}); using recently fetched {} wei", network.name(), e, price);
+                    return Ok(price);
+                }
+                let price = self.gas_price_fallback.price_for(&network);
+                log::warn!("eth_gasPrice failed on {} ({}); using fallback {} wei", network.name(), e, price);
+                Ok(price)
+            }
         }
     }
+
+    async fn fetch_gas_price(&self) -> Result<u64, WalletError> {
+        let resp_json = self.call("eth_gasPrice", json!([])).await?;
+        let result = resp_json.get("result")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| WalletError::network("Missing gas price result".to_string()))?;
+        u64::from_str_radix(result.trim_start_matches("0x"), 16)
+            .map_err(|_| WalletError::network("Invalid gas price".to_string()))
+    }
 }
 
 /// Initialize transactions
@@ -192,6 +896,7 @@ pub async fn cleanup() -> Result<(), WalletError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared::mock_http::{unreachable_url, MockResponse, MockServer};
 
     #[tokio::test]
     async fn test_transactions_init() {
@@ -213,15 +918,574 @@ mod tests {
 
         let transaction = manager
             .create_transaction(
-            "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
+            "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
                 "1000000000000000000".to_string(),
                 Network::CoreTestnet,
             )
             .await
             .expect("Failed to create transaction");
 
-        assert_eq!(transaction.to, "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6");
+        assert_eq!(transaction.to, "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6");
         assert_eq!(transaction.value, "1000000000000000000");
         assert_eq!(transaction.chain_id, 1114);
     }
-} 
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_create_transaction_empty_recipient() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        let err = manager
+            .create_transaction(String::new(), "1".to_string(), Network::CoreTestnet)
+            .await
+            .expect_err("empty recipient should be rejected");
+
+        let details = err.validation_details().expect("expected validation error");
+        assert_eq!(details.field, "to");
+        assert_eq!(details.code, "required");
+    }
+
+    #[tokio::test]
+    async fn test_create_transaction_empty_value() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        let err = manager
+            .create_transaction(
+                "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
+                String::new(),
+                Network::CoreTestnet,
+            )
+            .await
+            .expect_err("empty value should be rejected");
+
+        let details = err.validation_details().expect("expected validation error");
+        assert_eq!(details.field, "value");
+        assert!(err.to_string().contains("Transaction value cannot be empty"));
+    }
+
+    #[tokio::test]
+    async fn test_create_transaction_rejects_wrong_length_evm_address() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        for network in [Network::CoreTestnet, Network::EthereumHolesky] {
+            let err = manager
+                .create_transaction(
+                    "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B600".to_string(),
+                    "1".to_string(),
+                    network,
+                )
+                .await
+                .expect_err("a 21-byte address should be rejected");
+
+            let details = err.validation_details().expect("expected validation error");
+            assert_eq!(details.field, "to");
+            assert_eq!(details.code, "invalid_address");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_transaction_checks_eip55_checksum() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string())
+            .with_contract_recipient_policy(ContractRecipientPolicy::Allow);
+        for valid in [
+            "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6",
+            "0x742d35cc6634c0532925a3b8d4c9db96c4b4d8b6",
+            "0x742D35CC6634C0532925A3B8D4C9DB96C4B4D8B6",
+        ] {
+            let transaction = manager.create_transaction(valid.to_string(), "1".to_string(), Network::CoreTestnet).await.unwrap();
+            assert_eq!(transaction.to, valid);
+        }
+
+        // One letter's case flipped
+        let err = manager
+            .create_transaction("0x742d35Cc6634C0532925a3B8D4C9dB96C4B4d8B6".to_string(), "1".to_string(), Network::CoreTestnet)
+            .await
+            .expect_err("a corrupted checksum should be rejected");
+        let details = err.validation_details().expect("expected validation error");
+        assert_eq!(details.field, "to");
+        assert_eq!(details.code, "invalid_checksum");
+    }
+
+    #[tokio::test]
+    async fn test_sign_transaction_requires_both_1559_fee_fields() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        let storage = crate::infrastructure::platform::FileStorage::new().unwrap();
+        let mut tx = manager
+            .create_transaction("0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(), "1".to_string(), Network::BaseSepolia)
+            .await
+            .unwrap();
+        tx.nonce = Some(0);
+        tx.gas_limit = Some(21_000);
+        tx.max_fee_per_gas = Some(2_000_000_000);
+
+        let err = manager.sign_transaction(&tx, "unused_key", &storage).await
+            .expect_err("a lone max_fee_per_gas must not sign");
+        assert!(err.to_string().contains("max_priority_fee_per_gas"));
+    }
+
+    const TOKEN: &str = "0x1111111111111111111111111111111111111111";
+    const SPENDER: &str = "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6";
+
+    #[test]
+    fn test_create_approval_encoding() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        let tx = manager
+            .create_approval(TOKEN, SPENDER, "1000000".to_string(), Network::BaseSepolia)
+            .expect("Failed to create approval");
+
+        assert_eq!(tx.to, TOKEN);
+        assert_eq!(tx.value, "0");
+        assert_eq!(tx.chain_id, Network::BaseSepolia.chain_id());
+
+        let data = tx.data.expect("approval must carry calldata");
+        assert_eq!(data.len(), 68);
+        assert_eq!(hex::encode(&data[..4]), "095ea7b3");
+        assert_eq!(&data[4..16], &[0u8; 12]);
+        assert_eq!(hex::encode(&data[16..36]), SPENDER[2..].to_lowercase());
+        assert_eq!(U256::from_big_endian(&data[36..68]), U256::from(1_000_000u64));
+    }
+
+    #[tokio::test]
+    async fn test_create_transaction_ether_converts_exactly() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        let to = SPENDER.to_string();
+
+        let tx = manager.create_transaction_ether(to.clone(), "0.1", Network::CoreTestnet).await.unwrap();
+        assert_eq!(tx.value, "100000000000000000");
+        let tx = manager.create_transaction_ether(to.clone(), "1.000000000000000001", Network::CoreTestnet).await.unwrap();
+        assert_eq!(tx.value, "1000000000000000001");
+        let tx = manager.create_transaction_ether(to.clone(), "2", Network::CoreTestnet).await.unwrap();
+        assert_eq!(tx.value, "2000000000000000000");
+    }
+
+    #[tokio::test]
+    async fn test_create_transaction_ether_rejects_malformed_values() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        for amount in ["1.5.2", "-1", "-0.5", "1e18", "", "0.0000000000000000001"] {
+            let err = manager
+                .create_transaction_ether(SPENDER.to_string(), amount, Network::CoreTestnet)
+                .await
+                .expect_err(amount);
+            assert_eq!(err.validation_details().map(|d| d.field.as_str()), Some("value"), "{}", amount);
+        }
+    }
+
+    fn usdc() -> TokenInfo {
+        TokenInfo {
+            symbol: "USDC".to_string(),
+            name: "USD Coin".to_string(),
+            decimals: 6,
+            address: TOKEN.to_string(),
+            chain_id: Network::BaseSepolia.chain_id().to_string(),
+            is_native: false,
+            is_stablecoin: true,
+        }
+    }
+
+    #[test]
+    fn test_create_token_transfer_encoding() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        let tx = manager
+            .create_token_transfer(&usdc(), SPENDER, "12.5", Network::BaseSepolia)
+            .expect("Failed to create token transfer");
+
+        assert_eq!(tx.to, TOKEN);
+        assert_eq!(tx.value, "0");
+        let data = tx.data.expect("transfer must carry calldata");
+        let expected = format!(
+            "a9059cbb{:0>64}{:064x}",
+            SPENDER[2..].to_lowercase(),
+            12_500_000u64
+        );
+        assert_eq!(hex::encode(data), expected);
+    }
+
+    #[test]
+    fn test_create_token_transfer_rejects_bad_amounts() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        for amount in ["1.0000001", "abc", "", "0", "1.2.3"] {
+            let err = manager
+                .create_token_transfer(&usdc(), SPENDER, amount, Network::BaseSepolia)
+                .expect_err(amount);
+            assert!(err.to_string().contains("mount"), "{}: {}", amount, err);
+        }
+    }
+
+    #[test]
+    fn test_exact_approval_with_reset() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        let (approve, reset) = manager
+            .create_exact_approval_with_reset(TOKEN, SPENDER, "500".to_string(), Network::CoreTestnet)
+            .expect("Failed to build approval pair");
+
+        let approve_data = approve.data.expect("approval must carry calldata");
+        let reset_data = reset.data.expect("reset must carry calldata");
+        assert_eq!(U256::from_big_endian(&approve_data[36..]), U256::from(500u64));
+        assert_eq!(&reset_data[..36], &approve_data[..36]);
+        assert_eq!(&reset_data[36..], &[0u8; 32]);
+        assert_eq!(reset.to, TOKEN);
+    }
+
+    #[test]
+    fn test_create_approval_rejects_bad_spender() {
+        let manager = TransactionManager::new("http://localhost:8545".to_string());
+        let err = manager
+            .create_approval(TOKEN, "0x1234", "1".to_string(), Network::CoreTestnet)
+            .expect_err("short spender address should be rejected");
+        assert_eq!(err.validation_details().map(|d| d.field.as_str()), Some("spender"));
+    }
+
+    /// Serves `eth_gasPrice` with a fixed price
+    async fn spawn_gas_price_rpc(gas_price: u64) -> String {
+        MockServer::json_rpc(move |_, _| json!(format!("0x{:x}", gas_price))).await.url
+    }
+
+    /// Serves the lookups `fill_transaction` makes; `failing` answers with a JSON-RPC error
+    async fn spawn_fill_rpc(failing: Option<&'static str>) -> String {
+        spawn_method_rpc(
+            vec![
+                ("eth_getTransactionCount", json!("0x7")),
+                ("eth_estimateGas", json!("0x5208")),
+                ("eth_gasPrice", json!("0x3b9aca00")),
+            ],
+            failing,
+        ).await
+    }
+
+    /// JSON-RPC server answering each method with a fixed result; `failing` answers with an error
+    async fn spawn_method_rpc(results: Vec<(&'static str, serde_json::Value)>, failing: Option<&'static str>) -> String {
+        MockServer::start(move |request| {
+            let method = request.json()["method"].as_str().unwrap_or_default().to_string();
+            if Some(method.as_str()) == failing {
+                return MockResponse::rpc_error(request, json!({ "code": -32000, "message": "header not found" }));
+            }
+            let result = results.iter()
+                .find(|(name, _)| *name == method)
+                .map(|(_, result)| result.clone())
+                .unwrap_or_else(|| json!("0x0"));
+            // A configured `{ "error": ... }` is answered as a JSON-RPC error
+            match result.get("error") {
+                Some(error) => MockResponse::rpc_error(request, error.clone()),
+                None => MockResponse::rpc_result(request, result),
+            }
+        }).await.url
+    }
+
+    fn unfilled_transaction() -> Transaction {
+        Transaction {
+            to: SPENDER.to_string(),
+            value: "1000".to_string(),
+            data: None,
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+            chain_id: Network::CoreTestnet.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fill_transaction_populates_missing_fields() {
+        let manager = TransactionManager::new(spawn_fill_rpc(None).await)
+            .with_gas_price_cache(GasPriceCache::default());
+
+        let filled = manager.fill_transaction(&unfilled_transaction(), TOKEN, Network::CoreTestnet).await.unwrap();
+        assert_eq!(filled.nonce, Some(7));
+        assert_eq!(filled.gas_limit, Some(21_000));
+        assert_eq!(filled.gas_price, Some(1_000_000_000));
+
+        // Preset fields are kept; EIP-1559 transactions get no legacy gas price
+        let preset = Transaction {
+            nonce: Some(3),
+            max_fee_per_gas: Some(2_000_000_000),
+            max_priority_fee_per_gas: Some(1_000_000),
+            ..unfilled_transaction()
+        };
+        let filled = manager.fill_transaction(&preset, TOKEN, Network::CoreTestnet).await.unwrap();
+        assert_eq!(filled.nonce, Some(3));
+        assert_eq!(filled.gas_limit, Some(21_000));
+        assert_eq!(filled.gas_price, None);
+    }
+
+    #[tokio::test]
+    async fn test_fill_transaction_fails_on_rpc_error_mid_fill() {
+        // The nonce lookup succeeds, then gas estimation fails and strict mode forbids the default
+        let manager = TransactionManager::new(spawn_fill_rpc(Some("eth_estimateGas")).await)
+            .with_gas_price_cache(GasPriceCache::default())
+            .with_gas_limit_fallback(GasLimitFallback { strict: true, ..GasLimitFallback::default() });
+        assert!(manager.fill_transaction(&unfilled_transaction(), TOKEN, Network::CoreTestnet).await.is_err());
+
+        let manager = TransactionManager::new(spawn_fill_rpc(Some("eth_getTransactionCount")).await);
+        let err = manager.fill_transaction(&unfilled_transaction(), TOKEN, Network::CoreTestnet).await.unwrap_err();
+        assert!(err.to_string().contains("header not found"));
+    }
+
+    #[tokio::test]
+    async fn test_contract_recipient_warns_or_blocks() {
+        let contract = spawn_method_rpc(vec![("eth_getCode", json!("0x6080604052"))], None).await;
+        let transfer = unfilled_transaction();
+
+        let warn = TransactionManager::new(contract.clone()).with_contract_recipient_policy(ContractRecipientPolicy::Warn);
+        let warning = warn.check_contract_recipient(&transfer).await.unwrap();
+        assert!(warning.is_some_and(|w| w.contains(SPENDER)));
+        assert!(warn.create_transaction(SPENDER.to_string(), "1000".to_string(), Network::CoreTestnet).await.is_ok());
+
+        let block = TransactionManager::new(contract.clone()).with_contract_recipient_policy(ContractRecipientPolicy::Block);
+        let err = block.check_contract_recipient(&transfer).await.unwrap_err();
+        assert_eq!(err.validation_details().map(|d| d.code.as_str()), Some("contract_recipient"));
+        // Contract calls and zero-value transactions are not plain transfers
+        let call = Transaction { data: Some(vec![0xa9, 0x05, 0x9c, 0xbb]), ..unfilled_transaction() };
+        assert_eq!(block.check_contract_recipient(&call).await.unwrap(), None);
+        let empty = Transaction { value: "0".to_string(), ..unfilled_transaction() };
+        assert_eq!(block.check_contract_recipient(&empty).await.unwrap(), None);
+
+        let allow = TransactionManager::new(contract).with_contract_recipient_policy(ContractRecipientPolicy::Allow);
+        assert_eq!(allow.check_contract_recipient(&transfer).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_eoa_recipient_passes() {
+        let eoa = spawn_method_rpc(vec![("eth_getCode", json!("0x"))], None).await;
+        let manager = TransactionManager::new(eoa).with_contract_recipient_policy(ContractRecipientPolicy::Block);
+
+        assert!(!manager.is_contract(SPENDER).await.unwrap());
+        assert_eq!(manager.check_contract_recipient(&unfilled_transaction()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_transaction_decodes_revert_reason() {
+        let mut revert = REVERT_ERROR_SELECTOR.to_vec();
+        revert.extend(ethers::abi::encode(&[ethers::abi::Token::String("ERC20: transfer amount exceeds balance".to_string())]));
+        let error = json!({ "error": {
+            "code": 3,
+            "message": "execution reverted",
+            "data": format!("0x{}", hex::encode(&revert)),
+        }});
+        let url = spawn_method_rpc(vec![("eth_call", error)], None).await;
+
+        let err = TransactionManager::new(url).simulate_transaction(&unfilled_transaction(), TOKEN).await.unwrap_err();
+        assert!(matches!(err, WalletError::Transaction(_)));
+        assert_eq!(err.to_string(), "Transaction error: Transaction would revert: ERC20: transfer amount exceeds balance");
+    }
+
+    #[tokio::test]
+    async fn test_simulate_transaction_returns_call_data() {
+        let url = spawn_method_rpc(vec![("eth_call", json!("0x0000000000000000000000000000000000000000000000000000000000000001"))], None).await;
+
+        let output = TransactionManager::new(url).simulate_transaction(&unfilled_transaction(), TOKEN).await.unwrap();
+        assert_eq!(output.len(), 32);
+        assert_eq!(output[31], 1);
+    }
+
+    #[test]
+    fn test_replacement_transaction_requires_ten_percent_bump() {
+        let legacy = Transaction { gas_price: Some(1_000), nonce: Some(7), ..unfilled_transaction() };
+        let err = replacement_transaction(&legacy, 1_099).unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "replacement_underpriced");
+        let replacement = replacement_transaction(&legacy, 1_100).unwrap();
+        assert_eq!(replacement.gas_price, Some(1_100));
+        assert_eq!(replacement.nonce, Some(7));
+        assert_eq!(replacement.to, legacy.to);
+
+        let eip1559 = Transaction { max_fee_per_gas: Some(2_000), max_priority_fee_per_gas: Some(100), ..legacy.clone() };
+        assert!(replacement_transaction(&eip1559, 2_199).is_err());
+        let replacement = replacement_transaction(&eip1559, 3_000).unwrap();
+        assert_eq!(replacement.max_fee_per_gas, Some(3_000));
+        assert_eq!(replacement.max_priority_fee_per_gas, Some(150));
+
+        assert!(replacement_transaction(&unfilled_transaction(), 1_000).is_err());
+    }
+
+    #[test]
+    fn test_decode_revert_reason() {
+        let mut panic = REVERT_PANIC_SELECTOR.to_vec();
+        panic.extend_from_slice(&[0u8; 31]);
+        panic.push(0x11);
+        assert_eq!(decode_revert_reason(&panic).as_deref(), Some("panic code 0x11"));
+
+        assert_eq!(decode_revert_reason(&[]), None);
+        assert_eq!(decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef]), None);
+        assert_eq!(decode_revert_reason(&REVERT_ERROR_SELECTOR), None);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_gas_for_applies_safety_multiplier() {
+        let url = spawn_method_rpc(vec![("eth_estimateGas", json!("0x5208"))], None).await;
+        let transaction = Transaction { data: Some(vec![0xa9, 0x05, 0x9c, 0xbb]), ..unfilled_transaction() };
+
+        let manager = TransactionManager::new(url.clone()).with_gas_limit_multiplier(1.2);
+        let estimate = manager.estimate_gas_for(&transaction, Some(TOKEN)).await.unwrap();
+        assert_eq!(estimate, GasEstimate { estimated: 21_000, suggested: 25_200 });
+        assert_eq!(manager.estimate_gas(SPENDER, 1000).await.unwrap(), 21_000);
+
+        let manager = TransactionManager::new(url).with_gas_limit_multiplier(1.5);
+        assert_eq!(manager.estimate_gas_for(&transaction, None).await.unwrap().suggested, 31_500);
+        assert!(manager.estimate_gas_for(&transaction, Some("0x1234")).await.is_err());
+
+        // Fractional gas rounds up
+        assert_eq!(GasEstimate::with_multiplier(21_001, 1.2).suggested, 25_202);
+    }
+
+    #[tokio::test]
+    async fn test_failed_gas_estimate_uses_default_for_operation() {
+        let manager = TransactionManager::new(spawn_fill_rpc(Some("eth_estimateGas")).await)
+            .with_gas_limit_fallback(GasLimitFallback::default());
+        let token_transfer = Transaction {
+            data: Some(encode_address_amount_call(ERC20_TRANSFER_SELECTOR, SPENDER, U256::from(5u64)).unwrap()),
+            ..unfilled_transaction()
+        };
+        let contract_call = Transaction { data: Some(vec![0xde, 0xad, 0xbe, 0xef]), ..unfilled_transaction() };
+
+        for (tx, operation, expected) in [
+            (unfilled_transaction(), GasOperation::NativeTransfer, 21_000),
+            (token_transfer, GasOperation::TokenTransfer, 65_000),
+            (contract_call, GasOperation::ContractCall, 200_000),
+        ] {
+            assert_eq!(GasOperation::of(&tx), operation);
+            let limit = manager.estimate_transaction_gas_or_default(&tx, &Network::BaseSepolia).await.unwrap();
+            assert_eq!(limit, expected);
+        }
+
+        // A configured entry replaces the built-in default for that network only
+        let mut fallback = GasLimitFallback::default();
+        fallback.per_network.insert((Network::BaseSepolia, GasOperation::ContractCall), 400_000);
+        assert_eq!(fallback.limit_for(&Network::BaseSepolia, GasOperation::ContractCall), 400_000);
+        assert_eq!(fallback.limit_for(&Network::CoreTestnet, GasOperation::ContractCall), 200_000);
+
+        // Offline, estimation is skipped rather than failing
+        let offline = TransactionManager::new(String::new()).with_offline_mode(true).with_gas_limit_fallback(fallback);
+        let contract_call = Transaction { data: Some(vec![0x01]), ..unfilled_transaction() };
+        assert_eq!(offline.estimate_transaction_gas_or_default(&contract_call, &Network::BaseSepolia).await.unwrap(), 400_000);
+    }
+
+    #[tokio::test]
+    async fn test_reverting_gas_estimate_is_not_replaced_by_default() {
+        let reason = ethers::abi::encode(&[ethers::abi::Token::String("Not allowed".to_string())]);
+        let error = json!({ "error": { "code": 3, "message": "execution reverted", "data": format!("0x08c379a0{}", hex::encode(reason)) } });
+        let manager = TransactionManager::new(spawn_method_rpc(vec![("eth_estimateGas", error)], None).await)
+            .with_gas_limit_fallback(GasLimitFallback::default());
+        let contract_call = Transaction { data: Some(vec![0xde, 0xad, 0xbe, 0xef]), ..unfilled_transaction() };
+
+        let err = manager.estimate_transaction_gas_or_default(&contract_call, &Network::BaseSepolia).await.unwrap_err();
+        assert!(matches!(err, WalletError::Transaction(_)), "{:?}", err);
+        assert!(err.to_string().contains("Not allowed"), "{}", err);
+
+        // Without a response at all the default still applies
+        let down = TransactionManager::new(unreachable_url()).with_gas_limit_fallback(GasLimitFallback::default());
+        assert_eq!(down.estimate_transaction_gas_or_default(&contract_call, &Network::BaseSepolia).await.unwrap(), 200_000);
+    }
+
+    #[tokio::test]
+    async fn test_explicit_gas_estimate_overrides_default() {
+        let url = spawn_method_rpc(
+            vec![("eth_getTransactionCount", json!("0x7")), ("eth_estimateGas", json!("0xc350")), ("eth_gasPrice", json!("0x1"))],
+            None,
+        ).await;
+        let manager = TransactionManager::new(url)
+            .with_gas_price_cache(GasPriceCache::default())
+            .with_gas_limit_fallback(GasLimitFallback::default());
+        let contract_call = Transaction { data: Some(vec![0xde, 0xad, 0xbe, 0xef]), ..unfilled_transaction() };
+
+        let filled = manager.fill_transaction(&contract_call, TOKEN, Network::CoreTestnet).await.unwrap();
+        assert_eq!(filled.gas_limit, Some(50_000));
+        // A gas limit already on the transaction is never replaced
+        let preset = Transaction { gas_limit: Some(123_456), ..contract_call };
+        let filled = manager.fill_transaction(&preset, TOKEN, Network::CoreTestnet).await.unwrap();
+        assert_eq!(filled.gas_limit, Some(123_456));
+    }
+
+    fn receipt(status: &str, block_number: u64) -> serde_json::Value {
+        json!({ "transactionHash": "0xabc", "blockNumber": format!("0x{:x}", block_number), "status": status })
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_confirmation_maps_reverted_receipt() {
+        let url = spawn_method_rpc(
+            vec![("eth_getTransactionReceipt", receipt("0x0", 0x10)), ("eth_blockNumber", json!("0x20"))],
+            None,
+        ).await;
+        let manager = TransactionManager::new(url);
+
+        let status = manager
+            .wait_for_confirmation(&"0xabc".to_string(), 3, Duration::from_secs(5), Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert_eq!(status, ConfirmationStatus::Reverted { block_number: 0x10 });
+        assert!(matches!(manager.get_transaction_status(&"0xabc".to_string()).await.unwrap(), TransactionStatus::Failed));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_confirmation_counts_confirmations() {
+        let url = spawn_method_rpc(
+            vec![("eth_getTransactionReceipt", receipt("0x1", 100)), ("eth_blockNumber", json!("0x65"))],
+            None,
+        ).await;
+        let manager = TransactionManager::new(url);
+        let hash = "0xabc".to_string();
+
+        let status = manager.wait_for_confirmation(&hash, 2, Duration::from_secs(5), Duration::from_millis(10)).await.unwrap();
+        assert_eq!(status, ConfirmationStatus::Confirmed { block_number: 100, confirmations: 2 });
+
+        // Not enough blocks before the timeout: the last observation is returned
+        let status = manager.wait_for_confirmation(&hash, 6, Duration::from_millis(50), Duration::from_millis(10)).await.unwrap();
+        assert_eq!(status, ConfirmationStatus::Mined { block_number: 100, confirmations: 2 });
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_confirmation_times_out_without_receipt() {
+        let url = spawn_method_rpc(vec![("eth_getTransactionReceipt", serde_json::Value::Null)], None).await;
+        let manager = TransactionManager::new(url);
+
+        let status = manager
+            .wait_for_confirmation(&"0xabc".to_string(), 1, Duration::from_millis(30), Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert_eq!(status, ConfirmationStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_gas_price_uses_per_network_fallback_on_rpc_failure() {
+        let mut fallback = GasPriceFallback::default();
+        fallback.per_network.insert(Network::BaseSepolia, 50_000_000);
+        let manager = TransactionManager::new(unreachable_url())
+            .with_gas_price_fallback(fallback)
+            .with_gas_price_cache(GasPriceCache::default());
+
+        assert_eq!(manager.get_gas_price(Network::BaseSepolia).await.unwrap(), 50_000_000);
+        assert_eq!(
+            manager.get_gas_price(Network::CoreTestnet).await.unwrap(),
+            Network::CoreTestnet.fallback_gas_price()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gas_price_prefers_recent_cached_value() {
+        let cache = GasPriceCache::default();
+        let live = TransactionManager::new(spawn_gas_price_rpc(7_000_000_000).await)
+            .with_gas_price_fallback(GasPriceFallback::default())
+            .with_gas_price_cache(cache.clone());
+        assert_eq!(live.get_gas_price(Network::EthereumHolesky).await.unwrap(), 7_000_000_000);
+
+        let down = TransactionManager::new(unreachable_url())
+            .with_gas_price_fallback(GasPriceFallback::default())
+            .with_gas_price_cache(cache.clone());
+        assert_eq!(down.get_gas_price(Network::EthereumHolesky).await.unwrap(), 7_000_000_000);
+
+        // Stale cache entries are ignored
+        let stale = TransactionManager::new(unreachable_url())
+            .with_gas_price_fallback(GasPriceFallback { max_cache_age: Duration::ZERO, ..GasPriceFallback::default() })
+            .with_gas_price_cache(cache);
+        assert_eq!(
+            stale.get_gas_price(Network::EthereumHolesky).await.unwrap(),
+            Network::EthereumHolesky.fallback_gas_price()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gas_price_strict_mode_errors() {
+        let cache = GasPriceCache::default();
+        cache.store(Network::CoreTestnet.chain_id(), 1_000_000_000);
+        let manager = TransactionManager::new(unreachable_url())
+            .with_gas_price_fallback(GasPriceFallback { strict: true, ..GasPriceFallback::default() })
+            .with_gas_price_cache(cache);
+
+        assert!(manager.get_gas_price(Network::CoreTestnet).await.is_err());
+    }
+}
@@ -0,0 +1,279 @@
+//! Transaction history caching
+//!
+//! JSON-RPC cannot list an address's transactions, so history comes from a
+//! `TransactionHistorySource` (an indexer or explorer API). Fetched entries are cached
+//! per wallet together with the last synced block, and later syncs only ask for newer ones.
+//! `EtherscanHistorySource` is the built-in source for Etherscan-compatible explorer APIs.
+
+use crate::shared::error::WalletError;
+use crate::shared::types::{Network, SignedTransaction, Transaction};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// A transaction involving a wallet, as reported by a history source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub transaction: SignedTransaction,
+    pub block_number: u64,
+    /// Block timestamp, seconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// Where transaction history is fetched from
+#[async_trait]
+pub trait TransactionHistorySource: Send + Sync {
+    /// Transactions of `address` mined in `from_block` or later; everything when `None`.
+    /// The start block is inclusive, so entries already cached from it may come back.
+    async fn fetch_since(&self, address: &str, network: &Network, from_block: Option<u64>) -> Result<Vec<HistoryEntry>, WalletError>;
+}
+
+/// Cached history of one wallet, newest entries first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryCache {
+    pub entries: Vec<HistoryEntry>,
+    /// Highest block any cached entry was mined in
+    pub last_synced_block: Option<u64>,
+    /// When the cache was last synced, seconds since the Unix epoch
+    pub last_synced_at: Option<u64>,
+}
+
+impl HistoryCache {
+    /// Add fetched entries not cached yet (matched by hash) and advance the sync marker
+    pub fn merge(&mut self, fetched: Vec<HistoryEntry>, synced_at: u64) {
+        let mut known: HashSet<String> = self.entries.iter().map(|e| e.transaction.hash.to_lowercase()).collect();
+        for entry in fetched {
+            if known.insert(entry.transaction.hash.to_lowercase()) {
+                self.last_synced_block = self.last_synced_block.max(Some(entry.block_number));
+                self.entries.push(entry);
+            }
+        }
+        self.entries.sort_by_key(|e| std::cmp::Reverse(e.block_number));
+        self.last_synced_at = Some(synced_at);
+    }
+
+    pub fn transactions(&self) -> Vec<SignedTransaction> {
+        self.entries.iter().map(|e| e.transaction.clone()).collect()
+    }
+}
+
+/// Explorer API of one network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplorerApi {
+    /// Endpoint taking `module`/`action` query parameters, e.g. `https://api-sepolia.basescan.org/api`
+    pub base_url: String,
+    pub api_key: Option<String>,
+}
+
+/// History from Etherscan-compatible explorer APIs (`module=account&action=txlist`), one per network
+#[derive(Debug, Clone, Default)]
+pub struct EtherscanHistorySource {
+    apis: HashMap<u64, ExplorerApi>,
+    client: reqwest::Client,
+}
+
+/// Environment variable suffix of each built-in network, as used for RPC overrides
+const NETWORK_ENV_SUFFIXES: &[(Network, &str)] = &[
+    (Network::CoreTestnet, "CORE_TESTNET"),
+    (Network::BaseSepolia, "BASE_SEPOLIA"),
+    (Network::LiskSepolia, "LISK_SEPOLIA"),
+    (Network::EthereumHolesky, "HOLESKY"),
+];
+
+const EXPLORER_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// One entry of a `txlist` result; explorers report every number as a decimal string
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExplorerTransaction {
+    block_number: String,
+    time_stamp: String,
+    hash: String,
+    nonce: String,
+    #[serde(default)]
+    to: String,
+    #[serde(default)]
+    contract_address: String,
+    value: String,
+    gas: String,
+    gas_price: String,
+    #[serde(default)]
+    input: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExplorerResponse {
+    status: String,
+    message: String,
+    result: serde_json::Value,
+}
+
+impl EtherscanHistorySource {
+    pub fn new() -> Self {
+        Self {
+            apis: HashMap::new(),
+            client: reqwest::Client::builder().timeout(EXPLORER_TIMEOUT).build().unwrap_or_default(),
+        }
+    }
+
+    /// Use `api` for the network with `chain_id`
+    pub fn with_network(mut self, chain_id: u64, api: ExplorerApi) -> Self {
+        self.apis.insert(chain_id, api);
+        self
+    }
+
+    /// Explorer APIs of the built-in networks from `WALLET_CORE_EXPLORER_API_<NETWORK>` and
+    /// `WALLET_CORE_EXPLORER_API_KEY_<NETWORK>` (`CORE_TESTNET`, `BASE_SEPOLIA`, `LISK_SEPOLIA`,
+    /// `HOLESKY`). `None` if no network has one.
+    pub fn from_env() -> Option<Self> {
+        let mut source = Self::new();
+        for (network, suffix) in NETWORK_ENV_SUFFIXES {
+            let Some(base_url) = std::env::var(format!("WALLET_CORE_EXPLORER_API_{}", suffix)).ok().filter(|u| !u.trim().is_empty()) else {
+                continue;
+            };
+            let api_key = std::env::var(format!("WALLET_CORE_EXPLORER_API_KEY_{}", suffix)).ok().filter(|k| !k.is_empty());
+            source = source.with_network(network.chain_id(), ExplorerApi { base_url: base_url.trim().to_string(), api_key });
+        }
+        (!source.apis.is_empty()).then_some(source)
+    }
+
+    fn entry(chain_id: u64, tx: ExplorerTransaction) -> Result<HistoryEntry, WalletError> {
+        let number = |field: &str, value: &str| value.parse::<u64>()
+            .map_err(|_| WalletError::network(format!("Explorer returned an invalid {}: {}", field, value)));
+        let input = tx.input.trim_start_matches("0x");
+        let data = if input.is_empty() {
+            None
+        } else {
+            Some(hex::decode(input).map_err(|e| WalletError::network(format!("Explorer returned invalid input data: {}", e)))?)
+        };
+        let transaction = Transaction {
+            // Contract creations have an empty `to`; report the created contract instead
+            to: if tx.to.is_empty() { tx.contract_address } else { tx.to },
+            value: tx.value,
+            data,
+            gas_limit: Some(number("gas", &tx.gas)?),
+            gas_price: Some(number("gasPrice", &tx.gas_price)?),
+            nonce: Some(number("nonce", &tx.nonce)?),
+            chain_id,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        Ok(HistoryEntry {
+            transaction: SignedTransaction { transaction, signature: Vec::new(), hash: tx.hash, warnings: Vec::new() },
+            block_number: number("blockNumber", &tx.block_number)?,
+            timestamp: number("timeStamp", &tx.time_stamp)?,
+        })
+    }
+}
+
+#[async_trait]
+impl TransactionHistorySource for EtherscanHistorySource {
+    async fn fetch_since(&self, address: &str, network: &Network, from_block: Option<u64>) -> Result<Vec<HistoryEntry>, WalletError> {
+        let chain_id = network.chain_id();
+        let api = self.apis.get(&chain_id)
+            .ok_or_else(|| WalletError::not_implemented(&format!("Transaction history requires an indexer; none is configured for {}", network.name())))?;
+
+        let start_block = from_block.unwrap_or(0).to_string();
+        let mut query = vec![
+            ("module", "account"),
+            ("action", "txlist"),
+            ("address", address),
+            ("startblock", start_block.as_str()),
+            ("endblock", "99999999"),
+            ("sort", "desc"),
+        ];
+        if let Some(api_key) = &api.api_key {
+            query.push(("apikey", api_key.as_str()));
+        }
+        let response: ExplorerResponse = self.client.get(&api.base_url).query(&query).send().await
+            .map_err(|e| WalletError::network(format!("Explorer request failed: {}", e)))?
+            .error_for_status()
+            .map_err(|e| WalletError::network(format!("Explorer request failed: {}", e)))?
+            .json().await
+            .map_err(|e| WalletError::network(format!("Invalid explorer response: {}", e)))?;
+
+        // An address without transactions is reported as a failure with an empty result
+        if response.status != "1" {
+            return match response.result {
+                serde_json::Value::Array(ref txs) if txs.is_empty() => Ok(Vec::new()),
+                result => Err(WalletError::network(format!("Explorer error: {} ({})", response.message, result))),
+            };
+        }
+        let txs: Vec<ExplorerTransaction> = serde_json::from_value(response.result)
+            .map_err(|e| WalletError::network(format!("Invalid explorer response: {}", e)))?;
+        txs.into_iter().map(|tx| Self::entry(chain_id, tx)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::mock_http::{MockResponse, MockServer};
+
+    /// Explorer answering every request with `body`
+    async fn spawn_explorer(body: serde_json::Value) -> MockServer {
+        MockServer::start(move |_| MockResponse::json(&body)).await
+    }
+
+    #[tokio::test]
+    async fn test_etherscan_source_maps_txlist() {
+        let explorer = spawn_explorer(serde_json::json!({
+            "status": "1",
+            "message": "OK",
+            "result": [
+                {
+                    "blockNumber": "15", "timeStamp": "1700000015", "hash": "0xcc", "nonce": "3",
+                    "from": "0x1111111111111111111111111111111111111111",
+                    "to": "0x2222222222222222222222222222222222222222", "contractAddress": "",
+                    "value": "1000", "gas": "21000", "gasPrice": "1500000000", "input": "0x"
+                },
+                {
+                    "blockNumber": "12", "timeStamp": "1700000012", "hash": "0xbb", "nonce": "2",
+                    "from": "0x1111111111111111111111111111111111111111",
+                    "to": "", "contractAddress": "0x3333333333333333333333333333333333333333",
+                    "value": "0", "gas": "90000", "gasPrice": "1000000000", "input": "0x6080"
+                }
+            ]
+        })).await;
+        let source = EtherscanHistorySource::new()
+            .with_network(84532, ExplorerApi { base_url: explorer.url("/api"), api_key: Some("key".to_string()) });
+
+        let entries = source.fetch_since("0x1111111111111111111111111111111111111111", &Network::BaseSepolia, Some(12)).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        let transfer = &entries[0];
+        assert_eq!((transfer.block_number, transfer.timestamp), (15, 1_700_000_015));
+        assert_eq!(transfer.transaction.hash, "0xcc");
+        assert_eq!(transfer.transaction.transaction.to, "0x2222222222222222222222222222222222222222");
+        assert_eq!(transfer.transaction.transaction.value, "1000");
+        assert_eq!(transfer.transaction.transaction.data, None);
+        assert_eq!(transfer.transaction.transaction.gas_price, Some(1_500_000_000));
+        assert_eq!(transfer.transaction.transaction.chain_id, 84532);
+        let deployment = &entries[1].transaction.transaction;
+        assert_eq!(deployment.to, "0x3333333333333333333333333333333333333333");
+        assert_eq!(deployment.data, Some(vec![0x60, 0x80]));
+        assert_eq!(deployment.nonce, Some(2));
+
+        let request = &explorer.requests()[0];
+        assert_eq!(request.method, "GET");
+        for param in ["action=txlist", "address=0x1111111111111111111111111111111111111111", "startblock=12", "apikey=key"] {
+            assert!(request.path.contains(param), "{}", request.path);
+        }
+
+        // Networks without an explorer API keep the not-configured error
+        let err = source.fetch_since("0x11", &Network::CoreTestnet, None).await.unwrap_err();
+        assert!(err.to_string().contains("indexer"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_etherscan_source_no_transactions() {
+        let explorer = spawn_explorer(serde_json::json!({ "status": "0", "message": "No transactions found", "result": [] })).await;
+        let source = EtherscanHistorySource::new().with_network(1114, ExplorerApi { base_url: explorer.url("/api"), api_key: None });
+        assert!(source.fetch_since("0x11", &Network::CoreTestnet, None).await.unwrap().is_empty());
+
+        let explorer = spawn_explorer(serde_json::json!({ "status": "0", "message": "NOTOK", "result": "Invalid API Key" })).await;
+        let source = EtherscanHistorySource::new().with_network(1114, ExplorerApi { base_url: explorer.url("/api"), api_key: None });
+        let err = source.fetch_since("0x11", &Network::CoreTestnet, None).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid API Key"), "{}", err);
+    }
+}
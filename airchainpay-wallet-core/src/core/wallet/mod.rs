@@ -4,22 +4,252 @@
 
 use crate::domain::{SecureWallet, WalletBalance};
 use crate::shared::error::WalletError;
-use crate::shared::types::{Network, Transaction, SignedTransaction};
-use reqwest::Client;
+use crate::shared::types::{Network, Transaction, SignedTransaction, CostEstimate, SplitBackup};
+use crate::infrastructure::platform::{BiometricAuth, NoBiometricAuth, PlatformStorage};
+use crate::core::crypto::signatures::{Eip712Domain, Eip712Types, SignatureManager};
+use crate::core::crypto::SecurityAuditor;
+use crate::infrastructure::network::{RetryClock, RpcClient, SystemClock};
 use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::sync::Arc;
+use std::time::Duration;
+use zeroize::{Zeroize, Zeroizing};
 
-/// Wallet manager for handling multiple wallets
+pub mod history;
+pub use history::{EtherscanHistorySource, ExplorerApi, HistoryCache, HistoryEntry, TransactionHistorySource};
+
+const WALLET_BUNDLE_VERSION: &str = "1.0";
+const SHARE_BACKUP_VERSION: &str = "1.0";
+/// Ids of every persisted wallet. Loading goes through this record so storage keys never
+/// have to be enumerated.
+const WALLET_INDEX_KEY: &str = "wallet_index";
+
+/// Serializes read-modify-write updates of the wallet index, account lists and sent
+/// transactions within this process
+static WALLET_INDEX_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Storage key of the accounts added to a wallet with `add_account`
+fn accounts_key(wallet_id: &str) -> String {
+    format!("wallet_accounts_{}", wallet_id)
+}
+
+/// Storage key of the derivation path an imported wallet's own key was derived at
+fn derivation_path_key(wallet_id: &str) -> String {
+    format!("wallet_path_{}", wallet_id)
+}
+
+/// Storage key of the transactions a wallet's accounts broadcast, kept so a stuck one can be
+/// sped up or cancelled after a restart
+fn sent_transactions_key(wallet_id: &str) -> String {
+    format!("wallet_sent_{}", wallet_id)
+}
+
+/// A broadcast transaction and the lowercase address of the account that signed it
+#[derive(Serialize, Deserialize)]
+struct SentTransaction {
+    from: String,
+    transaction: Transaction,
+}
+
+/// Storage key of the private key of an account. Account 0 is the wallet's own `wallet_key_` key.
+fn account_key_id(wallet_id: &str, account_index: u32) -> String {
+    match account_index {
+        0 => format!("wallet_key_{}", wallet_id),
+        index => format!("wallet_account_key_{}_{}", index, wallet_id),
+    }
+}
+
+/// Encrypted multi-wallet export; `data` is the base64 salt + nonce + AES-GCM ciphertext
+#[derive(Serialize, Deserialize)]
+struct WalletBundle {
+    version: String,
+    data: String,
+}
+
+/// Single-wallet bundle produced by `split_backup`. `data` has the same layout as a
+/// bundle; its AES key is the secret split into shares.
+#[derive(Serialize, Deserialize)]
+struct ShareBackup {
+    version: String,
+    wallet_id: String,
+    threshold: u8,
+    data: String,
+}
+
+/// One wallet inside a decrypted bundle. The private key is zeroized on drop.
+#[derive(Serialize, Deserialize)]
+struct WalletBundleEntry {
+    id: String,
+    name: String,
+    address: String,
+    network: Network,
+    created_at: u64,
+    private_key: String,
+}
+
+impl Drop for WalletBundleEntry {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+/// Balance update events buffered per subscriber before the oldest are dropped
+const BALANCE_EVENT_CAPACITY: usize = 64;
+
+/// When cached balances are refreshed from the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BalanceRefreshStrategy {
+    /// Only when `get_balance` is called
+    #[default]
+    Lazy,
+    /// Additionally for every wallet on a background task started by `start_balance_refresh`
+    Eager { interval: Duration },
+}
+
+/// Emitted whenever a wallet's balance has been fetched from the chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceUpdated {
+    pub wallet_id: String,
+    pub amount: String,
+    pub currency: String,
+}
+
+/// An address of a seed-backed wallet, derived at `m/44'/60'/0'/0/{index}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletAccount {
+    pub index: u32,
+    pub address: String,
+}
+
+/// Wallet manager for handling multiple wallets. Clones share the same wallets and caches.
+#[derive(Clone)]
 pub struct WalletManager {
     // Removed CryptoManager for simplicity
     wallets: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, SecureWallet>>>,
     balances: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, WalletBalance>>>,
+    refresh_strategy: BalanceRefreshStrategy,
+    balance_events: tokio::sync::broadcast::Sender<BalanceUpdated>,
+    /// Refuse every RPC-backed operation; wallet storage and signing keep working
+    offline: bool,
+    history_source: Option<Arc<dyn TransactionHistorySource>>,
+    /// Unlocks keys of biometric-protected wallets
+    biometric_auth: Arc<dyn BiometricAuth + Send + Sync>,
+    /// Dry-run transactions with `eth_call` in `send_transaction_auto` and refuse ones that revert
+    simulate_before_send: bool,
+    /// RPC URLs by chain id, taking precedence over the environment and network defaults
+    rpc_urls: std::collections::HashMap<u64, String>,
 }
 
 impl WalletManager {
     pub fn new() -> Self {
+        SecurityAuditor::audit_storage_passwords();
         Self {
             wallets: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
             balances: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+            refresh_strategy: BalanceRefreshStrategy::default(),
+            balance_events: tokio::sync::broadcast::channel(BALANCE_EVENT_CAPACITY).0,
+            offline: false,
+            history_source: EtherscanHistorySource::from_env()
+                .map(|source| Arc::new(source) as Arc<dyn TransactionHistorySource>),
+            biometric_auth: Arc::new(NoBiometricAuth::new()),
+            simulate_before_send: false,
+            rpc_urls: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Use `rpc_url` for wallets on `network` instead of its `WALLET_CORE_RPC_*` variable
+    /// or default endpoint. The value may be a comma-separated list of endpoints.
+    pub fn with_rpc_url(mut self, network: &Network, rpc_url: impl Into<String>) -> Self {
+        self.rpc_urls.insert(network.chain_id(), rpc_url.into());
+        self
+    }
+
+    pub fn with_offline_mode(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    fn ensure_online(&self, operation: &str) -> Result<(), WalletError> {
+        if self.offline {
+            return Err(WalletError::offline(operation));
+        }
+        Ok(())
+    }
+
+    /// Fetch transaction history from `source` (an indexer or explorer API) instead of the
+    /// explorer APIs configured through the environment, see `EtherscanHistorySource::from_env`
+    pub fn with_history_source(mut self, source: Arc<dyn TransactionHistorySource>) -> Self {
+        self.history_source = Some(source);
+        self
+    }
+
+    /// Platform biometric prompt used for biometric-protected wallets. Without one such
+    /// wallets cannot be created or used.
+    pub fn with_biometric_auth(mut self, biometric_auth: Arc<dyn BiometricAuth + Send + Sync>) -> Self {
+        self.biometric_auth = biometric_auth;
+        self
+    }
+
+    /// Simulate every transaction sent through `send_transaction_auto` before signing it, so
+    /// one that would revert fails with its revert reason instead of spending gas
+    pub fn with_pre_broadcast_simulation(mut self, enabled: bool) -> Self {
+        self.simulate_before_send = enabled;
+        self
+    }
+
+    /// Key manager whose keys are unlocked through this manager's biometric prompt
+    fn key_manager<'a>(&self, storage: &'a dyn PlatformStorage) -> crate::core::crypto::keys::KeyManager<'a> {
+        crate::core::crypto::keys::KeyManager::new(storage).with_biometric_auth(self.biometric_auth.clone())
+    }
+
+    pub fn with_refresh_strategy(mut self, strategy: BalanceRefreshStrategy) -> Self {
+        self.refresh_strategy = strategy;
+        self
+    }
+
+    pub fn refresh_strategy(&self) -> BalanceRefreshStrategy {
+        self.refresh_strategy
+    }
+
+    /// Receive a `BalanceUpdated` for every balance fetched from the chain
+    pub fn subscribe_balance_updates(&self) -> tokio::sync::broadcast::Receiver<BalanceUpdated> {
+        self.balance_events.subscribe()
+    }
+
+    /// Start refreshing every wallet's balance on the eager interval. Returns `None` in lazy
+    /// or offline mode; abort the returned handle to stop refreshing.
+    pub fn start_balance_refresh(&self) -> Option<tokio::task::JoinHandle<()>> {
+        self.start_balance_refresh_with_clock(Arc::new(SystemClock))
+    }
+
+    fn start_balance_refresh_with_clock(&self, clock: Arc<dyn RetryClock>) -> Option<tokio::task::JoinHandle<()>> {
+        let BalanceRefreshStrategy::Eager { interval } = self.refresh_strategy else {
+            return None;
+        };
+        if self.offline {
+            log::info!("Offline mode: eager balance refresh not started");
+            return None;
+        }
+        let manager = self.clone();
+        Some(tokio::spawn(async move {
+            loop {
+                clock.sleep(interval).await;
+                manager.refresh_all_balances().await;
+            }
+        }))
+    }
+
+    /// Fetch the balance of every wallet; failures are logged and the stale value kept
+    pub async fn refresh_all_balances(&self) {
+        let wallet_ids: Vec<String> = self.wallets.read().await.keys().cloned().collect();
+        if let Err(e) = self.get_balances(&wallet_ids).await {
+            log::warn!("Balance refresh failed: {}", e);
         }
     }
 
@@ -30,9 +260,62 @@ impl WalletManager {
         name: &str,
         network: Network,
     ) -> Result<SecureWallet, WalletError> {
-        // Initialize secure file storage and key manager
+        // Initialize secure file storage
         let file_storage = crate::infrastructure::platform::FileStorage::new()?;
-        let key_manager = crate::core::crypto::keys::KeyManager::new(&file_storage);
+        self.create_wallet_with_storage(&file_storage, wallet_id, name, network).await
+    }
+
+    /// Create a wallet whose key can only be used after a successful biometric prompt.
+    /// Fails if this device has no biometric authentication.
+    pub async fn create_wallet_biometric_protected(
+        &self,
+        wallet_id: &str,
+        name: &str,
+        network: Network,
+    ) -> Result<SecureWallet, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.create_wallet_biometric_protected_with_storage(&file_storage, wallet_id, name, network).await
+    }
+
+    pub(crate) async fn create_wallet_with_storage(
+        &self,
+        storage: &dyn PlatformStorage,
+        wallet_id: &str,
+        name: &str,
+        network: Network,
+    ) -> Result<SecureWallet, WalletError> {
+        self.create_wallet_inner(storage, wallet_id, name, network, false).await
+    }
+
+    pub(crate) async fn create_wallet_biometric_protected_with_storage(
+        &self,
+        storage: &dyn PlatformStorage,
+        wallet_id: &str,
+        name: &str,
+        network: Network,
+    ) -> Result<SecureWallet, WalletError> {
+        if !self.biometric_auth.is_available()? {
+            return Err(WalletError::crypto("Biometric authentication is not available on this device".to_string()));
+        }
+        self.create_wallet_inner(storage, wallet_id, name, network, true).await
+    }
+
+    async fn create_wallet_inner(
+        &self,
+        storage: &dyn PlatformStorage,
+        wallet_id: &str,
+        name: &str,
+        network: Network,
+        biometric_protected: bool,
+    ) -> Result<SecureWallet, WalletError> {
+        if wallet_id.trim().is_empty() {
+            return Err(WalletError::invalid_field("wallet_id", "Wallet ID cannot be empty", "required"));
+        }
+        if name.trim().is_empty() {
+            return Err(WalletError::invalid_field("name", "Wallet name cannot be empty", "required"));
+        }
+
+        let key_manager = self.key_manager(storage);
 
         // Derive deterministic key id from wallet id
         let key_id = format!("wallet_key_{}", wallet_id);
@@ -41,6 +324,10 @@ impl WalletManager {
         let private_key = key_manager.generate_private_key(&key_id)?;
         let public_key = key_manager.get_public_key(&private_key)?;
         let address = key_manager.get_address(&public_key)?;
+        // Marked only once the address is known, so creation itself needs no prompt
+        if biometric_protected {
+            private_key.require_biometric(storage)?;
+        }
 
         // Construct secure wallet entity
         let wallet = SecureWallet::new(
@@ -49,6 +336,7 @@ impl WalletManager {
             address,
             network.clone(),
         );
+        Self::persist_wallet_info(storage, &wallet)?;
 
         // Persist in manager state
         {
@@ -85,8 +373,30 @@ impl WalletManager {
             .ok_or_else(|| WalletError::wallet_not_found(format!("Wallet not found: {}", wallet_id)))
     }
     
+    /// Resolve RPC URL via `with_rpc_url`, env override or network defaults. The value may
+    /// be a comma-separated list of endpoints, tried in order by `RpcClient`.
+    fn resolve_rpc_url(&self, network: &Network) -> Result<String, WalletError> {
+        if let Some(rpc_url) = self.rpc_urls.get(&network.chain_id()) {
+            return Ok(rpc_url.clone());
+        }
+        match network {
+            Network::CoreTestnet => Ok(std::env::var("WALLET_CORE_RPC_CORE_TESTNET")
+                .unwrap_or_else(|_| Network::CoreTestnet.rpc_url().to_string())),
+            Network::BaseSepolia => Ok(std::env::var("WALLET_CORE_RPC_BASE_SEPOLIA")
+                .unwrap_or_else(|_| Network::BaseSepolia.rpc_url().to_string())),
+            Network::LiskSepolia => std::env::var("WALLET_CORE_RPC_LISK_SEPOLIA")
+                .map_err(|_| WalletError::config("RPC URL not set for Lisk Sepolia")),
+            Network::EthereumHolesky => std::env::var("WALLET_CORE_RPC_HOLESKY")
+                .map_err(|_| WalletError::config("RPC URL not set for Holesky")),
+            Network::Custom { rpc_url, name, .. } if rpc_url.trim().is_empty() =>
+                Err(WalletError::config(format!("RPC URL not set for {}", name))),
+            Network::Custom { rpc_url, .. } => Ok(rpc_url.clone()),
+        }
+    }
+
     /// Get wallet balance (queries RPC by network and updates cache)
     pub async fn get_balance(&self, wallet_id: &str) -> Result<String, WalletError> {
+        self.ensure_online("balance lookup")?;
         // Resolve wallet, network, and address
         let (address, network) = {
             let wallets = self.wallets.read().await;
@@ -96,69 +406,143 @@ impl WalletManager {
             (wallet.address.clone(), wallet.network.clone())
         };
 
-        // Resolve RPC URL via env override or network defaults
-        let rpc_url = match network {
-            Network::CoreTestnet => std::env::var("WALLET_CORE_RPC_CORE_TESTNET")
-                .unwrap_or_else(|_| Network::CoreTestnet.rpc_url().to_string()),
-            Network::BaseSepolia => std::env::var("WALLET_CORE_RPC_BASE_SEPOLIA")
-                .unwrap_or_else(|_| Network::BaseSepolia.rpc_url().to_string()),
-            Network::LiskSepolia => std::env::var("WALLET_CORE_RPC_LISK_SEPOLIA")
-                .map_err(|_| WalletError::config("RPC URL not set for Lisk Sepolia"))?,
-            Network::EthereumHolesky => std::env::var("WALLET_CORE_RPC_HOLESKY")
-                .map_err(|_| WalletError::config("RPC URL not set for Holesky"))?,
-        };
+        let dec_balance = self.fetch_balance(&network, &address).await?;
+        self.record_balance(wallet_id, &network, dec_balance.clone()).await;
+        Ok(dec_balance)
+    }
 
-        // Query eth_getBalance
-        let client = Client::new();
-        let body = serde_json::json!({
-            "jsonrpc": "2.0",
-            "method": "eth_getBalance",
-            "params": [address, "latest"],
-            "id": 1
-        });
-        let resp = client
-            .post(&rpc_url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| WalletError::network(format!("Failed to query balance: {}", e)))?;
-        let resp_json: serde_json::Value = resp
-            .json()
-            .await
-            .map_err(|e| WalletError::network(format!("Invalid balance response: {}", e)))?;
+    /// Balance of account `account_index` of a wallet. Account 0 is `get_balance`; the balances
+    /// of added accounts are not cached.
+    pub async fn get_account_balance(&self, wallet_id: &str, account_index: u32) -> Result<String, WalletError> {
+        if account_index == 0 {
+            return self.get_balance(wallet_id).await;
+        }
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.get_account_balance_with_storage(&file_storage, wallet_id, account_index).await
+    }
+
+    async fn get_account_balance_with_storage(&self, storage: &dyn PlatformStorage, wallet_id: &str, account_index: u32) -> Result<String, WalletError> {
+        self.ensure_online("balance lookup")?;
+        let network = self.get_wallet(wallet_id).await?.network;
+        let (_, address) = self.account_key(storage, wallet_id, account_index).await?;
+        self.fetch_balance(&network, &address).await
+    }
+
+    /// Decimal `eth_getBalance` of `address` on `network`
+    async fn fetch_balance(&self, network: &Network, address: &str) -> Result<String, WalletError> {
+        let rpc_url = self.resolve_rpc_url(network)?;
+        let resp_json = crate::infrastructure::network::RpcClient::new(rpc_url)
+            .call("eth_getBalance", serde_json::json!([address, "latest"]))
+            .await?;
 
         let hex_balance = resp_json
             .get("result")
             .and_then(|v| v.as_str())
             .ok_or_else(|| WalletError::network("Missing balance result".to_string()))?;
+        Ok(Self::hex_to_decimal(hex_balance))
+    }
 
-        // Convert 0x hex to decimal string using U256
-        let dec_balance = {
-            let clean = hex_balance.trim_start_matches("0x");
-            let bytes = hex::decode(clean).unwrap_or_default();
-            let mut val = U256::zero();
-            if !bytes.is_empty() {
-                val = U256::from_big_endian(&bytes);
+    /// Fetch the balances of several wallets with one JSON-RPC batch request per network.
+    /// Returns wallet id to decimal balance; wallets whose call failed are left out.
+    pub async fn get_balances(&self, wallet_ids: &[String]) -> Result<std::collections::HashMap<String, String>, WalletError> {
+        self.ensure_online("balance lookup")?;
+        // Group by network, keeping the requested order within each group
+        let mut by_network: Vec<(Network, Vec<(String, String)>)> = Vec::new();
+        {
+            let wallets = self.wallets.read().await;
+            for wallet_id in wallet_ids {
+                let wallet = wallets
+                    .get(wallet_id)
+                    .ok_or_else(|| WalletError::wallet_not_found(format!("Wallet not found: {}", wallet_id)))?;
+                let entry = (wallet_id.clone(), wallet.address.clone());
+                match by_network.iter_mut().find(|(network, _)| *network == wallet.network) {
+                    Some((_, group)) => group.push(entry),
+                    None => by_network.push((wallet.network.clone(), vec![entry])),
+                }
             }
-            val.to_string()
-        };
+        }
+
+        let mut result = std::collections::HashMap::new();
+        for (network, group) in by_network {
+            let rpc_url = match self.resolve_rpc_url(&network) {
+                Ok(url) => url,
+                Err(e) => {
+                    log::warn!("Skipping {} wallet(s) on {}: {}", group.len(), network.name(), e);
+                    continue;
+                }
+            };
+            let calls: Vec<(&str, serde_json::Value)> = group.iter()
+                .map(|(_, address)| ("eth_getBalance", serde_json::json!([address, "latest"])))
+                .collect();
+            let responses = match crate::infrastructure::network::RpcClient::new(rpc_url).batch(&calls).await {
+                Ok(responses) => responses,
+                Err(e) => {
+                    log::warn!("Balance batch failed on {}: {}", network.name(), e);
+                    continue;
+                }
+            };
+
+            for ((wallet_id, _), response) in group.into_iter().zip(responses) {
+                let Some(hex_balance) = response.get("result").and_then(|v| v.as_str()) else {
+                    let reason = response.get("error").map(|e| e.to_string()).unwrap_or_else(|| "no response".to_string());
+                    log::warn!("Skipping balance of wallet {}: {}", wallet_id, reason);
+                    continue;
+                };
+                let dec_balance = Self::hex_to_decimal(hex_balance);
+                self.record_balance(&wallet_id, &network, dec_balance.clone()).await;
+                result.insert(wallet_id, dec_balance);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Convert a 0x hex quantity to a decimal string using U256
+    fn hex_to_decimal(hex_balance: &str) -> String {
+        U256::from_str_radix(hex_balance.trim_start_matches("0x"), 16)
+            .unwrap_or_default()
+            .to_string()
+    }
 
-        // Update cache
+    /// Update the balance cache and notify subscribers
+    async fn record_balance(&self, wallet_id: &str, network: &Network, amount: String) {
+        let currency = network.native_currency().to_string();
         {
             let mut balances = self.balances.write().await;
-            let currency = network.native_currency().to_string();
-            let balance = WalletBalance::new(wallet_id.to_string(), network.clone(), dec_balance.clone(), currency);
+            let balance = WalletBalance::new(wallet_id.to_string(), network.clone(), amount.clone(), currency.clone());
             balances.insert(wallet_id.to_string(), balance);
         }
-
-        Ok(dec_balance)
+        // Nobody listening is not an error
+        let _ = self.balance_events.send(BalanceUpdated {
+            wallet_id: wallet_id.to_string(),
+            amount,
+            currency,
+        });
     }
 
     /// Sign a message using a wallet's private key
     pub async fn sign_message(&self, wallet_id: &str, message: &str) -> Result<String, WalletError> {
-        // Get secure storage and key manager
+        // Get secure storage
         let file_storage = crate::infrastructure::platform::FileStorage::new()?;
-        let key_manager = crate::core::crypto::keys::KeyManager::new(&file_storage);
+        self.sign_message_with_storage(&file_storage, wallet_id, message).await
+    }
+
+    /// Sign a message with the key of account `account_index`, see `add_account`
+    pub async fn sign_message_for_account(&self, wallet_id: &str, account_index: u32, message: &str) -> Result<String, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.sign_message_for_account_with_storage(&file_storage, wallet_id, account_index, message).await
+    }
+
+    async fn sign_message_for_account_with_storage(&self, storage: &dyn PlatformStorage, wallet_id: &str, account_index: u32, message: &str) -> Result<String, WalletError> {
+        if account_index == 0 {
+            return self.sign_message_with_storage(storage, wallet_id, message).await;
+        }
+        let (key_id, _) = self.account_key(storage, wallet_id, account_index).await?;
+        let key_manager = self.key_manager(storage);
+        key_manager.sign_message(&key_manager.get_private_key(&key_id)?, message)
+    }
+
+    pub(crate) async fn sign_message_with_storage(&self, storage: &dyn PlatformStorage, wallet_id: &str, message: &str) -> Result<String, WalletError> {
+        let key_manager = self.key_manager(storage);
         
         // Get private key reference (does not load key into memory)
         let key_id = format!("wallet_key_{}", wallet_id);
@@ -168,16 +552,65 @@ impl WalletManager {
         key_manager.sign_message(&private_key, message)
     }
 
+    /// Sign EIP-712 typed data with the wallet's key. A domain `chainId` must match the wallet's network.
+    pub async fn sign_typed(
+        &self,
+        wallet_id: &str,
+        domain: &Eip712Domain,
+        types: &Eip712Types,
+        primary_type: &str,
+        message: &serde_json::Value,
+    ) -> Result<String, WalletError> {
+        let network = self.get_wallet(wallet_id).await?.network;
+        if let Some(chain_id) = domain.chain_id {
+            if chain_id != U256::from(network.chain_id()) {
+                return Err(WalletError::invalid_field("chainId", "Typed data domain chainId does not match wallet network", "chain_mismatch"));
+            }
+        }
+
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        let key_manager = self.key_manager(&file_storage);
+        let private_key = key_manager.get_private_key(&format!("wallet_key_{}", wallet_id))?;
+        if private_key.scheme() != crate::core::crypto::keys::SigningScheme::Secp256k1 {
+            return Err(WalletError::crypto("Typed data signing requires a secp256k1 key".to_string()));
+        }
+
+        let signature_manager = SignatureManager::new();
+        private_key.with_key(&file_storage, |key_bytes| {
+            signature_manager.sign_typed_data_with_bytes(domain, types, primary_type, message, key_bytes)
+        })
+    }
+
+    /// Verify an EIP-712 signature against the wallet's address
+    pub async fn verify_typed(
+        &self,
+        wallet_id: &str,
+        domain: &Eip712Domain,
+        types: &Eip712Types,
+        primary_type: &str,
+        message: &serde_json::Value,
+        signature: &str,
+    ) -> Result<bool, WalletError> {
+        let address = self.get_wallet(wallet_id).await?.address;
+        SignatureManager::new().verify_typed_data(domain, types, primary_type, message, signature, &address)
+    }
+
     /// Sign and broadcast a transaction using the wallet's private key
     pub async fn send_transaction(&self, wallet_id: &str, transaction: Transaction) -> Result<SignedTransaction, WalletError> {
+        self.send_transaction_from_account(wallet_id, 0, transaction).await
+    }
+
+    /// Sign and broadcast a transaction with the key of account `account_index`, see `add_account`
+    pub async fn send_transaction_from_account(&self, wallet_id: &str, account_index: u32, transaction: Transaction) -> Result<SignedTransaction, WalletError> {
+        self.ensure_online("transaction broadcast")?;
         // Resolve wallet and network
-        let (network, rpc_url) = {
+        let network = {
             let wallets = self.wallets.read().await;
             let wallet = wallets.get(wallet_id)
                 .ok_or_else(|| WalletError::wallet_not_found(format!("Wallet not found: {}", wallet_id)))?;
-            let rpc = wallet.network.rpc_url().to_string();
-            (wallet.network.clone(), rpc)
+            wallet.network.clone()
         };
+        let rpc_url = self.resolve_rpc_url(&network)?;
 
         // Validate chain id alignment
         if transaction.chain_id != network.chain_id() {
@@ -186,90 +619,1808 @@ impl WalletManager {
 
         // Prepare signing/storage
         let file_storage = crate::infrastructure::platform::FileStorage::new()?;
-        let key_id = format!("wallet_key_{}", wallet_id);
+        let (key_id, address) = self.account_key(&file_storage, wallet_id, account_index).await?;
+        let private_key = self.key_manager(&file_storage).get_private_key(&key_id)?;
 
         // Sign using the transaction manager
         let tx_manager = crate::core::transactions::TransactionManager::new(rpc_url);
+        let warning = tx_manager.check_contract_recipient(&transaction).await?;
         let mut signed = tx_manager
-            .sign_transaction(&transaction, &key_id, &file_storage)
+            .sign_transaction_with_key(&transaction, &private_key, &file_storage)
             .await?;
+        signed.warnings.extend(warning);
 
         // Broadcast and attach returned hash
         let tx_hash = tx_manager.send_transaction(&signed).await?;
         signed.hash = tx_hash;
+        Self::record_sent(&file_storage, &tx_manager, wallet_id, &address, &signed.transaction).await;
         Ok(signed)
     }
 
-    /// Get transaction history
-    pub async fn get_transaction_history(&self, _wallet_id: &str) -> Result<Vec<SignedTransaction>, WalletError> {
-        // Transaction history requires an indexer or third-party API; JSON-RPC alone cannot query by address efficiently.
-        Err(WalletError::not_implemented("Transaction history requires an indexer; not supported via JSON-RPC only"))
-    }
+    /// Keep a broadcast transaction for `speed_up_transaction` and `cancel_transaction`,
+    /// replacing any earlier one at its nonce and dropping the sender's mined ones. The
+    /// transaction is already sent, so a failure here is only logged.
+    async fn record_sent(
+        storage: &dyn PlatformStorage,
+        tx_manager: &crate::core::transactions::TransactionManager,
+        wallet_id: &str,
+        from: &str,
+        transaction: &Transaction,
+    ) {
+        let Some(nonce) = transaction.nonce else { return };
+        let from = from.to_lowercase();
+        let mined = tx_manager.get_mined_transaction_count(&from).await.ok();
 
-    /// Update wallet balance (uses wallet's configured network and currency)
-    pub async fn update_balance(&self, wallet_id: &str, balance: String) -> Result<(), WalletError> {
-        let (network, currency) = {
-            let wallets = self.wallets.read().await;
-            if let Some(wallet) = wallets.get(wallet_id) {
-                let n = wallet.network.clone();
-                let c = n.native_currency().to_string();
-                (n, c)
-            } else {
-                // Fallback if wallet not found
-                let n = Network::CoreTestnet;
-                (n.clone(), n.native_currency().to_string())
-            }
-        };
+        let _guard = WALLET_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let result = Self::read_sent_transactions(storage, wallet_id).and_then(|mut sent| {
+            sent.retain(|entry| {
+                let entry_nonce = entry.transaction.nonce.unwrap_or_default();
+                entry.from != from || (entry_nonce != nonce && mined.map_or(true, |mined| entry_nonce >= mined))
+            });
+            sent.push(SentTransaction { from: from.clone(), transaction: transaction.clone() });
+            storage.store(&sent_transactions_key(wallet_id), &serde_json::to_vec(&sent)?)
+        });
+        if let Err(e) = result {
+            log::warn!("Could not record transaction {} from {}: {}", nonce, from, e);
+        }
+    }
 
-        let mut balances = self.balances.write().await;
-        let wallet_balance = WalletBalance::new(
-            wallet_id.to_string(),
-            network,
-            balance,
-            currency,
-        );
-        balances.insert(wallet_id.to_string(), wallet_balance);
-        Ok(())
+    fn read_sent_transactions(storage: &dyn PlatformStorage, wallet_id: &str) -> Result<Vec<SentTransaction>, WalletError> {
+        let key = sent_transactions_key(wallet_id);
+        if !storage.exists(&key)? {
+            return Ok(Vec::new());
+        }
+        serde_json::from_slice(&storage.retrieve(&key)?)
+            .map_err(|e| WalletError::storage(format!("Sent transactions of wallet {} are corrupted: {}", wallet_id, e)))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// The recorded transaction `from` sent with `nonce`, if any
+    fn sent_transaction(storage: &dyn PlatformStorage, wallet_id: &str, from: &str, nonce: u64) -> Result<Option<Transaction>, WalletError> {
+        Ok(Self::read_sent_transactions(storage, wallet_id)?
+            .into_iter()
+            .find(|entry| entry.from.eq_ignore_ascii_case(from) && entry.transaction.nonce == Some(nonce))
+            .map(|entry| entry.transaction))
+    }
 
+    /// Like `send_transaction`, but a missing nonce, gas limit or gas price is fetched from
+    /// the RPC first. Nothing is signed or broadcast unless every lookup succeeds.
+    pub async fn send_transaction_auto(&self, wallet_id: &str, transaction: Transaction) -> Result<SignedTransaction, WalletError> {
+        self.send_transaction_auto_from_account(wallet_id, 0, transaction).await
+    }
 
-    #[tokio::test]
-    async fn test_wallet_manager_creation() {
-        let _manager = WalletManager::new();
-        // Test that the manager can be created successfully
-        assert!(true); // Basic creation test passed
+    /// `send_transaction_auto` with the key of account `account_index`, see `add_account`
+    pub async fn send_transaction_auto_from_account(&self, wallet_id: &str, account_index: u32, transaction: Transaction) -> Result<SignedTransaction, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.send_transaction_auto_with_storage(wallet_id, account_index, transaction, &file_storage).await
     }
 
-    #[tokio::test]
-    async fn test_wallet_balance_update() {
-        let manager = WalletManager::new();
-        
-        // Create a test wallet first
-        let _wallet = manager.create_wallet("test_wallet", "Test Wallet", Network::CoreTestnet).await
-            .expect("Failed to create test wallet");
-        
-        // Update the balance
-        manager.update_balance("test_wallet", "1000000".to_string()).await
-            .expect("Failed to update wallet balance");
-        
-        // Get the balance from cache (not from blockchain)
-        let balances = manager.balances.read().await;
-        if let Some(balance) = balances.get("test_wallet") {
-            assert_eq!(balance.amount, "1000000");
-        } else {
-            panic!("Balance not found in cache");
+    async fn send_transaction_auto_with_storage(
+        &self,
+        wallet_id: &str,
+        account_index: u32,
+        transaction: Transaction,
+        storage: &dyn PlatformStorage,
+    ) -> Result<SignedTransaction, WalletError> {
+        self.ensure_online("transaction broadcast")?;
+        let network = self.get_wallet(wallet_id).await?.network;
+        let (key_id, address) = self.account_key(storage, wallet_id, account_index).await?;
+        if transaction.chain_id != network.chain_id() {
+            return Err(WalletError::validation("Transaction chain_id does not match wallet network"));
         }
+
+        let tx_manager = crate::core::transactions::TransactionManager::new(self.resolve_rpc_url(&network)?);
+        let warning = tx_manager.check_contract_recipient(&transaction).await?;
+        let filled = tx_manager.fill_transaction(&transaction, &address, network).await?;
+        if self.simulate_before_send {
+            tx_manager.simulate_transaction(&filled, &address).await?;
+        }
+
+        let private_key = self.key_manager(storage).get_private_key(&key_id)?;
+        let mut signed = tx_manager.sign_transaction_with_key(&filled, &private_key, storage).await?;
+        signed.hash = tx_manager.send_transaction(&signed).await?;
+        signed.warnings.extend(warning);
+        Self::record_sent(storage, &tx_manager, wallet_id, &address, &signed.transaction).await;
+        Ok(signed)
     }
 
-    #[tokio::test]
-    async fn test_wallet_not_found() {
-        let manager = WalletManager::new();
-        let result = manager.get_wallet("nonexistent_wallet").await;
-        assert!(result.is_err());
+    /// Rebroadcast the transaction account `account_index` of the wallet sent with
+    /// `original_nonce`, at the same nonce but priced at `new_gas_price` (the max fee for
+    /// EIP-1559 transactions), so it replaces the stuck original. The price must be at least
+    /// 10% above the original's.
+    pub async fn speed_up_transaction(&self, wallet_id: &str, account_index: u32, original_nonce: u64, new_gas_price: u64) -> Result<SignedTransaction, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.speed_up_transaction_with_storage(&file_storage, wallet_id, account_index, original_nonce, new_gas_price).await
+    }
+
+    /// Replace the pending transaction of account `account_index` at `nonce` with a 0-value
+    /// transfer from the account to itself priced at `gas_price`. If the original was sent
+    /// through this wallet, the price must be at least 10% above its price; otherwise the node
+    /// decides whether the replacement is accepted.
+    pub async fn cancel_transaction(&self, wallet_id: &str, account_index: u32, nonce: u64, gas_price: u64) -> Result<SignedTransaction, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.cancel_transaction_with_storage(&file_storage, wallet_id, account_index, nonce, gas_price).await
+    }
+
+    async fn speed_up_transaction_with_storage(&self, storage: &dyn PlatformStorage, wallet_id: &str, account_index: u32, original_nonce: u64, new_gas_price: u64) -> Result<SignedTransaction, WalletError> {
+        let (_, address) = self.account_key(storage, wallet_id, account_index).await?;
+        let original = Self::sent_transaction(storage, wallet_id, &address, original_nonce)?
+            .ok_or_else(|| WalletError::invalid_field(
+                "nonce",
+                format!("No transaction with nonce {} was sent from account {} of wallet {}", original_nonce, account_index, wallet_id),
+                "unknown_nonce",
+            ))?;
+        let replacement = crate::core::transactions::replacement_transaction(&original, new_gas_price)?;
+        self.send_transaction_auto_with_storage(wallet_id, account_index, replacement, storage).await
+    }
+
+    async fn cancel_transaction_with_storage(&self, storage: &dyn PlatformStorage, wallet_id: &str, account_index: u32, nonce: u64, gas_price: u64) -> Result<SignedTransaction, WalletError> {
+        let wallet = self.get_wallet(wallet_id).await?;
+        let (_, address) = self.account_key(storage, wallet_id, account_index).await?;
+        let original = Self::sent_transaction(storage, wallet_id, &address, nonce)?;
+        let self_transfer = Transaction {
+            to: address.clone(),
+            value: "0".to_string(),
+            data: None,
+            gas_limit: Some(crate::core::transactions::GasOperation::NativeTransfer.default_limit(&wallet.network)),
+            gas_price: Some(gas_price),
+            nonce: Some(nonce),
+            chain_id: wallet.network.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        let cancellation = match original {
+            // Keep the original's fee fields so the price comparison is like for like
+            Some(original) => crate::core::transactions::replacement_transaction(
+                &Transaction { gas_price: original.gas_price, max_fee_per_gas: original.max_fee_per_gas, max_priority_fee_per_gas: original.max_priority_fee_per_gas, ..self_transfer },
+                gas_price,
+            )?,
+            None => self_transfer,
+        };
+        self.send_transaction_auto_with_storage(wallet_id, account_index, cancellation, storage).await
     }
-} 
\ No newline at end of file
+
+    /// Estimate the full debit (value + gas) of a transaction and compare it against the wallet balance.
+    /// Gas price and limit set on the transaction are used as-is; missing ones are fetched from the RPC,
+    /// with the default gas limit for the operation type when estimation fails.
+    pub async fn estimate_total_cost(&self, wallet_id: &str, transaction: &Transaction) -> Result<CostEstimate, WalletError> {
+        self.ensure_online("cost estimation")?;
+        let network = {
+            let wallets = self.wallets.read().await;
+            let wallet = wallets.get(wallet_id)
+                .ok_or_else(|| WalletError::wallet_not_found(format!("Wallet not found: {}", wallet_id)))?;
+            wallet.network.clone()
+        };
+        if transaction.chain_id != network.chain_id() {
+            return Err(WalletError::invalid_field("chain_id", "Transaction chain_id does not match wallet network", "chain_mismatch"));
+        }
+
+        let value = U256::from_dec_str(&transaction.value)
+            .map_err(|_| WalletError::invalid_field("value", "Transaction value must be a decimal integer", "invalid_amount"))?;
+
+        let tx_manager = crate::core::transactions::TransactionManager::new(self.resolve_rpc_url(&network)?);
+        let gas_price = match transaction.gas_price {
+            Some(price) => price,
+            None => tx_manager.get_gas_price(network.clone()).await?,
+        };
+        let gas_limit = match transaction.gas_limit {
+            Some(limit) => limit,
+            None => tx_manager.estimate_transaction_gas_or_default(transaction, &network).await?,
+        };
+
+        let gas_cost = U256::from(gas_price) * U256::from(gas_limit);
+        let total = value + gas_cost;
+        let balance = U256::from_dec_str(&self.get_balance(wallet_id).await?)
+            .map_err(|_| WalletError::network("Invalid balance value".to_string()))?;
+
+        Ok(CostEstimate {
+            value: value.to_string(),
+            gas_limit,
+            gas_price,
+            gas_cost: gas_cost.to_string(),
+            total: total.to_string(),
+            sufficient_balance: balance >= total,
+        })
+    }
+
+    /// Import a wallet from a BIP39 mnemonic. The key at `m/44'/60'/0'/0/0` becomes the
+    /// wallet key and the mnemonic is kept encrypted under `password` for `export_seed_phrase`.
+    pub async fn import_wallet(
+        &self,
+        wallet_id: &str,
+        name: &str,
+        seed_phrase: &str,
+        password: &str,
+        network: Network,
+    ) -> Result<SecureWallet, WalletError> {
+        self.import_wallet_with_path(wallet_id, name, seed_phrase, crate::core::crypto::keys::ETHEREUM_DERIVATION_PATH, password, network).await
+    }
+
+    /// Import a wallet from a BIP39 mnemonic using the key at `derivation_path`
+    pub async fn import_wallet_with_path(
+        &self,
+        wallet_id: &str,
+        name: &str,
+        seed_phrase: &str,
+        derivation_path: &str,
+        password: &str,
+        network: Network,
+    ) -> Result<SecureWallet, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.import_wallet_with_storage(&file_storage, wallet_id, name, seed_phrase, derivation_path, password, network).await
+    }
+
+    /// BIP-44 account discovery: check the addresses `m/44'/60'/0'/0/{index}` of `seed_phrase`
+    /// in order for on-chain activity (a nonzero nonce or balance) on `network`, stopping after
+    /// `gap_limit` consecutive unused ones. Returns the used account indices with their addresses.
+    pub async fn discover_accounts(&self, seed_phrase: &str, gap_limit: u32, network: Network) -> Result<Vec<(u32, String)>, WalletError> {
+        self.ensure_online("account discovery")?;
+        if gap_limit == 0 {
+            return Err(WalletError::invalid_field("gap_limit", "Gap limit must be at least 1", "invalid_gap_limit"));
+        }
+        let seed_phrase = Zeroizing::new(seed_phrase.split_whitespace().collect::<Vec<_>>().join(" "));
+        let addresses = crate::core::crypto::keys::AccountAddresses::from_seed_phrase(&seed_phrase)?;
+        let rpc = RpcClient::new(self.resolve_rpc_url(&network)?);
+
+        let mut used = Vec::new();
+        let mut unused_run = 0;
+        let mut next_index = 0u32;
+        while unused_run < gap_limit {
+            // One batch covers the most addresses the gap limit can still require
+            let window: Vec<(u32, String)> = (next_index..next_index.saturating_add(gap_limit - unused_run))
+                .map(|index| addresses.address(index).map(|address| (index, address)))
+                .collect::<Result<_, _>>()?;
+            let calls: Vec<(&str, serde_json::Value)> = window.iter()
+                .flat_map(|(_, address)| [
+                    ("eth_getTransactionCount", serde_json::json!([address, "latest"])),
+                    ("eth_getBalance", serde_json::json!([address, "latest"])),
+                ])
+                .collect();
+            let responses = rpc.batch(&calls).await?;
+
+            for ((index, address), activity) in window.into_iter().zip(responses.chunks(2)) {
+                let mut active = false;
+                for response in activity {
+                    let quantity = response.get("result").and_then(|v| v.as_str()).ok_or_else(|| {
+                        let reason = response.get("error").map(|e| e.to_string()).unwrap_or_else(|| "no response".to_string());
+                        WalletError::network(format!("Activity lookup failed for account {}: {}", index, reason))
+                    })?;
+                    active |= Self::hex_to_decimal(quantity) != "0";
+                }
+                if active {
+                    used.push((index, address));
+                    unused_run = 0;
+                } else {
+                    unused_run += 1;
+                    if unused_run == gap_limit {
+                        break;
+                    }
+                }
+                next_index = index + 1;
+            }
+        }
+        Ok(used)
+    }
+
+    /// Return the mnemonic a wallet was imported from, after checking `password`
+    pub async fn export_seed_phrase(&self, wallet_id: &str, password: &str) -> Result<String, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.export_seed_phrase_with_storage(&file_storage, wallet_id, password).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn import_wallet_with_storage(
+        &self,
+        storage: &dyn PlatformStorage,
+        wallet_id: &str,
+        name: &str,
+        seed_phrase: &str,
+        derivation_path: &str,
+        password: &str,
+        network: Network,
+    ) -> Result<SecureWallet, WalletError> {
+        if wallet_id.trim().is_empty() {
+            return Err(WalletError::invalid_field("wallet_id", "Wallet ID cannot be empty", "required"));
+        }
+        if name.trim().is_empty() {
+            return Err(WalletError::invalid_field("name", "Wallet name cannot be empty", "required"));
+        }
+        crate::shared::utils::validate_password(password)?;
+        if self.wallets.read().await.contains_key(wallet_id) {
+            return Err(WalletError::invalid_field("wallet_id", format!("Wallet already exists: {}", wallet_id), "already_exists"));
+        }
+        let seed_phrase = Zeroizing::new(seed_phrase.split_whitespace().collect::<Vec<_>>().join(" "));
+
+        let key_manager = crate::core::crypto::keys::KeyManager::new(storage);
+        let private_key = key_manager.derive_private_key_from_seed_with_path(&seed_phrase, derivation_path, &format!("wallet_key_{}", wallet_id))?;
+        let address = key_manager.get_address(&key_manager.get_public_key(&private_key)?)?;
+        crate::core::storage::SecureStorage::new(storage)
+            .store_data(&format!("wallet_seed_{}", wallet_id), seed_phrase.as_bytes(), password)
+            .await?;
+        storage.store(&derivation_path_key(wallet_id), derivation_path.trim().as_bytes())?;
+
+        let wallet = SecureWallet::new(wallet_id.to_string(), name.to_string(), address, network.clone());
+        Self::persist_wallet_info(storage, &wallet)?;
+        self.wallets.write().await.insert(
+            wallet_id.to_string(),
+            SecureWallet::new(wallet.id.clone(), wallet.name.clone(), wallet.address.clone(), wallet.network.clone()),
+        );
+
+        let currency = network.native_currency().to_string();
+        let balance = WalletBalance::new(wallet_id.to_string(), network, "0".to_string(), currency);
+        self.balances.write().await.insert(wallet_id.to_string(), balance);
+
+        Ok(wallet)
+    }
+
+    async fn export_seed_phrase_with_storage(&self, storage: &dyn PlatformStorage, wallet_id: &str, password: &str) -> Result<String, WalletError> {
+        if !self.wallets.read().await.contains_key(wallet_id) {
+            return Err(WalletError::wallet_not_found(format!("Wallet not found: {}", wallet_id)));
+        }
+        let seed_key = format!("wallet_seed_{}", wallet_id);
+        if !storage.exists(&seed_key)? {
+            return Err(WalletError::invalid_field("wallet_id", "Wallet was not imported from a seed phrase", "no_seed_phrase"));
+        }
+
+        // AES-GCM authentication fails on a wrong password
+        let plaintext = Zeroizing::new(crate::core::storage::SecureStorage::new(storage)
+            .retrieve_data(&seed_key, password)
+            .await
+            .map_err(|_| WalletError::invalid_field("password", "Incorrect password", "invalid_password"))?);
+        String::from_utf8(plaintext.to_vec())
+            .map_err(|_| WalletError::storage("Stored seed phrase is corrupted".to_string()))
+    }
+
+    /// Derive account `account_index` from the seed phrase of an imported wallet, decrypted with
+    /// `password`, and store its key for signing. Accounts are numbered from the wallet's own
+    /// derivation path: account 0 is its key and account `i` the `i`-th sibling after it, so a
+    /// wallet at `m/44'/60'/0'/0/0` gets `m/44'/60'/0'/0/{account_index}`. Adding an account
+    /// that exists returns it unchanged.
+    pub async fn add_account(&self, wallet_id: &str, account_index: u32, password: &str) -> Result<WalletAccount, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.add_account_with_storage(&file_storage, wallet_id, account_index, password).await
+    }
+
+    /// Accounts of a wallet by index: its own address as account 0, then those added with `add_account`
+    pub async fn list_accounts(&self, wallet_id: &str) -> Result<Vec<WalletAccount>, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.list_accounts_with_storage(&file_storage, wallet_id).await
+    }
+
+    async fn add_account_with_storage(&self, storage: &dyn PlatformStorage, wallet_id: &str, account_index: u32, password: &str) -> Result<WalletAccount, WalletError> {
+        let accounts = self.list_accounts_with_storage(storage, wallet_id).await?;
+        if let Some(account) = accounts.into_iter().find(|account| account.index == account_index) {
+            return Ok(account);
+        }
+
+        let seed_phrase = Zeroizing::new(self.export_seed_phrase_with_storage(storage, wallet_id, password).await?);
+        // Wallets imported before their path was recorded used the standard one
+        let base_path = match storage.exists(&derivation_path_key(wallet_id))? {
+            true => String::from_utf8(storage.retrieve(&derivation_path_key(wallet_id))?)
+                .map_err(|_| WalletError::storage("Stored derivation path is corrupted".to_string()))?,
+            false => crate::core::crypto::keys::ETHEREUM_DERIVATION_PATH.to_string(),
+        };
+        let derivation_path = crate::core::crypto::keys::account_derivation_path_from(&base_path, account_index)?;
+        let key_manager = crate::core::crypto::keys::KeyManager::new(storage);
+        let private_key = key_manager.derive_private_key_from_seed_with_path(&seed_phrase, &derivation_path, &account_key_id(wallet_id, account_index))?;
+        let account = WalletAccount {
+            index: account_index,
+            address: key_manager.get_address(&key_manager.get_public_key(&private_key)?)?,
+        };
+
+        let _guard = WALLET_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut added = Self::read_added_accounts(storage, wallet_id)?;
+        if !added.iter().any(|existing| existing.index == account_index) {
+            added.push(account.clone());
+            added.sort_by_key(|existing| existing.index);
+            storage.store(&accounts_key(wallet_id), &serde_json::to_vec(&added)?)?;
+        }
+        Ok(account)
+    }
+
+    async fn list_accounts_with_storage(&self, storage: &dyn PlatformStorage, wallet_id: &str) -> Result<Vec<WalletAccount>, WalletError> {
+        let address = self.get_wallet(wallet_id).await?.address;
+        let mut accounts = vec![WalletAccount { index: 0, address }];
+        accounts.extend(Self::read_added_accounts(storage, wallet_id)?);
+        Ok(accounts)
+    }
+
+    fn read_added_accounts(storage: &dyn PlatformStorage, wallet_id: &str) -> Result<Vec<WalletAccount>, WalletError> {
+        let key = accounts_key(wallet_id);
+        if !storage.exists(&key)? {
+            return Ok(Vec::new());
+        }
+        serde_json::from_slice(&storage.retrieve(&key)?)
+            .map_err(|e| WalletError::storage(format!("Account list of wallet {} is corrupted: {}", wallet_id, e)))
+    }
+
+    /// Key id and address of account `account_index` of a wallet
+    async fn account_key(&self, storage: &dyn PlatformStorage, wallet_id: &str, account_index: u32) -> Result<(String, String), WalletError> {
+        let accounts = self.list_accounts_with_storage(storage, wallet_id).await?;
+        let account = accounts.into_iter()
+            .find(|account| account.index == account_index)
+            .ok_or_else(|| WalletError::invalid_field(
+                "account_index",
+                format!("Account {} has not been added to wallet {}", account_index, wallet_id),
+                "unknown_account",
+            ))?;
+        Ok((account_key_id(wallet_id, account_index), account.address))
+    }
+
+    /// Export every wallet and its private key as a single password-encrypted bundle
+    pub async fn export_all(&self, password: &str) -> Result<String, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.export_all_with_storage(&file_storage, password).await
+    }
+
+    /// Restore all wallets from a bundle produced by `export_all`.
+    /// The whole bundle is decrypted and checked before any wallet is imported.
+    pub async fn import_all(&self, bundle: &str, password: &str) -> Result<Vec<String>, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.import_all_with_storage(&file_storage, bundle, password).await
+    }
+
+    /// Split a password-encrypted backup of one wallet into `shares` Shamir shares of its
+    /// key, any `threshold` of which restore it via `restore_from_shares`
+    pub async fn split_backup(&self, wallet_id: &str, password: &str, shares: u8, threshold: u8) -> Result<SplitBackup, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.split_backup_with_storage(&file_storage, wallet_id, password, shares, threshold).await
+    }
+
+    /// Restore the wallet in `backup` from at least its threshold of shares. Returns the wallet id.
+    pub async fn restore_from_shares(&self, shares: &[String], backup: &str) -> Result<String, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.restore_from_shares_with_storage(&file_storage, shares, backup).await
+    }
+
+    /// Exporting a biometric-protected wallet's key requires the biometric prompt
+    fn bundle_entry(&self, storage: &dyn PlatformStorage, wallet: &SecureWallet) -> Result<WalletBundleEntry, WalletError> {
+        let private_key = self.key_manager(storage).get_private_key(&format!("wallet_key_{}", wallet.id))?;
+        let private_key = private_key.with_key(storage, |key_bytes| Ok(hex::encode(key_bytes)))?;
+        Ok(WalletBundleEntry {
+            id: wallet.id.clone(),
+            name: wallet.name.clone(),
+            address: wallet.address.clone(),
+            network: wallet.network.clone(),
+            created_at: wallet.created_at,
+            private_key,
+        })
+    }
+
+    async fn encrypt_entries(storage: &dyn PlatformStorage, entries: &[WalletBundleEntry], password: &str) -> Result<Vec<u8>, WalletError> {
+        let plaintext = Zeroizing::new(serde_json::to_vec(entries)
+            .map_err(|e| WalletError::validation(format!("Bundle serialization failed: {}", e)))?);
+        crate::core::storage::SecureStorage::new(storage)
+            .encrypt_data(&plaintext, password)
+            .await
+    }
+
+    async fn split_backup_with_storage(
+        &self,
+        storage: &dyn PlatformStorage,
+        wallet_id: &str,
+        password: &str,
+        shares: u8,
+        threshold: u8,
+    ) -> Result<SplitBackup, WalletError> {
+        crate::shared::utils::validate_password(password)?;
+        SecurityAuditor::warn_if_weak_password(password, "backup");
+        if threshold < 2 {
+            return Err(WalletError::invalid_field("threshold", "Threshold must be at least 2", "out_of_range"));
+        }
+        if shares < threshold {
+            return Err(WalletError::invalid_field("shares", "Share count cannot be below the threshold", "out_of_range"));
+        }
+
+        let entry = {
+            let wallets = self.wallets.read().await;
+            let wallet = wallets.get(wallet_id)
+                .ok_or_else(|| WalletError::wallet_not_found(format!("Wallet not found: {}", wallet_id)))?;
+            self.bundle_entry(storage, wallet)?
+        };
+        let encrypted = Self::encrypt_entries(storage, std::slice::from_ref(&entry), password).await?;
+
+        // The secret is the derived AES key, so the shares never reveal the password itself
+        let key = crate::core::storage::SecureStorage::derive_key(password, &encrypted)?;
+        let dealer = blahaj::Sharks(threshold).dealer_rng(&key[..], &mut rand::rngs::OsRng);
+        let shares = dealer
+            .take(shares as usize)
+            .map(|share| hex::encode(Zeroizing::new(Vec::from(&share)).as_slice()))
+            .collect::<Vec<_>>();
+
+        let backup = serde_json::to_string(&ShareBackup {
+            version: SHARE_BACKUP_VERSION.to_string(),
+            wallet_id: wallet_id.to_string(),
+            threshold,
+            data: STANDARD.encode(&encrypted),
+        })
+        .map_err(|e| WalletError::validation(format!("Backup serialization failed: {}", e)))?;
+
+        Ok(SplitBackup { backup, shares: Zeroizing::new(shares) })
+    }
+
+    async fn restore_from_shares_with_storage(&self, storage: &dyn PlatformStorage, shares: &[String], backup: &str) -> Result<String, WalletError> {
+        let backup: ShareBackup = serde_json::from_str(backup)
+            .map_err(|_| WalletError::invalid_field("backup", "Share backup is malformed", "invalid_format"))?;
+        if backup.version != SHARE_BACKUP_VERSION {
+            return Err(WalletError::invalid_field("backup", format!("Unsupported share backup version: {}", backup.version), "unsupported_version"));
+        }
+        let encrypted = STANDARD.decode(&backup.data)
+            .map_err(|_| WalletError::invalid_field("backup", "Share backup data is not valid base64", "invalid_format"))?;
+
+        let shares = shares
+            .iter()
+            .map(|share| {
+                let bytes = Zeroizing::new(hex::decode(share.trim())
+                    .map_err(|_| WalletError::invalid_field("shares", "Share is not valid hex", "invalid_format"))?);
+                blahaj::Share::try_from(bytes.as_slice())
+                    .map_err(|e| WalletError::invalid_field("shares", format!("Invalid share: {}", e), "invalid_format"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let key = Zeroizing::new(blahaj::Sharks(backup.threshold).recover(&shares)
+            .map_err(|e| WalletError::invalid_field("shares", e, "insufficient_shares"))?);
+        let key: &[u8; 32] = key.as_slice().try_into()
+            .map_err(|_| WalletError::invalid_field("shares", "Shares do not belong to this backup", "invalid_share"))?;
+
+        // AES-GCM authentication fails if the shares belong to another backup
+        let plaintext = Zeroizing::new(crate::core::storage::SecureStorage::decrypt_with_key(&encrypted, key)?);
+        let entries: Vec<WalletBundleEntry> = serde_json::from_slice(&plaintext)
+            .map_err(|_| WalletError::invalid_field("backup", "Share backup contents are malformed", "invalid_format"))?;
+        if entries.len() != 1 || entries[0].id != backup.wallet_id {
+            return Err(WalletError::invalid_field("backup", "Share backup does not contain the expected wallet", "invalid_entry"));
+        }
+
+        let mut imported = self.import_entries(storage, &entries).await?;
+        Ok(imported.remove(0))
+    }
+
+    async fn export_all_with_storage(&self, storage: &dyn PlatformStorage, password: &str) -> Result<String, WalletError> {
+        crate::shared::utils::validate_password(password)?;
+        SecurityAuditor::warn_if_weak_password(password, "backup");
+
+        let entries = {
+            let wallets = self.wallets.read().await;
+            wallets.values()
+                .map(|wallet| self.bundle_entry(storage, wallet))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        let encrypted = Self::encrypt_entries(storage, &entries, password).await?;
+
+        serde_json::to_string(&WalletBundle {
+            version: WALLET_BUNDLE_VERSION.to_string(),
+            data: STANDARD.encode(&encrypted),
+        })
+        .map_err(|e| WalletError::validation(format!("Bundle serialization failed: {}", e)))
+    }
+
+    async fn import_all_with_storage(&self, storage: &dyn PlatformStorage, bundle: &str, password: &str) -> Result<Vec<String>, WalletError> {
+        let bundle: WalletBundle = serde_json::from_str(bundle)
+            .map_err(|_| WalletError::invalid_field("bundle", "Wallet bundle is malformed", "invalid_format"))?;
+        if bundle.version != WALLET_BUNDLE_VERSION {
+            return Err(WalletError::invalid_field("bundle", format!("Unsupported wallet bundle version: {}", bundle.version), "unsupported_version"));
+        }
+        let encrypted = STANDARD.decode(&bundle.data)
+            .map_err(|_| WalletError::invalid_field("bundle", "Wallet bundle data is not valid base64", "invalid_format"))?;
+
+        // AES-GCM authentication fails on any tampering or a wrong password
+        let plaintext = Zeroizing::new(crate::core::storage::SecureStorage::new(storage)
+            .decrypt_data(&encrypted, password)
+            .await?);
+        let entries: Vec<WalletBundleEntry> = serde_json::from_slice(&plaintext)
+            .map_err(|_| WalletError::invalid_field("bundle", "Wallet bundle contents are malformed", "invalid_format"))?;
+        self.import_entries(storage, &entries).await
+    }
+
+    async fn import_entries(&self, storage: &dyn PlatformStorage, entries: &[WalletBundleEntry]) -> Result<Vec<String>, WalletError> {
+        // Check every entry before touching storage so a bad bundle imports nothing
+        let key_manager = crate::core::crypto::keys::KeyManager::new(storage);
+        let secp = secp256k1::Secp256k1::new();
+        let mut keys = Vec::with_capacity(entries.len());
+        {
+            let wallets = self.wallets.read().await;
+            let mut seen = std::collections::HashSet::new();
+            for entry in entries {
+                if entry.id.trim().is_empty() || !seen.insert(entry.id.as_str()) {
+                    return Err(WalletError::invalid_field("bundle", format!("Invalid or duplicate wallet id in bundle: {:?}", entry.id), "invalid_entry"));
+                }
+                if wallets.contains_key(&entry.id) {
+                    return Err(WalletError::invalid_field("bundle", format!("Wallet already exists: {}", entry.id), "already_exists"));
+                }
+                let key_bytes = Zeroizing::new(hex::decode(&entry.private_key)
+                    .map_err(|_| WalletError::invalid_field("bundle", format!("Invalid private key for wallet {}", entry.id), "invalid_entry"))?);
+                let key_array: [u8; 32] = key_bytes.as_slice().try_into()
+                    .map_err(|_| WalletError::invalid_field("bundle", format!("Invalid private key for wallet {}", entry.id), "invalid_entry"))?;
+                let secret_key = secp256k1::SecretKey::from_byte_array(key_array)
+                    .map_err(|_| WalletError::invalid_field("bundle", format!("Invalid private key for wallet {}", entry.id), "invalid_entry"))?;
+                let public_key = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+                let address = key_manager.get_address(&hex::encode(public_key.serialize_uncompressed()))?;
+                if !address.eq_ignore_ascii_case(&entry.address) {
+                    return Err(WalletError::invalid_field("bundle", format!("Private key does not match address for wallet {}", entry.id), "address_mismatch"));
+                }
+                keys.push(key_bytes);
+            }
+        }
+
+        let mut imported = Vec::with_capacity(entries.len());
+        for (entry, key_bytes) in entries.iter().zip(keys.iter()) {
+            key_manager.import_private_key(&format!("wallet_key_{}", entry.id), key_bytes)?;
+
+            let mut wallet = SecureWallet::new(entry.id.clone(), entry.name.clone(), entry.address.clone(), entry.network.clone());
+            wallet.created_at = entry.created_at;
+            Self::persist_wallet_info(storage, &wallet)?;
+            self.wallets.write().await.insert(entry.id.clone(), wallet);
+
+            let currency = entry.network.native_currency().to_string();
+            let balance = WalletBalance::new(entry.id.clone(), entry.network.clone(), "0".to_string(), currency);
+            self.balances.write().await.insert(entry.id.clone(), balance);
+
+            imported.push(entry.id.clone());
+        }
+        Ok(imported)
+    }
+
+    /// Write the wallet's public metadata and add it to the wallet index
+    fn persist_wallet_info(storage: &dyn PlatformStorage, wallet: &SecureWallet) -> Result<(), WalletError> {
+        let info = crate::domain::WalletInfo {
+            id: wallet.id.clone(),
+            name: wallet.name.clone(),
+            network: wallet.network.clone(),
+            address: wallet.address.clone(),
+            balance: "0".to_string(),
+            created_at: wallet.created_at as i64,
+        };
+        storage.store(&format!("wallet_info_{}", wallet.id), &serde_json::to_vec(&info)?)?;
+
+        let _guard = WALLET_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut index = Self::read_wallet_index(storage)?;
+        if !index.contains(&wallet.id) {
+            index.push(wallet.id.clone());
+            storage.store(WALLET_INDEX_KEY, &serde_json::to_vec(&index)?)?;
+        }
+        Ok(())
+    }
+
+    fn read_wallet_index(storage: &dyn PlatformStorage) -> Result<Vec<String>, WalletError> {
+        if !storage.exists(WALLET_INDEX_KEY)? {
+            return Ok(Vec::new());
+        }
+        serde_json::from_slice(&storage.retrieve(WALLET_INDEX_KEY)?)
+            .map_err(|e| WalletError::storage(format!("Wallet index is corrupted: {}", e)))
+    }
+
+    /// Repopulate the manager with every wallet persisted by earlier runs, unlocking file
+    /// storage with `password`. Wallets already loaded are kept as they are, and a wallet whose
+    /// key or record is missing or unreadable is skipped with a warning. Returns the ids of
+    /// the wallets added.
+    pub async fn load_all_wallets(&self, password: &str) -> Result<Vec<String>, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::with_password(password)?;
+        self.load_all_wallets_with_storage(&file_storage).await
+    }
+
+    async fn load_all_wallets_with_storage(&self, storage: &dyn PlatformStorage) -> Result<Vec<String>, WalletError> {
+        let index = {
+            let _guard = WALLET_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            Self::read_wallet_index(storage)?
+        };
+
+        let mut loaded = Vec::new();
+        for wallet_id in index {
+            if self.wallets.read().await.contains_key(&wallet_id) {
+                continue;
+            }
+            // Metadata without key material is useless; the key was deleted out of band
+            if !storage.exists(&format!("wallet_key_{}", wallet_id))? {
+                log::warn!("Skipping wallet {}: key material not found", wallet_id);
+                continue;
+            }
+            // One unreadable record must not keep the other wallets from loading
+            let info = storage.retrieve(&format!("wallet_info_{}", wallet_id)).and_then(|raw| {
+                serde_json::from_slice::<crate::domain::WalletInfo>(&raw)
+                    .map_err(|e| WalletError::storage(format!("Wallet record {} is corrupted: {}", wallet_id, e)))
+            });
+            let info = match info {
+                Ok(info) => info,
+                Err(e) => {
+                    log::warn!("Skipping wallet {}: {}", wallet_id, e);
+                    continue;
+                }
+            };
+
+            let mut wallet = SecureWallet::new(info.id.clone(), info.name, info.address, info.network.clone());
+            wallet.created_at = info.created_at.max(0) as u64;
+            self.wallets.write().await.insert(info.id.clone(), wallet);
+
+            let currency = info.network.native_currency().to_string();
+            let balance = WalletBalance::new(info.id.clone(), info.network, "0".to_string(), currency);
+            self.balances.write().await.entry(info.id.clone()).or_insert(balance);
+
+            loaded.push(info.id);
+        }
+        Ok(loaded)
+    }
+
+    /// Delete a wallet after checking `password` against its stored key. Its keys, including
+    /// those of added accounts (overwritten before removal by file storage), seed phrase, metadata
+    /// and history cache are removed from storage and the wallet from the index, then its
+    /// in-memory wallet and balance entries.
+    pub async fn delete_wallet(&self, wallet_id: &str, password: &str) -> Result<(), WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::with_password(password)?;
+        self.delete_wallet_with_storage(&file_storage, wallet_id).await
+    }
+
+    async fn delete_wallet_with_storage(&self, storage: &dyn PlatformStorage, wallet_id: &str) -> Result<(), WalletError> {
+        let key_id = format!("wallet_key_{}", wallet_id);
+        if !self.wallets.read().await.contains_key(wallet_id) && !storage.exists(&key_id)? {
+            return Err(WalletError::wallet_not_found(format!("Wallet not found: {}", wallet_id)));
+        }
+        // File storage fails authenticated decryption on a wrong password
+        if storage.exists(&key_id)? {
+            drop(Zeroizing::new(storage.retrieve(&key_id)
+                .map_err(|_| WalletError::invalid_field("password", "Incorrect password", "invalid_password"))?));
+        }
+
+        crate::core::crypto::keys::SecurePrivateKey::new(key_id).delete(storage)?;
+        for account in Self::read_added_accounts(storage, wallet_id)? {
+            crate::core::crypto::keys::SecurePrivateKey::new(account_key_id(wallet_id, account.index)).delete(storage)?;
+        }
+        for key in [format!("wallet_seed_{}", wallet_id), derivation_path_key(wallet_id), format!("wallet_info_{}", wallet_id), format!("tx_history_{}", wallet_id), accounts_key(wallet_id), sent_transactions_key(wallet_id)] {
+            storage.delete(&key)?;
+        }
+        {
+            let _guard = WALLET_INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let mut index = Self::read_wallet_index(storage)?;
+            if let Some(position) = index.iter().position(|id| id == wallet_id) {
+                index.remove(position);
+                storage.store(WALLET_INDEX_KEY, &serde_json::to_vec(&index)?)?;
+            }
+        }
+
+        self.wallets.write().await.remove(wallet_id);
+        self.balances.write().await.remove(wallet_id);
+        Ok(())
+    }
+
+    /// Transaction history of a wallet, newest first. The history is cached encrypted with
+    /// `password`; only transactions from the last synced block on are fetched from the
+    /// history source. Offline, the cached history is returned as is.
+    pub async fn get_transaction_history(&self, wallet_id: &str, password: &str) -> Result<Vec<SignedTransaction>, WalletError> {
+        let file_storage = crate::infrastructure::platform::FileStorage::new()?;
+        self.get_transaction_history_with_storage(&file_storage, wallet_id, password).await
+    }
+
+    async fn get_transaction_history_with_storage(&self, storage: &dyn PlatformStorage, wallet_id: &str, password: &str) -> Result<Vec<SignedTransaction>, WalletError> {
+        let (address, network) = {
+            let wallets = self.wallets.read().await;
+            let wallet = wallets.get(wallet_id)
+                .ok_or_else(|| WalletError::wallet_not_found(format!("Wallet not found: {}", wallet_id)))?;
+            (wallet.address.clone(), wallet.network.clone())
+        };
+
+        let cache_key = format!("tx_history_{}", wallet_id);
+        let secure_storage = crate::core::storage::SecureStorage::new(storage);
+        let mut cache: HistoryCache = if storage.exists(&cache_key)? {
+            serde_json::from_slice(&secure_storage.retrieve_data(&cache_key, password).await?)
+                .map_err(|e| WalletError::storage(format!("Transaction history cache is corrupted: {}", e)))?
+        } else {
+            HistoryCache::default()
+        };
+
+        let Some(source) = self.history_source.as_ref().filter(|_| !self.offline) else {
+            if cache.last_synced_at.is_none() {
+                if self.offline {
+                    return Err(WalletError::offline("get_transaction_history"));
+                }
+                // JSON-RPC alone cannot query transactions by address
+                return Err(WalletError::not_implemented("Transaction history requires an indexer; none is configured"));
+            }
+            return Ok(cache.transactions());
+        };
+
+        let fetched = source.fetch_since(&address, &network, cache.last_synced_block).await?;
+        cache.merge(fetched, chrono::Utc::now().timestamp().max(0) as u64);
+        let serialized = serde_json::to_vec(&cache)
+            .map_err(|e| WalletError::storage(format!("Transaction history serialization failed: {}", e)))?;
+        secure_storage.store_data(&cache_key, &serialized, password).await?;
+        Ok(cache.transactions())
+    }
+
+    /// Update wallet balance (uses wallet's configured network and currency)
+    pub async fn update_balance(&self, wallet_id: &str, balance: String) -> Result<(), WalletError> {
+        let (network, currency) = {
+            let wallets = self.wallets.read().await;
+            if let Some(wallet) = wallets.get(wallet_id) {
+                let n = wallet.network.clone();
+                let c = n.native_currency().to_string();
+                (n, c)
+            } else {
+                // Fallback if wallet not found
+                let n = Network::CoreTestnet;
+                (n.clone(), n.native_currency().to_string())
+            }
+        };
+
+        let mut balances = self.balances.write().await;
+        let wallet_balance = WalletBalance::new(
+            wallet_id.to_string(),
+            network,
+            balance,
+            currency,
+        );
+        balances.insert(wallet_id.to_string(), wallet_balance);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::mock_http::MockServer;
+    use crate::shared::test_doubles::{MemoryStorage, MockBiometricAuth, PasswordStorage};
+
+
+    #[tokio::test]
+    async fn test_wallet_manager_creation() {
+        let _manager = WalletManager::new();
+        // Test that the manager can be created successfully
+        assert!(true); // Basic creation test passed
+    }
+
+    #[tokio::test]
+    async fn test_wallet_balance_update() {
+        let manager = WalletManager::new();
+        
+        // Create a test wallet first
+        let _wallet = manager.create_wallet("test_wallet", "Test Wallet", Network::CoreTestnet).await
+            .expect("Failed to create test wallet");
+        
+        // Update the balance
+        manager.update_balance("test_wallet", "1000000".to_string()).await
+            .expect("Failed to update wallet balance");
+        
+        // Get the balance from cache (not from blockchain)
+        let balances = manager.balances.read().await;
+        if let Some(balance) = balances.get("test_wallet") {
+            assert_eq!(balance.amount, "1000000");
+        } else {
+            panic!("Balance not found in cache");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wallet_not_found() {
+        let manager = WalletManager::new();
+        let result = manager.get_wallet("nonexistent_wallet").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_wallet_rejects_empty_name() {
+        let manager = WalletManager::new();
+        let err = manager.create_wallet("named_wallet", " ", Network::CoreTestnet).await
+            .err().expect("empty name should be rejected");
+        assert_eq!(err.validation_details().map(|d| d.field.as_str()), Some("name"));
+    }
+
+    #[tokio::test]
+    async fn test_sign_typed_round_trip_with_custom_struct() {
+        use crate::core::crypto::signatures::Eip712DomainType;
+
+        let manager = WalletManager::new();
+        manager.create_wallet("typed_data_wallet", "Typed Data Wallet", Network::CoreTestnet).await.unwrap();
+
+        let field = |name: &str, r#type: &str| Eip712DomainType { name: name.to_string(), r#type: r#type.to_string() };
+        let mut types = Eip712Types::new();
+        types.insert("Item".to_string(), vec![field("sku", "string"), field("quantity", "uint32")]);
+        types.insert(
+            "Order".to_string(),
+            vec![field("merchant", "address"), field("items", "Item[]"), field("total", "uint256")],
+        );
+        let domain = Eip712Domain {
+            name: Some("AirChainPay Orders".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some(U256::from(Network::CoreTestnet.chain_id())),
+            verifying_contract: None,
+            salt: None,
+        };
+        let message = serde_json::json!({
+            "merchant": "0x1111111111111111111111111111111111111111",
+            "items": [{ "sku": "coffee", "quantity": 2 }, { "sku": "bagel", "quantity": 1 }],
+            "total": "4500000000000000"
+        });
+
+        let signature = manager.sign_typed("typed_data_wallet", &domain, &types, "Order", &message).await.unwrap();
+        assert_eq!(signature.len(), 2 + 65 * 2);
+        assert!(manager.verify_typed("typed_data_wallet", &domain, &types, "Order", &message, &signature).await.unwrap());
+
+        let mut tampered = message.clone();
+        tampered["total"] = serde_json::json!("1");
+        assert!(!manager.verify_typed("typed_data_wallet", &domain, &types, "Order", &tampered, &signature).await.unwrap());
+
+        let other_chain = Eip712Domain { chain_id: Some(U256::from(1u64)), ..domain.clone() };
+        assert!(manager.sign_typed("typed_data_wallet", &other_chain, &types, "Order", &message).await.is_err());
+    }
+
+    /// Minimal JSON-RPC server answering by method name; balances are keyed by address
+    async fn spawn_mock_rpc(gas_price: u64, gas_limit: u64, balances: Vec<(String, U256)>) -> String {
+        MockServer::json_rpc_calls(move |request| {
+            // Calls with `0xdeadbeef` calldata revert with a reason
+            if request["method"] == "eth_call" && request["params"][0]["data"] == "0xdeadbeef" {
+                let reason = ethers::abi::encode(&[ethers::abi::Token::String("Not allowed".to_string())]);
+                let data = format!("0x08c379a0{}", hex::encode(reason));
+                return serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": 3, "message": "execution reverted", "data": data } });
+            }
+            let result = match request["method"].as_str().unwrap_or_default() {
+                "eth_gasPrice" => format!("0x{:x}", gas_price),
+                "eth_estimateGas" => format!("0x{:x}", gas_limit),
+                "eth_getCode" | "eth_call" => "0x".to_string(),
+                "eth_getBalance" => {
+                    let address = request["params"][0].as_str().unwrap_or_default();
+                    let balance = balances.iter()
+                        .find(|(a, _)| a == address)
+                        .map(|(_, b)| *b)
+                        .unwrap_or_default();
+                    format!("0x{:064x}", balance)
+                }
+                _ => "0x0".to_string(),
+            };
+            serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+        }).await.url
+    }
+
+    #[tokio::test]
+    async fn test_estimate_total_cost() {
+        let rich = "0x1111111111111111111111111111111111111111".to_string();
+        let poor = "0x2222222222222222222222222222222222222222".to_string();
+        let url = spawn_mock_rpc(
+            2_000_000_000,
+            21_000,
+            vec![(rich.clone(), U256::exp10(18)), (poor.clone(), U256::from(1_000_000u64))],
+        ).await;
+        let manager = WalletManager::new().with_rpc_url(&Network::EthereumHolesky, url);
+        {
+            let mut wallets = manager.wallets.write().await;
+            wallets.insert("rich".to_string(), SecureWallet::new("rich".to_string(), "Rich".to_string(), rich.clone(), Network::EthereumHolesky));
+            wallets.insert("poor".to_string(), SecureWallet::new("poor".to_string(), "Poor".to_string(), poor.clone(), Network::EthereumHolesky));
+        }
+
+        let tx = Transaction {
+            to: "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
+            value: "1000000000000000".to_string(),
+            data: None,
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+            chain_id: Network::EthereumHolesky.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+
+        let estimate = manager.estimate_total_cost("rich", &tx).await
+            .expect("Failed to estimate cost");
+        assert_eq!(estimate.gas_price, 2_000_000_000);
+        assert_eq!(estimate.gas_limit, 21_000);
+        assert_eq!(estimate.gas_cost, "42000000000000");
+        assert_eq!(estimate.total, "1042000000000000");
+        assert!(estimate.sufficient_balance);
+
+        // Preset gas fields skip the RPC lookups
+        let preset = Transaction { gas_limit: Some(50_000), gas_price: Some(1), ..tx.clone() };
+        let estimate = manager.estimate_total_cost("poor", &preset).await
+            .expect("Failed to estimate cost");
+        assert_eq!(estimate.total, "1000000000050000");
+        assert!(!estimate.sufficient_balance);
+    }
+
+    /// History source serving fixed entries and recording the start block of every fetch
+    #[derive(Default)]
+    struct FakeHistorySource {
+        entries: std::sync::Mutex<Vec<HistoryEntry>>,
+        fetches: std::sync::Mutex<Vec<Option<u64>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl TransactionHistorySource for FakeHistorySource {
+        async fn fetch_since(&self, _address: &str, _network: &Network, from_block: Option<u64>) -> Result<Vec<HistoryEntry>, WalletError> {
+            self.fetches.lock().unwrap().push(from_block);
+            Ok(self.entries.lock().unwrap().iter()
+                .filter(|e| from_block.map_or(true, |from| e.block_number >= from))
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn history_entry(hash: &str, block_number: u64) -> HistoryEntry {
+        let transaction = Transaction {
+            to: "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
+            value: "1".to_string(),
+            data: None,
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+            chain_id: 1114,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        HistoryEntry {
+            transaction: SignedTransaction { transaction, signature: Vec::new(), hash: hash.to_string(), warnings: Vec::new() },
+            block_number,
+            timestamp: 1_700_000_000 + block_number,
+        }
+    }
+
+    /// Generate a key in `storage` and register the matching wallet with `manager`
+    async fn add_wallet(manager: &WalletManager, storage: &MemoryStorage, id: &str, network: Network) -> String {
+        let key_manager = crate::core::crypto::keys::KeyManager::new(storage);
+        let key = key_manager.generate_private_key(&format!("wallet_key_{}", id)).unwrap();
+        let address = key_manager.get_address(&key_manager.get_public_key(&key).unwrap()).unwrap();
+        manager.wallets.write().await.insert(
+            id.to_string(),
+            SecureWallet::new(id.to_string(), format!("Wallet {}", id), address.clone(), network),
+        );
+        address
+    }
+
+    #[tokio::test]
+    async fn test_delete_wallet_removes_memory_and_storage() {
+        let manager = WalletManager::new();
+        let memory = MemoryStorage::default();
+        let storage = PasswordStorage::new(&memory, "storage-password");
+        manager.create_wallet_with_storage(&storage, "doomed", "Doomed", Network::CoreTestnet).await.unwrap();
+        manager.create_wallet_with_storage(&storage, "kept", "Kept", Network::CoreTestnet).await.unwrap();
+        storage.store("tx_history_doomed", b"cache").unwrap();
+
+        // Under the wrong password nothing is deleted
+        let wrong = PasswordStorage::new(&memory, "wrong-password");
+        let err = manager.delete_wallet_with_storage(&wrong, "doomed").await.unwrap_err();
+        assert!(err.to_string().contains("Incorrect password"), "{}", err);
+        assert!(manager.get_wallet("doomed").await.is_ok());
+        assert!(storage.exists("wallet_key_doomed").unwrap());
+
+        manager.delete_wallet_with_storage(&storage, "doomed").await.unwrap();
+        assert!(manager.get_wallet("doomed").await.is_err());
+        assert!(!manager.balances.read().await.contains_key("doomed"));
+        let remaining = storage.list_keys().unwrap();
+        assert!(remaining.iter().all(|key| !key.contains("doomed")), "{:?}", remaining);
+        assert_eq!(WalletManager::read_wallet_index(&storage).unwrap(), ["kept"]);
+        assert!(manager.get_wallet("kept").await.is_ok());
+
+        let err = manager.delete_wallet_with_storage(&storage, "doomed").await.unwrap_err();
+        assert!(matches!(err, WalletError::WalletNotFound(_)), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_history_syncs_incrementally() {
+        let source = Arc::new(FakeHistorySource::default());
+        source.entries.lock().unwrap().extend([history_entry("0xaa", 10), history_entry("0xbb", 12)]);
+        let manager = WalletManager::new().with_history_source(source.clone());
+        let storage = MemoryStorage::default();
+        add_wallet(&manager, &storage, "history", Network::CoreTestnet).await;
+
+        let first = manager.get_transaction_history_with_storage(&storage, "history", "history-password").await.unwrap();
+        assert_eq!(first.iter().map(|t| t.hash.as_str()).collect::<Vec<_>>(), ["0xbb", "0xaa"]);
+
+        // Block 12 is fetched again in case it was only partly indexed; its entry is not duplicated
+        source.entries.lock().unwrap().extend([history_entry("0xBB", 12), history_entry("0xcc", 15)]);
+        let second = manager.get_transaction_history_with_storage(&storage, "history", "history-password").await.unwrap();
+        assert_eq!(second.iter().map(|t| t.hash.as_str()).collect::<Vec<_>>(), ["0xcc", "0xbb", "0xaa"]);
+        assert_eq!(*source.fetches.lock().unwrap(), [None, Some(12)]);
+
+        // The cache is persisted encrypted: offline, a fresh manager serves it without fetching
+        let offline = WalletManager::new().with_history_source(source.clone()).with_offline_mode(true);
+        offline.wallets.write().await.insert("history".to_string(), manager.get_wallet("history").await.unwrap());
+        let cached = offline.get_transaction_history_with_storage(&storage, "history", "history-password").await.unwrap();
+        assert_eq!(cached.len(), 3);
+        assert_eq!(source.fetches.lock().unwrap().len(), 2);
+        assert!(offline.get_transaction_history_with_storage(&storage, "history", "wrong-password").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_transaction_auto_fills_and_signs() {
+        let url = spawn_mock_rpc(1_500_000_000, 30_000, Vec::new()).await;
+        let manager = WalletManager::new()
+            .with_rpc_url(&Network::LiskSepolia, url)
+            .with_pre_broadcast_simulation(true);
+        let storage = MemoryStorage::default();
+        add_wallet(&manager, &storage, "auto", Network::LiskSepolia).await;
+        let tx = Transaction {
+            to: "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
+            value: "1000".to_string(),
+            data: None,
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+            chain_id: Network::LiskSepolia.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+
+        let signed = manager.send_transaction_auto_with_storage("auto", 0, tx, &storage).await
+            .expect("auto send should fill the missing fields");
+        assert_eq!(signed.transaction.nonce, Some(0));
+        assert_eq!(signed.transaction.gas_limit, Some(30_000));
+        assert_eq!(signed.transaction.gas_price, Some(1_500_000_000));
+        assert!(!signed.signature.is_empty());
+        assert!(signed.warnings.is_empty());
+
+        // Simulation stops a reverting call before it is signed
+        let reverting = Transaction { data: Some(vec![0xde, 0xad, 0xbe, 0xef]), ..signed.transaction.clone() };
+        let err = manager.send_transaction_auto_with_storage("auto", 0, reverting, &storage).await.unwrap_err();
+        assert!(err.to_string().contains("would revert: Not allowed"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_send_to_contract_returns_recipient_warning() {
+        let url = MockServer::json_rpc_calls(|request| {
+            let result = match request["method"].as_str().unwrap_or_default() {
+                "eth_getCode" => "0x6080604052",
+                "eth_gasPrice" => "0x3b9aca00",
+                "eth_estimateGas" => "0x5208",
+                _ => "0x0",
+            };
+            serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+        }).await.url;
+        let manager = WalletManager::new().with_rpc_url(&Network::LiskSepolia, url);
+        let storage = MemoryStorage::default();
+        add_wallet(&manager, &storage, "contract", Network::LiskSepolia).await;
+        let tx = Transaction {
+            to: "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
+            value: "1000".to_string(),
+            data: None,
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+            chain_id: Network::LiskSepolia.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+
+        let signed = manager.send_transaction_auto_with_storage("contract", 0, tx, &storage).await.unwrap();
+        assert_eq!(signed.warnings.len(), 1);
+        assert!(signed.warnings[0].contains("is a contract"), "{}", signed.warnings[0]);
+    }
+
+    #[tokio::test]
+    async fn test_speed_up_and_cancel_replace_at_same_nonce() {
+        let url = spawn_mock_rpc(1_000, 21_000, Vec::new()).await;
+        let network = Network::Custom { chain_id: 31337, rpc_url: url, native_currency: "GO".to_string(), name: "Devnet".to_string() };
+        let manager = WalletManager::new();
+        let storage = MemoryStorage::default();
+        manager.import_wallet_with_storage(&storage, "stuck", "Stuck", TEST_MNEMONIC, ETHEREUM_DERIVATION_PATH, "seed-password", network.clone()).await.unwrap();
+        let account = manager.add_account_with_storage(&storage, "stuck", 1, "seed-password").await.unwrap();
+        let tx = Transaction {
+            to: "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
+            value: "1000".to_string(),
+            data: Some(vec![0x01]),
+            gas_limit: Some(30_000),
+            gas_price: Some(1_000),
+            nonce: Some(4),
+            chain_id: network.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        let original = manager.send_transaction_auto_with_storage("stuck", 1, tx, &storage).await.unwrap();
+
+        let err = manager.speed_up_transaction_with_storage(&storage, "stuck", 1, 4, 1_099).await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "replacement_underpriced");
+        let err = manager.speed_up_transaction_with_storage(&storage, "stuck", 1, 9, 5_000).await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "unknown_nonce");
+        // The primary account sent nothing at that nonce
+        let err = manager.speed_up_transaction_with_storage(&storage, "stuck", 0, 4, 5_000).await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "unknown_nonce");
+
+        // The original is persisted, so a restarted manager can still replace it
+        let restarted = WalletManager::new();
+        restarted.load_all_wallets_with_storage(&storage).await.unwrap();
+        let faster = restarted.speed_up_transaction_with_storage(&storage, "stuck", 1, 4, 1_100).await.unwrap();
+        assert_eq!(faster.transaction.gas_price, Some(1_100));
+        assert_eq!(faster.transaction.nonce, Some(4));
+        assert_eq!((&faster.transaction.to, &faster.transaction.value, &faster.transaction.data), (&original.transaction.to, &original.transaction.value, &original.transaction.data));
+        assert_ne!(faster.signature, original.signature);
+        assert_eq!(WalletManager::read_sent_transactions(&storage, "stuck").unwrap().len(), 1);
+
+        // The sped-up transaction is now the one to outbid
+        let err = manager.cancel_transaction_with_storage(&storage, "stuck", 1, 4, 1_200).await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "replacement_underpriced");
+        let cancel = manager.cancel_transaction_with_storage(&storage, "stuck", 1, 4, 1_210).await.unwrap();
+        assert_eq!(cancel.transaction.to, account.address);
+        assert_eq!(cancel.transaction.value, "0");
+        assert_eq!(cancel.transaction.data, None);
+        assert_eq!(cancel.transaction.nonce, Some(4));
+        assert_eq!(cancel.transaction.gas_limit, Some(21_000));
+        assert_eq!(cancel.transaction.gas_price, Some(1_210));
+
+        // Without a record of the original the node judges the price
+        let unknown = manager.cancel_transaction_with_storage(&storage, "stuck", 1, 5, 1).await.unwrap();
+        assert_eq!(unknown.transaction.nonce, Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_sent_transactions_are_pruned_once_mined() {
+        let mined = Arc::new(std::sync::atomic::AtomicU64::new(0));
+        let count = Arc::clone(&mined);
+        let rpc = MockServer::json_rpc_calls(move |call| {
+            let result = match call["method"].as_str().unwrap_or_default() {
+                "eth_getTransactionCount" => format!("0x{:x}", count.load(std::sync::atomic::Ordering::SeqCst)),
+                _ => "0x3e8".to_string(),
+            };
+            serde_json::json!({ "jsonrpc": "2.0", "id": call["id"], "result": result })
+        }).await;
+        let network = Network::Custom { chain_id: 31337, rpc_url: rpc.url.clone(), native_currency: "GO".to_string(), name: "Devnet".to_string() };
+        let manager = WalletManager::new();
+        let storage = MemoryStorage::default();
+        add_wallet(&manager, &storage, "busy", network.clone()).await;
+
+        for nonce in 0..3 {
+            let tx = Transaction {
+                to: "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(),
+                value: "1".to_string(),
+                data: None,
+                gas_limit: Some(21_000),
+                gas_price: Some(1_000),
+                nonce: Some(nonce),
+                chain_id: network.chain_id(),
+                max_fee_per_gas: None,
+                max_priority_fee_per_gas: None,
+            };
+            if nonce == 2 {
+                mined.store(2, std::sync::atomic::Ordering::SeqCst);
+            }
+            manager.send_transaction_auto_with_storage("busy", 0, tx, &storage).await.unwrap();
+        }
+
+        let sent = WalletManager::read_sent_transactions(&storage, "busy").unwrap();
+        assert_eq!(sent.iter().map(|entry| entry.transaction.nonce).collect::<Vec<_>>(), vec![Some(2)]);
+        let err = manager.speed_up_transaction_with_storage(&storage, "busy", 0, 1, 5_000).await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "unknown_nonce");
+    }
+
+    #[tokio::test]
+    async fn test_export_import_all_round_trip() {
+        let source_storage = MemoryStorage::default();
+        let source = WalletManager::new();
+        let first = add_wallet(&source, &source_storage, "first", Network::CoreTestnet).await;
+        let second = add_wallet(&source, &source_storage, "second", Network::BaseSepolia).await;
+
+        let bundle = source.export_all_with_storage(&source_storage, "bundle-password").await
+            .expect("Failed to export wallets");
+        assert!(!bundle.contains(&hex::encode(source_storage.retrieve("wallet_key_first").unwrap())));
+
+        let target_storage = MemoryStorage::default();
+        let target = WalletManager::new();
+        let mut imported = target.import_all_with_storage(&target_storage, &bundle, "bundle-password").await
+            .expect("Failed to import wallets");
+        imported.sort();
+        assert_eq!(imported, vec!["first".to_string(), "second".to_string()]);
+
+        assert_eq!(target.get_wallet("first").await.unwrap().address, first);
+        assert_eq!(target.get_wallet("second").await.unwrap().address, second);
+        assert!(matches!(target.get_wallet("second").await.unwrap().network, Network::BaseSepolia));
+        assert_eq!(
+            target_storage.retrieve("wallet_key_first").unwrap(),
+            source_storage.retrieve("wallet_key_first").unwrap()
+        );
+
+        // Wrong password is rejected
+        let other = WalletManager::new();
+        assert!(other.import_all_with_storage(&MemoryStorage::default(), &bundle, "wrong-password").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_split_backup_restores_from_threshold_shares() {
+        let source_storage = MemoryStorage::default();
+        let source = WalletManager::new();
+        let address = add_wallet(&source, &source_storage, "vault", Network::CoreTestnet).await;
+
+        let split = source.split_backup_with_storage(&source_storage, "vault", "backup-password", 5, 3).await
+            .expect("Failed to split backup");
+        assert_eq!(split.shares.len(), 5);
+        assert!(!format!("{:?}", split).contains(&split.shares[0]));
+        assert!(!split.backup.contains(&hex::encode(source_storage.retrieve("wallet_key_vault").unwrap())));
+
+        // Any three shares, in any order, are enough
+        let chosen = vec![split.shares[4].clone(), split.shares[0].clone(), split.shares[2].clone()];
+        let target_storage = MemoryStorage::default();
+        let target = WalletManager::new();
+        let restored = target.restore_from_shares_with_storage(&target_storage, &chosen, &split.backup).await
+            .expect("Failed to restore from shares");
+        assert_eq!(restored, "vault");
+        assert_eq!(target.get_wallet("vault").await.unwrap().address, address);
+        assert_eq!(
+            target_storage.retrieve("wallet_key_vault").unwrap(),
+            source_storage.retrieve("wallet_key_vault").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_every_threshold_subset_of_shares_reconstructs_the_backup_key() {
+        let source_storage = MemoryStorage::default();
+        let source = WalletManager::new();
+        add_wallet(&source, &source_storage, "vault", Network::CoreTestnet).await;
+        let split = source.split_backup_with_storage(&source_storage, "vault", "backup-password", 5, 3).await.unwrap();
+
+        let backup: ShareBackup = serde_json::from_str(&split.backup).unwrap();
+        let encrypted = STANDARD.decode(&backup.data).unwrap();
+        let key = crate::core::storage::SecureStorage::derive_key("backup-password", &encrypted).unwrap();
+        let shares = split.shares.iter()
+            .map(|share| blahaj::Share::try_from(hex::decode(share).unwrap().as_slice()).unwrap())
+            .collect::<Vec<_>>();
+
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset = [shares[a].clone(), shares[b].clone(), shares[c].clone()];
+                    assert_eq!(blahaj::Sharks(3).recover(&subset).unwrap(), key[..], "shares {} {} {}", a, b, c);
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restore_from_shares_fails_below_threshold() {
+        let source_storage = MemoryStorage::default();
+        let source = WalletManager::new();
+        add_wallet(&source, &source_storage, "vault", Network::CoreTestnet).await;
+        let split = source.split_backup_with_storage(&source_storage, "vault", "backup-password", 5, 3).await.unwrap();
+
+        let target_storage = MemoryStorage::default();
+        let target = WalletManager::new();
+        let two = vec![split.shares[1].clone(), split.shares[3].clone()];
+        let err = target.restore_from_shares_with_storage(&target_storage, &two, &split.backup).await
+            .err().expect("two of three shares must not restore");
+        assert_eq!(err.validation_details().unwrap().code, "insufficient_shares");
+
+        // Repeating a share does not count towards the threshold
+        let repeated = vec![split.shares[1].clone(), split.shares[3].clone(), split.shares[1].clone()];
+        assert!(target.restore_from_shares_with_storage(&target_storage, &repeated, &split.backup).await.is_err());
+        assert!(target.wallets.read().await.is_empty());
+        assert!(target_storage.list_keys().unwrap().is_empty());
+
+        // Threshold above the share count is rejected up front
+        assert!(source.split_backup_with_storage(&source_storage, "vault", "backup-password", 2, 3).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_all_rejects_tampered_bundle_without_partial_import() {
+        let source_storage = MemoryStorage::default();
+        let source = WalletManager::new();
+        add_wallet(&source, &source_storage, "first", Network::CoreTestnet).await;
+        add_wallet(&source, &source_storage, "second", Network::CoreTestnet).await;
+        let bundle = source.export_all_with_storage(&source_storage, "bundle-password").await.unwrap();
+
+        let mut parsed: serde_json::Value = serde_json::from_str(&bundle).unwrap();
+        let mut data = STANDARD.decode(parsed["data"].as_str().unwrap()).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0x01;
+        parsed["data"] = serde_json::Value::String(STANDARD.encode(&data));
+
+        let target_storage = MemoryStorage::default();
+        let target = WalletManager::new();
+        assert!(target.import_all_with_storage(&target_storage, &parsed.to_string(), "bundle-password").await.is_err());
+        assert!(target.wallets.read().await.is_empty());
+        assert!(target_storage.list_keys().unwrap().is_empty());
+
+        // A conflicting wallet id anywhere in the bundle also aborts the whole import
+        let conflict_storage = MemoryStorage::default();
+        let conflict = WalletManager::new();
+        add_wallet(&conflict, &conflict_storage, "second", Network::CoreTestnet).await;
+        let err = conflict.import_all_with_storage(&conflict_storage, &bundle, "bundle-password").await
+            .err().expect("conflicting bundle should be rejected");
+        assert_eq!(err.validation_details().unwrap().code, "already_exists");
+        assert!(!conflict.wallets.read().await.contains_key("first"));
+        assert!(!conflict_storage.exists("wallet_key_first").unwrap());
+    }
+
+    use crate::core::crypto::keys::{account_derivation_path, ETHEREUM_DERIVATION_PATH};
+
+    const TEST_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    #[tokio::test]
+    async fn test_import_wallet_round_trips_seed_phrase() {
+        let storage = MemoryStorage::default();
+        let manager = WalletManager::new();
+
+        let wallet = manager.import_wallet_with_storage(&storage, "imported", "Imported", TEST_MNEMONIC, ETHEREUM_DERIVATION_PATH, "seed-password", Network::CoreTestnet).await
+            .expect("Failed to import wallet");
+        // First account of the well-known test mnemonic
+        assert!(wallet.address.eq_ignore_ascii_case("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+        assert!(storage.exists("wallet_key_imported").unwrap());
+        assert!(!String::from_utf8_lossy(&storage.retrieve("wallet_seed_imported").unwrap()).contains("junk"));
+
+        let phrase = manager.export_seed_phrase_with_storage(&storage, "imported", "seed-password").await
+            .expect("Failed to export seed phrase");
+        assert_eq!(phrase, TEST_MNEMONIC);
+
+        let err = manager.export_seed_phrase_with_storage(&storage, "imported", "wrong-password").await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "invalid_password");
+    }
+
+    #[tokio::test]
+    async fn test_export_seed_phrase_requires_imported_wallet() {
+        let storage = MemoryStorage::default();
+        let manager = WalletManager::new();
+        add_wallet(&manager, &storage, "generated", Network::CoreTestnet).await;
+
+        let err = manager.export_seed_phrase_with_storage(&storage, "generated", "seed-password").await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "no_seed_phrase");
+        assert!(manager.export_seed_phrase_with_storage(&storage, "missing", "seed-password").await.is_err());
+        assert!(manager.import_wallet_with_storage(&storage, "generated", "Dup", TEST_MNEMONIC, ETHEREUM_DERIVATION_PATH, "seed-password", Network::CoreTestnet).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_wallet_account_indices_give_distinct_addresses() {
+        let storage = MemoryStorage::default();
+        let manager = WalletManager::new();
+
+        let first = manager.import_wallet_with_storage(&storage, "account0", "Account 0", TEST_MNEMONIC, &account_derivation_path(0), "seed-password", Network::CoreTestnet).await.unwrap();
+        let second = manager.import_wallet_with_storage(&storage, "account1", "Account 1", TEST_MNEMONIC, &account_derivation_path(1), "seed-password", Network::CoreTestnet).await.unwrap();
+        assert!(first.address.eq_ignore_ascii_case("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+        assert!(second.address.eq_ignore_ascii_case("0x70997970C51812dc3A010C7d01b50e0d17dc79C8"));
+
+        // The same path always derives the same address
+        let again = manager.import_wallet_with_storage(&storage, "account1b", "Account 1", TEST_MNEMONIC, "m/44'/60'/0'/0/1", "seed-password", Network::CoreTestnet).await.unwrap();
+        assert_eq!(again.address, second.address);
+    }
+
+    #[tokio::test]
+    async fn test_add_account_derives_distinct_deterministic_addresses() {
+        let storage = MemoryStorage::default();
+        let manager = WalletManager::new();
+        manager.import_wallet_with_storage(&storage, "hd", "HD", TEST_MNEMONIC, ETHEREUM_DERIVATION_PATH, "seed-password", Network::CoreTestnet).await.unwrap();
+
+        let first = manager.add_account_with_storage(&storage, "hd", 1, "seed-password").await.unwrap();
+        let second = manager.add_account_with_storage(&storage, "hd", 2, "seed-password").await.unwrap();
+        assert!(first.address.eq_ignore_ascii_case("0x70997970C51812dc3A010C7d01b50e0d17dc79C8"));
+
+        let accounts = manager.list_accounts_with_storage(&storage, "hd").await.unwrap();
+        assert_eq!(accounts.iter().map(|a| a.index).collect::<Vec<_>>(), vec![0, 1, 2]);
+        let addresses: std::collections::HashSet<String> = accounts.iter().map(|a| a.address.to_lowercase()).collect();
+        assert_eq!(addresses.len(), 3);
+
+        // Re-adding returns the stored account; a fresh derivation gives the same addresses
+        assert_eq!(manager.add_account_with_storage(&storage, "hd", 2, "seed-password").await.unwrap(), second);
+        let derived = crate::core::crypto::keys::AccountAddresses::from_seed_phrase(TEST_MNEMONIC).unwrap();
+        for account in &accounts {
+            assert!(account.address.eq_ignore_ascii_case(&derived.address(account.index).unwrap()));
+        }
+
+        // Each account signs with its own key
+        let by_primary = manager.sign_message_for_account_with_storage(&storage, "hd", 0, "hello").await.unwrap();
+        let by_second = manager.sign_message_for_account_with_storage(&storage, "hd", 2, "hello").await.unwrap();
+        assert_ne!(by_primary, by_second);
+        let err = manager.sign_message_for_account_with_storage(&storage, "hd", 3, "hello").await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "unknown_account");
+
+        let err = manager.add_account_with_storage(&storage, "hd", 3, "wrong-password").await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "invalid_password");
+
+        manager.delete_wallet_with_storage(&storage, "hd").await.unwrap();
+        assert!(!storage.exists(&account_key_id("hd", 1)).unwrap());
+        assert!(!storage.exists(&accounts_key("hd")).unwrap());
+        assert!(!storage.exists(&derivation_path_key("hd")).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_add_account_follows_custom_derivation_path() {
+        let storage = MemoryStorage::default();
+        let manager = WalletManager::new();
+        manager.import_wallet_with_storage(&storage, "custom", "Custom", TEST_MNEMONIC, "m/44'/60'/1'/0/0", "seed-password", Network::CoreTestnet).await.unwrap();
+        let account = manager.add_account_with_storage(&storage, "custom", 2, "seed-password").await.unwrap();
+
+        // Same key as importing the sibling path directly, not m/44'/60'/0'/0/2
+        let reference = WalletManager::new();
+        let expected = reference.import_wallet_with_storage(&MemoryStorage::default(), "direct", "Direct", TEST_MNEMONIC, "m/44'/60'/1'/0/2", "seed-password", Network::CoreTestnet).await.unwrap();
+        assert_eq!(account.address, expected.address);
+        let standard = crate::core::crypto::keys::AccountAddresses::from_seed_phrase(TEST_MNEMONIC).unwrap();
+        assert!(!account.address.eq_ignore_ascii_case(&standard.address(2).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_add_account_requires_seed_phrase() {
+        let storage = MemoryStorage::default();
+        let manager = WalletManager::new();
+        add_wallet(&manager, &storage, "generated-hd", Network::CoreTestnet).await;
+
+        let err = manager.add_account_with_storage(&storage, "generated-hd", 1, "seed-password").await.unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "no_seed_phrase");
+        assert_eq!(manager.list_accounts_with_storage(&storage, "generated-hd").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_discover_accounts_stops_at_gap_limit() {
+        let addresses = crate::core::crypto::keys::AccountAddresses::from_seed_phrase(TEST_MNEMONIC).unwrap();
+        assert!(addresses.address(0).unwrap().eq_ignore_ascii_case("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+        // Index 2 has only sent transactions, 5 only holds funds, 9 lies beyond a gap of three
+        let nonces = vec![(addresses.address(0).unwrap(), "0x3"), (addresses.address(2).unwrap(), "0x1")];
+        let balances = vec![(addresses.address(0).unwrap(), "0x64"), (addresses.address(5).unwrap(), "0x1"), (addresses.address(9).unwrap(), "0x1")];
+        let rpc = MockServer::json_rpc_calls(move |call| {
+            let address = call["params"][0].as_str().unwrap_or_default();
+            let active = match call["method"].as_str().unwrap_or_default() {
+                "eth_getTransactionCount" => &nonces,
+                _ => &balances,
+            };
+            let result = active.iter().find(|(a, _)| a == address).map(|(_, v)| *v).unwrap_or("0x0");
+            serde_json::json!({ "jsonrpc": "2.0", "id": call["id"], "result": result })
+        }).await;
+        let network = Network::Custom { chain_id: 31337, rpc_url: rpc.url.clone(), native_currency: "GO".to_string(), name: "Devnet".to_string() };
+        let manager = WalletManager::new();
+
+        let found = manager.discover_accounts(TEST_MNEMONIC, 3, network.clone()).await.unwrap();
+        let indices: Vec<u32> = found.iter().map(|(index, _)| *index).collect();
+        assert_eq!(indices, vec![0, 2, 5]);
+        assert_eq!(found[2].1, addresses.address(5).unwrap());
+
+        let found = manager.discover_accounts(TEST_MNEMONIC, 4, network.clone()).await.unwrap();
+        assert_eq!(found.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![0, 2, 5, 9]);
+
+        assert!(manager.discover_accounts(TEST_MNEMONIC, 0, network.clone()).await.is_err());
+        assert!(manager.discover_accounts("not a seed phrase", 3, network).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_wallet_rejects_malformed_path() {
+        let storage = MemoryStorage::default();
+        let manager = WalletManager::new();
+
+        for path in ["44'/60'/0'/0/0", "m/44'/60'/x/0/0", "m/44'/60'/0'/0/2147483648"] {
+            let err = manager.import_wallet_with_storage(&storage, "bad", "Bad", TEST_MNEMONIC, path, "seed-password", Network::CoreTestnet).await.err().unwrap();
+            assert!(matches!(err, WalletError::Validation(_)), "{} should be rejected", path);
+        }
+        assert!(manager.get_wallet("bad").await.is_err());
+        assert!(!storage.exists("wallet_key_bad").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_wallet_on_custom_network() {
+        let network = Network::Custom {
+            chain_id: 31337,
+            rpc_url: "http://127.0.0.1:8545".to_string(),
+            native_currency: "GO".to_string(),
+            name: "Local Devnet".to_string(),
+        };
+        let storage = MemoryStorage::default();
+        let manager = WalletManager::new();
+        add_wallet(&manager, &storage, "devnet", network.clone()).await;
+
+        let wallet = manager.get_wallet("devnet").await.unwrap();
+        assert_eq!(wallet.network.name(), "Local Devnet");
+        assert_eq!(manager.resolve_rpc_url(&wallet.network).unwrap(), "http://127.0.0.1:8545");
+        manager.update_balance("devnet", "5".to_string()).await.unwrap();
+        assert_eq!(manager.balances.read().await["devnet"].currency, "GO");
+
+        let tx = crate::core::transactions::TransactionManager::new(network.rpc_url().to_string())
+            .create_transaction("0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6".to_string(), "1000".to_string(), wallet.network)
+            .await
+            .unwrap();
+        assert_eq!(tx.chain_id, 31337);
+
+        let unconfigured = Network::Custom {
+            chain_id: 31337,
+            rpc_url: String::new(),
+            native_currency: "GO".to_string(),
+            name: "Local Devnet".to_string(),
+        };
+        assert!(matches!(manager.resolve_rpc_url(&unconfigured), Err(WalletError::Config(_))));
+        let configured = WalletManager::new().with_rpc_url(&unconfigured, "http://127.0.0.1:9545");
+        assert_eq!(configured.resolve_rpc_url(&unconfigured).unwrap(), "http://127.0.0.1:9545");
+    }
+
+    /// Clock whose `sleep` returns only when the test sends a tick
+    struct ManualClock {
+        ticks: tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<()>>,
+        sleeps: std::sync::Mutex<Vec<Duration>>,
+    }
+
+    #[async_trait::async_trait]
+    impl RetryClock for ManualClock {
+        fn now(&self) -> std::time::SystemTime {
+            std::time::SystemTime::now()
+        }
+
+        async fn sleep(&self, duration: Duration) {
+            self.sleeps.lock().unwrap().push(duration);
+            self.ticks.lock().await.recv().await;
+        }
+    }
+
+    fn manual_clock() -> (Arc<ManualClock>, tokio::sync::mpsc::UnboundedSender<()>) {
+        let (tick, ticks) = tokio::sync::mpsc::unbounded_channel();
+        let clock = ManualClock { ticks: tokio::sync::Mutex::new(ticks), sleeps: std::sync::Mutex::new(Vec::new()) };
+        (Arc::new(clock), tick)
+    }
+
+    /// Register a wallet on a custom chain served by a mock RPC reporting `balance` wei
+    async fn add_funded_wallet(manager: &WalletManager, id: &str, balance: u64) {
+        let storage = MemoryStorage::default();
+        let address = add_wallet(manager, &storage, id, Network::CoreTestnet).await;
+        let url = spawn_mock_rpc(1, 21_000, vec![(address.clone(), U256::from(balance))]).await;
+        let network = Network::Custom { chain_id: 31337, rpc_url: url, native_currency: "GO".to_string(), name: "Devnet".to_string() };
+        manager.wallets.write().await.insert(id.to_string(), SecureWallet::new(id.to_string(), id.to_string(), address, network));
+    }
+
+    #[tokio::test]
+    async fn test_eager_refresh_updates_balances_on_interval() {
+        let manager = WalletManager::new()
+            .with_refresh_strategy(BalanceRefreshStrategy::Eager { interval: Duration::from_secs(30) });
+        add_funded_wallet(&manager, "eager", 1_234).await;
+        let mut events = manager.subscribe_balance_updates();
+        let (clock, tick) = manual_clock();
+
+        let handle = manager.start_balance_refresh_with_clock(clock.clone()).expect("eager mode starts a task");
+        tokio::task::yield_now().await;
+        assert!(events.try_recv().is_err(), "nothing is fetched before the interval elapses");
+
+        for _ in 0..2 {
+            tick.send(()).unwrap();
+            let event = tokio::time::timeout(Duration::from_secs(5), events.recv()).await
+                .expect("refresh should run after the interval")
+                .unwrap();
+            assert_eq!(event, BalanceUpdated { wallet_id: "eager".to_string(), amount: "1234".to_string(), currency: "GO".to_string() });
+        }
+        assert_eq!(manager.balances.read().await["eager"].amount, "1234");
+        assert!(clock.sleeps.lock().unwrap().iter().all(|d| *d == Duration::from_secs(30)));
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_lazy_refresh_fetches_only_on_explicit_calls() {
+        let manager = WalletManager::new();
+        assert_eq!(manager.refresh_strategy(), BalanceRefreshStrategy::Lazy);
+        add_funded_wallet(&manager, "lazy", 99).await;
+        let mut events = manager.subscribe_balance_updates();
+
+        assert!(manager.start_balance_refresh().is_none());
+        tokio::task::yield_now().await;
+        assert!(events.try_recv().is_err());
+        assert!(!manager.balances.read().await.contains_key("lazy"));
+
+        assert_eq!(manager.get_balance("lazy").await.unwrap(), "99");
+        assert_eq!(events.try_recv().unwrap().amount, "99");
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_balances_batches_per_network_and_skips_errors() {
+        let manager = WalletManager::new();
+        let storage = MemoryStorage::default();
+        let rich = add_wallet(&manager, &storage, "rich", Network::CoreTestnet).await;
+        let broke = add_wallet(&manager, &storage, "broke", Network::CoreTestnet).await;
+        add_wallet(&manager, &storage, "failing", Network::CoreTestnet).await;
+        let other = add_wallet(&manager, &storage, "other", Network::CoreTestnet).await;
+
+        let balances = vec![(rich, "0x4d2"), (broke, "0x0"), (other, "0x2a")];
+        let devnet_rpc = MockServer::json_rpc_calls(move |call| {
+            let address = call["params"][0].as_str().unwrap_or_default();
+            match balances.iter().find(|(a, _)| a == address) {
+                Some((_, balance)) => serde_json::json!({ "jsonrpc": "2.0", "id": call["id"], "result": balance }),
+                None => serde_json::json!({ "jsonrpc": "2.0", "id": call["id"], "error": { "code": -32000, "message": "header not found" } }),
+            }
+        }).await;
+        let othernet_rpc = MockServer::json_rpc_calls(|call| {
+            serde_json::json!({ "jsonrpc": "2.0", "id": call["id"], "result": "0x2a" })
+        }).await;
+
+        let devnet = Network::Custom { chain_id: 31337, rpc_url: devnet_rpc.url.clone(), native_currency: "GO".to_string(), name: "Devnet".to_string() };
+        let othernet = Network::Custom { chain_id: 31338, rpc_url: othernet_rpc.url.clone(), native_currency: "GO".to_string(), name: "Othernet".to_string() };
+        {
+            let mut wallets = manager.wallets.write().await;
+            for (id, network) in [("rich", &devnet), ("broke", &devnet), ("failing", &devnet), ("other", &othernet)] {
+                let wallet = wallets.get_mut(id).unwrap();
+                wallet.network = network.clone();
+            }
+        }
+
+        let ids: Vec<String> = ["rich", "broke", "failing", "other"].iter().map(|id| id.to_string()).collect();
+        let result = manager.get_balances(&ids).await.unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result["rich"], "1234");
+        assert_eq!(result["broke"], "0");
+        assert_eq!(result["other"], "42");
+        assert!(!result.contains_key("failing"));
+        assert_eq!(devnet_rpc.requests().len(), 1);
+        assert_eq!(othernet_rpc.requests().len(), 1);
+
+        let cache = manager.balances.read().await;
+        assert_eq!(cache["rich"].amount, "1234");
+        assert_eq!(cache["other"].currency, "GO");
+        assert!(!cache.contains_key("failing"));
+        drop(cache);
+
+        let unknown = vec!["missing".to_string()];
+        assert!(matches!(manager.get_balances(&unknown).await, Err(WalletError::WalletNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_load_all_wallets_after_restart() {
+        let storage = MemoryStorage::default();
+        let first = WalletManager::new();
+        first.import_wallet_with_storage(&storage, "persisted", "Persisted", TEST_MNEMONIC, ETHEREUM_DERIVATION_PATH, "seed-password", Network::BaseSepolia)
+            .await
+            .unwrap();
+
+        // A fresh manager, as after a process restart, knows nothing until it loads
+        let restarted = WalletManager::new();
+        assert!(restarted.get_wallet("persisted").await.is_err());
+        assert_eq!(restarted.load_all_wallets_with_storage(&storage).await.unwrap(), vec!["persisted"]);
+
+        let wallet = restarted.get_wallet("persisted").await.unwrap();
+        assert_eq!(wallet.name, "Persisted");
+        assert_eq!(wallet.network, Network::BaseSepolia);
+        assert!(wallet.address.eq_ignore_ascii_case("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+        assert!(restarted.load_all_wallets_with_storage(&storage).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_all_wallets_skips_broken_record() {
+        let storage = MemoryStorage::default();
+        let first = WalletManager::new();
+        for id in ["broken", "valid"] {
+            first.import_wallet_with_storage(&storage, id, id, TEST_MNEMONIC, ETHEREUM_DERIVATION_PATH, "seed-password", Network::BaseSepolia)
+                .await
+                .unwrap();
+        }
+        storage.store("wallet_info_broken", b"not json").unwrap();
+
+        let restarted = WalletManager::new();
+        assert_eq!(restarted.load_all_wallets_with_storage(&storage).await.unwrap(), vec!["valid"]);
+        assert!(restarted.get_wallet("broken").await.is_err());
+
+        // A missing record is skipped the same way
+        storage.delete("wallet_info_broken").unwrap();
+        let restarted = WalletManager::new();
+        assert_eq!(restarted.load_all_wallets_with_storage(&storage).await.unwrap(), vec!["valid"]);
+    }
+
+    #[tokio::test]
+    async fn test_created_wallet_survives_restart() {
+        let wallet_id = format!("persist_{}", uuid::Uuid::new_v4().simple());
+        WalletManager::new().create_wallet(&wallet_id, "Restart", Network::CoreTestnet).await.unwrap();
+
+        let restarted = WalletManager::new();
+        let loaded = restarted.load_all_wallets("test_password").await.unwrap();
+        assert!(loaded.contains(&wallet_id));
+        assert_eq!(restarted.get_wallet(&wallet_id).await.unwrap().name, "Restart");
+
+        assert!(WalletManager::new().load_all_wallets("wrong_password").await.is_err());
+
+        let storage = crate::infrastructure::platform::FileStorage::new().unwrap();
+        storage.delete(&format!("wallet_key_{}", wallet_id)).unwrap();
+        storage.delete(&format!("wallet_info_{}", wallet_id)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_biometric_protected_wallet_requires_authentication() {
+        let storage = MemoryStorage::default();
+        let biometric = MockBiometricAuth::new(true, true);
+        let manager = WalletManager::new().with_biometric_auth(biometric.clone());
+        manager.create_wallet_biometric_protected_with_storage(&storage, "guarded", "Guarded", Network::CoreTestnet).await
+            .expect("Failed to create biometric-protected wallet");
+        add_wallet(&manager, &storage, "plain", Network::CoreTestnet).await;
+
+        assert!(manager.sign_message_with_storage(&storage, "guarded", "hello").await.is_ok());
+
+        biometric.set_approve(false);
+        let err = manager.sign_message_with_storage(&storage, "guarded", "hello").await.unwrap_err();
+        assert!(err.to_string().contains("Biometric authentication failed"), "{}", err);
+        assert!(manager.export_all_with_storage(&storage, "bundle-password").await.is_err());
+        // Unprotected wallets are unaffected by the prompt
+        assert!(manager.sign_message_with_storage(&storage, "plain", "hello").await.is_ok());
+
+        // A manager without biometrics fails closed instead of loading the key
+        let unguarded = WalletManager::new();
+        assert!(unguarded.sign_message_with_storage(&storage, "guarded", "hello").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_biometric_protected_wallet_needs_biometric_hardware() {
+        let storage = MemoryStorage::default();
+        for manager in [WalletManager::new(), WalletManager::new().with_biometric_auth(MockBiometricAuth::new(false, true))] {
+            let err = match manager.create_wallet_biometric_protected_with_storage(&storage, "guarded", "Guarded", Network::CoreTestnet).await {
+                Ok(_) => panic!("Created a biometric-protected wallet without biometrics"),
+                Err(e) => e,
+            };
+            assert!(err.to_string().contains("not available"), "{}", err);
+            assert!(manager.get_wallet("guarded").await.is_err());
+        }
+        assert!(storage.list_keys().unwrap().is_empty());
+    }
+}
@@ -9,8 +9,9 @@ use crate::infrastructure::platform::FileStorage;
 use serde_json;
 use std::path::PathBuf;
 use std::fs;
-use ethers::providers::{Provider, Http, Middleware};
+use crate::infrastructure::network::RpcClient;
 use ethers::types::H256;
+use serde_json::json;
 use crate::shared::types::{TransactionStatus, Network};
 
 /// Transaction repository trait
@@ -45,11 +46,16 @@ impl TransactionRepository for FileStorage {
         serde_json::from_slice(&data).map_err(|e| WalletError::transaction(format!("Deserialization failed: {}", e)))
     }
     async fn get_receipt(&self, hash: &str, network: Network) -> Result<crate::shared::types::TransactionReceipt, WalletError> {
-        let provider = Provider::<Http>::try_from(network.rpc_url())
-            .map_err(|e| WalletError::network(format!("Provider error: {e}")))?;
         let tx_hash: H256 = hash.parse().map_err(|_| WalletError::validation("Invalid transaction hash"))?;
-        let receipt = provider.get_transaction_receipt(tx_hash).await
-            .map_err(|e| WalletError::network(format!("RPC error: {e}")))?;
+        // Fails over between the network's endpoints like every other RPC call
+        let resp_json = RpcClient::new(network.rpc_url())
+            .call("eth_getTransactionReceipt", json!([tx_hash]))
+            .await?;
+        if let Some(error) = resp_json.get("error") {
+            return Err(WalletError::network(format!("RPC error: {}", error)));
+        }
+        let receipt: Option<ethers::types::TransactionReceipt> = serde_json::from_value(resp_json.get("result").cloned().unwrap_or_default())
+            .map_err(|e| WalletError::network(format!("Invalid receipt: {}", e)))?;
         let receipt = receipt.ok_or_else(|| WalletError::transaction("Receipt not found"))?;
         let status = if receipt.status.unwrap_or_default().as_u64() == 1 {
             TransactionStatus::Confirmed
@@ -84,4 +90,41 @@ impl TransactionRepository for FileStorage {
         }
         Ok(txs)
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::mock_http::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn test_get_receipt_fails_over_to_next_endpoint() {
+        let hash = format!("0x{}", "ab".repeat(32));
+        let down = MockServer::start(|_| MockResponse::status(503)).await;
+        let receipt_hash = hash.clone();
+        let up = MockServer::start(move |request| MockResponse::rpc_result(request, json!({
+            "transactionHash": receipt_hash,
+            "transactionIndex": "0x0",
+            "blockHash": format!("0x{}", "cd".repeat(32)),
+            "blockNumber": "0x10",
+            "from": format!("0x{}", "11".repeat(20)),
+            "to": format!("0x{}", "22".repeat(20)),
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "status": "0x1",
+        }))).await;
+        let network = Network::Custom {
+            chain_id: 31337,
+            rpc_url: format!("{},{}", down.url, up.url),
+            native_currency: "ETH".to_string(),
+            name: "Failover".to_string(),
+        };
+
+        let receipt = FileStorage::new().unwrap().get_receipt(&hash, network).await.unwrap();
+        assert_eq!(receipt.hash, hash);
+        assert_eq!(receipt.block_number, Some(16));
+        assert!(matches!(receipt.status, TransactionStatus::Confirmed));
+    }
+}
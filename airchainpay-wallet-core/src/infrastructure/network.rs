@@ -0,0 +1,510 @@
+//! JSON-RPC transport
+//!
+//! Sends JSON-RPC requests and retries transient failures (rate limiting, timeouts) with
+//! exponential backoff, honoring the `Retry-After` header when the provider sends one.
+//! With several endpoints configured, an unreachable or failing (5xx) endpoint is skipped
+//! for the next one.
+
+use crate::shared::constants::{ERROR_RETRY_DELAY, MAX_RETRY_ATTEMPTS, RPC_TIMEOUT};
+use crate::shared::error::WalletError;
+use airchainpay_common::http::parse_retry_after;
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+/// Longest `Retry-After` the client is willing to wait before giving up
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Method whose requests must not be repeated once they may have reached the node
+const SEND_RAW_TRANSACTION: &str = "eth_sendRawTransaction";
+
+/// How often and how patiently a request to one endpoint is retried
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts per endpoint, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for every further one
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Pick each delay at random from its upper half so clients don't retry in lockstep
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: MAX_RETRY_ATTEMPTS,
+            base_delay: Duration::from_millis(ERROR_RETRY_DELAY),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Read `WALLET_CORE_RPC_MAX_ATTEMPTS` and `WALLET_CORE_RPC_RETRY_BASE_MS`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_attempts: std::env::var("WALLET_CORE_RPC_MAX_ATTEMPTS").ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|attempts| *attempts > 0)
+                .unwrap_or(defaults.max_attempts),
+            base_delay: std::env::var("WALLET_CORE_RPC_RETRY_BASE_MS").ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.base_delay),
+            ..defaults
+        }
+    }
+
+    /// Delay before retry number `retry` (1 for the first retry)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        if !self.jitter || delay.is_zero() {
+            return delay;
+        }
+        use rand::Rng;
+        rand::thread_rng().gen_range(delay / 2..=delay)
+    }
+}
+
+/// Whether a JSON-RPC error answer to `method` is worth retrying. Only rate limiting and
+/// node-side timeouts are; invalid params, nonce and revert errors would fail again, and a
+/// broadcast is never repeated since the node may have accepted it the first time.
+pub fn is_retryable_rpc_error(method: &str, error: &Value) -> bool {
+    if method == SEND_RAW_TRANSACTION {
+        return false;
+    }
+    let message = error.get("message").and_then(Value::as_str).unwrap_or_default().to_ascii_lowercase();
+    let permanent = ["nonce", "revert", "invalid", "insufficient", "underpriced", "already known"];
+    if permanent.iter().any(|marker| message.contains(marker)) {
+        return false;
+    }
+    let code = error.get("code").and_then(Value::as_i64);
+    // -32005 is the common "limit exceeded" code
+    code == Some(-32005) || code == Some(429)
+        || ["rate limit", "too many requests", "timeout", "timed out"].iter().any(|marker| message.contains(marker))
+}
+
+/// Time source for retry delays, injectable so backoff can be tested without waiting
+#[async_trait]
+pub trait RetryClock: Send + Sync {
+    fn now(&self) -> SystemTime;
+    async fn sleep(&self, duration: Duration);
+}
+
+/// Wall clock backed by tokio timers
+pub struct SystemClock;
+
+#[async_trait]
+impl RetryClock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// Split an RPC URL setting into its endpoints: one URL or a comma-separated list
+pub fn parse_rpc_urls(value: &str) -> Vec<String> {
+    value.split(',').map(str::trim).filter(|url| !url.is_empty()).map(String::from).collect()
+}
+
+/// Index of the endpoint that last answered, per endpoint list, shared by all clients
+/// so a new client doesn't start with an endpoint already known to be down
+fn preferred_endpoints() -> &'static Mutex<HashMap<Vec<String>, usize>> {
+    static PREFERRED: OnceLock<Mutex<HashMap<Vec<String>, usize>>> = OnceLock::new();
+    PREFERRED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Why a request to one endpoint failed
+enum EndpointError {
+    /// Unreachable or answered with a server error; the next endpoint may do better
+    Unavailable(WalletError),
+    Failed(WalletError),
+}
+
+/// JSON-RPC client over an ordered list of endpoints. Rate-limited (429) responses and
+/// retryable JSON-RPC errors are retried on the same endpoint following the `RetryPolicy`;
+/// connection errors and 5xx responses move on to the next endpoint, starting from the one
+/// that last answered. On the last endpoint 503s and timeouts are retried as well.
+pub struct RpcClient {
+    client: Client,
+    urls: Vec<String>,
+    retry_policy: RetryPolicy,
+    clock: Arc<dyn RetryClock>,
+}
+
+impl RpcClient {
+    /// `url` is one endpoint or a comma-separated list in failover order
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_endpoints(parse_rpc_urls(&url.into()))
+    }
+
+    pub fn with_endpoints(urls: Vec<String>) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_millis(RPC_TIMEOUT))
+                .build()
+                .unwrap_or_default(),
+            urls,
+            retry_policy: RetryPolicy::from_env(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn RetryClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    fn preferred_index(&self) -> usize {
+        preferred_endpoints().lock().ok()
+            .and_then(|preferred| preferred.get(&self.urls).copied())
+            .filter(|index| *index < self.urls.len())
+            .unwrap_or(0)
+    }
+
+    fn set_preferred_index(&self, index: usize) {
+        if let Ok(mut preferred) = preferred_endpoints().lock() {
+            preferred.insert(self.urls.clone(), index);
+        }
+    }
+
+    /// Send a JSON-RPC request and return the full response object
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value, WalletError> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": 1
+        });
+        self.send(method, &body).await
+    }
+
+    /// Send several requests as one JSON-RPC batch. Returns the full response object of
+    /// each call in request order; a call the provider did not answer gets `Value::Null`.
+    pub async fn batch(&self, calls: &[(&str, Value)]) -> Result<Vec<Value>, WalletError> {
+        let body: Vec<Value> = calls.iter().enumerate()
+            .map(|(id, (method, params))| json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": params,
+                "id": id
+            }))
+            .collect();
+        let response = self.send("batch", &Value::Array(body)).await?;
+        let Value::Array(items) = response else {
+            // Providers without batch support answer with a single error object
+            let reason = response.get("error").map(|e| e.to_string()).unwrap_or_else(|| response.to_string());
+            return Err(WalletError::network(format!("Batch request rejected: {}", reason)));
+        };
+
+        // Responses may arrive in any order; match them back up by id
+        let mut ordered = vec![Value::Null; calls.len()];
+        for item in items {
+            if let Some(id) = item.get("id").and_then(Value::as_u64).map(|id| id as usize) {
+                if id < ordered.len() {
+                    ordered[id] = item;
+                }
+            }
+        }
+        Ok(ordered)
+    }
+
+    async fn send(&self, method: &str, body: &Value) -> Result<Value, WalletError> {
+        let start = self.preferred_index();
+        let mut last_error = None;
+        for offset in 0..self.urls.len() {
+            let index = (start + offset) % self.urls.len();
+            let is_last = offset + 1 == self.urls.len();
+            match self.send_to(&self.urls[index], method, body, is_last).await {
+                Ok(response) => {
+                    if offset > 0 {
+                        self.set_preferred_index(index);
+                    }
+                    return Ok(response);
+                }
+                Err(EndpointError::Unavailable(e)) => {
+                    if !is_last {
+                        log::warn!("{} failed on RPC endpoint {} ({}), trying the next one", method, index + 1, e);
+                    }
+                    last_error = Some(e);
+                }
+                Err(EndpointError::Failed(e)) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| WalletError::config("No RPC URL configured")))
+    }
+
+    /// Send to one endpoint. A 503 moves on to the next endpoint unless this is the last one,
+    /// where it is retried like a 429.
+    async fn send_to(&self, url: &str, method: &str, body: &Value, is_last: bool) -> Result<Value, EndpointError> {
+        let max_attempts = self.retry_policy.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let resp = match self.client.post(url).json(body).send().await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    let error = WalletError::network(format!("{} request failed: {}", method, e));
+                    if e.is_timeout() && method == SEND_RAW_TRANSACTION {
+                        return Err(EndpointError::Failed(error));
+                    }
+                    if e.is_timeout() && is_last && attempt < max_attempts {
+                        let delay = self.retry_policy.backoff(attempt);
+                        log::warn!("{} timed out, retrying in {:?}", method, delay);
+                        self.clock.sleep(delay).await;
+                        attempt += 1;
+                        continue;
+                    }
+                    return Err(EndpointError::Unavailable(error));
+                }
+            };
+
+            let status = resp.status();
+            if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE && is_last {
+                let retry_after = resp.headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| parse_retry_after(v, self.clock.now()));
+                let delay = retry_after.unwrap_or_else(|| self.retry_policy.backoff(attempt));
+                if attempt >= max_attempts || delay > MAX_RETRY_AFTER {
+                    return Err(EndpointError::Failed(WalletError::network(format!(
+                        "{} rate limited by provider (HTTP {}), retry after {:?}", method, status.as_u16(), delay
+                    ))));
+                }
+                log::warn!("{} rate limited (HTTP {}), retrying in {:?}", method, status.as_u16(), delay);
+                self.clock.sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+            if status.is_server_error() {
+                return Err(EndpointError::Unavailable(WalletError::network(format!(
+                    "{} failed with HTTP {}", method, status.as_u16()
+                ))));
+            }
+
+            let response: Value = resp.json().await
+                .map_err(|e| EndpointError::Failed(WalletError::network(format!("Invalid {} response: {}", method, e))))?;
+            if let Some(error) = response.get("error") {
+                if attempt < max_attempts && is_retryable_rpc_error(method, error) {
+                    let delay = self.retry_policy.backoff(attempt);
+                    log::warn!("{} failed with retryable error {}, retrying in {:?}", method, error, delay);
+                    self.clock.sleep(delay).await;
+                    attempt += 1;
+                    continue;
+                }
+            }
+            return Ok(response);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::mock_http::{unreachable_url, MockResponse, MockServer};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Clock that records requested sleeps and advances virtual time instead of waiting
+    struct ManualClock {
+        now: Mutex<SystemTime>,
+        sleeps: Mutex<Vec<Duration>>,
+    }
+
+    #[async_trait]
+    impl RetryClock for ManualClock {
+        fn now(&self) -> SystemTime {
+            *self.now.lock().unwrap()
+        }
+
+        async fn sleep(&self, duration: Duration) {
+            self.sleeps.lock().unwrap().push(duration);
+            *self.now.lock().unwrap() += duration;
+        }
+    }
+
+    /// Answers the first `throttled` requests with 429 + the given Retry-After, then succeeds
+    async fn spawn_throttling_rpc(throttled: usize, retry_after: String) -> MockServer {
+        let seen = AtomicUsize::new(0);
+        MockServer::start(move |request| {
+            if seen.fetch_add(1, Ordering::SeqCst) < throttled {
+                MockResponse::status(429).header("retry-after", &retry_after)
+            } else {
+                MockResponse::rpc_result(request, json!("0x3b9aca00"))
+            }
+        }).await
+    }
+
+    /// Answers every request with `status` and no body
+    async fn spawn_failing_rpc(status: u16) -> MockServer {
+        MockServer::start(move |_| MockResponse::status(status)).await
+    }
+
+    /// Answers the first `failures` requests with a JSON-RPC `error`, then succeeds
+    async fn spawn_rpc_error(failures: usize, error: Value) -> MockServer {
+        let seen = AtomicUsize::new(0);
+        MockServer::start(move |request| {
+            if seen.fetch_add(1, Ordering::SeqCst) < failures {
+                MockResponse::rpc_error(request, error.clone())
+            } else {
+                MockResponse::rpc_result(request, json!("0x1"))
+            }
+        }).await
+    }
+
+    fn fast_retries() -> RetryPolicy {
+        RetryPolicy { base_delay: Duration::from_millis(100), ..RetryPolicy::default() }
+    }
+
+    fn manual_clock() -> Arc<ManualClock> {
+        Arc::new(ManualClock {
+            now: Mutex::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            sleeps: Mutex::new(Vec::new()),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_call_waits_for_retry_after() {
+        let rpc = spawn_throttling_rpc(1, "2".to_string()).await;
+        let clock = manual_clock();
+        let client = RpcClient::new(rpc.url.clone()).with_clock(clock.clone());
+
+        let response = client.call("eth_gasPrice", json!([])).await.unwrap();
+        assert_eq!(response["result"], "0x3b9aca00");
+        assert_eq!(rpc.requests().len(), 2);
+
+        let sleeps = clock.sleeps.lock().unwrap().clone();
+        assert_eq!(sleeps.len(), 1);
+        assert!(sleeps[0] >= Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_call_honors_http_date_and_gives_up() {
+        let clock = manual_clock();
+        let retry_at = chrono::DateTime::<chrono::Utc>::from(clock.now() + Duration::from_secs(5)).to_rfc2822();
+        let rpc = spawn_throttling_rpc(usize::MAX, retry_at).await;
+        let client = RpcClient::new(rpc.url.clone()).with_clock(clock.clone());
+
+        assert!(client.call("eth_gasPrice", json!([])).await.is_err());
+        assert_eq!(rpc.requests().len(), MAX_RETRY_ATTEMPTS as usize);
+        let sleeps = clock.sleeps.lock().unwrap().clone();
+        assert_eq!(sleeps.len(), MAX_RETRY_ATTEMPTS as usize - 1);
+        assert!(sleeps[0] >= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_parse_rpc_urls() {
+        assert_eq!(parse_rpc_urls("https://a.example"), vec!["https://a.example"]);
+        assert_eq!(parse_rpc_urls(" https://a.example, https://b.example ,"), vec!["https://a.example", "https://b.example"]);
+        assert!(parse_rpc_urls("").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_endpoint_fails_over_and_is_skipped_afterwards() {
+        let down = spawn_failing_rpc(503).await;
+        let up = spawn_throttling_rpc(0, String::new()).await;
+        let client = RpcClient::new(format!("{},{}", down.url, up.url)).with_clock(manual_clock());
+
+        let response = client.call("eth_gasPrice", json!([])).await.unwrap();
+        assert_eq!(response["result"], "0x3b9aca00");
+        assert_eq!(down.requests().len(), 1);
+
+        // A new client over the same endpoints starts with the one that answered
+        let client = RpcClient::new(format!("{},{}", down.url, up.url));
+        client.call("eth_gasPrice", json!([])).await.unwrap();
+        assert_eq!(down.requests().len(), 1);
+        assert_eq!(up.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoints_fail_over_until_exhausted() {
+        let closed = unreachable_url();
+        let up = spawn_throttling_rpc(0, String::new()).await;
+        let client = RpcClient::with_endpoints(vec![closed.clone(), up.url.clone()]);
+        assert!(client.call("eth_gasPrice", json!([])).await.is_ok());
+
+        let failing = spawn_failing_rpc(502).await;
+        let client = RpcClient::with_endpoints(vec![closed, failing.url.clone()]);
+        let err = client.call("eth_gasPrice", json!([])).await.unwrap_err();
+        assert!(err.to_string().contains("HTTP 502"), "{}", err);
+        assert!(RpcClient::new("").call("eth_gasPrice", json!([])).await.is_err());
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max_delay() {
+        let policy = RetryPolicy { jitter: false, max_delay: Duration::from_millis(300), ..fast_retries() };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(300));
+        assert_eq!(policy.backoff(40), Duration::from_millis(300));
+
+        let jittered = fast_retries();
+        for _ in 0..20 {
+            let delay = jittered.backoff(2);
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200), "{:?}", delay);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_twice_then_succeeds_on_third_attempt() {
+        // Without a usable Retry-After the policy's backoff applies
+        let rpc = spawn_throttling_rpc(2, "later".to_string()).await;
+        let clock = manual_clock();
+        let client = RpcClient::new(rpc.url.clone()).with_clock(clock.clone()).with_retry_policy(fast_retries());
+
+        let response = client.call("eth_getBalance", json!(["0x0", "latest"])).await.unwrap();
+        assert_eq!(response["result"], "0x3b9aca00");
+        assert_eq!(rpc.requests().len(), 3);
+
+        let sleeps = clock.sleeps.lock().unwrap().clone();
+        assert_eq!(sleeps.len(), 2);
+        assert!(sleeps[0] >= Duration::from_millis(50) && sleeps[0] <= Duration::from_millis(100));
+        assert!(sleeps[1] >= Duration::from_millis(100) && sleeps[1] <= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_only_transient_rpc_errors_are_retried() {
+        let limit_exceeded = json!({ "code": -32005, "message": "limit exceeded" });
+        let rpc = spawn_rpc_error(1, limit_exceeded.clone()).await;
+        let client = RpcClient::new(rpc.url.clone()).with_clock(manual_clock()).with_retry_policy(fast_retries());
+        assert_eq!(client.call("eth_getBalance", json!([])).await.unwrap()["result"], "0x1");
+        assert_eq!(rpc.requests().len(), 2);
+
+        // A broadcast is never repeated, even on a transient error
+        let rpc = spawn_rpc_error(1, limit_exceeded).await;
+        let client = RpcClient::new(rpc.url.clone()).with_clock(manual_clock()).with_retry_policy(fast_retries());
+        let response = client.call("eth_sendRawTransaction", json!(["0x00"])).await.unwrap();
+        assert_eq!(response["error"]["code"], -32005);
+        assert_eq!(rpc.requests().len(), 1);
+
+        for error in [
+            json!({ "code": -32000, "message": "nonce too low" }),
+            json!({ "code": 3, "message": "execution reverted" }),
+            json!({ "code": -32602, "message": "invalid params" }),
+        ] {
+            let rpc = spawn_rpc_error(1, error.clone()).await;
+            let client = RpcClient::new(rpc.url.clone()).with_clock(manual_clock()).with_retry_policy(fast_retries());
+            let response = client.call("eth_estimateGas", json!([])).await.unwrap();
+            assert_eq!(response["error"], error);
+            assert_eq!(rpc.requests().len(), 1, "{} should not be retried", error);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Secure Enclave keys on iOS and macOS
+//!
+//! Keys are P-256 key pairs generated inside the Secure Enclave through the Security
+//! framework. The private key never leaves the enclave; the keychain only holds a
+//! reference to it, found again by its label.
+
+use super::SecureEnclave;
+use crate::shared::error::WalletError;
+use security_framework::access_control::{ProtectionMode, SecAccessControl};
+use security_framework::item::{ItemClass, ItemSearchOptions, KeyClass, Limit, Location, Reference, SearchResult};
+use security_framework::key::{Algorithm, GenerateKeyOptions, KeyType, SecKey, Token};
+use security_framework_sys::access_control::kSecAccessControlPrivateKeyUsage;
+use security_framework_sys::base::errSecItemNotFound;
+
+const LABEL_PREFIX: &str = "airchainpay.secure-enclave.";
+
+pub struct KeychainSecureEnclave;
+
+impl KeychainSecureEnclave {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn label(key_id: &str) -> String {
+        format!("{}{}", LABEL_PREFIX, key_id)
+    }
+
+    fn options(location: Option<Location>) -> Result<GenerateKeyOptions, WalletError> {
+        let access_control = SecAccessControl::create_with_protection(
+            Some(ProtectionMode::AccessibleWhenUnlockedThisDeviceOnly),
+            kSecAccessControlPrivateKeyUsage,
+        )
+        .map_err(|e| WalletError::crypto(format!("Failed to create key access control: {}", e)))?;
+
+        let mut options = GenerateKeyOptions::default();
+        options
+            .set_key_type(KeyType::ec())
+            .set_size_in_bits(256)
+            .set_token(Token::SecureEnclave)
+            .set_access_control(access_control);
+        if let Some(location) = location {
+            options.set_location(location);
+        }
+        Ok(options)
+    }
+
+    /// The private key stored under `key_id`, if any
+    fn find_key(key_id: &str) -> Result<Option<SecKey>, WalletError> {
+        let results = ItemSearchOptions::new()
+            .class(ItemClass::key())
+            .key_class(KeyClass::private())
+            .label(&Self::label(key_id))
+            .load_refs(true)
+            .limit(Limit::Max(1))
+            .search();
+        let results = match results {
+            Ok(results) => results,
+            Err(e) if e.code() == errSecItemNotFound => return Ok(None),
+            Err(e) => return Err(WalletError::crypto(format!("Keychain lookup failed: {}", e))),
+        };
+        Ok(results.into_iter().find_map(|result| match result {
+            SearchResult::Ref(Reference::Key(key)) => Some(key),
+            _ => None,
+        }))
+    }
+
+    fn require_key(key_id: &str) -> Result<SecKey, WalletError> {
+        Self::find_key(key_id)?
+            .ok_or_else(|| WalletError::crypto(format!("No secure enclave key for {}", key_id)))
+    }
+
+    /// Uncompressed SEC1 public key (`04 || X || Y`), hex encoded
+    fn public_key_hex(private_key: &SecKey) -> Result<String, WalletError> {
+        let public_key = private_key.public_key()
+            .ok_or_else(|| WalletError::crypto("Secure enclave key has no public key"))?;
+        let bytes = public_key.external_representation()
+            .ok_or_else(|| WalletError::crypto("Failed to export secure enclave public key"))?;
+        Ok(hex::encode(bytes.bytes()))
+    }
+}
+
+impl SecureEnclave for KeychainSecureEnclave {
+    fn is_available(&self) -> Result<bool, WalletError> {
+        // Generating a throwaway key that is never stored is the only reliable probe:
+        // Macs without a T2 or Apple silicon chip have no Secure Enclave
+        let options = Self::options(None)?;
+        Ok(SecKey::generate(options.to_dictionary()).is_ok())
+    }
+
+    fn generate_key_pair(&self, key_id: &str) -> Result<String, WalletError> {
+        if Self::find_key(key_id)?.is_some() {
+            return Err(WalletError::crypto(format!("Secure enclave key {} already exists", key_id)));
+        }
+        let mut options = Self::options(Some(Location::DataProtectionKeychain))?;
+        options.set_label(Self::label(key_id));
+        let key = SecKey::generate(options.to_dictionary())
+            .map_err(|e| WalletError::crypto(format!("Secure enclave key generation failed: {}", e)))?;
+        Self::public_key_hex(&key)
+    }
+
+    /// DER-encoded ECDSA signature over the SHA-256 digest of `data`
+    fn sign(&self, key_id: &str, data: &[u8]) -> Result<Vec<u8>, WalletError> {
+        Self::require_key(key_id)?
+            .create_signature(Algorithm::ECDSASignatureMessageX962SHA256, data)
+            .map_err(|e| WalletError::crypto(format!("Secure enclave signing failed: {}", e)))
+    }
+
+    fn get_public_key(&self, key_id: &str) -> Result<String, WalletError> {
+        Self::public_key_hex(&Self::require_key(key_id)?)
+    }
+
+    fn delete_key(&self, key_id: &str) -> Result<(), WalletError> {
+        Self::require_key(key_id)?
+            .delete()
+            .map_err(|e| WalletError::crypto(format!("Failed to delete secure enclave key: {}", e)))
+    }
+}
@@ -18,12 +18,19 @@ use rand_core::OsRng;
 use rand_core::RngCore;
 use std::fs::{self, File};
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use zeroize::Zeroizing;
-#[cfg(not(target_os = "android"))]
+#[cfg(not(any(target_os = "android", target_os = "ios", target_os = "macos", target_arch = "wasm32")))]
 use sys_info;
 use std::env;
+use std::sync::OnceLock;
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+mod keychain;
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+pub use keychain::KeychainSecureEnclave;
 
 /// Platform-specific features and capabilities
 pub struct PlatformFeatures {
@@ -40,9 +47,9 @@ pub struct PlatformFeatures {
 impl PlatformFeatures {
     /// Detect platform features
     pub fn detect() -> Self {
-        #[cfg(target_os = "android")]
-        let platform_name = "android".to_string();
-        #[cfg(not(target_os = "android"))]
+        #[cfg(any(target_os = "android", target_os = "ios", target_os = "macos", target_arch = "wasm32"))]
+        let platform_name = env::consts::OS.to_string();
+        #[cfg(not(any(target_os = "android", target_os = "ios", target_os = "macos", target_arch = "wasm32")))]
         let platform_name = sys_info::os_type().unwrap_or_else(|_| "unknown".to_string());
         let architecture = "unknown".to_string();
         
@@ -94,6 +101,21 @@ impl PlatformFeatures {
     }
 }
 
+/// Check run on every password a `FileStorage` or `SecureFileStorage` is given or reads.
+/// Core installs its weak-password audit here so this layer doesn't depend on it.
+static STORAGE_PASSWORD_CHECK: OnceLock<fn(&str)> = OnceLock::new();
+
+/// Install the check run on storage passwords; only the first call takes effect
+pub fn set_storage_password_check(check: fn(&str)) {
+    let _ = STORAGE_PASSWORD_CHECK.set(check);
+}
+
+fn check_storage_password(password: &str) {
+    if let Some(check) = STORAGE_PASSWORD_CHECK.get() {
+        check(password);
+    }
+}
+
 /// Platform-specific storage implementation
 pub trait PlatformStorage {
     /// Store data securely
@@ -174,8 +196,8 @@ impl PlatformManager {
         };
         
         let secure_enclave: Box<dyn SecureEnclave> = match features.platform_name.as_str() {
-            "ios" => Box::new(NoSecureEnclave::new()),
-            "macos" => Box::new(NoSecureEnclave::new()),
+            #[cfg(any(target_os = "ios", target_os = "macos"))]
+            "ios" | "macos" => Box::new(KeychainSecureEnclave::new()),
             _ => Box::new(NoSecureEnclave::new()),
         };
         
@@ -213,12 +235,110 @@ impl PlatformManager {
     }
 }
 
+/// Argon2id parameters used to derive an encryption key from a password.
+///
+/// Encrypted blobs start with a header recording the parameters they were written with,
+/// so they decrypt the same way regardless of the crate version reading them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory cost in KiB
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    const HEADER_MAGIC: &'static [u8; 4] = b"ACK1";
+    pub const HEADER_LEN: usize = 16;
+
+    /// Upper bounds accepted from a header, so a crafted blob cannot demand unbounded work
+    const MAX_MEMORY_KIB: u32 = 1 << 20;
+    const MAX_ITERATIONS: u32 = 64;
+
+    /// Parameters for everything written now (64 MiB, 3 iterations)
+    pub const SECURE: KdfParams = KdfParams { memory_kib: 65536, iterations: 3, parallelism: 1 };
+
+    /// `Argon2::default()` as of argon2 0.5, which blobs without a header were written with
+    pub const LEGACY: KdfParams = KdfParams { memory_kib: 19456, iterations: 2, parallelism: 1 };
+
+    pub fn header(&self) -> [u8; Self::HEADER_LEN] {
+        let mut header = [0u8; Self::HEADER_LEN];
+        header[..4].copy_from_slice(Self::HEADER_MAGIC);
+        header[4..8].copy_from_slice(&self.memory_kib.to_le_bytes());
+        header[8..12].copy_from_slice(&self.iterations.to_le_bytes());
+        header[12..16].copy_from_slice(&self.parallelism.to_le_bytes());
+        header
+    }
+
+    /// Split `blob` into the parameters in its header and the rest.
+    /// Blobs without a header were written with `LEGACY`.
+    pub fn split(blob: &[u8]) -> (KdfParams, &[u8]) {
+        if blob.len() < Self::HEADER_LEN || &blob[..4] != Self::HEADER_MAGIC {
+            return (Self::LEGACY, blob);
+        }
+        let field = |at: usize| u32::from_le_bytes([blob[at], blob[at + 1], blob[at + 2], blob[at + 3]]);
+        let params = KdfParams { memory_kib: field(4), iterations: field(8), parallelism: field(12) };
+        (params, &blob[Self::HEADER_LEN..])
+    }
+
+    /// Derive a 32-byte key from `password` and `salt`
+    pub fn derive_key(&self, password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, WalletError> {
+        if self.memory_kib > Self::MAX_MEMORY_KIB || self.iterations > Self::MAX_ITERATIONS {
+            return Err(WalletError::crypto(format!(
+                "Unsupported key derivation parameters: {} KiB, {} iterations",
+                self.memory_kib, self.iterations
+            )));
+        }
+        let salt = argon2::password_hash::SaltString::encode_b64(salt)?;
+        let argon2 = Argon2::new(
+            argon2::Algorithm::Argon2id,
+            argon2::Version::V0x13,
+            argon2::Params::new(self.memory_kib, self.iterations, self.parallelism, Some(32))?,
+        );
+        let password_hash = argon2.hash_password(password.as_bytes(), &salt)
+            .map_err(|e| WalletError::crypto(format!("Password hashing failed: {}", e)))?;
+
+        // Handle the case where hash might be None
+        let hash = password_hash.hash
+            .ok_or_else(|| WalletError::crypto("Password hash is empty".to_string()))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        key.copy_from_slice(&hash.as_bytes()[..32]);
+        Ok(key)
+    }
+}
+
+/// Length of the random salt written next to every `SecureFileStorage` entry
+pub const SECURE_SALT_LEN: usize = 32;
+
 // Hardened file storage implementation
-pub struct SecureFileStorage;
+/// Overwrite a file with random bytes, then remove it, so the old contents don't stay on
+/// disk in the freed blocks. Missing files are ignored.
+fn wipe_file(path: &Path) {
+    if let Ok(mut file) = fs::OpenOptions::new().write(true).open(path) {
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0) as usize;
+        let mut noise = vec![0u8; len];
+        OsRng.fill_bytes(&mut noise);
+        if file.write_all(&noise).and_then(|_| file.sync_all()).is_err() {
+            log::warn!("Could not overwrite {} before removing it", path.display());
+        }
+    }
+    let _ = fs::remove_file(path);
+}
+
+pub struct SecureFileStorage {
+    /// Storage password; when unset it is read from the environment or prompted for
+    password: Option<Zeroizing<String>>,
+}
 
 impl SecureFileStorage {
     pub fn new() -> Result<Self, WalletError> {
-        Ok(Self {})
+        Ok(Self { password: None })
+    }
+
+    /// Secure storage unlocked with `password` instead of `WALLET_CORE_PASSWORD` or a prompt
+    pub fn with_password(password: &str) -> Result<Self, WalletError> {
+        check_storage_password(password);
+        Ok(Self { password: Some(Zeroizing::new(password.to_string())) })
     }
 
     // Helper: Get password from env or prompt (tests are non-interactive)
@@ -236,22 +356,7 @@ impl SecureFileStorage {
 
     // Helper: Derive encryption key from password using Argon2 with secure parameters
     fn derive_key(password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, WalletError> {
-        let salt = argon2::password_hash::SaltString::encode_b64(salt)?;
-        let argon2 = Argon2::new(
-            argon2::Algorithm::Argon2id,
-            argon2::Version::V0x13,
-            argon2::Params::new(65536, 3, 1, Some(32))?,
-        );
-        let password_hash = argon2.hash_password(password.as_bytes(), &salt)
-            .map_err(|e| WalletError::crypto(format!("Password hashing failed: {}", e)))?;
-        
-        // Handle the case where hash might be None
-        let hash = password_hash.hash
-            .ok_or_else(|| WalletError::crypto("Password hash is empty".to_string()))?;
-        let hash_bytes = hash.as_bytes();
-        let mut key = Zeroizing::new([0u8; 32]);
-        key.copy_from_slice(&hash_bytes[..32]);
-        Ok(key)
+        KdfParams::SECURE.derive_key(password, salt)
     }
 
     // Helper: Get secure file path for a given key
@@ -272,8 +377,8 @@ impl SecureFileStorage {
         path
     }
 
-    // Helper: Get or generate salt for a key
-    fn get_salt(key: &str) -> Result<Zeroizing<Vec<u8>>, WalletError> {
+    // Helper: Get secure salt file path for a given key
+    fn salt_path(key: &str) -> PathBuf {
         let base_dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from("./secure_storage"));
         let mut salt_path = base_dir.join("airchainpay");
         fs::create_dir_all(&salt_path).ok();
@@ -286,13 +391,29 @@ impl SecureFileStorage {
         let filename = hex::encode(&hash[..16]);
         
         salt_path.push(format!("{}.salt", filename));
+        salt_path
+    }
+
+    // Helper: Get or generate salt for a key
+    fn get_salt(key: &str) -> Result<Zeroizing<Vec<u8>>, WalletError> {
+        let salt_path = Self::salt_path(key);
         
         if salt_path.exists() {
             let mut salt = Zeroizing::new(vec![]);
             File::open(&salt_path)?.read_to_end(&mut salt)?;
+            // A short salt (e.g. left truncated by a crash) derives the wrong key; say so
+            // instead of letting decryption fail as if the password were wrong
+            if salt.len() != SECURE_SALT_LEN {
+                return Err(WalletError::corrupted_salt(format!(
+                    "salt file {} is {} bytes, expected {}. Restore it from a backup of the storage directory; \
+                     without the original salt this entry cannot be decrypted and the wallet must be re-imported \
+                     from its seed phrase or a wallet backup",
+                    salt_path.display(), salt.len(), SECURE_SALT_LEN
+                )));
+            }
             Ok(salt)
         } else {
-            let mut salt = Zeroizing::new([0u8; 32]);
+            let mut salt = Zeroizing::new([0u8; SECURE_SALT_LEN]);
             let mut rng = OsRng;
             rng.fill_bytes(&mut *salt);
             let mut f = File::create(&salt_path)?;
@@ -302,7 +423,10 @@ impl SecureFileStorage {
     }
 
     // Helper: Get secure password from OS keyring or prompt
-    fn get_password() -> Result<Zeroizing<String>, WalletError> {
+    fn get_password(&self) -> Result<Zeroizing<String>, WalletError> {
+        if let Some(password) = &self.password {
+            return Ok(password.clone());
+        }
         let password = Self::get_password_string()?;
         Ok(Zeroizing::new(password))
     }
@@ -310,7 +434,7 @@ impl SecureFileStorage {
 
 impl PlatformStorage for SecureFileStorage {
     fn store(&self, key: &str, data: &[u8]) -> Result<(), WalletError> {
-        let password = Self::get_password()?;
+        let password = self.get_password()?;
         let salt = Self::get_salt(key)?;
         let key_bytes = Self::derive_key(&password, &salt)?;
         
@@ -323,6 +447,7 @@ impl PlatformStorage for SecureFileStorage {
             .map_err(|e| WalletError::crypto(format!("Encryption failed: {}", e)))?;
         
         let mut file = File::create(Self::file_path(key))?;
+        #[cfg(unix)]
         file.set_permissions(fs::Permissions::from_mode(0o600))?;
         file.write_all(&nonce)?;
         file.write_all(&ciphertext)?;
@@ -331,7 +456,7 @@ impl PlatformStorage for SecureFileStorage {
     }
 
     fn retrieve(&self, key: &str) -> Result<Vec<u8>, WalletError> {
-        let password = Self::get_password()?;
+        let password = self.get_password()?;
         let salt = Self::get_salt(key)?;
         let key_bytes = Self::derive_key(&password, &salt)?;
         
@@ -351,20 +476,10 @@ impl PlatformStorage for SecureFileStorage {
     }
 
     fn delete(&self, key: &str) -> Result<(), WalletError> {
-        let _ = fs::remove_file(Self::file_path(key));
+        wipe_file(&Self::file_path(key));
         
         // Also delete the salt file
-        let base_dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from("./secure_storage"));
-        let mut salt_path = base_dir.join("airchainpay");
-        
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-        hasher.update(key.as_bytes());
-        let hash = hasher.finalize();
-        let filename = hex::encode(&hash[..16]);
-        
-        salt_path.push(format!("{}.salt", filename));
-        let _ = fs::remove_file(salt_path);
+        wipe_file(&Self::salt_path(key));
         
         Ok(())
     }
@@ -381,134 +496,194 @@ impl PlatformStorage for SecureFileStorage {
 }
 
 // Legacy file storage for backward compatibility
-pub struct FileStorage;
+pub struct FileStorage {
+    /// Storage password; when unset it is read from the environment or prompted for
+    password: Option<Zeroizing<String>>,
+}
 
 impl FileStorage {
     pub fn new() -> Result<Self, WalletError> {
-        Ok(Self {})
+        Ok(Self { password: None })
+    }
+
+    /// File storage unlocked with `password` instead of `WALLET_CORE_PASSWORD` or a prompt
+    pub fn with_password(password: &str) -> Result<Self, WalletError> {
+        check_storage_password(password);
+        Ok(Self { password: Some(Zeroizing::new(password.to_string())) })
+    }
+
+    fn password_string(&self) -> Result<String, WalletError> {
+        match &self.password {
+            Some(password) => Ok(password.to_string()),
+            None => Self::get_password_string(),
+        }
     }
 
     // Helper: Get password from env or prompt (tests are non-interactive)
     fn get_password_string() -> Result<String, WalletError> {
         if let Ok(pw) = env::var("WALLET_CORE_PASSWORD") {
+            // Read on every storage operation; one warning per process is enough
+            static AUDITED: std::sync::Once = std::sync::Once::new();
+            AUDITED.call_once(|| {
+                check_storage_password(&pw);
+            });
             return Ok(pw);
         }
         #[cfg(test)]
         {
             return Ok("test_password".to_string());
         }
-        rpassword::prompt_password("Enter password for secure storage: ")
-            .map_err(|e| WalletError::crypto(format!("Password prompt failed: {}", e)))
-    }
-
-    // Helper: Derive encryption key from password using Argon2
-    fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], WalletError> {
-        let salt = argon2::password_hash::SaltString::encode_b64(salt)?;
-        let argon2 = Argon2::default();
-        let password_hash = argon2.hash_password(password.as_bytes(), &salt)
-            .map_err(|e| WalletError::crypto(format!("Password hashing failed: {}", e)))?;
-        
-        // Handle the case where hash might be None
-        let hash = password_hash.hash
-            .ok_or_else(|| WalletError::crypto("Password hash is empty".to_string()))?;
-        let hash_bytes = hash.as_bytes();
-        let mut key = [0u8; 32];
-        key.copy_from_slice(&hash_bytes[..32]);
-        Ok(key)
+        let pw = rpassword::prompt_password("Enter password for secure storage: ")
+            .map_err(|e| WalletError::crypto(format!("Password prompt failed: {}", e)))?;
+        check_storage_password(&pw);
+        Ok(pw)
     }
 
-    // Helper: Get file path for a given key
-    fn file_path(key: &str) -> PathBuf {
+    fn storage_dir() -> PathBuf {
         // Use OS-specific secure app data directory
         let base_dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from("./secure_storage"));
-        let mut path = base_dir.join("airchainpay");
+        let path = base_dir.join("airchainpay");
         fs::create_dir_all(&path).ok();
-        path.push(format!("{}.dat", key));
         path
     }
 
+    // Helper: File name stem for a key. Hashed like SecureFileStorage so any key is a valid
+    // file name; the prefix keeps the two storages from sharing files in the same directory.
+    fn file_stem(key: &str) -> String {
+        use sha2::{Sha256, Digest};
+        let mut hasher = Sha256::new();
+        hasher.update(b"file_storage:");
+        hasher.update(key.as_bytes());
+        hex::encode(&hasher.finalize()[..16])
+    }
+
+    // Helper: Get file path for a given key
+    fn file_path(key: &str) -> PathBuf {
+        Self::storage_dir().join(format!("{}.dat", Self::file_stem(key)))
+    }
+
+    // Helper: Get salt file path for a given key
+    fn salt_path(key: &str) -> PathBuf {
+        Self::storage_dir().join(format!("{}.salt", Self::file_stem(key)))
+    }
+
+    // Helper: Data and salt paths of an entry written before file names were hashed.
+    // Keys that could point outside the storage directory never had a usable legacy file.
+    fn legacy_paths(key: &str) -> Option<(PathBuf, PathBuf)> {
+        use std::path::Component;
+        if key.is_empty() || !Path::new(key).components().all(|c| matches!(c, Component::Normal(_))) {
+            return None;
+        }
+        let dir = Self::storage_dir();
+        Some((dir.join(format!("{}.dat", key)), dir.join(format!("{}.salt", key))))
+    }
+
     // Helper: Get or generate salt for a key
     fn get_salt(key: &str) -> Result<Vec<u8>, WalletError> {
-        let base_dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from("./secure_storage"));
-        let mut salt_path = base_dir.join("airchainpay");
-        fs::create_dir_all(&salt_path).ok();
-        salt_path.push(format!("{}.salt", key));
+        Self::read_or_create_salt(&Self::salt_path(key))
+    }
+
+    fn read_or_create_salt(salt_path: &Path) -> Result<Vec<u8>, WalletError> {
         if salt_path.exists() {
             let mut salt = vec![];
-            File::open(&salt_path)?.read_to_end(&mut salt)?;
+            File::open(salt_path)?.read_to_end(&mut salt)?;
             Ok(salt)
         } else {
             let mut salt = [0u8; 16];
             let mut rng = OsRng;
             rng.fill_bytes(&mut salt);
-            let mut f = File::create(&salt_path)?;
+            let mut f = File::create(salt_path)?;
             f.write_all(&salt)?;
             Ok(salt.to_vec())
         }
     }
+
+    fn decrypt_file(&self, data_path: &Path, salt: &[u8]) -> Result<Vec<u8>, WalletError> {
+        let password = self.password_string()?;
+        let contents = fs::read(data_path)?;
+        let (params, contents) = KdfParams::split(&contents);
+        if contents.len() < 12 {
+            return Err(WalletError::crypto("Encrypted data too short".to_string()));
+        }
+        let (nonce, ciphertext) = contents.split_at(12);
+        let key_bytes = params.derive_key(&password, salt)?;
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(&*key_bytes));
+        let plaintext = cipher.decrypt(GenericArray::from_slice(nonce), ciphertext)
+            .map_err(|e| WalletError::crypto(format!("Decryption failed: {}", e)))?;
+        Ok(plaintext)
+    }
+
+    // Helper: Move a legacy-named entry to its hashed file name. Returns whether one was found.
+    // The legacy files are only removed once the entry has been re-encrypted under the new name.
+    fn migrate_legacy(&self, key: &str) -> Result<bool, WalletError> {
+        let Some((legacy_data, legacy_salt)) = Self::legacy_paths(key) else {
+            return Ok(false);
+        };
+        if Self::file_path(key).exists() || !legacy_data.exists() {
+            return Ok(false);
+        }
+        let salt = Self::read_or_create_salt(&legacy_salt)?;
+        let data = Zeroizing::new(self.decrypt_file(&legacy_data, &salt)?);
+        self.store(key, &data)?;
+        let _ = fs::remove_file(legacy_data);
+        let _ = fs::remove_file(legacy_salt);
+        log::info!("Migrated a storage entry to a hashed file name");
+        Ok(true)
+    }
 }
 
 impl PlatformStorage for FileStorage {
     fn store(&self, key: &str, data: &[u8]) -> Result<(), WalletError> {
-        let password = Self::get_password_string()?;
+        let password = self.password_string()?;
         let salt = Self::get_salt(key)?;
-        let key_bytes = Self::derive_key(&password, &salt)?;
-        let cipher = Aes256Gcm::new(GenericArray::from_slice(&key_bytes));
+        let params = KdfParams::SECURE;
+        let key_bytes = params.derive_key(&password, &salt)?;
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(&*key_bytes));
         let mut nonce = [0u8; 12];
         let mut rng = OsRng;
         rng.fill_bytes(&mut nonce);
         let ciphertext = cipher.encrypt(GenericArray::from_slice(&nonce), data)
             .map_err(|e| WalletError::crypto(format!("Encryption failed: {}", e)))?;
         let mut file = File::create(Self::file_path(key))?;
+        #[cfg(unix)]
         file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(&params.header())?;
         file.write_all(&nonce)?;
         file.write_all(&ciphertext)?;
+        // A newer value replaces any entry still stored under the legacy name
+        if let Some((legacy_data, legacy_salt)) = Self::legacy_paths(key) {
+            let _ = fs::remove_file(legacy_data);
+            let _ = fs::remove_file(legacy_salt);
+        }
         Ok(())
     }
 
     fn retrieve(&self, key: &str) -> Result<Vec<u8>, WalletError> {
-        let password = Self::get_password_string()?;
+        self.migrate_legacy(key)?;
         let salt = Self::get_salt(key)?;
-        let key_bytes = Self::derive_key(&password, &salt)?;
-        let cipher = Aes256Gcm::new(GenericArray::from_slice(&key_bytes));
-        let mut file = File::open(Self::file_path(key))?;
-        let mut nonce = [0u8; 12];
-        file.read_exact(&mut nonce)?;
-        let mut ciphertext = vec![];
-        file.read_to_end(&mut ciphertext)?;
-        let plaintext = cipher.decrypt(GenericArray::from_slice(&nonce), ciphertext.as_slice())
-            .map_err(|e| WalletError::crypto(format!("Decryption failed: {}", e)))?;
-        Ok(plaintext)
+        self.decrypt_file(&Self::file_path(key), &salt)
     }
 
     fn delete(&self, key: &str) -> Result<(), WalletError> {
-        let _ = fs::remove_file(Self::file_path(key));
-        let base_dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from("./secure_storage"));
-        let mut salt_path = base_dir.join("airchainpay");
-        salt_path.push(format!("{}.salt", key));
-        let _ = fs::remove_file(salt_path);
+        wipe_file(&Self::file_path(key));
+        wipe_file(&Self::salt_path(key));
+        if let Some((legacy_data, legacy_salt)) = Self::legacy_paths(key) {
+            wipe_file(&legacy_data);
+            wipe_file(&legacy_salt);
+        }
         Ok(())
     }
 
     fn exists(&self, key: &str) -> Result<bool, WalletError> {
-        Ok(Self::file_path(key).exists())
+        if Self::file_path(key).exists() {
+            return Ok(true);
+        }
+        Ok(Self::legacy_paths(key).is_some_and(|(legacy_data, _)| legacy_data.exists()))
     }
 
     fn list_keys(&self) -> Result<Vec<String>, WalletError> {
-        let base_dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from("./secure_storage"));
-        let dir = base_dir.join("airchainpay");
-        let mut keys = vec![];
-        if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if let Some(name) = path.file_stem().and_then(|n| n.to_str()) {
-                    if path.extension().and_then(|e| e.to_str()) == Some("dat") {
-                        keys.push(name.to_string());
-                    }
-                }
-            }
-        }
-        Ok(keys)
+        // File names are hashes, so keys cannot be recovered from them
+        Err(WalletError::crypto("Key enumeration not allowed for security".to_string()))
     }
 }
 
@@ -590,4 +765,74 @@ mod tests {
         let manager = PlatformManager::new();
         assert!(manager.is_ok());
     }
+
+    #[test]
+    fn test_file_storage_key_with_path_characters() {
+        let key = format!("wallet_key_wallet_abc/def:{} ü", uuid::Uuid::new_v4().simple());
+        let storage = FileStorage::with_password("test_password").unwrap();
+        storage.store(&key, b"secret").unwrap();
+
+        assert!(storage.exists(&key).unwrap());
+        assert_eq!(storage.retrieve(&key).unwrap(), b"secret");
+        assert_eq!(FileStorage::file_path(&key).parent(), Some(FileStorage::storage_dir().as_path()));
+        assert!(!FileStorage::storage_dir().join(key.split('/').next().unwrap()).exists());
+        // The same key in SecureFileStorage lives in different files
+        assert_ne!(FileStorage::file_path(&key), SecureFileStorage::file_path(&key));
+
+        storage.delete(&key).unwrap();
+        assert!(!storage.exists(&key).unwrap());
+        assert!(!FileStorage::salt_path(&key).exists());
+    }
+
+    #[test]
+    fn test_file_storage_migrates_legacy_file_names() {
+        let key = format!("wallet_key_legacy_{}", uuid::Uuid::new_v4().simple());
+        let storage = FileStorage::with_password("test_password").unwrap();
+
+        // Write the entry the way FileStorage did before file names were hashed
+        let (legacy_data, legacy_salt) = FileStorage::legacy_paths(&key).unwrap();
+        let salt = FileStorage::read_or_create_salt(&legacy_salt).unwrap();
+        let key_bytes = KdfParams::LEGACY.derive_key("test_password", &salt).unwrap();
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(&*key_bytes));
+        let nonce = [7u8; 12];
+        let ciphertext = cipher.encrypt(GenericArray::from_slice(&nonce), b"legacy secret".as_ref()).unwrap();
+        fs::write(&legacy_data, [nonce.as_slice(), &ciphertext].concat()).unwrap();
+
+        assert!(storage.exists(&key).unwrap());
+        assert_eq!(storage.retrieve(&key).unwrap(), b"legacy secret");
+        assert!(!legacy_data.exists());
+        assert!(!legacy_salt.exists());
+        assert!(FileStorage::file_path(&key).exists());
+        assert_eq!(storage.retrieve(&key).unwrap(), b"legacy secret");
+
+        storage.delete(&key).unwrap();
+        assert!(FileStorage::legacy_paths("../outside").is_none());
+        assert!(FileStorage::legacy_paths("/etc/passwd").is_none());
+    }
+
+    #[test]
+    fn test_corrupted_salt_is_reported_distinctly_from_wrong_password() {
+        let key = format!("salt_test_{}", uuid::Uuid::new_v4().simple());
+        let storage = SecureFileStorage::with_password("correct horse").unwrap();
+        storage.store(&key, b"secret").unwrap();
+        assert_eq!(storage.retrieve(&key).unwrap(), b"secret");
+
+        let wrong = SecureFileStorage::with_password("battery staple").unwrap();
+        assert!(matches!(wrong.retrieve(&key), Err(WalletError::Crypto(_))));
+
+        for corrupted in [&[][..], &[7u8; 5][..]] {
+            fs::write(SecureFileStorage::salt_path(&key), corrupted).unwrap();
+            match storage.retrieve(&key) {
+                Err(WalletError::CorruptedSalt(message)) => {
+                    assert!(message.contains(&format!("{} bytes, expected {}", corrupted.len(), SECURE_SALT_LEN)));
+                }
+                other => panic!("expected a corrupted salt error, got {:?}", other.map(|_| ())),
+            }
+        }
+        // Writing over a corrupted salt must not silently re-encrypt with it either
+        assert!(matches!(storage.store(&key, b"other"), Err(WalletError::CorruptedSalt(_))));
+
+        storage.delete(&key).unwrap();
+        assert!(!SecureFileStorage::salt_path(&key).exists());
+    }
 } 
\ No newline at end of file
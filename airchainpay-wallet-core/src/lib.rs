@@ -52,7 +52,7 @@ use crate::core::storage::StorageManager;
 use crate::shared::types::WalletBackupInfo;
 
 // Re-export specific components
-pub use core::wallet::WalletManager;
+pub use core::wallet::{WalletAccount, WalletManager};
 pub use core::storage::SecureStorage;
 pub use core::transactions::TransactionManager;
 pub use core::ble::BLESecurityManager;
@@ -73,7 +73,7 @@ pub fn init() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::init();
     
     // Initialize core modules
-    tokio::runtime::Runtime::new()?.block_on(async {
+    tokio::runtime::Builder::new_current_thread().build()?.block_on(async {
         // core::init().await?;
     Ok(())
     })
@@ -92,8 +92,8 @@ pub mod ffi;
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
-#[cfg(feature = "no_std")]
-pub mod no_std;
+#[cfg(feature = "offline_signing")]
+pub mod offline_signing;
 
 // Re-export FFI functions when feature is enabled
 #[cfg(feature = "ffi")]
@@ -103,9 +103,9 @@ pub use ffi::*;
 #[cfg(feature = "wasm")]
 pub use wasm::*;
 
-// Re-export no_std functions when feature is enabled
-#[cfg(feature = "no_std")]
-pub use no_std::*;
+// Re-export offline signing functions when feature is enabled
+#[cfg(feature = "offline_signing")]
+pub use offline_signing::*;
 
 /// Initialize the wallet core with configuration from .env or safe defaults
 pub async fn init_wallet_core() -> Result<WalletCore, WalletError> {
@@ -122,6 +122,7 @@ pub async fn init_wallet_core() -> Result<WalletCore, WalletError> {
     // Read from environment variables; fall back to known defaults where safe
     // Keys: WALLET_CORE_RPC_CORE_TESTNET, WALLET_CORE_RPC_BASE_SEPOLIA,
     //       WALLET_CORE_RPC_LISK_SEPOLIA, WALLET_CORE_RPC_HOLESKY
+    // Each may list several comma-separated endpoints, tried in order
     let core_testnet_url = env::var("WALLET_CORE_RPC_CORE_TESTNET")
         .unwrap_or_else(|_| "https://rpc.test2.btcs.network".to_string());
     let base_sepolia_url = env::var("WALLET_CORE_RPC_BASE_SEPOLIA")
@@ -147,6 +148,8 @@ pub async fn init_wallet_core() -> Result<WalletCore, WalletError> {
         wallet_manager,
         storage,
         transaction_manager,
+        ble: BLESecurityManager::new(),
+        offline: false,
     })
 }
 
@@ -190,47 +193,106 @@ pub struct WalletCore {
     pub wallet_manager: WalletManager,
     pub storage: StorageManager,
     pub transaction_manager: TransactionManager,
+    pub ble: BLESecurityManager,
+    offline: bool,
 }
 
 impl WalletCore {
+    /// Wallet core without any network or BLE surface, for auditing the crypto. RPC and
+    /// BLE operations fail with `WalletError::Offline`; key storage and signing work as usual.
+    pub fn offline() -> Self {
+        Self {
+            wallet_manager: WalletManager::new().with_offline_mode(true),
+            storage: StorageManager::new(),
+            transaction_manager: TransactionManager::new(String::new()).with_offline_mode(true),
+            ble: BLESecurityManager::new().with_offline_mode(true),
+            offline: true,
+        }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
     /// Create a new wallet
     pub async fn create_wallet(&self, wallet_id: &str, name: &str, network: Network) -> Result<Wallet, WalletError> {
         let secure_wallet = self.wallet_manager.create_wallet(wallet_id, name, network).await?;
         Ok(Wallet::from(secure_wallet))
     }
 
-    pub async fn import_wallet(&self, seed_phrase: &str) -> Result<Wallet, WalletError> {
-        use bip39::{Mnemonic};
-        use bip32::{XPrv, DerivationPath, Seed};
-        use std::str::FromStr;
-        let mnemonic = Mnemonic::parse(seed_phrase)
-            .map_err(|e| WalletError::validation(format!("Invalid seed phrase: {}", e)))?;
-        let seed_bytes = mnemonic.to_seed("");
-        let seed = Seed::new(seed_bytes); 
-        let xprv = XPrv::new(seed.as_bytes())
-            .map_err(|e| WalletError::crypto(format!("Failed to create XPrv: {}", e)))?;
-        let derivation_path = DerivationPath::from_str("m/44'/60'/0'/0/0")
-            .map_err(|e| WalletError::crypto(format!("Invalid derivation path: {}", e)))?;
-        let mut child_xprv = xprv;
-        for child_number in derivation_path.into_iter() {
-            child_xprv = child_xprv.derive_child(child_number)
-                .map_err(|e| WalletError::crypto(format!("Failed to derive child XPrv: {}", e)))?;
-        }
-        let _private_key_bytes = child_xprv.private_key().to_bytes();
+    /// Create a wallet whose key is only usable after a biometric prompt; fails without biometrics
+    pub async fn create_wallet_biometric_protected(&self, wallet_id: &str, name: &str, network: Network) -> Result<Wallet, WalletError> {
+        let secure_wallet = self.wallet_manager.create_wallet_biometric_protected(wallet_id, name, network).await?;
+        Ok(Wallet::from(secure_wallet))
+    }
+
+    /// Import a wallet from a BIP39 mnemonic; `password` protects the stored mnemonic
+    pub async fn import_wallet(&self, seed_phrase: &str, password: &str) -> Result<Wallet, WalletError> {
+        let wallet_id = format!("wallet_{}", uuid::Uuid::new_v4());
+        let network = Network::CoreTestnet;
+        let wallet = self.wallet_manager.import_wallet(&wallet_id, "Imported Wallet", seed_phrase, password, network).await?;
+        Ok(Wallet::from(wallet))
+    }
+
+    /// Import a wallet using a custom derivation path, e.g. Ledger Live's `m/44'/60'/1'/0/0`
+    pub async fn import_wallet_with_path(&self, seed_phrase: &str, derivation_path: &str, password: &str) -> Result<Wallet, WalletError> {
         let wallet_id = format!("wallet_{}", uuid::Uuid::new_v4());
         let network = Network::CoreTestnet;
-        let wallet = self.wallet_manager.create_wallet(&wallet_id, "Imported Wallet", network).await?;
+        let wallet = self.wallet_manager
+            .import_wallet_with_path(&wallet_id, "Imported Wallet", seed_phrase, derivation_path, password, network)
+            .await?;
         Ok(Wallet::from(wallet))
     }
 
+    /// Import the `account_index`-th account (`m/44'/60'/0'/0/{index}`), as numbered by MetaMask
+    pub async fn import_wallet_account(&self, seed_phrase: &str, account_index: u32, password: &str) -> Result<Wallet, WalletError> {
+        let derivation_path = crate::core::crypto::keys::account_derivation_path(account_index);
+        self.import_wallet_with_path(seed_phrase, &derivation_path, password).await
+    }
+
+    /// Used accounts of `seed_phrase` on Core Testnet, up to `gap_limit` consecutive unused ones;
+    /// see `WalletManager::discover_accounts`
+    pub async fn discover_accounts(&self, seed_phrase: &str, gap_limit: u32) -> Result<Vec<(u32, String)>, WalletError> {
+        self.wallet_manager.discover_accounts(seed_phrase, gap_limit, Network::CoreTestnet).await
+    }
+
+    /// Derive and store account `account_index` of an imported wallet; see `WalletManager::add_account`
+    pub async fn add_account(&self, wallet: &Wallet, account_index: u32, password: &str) -> Result<WalletAccount, WalletError> {
+        self.wallet_manager.add_account(&wallet.id, account_index, password).await
+    }
+
+    /// The wallet's own address as account 0, followed by the accounts added to it
+    pub async fn list_accounts(&self, wallet: &Wallet) -> Result<Vec<WalletAccount>, WalletError> {
+        self.wallet_manager.list_accounts(&wallet.id).await
+    }
+
+    /// Return the mnemonic an imported wallet was created from
+    pub async fn export_seed_phrase(&self, wallet: &Wallet, password: &str) -> Result<String, WalletError> {
+        self.wallet_manager.export_seed_phrase(&wallet.id, password).await
+    }
+
     pub async fn sign_message(&self, wallet: &Wallet, message: &str) -> Result<String, WalletError> {
         self.wallet_manager.sign_message(&wallet.id, message).await
     }
 
+    pub async fn sign_message_for_account(&self, wallet: &Wallet, account_index: u32, message: &str) -> Result<String, WalletError> {
+        self.wallet_manager.sign_message_for_account(&wallet.id, account_index, message).await
+    }
+
     pub async fn get_balance(&self, wallet: &Wallet) -> Result<String, WalletError> {
         self.wallet_manager.get_balance(&wallet.id).await
     }
 
+    pub async fn get_account_balance(&self, wallet: &Wallet, account_index: u32) -> Result<String, WalletError> {
+        self.wallet_manager.get_account_balance(&wallet.id, account_index).await
+    }
+
+    /// Balances of several wallets by wallet id, fetched with one batch request per network
+    pub async fn get_balances(&self, wallets: &[Wallet]) -> Result<std::collections::HashMap<String, String>, WalletError> {
+        let wallet_ids: Vec<String> = wallets.iter().map(|w| w.id.clone()).collect();
+        self.wallet_manager.get_balances(&wallet_ids).await
+    }
+
     pub async fn backup_wallet(&self, wallet: &Wallet, password: &str) -> Result<WalletBackup, WalletError> {
         let backup_info = self.storage.backup_wallet(wallet, password).await?;
         Ok(WalletBackup::from(backup_info))
@@ -240,6 +302,12 @@ impl WalletCore {
         let backup_info = WalletBackupInfo::from(backup.clone());
         self.storage.restore_wallet(&backup_info, password).await
     }
+
+    /// Check that a backup restores with `password` without restoring it
+    pub async fn verify_backup(&self, backup: &WalletBackup, password: &str) -> Result<bool, WalletError> {
+        let backup_info = WalletBackupInfo::from(backup.clone());
+        self.storage.verify_backup(&backup_info, password).await
+    }
 }
 
 // Implement Drop for secure cleanup
@@ -269,4 +337,73 @@ mod tests {
             .expect("Failed to create test wallet");
         assert_eq!(wallet.name, "Test Wallet");
     }
-} 
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_offline_core_refuses_network_and_ble() {
+        let core = WalletCore::offline();
+        assert!(core.is_offline());
+        let wallet = core.create_wallet("offline_audit_wallet", "Audit", Network::CoreTestnet).await
+            .expect("wallet storage works offline");
+
+        assert!(matches!(core.get_balance(&wallet).await, Err(WalletError::Offline(_))));
+        assert!(matches!(core.get_balances(std::slice::from_ref(&wallet)).await, Err(WalletError::Offline(_))));
+        let transaction = Transaction {
+            to: "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
+            value: "1".to_string(),
+            data: None,
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+            chain_id: Network::CoreTestnet.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        assert!(matches!(core.wallet_manager.send_transaction(&wallet.id, transaction).await, Err(WalletError::Offline(_))));
+        assert!(matches!(
+            core.transaction_manager.get_transaction_status(&"0xabc".to_string()).await,
+            Err(WalletError::Offline(_))
+        ));
+        assert!(matches!(core.transaction_manager.get_gas_price(Network::CoreTestnet).await, Err(WalletError::Offline(_))));
+        assert!(matches!(core.ble.start_scanning().await, Err(WalletError::Offline(_))));
+        assert!(matches!(core.ble.send_payment().await, Err(WalletError::Offline(_))));
+        assert!(core.wallet_manager.start_balance_refresh().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_offline_core_still_signs() {
+        let core = WalletCore::offline();
+        let wallet = core.create_wallet("offline_signing_wallet", "Audit", Network::CoreTestnet).await.unwrap();
+
+        let signature = core.sign_message(&wallet, "audit me").await.expect("signing works offline");
+        assert_eq!(hex::decode(&signature).unwrap().len(), 64);
+
+        let transaction = Transaction {
+            to: "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
+            value: "1".to_string(),
+            data: None,
+            gas_limit: Some(21_000),
+            gas_price: Some(1_000_000_000),
+            nonce: Some(0),
+            chain_id: Network::CoreTestnet.chain_id(),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        let storage = crate::infrastructure::platform::FileStorage::new().unwrap();
+        let signed = core.transaction_manager
+            .sign_transaction(&transaction, &format!("wallet_key_{}", wallet.id), &storage)
+            .await
+            .expect("transaction signing works offline");
+        assert!(!signed.signature.is_empty());
+
+        let key = [7u8; 32];
+        let payment = shared::types::BLEPaymentData {
+            amount: "1".to_string(),
+            to_address: wallet.address.clone(),
+            token_symbol: "TCORE2".to_string(),
+            network: Network::CoreTestnet,
+            reference: None,
+        };
+        let encrypted = core.ble.encrypt_payment_data(&payment, &key).await.unwrap();
+        assert_eq!(core.ble.decrypt_payment_data(&encrypted, &key).await.unwrap().amount, "1");
+    }
+}
@@ -1,7 +0,0 @@
-//! no_std compatibility stub for `airchainpay-wallet-core`
-//!
-//! This module is a placeholder to satisfy the `no_std` feature flag.
-//! Implement actual no_std-compatible APIs here when targeting embedded.
-
-#![cfg_attr(feature = "no_std", no_std)]
-
@@ -0,0 +1,15 @@
+//! Offline signing for hardware-wallet and air-gapped builds
+//!
+//! Raw key bytes and a `Transaction` go in, signed RLP bytes come out, with no network,
+//! filesystem or async runtime involved. This crate still needs `std`; firmware without it
+//! depends on the `no_std` `airchainpay-signing` crate, which does the actual signing.
+//!
+//! ```ignore
+//! let raw_tx = airchainpay_wallet_core::offline_signing::sign_legacy_raw(&transaction, &key_bytes)?;
+//! let tx_hash = airchainpay_wallet_core::offline_signing::transaction_hash(&raw_tx);
+//! ```
+
+pub use crate::core::crypto::signatures::raw_transaction::{
+    encode_legacy_signing_payload, keccak256, sign_legacy_raw, transaction_hash,
+};
+pub use crate::shared::types::Transaction;
@@ -3,6 +3,34 @@
 //! This module defines the error types used throughout the wallet core.
 
 use thiserror::Error;
+use std::fmt;
+
+/// Structured details for a validation failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Name of the offending input field, empty when not tied to a single field
+    pub field: String,
+    /// Human-readable description of the failure
+    pub reason: String,
+    /// Stable machine-readable error code
+    pub code: String,
+}
+
+impl ValidationError {
+    pub fn new(field: impl Into<String>, reason: impl Into<String>, code: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            reason: reason.into(),
+            code: code.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
 
 /// Wallet error type
 #[derive(Error, Debug, Clone)]
@@ -14,7 +42,7 @@ pub enum WalletError {
     Crypto(String),
     
     #[error("Validation error: {0}")]
-    Validation(String),
+    Validation(ValidationError),
     
     #[error("Storage error: {0}")]
     Storage(String),
@@ -39,6 +67,15 @@ pub enum WalletError {
     
     #[error("Not implemented: {0}")]
     NotImplemented(String),
+
+    #[error("Offline mode: {0} is disabled")]
+    Offline(String),
+
+    #[error("Corrupted salt: {0}")]
+    CorruptedSalt(String),
+
+    #[error("Backup integrity check failed: {0}")]
+    BackupIntegrity(String),
 }
 
 impl WalletError {
@@ -54,7 +91,20 @@ impl WalletError {
     
     /// Create a validation error
     pub fn validation(message: impl Into<String>) -> Self {
-        Self::Validation(message.into())
+        Self::Validation(ValidationError::new("", message, "invalid"))
+    }
+
+    /// Create a validation error for a specific input field
+    pub fn invalid_field(field: impl Into<String>, reason: impl Into<String>, code: impl Into<String>) -> Self {
+        Self::Validation(ValidationError::new(field, reason, code))
+    }
+
+    /// Structured validation details, if this is a validation error
+    pub fn validation_details(&self) -> Option<&ValidationError> {
+        match self {
+            Self::Validation(details) => Some(details),
+            _ => None,
+        }
     }
     
     /// Create a storage error
@@ -95,6 +145,21 @@ impl WalletError {
     pub fn not_implemented(message: &str) -> Self {
         Self::NotImplemented(message.to_string())
     }
+
+    /// Create an error for a salt file that cannot be the one the data was encrypted with
+    pub fn corrupted_salt(message: impl Into<String>) -> Self {
+        Self::CorruptedSalt(message.into())
+    }
+
+    /// Create an error for a backup that was truncated or modified, as opposed to a wrong password
+    pub fn backup_integrity(message: impl Into<String>) -> Self {
+        Self::BackupIntegrity(message.into())
+    }
+
+    /// Create an error for a network or BLE operation refused in offline mode
+    pub fn offline(operation: impl Into<String>) -> Self {
+        Self::Offline(operation.into())
+    }
 }
 
 // Standard library error conversions
@@ -181,6 +246,17 @@ mod tests {
         assert!(matches!(validation_error, WalletError::Validation(_)));
     }
 
+    #[test]
+    fn test_field_validation_error() {
+        let error = WalletError::invalid_field("to", "Recipient address cannot be empty", "required");
+        let details = error.validation_details().expect("expected validation details");
+
+        assert_eq!(details.field, "to");
+        assert_eq!(details.code, "required");
+        assert_eq!(error.to_string(), "Validation error: Recipient address cannot be empty");
+        assert!(WalletError::crypto("x").validation_details().is_none());
+    }
+
     #[test]
     fn test_error_conversions() {
         let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "File not found");
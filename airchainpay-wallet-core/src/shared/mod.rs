@@ -7,6 +7,10 @@ pub mod types;
 pub mod utils;
 pub mod constants;
 pub mod error;
+#[cfg(test)]
+pub(crate) mod mock_http;
+#[cfg(test)]
+pub(crate) mod test_doubles;
 
 // Re-export shared components
 pub use types::*;
@@ -0,0 +1,125 @@
+//! In-memory stand-ins for the platform traits, shared by the unit tests
+
+use crate::core::storage::SecureStorage;
+use crate::infrastructure::platform::{BiometricAuth, KdfParams, PlatformStorage};
+use crate::shared::error::WalletError;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// In-memory stand-in for the platform key store
+#[derive(Default)]
+pub struct MemoryStorage {
+    items: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl PlatformStorage for MemoryStorage {
+    fn store(&self, key: &str, data: &[u8]) -> Result<(), WalletError> {
+        self.items.lock().unwrap().insert(key.to_string(), data.to_vec());
+        Ok(())
+    }
+
+    fn retrieve(&self, key: &str) -> Result<Vec<u8>, WalletError> {
+        self.items.lock().unwrap().get(key).cloned()
+            .ok_or_else(|| WalletError::storage(format!("Key not found: {}", key)))
+    }
+
+    fn delete(&self, key: &str) -> Result<(), WalletError> {
+        self.items.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, WalletError> {
+        Ok(self.items.lock().unwrap().contains_key(key))
+    }
+
+    fn list_keys(&self) -> Result<Vec<String>, WalletError> {
+        Ok(self.items.lock().unwrap().keys().cloned().collect())
+    }
+}
+
+/// Password-encrypted view of a `MemoryStorage`, standing in for file storage: entries are
+/// encrypted like `SecureStorage` blobs and fail to decrypt under any other password
+pub struct PasswordStorage<'a> {
+    inner: &'a MemoryStorage,
+    password: String,
+}
+
+impl<'a> PasswordStorage<'a> {
+    /// Cheap key derivation; the tests only need a wrong password to be told apart
+    const KDF: KdfParams = KdfParams { memory_kib: 8, iterations: 1, parallelism: 1 };
+
+    pub fn new(inner: &'a MemoryStorage, password: &str) -> Self {
+        Self { inner, password: password.to_string() }
+    }
+}
+
+impl PlatformStorage for PasswordStorage<'_> {
+    fn store(&self, key: &str, data: &[u8]) -> Result<(), WalletError> {
+        self.inner.store(key, &SecureStorage::encrypt_with_params(data, &self.password, Self::KDF)?)
+    }
+
+    fn retrieve(&self, key: &str) -> Result<Vec<u8>, WalletError> {
+        let encrypted = self.inner.retrieve(key)?;
+        let derived = SecureStorage::derive_key(&self.password, &encrypted)?;
+        SecureStorage::decrypt_with_key(&encrypted, &derived)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), WalletError> {
+        self.inner.delete(key)
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, WalletError> {
+        self.inner.exists(key)
+    }
+
+    fn list_keys(&self) -> Result<Vec<String>, WalletError> {
+        self.inner.list_keys()
+    }
+}
+
+/// Biometric prompt whose answer the test controls
+pub struct MockBiometricAuth {
+    available: bool,
+    approve: AtomicBool,
+    prompts: AtomicU32,
+}
+
+impl MockBiometricAuth {
+    pub fn new(available: bool, approve: bool) -> Arc<Self> {
+        Arc::new(Self { available, approve: AtomicBool::new(approve), prompts: AtomicU32::new(0) })
+    }
+
+    /// Answer later prompts with `approve`
+    pub fn set_approve(&self, approve: bool) {
+        self.approve.store(approve, Ordering::SeqCst);
+    }
+
+    /// Prompts shown so far
+    pub fn prompts(&self) -> u32 {
+        self.prompts.load(Ordering::SeqCst)
+    }
+}
+
+impl BiometricAuth for MockBiometricAuth {
+    fn is_available(&self) -> Result<bool, WalletError> {
+        Ok(self.available)
+    }
+
+    fn authenticate(&self, _reason: &str) -> Result<bool, WalletError> {
+        self.prompts.fetch_add(1, Ordering::SeqCst);
+        Ok(self.approve.load(Ordering::SeqCst))
+    }
+
+    fn is_enabled(&self) -> Result<bool, WalletError> {
+        Ok(self.available)
+    }
+
+    fn enable(&self) -> Result<(), WalletError> {
+        Ok(())
+    }
+
+    fn disable(&self) -> Result<(), WalletError> {
+        Ok(())
+    }
+}
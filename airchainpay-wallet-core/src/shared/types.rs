@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
 
 // Basic types for wallet operations
 pub type Address = String;
@@ -11,13 +12,20 @@ pub type GasLimit = u64;
 pub type Amount = String; 
 pub type Balance = String;
 
-// Network types - Core Testnet, Base Sepolia, Lisk Sepolia, Ethereum Holesky
+// Network types - Core Testnet, Base Sepolia, Lisk Sepolia, Ethereum Holesky, or any EVM chain
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum Network {
     CoreTestnet,
     BaseSepolia,
     LiskSepolia,
     EthereumHolesky,
+    /// An EVM chain configured at runtime rather than built into the crate
+    Custom {
+        chain_id: u64,
+        rpc_url: String,
+        native_currency: String,
+        name: String,
+    },
 }
 
 impl Network {
@@ -27,34 +35,38 @@ impl Network {
             Network::BaseSepolia => 84532,
             Network::LiskSepolia => 4202,
             Network::EthereumHolesky => 17000,
+            Network::Custom { chain_id, .. } => *chain_id,
         }
     }
 
-    pub fn name(&self) -> &'static str {
+    pub fn name(&self) -> &str {
         match self {
             Network::CoreTestnet => "Core Testnet",
             Network::BaseSepolia => "Base Sepolia",
             Network::LiskSepolia => "Lisk Sepolia",
             Network::EthereumHolesky => "Ethereum Holesky",
+            Network::Custom { name, .. } => name,
         }
     }
 
-    pub fn rpc_url(&self) -> &'static str {
+    pub fn rpc_url(&self) -> &str {
         match self {
             Network::CoreTestnet => "https://rpc.test2.btcs.network",
             Network::BaseSepolia => "https://sepolia.base.org",
             // For these networks, require env overrides; return empty to force config
             Network::LiskSepolia => "",
             Network::EthereumHolesky => "",
+            Network::Custom { rpc_url, .. } => rpc_url,
         }
     }
 
-    pub fn native_currency(&self) -> &'static str {
+    pub fn native_currency(&self) -> &str {
         match self {
             Network::CoreTestnet => "TCORE2",
             Network::BaseSepolia => "ETH",
             Network::LiskSepolia => "ETH",
             Network::EthereumHolesky => "ETH",
+            Network::Custom { native_currency, .. } => native_currency,
         }
     }
 
@@ -64,6 +76,19 @@ impl Network {
             Network::BaseSepolia => "https://sepolia.basescan.org",
             Network::LiskSepolia => "https://sepolia.lisk.com",
             Network::EthereumHolesky => "https://holesky.etherscan.io",
+            // No explorer is known for a custom chain
+            Network::Custom { .. } => "",
+        }
+    }
+
+    /// Gas price (wei) assumed when the RPC cannot provide one and nothing recent is cached
+    pub fn fallback_gas_price(&self) -> u64 {
+        match self {
+            Network::CoreTestnet => 30_000_000_000,   // 30 Gwei
+            Network::BaseSepolia => 100_000_000,      // 0.1 Gwei
+            Network::LiskSepolia => 100_000_000,      // 0.1 Gwei
+            Network::EthereumHolesky => 2_000_000_000, // 2 Gwei
+            Network::Custom { .. } => 1_000_000_000,   // 1 Gwei
         }
     }
 
@@ -73,6 +98,40 @@ impl Network {
             Network::BaseSepolia => "0x7B79117445C57eea1CEAb4733020A55e1D503934",
             Network::LiskSepolia => "0xaBEEEc6e6c1f6bfDE1d05db74B28847Ba5b44EAF",
             Network::EthereumHolesky => "0x26C59cd738Df90604Ebb13Ed8DB76657cfD51f40",
+            // The payment contract is not deployed on custom chains
+            Network::Custom { .. } => "",
+        }
+    }
+
+    /// Address encoding used on this network
+    pub fn address_format(&self) -> AddressFormat {
+        match self {
+            Network::CoreTestnet
+            | Network::BaseSepolia
+            | Network::LiskSepolia
+            | Network::EthereumHolesky
+            | Network::Custom { .. } => AddressFormat::Evm,
+        }
+    }
+
+    /// Validate an address with the validator for this network's address format
+    pub fn validate_address(&self, address: &str) -> Result<(), crate::shared::error::WalletError> {
+        self.address_format().validate(address)
+    }
+}
+
+/// How account addresses are written on a network. All built-in networks are EVM;
+/// a non-EVM network gets its own variant and validator here.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AddressFormat {
+    /// 0x-prefixed 20-byte hex
+    Evm,
+}
+
+impl AddressFormat {
+    pub fn validate(&self, address: &str) -> Result<(), crate::shared::error::WalletError> {
+        match self {
+            AddressFormat::Evm => crate::shared::utils::validate_ethereum_address(address),
         }
     }
 }
@@ -87,6 +146,29 @@ pub struct Transaction {
     pub gas_price: Option<GasPrice>,
     pub nonce: Option<u64>,
     pub chain_id: u64,
+    /// EIP-1559 fee cap; with `max_priority_fee_per_gas` set the transaction is signed as type 2
+    #[serde(default)]
+    pub max_fee_per_gas: Option<GasPrice>,
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<GasPrice>,
+}
+
+impl Transaction {
+    /// Both EIP-1559 fee fields are set
+    pub fn is_eip1559(&self) -> bool {
+        self.max_fee_per_gas.is_some() && self.max_priority_fee_per_gas.is_some()
+    }
+}
+
+/// Full debit estimate for a transaction (all amounts in wei)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostEstimate {
+    pub value: Amount,
+    pub gas_limit: GasLimit,
+    pub gas_price: GasPrice,
+    pub gas_cost: Amount,
+    pub total: Amount,
+    pub sufficient_balance: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,6 +176,9 @@ pub struct SignedTransaction {
     pub transaction: Transaction,
     pub signature: Vec<u8>,
     pub hash: TransactionHash,
+    /// Warnings raised while sending, such as a contract recipient under `ContractRecipientPolicy::Warn`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,6 +188,19 @@ pub enum TransactionStatus {
     Failed,
 }
 
+/// Where a broadcast transaction stands, as observed through its receipt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfirmationStatus {
+    /// No receipt yet
+    Pending,
+    /// Mined successfully, with fewer confirmations than requested
+    Mined { block_number: BlockNumber, confirmations: u64 },
+    /// Mined successfully with at least the requested confirmations
+    Confirmed { block_number: BlockNumber, confirmations: u64 },
+    /// Mined, but execution reverted (receipt `status` 0)
+    Reverted { block_number: BlockNumber },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionReceipt {
     pub hash: TransactionHash,
@@ -154,6 +252,9 @@ pub struct WalletBackup {
     pub encrypted_data: String,
     pub salt: String,
     pub version: String,
+    /// Hex SHA-256 of the decoded `encrypted_data`; absent in backups made before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -162,6 +263,9 @@ pub struct WalletBackupInfo {
     pub encrypted_data: String,
     pub salt: String,
     pub version: String,
+    /// Hex SHA-256 of the decoded `encrypted_data`; absent in backups made before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
 }
 
 impl From<WalletBackupInfo> for WalletBackup {
@@ -171,6 +275,7 @@ impl From<WalletBackupInfo> for WalletBackup {
             encrypted_data: info.encrypted_data,
             salt: info.salt,
             version: info.version,
+            checksum: info.checksum,
         }
     }
 }
@@ -182,10 +287,28 @@ impl From<WalletBackup> for WalletBackupInfo {
             encrypted_data: backup.encrypted_data,
             salt: backup.salt,
             version: backup.version,
+            checksum: backup.checksum,
         }
     }
 }
 
+/// Wallet backup whose decryption key is split into Shamir shares; any `threshold`
+/// of the hex-encoded `shares` restore it without the password
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SplitBackup {
+    pub backup: String,
+    pub shares: Zeroizing<Vec<String>>,
+}
+
+impl std::fmt::Debug for SplitBackup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SplitBackup")
+            .field("backup", &self.backup)
+            .field("shares", &format_args!("[{} redacted]", self.shares.len()))
+            .finish()
+    }
+}
+
 // BLE types - minimal for payment functionality
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BLEPaymentData {
@@ -196,6 +319,44 @@ pub struct BLEPaymentData {
     pub reference: Option<String>,
 }
 
+impl BLEPaymentData {
+    /// Build payment data for `network`, rejecting an empty amount or token and an
+    /// invalid recipient. EVM recipients are stored in EIP-55 checksum form.
+    pub fn new(
+        amount: Amount,
+        to_address: Address,
+        token_symbol: String,
+        network: Network,
+        reference: Option<String>,
+    ) -> Result<Self, crate::shared::error::WalletError> {
+        let mut payment = Self { amount, to_address, token_symbol, network, reference };
+        payment.validate()?;
+        payment.to_address = match payment.network.address_format() {
+            AddressFormat::Evm => crate::shared::utils::to_checksum_address(&payment.to_address)?,
+        };
+        Ok(payment)
+    }
+
+    /// Check payment data received from a peer: a non-empty amount and token, and a
+    /// recipient valid for `network` whose checksum, if mixed-case, matches.
+    pub fn validate(&self) -> Result<(), crate::shared::error::WalletError> {
+        use crate::shared::error::WalletError;
+
+        if self.amount.is_empty() {
+            return Err(WalletError::invalid_field("amount", "Payment amount cannot be empty", "required"));
+        }
+        if self.token_symbol.is_empty() {
+            return Err(WalletError::invalid_field("token_symbol", "Token symbol cannot be empty", "required"));
+        }
+        self.network.validate_address(&self.to_address)
+            .map_err(|e| WalletError::invalid_field("to_address", e.to_string(), "invalid_address"))?;
+        match self.network.address_format() {
+            AddressFormat::Evm => crate::shared::utils::validate_address_checksum(&self.to_address)
+                .map_err(|e| WalletError::invalid_field("to_address", e.to_string(), "invalid_checksum")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BLEDeviceInfo {
     pub id: String,
@@ -272,6 +433,37 @@ mod tests {
         assert_eq!(Network::BaseSepolia.rpc_url(), "https://sepolia.base.org");
     }
 
+    #[test]
+    fn test_address_validation_is_dispatched_per_network() {
+        let networks = [Network::CoreTestnet, Network::BaseSepolia, Network::LiskSepolia, Network::EthereumHolesky];
+        for network in networks {
+            assert_eq!(network.address_format(), AddressFormat::Evm);
+            assert!(network.validate_address("0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6").is_ok());
+            // 19 bytes
+            assert!(network.validate_address("0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8").is_err());
+        }
+    }
+
+    #[test]
+    fn test_ble_payment_data_validates_recipient() {
+        let payment = BLEPaymentData::new(
+            "1000".to_string(),
+            "0x742d35cc6634c0532925a3b8d4c9db96c4b4d8b6".to_string(),
+            "TCORE2".to_string(),
+            Network::CoreTestnet,
+            None,
+        ).unwrap();
+        assert_eq!(payment.to_address, "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6");
+
+        let build = |to: &str| BLEPaymentData::new("1000".to_string(), to.to_string(), "TCORE2".to_string(), Network::CoreTestnet, None);
+        let err = build("0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6").unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "invalid_checksum");
+        let err = build("0x742d35cc6634c0532925a3b8d4c9db96c4b4d8").unwrap_err();
+        assert_eq!(err.validation_details().unwrap().code, "invalid_address");
+        let err = BLEPaymentData::new(String::new(), "0x742d35cc6634c0532925a3b8d4c9db96c4b4d8b6".to_string(), "TCORE2".to_string(), Network::CoreTestnet, None).unwrap_err();
+        assert_eq!(err.validation_details().unwrap().field, "amount");
+    }
+
     #[test]
     fn test_network_native_currencies() {
         assert_eq!(Network::CoreTestnet.native_currency(), "TCORE2");
@@ -288,6 +480,8 @@ mod tests {
             gas_price: None,
             nonce: None,
             chain_id: 1114,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
         };
 
         assert_eq!(transaction.to, "0x1234");
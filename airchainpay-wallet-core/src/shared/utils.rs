@@ -5,8 +5,10 @@
 use crate::shared::error::WalletError;
 use std::time::{SystemTime, UNIX_EPOCH};
 use bip39::Mnemonic;
+use ethers::types::U256;
 use rand_core::OsRng;
 use rand_core::RngCore;
+use sha3::{Digest, Keccak256};
 
 /// Generate a unique ID
 pub fn generate_id() -> String {
@@ -39,6 +41,41 @@ pub fn validate_ethereum_address(address: &str) -> Result<(), WalletError> {
     Ok(())
 }
 
+/// Whether `address` is 0x-prefixed 20-byte hex whose EIP-55 checksum, if it has one, is correct
+pub fn is_valid_ethereum_address(address: &str) -> bool {
+    validate_ethereum_address(address).is_ok() && validate_address_checksum(address).is_ok()
+}
+
+/// EIP-55 checksum form of an address: each hex letter is uppercased when the matching
+/// nibble of keccak256(lowercase hex) is 8 or more. The input's case is ignored.
+pub fn to_checksum_address(address: &str) -> Result<String, WalletError> {
+    validate_ethereum_address(address)?;
+    let hex = address[2..].to_ascii_lowercase();
+    let hash = Keccak256::digest(hex.as_bytes());
+
+    let mut checksummed = String::with_capacity(42);
+    checksummed.push_str("0x");
+    for (i, c) in hex.chars().enumerate() {
+        let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+        checksummed.push(if nibble >= 8 { c.to_ascii_uppercase() } else { c });
+    }
+    Ok(checksummed)
+}
+
+/// Validate the EIP-55 checksum of an address already known to be 0x-prefixed 20-byte hex.
+/// Single-case addresses carry no checksum and pass; mixed case must match exactly.
+pub fn validate_address_checksum(address: &str) -> Result<(), WalletError> {
+    let hex = address.trim_start_matches("0x");
+    let mixed_case = hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    if !mixed_case {
+        return Ok(());
+    }
+    if to_checksum_address(address)? != address {
+        return Err(WalletError::validation("Address checksum is invalid"));
+    }
+    Ok(())
+}
+
 /// Validate private key format
 pub fn validate_private_key(private_key: &str) -> Result<(), WalletError> {
     if !private_key.starts_with("0x") {
@@ -68,11 +105,11 @@ pub fn validate_seed_phrase(seed_phrase: &str) -> Result<(), WalletError> {
 /// Validate password strength
 pub fn validate_password(password: &str) -> Result<(), WalletError> {
     if password.len() < 8 {
-        return Err(WalletError::validation("Password must be at least 8 characters long"));
+        return Err(WalletError::invalid_field("password", "Password must be at least 8 characters long", "too_short"));
     }
     
     if password.len() > 128 {
-        return Err(WalletError::validation("Password must be at most 128 characters long"));
+        return Err(WalletError::invalid_field("password", "Password must be at most 128 characters long", "too_long"));
     }
     
     Ok(())
@@ -193,6 +230,52 @@ pub fn parse_amount(amount: &str, decimals: u8) -> Result<String, WalletError> {
     }
 }
 
+/// Decimals of ether (and of the native currency on every supported network)
+pub const ETHER_DECIMALS: u8 = 18;
+
+/// Convert a human-readable amount such as `"12.5"` into base units with `decimals`,
+/// e.g. `parse_units("1.5", 6)` is `"1500000"`. The conversion is exact: more fractional
+/// digits than the unit supports are rejected rather than truncated.
+pub fn parse_units(amount: &str, decimals: u8) -> Result<String, WalletError> {
+    let invalid = |message: &str| WalletError::invalid_field("amount", message, "invalid_amount");
+    let amount = amount.trim();
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if whole.is_empty() && fraction.is_empty()
+        || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid("Amount must be a decimal number"));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(invalid(&format!("Amount has more than {} decimal places", decimals)));
+    }
+
+    let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+    let units = U256::from_dec_str(if digits.is_empty() { "0" } else { &digits })
+        .map_err(|_| invalid("Amount is too large"))?;
+    Ok(units.to_string())
+}
+
+/// Render an amount in base units as a decimal with `decimals`, without trailing zeros:
+/// `format_units("1500000", 6)` is `"1.5"` and `format_units("2000000", 6)` is `"2"`
+pub fn format_units(amount: &str, decimals: u8) -> Result<String, WalletError> {
+    let units = U256::from_dec_str(amount.trim())
+        .map_err(|_| WalletError::invalid_field("amount", "Amount must be a decimal integer", "invalid_amount"))?;
+    let digits = format!("{:0>width$}", units.to_string(), width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    Ok(if fraction.is_empty() { whole.to_string() } else { format!("{}.{}", whole, fraction) })
+}
+
+/// `parse_units` for ether: `"1.5"` becomes `"1500000000000000000"` wei
+pub fn parse_ether(ether: &str) -> Result<String, WalletError> {
+    parse_units(ether, ETHER_DECIMALS)
+}
+
+/// `format_units` for wei: `"1500000000000000000"` becomes `"1.5"` ether
+pub fn format_ether(wei: &str) -> Result<String, WalletError> {
+    format_units(wei, ETHER_DECIMALS)
+}
+
 /// Generate random bytes
 pub fn generate_random_bytes(length: usize) -> Vec<u8> {
     let mut bytes = vec![0u8; length];
@@ -209,6 +292,52 @@ pub fn generate_secure_random_bytes(length: usize) -> Result<Vec<u8>, WalletErro
     Ok(bytes)
 }
 
+/// Serialize a value as canonical JSON: object keys sorted by their UTF-8 bytes
+/// and no insignificant whitespace. Signed payloads must be serialized this way
+/// so signer and verifier hash identical bytes regardless of field order.
+pub fn to_canonical_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, WalletError> {
+    let value = serde_json::to_value(value)
+        .map_err(|e| WalletError::validation(format!("Serialization failed: {}", e)))?;
+    let mut out = Vec::new();
+    write_canonical_json(&value, &mut out)?;
+    Ok(out)
+}
+
+fn write_canonical_json(value: &serde_json::Value, out: &mut Vec<u8>) -> Result<(), WalletError> {
+    use serde_json::Value;
+
+    match value {
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_canonical_json(item, out)?;
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+            out.push(b'{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                serde_json::to_writer(&mut *out, key)
+                    .map_err(|e| WalletError::validation(format!("Serialization failed: {}", e)))?;
+                out.push(b':');
+                write_canonical_json(item, out)?;
+            }
+            out.push(b'}');
+        }
+        scalar => serde_json::to_writer(&mut *out, scalar)
+            .map_err(|e| WalletError::validation(format!("Serialization failed: {}", e)))?,
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,6 +367,30 @@ mod tests {
         assert!(validate_ethereum_address("0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6g").is_err()); // Invalid char
     }
 
+    #[test]
+    fn test_to_checksum_address_matches_eip55_vectors() {
+        for expected in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+            "0x52908400098527886E0F7030069857D2E4169EE7",
+            "0xde709f2102306220921060314715629080e2fb77",
+        ] {
+            assert_eq!(to_checksum_address(&expected.to_lowercase()).unwrap(), expected);
+            assert_eq!(to_checksum_address(&expected.to_uppercase().replacen("0X", "0x", 1)).unwrap(), expected);
+            assert!(is_valid_ethereum_address(expected));
+        }
+
+        assert!(to_checksum_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA").is_err());
+        assert!(to_checksum_address("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+        assert!(to_checksum_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeZ").is_err());
+        // Valid hex, one letter's case flipped
+        assert!(!is_valid_ethereum_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"));
+        assert!(!is_valid_ethereum_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beae"));
+        assert!(is_valid_ethereum_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+    }
+
     #[test]
     fn test_validate_private_key() {
         // Valid private key
@@ -324,6 +477,40 @@ mod tests {
             .expect("Failed to parse amount"), "1000000000000000000");
     }
 
+    #[test]
+    fn test_parse_and_format_units() {
+        assert_eq!(parse_ether("1.5").unwrap(), "1500000000000000000");
+        assert_eq!(parse_ether("1.500").unwrap(), "1500000000000000000");
+        assert_eq!(parse_ether(".25").unwrap(), "250000000000000000");
+        assert_eq!(parse_units("12", 6).unwrap(), "12000000");
+        assert_eq!(parse_units("0", 6).unwrap(), "0");
+
+        assert_eq!(format_ether("1500000000000000000").unwrap(), "1.5");
+        assert_eq!(format_ether("2000000000000000000").unwrap(), "2");
+        assert_eq!(format_ether("1").unwrap(), "0.000000000000000001");
+        assert_eq!(format_units("1230000", 6).unwrap(), "1.23");
+        assert_eq!(format_units("0", 6).unwrap(), "0");
+        assert_eq!(format_units("42", 0).unwrap(), "42");
+
+        for amount in ["1.5", "0.000000000000000001", "123456789.987654321"] {
+            assert_eq!(format_ether(&parse_ether(amount).unwrap()).unwrap(), amount);
+        }
+    }
+
+    #[test]
+    fn test_parse_units_rejects_invalid_amounts() {
+        let reason = |amount: &str, decimals: u8| {
+            parse_units(amount, decimals).unwrap_err().validation_details().unwrap().reason.clone()
+        };
+        assert_eq!(reason("1.0000001", 6), "Amount has more than 6 decimal places");
+        assert_eq!(reason("0.0000000000000000001", 18), "Amount has more than 18 decimal places");
+        assert_eq!(reason("1,5", 18), "Amount must be a decimal number");
+        assert_eq!(reason("-1", 18), "Amount must be a decimal number");
+        assert_eq!(reason(".", 18), "Amount must be a decimal number");
+        assert_eq!(reason(&"9".repeat(80), 18), "Amount is too large");
+        assert!(format_ether("1.5").is_err());
+    }
+
     #[test]
     fn test_random_bytes() {
         let bytes1 = generate_random_bytes(32);
@@ -332,4 +519,20 @@ mod tests {
         assert_eq!(bytes2.len(), 32);
         assert_ne!(bytes1, bytes2);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_canonical_json_ignores_field_order() {
+        #[derive(serde::Serialize)]
+        struct Forward { amount: &'static str, to: &'static str, meta: serde_json::Value }
+        #[derive(serde::Serialize)]
+        struct Reversed { meta: serde_json::Value, to: &'static str, amount: &'static str }
+
+        let forward = Forward { amount: "1", to: "0xabc", meta: serde_json::json!({ "z": [1, 2], "a": null }) };
+        let reversed = Reversed { meta: serde_json::json!({ "a": null, "z": [1, 2] }), to: "0xabc", amount: "1" };
+
+        assert_ne!(serde_json::to_vec(&forward).unwrap(), serde_json::to_vec(&reversed).unwrap());
+        let canonical = to_canonical_json(&forward).unwrap();
+        assert_eq!(canonical, to_canonical_json(&reversed).unwrap());
+        assert_eq!(canonical, br#"{"amount":"1","meta":{"a":null,"z":[1,2]},"to":"0xabc"}"#.to_vec());
+    }
+}
@@ -1,7 +1,169 @@
-//! WASM bindings stub for `airchainpay-wallet-core`
+//! WASM bindings for `airchainpay-wallet-core`
 //!
-//! This module is a placeholder to satisfy the `wasm` feature flag.
-//! Real WASM bindings can be added here when needed.
+//! `WalletCoreJs` exposes wallet creation, message signing and balance lookups to
+//! JavaScript as promise-returning methods. There is no filesystem in the browser, so
+//! key material and wallet records are persisted through a storage object supplied by
+//! the host page (see `JsStorage`), e.g. one backed by `chrome.storage` or IndexedDB
+//! with its contents mirrored in memory.
 
-// Public API surface can be expanded later; keep empty for now.
+use crate::core::wallet::WalletManager;
+use crate::infrastructure::platform::PlatformStorage;
+use crate::shared::error::WalletError;
+use crate::shared::types::Network;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
 
+#[wasm_bindgen(typescript_custom_section)]
+const JS_STORAGE_TS: &str = r#"
+export interface JsStorage {
+    get(key: string): Uint8Array | undefined;
+    set(key: string, value: Uint8Array): void;
+    remove(key: string): void;
+    keys(): string[];
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Synchronous key-value persistence implemented in JavaScript
+    #[wasm_bindgen(typescript_type = "JsStorage")]
+    pub type JsStorage;
+
+    #[wasm_bindgen(method, catch)]
+    fn get(this: &JsStorage, key: &str) -> Result<Option<Vec<u8>>, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn set(this: &JsStorage, key: &str, value: &[u8]) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn remove(this: &JsStorage, key: &str) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn keys(this: &JsStorage) -> Result<Vec<String>, JsValue>;
+}
+
+fn storage_error(operation: &str, error: JsValue) -> WalletError {
+    WalletError::storage(format!("JS storage {} failed: {:?}", operation, error))
+}
+
+impl PlatformStorage for JsStorage {
+    fn store(&self, key: &str, data: &[u8]) -> Result<(), WalletError> {
+        self.set(key, data).map_err(|e| storage_error("set", e))
+    }
+
+    fn retrieve(&self, key: &str) -> Result<Vec<u8>, WalletError> {
+        self.get(key)
+            .map_err(|e| storage_error("get", e))?
+            .ok_or_else(|| WalletError::storage(format!("Key not found: {}", key)))
+    }
+
+    fn delete(&self, key: &str) -> Result<(), WalletError> {
+        self.remove(key).map_err(|e| storage_error("remove", e))
+    }
+
+    fn exists(&self, key: &str) -> Result<bool, WalletError> {
+        Ok(self.get(key).map_err(|e| storage_error("get", e))?.is_some())
+    }
+
+    fn list_keys(&self) -> Result<Vec<String>, WalletError> {
+        self.keys().map_err(|e| storage_error("keys", e))
+    }
+}
+
+fn to_js_error(error: WalletError) -> JsValue {
+    js_sys::Error::new(&error.to_string()).into()
+}
+
+/// Wallet core for JavaScript callers. Every method returns a `Promise`.
+#[wasm_bindgen]
+pub struct WalletCoreJs {
+    manager: WalletManager,
+    storage: Rc<JsStorage>,
+}
+
+#[wasm_bindgen]
+impl WalletCoreJs {
+    #[wasm_bindgen(constructor)]
+    pub fn new(storage: JsStorage) -> WalletCoreJs {
+        WalletCoreJs {
+            manager: WalletManager::new(),
+            storage: Rc::new(storage),
+        }
+    }
+
+    /// Create a wallet; `network` is a serialized `Network` such as `"CoreTestnet"`.
+    /// Resolves to the wallet's public info.
+    pub fn create_wallet(&self, wallet_id: String, name: String, network: JsValue) -> js_sys::Promise {
+        let manager = self.manager.clone();
+        let storage = Rc::clone(&self.storage);
+        future_to_promise(async move {
+            let network: Network = serde_wasm_bindgen::from_value(network)?;
+            let wallet = manager.create_wallet_with_storage(storage.as_ref(), &wallet_id, &name, network).await
+                .map_err(to_js_error)?;
+            Ok(serde_wasm_bindgen::to_value(&wallet.to_safe_wallet_info())?)
+        })
+    }
+
+    /// Sign a message with a wallet's key. Resolves to the hex signature.
+    pub fn sign_message(&self, wallet_id: String, message: String) -> js_sys::Promise {
+        let manager = self.manager.clone();
+        let storage = Rc::clone(&self.storage);
+        future_to_promise(async move {
+            let signature = manager.sign_message_with_storage(storage.as_ref(), &wallet_id, &message).await
+                .map_err(to_js_error)?;
+            Ok(JsValue::from_str(&signature))
+        })
+    }
+
+    /// Fetch a wallet's balance from its network. Resolves to the balance in wei as a decimal string.
+    pub fn get_balance(&self, wallet_id: String) -> js_sys::Promise {
+        let manager = self.manager.clone();
+        future_to_promise(async move {
+            let balance = manager.get_balance(&wallet_id).await.map_err(to_js_error)?;
+            Ok(JsValue::from_str(&balance))
+        })
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_futures::JsFuture;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen(inline_js = "
+        export function memoryStorage() {
+            const items = new Map();
+            return {
+                get: (key) => items.get(key),
+                set: (key, value) => { items.set(key, value.slice()); },
+                remove: (key) => { items.delete(key); },
+                keys: () => Array.from(items.keys()),
+            };
+        }
+    ")]
+    extern "C" {
+        fn memoryStorage() -> JsStorage;
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_create_wallet_and_sign_message() {
+        let core = WalletCoreJs::new(memoryStorage());
+        let network = serde_wasm_bindgen::to_value(&Network::CoreTestnet).unwrap();
+
+        let info = JsFuture::from(core.create_wallet("browser".into(), "Browser Wallet".into(), network)).await.unwrap();
+        let info: crate::domain::WalletInfo = serde_wasm_bindgen::from_value(info).unwrap();
+        assert_eq!(info.id, "browser");
+        assert!(info.address.starts_with("0x"));
+
+        let signature = JsFuture::from(core.sign_message("browser".into(), "hello".into())).await.unwrap();
+        let signature = signature.as_string().unwrap();
+        assert!(!signature.is_empty());
+
+        // Unknown wallets reject the promise instead of panicking
+        assert!(JsFuture::from(core.sign_message("missing".into(), "hello".into())).await.is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! Offline signing through the `offline_signing` module:
+//! `cargo test --no-default-features --features offline_signing --test offline_signing`
+#![cfg(feature = "offline_signing")]
+
+use airchainpay_wallet_core::offline_signing::{sign_legacy_raw, transaction_hash, Transaction};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, TransactionRequest, U256};
+use ethers::signers::{LocalWallet, Signer};
+
+const KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+fn fixed_transaction() -> Transaction {
+    Transaction {
+        to: "0x3535353535353535353535353535353535353535".to_string(),
+        value: "1000000000000000000".to_string(),
+        data: Some(vec![0xde, 0xad, 0xbe, 0xef]),
+        gas_limit: Some(21_000),
+        gas_price: Some(20_000_000_000),
+        nonce: Some(9),
+        chain_id: 1114,
+        max_fee_per_gas: None,
+        max_priority_fee_per_gas: None,
+    }
+}
+
+#[test]
+fn test_signs_fixed_transaction_like_ethers() {
+    let key = hex::decode(KEY).unwrap();
+    let transaction = fixed_transaction();
+    let raw_tx = sign_legacy_raw(&transaction, &key).unwrap();
+
+    // ECDSA signing is deterministic (RFC 6979), so ethers must produce the same bytes
+    let wallet: LocalWallet = KEY.parse::<LocalWallet>().unwrap().with_chain_id(transaction.chain_id);
+    let request: TypedTransaction = TransactionRequest::new()
+        .to(transaction.to.parse::<Address>().unwrap())
+        .value(U256::exp10(18))
+        .data(Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]))
+        .gas(21_000)
+        .gas_price(20_000_000_000u64)
+        .nonce(9)
+        .chain_id(transaction.chain_id)
+        .into();
+    let signature = wallet.sign_transaction_sync(&request).unwrap();
+    assert_eq!(raw_tx, request.rlp_signed(&signature).to_vec());
+    assert_eq!(transaction_hash(&raw_tx), request.hash(&signature).0);
+}
+
+#[test]
+fn test_rejects_incomplete_transactions_and_bad_keys() {
+    let key = hex::decode(KEY).unwrap();
+    let missing_nonce = Transaction { nonce: None, ..fixed_transaction() };
+    assert!(sign_legacy_raw(&missing_nonce, &key).is_err());
+    let bad_value = Transaction { value: "1e18".to_string(), ..fixed_transaction() };
+    assert!(sign_legacy_raw(&bad_value, &key).is_err());
+    assert!(sign_legacy_raw(&fixed_transaction(), &key[..31]).is_err());
+    assert!(sign_legacy_raw(&fixed_transaction(), &[0u8; 32]).is_err());
+}
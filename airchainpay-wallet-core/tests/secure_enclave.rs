@@ -0,0 +1,35 @@
+//! Secure Enclave round trip through `PlatformManager` on Apple platforms.
+//!
+//! Needs Secure Enclave hardware and a code-signed test binary with a keychain access
+//! group entitlement, so it is ignored by default:
+//! `cargo test --test secure_enclave -- --ignored`
+#![cfg(any(target_os = "ios", target_os = "macos"))]
+
+use airchainpay_wallet_core::infrastructure::platform::PlatformManager;
+
+#[test]
+#[ignore = "requires Secure Enclave hardware and keychain entitlements"]
+fn test_secure_enclave_key_lifecycle() {
+    let manager = PlatformManager::new().unwrap();
+    let enclave = manager.secure_enclave();
+    if !enclave.is_available().unwrap() {
+        eprintln!("No Secure Enclave on this machine, skipping");
+        return;
+    }
+
+    let key_id = format!("test_{}", uuid::Uuid::new_v4().simple());
+    let public_key = enclave.generate_key_pair(&key_id).unwrap();
+    let public_key_bytes = hex::decode(&public_key).unwrap();
+    assert_eq!(public_key_bytes.len(), 65);
+    assert_eq!(public_key_bytes[0], 0x04);
+    assert_eq!(enclave.get_public_key(&key_id).unwrap(), public_key);
+    assert!(enclave.generate_key_pair(&key_id).is_err());
+
+    // DER-encoded ECDSA signature: SEQUENCE { r INTEGER, s INTEGER }
+    let signature = enclave.sign(&key_id, b"hello").unwrap();
+    assert_eq!(signature[0], 0x30);
+
+    enclave.delete_key(&key_id).unwrap();
+    assert!(enclave.sign(&key_id, b"hello").is_err());
+    assert!(enclave.get_public_key(&key_id).is_err());
+}